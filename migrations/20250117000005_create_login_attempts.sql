@@ -0,0 +1,22 @@
+-- Create login_attempts table
+-- This table records per-user login outcomes for the account security summary
+
+CREATE TABLE login_attempts (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    succeeded BOOLEAN NOT NULL,
+    ip_address TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Failed attempts are queried per user over a rolling window
+CREATE INDEX idx_login_attempts_user_failed ON login_attempts(user_id, created_at DESC) WHERE NOT succeeded;
+CREATE INDEX idx_login_attempts_created_at ON login_attempts(created_at);
+
+-- Add comments for documentation
+COMMENT ON TABLE login_attempts IS 'Per-user login outcomes for recent activity reporting';
+COMMENT ON COLUMN login_attempts.id IS 'UUID v7 primary key';
+COMMENT ON COLUMN login_attempts.user_id IS 'Foreign key to users table';
+COMMENT ON COLUMN login_attempts.succeeded IS 'Whether the login attempt succeeded';
+COMMENT ON COLUMN login_attempts.ip_address IS 'IP address of the client, if known';
+COMMENT ON COLUMN login_attempts.created_at IS 'Attempt timestamp';
@@ -0,0 +1,15 @@
+-- Create token_watermark table
+-- Single-row table holding the global "tokens valid after" watermark.
+-- Tokens issued before the watermark are rejected, logging everyone out at once.
+
+CREATE TABLE token_watermark (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    tokens_valid_after TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Add comments for documentation
+COMMENT ON TABLE token_watermark IS 'Global JWT watermark for mass token invalidation (single row)';
+COMMENT ON COLUMN token_watermark.id IS 'Always TRUE - enforces a single row';
+COMMENT ON COLUMN token_watermark.tokens_valid_after IS 'Tokens issued before this timestamp are rejected';
+COMMENT ON COLUMN token_watermark.updated_at IS 'Last time the watermark was moved';
@@ -0,0 +1,9 @@
+-- Migration: Add per-user token watermark
+-- Purpose: Invalidate all of a user's tokens at once (password change, log out everywhere)
+-- Date: 2025-01-17
+
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS tokens_valid_after TIMESTAMPTZ;
+
+-- Add comment for documentation
+COMMENT ON COLUMN users.tokens_valid_after IS 'Tokens for this user issued before this timestamp are rejected';
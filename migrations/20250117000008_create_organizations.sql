@@ -0,0 +1,33 @@
+-- Create organizations and tenant_memberships tables
+-- Organizations are the tenants; a user may belong to several of them
+
+CREATE TABLE organizations (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    name VARCHAR(255) NOT NULL,
+    slug VARCHAR(63) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE tenant_memberships (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, user_id)
+);
+
+-- Memberships are counted and listed per user
+CREATE INDEX idx_tenant_memberships_user_id ON tenant_memberships(user_id);
+
+-- Add comments for documentation
+COMMENT ON TABLE organizations IS 'Tenants (organizations)';
+COMMENT ON COLUMN organizations.id IS 'UUID v7 primary key';
+COMMENT ON COLUMN organizations.name IS 'Organization display name';
+COMMENT ON COLUMN organizations.slug IS 'URL-safe unique identifier';
+COMMENT ON COLUMN organizations.created_at IS 'Creation timestamp';
+COMMENT ON COLUMN organizations.updated_at IS 'Last update timestamp';
+
+COMMENT ON TABLE tenant_memberships IS 'Users belonging to organizations';
+COMMENT ON COLUMN tenant_memberships.organization_id IS 'Foreign key to organizations table';
+COMMENT ON COLUMN tenant_memberships.user_id IS 'Foreign key to users table';
+COMMENT ON COLUMN tenant_memberships.created_at IS 'Timestamp the user joined';
@@ -0,0 +1,24 @@
+-- Create oauth_accounts table
+-- Links external identity provider accounts (Google, GitHub, ...) to users
+
+CREATE TABLE oauth_accounts (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider VARCHAR(50) NOT NULL,
+    provider_user_id VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (provider, provider_user_id)
+);
+
+-- Accounts are looked up per user when listing linked providers
+CREATE INDEX idx_oauth_accounts_user_id ON oauth_accounts(user_id);
+
+-- Add comments for documentation
+COMMENT ON TABLE oauth_accounts IS 'External identity provider accounts linked to users';
+COMMENT ON COLUMN oauth_accounts.id IS 'UUID v7 primary key';
+COMMENT ON COLUMN oauth_accounts.user_id IS 'Foreign key to users table';
+COMMENT ON COLUMN oauth_accounts.provider IS 'Provider name (google, github, ...)';
+COMMENT ON COLUMN oauth_accounts.provider_user_id IS 'Stable subject identifier at the provider';
+COMMENT ON COLUMN oauth_accounts.email IS 'Email reported by the provider when linked';
+COMMENT ON COLUMN oauth_accounts.created_at IS 'Timestamp the account was linked';
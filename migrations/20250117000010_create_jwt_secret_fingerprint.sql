@@ -0,0 +1,15 @@
+-- Create jwt_secret_fingerprint table
+-- Single-row table holding a fingerprint of the JWT signing secret.
+-- Compared at startup to detect a secret change that invalidates every token.
+
+CREATE TABLE jwt_secret_fingerprint (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    fingerprint VARCHAR(64) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Add comments for documentation
+COMMENT ON TABLE jwt_secret_fingerprint IS 'Fingerprint of the JWT secret seen at last startup (single row)';
+COMMENT ON COLUMN jwt_secret_fingerprint.id IS 'Always TRUE - enforces a single row';
+COMMENT ON COLUMN jwt_secret_fingerprint.fingerprint IS 'Hex HMAC-SHA256 keyed with the secret (not the secret itself)';
+COMMENT ON COLUMN jwt_secret_fingerprint.updated_at IS 'Last time the fingerprint changed';
@@ -0,0 +1,11 @@
+-- Add owner to organizations
+-- The user who created the organization; members live in tenant_memberships.
+-- Nullable: organizations provisioned before ownership existed have no owner.
+
+ALTER TABLE organizations
+    ADD COLUMN owner_id UUID REFERENCES users(id) ON DELETE SET NULL;
+
+-- Organizations are looked up by owner
+CREATE INDEX idx_organizations_owner_id ON organizations(owner_id);
+
+COMMENT ON COLUMN organizations.owner_id IS 'Foreign key to users table (creator), NULL if unowned';
@@ -0,0 +1,15 @@
+-- Add per-tenant two-factor policy and the user's 2FA status
+-- optional: 2FA is up to each user
+-- required: every member must have 2FA enabled to log into the tenant
+-- required_for_admins: the tenant owner must have 2FA enabled
+
+CREATE TYPE two_factor_policy AS ENUM ('optional', 'required', 'required_for_admins');
+
+ALTER TABLE organizations
+    ADD COLUMN two_factor_policy two_factor_policy NOT NULL DEFAULT 'optional';
+
+ALTER TABLE users
+    ADD COLUMN two_factor_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN organizations.two_factor_policy IS 'Which members must have 2FA enabled to log in';
+COMMENT ON COLUMN users.two_factor_enabled IS 'Whether the user has completed 2FA setup';
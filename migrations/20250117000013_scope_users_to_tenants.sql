@@ -0,0 +1,14 @@
+-- Scope users to tenants
+-- Tenant users (tenant_id set) exist only inside their organization, so two
+-- tenants can each have a user with the same email. Users without a tenant
+-- stay global and join organizations through tenant_memberships.
+
+ALTER TABLE users
+    ADD COLUMN tenant_id UUID REFERENCES organizations(id) ON DELETE CASCADE;
+
+-- Email is unique per tenant, and among global users
+ALTER TABLE users DROP CONSTRAINT users_email_key;
+CREATE UNIQUE INDEX idx_users_global_email ON users(email) WHERE tenant_id IS NULL;
+CREATE UNIQUE INDEX idx_users_tenant_email ON users(tenant_id, email) WHERE tenant_id IS NOT NULL;
+
+COMMENT ON COLUMN users.tenant_id IS 'Organization the user belongs to, NULL for global users';
@@ -0,0 +1,24 @@
+-- Create audit_events table
+-- Security-relevant auth events; also forwarded to the sinks in AUDIT_SINK
+
+CREATE TABLE audit_events (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    action TEXT NOT NULL,
+    user_id UUID,
+    tenant_id UUID,
+    ip_address TEXT,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Events are reviewed per user and exported by time
+CREATE INDEX idx_audit_events_user ON audit_events(user_id, occurred_at DESC);
+CREATE INDEX idx_audit_events_occurred_at ON audit_events(occurred_at);
+
+-- Add comments for documentation
+COMMENT ON TABLE audit_events IS 'Audit trail of authentication events';
+COMMENT ON COLUMN audit_events.id IS 'UUID v7 primary key';
+COMMENT ON COLUMN audit_events.action IS 'Event name (e.g. login_succeeded)';
+COMMENT ON COLUMN audit_events.user_id IS 'User the event is about (kept after the user is deleted)';
+COMMENT ON COLUMN audit_events.tenant_id IS 'Organization the event happened in, if any';
+COMMENT ON COLUMN audit_events.ip_address IS 'IP address of the client, if known';
+COMMENT ON COLUMN audit_events.occurred_at IS 'Event timestamp';
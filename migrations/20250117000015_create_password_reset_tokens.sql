@@ -0,0 +1,24 @@
+-- Create password_reset_tokens table
+-- Single-use tokens for the forgot/reset password flow; only hashes are stored
+
+CREATE TABLE password_reset_tokens (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Outstanding tokens are invalidated per user after a reset
+CREATE INDEX idx_password_reset_tokens_user_unused ON password_reset_tokens(user_id) WHERE used_at IS NULL;
+CREATE INDEX idx_password_reset_tokens_expires_at ON password_reset_tokens(expires_at);
+
+-- Add comments for documentation
+COMMENT ON TABLE password_reset_tokens IS 'Single-use password reset tokens';
+COMMENT ON COLUMN password_reset_tokens.id IS 'UUID v7 primary key';
+COMMENT ON COLUMN password_reset_tokens.user_id IS 'Foreign key to users table';
+COMMENT ON COLUMN password_reset_tokens.token_hash IS 'SHA-256 hash of the token (base64url); the token itself is never stored';
+COMMENT ON COLUMN password_reset_tokens.expires_at IS 'Token expiration timestamp';
+COMMENT ON COLUMN password_reset_tokens.used_at IS 'When the token was redeemed or invalidated (NULL if still outstanding)';
+COMMENT ON COLUMN password_reset_tokens.created_at IS 'Token creation timestamp';
@@ -0,0 +1,24 @@
+-- Create email_verification_tokens table
+-- Single-use tokens confirming ownership of a user's email; only hashes are stored
+
+CREATE TABLE email_verification_tokens (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Outstanding tokens are invalidated per user once the email is verified
+CREATE INDEX idx_email_verification_tokens_user_unused ON email_verification_tokens(user_id) WHERE used_at IS NULL;
+CREATE INDEX idx_email_verification_tokens_expires_at ON email_verification_tokens(expires_at);
+
+-- Add comments for documentation
+COMMENT ON TABLE email_verification_tokens IS 'Single-use email verification tokens';
+COMMENT ON COLUMN email_verification_tokens.id IS 'UUID v7 primary key';
+COMMENT ON COLUMN email_verification_tokens.user_id IS 'Foreign key to users table';
+COMMENT ON COLUMN email_verification_tokens.token_hash IS 'SHA-256 hash of the token (base64url); the token itself is never stored';
+COMMENT ON COLUMN email_verification_tokens.expires_at IS 'Token expiration timestamp';
+COMMENT ON COLUMN email_verification_tokens.used_at IS 'When the token was redeemed or invalidated (NULL if still outstanding)';
+COMMENT ON COLUMN email_verification_tokens.created_at IS 'Token creation timestamp';
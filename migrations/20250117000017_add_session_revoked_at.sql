@@ -0,0 +1,9 @@
+-- Add revoked_at to sessions
+-- With SESSION_SOFT_DELETE, logged-out sessions are marked revoked instead of
+-- deleted, and kept for incident investigation until the retention window ends
+
+ALTER TABLE sessions ADD COLUMN revoked_at TIMESTAMPTZ;
+
+CREATE INDEX idx_sessions_revoked_at ON sessions(revoked_at) WHERE revoked_at IS NOT NULL;
+
+COMMENT ON COLUMN sessions.revoked_at IS 'When the session was logged out (soft delete); NULL while active';
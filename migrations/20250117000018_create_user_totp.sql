@@ -0,0 +1,36 @@
+-- Create user_totp and mfa_challenges tables
+-- user_totp holds each user's TOTP secret; it is pending until the first code is confirmed
+-- mfa_challenges are single-use tokens bridging a password login and its TOTP code
+
+CREATE TABLE user_totp (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret TEXT NOT NULL,
+    confirmed_at TIMESTAMPTZ,
+    last_used_step BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE mfa_challenges (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tenant_id UUID REFERENCES organizations(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_mfa_challenges_expires_at ON mfa_challenges(expires_at);
+
+-- Add comments for documentation
+COMMENT ON TABLE user_totp IS 'TOTP (RFC 6238) secrets for two-factor authentication';
+COMMENT ON COLUMN user_totp.secret IS 'Base32 shared secret';
+COMMENT ON COLUMN user_totp.confirmed_at IS 'When the first code was verified (NULL while setup is pending)';
+COMMENT ON COLUMN user_totp.last_used_step IS 'Time step of the last accepted code; older or equal steps are rejected as replays';
+COMMENT ON TABLE mfa_challenges IS 'Pending second-factor steps of API logins';
+COMMENT ON COLUMN mfa_challenges.tenant_id IS 'Tenant the tokens will be minted for, resolved at password login';
+COMMENT ON COLUMN mfa_challenges.token_hash IS 'SHA-256 hash of the challenge token (base64url); the token itself is never stored';
+COMMENT ON COLUMN mfa_challenges.used_at IS 'When the challenge was completed (NULL if still pending)';
+COMMENT ON COLUMN mfa_challenges.failed_attempts IS 'Wrong codes submitted; the challenge is unusable after 5';
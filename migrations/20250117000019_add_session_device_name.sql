@@ -0,0 +1,7 @@
+-- Add device_name to sessions
+-- Optional label the client picks at login (e.g. "John's iPhone"), shown in
+-- the user's session list; length is capped by SESSION_DEVICE_NAME_MAX_LENGTH
+
+ALTER TABLE sessions ADD COLUMN device_name TEXT;
+
+COMMENT ON COLUMN sessions.device_name IS 'Client-chosen device label; NULL when none was given';
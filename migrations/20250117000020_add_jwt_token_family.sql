@@ -0,0 +1,10 @@
+-- Add family_id to jwt_tokens
+-- Tokens minted at one login share a family, kept across refresh-token
+-- rotation. Replaying an already-rotated refresh token revokes the family.
+-- Existing tokens each start a family of their own.
+
+ALTER TABLE jwt_tokens ADD COLUMN family_id UUID NOT NULL DEFAULT uuidv7();
+
+CREATE INDEX idx_jwt_tokens_family_id ON jwt_tokens(family_id);
+
+COMMENT ON COLUMN jwt_tokens.family_id IS 'Login the token descends from, shared across refresh rotations';
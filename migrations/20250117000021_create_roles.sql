@@ -0,0 +1,27 @@
+-- Create roles and user_roles tables
+-- Roles grant access to privileged endpoints (require_role). They are
+-- global, not per tenant, and embedded in access tokens as the `roles` claim
+
+CREATE TABLE roles (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    name VARCHAR(50) NOT NULL UNIQUE,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE user_roles (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role_id UUID NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, role_id)
+);
+
+CREATE INDEX idx_user_roles_role_id ON user_roles(role_id);
+
+INSERT INTO roles (name, description) VALUES
+    ('admin', 'Manages users and their roles');
+
+-- Add comments for documentation
+COMMENT ON TABLE roles IS 'Roles known to the application; only these can be granted';
+COMMENT ON COLUMN roles.name IS 'Lowercase identifier checked by require_role (e.g. admin)';
+COMMENT ON TABLE user_roles IS 'Roles granted to users';
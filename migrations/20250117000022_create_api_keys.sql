@@ -0,0 +1,27 @@
+-- Create api_keys table
+-- Long-lived credentials for service-to-service callers; only hashes are
+-- stored, the plain key is shown once when it is minted
+
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tenant_id UUID REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    last_used_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_api_keys_user_active ON api_keys(user_id) WHERE revoked_at IS NULL;
+
+-- Add comments for documentation
+COMMENT ON TABLE api_keys IS 'API keys accepted as Authorization: ApiKey <key> or X-API-Key';
+COMMENT ON COLUMN api_keys.user_id IS 'User the key authenticates as';
+COMMENT ON COLUMN api_keys.tenant_id IS 'Tenant selected by the token that minted the key';
+COMMENT ON COLUMN api_keys.name IS 'Label chosen by the user (e.g. billing-service)';
+COMMENT ON COLUMN api_keys.key_hash IS 'SHA-256 hash of the key (base64url); the key itself is never stored';
+COMMENT ON COLUMN api_keys.last_used_at IS 'Last successful authentication, at minute precision (NULL if never used)';
+COMMENT ON COLUMN api_keys.expires_at IS 'Expiration timestamp (NULL for keys that never expire)';
+COMMENT ON COLUMN api_keys.revoked_at IS 'When the key was revoked (NULL if still active)';
@@ -0,0 +1,12 @@
+-- Add client-side password hashing parameters to users
+-- Set for accounts whose password_hash is a bcrypt of the client-side
+-- PBKDF2 hash rather than of the password itself (CLIENT_PASSWORD_HASHING)
+
+ALTER TABLE users
+    ADD COLUMN client_hash_salt TEXT,
+    ADD COLUMN client_hash_iterations INTEGER,
+    ADD CONSTRAINT users_client_hash_params_check
+        CHECK ((client_hash_salt IS NULL) = (client_hash_iterations IS NULL));
+
+COMMENT ON COLUMN users.client_hash_salt IS 'PBKDF2 salt the client hashes the password with (NULL for plaintext-based accounts)';
+COMMENT ON COLUMN users.client_hash_iterations IS 'PBKDF2 iterations the client hashes the password with (NULL for plaintext-based accounts)';
@@ -0,0 +1,6 @@
+-- Add the super_admin role
+-- Super admins act across tenants, e.g. revoking every credential of a
+-- tenant during a security incident. Admins stay limited to user roles
+
+INSERT INTO roles (name, description) VALUES
+    ('super_admin', 'Acts across tenants (e.g. revokes all credentials of a tenant)');
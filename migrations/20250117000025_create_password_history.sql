@@ -0,0 +1,21 @@
+-- Create password_history table
+-- Hashes of users' previous passwords, so a password change or reset can
+-- refuse recently used ones (PASSWORD_HISTORY). Only the newest entries the
+-- policy needs are kept per user
+
+CREATE TABLE password_history (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    password_hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Entries are read and trimmed newest first per user
+CREATE INDEX idx_password_history_user_id ON password_history(user_id, created_at DESC);
+
+-- Add comments for documentation
+COMMENT ON TABLE password_history IS 'Previous password hashes of users';
+COMMENT ON COLUMN password_history.id IS 'UUID v7 primary key';
+COMMENT ON COLUMN password_history.user_id IS 'Foreign key to users table';
+COMMENT ON COLUMN password_history.password_hash IS 'Hash the user had before a password change or reset';
+COMMENT ON COLUMN password_history.created_at IS 'When the password was replaced';
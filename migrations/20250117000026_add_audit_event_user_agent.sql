@@ -0,0 +1,6 @@
+-- Add user_agent to audit_events
+-- Device of the client that triggered the event, next to its IP address
+
+ALTER TABLE audit_events ADD COLUMN user_agent TEXT;
+
+COMMENT ON COLUMN audit_events.user_agent IS 'User-Agent header of the client, if sent';
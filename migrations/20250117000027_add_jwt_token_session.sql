@@ -0,0 +1,10 @@
+-- Add session_id to jwt_tokens
+-- Tokens minted from a web session (the `sid` claim) are revoked together
+-- with the session. No foreign key: sessions may be hard-deleted while the
+-- revoked token rows are kept until they expire.
+
+ALTER TABLE jwt_tokens ADD COLUMN session_id UUID;
+
+CREATE INDEX idx_jwt_tokens_session_id ON jwt_tokens(session_id) WHERE session_id IS NOT NULL;
+
+COMMENT ON COLUMN jwt_tokens.session_id IS 'Web session the token was minted under (sid claim), if any';
@@ -0,0 +1,14 @@
+-- Add a recovery email to users
+-- A secondary address password resets can be delivered to. It is only used
+-- once verified; verification tokens sent to it record the address, so a
+-- token confirms exactly the address it was mailed to.
+
+ALTER TABLE users
+    ADD COLUMN recovery_email VARCHAR(255),
+    ADD COLUMN recovery_email_verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE email_verification_tokens ADD COLUMN recovery_email VARCHAR(255);
+
+COMMENT ON COLUMN users.recovery_email IS 'Secondary address for password resets, if set';
+COMMENT ON COLUMN users.recovery_email_verified IS 'Whether the recovery email has been confirmed';
+COMMENT ON COLUMN email_verification_tokens.recovery_email IS 'Recovery address the token confirms (NULL for the login email)';
@@ -0,0 +1,11 @@
+-- Add notification preferences to users
+-- Security emails the user opted out of. Keys missing from the object are
+-- enabled, so an empty object means every email is sent. Mandatory emails
+-- (password reset) are not listed: they can't be disabled.
+
+ALTER TABLE users ADD COLUMN notification_preferences JSONB NOT NULL DEFAULT '{}';
+
+COMMENT ON COLUMN users.notification_preferences IS 'Security email preferences, e.g. {"new_device_login": false}';
+
+-- New-device login emails look up the addresses a user logged in from
+CREATE INDEX idx_login_attempts_user_succeeded ON login_attempts(user_id, ip_address) WHERE succeeded;
@@ -0,0 +1,8 @@
+-- Add soft deletion to users
+-- Users deleting their own account are kept (audit trail, foreign keys) but
+-- deactivated; depending on ANONYMIZE_DELETED_ACCOUNTS their email and name
+-- are replaced as well.
+
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN users.deleted_at IS 'When the user deleted their account (NULL while it exists)';
@@ -0,0 +1,29 @@
+-- Create user_profiles table
+-- Profile data (bio, avatar) moves out of users, which keeps authentication
+-- data only. Users without a profile row have an empty profile; the row is
+-- created on the first profile update
+
+CREATE TABLE user_profiles (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    bio TEXT CONSTRAINT bio_length CHECK (LENGTH(bio) <= 500),
+    avatar_url TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Move existing profile data
+INSERT INTO user_profiles (user_id, bio, avatar_url, updated_at)
+SELECT id, bio, avatar_url, updated_at
+FROM users
+WHERE bio IS NOT NULL OR avatar_url IS NOT NULL;
+
+ALTER TABLE users
+DROP COLUMN bio,
+DROP COLUMN avatar_url;
+
+-- Add comments for documentation
+COMMENT ON TABLE user_profiles IS 'Profile information of users, separate from authentication data';
+COMMENT ON COLUMN user_profiles.user_id IS 'Primary key and foreign key to users table';
+COMMENT ON COLUMN user_profiles.bio IS 'User biography/description, max 500 characters';
+COMMENT ON COLUMN user_profiles.avatar_url IS 'URL to user profile avatar image';
+COMMENT ON COLUMN user_profiles.updated_at IS 'Last profile update';
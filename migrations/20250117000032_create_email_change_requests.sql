@@ -0,0 +1,28 @@
+-- Create email_change_requests table
+-- Pending login email changes: the new address receives a single-use
+-- confirmation link and the change applies only once it is opened. Only
+-- hashes of the links' tokens are stored
+
+CREATE TABLE email_change_requests (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    new_email VARCHAR(255) NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- A new request supersedes the user's outstanding ones
+CREATE INDEX idx_email_change_requests_user_unused ON email_change_requests(user_id) WHERE used_at IS NULL;
+CREATE INDEX idx_email_change_requests_expires_at ON email_change_requests(expires_at);
+
+-- Add comments for documentation
+COMMENT ON TABLE email_change_requests IS 'Pending login email changes awaiting confirmation from the new address';
+COMMENT ON COLUMN email_change_requests.id IS 'UUID v7 primary key';
+COMMENT ON COLUMN email_change_requests.user_id IS 'Foreign key to users table';
+COMMENT ON COLUMN email_change_requests.new_email IS 'Address the login email changes to once confirmed';
+COMMENT ON COLUMN email_change_requests.token_hash IS 'SHA-256 hash of the confirmation token (base64url); the token itself is never stored';
+COMMENT ON COLUMN email_change_requests.expires_at IS 'Request expiration timestamp';
+COMMENT ON COLUMN email_change_requests.used_at IS 'When the request was confirmed or superseded (NULL if still outstanding)';
+COMMENT ON COLUMN email_change_requests.created_at IS 'Request creation timestamp';
@@ -0,0 +1,8 @@
+-- Add an active flag to organizations
+-- Inactive tenants are kept for the record but can be filtered out of
+-- admin listings
+
+ALTER TABLE organizations
+    ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT TRUE;
+
+COMMENT ON COLUMN organizations.is_active IS 'Whether the tenant is active';
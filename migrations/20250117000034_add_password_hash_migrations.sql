@@ -0,0 +1,38 @@
+-- Flag users whose password hash uses outdated parameters
+-- Dormant accounts never log in to get their hash upgraded, so an admin
+-- job flags them and login then requires a password change
+
+ALTER TABLE users
+    ADD COLUMN must_change_password BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN users.must_change_password IS 'Whether the password must be changed before the next login';
+
+-- Flagged users are counted to report migration progress
+CREATE INDEX idx_users_must_change_password ON users(id) WHERE must_change_password;
+
+-- Runs of the job flagging outdated hashes
+CREATE TYPE password_hash_migration_status AS ENUM ('running', 'completed', 'failed');
+
+CREATE TABLE password_hash_migrations (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    started_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    status password_hash_migration_status NOT NULL DEFAULT 'running',
+    scanned_users BIGINT NOT NULL DEFAULT 0,
+    flagged_users BIGINT NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_password_hash_migrations_started_at ON password_hash_migrations(started_at DESC);
+
+-- Add comments for documentation
+COMMENT ON TABLE password_hash_migrations IS 'Runs of the admin job flagging outdated password hashes';
+COMMENT ON COLUMN password_hash_migrations.id IS 'UUID v7 primary key';
+COMMENT ON COLUMN password_hash_migrations.started_by IS 'Admin who started the run';
+COMMENT ON COLUMN password_hash_migrations.status IS 'running, completed or failed';
+COMMENT ON COLUMN password_hash_migrations.scanned_users IS 'Users checked so far';
+COMMENT ON COLUMN password_hash_migrations.flagged_users IS 'Users flagged so far';
+COMMENT ON COLUMN password_hash_migrations.error IS 'Why the run failed, if it did';
+COMMENT ON COLUMN password_hash_migrations.started_at IS 'When the run started';
+COMMENT ON COLUMN password_hash_migrations.finished_at IS 'When the run completed or failed';
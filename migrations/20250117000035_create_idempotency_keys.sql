@@ -0,0 +1,31 @@
+-- Responses stored per Idempotency-Key, replayed to clients retrying a request
+-- Keys are scoped to the caller (user, or client IP for anonymous requests)
+-- and the route, so one client's key never matches another's
+
+CREATE TABLE idempotency_keys (
+    scope TEXT NOT NULL,
+    route TEXT NOT NULL,
+    key TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    status_code SMALLINT,
+    content_type TEXT,
+    body BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (scope, route, key)
+);
+
+-- Cleanup job purges expired keys
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys(expires_at);
+
+-- Add comments for documentation
+COMMENT ON TABLE idempotency_keys IS 'Responses stored per Idempotency-Key header for replay on retry';
+COMMENT ON COLUMN idempotency_keys.scope IS 'Caller the key belongs to: user ID or client IP, and tenant';
+COMMENT ON COLUMN idempotency_keys.route IS 'Method and path of the request';
+COMMENT ON COLUMN idempotency_keys.key IS 'Idempotency-Key header value chosen by the client';
+COMMENT ON COLUMN idempotency_keys.request_hash IS 'SHA-256 of the request body, to refuse reusing a key for another request';
+COMMENT ON COLUMN idempotency_keys.status_code IS 'Stored response status; NULL while the first request is in progress';
+COMMENT ON COLUMN idempotency_keys.content_type IS 'Stored response Content-Type';
+COMMENT ON COLUMN idempotency_keys.body IS 'Stored response body';
+COMMENT ON COLUMN idempotency_keys.created_at IS 'When the first request with the key arrived';
+COMMENT ON COLUMN idempotency_keys.expires_at IS 'When the key may be reused for a new request';
@@ -0,0 +1,12 @@
+-- Create user_avatars table
+-- Uploaded avatars, normalized to PNG and served from
+-- GET /api/users/{id}/avatar; user_profiles.avatar_url points there
+
+CREATE TABLE user_avatars (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    data BYTEA NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE user_avatars IS 'Uploaded avatar images, one per user';
+COMMENT ON COLUMN user_avatars.data IS 'PNG image, at most AVATAR_MAX_DIMENSION pixels per side';
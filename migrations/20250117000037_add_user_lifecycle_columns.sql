@@ -0,0 +1,12 @@
+-- Add the remaining account lifecycle states to users
+-- Together with is_active, email_verified and deleted_at these make up the
+-- effective account status (AccountStatus in the auth domain).
+
+ALTER TABLE users
+ADD COLUMN locked_until TIMESTAMPTZ,
+ADD COLUMN deletion_scheduled_at TIMESTAMPTZ,
+ADD COLUMN expires_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN users.locked_until IS 'Logins are refused until this time (NULL when not locked)';
+COMMENT ON COLUMN users.deletion_scheduled_at IS 'When the account is due to be deleted (NULL when no deletion is pending)';
+COMMENT ON COLUMN users.expires_at IS 'When a time-limited (e.g. guest) account stops working (NULL for permanent accounts)';
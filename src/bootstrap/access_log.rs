@@ -0,0 +1,221 @@
+use crate::moduls::auth::api::middleware::AuthenticatedUser;
+use crate::shared::client_ip::client_ip;
+use crate::shared::request_id::RequestId;
+use axum::{extract::Request, http::Uri, middleware::Next, response::Response};
+use serde::Serialize;
+use std::time::Instant;
+
+/// Query parameters whose values never reach the access log
+const SENSITIVE_QUERY_PARAMS: &[&str] = &[
+    "access_token",
+    "api_key",
+    "client_secret",
+    "code",
+    "key",
+    "password",
+    "refresh_token",
+    "secret",
+    "state",
+    "token",
+];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// One access-log line
+#[derive(Debug, Serialize)]
+struct AccessLogEntry<'a> {
+    request_id: &'a str,
+    method: &'a str,
+    path: String,
+    status: u16,
+    latency_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_ip: Option<String>,
+}
+
+/// Emit one structured JSON line per request (target `access_log`)
+///
+/// Enabled with `ACCESS_LOG=true` in place of the `TraceLayer`, so requests
+/// are not logged twice. The request ID is the one assigned by the
+/// `request_id` middleware; the user ID is filled in when
+/// `jwt_auth_middleware` authenticated the caller.
+pub async fn access_log(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .cloned()
+        .unwrap_or_else(|| RequestId::from_headers(request.headers()));
+    let method = request.method().to_string();
+    let path = redact(request.uri());
+    let client_ip = client_ip(request.headers(), request.extensions());
+
+    let response = next.run(request).await;
+
+    let entry = AccessLogEntry {
+        request_id: request_id.as_str(),
+        method: &method,
+        path,
+        status: response.status().as_u16(),
+        latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+        user_id: response
+            .extensions()
+            .get::<AuthenticatedUser>()
+            .map(|user| user.user_id.to_string()),
+        client_ip,
+    };
+
+    match serde_json::to_string(&entry) {
+        Ok(line) => tracing::info!(target: "access_log", "{}", line),
+        Err(e) => tracing::error!("Failed to serialize access log entry: {}", e),
+    }
+
+    response
+}
+
+/// Path plus query string with sensitive parameter values replaced
+fn redact(uri: &Uri) -> String {
+    let Some(query) = uri.query() else {
+        return uri.path().to_string();
+    };
+
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if SENSITIVE_QUERY_PARAMS.contains(&key.to_ascii_lowercase().as_str()) => {
+                format!("{}={}", key, REDACTED)
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{}?{}", uri.path(), query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::client_ip::TrustedProxies;
+    use crate::shared::request_id::request_id;
+    use axum::extract::ConnectInfo;
+    use std::net::SocketAddr;
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Extension, Router};
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+
+    /// Collects formatted log output for assertions
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CapturedLogs {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    /// Run one request through the access log and return the emitted JSON line
+    async fn logged_line(app: Router, request: Request) -> (Response, serde_json::Value) {
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .with_level(false)
+            .with_target(false)
+            .without_time()
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let response = app
+            .layer(middleware::from_fn(access_log))
+            .layer(middleware::from_fn(request_id))
+            .oneshot(request)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let line = output.lines().last().expect("no access log line emitted");
+        // Skip the `request{request_id=..}:` span prefix
+        let json = &line[line.find("{\"").expect("no JSON in access log line")..];
+        (response, serde_json::from_str(json.trim()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_access_log_line_has_expected_fields() {
+        let user_id = crate::shared::types::UserId::new();
+        let app = Router::new().route(
+            "/api/auth/oauth/google/callback",
+            get(move || async move {
+                let user = AuthenticatedUser {
+                    user_id,
+                    tenant_id: None,
+                    authenticated_at: chrono::Utc::now(),
+                    roles: Vec::new(),
+                    impersonator: None,
+                };
+                (StatusCode::ACCEPTED, Extension(user))
+            }),
+        );
+        let request = Request::builder()
+            .uri("/api/auth/oauth/google/callback?code=secret-code&state=abc&lang=en")
+            .header("x-request-id", "req-123")
+            .header("x-forwarded-for", "203.0.113.7, 10.0.0.1")
+            .extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 2], 4000))))
+            .extension(TrustedProxies::parse("10.0.0.0/8").unwrap())
+            .body(Body::empty())
+            .unwrap();
+
+        let (response, line) = logged_line(app, request).await;
+
+        assert_eq!(response.headers()["x-request-id"], "req-123");
+        assert_eq!(line["request_id"], "req-123");
+        assert_eq!(line["method"], "GET");
+        assert_eq!(
+            line["path"],
+            "/api/auth/oauth/google/callback?code=[REDACTED]&state=[REDACTED]&lang=en"
+        );
+        assert_eq!(line["status"], 202);
+        assert!(line["latency_ms"].is_number());
+        assert_eq!(line["user_id"], user_id.to_string());
+        assert_eq!(line["client_ip"], "203.0.113.7");
+    }
+
+    #[tokio::test]
+    async fn test_access_log_generates_request_id() {
+        let app = Router::new().route("/health", get(|| async { "ok" }));
+        let request = Request::builder().uri("/health").body(Body::empty()).unwrap();
+
+        let (response, line) = logged_line(app, request).await;
+
+        let request_id = line["request_id"].as_str().unwrap();
+        assert!(uuid::Uuid::parse_str(request_id).is_ok());
+        assert_eq!(response.headers()["x-request-id"], request_id);
+        assert!(line.get("user_id").is_none());
+        assert!(line.get("client_ip").is_none());
+    }
+
+    #[test]
+    fn test_redact_leaves_plain_paths_alone() {
+        assert_eq!(redact(&Uri::from_static("/api/user/profile")), "/api/user/profile");
+        assert_eq!(
+            redact(&Uri::from_static("/reset?Token=abc&next=/home")),
+            "/reset?Token=[REDACTED]&next=/home"
+        );
+    }
+}
@@ -1,15 +1,49 @@
-use crate::config::Config;
+use crate::bootstrap::{idempotency::IdempotencyStore, rate_limit::RateLimiter, BackgroundTasks, Readiness};
+use crate::config::{AuditSinkKind, Config, MailerBackend, PasswordHashAlgorithm};
+use crate::moduls::audit::infra::{
+    HttpAuditSink, PostgresAuditEventRepository, PostgresAuditSink, SyslogAuditSink,
+};
+use crate::moduls::audit::AuditLog;
 use crate::moduls::auth::application::{
-    AuthConfig, LoginUserUseCase, LogoutUserUseCase, RefreshConfig, RefreshTokenUseCase,
-    RegisterUserUseCase,
+    AccountLifecycleUseCase, AccountLockout, AuthConfig, ConfirmTotpUseCase, EnableTotpUseCase, GetCurrentUserUseCase, HashMigrationUseCase, ImpersonateUserUseCase, ListUsersUseCase, LoginUserUseCase,
+    LogoutUserUseCase, ManageRolesUseCase, PasswordHistory, RefreshConfig, RefreshTokenUseCase, RegisterUserUseCase,
+    LockoutPolicy, ResetPasswordConfig, ResetPasswordUseCase, RevokeTenantCredentialsUseCase, RevokeTokenUseCase,
+    SecurityNotifier, SendLimits, SetUserStatusUseCase, TokenWatermark,
+    VerifyEmailUseCase,
+};
+use crate::moduls::auth::domain::{
+    ClaimsFormat, ClientHashing, JwtKeys, PasswordDenylist, PasswordHasher, PasswordPolicy,
 };
 use crate::moduls::auth::infra::{
-    PostgresSessionRepository, PostgresTokenRepository, PostgresUserRepository,
+    CachedTokenRepository, PostgresApiKeyRepository, PostgresEmailVerificationRepository, PostgresHashMigrationRepository, PostgresLoginAttemptRepository,
+    PostgresMfaChallengeRepository, PostgresPasswordHistoryRepository,
+    PostgresPasswordResetRepository, PostgresRoleRepository,
+    PostgresSessionRepository, PostgresTokenRepository, PostgresTokenWatermarkRepository, PostgresTotpRepository,
+    PostgresUserRepository, TokenRepository,
+};
+use crate::moduls::oauth::application::{
+    OAuthLoginConfig, OAuthLoginUseCase, UnlinkOAuthAccountUseCase,
+};
+use crate::moduls::oauth::infra::{
+    FlowStateStore, HttpOAuthClient, InMemoryFlowStateStore, PostgresOAuthAccountRepository,
+};
+use crate::moduls::organization::application::{
+    CreateOrganizationUseCase, JoinOrganizationUseCase, ListTenantsUseCase,
+    UpdateOrganizationUseCase,
+};
+use crate::moduls::organization::infra::{
+    PostgresMembershipRepository, PostgresOrganizationRepository,
 };
 use crate::moduls::user::application::{
-    ChangePasswordUseCase, GetProfileUseCase, UpdateProfileUseCase,
+    ChangeEmailUseCase, ChangePasswordUseCase, DeleteAccountUseCase, GetProfileUseCase, GetPublicProfileUseCase, ListAuditEventsUseCase, ListSessionsUseCase, ManageApiKeysUseCase, ManageAvatarUseCase,
+    NotificationPreferencesUseCase, RevokeSessionUseCase, UpdateProfileUseCase, VerifyPasswordLimits, VerifyPasswordUseCase,
 };
-use crate::moduls::user::infra::PostgresUserProfileRepository;
+use crate::moduls::user::domain::AvatarLimits;
+use crate::moduls::user::infra::{
+    PostgresAvatarRepository, PostgresEmailChangeRepository, PostgresUserProfileRepository,
+};
+use crate::shared::db::DbPools;
+use crate::shared::mailer::{LogMailer, Mailer, SmtpMailer};
 use sqlx::PgPool;
 use std::sync::Arc;
 
@@ -19,106 +53,594 @@ use std::sync::Arc;
 /// across the application. It implements Clone for use with Axum's State.
 #[derive(Clone)]
 pub struct AppState {
-    /// Database connection pool
+    /// Database connection pool (primary)
     pub db: PgPool,
 
+    /// Primary and replica pools, for pool stats (`POOL_STATS`)
+    pub pools: DbPools,
+
     /// Application configuration
     pub config: Config,
 
-    /// JWT secret for signing tokens
+    /// JWT secret (`JWT_SECRET`)
     pub jwt_secret: String,
 
+    /// Keys for signing and verifying tokens (HS256 secret or RS256 key pair)
+    pub jwt_keys: Arc<JwtKeys>,
+
     /// Session secret for cookie encryption
     pub session_secret: String,
 
     /// CSRF secret for token generation
     pub csrf_secret: String,
 
+    /// Readiness flag for the `/health/ready` probe
+    pub readiness: Readiness,
+
+    /// Tracker for side-effect tasks awaited at shutdown
+    pub background_tasks: BackgroundTasks,
+
     /// Repositories (exposed for direct access when needed)
-    pub token_repo: Arc<PostgresTokenRepository>,
+    pub user_repo: Arc<PostgresUserRepository>,
+    /// Cached in memory with `REVOCATION_CACHE`
+    pub token_repo: Arc<dyn TokenRepository>,
+    pub session_repo: Arc<PostgresSessionRepository>,
+    pub org_repo: Arc<PostgresOrganizationRepository>,
+    pub membership_repo: Arc<PostgresMembershipRepository>,
+    pub role_repo: Arc<PostgresRoleRepository>,
+    pub api_key_repo: Arc<PostgresApiKeyRepository>,
+
+    /// Token watermarks (checked by JWT middleware and refresh)
+    pub token_watermark: Arc<TokenWatermark>,
+
+    /// Outgoing email (`MAILER_BACKEND`)
+    pub mailer: Arc<dyn Mailer>,
+
+    /// OAuth flow state (in-process, or Redis with `OAUTH_STATE_REDIS_URL`)
+    pub flow_state_store: Arc<dyn FlowStateStore>,
+
+    /// Per-IP limit for login, registration and password reset requests
+    pub rate_limiter: Arc<RateLimiter>,
+
+    /// Responses stored per `Idempotency-Key`
+    pub idempotency_store: Arc<IdempotencyStore>,
 
     /// Auth use cases
     pub register_user_use_case: Arc<RegisterUserUseCase>,
     pub login_user_use_case: Arc<LoginUserUseCase>,
     pub logout_user_use_case: Arc<LogoutUserUseCase>,
     pub refresh_token_use_case: Arc<RefreshTokenUseCase>,
+    pub get_current_user_use_case: Arc<GetCurrentUserUseCase>,
+    pub reset_password_use_case: Arc<ResetPasswordUseCase>,
+    pub verify_email_use_case: Arc<VerifyEmailUseCase>,
+    pub enable_totp_use_case: Arc<EnableTotpUseCase>,
+    pub confirm_totp_use_case: Arc<ConfirmTotpUseCase>,
+    pub manage_roles_use_case: Arc<ManageRolesUseCase>,
+    pub revoke_token_use_case: Arc<RevokeTokenUseCase>,
+    pub revoke_tenant_credentials_use_case: Arc<RevokeTenantCredentialsUseCase>,
+    pub impersonate_user_use_case: Arc<ImpersonateUserUseCase>,
+    pub hash_migration_use_case: Arc<HashMigrationUseCase>,
+    pub list_users_use_case: Arc<ListUsersUseCase>,
+    pub set_user_status_use_case: Arc<SetUserStatusUseCase>,
+    pub account_lifecycle_use_case: Arc<AccountLifecycleUseCase>,
+
+    /// OAuth module use cases
+    pub oauth_login_use_case: Arc<OAuthLoginUseCase>,
+    pub unlink_oauth_account_use_case: Arc<UnlinkOAuthAccountUseCase>,
+
+    /// Organization module use cases
+    pub create_organization_use_case: Arc<CreateOrganizationUseCase>,
+    pub join_organization_use_case: Arc<JoinOrganizationUseCase>,
+    pub update_organization_use_case: Arc<UpdateOrganizationUseCase>,
+    pub list_tenants_use_case: Arc<ListTenantsUseCase>,
 
     /// User module use cases
     pub get_profile_use_case: Arc<GetProfileUseCase>,
+    pub get_public_profile_use_case: Arc<GetPublicProfileUseCase>,
     pub update_profile_use_case: Arc<UpdateProfileUseCase>,
+    pub manage_avatar_use_case: Arc<ManageAvatarUseCase>,
     pub change_password_use_case: Arc<ChangePasswordUseCase>,
+    pub verify_password_use_case: Arc<VerifyPasswordUseCase>,
+    pub list_sessions_use_case: Arc<ListSessionsUseCase>,
+    pub list_audit_events_use_case: Arc<ListAuditEventsUseCase>,
+    pub revoke_session_use_case: Arc<RevokeSessionUseCase>,
+    pub manage_api_keys_use_case: Arc<ManageApiKeysUseCase>,
+    pub notification_preferences_use_case: Arc<NotificationPreferencesUseCase>,
+    pub delete_account_use_case: Arc<DeleteAccountUseCase>,
+    pub change_email_use_case: Arc<ChangeEmailUseCase>,
 }
 
 impl AppState {
     /// Create a new AppState instance with repositories and use cases
+    ///
+    /// Repositories read from the replica in `db`, when it has one.
     pub fn new(
-        db: PgPool,
+        db: DbPools,
         config: Config,
-        jwt_secret: String,
+        jwt_keys: JwtKeys,
         session_secret: String,
         csrf_secret: String,
     ) -> Self {
+        let jwt_keys = Arc::new(jwt_keys);
+
         // Create repositories
         let user_repo = Arc::new(PostgresUserRepository::new(db.clone()));
-        let session_repo = Arc::new(PostgresSessionRepository::new(db.clone()));
-        let token_repo = Arc::new(PostgresTokenRepository::new(db.clone()));
+        let session_repo = PostgresSessionRepository::new(db.clone())
+            .with_max_concurrent(config.session.max_concurrent);
+        let session_repo = Arc::new(if config.session.soft_delete {
+            session_repo.with_soft_delete(config.session.retention as i64)
+        } else {
+            session_repo
+        });
+        let token_repo: Arc<dyn TokenRepository> = {
+            let postgres = Arc::new(PostgresTokenRepository::new(db.clone()));
+            if config.jwt.revocation_cache {
+                let ttl = std::time::Duration::from_secs(config.jwt.revocation_cache_ttl);
+                Arc::new(CachedTokenRepository::new(postgres, ttl))
+            } else {
+                postgres
+            }
+        };
         let profile_repo = Arc::new(PostgresUserProfileRepository::new(db.clone()));
+        let login_attempt_repo = Arc::new(PostgresLoginAttemptRepository::new(db.clone()));
+        let org_repo = Arc::new(PostgresOrganizationRepository::new(db.clone()));
+        let membership_repo = Arc::new(PostgresMembershipRepository::new(db.clone()));
+        let totp_repo = Arc::new(PostgresTotpRepository::new(db.clone()));
+        let mfa_challenge_repo = Arc::new(PostgresMfaChallengeRepository::new(db.clone()));
+        let role_repo = Arc::new(PostgresRoleRepository::new(db.clone()));
+        let api_key_repo = Arc::new(PostgresApiKeyRepository::new(db.clone()));
+        let token_watermark = Arc::new(TokenWatermark::new(
+            Arc::new(PostgresTokenWatermarkRepository::new(db.clone())),
+            user_repo.clone(),
+            config.security.tokens_valid_after,
+            std::time::Duration::from_secs(config.security.token_watermark_cache_ttl),
+        ));
+
+        // Audit trail: Postgres, plus the sinks in AUDIT_SINK
+        let background_tasks = BackgroundTasks::new();
+        let mut audit_log = AuditLog::new(
+            Arc::new(PostgresAuditSink::new(db.clone())),
+            background_tasks.clone(),
+        );
+        for sink in &config.audit.sinks {
+            audit_log = match sink {
+                AuditSinkKind::Syslog => audit_log.with_sink(Arc::new(SyslogAuditSink::new(
+                    config.audit.syslog_addr.clone(),
+                    config.audit.syslog_hostname.clone(),
+                ))),
+                AuditSinkKind::Http => match &config.audit.http_url {
+                    Some(url) => audit_log.with_sink(Arc::new(HttpAuditSink::new(url.clone()))),
+                    None => audit_log,
+                },
+            };
+        }
+        let audit_log = Arc::new(audit_log);
+
+        let claims_format = ClaimsFormat::from_minimal_flag(config.jwt.minimal_claims);
+
+        // Salts for client-side password hashing are derived with the session secret
+        let client_hashing = config.security.client_password_hashing.then(|| {
+            ClientHashing::new(session_secret.clone(), config.security.client_hash_iterations)
+        });
+
+        let password_hasher = match config.security.password_hasher {
+            PasswordHashAlgorithm::Bcrypt => PasswordHasher::Bcrypt {
+                cost: config.security.bcrypt_cost,
+            },
+            PasswordHashAlgorithm::Argon2 => PasswordHasher::Argon2,
+        };
 
         // Create auth config
         let auth_config = AuthConfig {
             session_ttl_seconds: config.session.expiry as i64,
+            session_remember_ttl_seconds: config.session.remember_expiry as i64,
+            session_min_ttl_seconds: config.session.min_expiry as i64,
+            session_max_ttl_seconds: config.session.max_expiry as i64,
             jwt_access_ttl_seconds: config.jwt.access_expiry as i64,
             jwt_refresh_ttl_seconds: config.jwt.refresh_expiry as i64,
+            claims_format,
+            require_verified_email: config.security.require_email_verification,
+            max_password_length: config.security.max_password_length,
+            mfa_challenge_ttl_seconds: config.security.mfa_challenge_ttl as i64,
+            device_name_max_length: config.session.device_name_max_length,
+            strip_zero_width_identifier: config.security.login_strip_zero_width,
+            client_hashing: client_hashing.clone(),
+            password_hasher,
         };
 
         let refresh_config = RefreshConfig {
-            jwt_secret: jwt_secret.clone(),
+            jwt_keys: jwt_keys.clone(),
             access_ttl_seconds: config.jwt.access_expiry as i64,
             refresh_ttl_seconds: config.jwt.refresh_expiry as i64,
+            claims_format,
+            grace_seconds: config.jwt.refresh_grace,
         };
 
         // Create use cases
-        let register_user_use_case = Arc::new(RegisterUserUseCase::new(user_repo.clone()));
+        let denylist = match &config.security.password_denylist_path {
+            Some(path) => {
+                let list = std::fs::read_to_string(path)
+                    .expect("PASSWORD_DENYLIST_PATH must be a readable file");
+                let denylist = PasswordDenylist::parse(&list);
+                tracing::info!("Loaded {} denied passwords from {}", denylist.len(), path);
+                denylist
+            }
+            None => PasswordDenylist::default(),
+        };
+        let password_policy = PasswordPolicy {
+            min_length: config.security.password_min_length,
+            max_length: config.security.password_max_length,
+            require_uppercase: config.security.password_require_uppercase,
+            require_lowercase: config.security.password_require_lowercase,
+            require_digit: config.security.password_require_digit,
+            require_symbol: config.security.password_require_symbol,
+            denylist,
+            history: (config.security.password_history_size > 0)
+                .then_some(config.security.password_history_size),
+        };
+        let password_history =
+            PasswordHistory::new(Arc::new(PostgresPasswordHistoryRepository::new(db.clone())));
+
+        let register_user_use_case = Arc::new(RegisterUserUseCase::new(
+            user_repo.clone(),
+            membership_repo.clone(),
+            config.security.max_password_length,
+            password_policy.clone(),
+        )
+        .with_client_hashing(client_hashing)
+        .with_password_hasher(password_hasher));
+
+        let mailer: Arc<dyn Mailer> = match config.mailer.backend {
+            MailerBackend::Log => Arc::new(LogMailer),
+            MailerBackend::Smtp => Arc::new(
+                SmtpMailer::new(&config.mailer).expect("SMTP mailer configuration must be valid"),
+            ),
+        };
+        let security_notifier = SecurityNotifier::new(mailer.clone());
+        let lockout = (config.security.lockout_threshold > 0).then(|| {
+            Arc::new(AccountLockout::new(
+                user_repo.clone(),
+                login_attempt_repo.clone(),
+                audit_log.clone(),
+                LockoutPolicy {
+                    threshold: config.security.lockout_threshold,
+                    duration_seconds: config.security.lockout_duration as i64,
+                },
+            ))
+        });
 
         let login_user_use_case = Arc::new(LoginUserUseCase::new(
             user_repo.clone(),
             session_repo.clone(),
             token_repo.clone(),
-            jwt_secret.clone(),
+            login_attempt_repo.clone(),
+            membership_repo.clone(),
+            totp_repo.clone(),
+            mfa_challenge_repo.clone(),
+            role_repo.clone(),
+            jwt_keys.clone(),
+            audit_log.clone(),
             auth_config,
-        ));
+        )
+        .with_notifier(security_notifier.clone())
+        .with_lockout(lockout.clone()));
 
         let logout_user_use_case = Arc::new(LogoutUserUseCase::new(
             session_repo.clone(),
             token_repo.clone(),
+            token_watermark.clone(),
+            audit_log.clone(),
         ));
 
         let refresh_token_use_case = Arc::new(RefreshTokenUseCase::new(
             token_repo.clone(),
+            token_watermark.clone(),
+            role_repo.clone(),
+            audit_log.clone(),
             refresh_config,
         ));
 
+        let get_current_user_use_case = Arc::new(GetCurrentUserUseCase::new(
+            user_repo.clone(),
+            login_attempt_repo.clone(),
+            config.security.login_activity_window as i64,
+        ));
+
+        let account_email_limits = SendLimits {
+            per_email: config.security.account_email_limit_per_email,
+            per_ip: config.security.account_email_limit_per_ip,
+            window_seconds: config.security.account_email_window as i64,
+        };
+
+        let reset_password_use_case = Arc::new(ResetPasswordUseCase::new(
+            user_repo.clone(),
+            Arc::new(PostgresPasswordResetRepository::new(db.clone())),
+            session_repo.clone(),
+            token_repo.clone(),
+            mailer.clone(),
+            audit_log.clone(),
+            ResetPasswordConfig {
+                reset_url: format!("{}/reset-password", config.mailer.app_url),
+                token_ttl_seconds: config.security.password_reset_ttl as i64,
+                max_password_length: config.security.max_password_length,
+                password_policy: password_policy.clone(),
+                password_hasher,
+                send_limits: account_email_limits,
+            },
+        )
+        .with_password_history(password_history.clone()));
+
+        let verify_email_use_case = Arc::new(VerifyEmailUseCase::new(
+            user_repo.clone(),
+            Arc::new(PostgresEmailVerificationRepository::new(db.clone())),
+            mailer.clone(),
+            format!("{}/api/auth/verify-email", config.mailer.app_url),
+            config.security.email_verification_ttl as i64,
+            account_email_limits,
+        ));
+
+        let enable_totp_use_case = Arc::new(EnableTotpUseCase::new(
+            user_repo.clone(),
+            totp_repo.clone(),
+            config.security.totp_issuer.clone(),
+        ));
+
+        let confirm_totp_use_case = Arc::new(ConfirmTotpUseCase::new(
+            user_repo.clone(),
+            totp_repo,
+            audit_log.clone(),
+        ));
+
+        let revoke_token_use_case = Arc::new(RevokeTokenUseCase::new(
+            token_repo.clone(),
+            jwt_keys.clone(),
+            config.jwt.refresh_grace,
+            audit_log.clone(),
+        ));
+
+        let revoke_tenant_credentials_use_case = Arc::new(RevokeTenantCredentialsUseCase::new(
+            org_repo.clone(),
+            token_repo.clone(),
+            session_repo.clone(),
+            audit_log.clone(),
+            config.tenancy.revoke_batch_size,
+        ));
+
+        let hash_migration_use_case = Arc::new(HashMigrationUseCase::new(
+            Arc::new(PostgresHashMigrationRepository::new(db.clone())),
+            password_hasher,
+            config.security.hash_migration_batch_size,
+            background_tasks.clone(),
+        ));
+
+        let list_users_use_case = Arc::new(ListUsersUseCase::new(user_repo.clone()));
+
+        let set_user_status_use_case = Arc::new(SetUserStatusUseCase::new(
+            user_repo.clone(),
+            session_repo.clone(),
+            token_repo.clone(),
+            api_key_repo.clone(),
+            audit_log.clone(),
+        ));
+
+        let account_lifecycle_use_case = Arc::new(AccountLifecycleUseCase::new(
+            user_repo.clone(),
+            session_repo.clone(),
+            token_repo.clone(),
+            api_key_repo.clone(),
+            audit_log.clone(),
+            config.security.anonymize_deleted_accounts,
+        ));
+
+        let impersonate_user_use_case = Arc::new(ImpersonateUserUseCase::new(
+            user_repo.clone(),
+            token_repo.clone(),
+            role_repo.clone(),
+            audit_log.clone(),
+            jwt_keys.clone(),
+            claims_format,
+            config.jwt.impersonation_ttl as i64,
+        ));
+
+        let manage_roles_use_case = Arc::new(ManageRolesUseCase::new(
+            user_repo.clone(),
+            role_repo.clone(),
+            audit_log.clone(),
+        ));
+
+        // Create OAuth module use cases
+        let flow_state_store: Arc<dyn FlowStateStore> = match &config.oauth.state_redis_url {
+            #[cfg(feature = "redis")]
+            Some(url) => Arc::new(
+                crate::moduls::oauth::infra::RedisFlowStateStore::new(url)
+                    .expect("OAUTH_STATE_REDIS_URL must be a valid Redis URL"),
+            ),
+            _ => Arc::new(InMemoryFlowStateStore::new()),
+        };
+
+        let oauth_account_repo = Arc::new(PostgresOAuthAccountRepository::new(db.clone()));
+        let oauth_login_use_case = Arc::new(OAuthLoginUseCase::new(
+            user_repo.clone(),
+            token_repo.clone(),
+            membership_repo.clone(),
+            mfa_challenge_repo.clone(),
+            oauth_account_repo.clone(),
+            Arc::new(HttpOAuthClient::new()),
+            flow_state_store.clone(),
+            config.oauth.providers.clone(),
+            OAuthLoginConfig {
+                jwt_keys: jwt_keys.clone(),
+                access_ttl_seconds: config.jwt.access_expiry as i64,
+                refresh_ttl_seconds: config.jwt.refresh_expiry as i64,
+                claims_format,
+                state_ttl_seconds: config.oauth.state_ttl as i64,
+                mfa_challenge_ttl_seconds: config.security.mfa_challenge_ttl as i64,
+            },
+        ));
+
+        let unlink_oauth_account_use_case =
+            Arc::new(UnlinkOAuthAccountUseCase::new(oauth_account_repo));
+
+        // Create organization module use cases
+        let create_organization_use_case = Arc::new(CreateOrganizationUseCase::new(
+            org_repo.clone(),
+            membership_repo.clone(),
+            config.tenancy.max_memberships_per_user,
+        ));
+
+        let join_organization_use_case = Arc::new(JoinOrganizationUseCase::new(
+            org_repo.clone(),
+            membership_repo.clone(),
+            config.tenancy.max_memberships_per_user,
+        ));
+
+        let update_organization_use_case =
+            Arc::new(UpdateOrganizationUseCase::new(org_repo.clone()));
+
+        let list_tenants_use_case = Arc::new(ListTenantsUseCase::new(
+            org_repo.clone(),
+            config.tenancy.admin_max_per_page,
+        ));
+
         // Create user module use cases
         let get_profile_use_case = Arc::new(GetProfileUseCase::new(profile_repo.clone()));
 
+        let get_public_profile_use_case = Arc::new(GetPublicProfileUseCase::new(profile_repo.clone()));
+        let notification_preferences_use_case =
+            Arc::new(NotificationPreferencesUseCase::new(user_repo.clone()));
+
         let update_profile_use_case = Arc::new(UpdateProfileUseCase::new(profile_repo.clone()));
 
-        let change_password_use_case = Arc::new(ChangePasswordUseCase::new(user_repo.clone()));
+        let manage_avatar_use_case = Arc::new(ManageAvatarUseCase::new(
+            Arc::new(PostgresAvatarRepository::new(db.clone())),
+            profile_repo.clone(),
+            AvatarLimits {
+                max_dimension: config.avatar.max_dimension,
+                max_source_dimension: config.avatar.max_source_dimension,
+            },
+            config.mailer.app_url.clone(),
+        ));
+
+        let change_password_use_case = Arc::new(ChangePasswordUseCase::new(
+            user_repo.clone(),
+            audit_log.clone(),
+            config.security.max_password_length,
+            password_policy,
+        )
+        .with_password_hasher(password_hasher)
+        .with_password_history(password_history)
+        .with_notifier(security_notifier));
+
+        let verify_password_use_case = Arc::new(VerifyPasswordUseCase::new(
+            user_repo.clone(),
+            login_attempt_repo,
+            config.security.max_password_length,
+            VerifyPasswordLimits {
+                max_failures: config.security.password_verify_max_failures,
+                window_seconds: config.security.password_verify_window as i64,
+            },
+        )
+        .with_lockout(lockout));
+
+        let list_sessions_use_case = Arc::new(ListSessionsUseCase::new(session_repo.clone()));
+
+        let list_audit_events_use_case = Arc::new(ListAuditEventsUseCase::new(Arc::new(
+            PostgresAuditEventRepository::new(db.clone()),
+        )));
+
+        let revoke_session_use_case =
+            Arc::new(RevokeSessionUseCase::new(session_repo.clone(), token_repo.clone(), audit_log.clone()));
+
+        let manage_api_keys_use_case =
+            Arc::new(ManageApiKeysUseCase::new(api_key_repo.clone(), audit_log.clone()));
+
+        let change_email_use_case = Arc::new(ChangeEmailUseCase::new(
+            user_repo.clone(),
+            Arc::new(PostgresEmailChangeRepository::new(db.clone())),
+            Arc::new(PostgresEmailVerificationRepository::new(db.clone())),
+            verify_password_use_case.clone(),
+            mailer.clone(),
+            audit_log.clone(),
+            format!("{}/api/user/email/confirm", config.mailer.app_url),
+            config.security.email_verification_ttl as i64,
+            account_email_limits,
+        ));
+
+        let delete_account_use_case = Arc::new(DeleteAccountUseCase::new(
+            user_repo.clone(),
+            session_repo.clone(),
+            token_repo.clone(),
+            api_key_repo.clone(),
+            verify_password_use_case.clone(),
+            audit_log,
+            config.security.anonymize_deleted_accounts,
+        ));
+
+        let rate_limiter = Arc::new(RateLimiter::new(config.security.rate_limit_per_minute));
+        let idempotency_store = Arc::new(IdempotencyStore::new(
+            db.clone(),
+            config.server.idempotency_key_ttl as i64,
+            &session_secret,
+        ));
 
         Self {
-            db,
+            db: db.primary().clone(),
+            pools: db.clone(),
+            jwt_secret: config.jwt.secret.clone(),
             config,
-            jwt_secret,
+            jwt_keys,
             session_secret,
             csrf_secret,
+            readiness: Readiness::new(),
+            background_tasks,
+            user_repo,
             token_repo,
+            session_repo,
+            org_repo,
+            membership_repo,
+            role_repo,
+            api_key_repo,
+            token_watermark,
+            mailer,
+            flow_state_store,
+            rate_limiter,
+            idempotency_store,
             register_user_use_case,
             login_user_use_case,
             logout_user_use_case,
             refresh_token_use_case,
+            get_current_user_use_case,
+            reset_password_use_case,
+            verify_email_use_case,
+            enable_totp_use_case,
+            confirm_totp_use_case,
+            manage_roles_use_case,
+            revoke_token_use_case,
+            revoke_tenant_credentials_use_case,
+            impersonate_user_use_case,
+            hash_migration_use_case,
+            list_users_use_case,
+            set_user_status_use_case,
+            account_lifecycle_use_case,
+            oauth_login_use_case,
+            unlink_oauth_account_use_case,
+            create_organization_use_case,
+            join_organization_use_case,
+            update_organization_use_case,
+            list_tenants_use_case,
             get_profile_use_case,
+            get_public_profile_use_case,
             update_profile_use_case,
+            manage_avatar_use_case,
             change_password_use_case,
+            verify_password_use_case,
+            list_sessions_use_case,
+            list_audit_events_use_case,
+            revoke_session_use_case,
+            manage_api_keys_use_case,
+            notification_preferences_use_case,
+            delete_account_use_case,
+            change_email_use_case,
         }
     }
 
@@ -133,6 +655,32 @@ impl AppState {
     }
 }
 
+#[cfg(test)]
+impl AppState {
+    /// Build an AppState for unit tests
+    ///
+    /// Uses a lazy pool that never connects unless a query is issued,
+    /// so tests must not reach code paths that hit the database.
+    pub(crate) fn for_tests() -> Self {
+        let config = Config::for_tests();
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(std::time::Duration::from_millis(100))
+            .connect_lazy(&config.database.url)
+            .expect("Failed to create lazy test pool");
+
+        let state = Self::new(
+            DbPools::from(db),
+            config.clone(),
+            JwtKeys::hmac(config.jwt.secret.clone()),
+            config.session.secret.clone(),
+            config.csrf.secret.clone(),
+        );
+        state.readiness.mark_ready();
+        state
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
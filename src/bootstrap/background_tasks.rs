@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::{Id, JoinSet};
+
+/// Most tasks in flight at once; beyond it new tasks are refused
+const MAX_TASKS: usize = 10_000;
+
+/// Tracker for fire-and-forget side effects (webhooks, emails)
+///
+/// Tasks spawned here are awaited during graceful shutdown so a deploy
+/// does not silently drop notifications. Clones share the same tracker.
+#[derive(Clone)]
+pub struct BackgroundTasks {
+    inner: Arc<Mutex<TrackedTasks>>,
+    capacity: usize,
+}
+
+#[derive(Default)]
+struct TrackedTasks {
+    set: JoinSet<()>,
+    names: HashMap<Id, &'static str>,
+    /// Set once shutdown starts; no task is accepted after that
+    closed: bool,
+}
+
+impl TrackedTasks {
+    /// Drop bookkeeping for tasks that already finished
+    fn reap_finished(&mut self) {
+        while let Some(result) = self.set.try_join_next_with_id() {
+            let id = match result {
+                Ok((id, ())) => id,
+                Err(e) => {
+                    tracing::error!("Background task {} failed: {}", self.name_of(e.id()), e);
+                    e.id()
+                }
+            };
+            self.names.remove(&id);
+        }
+    }
+
+    fn name_of(&self, id: Id) -> &'static str {
+        self.names.get(&id).copied().unwrap_or("unknown")
+    }
+}
+
+impl Default for BackgroundTasks {
+    fn default() -> Self {
+        Self::with_capacity(MAX_TASKS)
+    }
+}
+
+impl BackgroundTasks {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty tracker holding at most `capacity` tasks at once
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Arc::default(),
+            capacity,
+        }
+    }
+
+    /// Spawn a tracked side-effect task
+    ///
+    /// `name` is used in logs if the task fails or is dropped at shutdown.
+    /// Once shutdown has started, or while `capacity` tasks are in flight,
+    /// the task is dropped (and logged) instead of spawned; returns whether
+    /// it was spawned.
+    pub fn spawn<F>(&self, name: &'static str, task: F) -> bool
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.closed {
+            tracing::warn!("Background task {} refused: shutting down", name);
+            return false;
+        }
+
+        inner.reap_finished();
+        if inner.set.len() >= self.capacity {
+            tracing::warn!(
+                "Background task {} refused: {} task(s) already in flight",
+                name,
+                inner.set.len()
+            );
+            return false;
+        }
+
+        let handle = inner.set.spawn(task);
+        inner.names.insert(handle.id(), name);
+        true
+    }
+
+    /// Number of tasks that have not been reaped yet
+    pub fn pending(&self) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+        inner.reap_finished();
+        inner.set.len()
+    }
+
+    /// Close the tracker, then `drain` it
+    ///
+    /// Closing first means tasks spawned while draining are refused rather
+    /// than left untracked. Returns the number of tasks that were dropped.
+    pub async fn shutdown(&self, deadline: Duration) -> usize {
+        self.inner.lock().unwrap().closed = true;
+        self.drain(deadline).await
+    }
+
+    /// Wait for in-flight tasks up to `deadline`, then abort the rest
+    ///
+    /// The tracker stays open; tasks spawned meanwhile are kept for the
+    /// next drain. Returns the number of tasks that were dropped.
+    pub async fn drain(&self, deadline: Duration) -> usize {
+        let (mut set, names) = {
+            let mut inner = self.inner.lock().unwrap();
+            (std::mem::take(&mut inner.set), std::mem::take(&mut inner.names))
+        };
+
+        if set.is_empty() {
+            return 0;
+        }
+
+        tracing::info!("Waiting for {} background task(s) to finish...", set.len());
+
+        let drain = async {
+            while let Some(result) = set.join_next().await {
+                if let Err(e) = result {
+                    let name = names.get(&e.id()).copied().unwrap_or("unknown");
+                    tracing::error!("Background task {} failed: {}", name, e);
+                }
+            }
+        };
+
+        if tokio::time::timeout(deadline, drain).await.is_ok() {
+            return 0;
+        }
+
+        let dropped = set.len();
+        set.abort_all();
+        while let Some(result) = set.join_next_with_id().await {
+            let id = match result {
+                Ok((id, ())) => id,
+                Err(e) => e.id(),
+            };
+            let name = names.get(&id).copied().unwrap_or("unknown");
+            tracing::warn!("Dropped background task {} at shutdown deadline", name);
+        }
+
+        dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_pending_task() {
+        let tasks = BackgroundTasks::new();
+        let delivered = Arc::new(AtomicBool::new(false));
+
+        let flag = delivered.clone();
+        tasks.spawn("password_changed_email", async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            flag.store(true, Ordering::SeqCst);
+        });
+
+        let dropped = tasks.shutdown(Duration::from_secs(5)).await;
+
+        assert_eq!(dropped, 0);
+        assert!(delivered.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drops_tasks_past_deadline() {
+        let tasks = BackgroundTasks::new();
+
+        tasks.spawn("slow_webhook", async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+
+        let dropped = tasks.shutdown(Duration::from_millis(20)).await;
+
+        assert_eq!(dropped, 1);
+        assert_eq!(tasks.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tasks_spawned_during_shutdown_are_refused() {
+        let tasks = BackgroundTasks::new();
+        let late_spawned = Arc::new(AtomicBool::new(true));
+
+        // A draining task trying to spawn a follow-up (e.g. a retry)
+        let (spawner, flag) = (tasks.clone(), late_spawned.clone());
+        tasks.spawn("webhook", async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            flag.store(spawner.spawn("webhook_retry", async {}), Ordering::SeqCst);
+        });
+
+        let dropped = tasks.shutdown(Duration::from_secs(5)).await;
+
+        assert_eq!(dropped, 0);
+        assert!(!late_spawned.load(Ordering::SeqCst));
+        assert!(!tasks.spawn("after_shutdown", async {}));
+        assert_eq!(tasks.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_is_refused_at_capacity() {
+        let tasks = BackgroundTasks::with_capacity(2);
+
+        for _ in 0..2 {
+            assert!(tasks.spawn("slow_webhook", async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }));
+        }
+        assert!(!tasks.spawn("slow_webhook", async {}));
+        assert_eq!(tasks.pending(), 2);
+
+        tasks.shutdown(Duration::from_millis(10)).await;
+    }
+
+    #[tokio::test]
+    async fn test_finished_tasks_are_reaped() {
+        let tasks = BackgroundTasks::new();
+
+        tasks.spawn("quick", async {});
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert_eq!(tasks.pending(), 0);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::bootstrap::AppState;
+use crate::shared::AppError;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body_util::LengthLimitError;
+use std::time::Duration;
+
+/// Largest body buffered by `body_read_timeout` (matches Axum's default body limit)
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Abort requests whose body is not fully received in time
+///
+/// Buffers the body under `server.body_read_timeout` before the handler
+/// (and its own timeout) runs, so a client trickling a body (slow-loris)
+/// gets 408 Request Timeout instead of tying up the connection.
+pub async fn body_read_timeout(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let timeout = Duration::from_secs(state.config.server.body_read_timeout);
+    let (parts, body) = request.into_parts();
+
+    let bytes = match tokio::time::timeout(timeout, axum::body::to_bytes(body, MAX_BODY_BYTES)).await {
+        Ok(Ok(bytes)) => bytes,
+        Ok(Err(e)) if std::error::Error::source(&e).is_some_and(|s| s.is::<LengthLimitError>()) => {
+            return AppError::payload_too_large("Request body is too large").into_response();
+        }
+        Ok(Err(_)) => {
+            return AppError::bad_request("Failed to read request body").into_response();
+        }
+        Err(_) => {
+            tracing::warn!(
+                "Request body for {} {} not received within {:?}",
+                parts.method,
+                parts.uri.path(),
+                timeout
+            );
+            return AppError::request_timeout("Request body was not received in time").into_response();
+        }
+    };
+
+    next.run(Request::from_parts(parts, Body::from(bytes))).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let state = AppState::for_tests();
+        Router::new()
+            .route("/echo", post(|body: String| async move { body }))
+            .layer(middleware::from_fn_with_state(state, body_read_timeout))
+    }
+
+    fn post_body(body: impl Into<Body>) -> Request {
+        Request::builder()
+            .method("POST")
+            .uri("/echo")
+            .body(body.into())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_body_is_forwarded_to_handler() {
+        let response = app().oneshot(post_body("hello")).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_rejected() {
+        let response = app()
+            .oneshot(post_body(vec![b'a'; MAX_BODY_BYTES + 1]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
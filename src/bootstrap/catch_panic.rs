@@ -0,0 +1,84 @@
+use crate::shared::AppError;
+use axum::response::{IntoResponse, Response};
+use std::any::Any;
+use tower_http::catch_panic::CatchPanicLayer;
+
+type PanicHandler = fn(Box<dyn Any + Send + 'static>) -> Response;
+
+/// Turn handler panics into the JSON error envelope
+///
+/// The panic is logged as an internal error (inside the request's span, so
+/// with its request ID) and answered with a 500 `INTERNAL_ERROR`; like any
+/// internal error, the panic message is only in `details` in debug builds.
+/// The connection stays usable for the next request.
+pub fn catch_panic_layer() -> CatchPanicLayer<PanicHandler> {
+    CatchPanicLayer::custom(panic_response as PanicHandler)
+}
+
+fn panic_response(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".to_string());
+
+    AppError::internal(format!("Handler panicked: {}", message)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, http::StatusCode, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn panics() -> &'static str {
+        panic!("boom")
+    }
+
+    async fn panics_formatted() -> &'static str {
+        panic!("index {} out of range", 3)
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/panic", get(panics))
+            .route("/panic-formatted", get(panics_formatted))
+            .route("/ok", get(|| async { "ok" }))
+            .layer(catch_panic_layer())
+    }
+
+    async fn get_status(app: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_panic_becomes_structured_500() {
+        let app = app();
+
+        let (status, body) = get_status(&app, "/panic").await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["error"]["code"], "INTERNAL_ERROR");
+        assert_eq!(body["error"]["message"], "An internal error occurred");
+        assert_eq!(body["error"]["details"], "Handler panicked: boom");
+
+        // The service keeps serving
+        let (status, _) = get_status(&app, "/ok").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_formatted_panic_message_is_kept() {
+        let (status, body) = get_status(&app(), "/panic-formatted").await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["error"]["details"], "Handler panicked: index 3 out of range");
+    }
+}
@@ -1,34 +1,63 @@
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use crate::config::ConfigSource;
+use crate::shared::db::DbPools;
+use sqlx::{migrate::Migrator, postgres::PgPoolOptions, PgPool};
 use std::time::Duration;
 
 /// Configuration for database connection
 #[derive(Debug, Clone)]
 pub struct DatabaseConfig {
     pub url: String,
+    /// Read replica for read-only queries (`None`: everything uses `url`)
+    pub replica_url: Option<String>,
     pub max_connections: u32,
+    pub min_connections: u32,
     pub connect_timeout: u64,
+    /// Pre-acquire `min_connections` before reporting readiness
+    pub warmup: bool,
 }
 
 impl DatabaseConfig {
     /// Create database configuration from environment variables
     pub fn from_env() -> Result<Self, String> {
-        let url = std::env::var("DATABASE_URL")
+        Self::from_source(&ConfigSource::env())
+    }
+
+    /// Create database configuration from a configuration source
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let url = source.var("DATABASE_URL")
             .map_err(|_| "DATABASE_URL must be set".to_string())?;
 
-        let max_connections = std::env::var("DATABASE_MAX_CONNECTIONS")
+        let replica_url = source.var("DATABASE_REPLICA_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+
+        let max_connections = source.var("DATABASE_MAX_CONNECTIONS")
             .unwrap_or_else(|_| "10".to_string())
             .parse()
             .map_err(|_| "DATABASE_MAX_CONNECTIONS must be a valid number".to_string())?;
 
-        let connect_timeout = std::env::var("DATABASE_CONNECT_TIMEOUT")
+        let min_connections = source.var("DATABASE_MIN_CONNECTIONS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .map_err(|_| "DATABASE_MIN_CONNECTIONS must be a valid number".to_string())?;
+
+        let connect_timeout = source.var("DATABASE_CONNECT_TIMEOUT")
             .unwrap_or_else(|_| "30".to_string())
             .parse()
             .map_err(|_| "DATABASE_CONNECT_TIMEOUT must be a valid number".to_string())?;
 
+        let warmup = source.var("DATABASE_WARMUP")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| "DATABASE_WARMUP must be true or false".to_string())?;
+
         Ok(Self {
             url,
+            replica_url,
             max_connections,
+            min_connections,
             connect_timeout,
+            warmup,
         })
     }
 }
@@ -37,19 +66,13 @@ impl DatabaseConfig {
 pub async fn init_database(config: &DatabaseConfig) -> Result<PgPool, sqlx::Error> {
     tracing::info!("Initializing database connection pool...");
     tracing::debug!(
-        "Database config: max_connections={}, connect_timeout={}s",
+        "Database config: max_connections={}, min_connections={}, connect_timeout={}s",
         config.max_connections,
+        config.min_connections,
         config.connect_timeout
     );
 
-    let pool = PgPoolOptions::new()
-        .max_connections(config.max_connections)
-        .min_connections(5) // Keep minimum connections alive
-        .acquire_timeout(Duration::from_secs(config.connect_timeout))
-        .idle_timeout(Some(Duration::from_secs(600))) // 10 minutes
-        .max_lifetime(Some(Duration::from_secs(1800))) // 30 minutes
-        .connect(&config.url)
-        .await?;
+    let pool = connect(config, &config.url).await?;
 
     tracing::info!("Database connection pool initialized successfully");
 
@@ -59,6 +82,53 @@ pub async fn init_database(config: &DatabaseConfig) -> Result<PgPool, sqlx::Erro
     Ok(pool)
 }
 
+/// Primary pool plus the read replica pool, when `DATABASE_REPLICA_URL` is set
+///
+/// The replica uses the same pool settings as the primary.
+pub async fn init_pools(config: &DatabaseConfig, primary: PgPool) -> Result<DbPools, sqlx::Error> {
+    let Some(replica_url) = &config.replica_url else {
+        return Ok(DbPools::from(primary));
+    };
+
+    tracing::info!("Initializing read replica connection pool...");
+    let replica = connect(config, replica_url).await?;
+    health_check(&replica).await?;
+    tracing::info!("Read replica connection pool initialized successfully");
+
+    Ok(DbPools::new(primary, Some(replica)))
+}
+
+async fn connect(config: &DatabaseConfig, url: &str) -> Result<PgPool, sqlx::Error> {
+    PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections) // Keep minimum connections alive
+        .acquire_timeout(Duration::from_secs(config.connect_timeout))
+        .idle_timeout(Some(Duration::from_secs(600))) // 10 minutes
+        .max_lifetime(Some(Duration::from_secs(1800))) // 30 minutes
+        .connect(url)
+        .await
+}
+
+/// Warm up the connection pool
+///
+/// Acquires `connections` connections at the same time and runs a trivial
+/// query on each, so the first requests after a deploy don't pay the
+/// connection-establishment latency.
+pub async fn warmup_pool(pool: &PgPool, connections: u32) -> Result<(), sqlx::Error> {
+    tracing::info!("Warming up database pool ({} connections)...", connections);
+
+    // Hold every connection until the loop ends so each iteration opens a new one
+    let mut warmed = Vec::with_capacity(connections as usize);
+    for _ in 0..connections {
+        let mut conn = pool.acquire().await?;
+        sqlx::query("SELECT 1").execute(&mut *conn).await?;
+        warmed.push(conn);
+    }
+
+    tracing::info!("Database pool warmed up ({} connections)", warmed.len());
+    Ok(())
+}
+
 /// Check database health
 pub async fn health_check(pool: &PgPool) -> Result<(), sqlx::Error> {
     sqlx::query("SELECT 1")
@@ -69,6 +139,30 @@ pub async fn health_check(pool: &PgPool) -> Result<(), sqlx::Error> {
     Ok(())
 }
 
+/// Migrations embedded at compile time
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Check whether any embedded migration hasn't been applied yet
+///
+/// A database without the migrations table has every migration pending.
+pub async fn has_pending_migrations(pool: &PgPool) -> Result<bool, sqlx::Error> {
+    let applied: Vec<i64> =
+        match sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await
+        {
+            Ok(applied) => applied,
+            // undefined_table
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("42P01") => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .any(|m| !applied.contains(&m.version)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,11 +171,17 @@ mod tests {
     fn test_database_config_from_env() {
         std::env::set_var("DATABASE_URL", "postgres://localhost/test");
         std::env::set_var("DATABASE_MAX_CONNECTIONS", "5");
+        std::env::set_var("DATABASE_MIN_CONNECTIONS", "2");
         std::env::set_var("DATABASE_CONNECT_TIMEOUT", "20");
+        std::env::set_var("DATABASE_WARMUP", "true");
+        std::env::set_var("DATABASE_REPLICA_URL", "postgres://replica/test");
 
         let config = DatabaseConfig::from_env().unwrap();
         assert_eq!(config.url, "postgres://localhost/test");
+        assert_eq!(config.replica_url.as_deref(), Some("postgres://replica/test"));
         assert_eq!(config.max_connections, 5);
+        assert_eq!(config.min_connections, 2);
         assert_eq!(config.connect_timeout, 20);
+        assert!(config.warmup);
     }
 }
@@ -0,0 +1,159 @@
+use super::{AppState, Readiness};
+use crate::config::{MailerBackend, StartupConfig};
+use crate::shared::AppResult;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// A subsystem pinged before the listener binds
+pub struct DependencyCheck {
+    pub name: &'static str,
+    /// Failure aborts startup instead of marking the dependency degraded
+    pub required: bool,
+    pub timeout: Duration,
+    probe: Pin<Box<dyn Future<Output = AppResult<()>> + Send>>,
+}
+
+impl DependencyCheck {
+    pub fn new<F>(name: &'static str, required: bool, timeout: Duration, probe: F) -> Self
+    where
+        F: Future<Output = AppResult<()>> + Send + 'static,
+    {
+        Self {
+            name,
+            required,
+            timeout,
+            probe: Box::pin(probe),
+        }
+    }
+}
+
+/// Checks for the subsystems enabled in config
+///
+/// SMTP is checked when `MAILER_BACKEND=smtp`, Redis when
+/// `OAUTH_STATE_REDIS_URL` is set. The log mailer and in-process flow state
+/// have nothing to ping.
+pub fn configured_checks(state: &AppState) -> Vec<DependencyCheck> {
+    let startup = &state.config.startup;
+    let mut checks = Vec::new();
+
+    if state.config.mailer.backend == MailerBackend::Smtp {
+        let mailer = state.mailer.clone();
+        checks.push(check(startup, "smtp", async move { mailer.ping().await }));
+    }
+
+    if state.config.oauth.state_redis_url.is_some() {
+        let store = state.flow_state_store.clone();
+        checks.push(check(startup, "redis", async move { store.ping().await }));
+    }
+
+    checks
+}
+
+fn check<F>(startup: &StartupConfig, name: &'static str, probe: F) -> DependencyCheck
+where
+    F: Future<Output = AppResult<()>> + Send + 'static,
+{
+    DependencyCheck::new(name, startup.is_required(name), startup.timeout_for(name), probe)
+}
+
+/// Run every check concurrently, each bounded by its own timeout
+///
+/// A failing required dependency is returned as an error so the caller can
+/// abort startup. A failing optional one is logged and marked degraded on
+/// `readiness`.
+///
+/// # Errors
+/// - Description of the first required dependency that failed
+pub async fn run_dependency_checks(
+    checks: Vec<DependencyCheck>,
+    readiness: &Readiness,
+) -> Result<(), String> {
+    let pending: Vec<_> = checks
+        .into_iter()
+        .map(|check| {
+            let outcome = tokio::spawn(async move {
+                match tokio::time::timeout(check.timeout, check.probe).await {
+                    Ok(Ok(())) => Ok(()),
+                    Ok(Err(e)) => Err(e.to_string()),
+                    Err(_) => Err(format!("timed out after {:?}", check.timeout)),
+                }
+            });
+            (check.name, check.required, outcome)
+        })
+        .collect();
+
+    let mut first_failure = None;
+    for (name, required, outcome) in pending {
+        let outcome = outcome
+            .await
+            .unwrap_or_else(|e| Err(format!("check panicked: {}", e)));
+        match outcome {
+            Ok(()) => tracing::info!("Dependency {} is reachable", name),
+            Err(e) if required => {
+                tracing::error!("Required dependency {} failed its startup check: {}", name, e);
+                first_failure.get_or_insert(format!("{}: {}", name, e));
+            }
+            Err(e) => {
+                tracing::warn!("Dependency {} failed its startup check, continuing degraded: {}", name, e);
+                readiness.mark_degraded(name);
+            }
+        }
+    }
+
+    match first_failure {
+        Some(failure) => Err(failure),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::AppError;
+
+    fn failing(name: &'static str, required: bool) -> DependencyCheck {
+        DependencyCheck::new(name, required, Duration::from_secs(1), async {
+            Err(AppError::internal("connection refused"))
+        })
+    }
+
+    #[tokio::test]
+    async fn test_failing_required_dependency_aborts() {
+        let readiness = Readiness::new();
+
+        let result = run_dependency_checks(vec![failing("smtp", true)], &readiness).await;
+
+        assert!(result.unwrap_err().starts_with("smtp:"));
+    }
+
+    #[tokio::test]
+    async fn test_failing_optional_dependency_is_marked_degraded() {
+        let readiness = Readiness::new();
+        let checks = vec![
+            failing("redis", false),
+            DependencyCheck::new("smtp", true, Duration::from_secs(1), async { Ok(()) }),
+        ];
+
+        run_dependency_checks(checks, &readiness).await.unwrap();
+
+        assert_eq!(readiness.degraded(), vec!["redis".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_slow_dependency_times_out() {
+        let readiness = Readiness::new();
+        let hanging = DependencyCheck::new("redis", true, Duration::from_millis(10), async {
+            std::future::pending::<AppResult<()>>().await
+        });
+
+        let result = run_dependency_checks(vec![hanging], &readiness).await;
+
+        assert!(result.unwrap_err().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_log_mailer_and_in_memory_state_need_no_checks() {
+        assert!(configured_checks(&AppState::for_tests()).is_empty());
+    }
+}
@@ -0,0 +1,475 @@
+//! `Idempotency-Key` support for non-idempotent POSTs
+//!
+//! Clients retrying a request, e.g. registration over a flaky mobile
+//! connection, send the same `Idempotency-Key` header. The first response
+//! is stored for `IDEMPOTENCY_KEY_TTL` and replayed to the retries instead
+//! of running the request again. Requests without the header are untouched.
+//!
+//! Stored bodies are sealed (ChaCha20-Poly1305, keyed from `SESSION_SECRET`
+//! and bound to their key): registration's carries live tokens, which must
+//! not sit in the database in the clear. A body that can't be opened, e.g.
+//! after the secret changed, is refused with 409 rather than replayed.
+
+use crate::bootstrap::AppState;
+use crate::moduls::auth::api::middleware::AuthenticatedUser;
+use crate::moduls::organization::api::TenantContext;
+use crate::shared::{client_ip::client_ip, db::DbPools, AppError, AppResult};
+use axum::{
+    body::Body,
+    extract::{OriginalUri, Request, State},
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    ChaCha20Poly1305, Nonce,
+};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+/// Header carrying the client's key
+pub const IDEMPOTENCY_KEY_HEADER: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// Header marking a replayed response
+pub const IDEMPOTENT_REPLAYED_HEADER: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Longest accepted key
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Largest request or response body buffered for a keyed request (2 MB)
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// A key as scoped to its caller and route
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyKey {
+    /// User ID, or client IP for anonymous requests, and tenant
+    pub scope: String,
+    /// Method and path
+    pub route: String,
+    pub key: String,
+}
+
+/// Response stored for a key
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status_code: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl IntoResponse for StoredResponse {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status_code).unwrap_or(StatusCode::OK);
+        let mut response = (status, self.body).into_response();
+        let headers = response.headers_mut();
+        headers.remove(header::CONTENT_TYPE);
+        if let Some(value) = self.content_type.and_then(|ct| HeaderValue::from_str(&ct).ok()) {
+            headers.insert(header::CONTENT_TYPE, value);
+        }
+        headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+        response
+    }
+}
+
+/// Outcome of claiming a key for a request
+#[derive(Debug)]
+pub enum Claim {
+    /// First use (or the previous one expired): run the request
+    New,
+    /// Response of the first request with the key
+    Replay(StoredResponse),
+    /// The first request with the key hasn't completed yet
+    InProgress,
+    /// The key was used for a request with another body
+    Mismatch,
+    /// The stored body can't be opened (the secret changed)
+    Unreadable,
+}
+
+/// Nonce length of ChaCha20-Poly1305, prepended to sealed bodies
+const NONCE_LENGTH: usize = 12;
+
+/// Encryption of stored response bodies
+struct BodySeal {
+    cipher: ChaCha20Poly1305,
+}
+
+impl BodySeal {
+    /// Seal with a key derived from `secret`
+    fn new(secret: &str) -> Self {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(b"idempotency-response-body");
+        let key = mac.finalize().into_bytes();
+        Self {
+            cipher: ChaCha20Poly1305::new(&key),
+        }
+    }
+
+    /// Nonce followed by the ciphertext of `body`, bound to `key`
+    fn seal(&self, key: &IdempotencyKey, body: &[u8]) -> AppResult<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let aad = associated_data(key);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, Payload { msg: body, aad: &aad })
+            .map_err(|_| AppError::internal("Failed to seal idempotent response"))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    /// Body sealed by `seal` for the same `key`, if it opens
+    fn open(&self, key: &IdempotencyKey, sealed: &[u8]) -> Option<Vec<u8>> {
+        if sealed.len() < NONCE_LENGTH {
+            return None;
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+        let aad = associated_data(key);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: &aad })
+            .ok()
+    }
+}
+
+/// Binds a sealed body to its row, so it can't be replayed under another key
+fn associated_data(key: &IdempotencyKey) -> Vec<u8> {
+    format!("{}\n{}\n{}", key.scope, key.route, key.key).into_bytes()
+}
+
+/// Stored responses per idempotency key (`idempotency_keys` table)
+pub struct IdempotencyStore {
+    db: DbPools,
+    ttl_seconds: i64,
+    seal: BodySeal,
+}
+
+impl IdempotencyStore {
+    /// Store for `ttl_seconds`, sealing bodies with a key derived from `secret`
+    pub fn new(db: DbPools, ttl_seconds: i64, secret: &str) -> Self {
+        Self {
+            db,
+            ttl_seconds,
+            seal: BodySeal::new(secret),
+        }
+    }
+
+    /// Claim `key` for a request whose body hashes to `request_hash`
+    ///
+    /// Claims are atomic: of concurrent requests with the same key, one
+    /// gets `New` and the others `InProgress`.
+    pub async fn claim(&self, key: &IdempotencyKey, request_hash: &str) -> AppResult<Claim> {
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (scope, route, key, request_hash, expires_at)
+            VALUES ($1, $2, $3, $4, NOW() + make_interval(secs => $5))
+            ON CONFLICT (scope, route, key) DO UPDATE
+            SET request_hash = EXCLUDED.request_hash, status_code = NULL, content_type = NULL,
+                body = NULL, created_at = NOW(), expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at <= NOW()
+            "#,
+        )
+        .bind(&key.scope)
+        .bind(&key.route)
+        .bind(&key.key)
+        .bind(request_hash)
+        .bind(self.ttl_seconds as f64)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to claim idempotency key: {}", e)))?
+        .rows_affected();
+        if claimed > 0 {
+            return Ok(Claim::New);
+        }
+
+        let stored = sqlx::query_as::<_, (String, Option<i16>, Option<String>, Option<Vec<u8>>)>(
+            r#"
+            SELECT request_hash, status_code, content_type, body
+            FROM idempotency_keys
+            WHERE scope = $1 AND route = $2 AND key = $3
+            "#,
+        )
+        .bind(&key.scope)
+        .bind(&key.route)
+        .bind(&key.key)
+        .fetch_optional(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find idempotency key: {}", e)))?;
+
+        Ok(match stored {
+            Some((hash, _, _, _)) if hash != request_hash => Claim::Mismatch,
+            Some((_, Some(status_code), content_type, body)) => {
+                match self.seal.open(key, &body.unwrap_or_default()) {
+                    Some(body) => Claim::Replay(StoredResponse {
+                        status_code: status_code as u16,
+                        content_type,
+                        body,
+                    }),
+                    None => Claim::Unreadable,
+                }
+            }
+            // Still running, or released by a failed first request meanwhile
+            _ => Claim::InProgress,
+        })
+    }
+
+    /// Store the response of the request that claimed `key`, body sealed
+    pub async fn complete(&self, key: &IdempotencyKey, response: &StoredResponse) -> AppResult<()> {
+        let body = self.seal.seal(key, &response.body)?;
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys SET status_code = $4, content_type = $5, body = $6
+            WHERE scope = $1 AND route = $2 AND key = $3
+            "#,
+        )
+        .bind(&key.scope)
+        .bind(&key.route)
+        .bind(&key.key)
+        .bind(response.status_code as i16)
+        .bind(&response.content_type)
+        .bind(&body)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to store idempotent response: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Give up a claim without a response, so a retry runs the request again
+    pub async fn release(&self, key: &IdempotencyKey) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE scope = $1 AND route = $2 AND key = $3 AND status_code IS NULL
+            "#,
+        )
+        .bind(&key.scope)
+        .bind(&key.route)
+        .bind(&key.key)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to release idempotency key: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Delete expired keys, returning how many were deleted
+    pub async fn delete_expired(&self) -> AppResult<u64> {
+        let rows_affected = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= NOW()")
+            .execute(self.db.writer())
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to delete expired idempotency keys: {}", e)))?
+            .rows_affected();
+
+        Ok(rows_affected)
+    }
+}
+
+/// Replay the stored response of requests retried with an `Idempotency-Key`
+///
+/// Must run after the authentication middleware on authenticated routes,
+/// so keys are scoped to the user rather than the client IP.
+///
+/// # Flow
+/// 1. Without the header, run the request as is
+/// 2. Claim the key for this caller, route and request body
+/// 3. Replay a stored response (`Idempotent-Replayed: true`), or refuse
+///    with 409 while the first request runs or if the stored body can't
+///    be opened, and 400 for another body
+/// 4. Otherwise run the request and store its response; server errors
+///    release the key so a retry runs again
+pub async fn idempotency(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(value) = request.headers().get(&IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let Some(key) = value
+        .to_str()
+        .ok()
+        .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH)
+    else {
+        return AppError::bad_request("Idempotency-Key must be 1 to 255 visible ASCII characters")
+            .into_response();
+    };
+    let key = IdempotencyKey {
+        scope: scope(&request),
+        route: route(&request),
+        key: key.to_string(),
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_BODY_BYTES).await else {
+        return AppError::payload_too_large("Request body too large").into_response();
+    };
+    let request_hash = URL_SAFE_NO_PAD.encode(Sha256::digest(&bytes));
+
+    match state.idempotency_store.claim(&key, &request_hash).await {
+        Ok(Claim::New) => {}
+        Ok(Claim::Replay(stored)) => {
+            tracing::debug!("Replaying response for idempotency key on {}", key.route);
+            return stored.into_response();
+        }
+        Ok(Claim::InProgress) => {
+            return AppError::conflict("A request with this Idempotency-Key is still in progress")
+                .into_response()
+        }
+        Ok(Claim::Mismatch) => {
+            return AppError::bad_request("Idempotency-Key was already used for a different request")
+                .into_response()
+        }
+        Ok(Claim::Unreadable) => {
+            tracing::warn!("Stored response for idempotency key on {} can't be opened", key.route);
+            return AppError::conflict(
+                "The response to this Idempotency-Key can no longer be replayed; retry with a new key",
+            )
+            .into_response();
+        }
+        Err(e) => return e.into_response(),
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+    if response.status().is_server_error() {
+        release(&state, &key).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for idempotency key: {}", e);
+            release(&state, &key).await;
+            return AppError::internal("Failed to read response").into_response();
+        }
+    };
+    let stored = StoredResponse {
+        status_code: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        body: bytes.to_vec(),
+    };
+    if let Err(e) = state.idempotency_store.complete(&key, &stored).await {
+        // The request succeeded; a retry will get 409 until the key expires
+        tracing::error!("Failed to store response for idempotency key: {}", e);
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+async fn release(state: &AppState, key: &IdempotencyKey) {
+    if let Err(e) = state.idempotency_store.release(key).await {
+        tracing::error!("Failed to release idempotency key: {}", e);
+    }
+}
+
+/// Who the key belongs to: the user, else the client IP, within the tenant
+fn scope(request: &Request) -> String {
+    let caller = match request.extensions().get::<AuthenticatedUser>() {
+        Some(user) => format!("user:{}", user.user_id),
+        None => match client_ip(request.headers(), request.extensions()) {
+            Some(ip) => format!("ip:{}", ip),
+            None => "anonymous".to_string(),
+        },
+    };
+    match request.extensions().get::<TenantContext>() {
+        Some(tenant) => format!("{} tenant:{}", caller, tenant.organization_id),
+        None => caller,
+    }
+}
+
+/// Method and full path (before nesting stripped its prefix)
+fn route(request: &Request) -> String {
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map_or_else(|| request.uri().path(), |uri| uri.path());
+    format!("{} {}", request.method(), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::types::OrganizationId;
+    use crate::shared::client_ip::TrustedProxies;
+    use axum::extract::ConnectInfo;
+    use std::net::SocketAddr;
+
+    #[test]
+    fn test_keys_scoped_to_user_ip_and_tenant() {
+        let request = |ip: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("/register")
+                .header("x-forwarded-for", ip)
+                .extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 2], 4000))))
+                .extension(TrustedProxies::parse("10.0.0.0/8").unwrap())
+                .extension(OriginalUri("/api/auth/register".parse().unwrap()))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        assert_eq!(route(&request("10.0.0.1")), "POST /api/auth/register");
+        assert_ne!(scope(&request("10.0.0.1")), scope(&request("10.0.0.2")));
+
+        let mut tenant_request = request("10.0.0.1");
+        let organization_id = OrganizationId::new();
+        tenant_request.extensions_mut().insert(TenantContext { organization_id });
+        assert!(scope(&tenant_request).ends_with(&format!("tenant:{}", organization_id)));
+        assert_ne!(scope(&tenant_request), scope(&request("10.0.0.1")));
+    }
+
+    #[tokio::test]
+    async fn test_replayed_response_keeps_status_and_body() {
+        let stored = StoredResponse {
+            status_code: 201,
+            content_type: Some("application/json".to_string()),
+            body: br#"{"id":1}"#.to_vec(),
+        };
+
+        let response = stored.into_response();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[&IDEMPOTENT_REPLAYED_HEADER], "true");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], br#"{"id":1}"#);
+    }
+
+    fn key(key: &str) -> IdempotencyKey {
+        IdempotencyKey {
+            scope: "ip:10.0.0.1".to_string(),
+            route: "POST /api/auth/register".to_string(),
+            key: key.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_stored_body_is_sealed() {
+        let seal = BodySeal::new("test_session_secret_key_minimum_32_characters_long");
+        let body = br#"{"access_token":"secret"}"#;
+
+        let sealed = seal.seal(&key("retry-1"), body).unwrap();
+        assert!(!sealed.windows(6).any(|w| w == b"secret"), "Body should not be stored in the clear");
+        assert_eq!(seal.open(&key("retry-1"), &sealed).as_deref(), Some(&body[..]));
+
+        // Nonces differ between two seals of the same body
+        assert_ne!(seal.seal(&key("retry-1"), body).unwrap(), sealed);
+    }
+
+    #[test]
+    fn test_sealed_body_opens_only_for_its_key_and_secret() {
+        let seal = BodySeal::new("test_session_secret_key_minimum_32_characters_long");
+        let sealed = seal.seal(&key("retry-1"), b"{}").unwrap();
+
+        assert_eq!(seal.open(&key("retry-2"), &sealed), None);
+        let rotated = BodySeal::new("another_session_secret_key_minimum_32_characters");
+        assert_eq!(rotated.open(&key("retry-1"), &sealed), None);
+        // Unsealed bodies (stored before sealing) don't open either
+        assert_eq!(seal.open(&key("retry-1"), b"{}"), None);
+    }
+}
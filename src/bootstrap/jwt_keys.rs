@@ -0,0 +1,98 @@
+use crate::config::JwtConfig;
+use crate::moduls::auth::domain::JwtKeys;
+use crate::shared::{AppError, AppResult};
+use jsonwebtoken::Algorithm;
+
+/// Build the signing/verification keys selected by `JWT_ALGORITHM`
+///
+/// HS256 uses `JWT_SECRET`. RS256 reads the PEM files: the private key
+/// signs, the first public key must be its pair, and any further public
+/// keys are retired keys kept so their tokens verify until they expire.
+pub fn load_jwt_keys(config: &JwtConfig) -> AppResult<JwtKeys> {
+    let keys = if config.algorithm == Algorithm::RS256 {
+        load_rsa_keys(config)?
+    } else {
+        JwtKeys::hmac(config.secret.clone())
+    };
+
+    Ok(keys
+        .with_issuer(config.issuer.clone())
+        .with_audience(config.audience.clone()))
+}
+
+fn load_rsa_keys(config: &JwtConfig) -> AppResult<JwtKeys> {
+    let private_key_path = config
+        .private_key_path
+        .as_deref()
+        .ok_or_else(|| AppError::Config("JWT_PRIVATE_KEY_PATH must be set for RS256".to_string()))?;
+    let (current, retired) = config
+        .public_key_paths
+        .split_first()
+        .ok_or_else(|| AppError::Config("JWT_PUBLIC_KEY_PATH must be set for RS256".to_string()))?;
+
+    let mut keys = JwtKeys::rsa(&read_pem(private_key_path)?, &read_pem(current)?)?;
+    for path in retired {
+        keys = keys.with_retired_key(&read_pem(path)?)?;
+    }
+
+    tracing::info!(
+        "JWT signing with RS256 (kid {}, {} retired key(s))",
+        keys.kid().unwrap_or_default(),
+        retired.len()
+    );
+    Ok(keys)
+}
+
+fn read_pem(path: &str) -> AppResult<Vec<u8>> {
+    std::fs::read(path).map_err(|e| AppError::Config(format!("Failed to read JWT key {}: {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    fn rs256(public_keys: &[&str]) -> JwtConfig {
+        JwtConfig {
+            algorithm: Algorithm::RS256,
+            private_key_path: Some(fixture("jwt_rsa_b.pem")),
+            public_key_paths: public_keys.iter().map(|name| fixture(name)).collect(),
+            ..Config::for_tests().jwt
+        }
+    }
+
+    #[test]
+    fn test_hs256_is_the_default() {
+        let keys = load_jwt_keys(&Config::for_tests().jwt).unwrap();
+
+        assert_eq!(keys.algorithm(), Algorithm::HS256);
+        assert_eq!(keys.kid(), None);
+    }
+
+    #[test]
+    fn test_rs256_loads_current_and_retired_keys() {
+        let keys = load_jwt_keys(&rs256(&["jwt_rsa_b.pub.pem", "jwt_rsa_a.pub.pem"])).unwrap();
+
+        assert_eq!(keys.algorithm(), Algorithm::RS256);
+        assert_eq!(keys.jwks().keys.len(), 2);
+    }
+
+    #[test]
+    fn test_rs256_requires_matching_current_public_key() {
+        // The first public key must belong to the private key
+        let result = load_jwt_keys(&rs256(&["jwt_rsa_a.pub.pem", "jwt_rsa_b.pub.pem"]));
+
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
+
+    #[test]
+    fn test_rs256_missing_key_file() {
+        let result = load_jwt_keys(&rs256(&["does-not-exist.pem"]));
+
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
+}
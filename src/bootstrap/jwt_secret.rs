@@ -0,0 +1,97 @@
+use crate::shared::{metrics, types::*, AppError, AppResult};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::PgPool;
+
+/// Result of comparing the JWT secret with the one seen at last startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwtSecretStatus {
+    /// No fingerprint was stored yet (fresh database)
+    Recorded,
+    /// Same secret as the last startup
+    Unchanged,
+    /// Secret differs: every previously issued token will fail validation
+    Changed,
+}
+
+/// Fingerprint of the JWT secret
+///
+/// HMAC keyed with the secret over a fixed label, so the stored value
+/// does not reveal the secret.
+pub fn fingerprint(secret: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(b"jwt-secret-fingerprint");
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Compare the JWT secret with the stored fingerprint and record it
+///
+/// Logs a warning when the secret changed, since all tokens issued
+/// with the old secret are now rejected with "invalid signature".
+pub async fn check_jwt_secret(db: &PgPool, secret: &str) -> AppResult<JwtSecretStatus> {
+    let current = fingerprint(secret);
+
+    let previous = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT fingerprint
+        FROM jwt_secret_fingerprint
+        WHERE id
+        "#,
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::internal(format!("Failed to load JWT secret fingerprint: {}", e)))?;
+
+    let status = match previous {
+        None => JwtSecretStatus::Recorded,
+        Some(previous) if previous == current => return Ok(JwtSecretStatus::Unchanged),
+        Some(_) => JwtSecretStatus::Changed,
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO jwt_secret_fingerprint (id, fingerprint, updated_at)
+        VALUES (TRUE, $1, $2)
+        ON CONFLICT (id) DO UPDATE
+        SET fingerprint = EXCLUDED.fingerprint, updated_at = EXCLUDED.updated_at
+        "#,
+    )
+    .bind(&current)
+    .bind(now())
+    .execute(db)
+    .await
+    .map_err(|e| AppError::internal(format!("Failed to store JWT secret fingerprint: {}", e)))?;
+
+    if status == JwtSecretStatus::Changed {
+        tracing::warn!(
+            metric = metrics::JWT_SIGNATURE_FAILURES.name(),
+            "JWT_SECRET changed since the last startup: all previously issued tokens will be \
+             rejected with \"invalid signature\" and users must log in again. \
+             Expect a spike in {}; rotate with a signing keyring to avoid this.",
+            metrics::JWT_SIGNATURE_FAILURES.name()
+        );
+    }
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_is_stable_and_secret_specific() {
+        let a = fingerprint("test_jwt_secret_key_minimum_32_characters_long");
+
+        assert_eq!(a, fingerprint("test_jwt_secret_key_minimum_32_characters_long"));
+        assert_ne!(a, fingerprint("another_jwt_secret_key_minimum_32_characters"));
+        assert_eq!(a.len(), 64);
+        assert!(!a.contains("test_jwt_secret"));
+    }
+}
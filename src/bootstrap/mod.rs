@@ -1,5 +1,17 @@
+pub mod access_log;
 pub mod app_state;
+pub mod background_tasks;
+pub mod body_timeout;
+pub mod catch_panic;
 pub mod database;
+pub mod dependency_check;
+pub mod idempotency;
+pub mod jwt_keys;
+pub mod jwt_secret;
+pub mod rate_limit;
+pub mod readiness;
 pub mod telemetry;
 
 pub use app_state::AppState;
+pub use background_tasks::BackgroundTasks;
+pub use readiness::Readiness;
@@ -0,0 +1,196 @@
+use crate::bootstrap::AppState;
+use crate::shared::{client_ip::client_ip, types::*, AppError};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Per-IP token bucket for unauthenticated auth endpoints
+///
+/// Each client IP gets a bucket of `per_minute` requests that refills
+/// continuously at the same rate, so bursts up to the limit pass and a
+/// steady client is held to `per_minute`. State is per process, so with
+/// several instances the limit applies to each. A limit of 0 disables it.
+pub struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Timestamp,
+}
+
+/// Buckets kept before full (idle) ones are swept from the map
+const SWEEP_THRESHOLD: usize = 10_000;
+
+/// Bucket key for requests whose client address is unknown
+const UNKNOWN_CLIENT: &str = "unknown";
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token from `ip`'s bucket
+    ///
+    /// Returns the seconds until a token is available when the bucket is
+    /// empty (nothing is taken then).
+    pub fn check(&self, ip: &str) -> Result<(), u64> {
+        self.check_at(ip, now())
+    }
+
+    fn check_at(&self, ip: &str, at: Timestamp) -> Result<(), u64> {
+        if self.per_minute == 0 {
+            return Ok(());
+        }
+
+        let capacity = f64::from(self.per_minute);
+        let refill = |bucket: &Bucket| {
+            let elapsed = (at - bucket.updated_at).num_milliseconds().max(0) as f64 / 1000.0;
+            (bucket.tokens + elapsed * self.per_second()).min(capacity)
+        };
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() > SWEEP_THRESHOLD {
+            buckets.retain(|_, bucket| refill(bucket) < capacity);
+        }
+
+        let bucket = buckets.entry(ip.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: at,
+        });
+        bucket.tokens = refill(bucket);
+        bucket.updated_at = at;
+
+        if bucket.tokens < 1.0 {
+            let wait = (1.0 - bucket.tokens) / self.per_second();
+            return Err(wait.ceil().max(1.0) as u64);
+        }
+
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+
+    fn per_second(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+/// Throttle requests per client IP (`security.rate_limit_per_minute`)
+///
+/// Answers 429 Too Many Requests with a `Retry-After` header once the
+/// client's bucket is empty.
+pub async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let ip = client_ip(request.headers(), request.extensions());
+
+    if let Err(retry_after) = state
+        .rate_limiter
+        .check(ip.as_deref().unwrap_or(UNKNOWN_CLIENT))
+    {
+        tracing::warn!(
+            "Rate limit exceeded for {} on {}",
+            ip.as_deref().unwrap_or(UNKNOWN_CLIENT),
+            request.uri().path()
+        );
+        let mut response =
+            AppError::too_many_requests("Too many requests, please try again later").into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        return response;
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::client_ip::TrustedProxies;
+    use axum::extract::ConnectInfo;
+    use std::net::SocketAddr;
+    use axum::{body::Body, http::StatusCode, middleware, routing::post, Router};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_request_over_limit_is_refused() {
+        let limiter = RateLimiter::new(3);
+        let start = now();
+
+        for _ in 0..3 {
+            assert!(limiter.check_at("10.0.0.1", start).is_ok());
+        }
+        assert_eq!(limiter.check_at("10.0.0.1", start), Err(20));
+        assert!(limiter.check_at("10.0.0.2", start).is_ok());
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let limiter = RateLimiter::new(6);
+        let start = now();
+
+        for _ in 0..6 {
+            assert!(limiter.check_at("10.0.0.1", start).is_ok());
+        }
+        // One token every 10 seconds
+        let later = start + chrono::Duration::seconds(9);
+        assert_eq!(limiter.check_at("10.0.0.1", later), Err(1));
+        let later = start + chrono::Duration::seconds(10);
+        assert!(limiter.check_at("10.0.0.1", later).is_ok());
+        assert!(limiter.check_at("10.0.0.1", later).is_err());
+
+        // Never refills past the limit
+        let much_later = start + chrono::Duration::hours(1);
+        for _ in 0..6 {
+            assert!(limiter.check_at("10.0.0.1", much_later).is_ok());
+        }
+        assert!(limiter.check_at("10.0.0.1", much_later).is_err());
+    }
+
+    #[test]
+    fn test_zero_disables_limit() {
+        let limiter = RateLimiter::new(0);
+
+        for _ in 0..100 {
+            assert!(limiter.check("10.0.0.1").is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limited_response_has_retry_after() {
+        let mut state = AppState::for_tests();
+        state.rate_limiter = Arc::new(RateLimiter::new(2));
+        let app = Router::new()
+            .route("/login", post(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(state, rate_limit));
+        let request = || {
+            Request::builder()
+                .method("POST")
+                .uri("/login")
+                .header("x-forwarded-for", "203.0.113.7")
+                .extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 2], 4000))))
+                .extension(TrustedProxies::parse("10.0.0.0/8").unwrap())
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..2 {
+            let response = app.clone().oneshot(request()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = app.oneshot(request()).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "30");
+    }
+}
@@ -0,0 +1,160 @@
+use std::future::Future;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
+
+/// Readiness flag for the `/health/ready` probe
+///
+/// Starts out not ready and is flipped once startup work (such as the
+/// connection pool warmup) has completed. Clones share the same flag.
+///
+/// Also records optional dependencies that failed their startup check, so
+/// the health endpoints can report them as degraded.
+#[derive(Debug, Clone, Default)]
+pub struct Readiness {
+    ready: Arc<AtomicBool>,
+    degraded: Arc<Mutex<Vec<String>>>,
+}
+
+impl Readiness {
+    /// Create a new readiness flag (not ready)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if the application is ready to serve traffic
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Mark the application as ready
+    pub fn mark_ready(&self) {
+        self.ready.store(true, Ordering::Release);
+    }
+
+    /// Record a dependency as degraded
+    pub fn mark_degraded(&self, name: &str) {
+        let mut degraded = self.degraded.lock().unwrap();
+        if !degraded.iter().any(|d| d == name) {
+            degraded.push(name.to_string());
+        }
+    }
+
+    /// Dependencies recorded as degraded
+    pub fn degraded(&self) -> Vec<String> {
+        self.degraded.lock().unwrap().clone()
+    }
+
+    /// Run a warmup future and mark ready once it succeeds
+    ///
+    /// On failure the flag is left untouched and the error is returned,
+    /// so the caller decides whether to retry (see `gate_with_retry`).
+    pub async fn gate<F, E>(&self, warmup: F) -> Result<(), E>
+    where
+        F: Future<Output = Result<(), E>>,
+    {
+        warmup.await?;
+        self.mark_ready();
+        Ok(())
+    }
+
+    /// Run `warmup` until an attempt succeeds, then mark ready
+    ///
+    /// A failed attempt is retried after `backoff`, doubled after every
+    /// failure up to `max_backoff`. The flag stays unset in the meantime:
+    /// an instance whose pool can't connect never reports ready.
+    pub async fn gate_with_retry<F, Fut, E>(
+        &self,
+        mut warmup: F,
+        mut backoff: Duration,
+        max_backoff: Duration,
+    ) where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: std::fmt::Debug,
+    {
+        while let Err(e) = self.gate(warmup()).await {
+            tracing::warn!("Warmup failed, retrying in {:?}: {:?}", backoff, e);
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(max_backoff);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readiness_starts_not_ready() {
+        let readiness = Readiness::new();
+        assert!(!readiness.is_ready());
+
+        readiness.mark_ready();
+        assert!(readiness.is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_readiness_false_until_warmup_completes() {
+        let readiness = Readiness::new();
+        let (signal, warmup_done) = tokio::sync::oneshot::channel::<()>();
+
+        let gate = {
+            let readiness = readiness.clone();
+            tokio::spawn(async move {
+                readiness
+                    .gate(async move { warmup_done.await.map_err(|_| "warmup aborted") })
+                    .await
+            })
+        };
+
+        tokio::task::yield_now().await;
+        assert!(!readiness.is_ready());
+
+        signal.send(()).unwrap();
+        gate.await.unwrap().unwrap();
+        assert!(readiness.is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_readiness_stays_false_when_warmup_fails() {
+        let readiness = Readiness::new();
+
+        let result = readiness.gate(async { Err::<(), _>("connection refused") }).await;
+
+        assert!(result.is_err());
+        assert!(!readiness.is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_readiness_retries_warmup_until_it_succeeds() {
+        let readiness = Readiness::new();
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+
+        readiness
+            .gate_with_retry(
+                || {
+                    let attempts = attempts.clone();
+                    let readiness = readiness.clone();
+                    async move {
+                        let mut attempts = attempts.lock().unwrap();
+                        attempts.push(readiness.is_ready());
+                        if attempts.len() < 3 {
+                            Err("connection refused")
+                        } else {
+                            Ok(())
+                        }
+                    }
+                },
+                Duration::from_millis(1),
+                Duration::from_millis(2),
+            )
+            .await;
+
+        // Never ready while attempts were failing
+        assert_eq!(*attempts.lock().unwrap(), [false, false, false]);
+        assert!(readiness.is_ready());
+    }
+}
@@ -0,0 +1,64 @@
+use crate::moduls::auth::application::AccountLifecycleUseCase;
+
+/// Accounts deleted per run; the rest wait for the next one
+const BATCH_SIZE: u32 = 100;
+
+/// Scheduled account deletion job
+///
+/// Deletes the accounts whose deletion admins scheduled once it is due;
+/// scheduled periodically by `jobs::scheduler`. Until then the user can
+/// still log in, and an admin can cancel the deletion.
+pub async fn account_deletion_job(lifecycle: &AccountLifecycleUseCase) {
+    match lifecycle.delete_due(BATCH_SIZE).await {
+        Ok(deleted) => {
+            if deleted > 0 {
+                tracing::info!("Deleted {} accounts due for deletion", deleted);
+            } else {
+                tracing::debug!("No accounts due for deletion");
+            }
+        }
+        Err(e) => {
+            tracing::error!("Scheduled account deletion failed: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::audit::AuditLog;
+    use crate::moduls::auth::domain::{AccountStatus, Email, User};
+    use crate::moduls::auth::infra::in_memory::*;
+    use crate::moduls::auth::infra::UserRepository;
+    use crate::shared::types::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_deletes_accounts_once_due() {
+        let user = |email: &str| {
+            User::new(Email::new(email).unwrap(), "password123", "Some User".to_string()).unwrap()
+        };
+        let mut due = user("due@example.com");
+        due.schedule_deletion(now() - chrono::Duration::seconds(1)).unwrap();
+        let mut later = user("later@example.com");
+        later.schedule_deletion(now() + chrono::Duration::days(30)).unwrap();
+        let user_repo = Arc::new(InMemoryUserRepository::default());
+        user_repo.save(&due).await.unwrap();
+        user_repo.save(&later).await.unwrap();
+        let lifecycle = AccountLifecycleUseCase::new(
+            user_repo.clone(),
+            Arc::new(InMemorySessionRepository::default()),
+            Arc::new(InMemoryTokenRepository::default()),
+            Arc::new(InMemoryApiKeyRepository::default()),
+            Arc::new(AuditLog::for_tests()),
+            true,
+        );
+
+        account_deletion_job(&lifecycle).await;
+
+        let due = user_repo.find_by_id(due.id).await.unwrap().unwrap();
+        let later = user_repo.find_by_id(later.id).await.unwrap().unwrap();
+        assert_eq!(due.status(), AccountStatus::Deleted);
+        assert_eq!(later.status(), AccountStatus::PendingDeletion);
+    }
+}
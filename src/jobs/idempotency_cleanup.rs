@@ -0,0 +1,21 @@
+use crate::bootstrap::idempotency::IdempotencyStore;
+
+/// Idempotency key cleanup job
+///
+/// Deletes keys past `IDEMPOTENCY_KEY_TTL` along with their stored
+/// responses; scheduled periodically by `jobs::scheduler`. Expired keys
+/// are already ignored by the middleware, this only reclaims the space.
+pub async fn idempotency_cleanup_job(store: &IdempotencyStore) {
+    match store.delete_expired().await {
+        Ok(deleted) => {
+            if deleted > 0 {
+                tracing::info!("Cleaned up {} expired idempotency keys", deleted);
+            } else {
+                tracing::debug!("No expired idempotency keys to clean up");
+            }
+        }
+        Err(e) => {
+            tracing::error!("Idempotency key cleanup failed: {:?}", e);
+        }
+    }
+}
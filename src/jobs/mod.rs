@@ -1,5 +1,11 @@
+pub mod account_deletion;
+pub mod idempotency_cleanup;
+pub mod scheduler;
 pub mod session_cleanup;
 pub mod token_cleanup;
 
+pub use account_deletion::account_deletion_job;
+pub use idempotency_cleanup::idempotency_cleanup_job;
+pub use scheduler::{start_cleanup_jobs, Scheduler};
 pub use session_cleanup::session_cleanup_job;
 pub use token_cleanup::token_cleanup_job;
@@ -0,0 +1,148 @@
+//! Periodic background jobs
+//!
+//! Jobs run on their own tokio task until `Scheduler::shutdown`, which lets
+//! a run in progress finish so a cleanup is never cut off mid-statement.
+
+use crate::bootstrap::AppState;
+use crate::jobs::{account_deletion_job, idempotency_cleanup_job, session_cleanup_job, token_cleanup_job};
+use crate::moduls::auth::infra::{SessionRepository, TokenRepository};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use tokio::time::MissedTickBehavior;
+
+/// Handle to the running periodic jobs
+pub struct Scheduler {
+    shutdown: watch::Sender<bool>,
+    tasks: JoinSet<()>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            shutdown: watch::channel(false).0,
+            tasks: JoinSet::new(),
+        }
+    }
+
+    /// Run `job` right away and then every `period`
+    ///
+    /// A run that overruns the period delays the next one instead of
+    /// triggering a burst of catch-up runs.
+    pub fn every<F, Fut>(&mut self, name: &'static str, period: Duration, mut job: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut shutdown = self.shutdown.subscribe();
+
+        self.tasks.spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            tracing::info!("Job {} scheduled every {:?}", name, period);
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => job().await,
+                    _ = shutdown.changed() => break,
+                }
+            }
+
+            tracing::debug!("Job {} stopped", name);
+        });
+    }
+
+    /// Stop scheduling runs and wait for runs in progress to finish
+    pub async fn shutdown(mut self) {
+        self.shutdown.send_replace(true);
+
+        while let Some(result) = self.tasks.join_next().await {
+            if let Err(e) = result {
+                tracing::error!("Scheduled job failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Schedule the expired session and token cleanups (`cleanup_interval`)
+pub fn start_cleanup_jobs(state: &AppState) -> Scheduler {
+    let period = Duration::from_secs(state.config.server.cleanup_interval);
+    let mut scheduler = Scheduler::new();
+
+    let session_repo: Arc<dyn SessionRepository> = state.session_repo.clone();
+    scheduler.every("session_cleanup", period, move || {
+        let session_repo = session_repo.clone();
+        async move { session_cleanup_job(session_repo.as_ref()).await }
+    });
+
+    let token_repo: Arc<dyn TokenRepository> = state.token_repo.clone();
+    scheduler.every("token_cleanup", period, move || {
+        let token_repo = token_repo.clone();
+        async move { token_cleanup_job(token_repo.as_ref()).await }
+    });
+
+    let idempotency_store = state.idempotency_store.clone();
+    scheduler.every("idempotency_cleanup", period, move || {
+        let idempotency_store = idempotency_store.clone();
+        async move { idempotency_cleanup_job(&idempotency_store).await }
+    });
+
+    let account_lifecycle = state.account_lifecycle_use_case.clone();
+    scheduler.every("account_deletion", period, move || {
+        let account_lifecycle = account_lifecycle.clone();
+        async move { account_deletion_job(&account_lifecycle).await }
+    });
+
+    scheduler
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn counting(scheduler: &mut Scheduler, period: Duration) -> Arc<AtomicUsize> {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        scheduler.every("test", period, move || {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        runs
+    }
+
+    #[tokio::test]
+    async fn test_job_runs_on_interval() {
+        let mut scheduler = Scheduler::new();
+        let runs = counting(&mut scheduler, Duration::from_millis(10));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        scheduler.shutdown().await;
+
+        assert!(runs.load(Ordering::SeqCst) >= 2);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_jobs() {
+        let mut scheduler = Scheduler::new();
+        let runs = counting(&mut scheduler, Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        scheduler.shutdown().await;
+        let after_shutdown = runs.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert!(after_shutdown >= 1);
+        assert_eq!(runs.load(Ordering::SeqCst), after_shutdown);
+    }
+}
@@ -1,49 +1,44 @@
-use sqlx::PgPool;
-use tokio::time::{interval, Duration};
+use crate::moduls::auth::infra::SessionRepository;
 
 /// Session cleanup job
 ///
-/// Runs periodically to delete expired sessions from the database.
-/// This helps keep the sessions table clean and performant.
-pub async fn session_cleanup_job(pool: PgPool) {
-    let mut interval = interval(Duration::from_secs(3600)); // Every hour
-
-    tracing::info!("Session cleanup job started (running every 1 hour)");
-
-    loop {
-        interval.tick().await;
-
-        match cleanup_expired_sessions(&pool).await {
-            Ok(deleted) => {
-                if deleted > 0 {
-                    tracing::info!("Cleaned up {} expired sessions", deleted);
-                } else {
-                    tracing::debug!("No expired sessions to clean up");
-                }
-            }
-            Err(e) => {
-                tracing::error!("Session cleanup failed: {:?}", e);
+/// Deletes expired sessions from the database; scheduled periodically by
+/// `jobs::scheduler`. This helps keep the sessions table clean and
+/// performant. With `SESSION_SOFT_DELETE`, sessions are only purged once
+/// the retention window has passed.
+pub async fn session_cleanup_job(session_repo: &dyn SessionRepository) {
+    match session_repo.delete_expired().await {
+        Ok(deleted) => {
+            if deleted > 0 {
+                tracing::info!("Cleaned up {} expired sessions", deleted);
+            } else {
+                tracing::debug!("No expired sessions to clean up");
             }
         }
+        Err(e) => {
+            tracing::error!("Session cleanup failed: {:?}", e);
+        }
     }
 }
 
-/// Delete expired sessions from database
-async fn cleanup_expired_sessions(pool: &PgPool) -> Result<u64, sqlx::Error> {
-    let result = sqlx::query("DELETE FROM sessions WHERE expires_at < NOW()")
-        .execute(pool)
-        .await?;
-
-    Ok(result.rows_affected())
-}
-
 #[cfg(test)]
 mod tests {
-    
+    use super::*;
+    use crate::moduls::auth::domain::Session;
+    use crate::moduls::auth::infra::in_memory::InMemorySessionRepository;
+    use crate::shared::types::*;
 
     #[tokio::test]
     async fn test_cleanup_expired_sessions() {
-        // This test requires a database connection
-        // Skip in unit tests, run in integration tests instead
+        let repo = InMemorySessionRepository::default();
+        let live = repo.save(&Session::new(UserId::new(), None, None, 3600)).await.unwrap();
+        let mut expired = Session::new(UserId::new(), None, None, 3600);
+        expired.expires_at = now() - chrono::Duration::seconds(1);
+        repo.save(&expired).await.unwrap();
+
+        session_cleanup_job(&repo).await;
+
+        assert!(repo.find_by_id(live.id).await.unwrap().is_some());
+        assert!(repo.find_by_id(expired.id).await.unwrap().is_none());
     }
 }
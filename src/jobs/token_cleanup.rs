@@ -1,49 +1,44 @@
-use sqlx::PgPool;
-use tokio::time::{interval, Duration};
+use crate::moduls::auth::infra::TokenRepository;
 
 /// Token cleanup job
 ///
-/// Runs periodically to delete expired JWT tokens from the database.
-/// This helps keep the jwt_tokens table clean and performant.
-pub async fn token_cleanup_job(pool: PgPool) {
-    let mut interval = interval(Duration::from_secs(21600)); // Every 6 hours
-
-    tracing::info!("Token cleanup job started (running every 6 hours)");
-
-    loop {
-        interval.tick().await;
-
-        match cleanup_expired_tokens(&pool).await {
-            Ok(deleted) => {
-                if deleted > 0 {
-                    tracing::info!("Cleaned up {} expired JWT tokens", deleted);
-                } else {
-                    tracing::debug!("No expired JWT tokens to clean up");
-                }
-            }
-            Err(e) => {
-                tracing::error!("Token cleanup failed: {:?}", e);
+/// Deletes expired JWT tokens from the database; scheduled periodically
+/// by `jobs::scheduler`. This helps keep the jwt_tokens table clean and
+/// performant.
+pub async fn token_cleanup_job(token_repo: &dyn TokenRepository) {
+    match token_repo.delete_expired().await {
+        Ok(deleted) => {
+            if deleted > 0 {
+                tracing::info!("Cleaned up {} expired JWT tokens", deleted);
+            } else {
+                tracing::debug!("No expired JWT tokens to clean up");
             }
         }
+        Err(e) => {
+            tracing::error!("Token cleanup failed: {:?}", e);
+        }
     }
 }
 
-/// Delete expired JWT tokens from database
-async fn cleanup_expired_tokens(pool: &PgPool) -> Result<u64, sqlx::Error> {
-    let result = sqlx::query("DELETE FROM jwt_tokens WHERE expires_at < NOW()")
-        .execute(pool)
-        .await?;
-
-    Ok(result.rows_affected())
-}
-
 #[cfg(test)]
 mod tests {
-    
+    use super::*;
+    use crate::moduls::auth::domain::{JwtKeys, TokenPair};
+    use crate::moduls::auth::infra::in_memory::InMemoryTokenRepository;
+    use crate::shared::types::*;
 
     #[tokio::test]
     async fn test_cleanup_expired_tokens() {
-        // This test requires a database connection
-        // Skip in unit tests, run in integration tests instead
+        let repo = InMemoryTokenRepository::default();
+        let keys = JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars");
+        let (_, mut access, refresh) = TokenPair::generate(UserId::new(), &keys, 900, 604800).unwrap();
+        access.expires_at = now() - chrono::Duration::seconds(1);
+        repo.save(&access).await.unwrap();
+        repo.save(&refresh).await.unwrap();
+
+        token_cleanup_job(&repo).await;
+
+        assert!(repo.find_by_jti(access.jti).await.unwrap().is_none());
+        assert!(repo.find_by_jti(refresh.jti).await.unwrap().is_some());
     }
 }
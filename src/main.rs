@@ -1,13 +1,15 @@
-mod bootstrap;
-mod config;
-mod shared;
-mod startup;
-mod moduls;
-mod jobs;
-
-use bootstrap::{app_state::AppState, database::init_database, telemetry::init_telemetry};
-use config::Config;
+use multitenant::bootstrap::{
+    app_state::AppState,
+    database::{init_database, init_pools, warmup_pool},
+    dependency_check::{configured_checks, run_dependency_checks},
+    jwt_keys::load_jwt_keys,
+    jwt_secret::check_jwt_secret,
+    telemetry::init_telemetry,
+};
+use multitenant::config::Config;
+use multitenant::{jobs, startup};
 use std::net::SocketAddr;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -18,9 +20,9 @@ async fn main() -> anyhow::Result<()> {
     init_telemetry()?;
     tracing::info!("Starting Multitenant Auth Application...");
 
-    // 3. Load configuration from environment
+    // 3. Load configuration from CONFIG_FILE and the environment
     tracing::info!("Loading configuration...");
-    let config = Config::from_env()
+    let config = Config::load()
         .map_err(|e| anyhow::anyhow!("Failed to load configuration: {}", e))?;
     tracing::info!("Configuration loaded successfully");
 
@@ -39,30 +41,63 @@ async fn main() -> anyhow::Result<()> {
         .map_err(|e| anyhow::anyhow!("Failed to run migrations: {}", e))?;
     tracing::info!("Database migrations completed");
 
+    // 5.5. Detect a JWT secret change (invalidates every issued token)
+    if let Err(e) = check_jwt_secret(&db, &config.jwt.secret).await {
+        tracing::warn!("Could not check JWT secret fingerprint: {}", e);
+    }
+
+    // 5.6. Connect the read replica, if configured
+    let pools = init_pools(&config.database, db)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to initialize read replica: {}", e))?;
+
+    // 5.7. Load the JWT signing keys (HS256 secret or RS256 key files)
+    let jwt_keys = load_jwt_keys(&config.jwt)
+        .map_err(|e| anyhow::anyhow!("Failed to load JWT keys: {}", e))?;
+
     // 6. Create application state
     let state = AppState::new(
-        db,
+        pools,
         config.clone(),
-        config.jwt.secret.clone(),
+        jwt_keys,
         config.session.secret.clone(),
         config.csrf.secret.clone(),
     );
 
+    // 6.2. Ping dependent subsystems (SMTP, Redis) before accepting traffic
+    if config.startup.check_dependencies {
+        tracing::info!("Checking dependencies...");
+        run_dependency_checks(configured_checks(&state), &state.readiness)
+            .await
+            .map_err(|e| anyhow::anyhow!("Dependency check failed: {}", e))?;
+    }
+
+    // 6.5. Warm up the connection pool before reporting readiness; stay
+    // not ready (retrying with backoff) until the database answers
+    if config.database.warmup {
+        let readiness = state.readiness.clone();
+        let pool = state.db().clone();
+        let connections = config.database.min_connections;
+        tokio::spawn(async move {
+            readiness
+                .gate_with_retry(
+                    || warmup_pool(&pool, connections),
+                    Duration::from_secs(1),
+                    Duration::from_secs(30),
+                )
+                .await;
+        });
+    } else {
+        state.readiness.mark_ready();
+    }
+
     // 7. Build Axum application with all routes and middleware
     tracing::info!("Building application...");
     let app = startup::build_app(state.clone()).await;
 
-    // 7.5. Spawn background cleanup jobs
+    // 7.5. Schedule background cleanup jobs
     tracing::info!("Starting background cleanup jobs...");
-    let db_for_session_cleanup = state.db().clone();
-    tokio::spawn(async move {
-        jobs::session_cleanup_job(db_for_session_cleanup).await;
-    });
-
-    let db_for_token_cleanup = state.db().clone();
-    tokio::spawn(async move {
-        jobs::token_cleanup_job(db_for_token_cleanup).await;
-    });
+    let scheduler = jobs::start_cleanup_jobs(&state);
     tracing::info!("Background cleanup jobs started successfully");
 
     // 8. Parse server address
@@ -84,9 +119,47 @@ async fn main() -> anyhow::Result<()> {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", addr, e))?;
 
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal())
         .await
         .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
 
+    // 10. Stop the cleanup jobs and let in-flight side effects (emails,
+    // webhooks) finish
+    scheduler.shutdown().await;
+    let grace_period = Duration::from_secs(config.server.shutdown_grace_period);
+    let dropped = state.background_tasks.shutdown(grace_period).await;
+    if dropped > 0 {
+        tracing::warn!("{} background task(s) dropped at shutdown", dropped);
+    }
+    tracing::info!("Shutdown complete");
+
     Ok(())
 }
+
+/// Resolve on Ctrl+C or SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutdown signal received, draining connections...");
+}
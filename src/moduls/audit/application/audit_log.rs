@@ -0,0 +1,133 @@
+use crate::bootstrap::BackgroundTasks;
+use super::current_client;
+use crate::moduls::audit::domain::AuditEvent;
+use crate::moduls::audit::infra::AuditSink;
+use std::sync::Arc;
+
+/// Audit trail writer
+///
+/// Each event is written to the primary sink (Postgres) first; the
+/// secondary sinks (syslog, HTTP) are then fed from background tasks, so a
+/// slow or failing collector never delays or fails the primary write or
+/// the request that produced the event.
+pub struct AuditLog {
+    primary: Arc<dyn AuditSink>,
+    secondary: Vec<Arc<dyn AuditSink>>,
+    tasks: BackgroundTasks,
+}
+
+impl AuditLog {
+    pub fn new(primary: Arc<dyn AuditSink>, tasks: BackgroundTasks) -> Self {
+        Self {
+            primary,
+            secondary: Vec::new(),
+            tasks,
+        }
+    }
+
+    /// Also forward events to `sink`
+    pub fn with_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.secondary.push(sink);
+        self
+    }
+
+    /// Record an event
+    ///
+    /// The IP address and User-Agent default to the client of the request
+    /// being handled (see `audit_context`). Never fails: audit problems are
+    /// logged, not surfaced to the caller.
+    pub async fn record(&self, mut event: AuditEvent) {
+        let client = current_client();
+        event.ip_address = event.ip_address.or(client.ip_address);
+        event.user_agent = event.user_agent.or(client.user_agent);
+
+        if let Err(e) = self.primary.write(&event).await {
+            tracing::error!(
+                "Failed to write audit event {} to {}: {}",
+                event.action.as_str(),
+                self.primary.name(),
+                e
+            );
+        }
+
+        for sink in &self.secondary {
+            let sink = sink.clone();
+            let event = event.clone();
+            self.tasks.spawn("audit_sink", async move {
+                if let Err(e) = sink.write(&event).await {
+                    tracing::warn!(
+                        "Failed to forward audit event {} to {}: {}",
+                        event.action.as_str(),
+                        sink.name(),
+                        e
+                    );
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+impl AuditLog {
+    /// AuditLog writing to a capturing sink only
+    pub fn for_tests() -> Self {
+        Self::new(
+            Arc::new(crate::moduls::audit::infra::in_memory::CapturingAuditSink::default()),
+            BackgroundTasks::new(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::audit::domain::AuditAction;
+    use crate::moduls::audit::infra::in_memory::CapturingAuditSink;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_events_reach_every_sink() {
+        let primary = Arc::new(CapturingAuditSink::default());
+        let siem = Arc::new(CapturingAuditSink::default());
+        let tasks = BackgroundTasks::new();
+        let audit = AuditLog::new(primary.clone(), tasks.clone()).with_sink(siem.clone());
+        let user_id = crate::shared::types::UserId::new();
+
+        audit
+            .record(
+                AuditEvent::new(AuditAction::LoginSucceeded, Some(user_id))
+                    .with_ip_address(Some("10.0.0.1".to_string())),
+            )
+            .await;
+        tasks.shutdown(Duration::from_secs(1)).await;
+
+        assert_eq!(primary.actions(), vec!["login_succeeded"]);
+        let events = siem.events.lock().unwrap();
+        let event = &events[0];
+        assert_eq!(event["action"], "login_succeeded");
+        assert_eq!(event["user_id"], user_id.to_string());
+        assert_eq!(event["tenant_id"], serde_json::Value::Null);
+        assert_eq!(event["ip_address"], "10.0.0.1");
+        assert!(event["id"].is_string());
+        assert!(event["occurred_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_failing_secondary_sink_does_not_affect_primary() {
+        let primary = Arc::new(CapturingAuditSink::default());
+        let other = Arc::new(CapturingAuditSink::default());
+        let tasks = BackgroundTasks::new();
+        let audit = AuditLog::new(primary.clone(), tasks.clone())
+            .with_sink(Arc::new(CapturingAuditSink::failing()))
+            .with_sink(other.clone());
+
+        audit.record(AuditEvent::new(AuditAction::Logout, None)).await;
+        audit.record(AuditEvent::new(AuditAction::PasswordChanged, None)).await;
+        tasks.shutdown(Duration::from_secs(1)).await;
+
+        assert_eq!(primary.actions(), vec!["logout", "password_changed"]);
+        let mut forwarded = other.actions();
+        forwarded.sort();
+        assert_eq!(forwarded, vec!["logout", "password_changed"]);
+    }
+}
@@ -0,0 +1,7 @@
+//! Application layer for audit module
+
+pub mod audit_log;
+pub mod request_client;
+
+pub use audit_log::AuditLog;
+pub use request_client::{audit_context, current_client, RequestClient};
@@ -0,0 +1,99 @@
+use crate::shared::client_ip::client_ip;
+use axum::{extract::Request, http::header, middleware::Next, response::Response};
+
+/// Longest User-Agent kept on audit events, in bytes
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// Client of the request being handled, as recorded on audit events
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestClient {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+tokio::task_local! {
+    static REQUEST_CLIENT: RequestClient;
+}
+
+/// Client of the request being handled (empty outside `audit_context`)
+pub fn current_client() -> RequestClient {
+    REQUEST_CLIENT.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// Make the client's IP address and User-Agent available to `AuditLog`
+///
+/// Events recorded while handling the request get them unless the use
+/// case set its own.
+pub async fn audit_context(request: Request, next: Next) -> Response {
+    let client = RequestClient {
+        ip_address: client_ip(request.headers(), request.extensions()),
+        user_agent: request
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+            .map(|v| truncate(v, MAX_USER_AGENT_LENGTH).to_string()),
+    };
+
+    REQUEST_CLIENT.scope(client, next.run(request)).await
+}
+
+fn truncate(value: &str, max: usize) -> &str {
+    if value.len() <= max {
+        return value;
+    }
+    let mut end = max;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    &value[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::client_ip::TrustedProxies;
+    use axum::extract::ConnectInfo;
+    use std::net::SocketAddr;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_client_is_available_while_handling() {
+        let app = Router::new()
+            .route(
+                "/",
+                get(|| async {
+                    let client = current_client();
+                    format!("{:?}|{:?}", client.ip_address, client.user_agent)
+                }),
+            )
+            .layer(middleware::from_fn(audit_context));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header("x-forwarded-for", "203.0.113.7")
+                    .header(header::USER_AGENT, "curl/8.0")
+                    .extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 2], 4000))))
+                    .extension(TrustedProxies::parse("10.0.0.0/8").unwrap())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], br#"Some("203.0.113.7")|Some("curl/8.0")"#);
+        assert_eq!(current_client(), RequestClient::default());
+    }
+
+    #[test]
+    fn test_long_user_agent_is_truncated_on_a_char_boundary() {
+        let agent = format!("{}é", "a".repeat(MAX_USER_AGENT_LENGTH - 1));
+
+        assert_eq!(truncate(&agent, MAX_USER_AGENT_LENGTH).len(), MAX_USER_AGENT_LENGTH - 1);
+        assert_eq!(truncate("short", MAX_USER_AGENT_LENGTH), "short");
+    }
+}
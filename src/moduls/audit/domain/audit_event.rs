@@ -0,0 +1,159 @@
+use crate::shared::types::*;
+use serde::Serialize;
+
+/// Audited authentication actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    LoginSucceeded,
+    LoginFailed,
+    Logout,
+    SessionRevoked,
+    PasswordChanged,
+    PasswordReset,
+    TwoFactorEnabled,
+    MfaFailed,
+    RoleGranted,
+    RoleRevoked,
+    ApiKeyCreated,
+    ApiKeyRevoked,
+    TokenRevoked,
+    TokenRefreshed,
+    TenantCredentialsRevoked,
+    ImpersonationStarted,
+    AccountDeleted,
+    EmailChanged,
+    AccountDeactivated,
+    AccountReactivated,
+    AccountLocked,
+    AccountUnlocked,
+    AccountExpiryChanged,
+    AccountDeletionScheduled,
+    AccountDeletionCancelled,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 25] = [
+        AuditAction::LoginSucceeded,
+        AuditAction::LoginFailed,
+        AuditAction::Logout,
+        AuditAction::SessionRevoked,
+        AuditAction::PasswordChanged,
+        AuditAction::PasswordReset,
+        AuditAction::TwoFactorEnabled,
+        AuditAction::MfaFailed,
+        AuditAction::RoleGranted,
+        AuditAction::RoleRevoked,
+        AuditAction::ApiKeyCreated,
+        AuditAction::ApiKeyRevoked,
+        AuditAction::TokenRevoked,
+        AuditAction::TokenRefreshed,
+        AuditAction::TenantCredentialsRevoked,
+        AuditAction::ImpersonationStarted,
+        AuditAction::AccountDeleted,
+        AuditAction::EmailChanged,
+        AuditAction::AccountDeactivated,
+        AuditAction::AccountReactivated,
+        AuditAction::AccountLocked,
+        AuditAction::AccountUnlocked,
+        AuditAction::AccountExpiryChanged,
+        AuditAction::AccountDeletionScheduled,
+        AuditAction::AccountDeletionCancelled,
+    ];
+
+    /// Action with the stored name `name`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_str() == name)
+    }
+
+    /// Stable event name, as stored and exported
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::LoginSucceeded => "login_succeeded",
+            AuditAction::LoginFailed => "login_failed",
+            AuditAction::Logout => "logout",
+            AuditAction::SessionRevoked => "session_revoked",
+            AuditAction::PasswordChanged => "password_changed",
+            AuditAction::PasswordReset => "password_reset",
+            AuditAction::TwoFactorEnabled => "two_factor_enabled",
+            AuditAction::MfaFailed => "mfa_failed",
+            AuditAction::RoleGranted => "role_granted",
+            AuditAction::RoleRevoked => "role_revoked",
+            AuditAction::ApiKeyCreated => "api_key_created",
+            AuditAction::ApiKeyRevoked => "api_key_revoked",
+            AuditAction::TokenRevoked => "token_revoked",
+            AuditAction::TokenRefreshed => "token_refreshed",
+            AuditAction::TenantCredentialsRevoked => "tenant_credentials_revoked",
+            AuditAction::ImpersonationStarted => "impersonation_started",
+            AuditAction::AccountDeleted => "account_deleted",
+            AuditAction::EmailChanged => "email_changed",
+            AuditAction::AccountDeactivated => "account_deactivated",
+            AuditAction::AccountReactivated => "account_reactivated",
+            AuditAction::AccountLocked => "account_locked",
+            AuditAction::AccountUnlocked => "account_unlocked",
+            AuditAction::AccountExpiryChanged => "account_expiry_changed",
+            AuditAction::AccountDeletionScheduled => "account_deletion_scheduled",
+            AuditAction::AccountDeletionCancelled => "account_deletion_cancelled",
+        }
+    }
+
+    /// Failures are worth a SIEM's attention; the rest is routine
+    pub fn is_failure(&self) -> bool {
+        matches!(self, AuditAction::LoginFailed | AuditAction::MfaFailed)
+    }
+}
+
+/// A single audit trail entry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditEvent {
+    pub id: uuid::Uuid,
+    pub action: AuditAction,
+    pub user_id: Option<UserId>,
+    pub tenant_id: Option<OrganizationId>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub occurred_at: Timestamp,
+}
+
+impl AuditEvent {
+    pub fn new(action: AuditAction, user_id: Option<UserId>) -> Self {
+        Self {
+            id: new_id(),
+            action,
+            user_id,
+            tenant_id: None,
+            ip_address: None,
+            user_agent: None,
+            occurred_at: now(),
+        }
+    }
+
+    pub fn with_tenant(mut self, tenant_id: Option<OrganizationId>) -> Self {
+        self.tenant_id = tenant_id;
+        self
+    }
+
+    pub fn with_ip_address(mut self, ip_address: Option<String>) -> Self {
+        self.ip_address = ip_address;
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: Option<String>) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_names_match_serialization() {
+        for action in AuditAction::ALL {
+            assert_eq!(serde_json::to_value(action).unwrap(), action.as_str());
+            assert_eq!(AuditAction::from_name(action.as_str()), Some(action));
+        }
+        assert_eq!(AuditAction::from_name("unknown"), None);
+    }
+}
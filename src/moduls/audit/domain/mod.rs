@@ -0,0 +1,5 @@
+//! Domain layer for audit module
+
+pub mod audit_event;
+
+pub use audit_event::{AuditAction, AuditEvent};
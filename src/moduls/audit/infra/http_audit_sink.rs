@@ -0,0 +1,92 @@
+use super::AuditSink;
+use crate::moduls::audit::domain::AuditEvent;
+use crate::shared::{AppError, AppResult};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// HTTP AuditSink: POSTs each event as JSON to a collector
+pub struct HttpAuditSink {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl HttpAuditSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            // A stuck collector must not pile up background tasks
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            url: url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for HttpAuditSink {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn write(&self, event: &AuditEvent) -> AppResult<()> {
+        let response = self
+            .http
+            .post(&self.url)
+            .json(event)
+            .send()
+            .await
+            .map_err(|e| AppError::internal(format!("Audit HTTP request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::internal(format!(
+                "Audit collector answered {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::audit::domain::AuditAction;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_posts_event_as_json() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/audit"))
+            .respond_with(ResponseTemplate::new(202))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let sink = HttpAuditSink::new(format!("{}/audit", server.uri()));
+        let event = AuditEvent::new(AuditAction::PasswordChanged, Some(crate::shared::types::UserId::new()));
+
+        sink.write(&event).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = requests[0].body_json().unwrap();
+        assert_eq!(body["action"], "password_changed");
+        assert_eq!(body["user_id"], event.user_id.unwrap().to_string());
+    }
+
+    #[tokio::test]
+    async fn test_error_status_fails() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        let sink = HttpAuditSink::new(server.uri());
+
+        let result = sink.write(&AuditEvent::new(AuditAction::Logout, None)).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,71 @@
+//! In-memory sink and repository implementations for unit tests
+
+use super::{AuditEventRepository, AuditSink};
+use crate::moduls::audit::domain::AuditEvent;
+use crate::shared::{types::UserId, AppError, AppResult};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// AuditSink that keeps the serialized events (or always fails)
+#[derive(Default)]
+pub struct CapturingAuditSink {
+    pub events: Mutex<Vec<serde_json::Value>>,
+    pub fail: bool,
+}
+
+impl CapturingAuditSink {
+    pub fn failing() -> Self {
+        Self {
+            fail: true,
+            ..Default::default()
+        }
+    }
+
+    /// Names of the captured actions, in order
+    pub fn actions(&self) -> Vec<String> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e["action"].as_str().unwrap_or_default().to_string())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl AuditSink for CapturingAuditSink {
+    fn name(&self) -> &'static str {
+        "capturing"
+    }
+
+    async fn write(&self, event: &AuditEvent) -> AppResult<()> {
+        if self.fail {
+            return Err(AppError::internal("sink unavailable"));
+        }
+        let value = serde_json::to_value(event)
+            .map_err(|e| AppError::internal(format!("Failed to serialize audit event: {}", e)))?;
+        self.events.lock().unwrap().push(value);
+        Ok(())
+    }
+}
+
+/// In-memory AuditEventRepository
+#[derive(Default)]
+pub struct InMemoryAuditEventRepository {
+    /// Oldest first
+    pub events: Mutex<Vec<AuditEvent>>,
+}
+
+#[async_trait]
+impl AuditEventRepository for InMemoryAuditEventRepository {
+    async fn find_recent_by_user(&self, user_id: UserId, limit: u32) -> AppResult<Vec<AuditEvent>> {
+        let events = self.events.lock().unwrap();
+        Ok(events
+            .iter()
+            .rev()
+            .filter(|e| e.user_id == Some(user_id))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+}
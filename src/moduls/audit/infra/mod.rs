@@ -0,0 +1,18 @@
+//! Infrastructure layer for audit module
+//!
+//! Audit sinks: PostgreSQL (always on), RFC 5424 syslog over UDP and a
+//! generic HTTP collector; the PostgreSQL trail is read back per user.
+
+pub mod postgres_audit_sink;
+pub mod postgres_audit_event_repository;
+pub mod syslog_audit_sink;
+pub mod http_audit_sink;
+
+#[cfg(test)]
+pub mod in_memory;
+
+// Re-export sink trait and implementations
+pub use postgres_audit_sink::{AuditSink, PostgresAuditSink};
+pub use postgres_audit_event_repository::{AuditEventRepository, PostgresAuditEventRepository};
+pub use syslog_audit_sink::SyslogAuditSink;
+pub use http_audit_sink::HttpAuditSink;
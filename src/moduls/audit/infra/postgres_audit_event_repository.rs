@@ -0,0 +1,68 @@
+use crate::moduls::audit::domain::{AuditAction, AuditEvent};
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// AuditEventRepository trait: reading back the Postgres audit trail
+#[async_trait]
+pub trait AuditEventRepository: Send + Sync {
+    /// Newest `limit` events about a user, newest first
+    async fn find_recent_by_user(&self, user_id: UserId, limit: u32) -> AppResult<Vec<AuditEvent>>;
+}
+
+/// PostgreSQL implementation of AuditEventRepository
+pub struct PostgresAuditEventRepository {
+    db: DbPools,
+}
+
+impl PostgresAuditEventRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AuditEventRow {
+    id: uuid::Uuid,
+    action: String,
+    user_id: Option<UserId>,
+    tenant_id: Option<OrganizationId>,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    occurred_at: Timestamp,
+}
+
+#[async_trait]
+impl AuditEventRepository for PostgresAuditEventRepository {
+    async fn find_recent_by_user(&self, user_id: UserId, limit: u32) -> AppResult<Vec<AuditEvent>> {
+        let rows = sqlx::query_as::<_, AuditEventRow>(
+            r#"
+            SELECT id, action, user_id, tenant_id, ip_address, user_agent, occurred_at
+            FROM audit_events
+            WHERE user_id = $1
+            ORDER BY occurred_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(i64::from(limit))
+        .fetch_all(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to load audit events: {}", e)))?;
+
+        // Skip actions this build doesn't know (written by a newer one)
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(AuditEvent {
+                    id: row.id,
+                    action: AuditAction::from_name(&row.action)?,
+                    user_id: row.user_id,
+                    tenant_id: row.tenant_id,
+                    ip_address: row.ip_address,
+                    user_agent: row.user_agent,
+                    occurred_at: row.occurred_at,
+                })
+            })
+            .collect())
+    }
+}
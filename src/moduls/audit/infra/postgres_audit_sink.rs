@@ -0,0 +1,52 @@
+use crate::moduls::audit::domain::AuditEvent;
+use crate::shared::{db::DbPools, AppError, AppResult};
+use async_trait::async_trait;
+
+/// AuditSink trait: a destination for audit events
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Short name used in logs (`postgres`, `syslog`, `http`)
+    fn name(&self) -> &'static str;
+
+    /// Write one event
+    async fn write(&self, event: &AuditEvent) -> AppResult<()>;
+}
+
+/// PostgreSQL AuditSink (the primary audit trail)
+pub struct PostgresAuditSink {
+    db: DbPools,
+}
+
+impl PostgresAuditSink {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AuditSink for PostgresAuditSink {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn write(&self, event: &AuditEvent) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_events (id, action, user_id, tenant_id, ip_address, user_agent, occurred_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(event.id)
+        .bind(event.action.as_str())
+        .bind(event.user_id)
+        .bind(event.tenant_id)
+        .bind(&event.ip_address)
+        .bind(&event.user_agent)
+        .bind(event.occurred_at)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to write audit event: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use super::AuditSink;
+use crate::moduls::audit::domain::AuditEvent;
+use crate::shared::{AppError, AppResult};
+use async_trait::async_trait;
+use chrono::SecondsFormat;
+use tokio::net::UdpSocket;
+
+/// `authpriv` facility (RFC 5424 §6.2.1)
+const FACILITY_AUTHPRIV: u8 = 10;
+const SEVERITY_WARNING: u8 = 4;
+const SEVERITY_INFO: u8 = 6;
+
+/// SD-ID for our structured data (32473 is the documentation enterprise number)
+const SD_ID: &str = "audit@32473";
+
+/// Syslog AuditSink: RFC 5424 messages over UDP
+pub struct SyslogAuditSink {
+    addr: String,
+    hostname: String,
+}
+
+impl SyslogAuditSink {
+    /// `addr` is the collector's `host:port`; `hostname` goes into the
+    /// HOSTNAME field (`-` when unknown)
+    pub fn new(addr: impl Into<String>, hostname: Option<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            hostname: hostname.unwrap_or_else(|| "-".to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for SyslogAuditSink {
+    fn name(&self) -> &'static str {
+        "syslog"
+    }
+
+    async fn write(&self, event: &AuditEvent) -> AppResult<()> {
+        let message = format_rfc5424(event, &self.hostname);
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to open syslog socket: {}", e)))?;
+        socket
+            .send_to(message.as_bytes(), &self.addr)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to send syslog message: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Format an event as an RFC 5424 syslog message
+///
+/// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID [SD] MSG`, with the
+/// event fields as structured data and the JSON event as MSG.
+pub fn format_rfc5424(event: &AuditEvent, hostname: &str) -> String {
+    let severity = if event.action.is_failure() {
+        SEVERITY_WARNING
+    } else {
+        SEVERITY_INFO
+    };
+    let pri = FACILITY_AUTHPRIV * 8 + severity;
+
+    let mut params = vec![format!("event_id=\"{}\"", event.id)];
+    if let Some(user_id) = event.user_id {
+        params.push(format!("user_id=\"{}\"", user_id));
+    }
+    if let Some(tenant_id) = event.tenant_id {
+        params.push(format!("tenant_id=\"{}\"", tenant_id));
+    }
+    if let Some(ip_address) = &event.ip_address {
+        params.push(format!("ip=\"{}\"", escape_param(ip_address)));
+    }
+
+    let json = serde_json::to_string(event).unwrap_or_default();
+
+    format!(
+        "<{}>1 {} {} {} {} {} [{} {}] {}",
+        pri,
+        event.occurred_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+        hostname,
+        env!("CARGO_PKG_NAME"),
+        std::process::id(),
+        event.action.as_str(),
+        SD_ID,
+        params.join(" "),
+        json
+    )
+}
+
+/// Escape `"`, `\` and `]` in a PARAM-VALUE (RFC 5424 §6.3.3)
+fn escape_param(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::audit::domain::AuditAction;
+
+    #[test]
+    fn test_format_rfc5424() {
+        let user_id = crate::shared::types::UserId::new();
+        let event = AuditEvent::new(AuditAction::LoginFailed, Some(user_id))
+            .with_ip_address(Some("10.0.0.1".to_string()));
+
+        let message = format_rfc5424(&event, "app-1");
+
+        // authpriv.warning
+        assert!(message.starts_with("<84>1 "), "{}", message);
+        let fields: Vec<&str> = message.splitn(7, ' ').collect();
+        assert!(fields[1].ends_with('Z'));
+        assert_eq!(fields[2], "app-1");
+        assert_eq!(fields[3], env!("CARGO_PKG_NAME"));
+        assert_eq!(fields[5], "login_failed");
+        assert!(fields[6].starts_with(&format!(
+            "[audit@32473 event_id=\"{}\" user_id=\"{}\" ip=\"10.0.0.1\"] {{",
+            event.id, user_id
+        )));
+
+        let json = message.split_once("] ").unwrap().1;
+        let body: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(body["action"], "login_failed");
+    }
+
+    #[test]
+    fn test_routine_events_are_info() {
+        let event = AuditEvent::new(AuditAction::Logout, None);
+
+        assert!(format_rfc5424(&event, "-").starts_with("<86>1 "));
+    }
+
+    #[test]
+    fn test_escape_param() {
+        assert_eq!(escape_param(r#"a"b\c]"#), r#"a\"b\\c\]"#);
+    }
+
+    #[tokio::test]
+    async fn test_sends_udp_datagram() {
+        let collector = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sink = SyslogAuditSink::new(collector.local_addr().unwrap().to_string(), None);
+        let event = AuditEvent::new(AuditAction::LoginSucceeded, None);
+
+        sink.write(&event).await.unwrap();
+
+        let mut buf = vec![0; 4096];
+        let len = collector.recv(&mut buf).await.unwrap();
+        assert_eq!(std::str::from_utf8(&buf[..len]).unwrap(), format_rfc5424(&event, "-"));
+    }
+}
@@ -0,0 +1,18 @@
+//! Audit module
+//!
+//! Records security-relevant auth events (logins, logouts, password
+//! changes). Every event is written to Postgres and, depending on
+//! `AUDIT_SINK`, forwarded to syslog or an HTTP collector for a SIEM.
+//! Users can list their own events (`GET /api/user/audit`).
+//! - Domain: AuditEvent
+//! - Application: AuditLog (fan-out to the configured sinks), request
+//!   client context (IP address, User-Agent)
+//! - Infrastructure: Sinks (PostgreSQL, syslog, HTTP), event repository
+
+pub mod domain;
+pub mod application;
+pub mod infra;
+
+// Re-export commonly used items
+pub use application::{audit_context, AuditLog};
+pub use domain::{AuditAction, AuditEvent};
@@ -1,22 +1,44 @@
 use crate::bootstrap::AppState;
 use crate::moduls::auth::application::{
-    RegisterUserCommand, LoginApiCommand, RefreshTokenCommand,
+    ApiLoginOutcome, ApiLoginResult, ConfirmTotpCommand, EnableTotpResult, ForgotPasswordCommand,
+    HashMigrationProgress, ListUsersQuery, RegisterUserCommand, LoginApiCommand, PasswordParams, PasswordParamsCommand,
+    RefreshTokenCommand, ResendVerificationCommand, ResetPasswordCommand, RevokeTokenCommand,
+    RevokedCredentials, ScheduleDeletionCommand, SetExpiryCommand, SetRecoveryEmailCommand, SetUserStatusCommand, VerifyEmailCommand, VerifyMfaCommand,
 };
-use crate::moduls::auth::domain::{TokenPair, UserDto};
-use crate::moduls::auth::infra::TokenRepository;
-use crate::shared::AppError;
+use crate::moduls::auth::api::{middleware::AuthenticatedUser, refresh_cookie};
+use crate::moduls::auth::domain::{
+    AccountStatus, ClaimsFormat, ClientHashParams, HashMigrationRun, ImpersonationToken,
+    LoginSecuritySummary, Role, TokenPair, UserDto,
+};
+use crate::moduls::organization::api::TenantContext;
+use crate::moduls::organization::domain::OrganizationDto;
+use crate::shared::pagination::Page;
+use crate::shared::{types::{OrganizationId, Timestamp, UserId}, AppError, ClientIp, UncheckedJson, ValidatedJson};
 use axum::{
-    extract::State,
-    http::StatusCode,
-    Json,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 
 /// Request for API login
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct LoginRequest {
+    /// Validated after normalization (see `Email::from_login_input`), so
+    /// pasted whitespace doesn't fail the format check
+    #[validate(length(min = 1, message = "Email is required"))]
     pub email: String,
+    /// The password, or its client hash when `client_hash` is set
+    #[validate(length(min = 1, message = "Password is required"))]
     pub password: String,
+    /// Parameters from `/password-params` the password was hashed with
+    #[serde(default)]
+    pub client_hash: Option<ClientHashParams>,
+    /// Required when the user belongs to several organizations
+    #[serde(default)]
+    pub tenant_slug: Option<String>,
 }
 
 /// Response for API login (token pair)
@@ -26,7 +48,28 @@ pub struct TokenResponse {
     pub refresh_token: String,
     pub token_type: String,
     pub expires_in: i64,
+    /// When the access token expires (RFC 3339), for scheduling refreshes
+    pub access_expires_at: Timestamp,
+    pub refresh_expires_at: Timestamp,
     pub user: UserDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<OrganizationDto>,
+}
+
+/// Response when the user must choose a tenant before tokens are minted
+#[derive(Debug, Serialize)]
+pub struct TenantSelectionResponse {
+    pub message: String,
+    pub tenants: Vec<OrganizationDto>,
+}
+
+/// Response when a TOTP code is needed before tokens are minted
+#[derive(Debug, Serialize)]
+pub struct MfaChallengeResponse {
+    pub message: String,
+    pub mfa_required: bool,
+    pub mfa_token: String,
+    pub expires_in: i64,
 }
 
 impl From<TokenPair> for TokenResponse {
@@ -36,15 +79,22 @@ impl From<TokenPair> for TokenResponse {
             refresh_token: token_pair.refresh_token,
             token_type: token_pair.token_type,
             expires_in: token_pair.expires_in,
+            access_expires_at: token_pair.access_expires_at,
+            refresh_expires_at: token_pair.refresh_expires_at,
             // User will be added separately
             user: UserDto {
-                id: uuid::Uuid::nil(), // Placeholder
+                id: UserId::from_uuid(uuid::Uuid::nil()), // Placeholder
                 email: String::new(),
                 name: String::new(),
                 email_verified: false,
                 is_active: false,
+                two_factor_enabled: false,
+                recovery_email: None,
+                recovery_email_verified: false,
+                status: AccountStatus::Inactive,
                 created_at: chrono::Utc::now(),
             },
+            tenant: None,
         }
     }
 }
@@ -53,21 +103,37 @@ impl From<TokenPair> for TokenResponse {
 #[derive(Debug, Serialize)]
 pub struct UserResponse {
     pub user: UserDto,
+    pub security: LoginSecuritySummary,
+}
+
+/// Response with a message only
+#[derive(Debug, Serialize)]
+pub struct MessageResponse {
+    pub message: String,
 }
 
 /// POST /api/auth/register
 /// Register a new user and return tokens for immediate login
+///
+/// With a `TenantContext` the user is created in (and logged into) that tenant.
+/// The use case validates the body, so invalid fields are reported together
+/// with password policy failures.
 pub async fn register(
     State(state): State<AppState>,
-    Json(payload): Json<RegisterUserCommand>,
-) -> Result<(StatusCode, Json<TokenResponse>), AppError> {
+    tenant: Option<Extension<TenantContext>>,
+    UncheckedJson(mut payload): UncheckedJson<RegisterUserCommand>,
+) -> Result<Response, AppError> {
     // Register the user
+    payload.tenant_id = tenant.map(|Extension(t)| t.organization_id);
+    let tenant_id = payload.tenant_id;
     let user = state.register_user_use_case.execute(payload).await?;
 
     // Generate token pair for immediate login
-    let (token_pair, access_token, refresh_token) = TokenPair::generate(
+    let (token_pair, access_token, refresh_token) = TokenPair::generate_with_format(
         user.id,
-        &state.jwt_secret,
+        tenant_id,
+        ClaimsFormat::from_minimal_flag(state.config.jwt.minimal_claims),
+        &state.jwt_keys,
         state.config.jwt.access_expiry as i64,
         state.config.jwt.refresh_expiry as i64,
     )?;
@@ -80,63 +146,599 @@ pub async fn register(
     let mut response = TokenResponse::from(token_pair);
     response.user = user;
 
-    Ok((StatusCode::CREATED, Json(response)))
+    // Tokens must not be cached
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    refresh_cookie::set(&mut headers, &state.config.jwt, &response.refresh_token);
+
+    Ok((StatusCode::CREATED, headers, Json(response)).into_response())
 }
 
 /// POST /api/auth/login
 /// Login and get JWT token pair
+///
+/// Returns 300 Multiple Choices with the available tenants when the user
+/// belongs to several organizations and no `tenant_slug` was given.
+/// With a `TenantContext` only that tenant's users (and global members of
+/// it) can log in, and tokens are minted for it.
 pub async fn login(
     State(state): State<AppState>,
-    Json(payload): Json<LoginRequest>,
-) -> Result<Json<TokenResponse>, AppError> {
+    tenant: Option<Extension<TenantContext>>,
+    ClientIp(ip_address): ClientIp,
+    ValidatedJson(payload): ValidatedJson<LoginRequest>,
+) -> Result<Response, AppError> {
     let cmd = LoginApiCommand {
         email: payload.email,
         password: payload.password,
+        client_hash: payload.client_hash,
+        tenant_slug: payload.tenant_slug,
+        tenant_id: tenant.map(|Extension(t)| t.organization_id),
+        ip_address,
     };
 
-    let result = state.login_user_use_case.login_api(cmd).await?;
+    let outcome = state.login_user_use_case.login_api(cmd).await?;
+
+    Ok(login_outcome_response(&state, outcome))
+}
+
+/// POST /api/dev/token
+/// Mint a token pair for an existing user without a password
+///
+/// Only built with the `dev-tools` feature and only routed with
+/// `DEV_MODE=true`. Answers like a login (300 when a tenant must be chosen).
+#[cfg(feature = "dev-tools")]
+pub async fn dev_token(
+    State(state): State<AppState>,
+    ValidatedJson(payload): ValidatedJson<crate::moduls::auth::application::DevLoginCommand>,
+) -> Result<Response, AppError> {
+    let outcome = state.login_user_use_case.login_dev(payload).await?;
+
+    Ok(login_outcome_response(&state, outcome))
+}
 
+/// Response for a login outcome: tokens, tenant choice or MFA challenge
+pub(crate) fn login_outcome_response(state: &AppState, outcome: ApiLoginOutcome) -> Response {
+    match outcome {
+        ApiLoginOutcome::LoggedIn(result) => logged_in_response(state, *result),
+        ApiLoginOutcome::TenantSelectionRequired { tenants } => {
+            let response = TenantSelectionResponse {
+                message: "Multiple organizations available, retry with tenant_slug".to_string(),
+                tenants,
+            };
+
+            (StatusCode::MULTIPLE_CHOICES, Json(response)).into_response()
+        }
+        ApiLoginOutcome::MfaRequired { mfa_token, expires_in } => {
+            let response = MfaChallengeResponse {
+                message: "Two-factor authentication required, submit a code to /api/auth/mfa/verify"
+                    .to_string(),
+                mfa_required: true,
+                mfa_token,
+                expires_in,
+            };
+
+            (StatusCode::ACCEPTED, Json(response)).into_response()
+        }
+    }
+}
+
+/// POST /api/auth/password-params
+/// How to send the user's password: as typed, or hashed client-side with
+/// the returned parameters (when `CLIENT_PASSWORD_HASHING` is enabled)
+///
+/// Answers for unknown emails too, without revealing that they are unknown.
+pub async fn password_params(
+    State(state): State<AppState>,
+    tenant: Option<Extension<TenantContext>>,
+    ValidatedJson(mut payload): ValidatedJson<PasswordParamsCommand>,
+) -> Result<Json<PasswordParams>, AppError> {
+    payload.tenant_id = tenant.map(|Extension(t)| t.organization_id);
+
+    let params = state.login_user_use_case.password_params(payload).await?;
+
+    Ok(Json(params))
+}
+
+/// POST /api/auth/mfa/verify
+/// Complete a login that answered `mfa_required` with a TOTP code
+///
+/// Answers like a successful login. A challenge is single-use and becomes
+/// unusable after too many wrong codes.
+pub async fn verify_mfa(
+    State(state): State<AppState>,
+    ValidatedJson(payload): ValidatedJson<VerifyMfaCommand>,
+) -> Result<Response, AppError> {
+    let result = state.login_user_use_case.verify_mfa(payload).await?;
+
+    Ok(logged_in_response(&state, result))
+}
+
+/// Token response for a completed login, with the refresh cookie if enabled
+fn logged_in_response(state: &AppState, result: ApiLoginResult) -> Response {
     let mut response = TokenResponse::from(result.token_pair);
     response.user = result.user;
+    response.tenant = result.tenant;
+
+    let mut headers = HeaderMap::new();
+    refresh_cookie::set(&mut headers, &state.config.jwt, &response.refresh_token);
 
-    Ok(Json(response))
+    (headers, Json(response)).into_response()
 }
 
 /// POST /api/auth/refresh
 /// Refresh access token using refresh token
+///
+/// Reads the token from the `refresh_token` cookie when cookies are
+/// enabled, falling back to the JSON body; the cookie is rotated together
+/// with the token.
 pub async fn refresh(
     State(state): State<AppState>,
-    Json(payload): Json<RefreshTokenCommand>,
-) -> Result<Json<TokenResponse>, AppError> {
-    let token_pair = state.refresh_token_use_case.execute(payload).await?;
+    request_headers: HeaderMap,
+    payload: Option<Json<RefreshTokenCommand>>,
+) -> Result<Response, AppError> {
+    let cookie_token = state
+        .config
+        .jwt
+        .refresh_cookie
+        .then(|| refresh_cookie::read(&request_headers))
+        .flatten();
+
+    let cmd = match (cookie_token, payload) {
+        (Some(refresh_token), _) => RefreshTokenCommand { refresh_token },
+        (None, Some(Json(payload))) => payload,
+        (None, None) => return Err(AppError::authentication("Missing refresh token")),
+    };
+
+    let token_pair = state.refresh_token_use_case.execute(cmd).await?;
 
     let response = TokenResponse::from(token_pair);
 
-    Ok(Json(response))
+    let mut headers = HeaderMap::new();
+    refresh_cookie::set(&mut headers, &state.config.jwt, &response.refresh_token);
+
+    Ok((headers, Json(response)).into_response())
+}
+
+/// POST /api/auth/forgot-password
+/// Request a password reset token for an email
+///
+/// Always answers 200 with the same body, whether or not the account
+/// exists or the email was throttled. Tenant users are looked up in the
+/// request's `TenantContext`.
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    tenant: Option<Extension<TenantContext>>,
+    ClientIp(ip_address): ClientIp,
+    ValidatedJson(mut payload): ValidatedJson<ForgotPasswordCommand>,
+) -> Result<Json<MessageResponse>, AppError> {
+    payload.tenant_id = tenant.map(|Extension(t)| t.organization_id);
+    payload.ip_address = ip_address;
+
+    state.reset_password_use_case.request_reset(payload).await?;
+
+    Ok(Json(MessageResponse {
+        message: "If the account exists, a password reset link has been sent".to_string(),
+    }))
+}
+
+/// POST /api/auth/reset-password
+/// Set a new password with a reset token
+///
+/// The token is single-use; all sessions and tokens of the user are
+/// revoked afterwards.
+pub async fn reset_password(
+    State(state): State<AppState>,
+    ValidatedJson(payload): ValidatedJson<ResetPasswordCommand>,
+) -> Result<Json<MessageResponse>, AppError> {
+    state.reset_password_use_case.reset_password(payload).await?;
+
+    Ok(Json(MessageResponse {
+        message: "Password has been reset".to_string(),
+    }))
+}
+
+/// POST /api/auth/send-verification
+/// Send an email verification token to the current user
+/// Requires authentication (JWT middleware)
+pub async fn send_verification(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ClientIp(ip_address): ClientIp,
+) -> Result<Json<MessageResponse>, AppError> {
+    state
+        .verify_email_use_case
+        .send_verification(auth_user.user_id, ip_address)
+        .await?;
+
+    Ok(Json(MessageResponse {
+        message: "Verification email sent".to_string(),
+    }))
+}
+
+/// PUT /api/auth/recovery-email
+/// Set (or with `"email": null`, remove) the current user's recovery email
+/// Requires a recent login
+///
+/// A new address is sent a verification link; password resets can be
+/// delivered to it once confirmed.
+pub async fn set_recovery_email(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ClientIp(ip_address): ClientIp,
+    ValidatedJson(payload): ValidatedJson<SetRecoveryEmailCommand>,
+) -> Result<Json<UserDto>, AppError> {
+    let user = state
+        .verify_email_use_case
+        .set_recovery_email(auth_user.user_id, payload, ip_address)
+        .await?;
+
+    Ok(Json(UserDto::from(user)))
+}
+
+/// POST /api/auth/resend-verification
+/// Send an email verification token by email address
+///
+/// For users who can't log in before verifying. Always answers 200 with
+/// the same body, like `forgot-password`.
+pub async fn resend_verification(
+    State(state): State<AppState>,
+    tenant: Option<Extension<TenantContext>>,
+    ClientIp(ip_address): ClientIp,
+    ValidatedJson(mut payload): ValidatedJson<ResendVerificationCommand>,
+) -> Result<Json<MessageResponse>, AppError> {
+    payload.tenant_id = tenant.map(|Extension(t)| t.organization_id);
+    payload.ip_address = ip_address;
+
+    state.verify_email_use_case.resend_verification(payload).await?;
+
+    Ok(Json(MessageResponse {
+        message: "If the account needs verification, an email has been sent".to_string(),
+    }))
+}
+
+/// GET /api/auth/verify-email?token=...
+/// Confirm the user's email with a verification token
+///
+/// The token is single-use; no authentication is needed, as the link is
+/// opened from the email.
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Query(cmd): Query<VerifyEmailCommand>,
+) -> Result<Json<MessageResponse>, AppError> {
+    state.verify_email_use_case.verify(cmd).await?;
+
+    Ok(Json(MessageResponse {
+        message: "Email verified".to_string(),
+    }))
+}
+
+/// POST /api/auth/mfa/totp/enable
+/// Start TOTP setup: returns the secret and its `otpauth://` URI
+/// Requires authentication (JWT middleware) and a recent login
+///
+/// 2FA stays off until the first code is confirmed.
+pub async fn enable_totp(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> Result<Json<EnableTotpResult>, AppError> {
+    let result = state.enable_totp_use_case.execute(auth_user.user_id).await?;
+
+    Ok(Json(result))
+}
+
+/// POST /api/auth/mfa/totp/confirm
+/// Finish TOTP setup with the first code from the authenticator app
+/// Requires authentication (JWT middleware)
+pub async fn confirm_totp(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<ConfirmTotpCommand>,
+) -> Result<Json<MessageResponse>, AppError> {
+    state
+        .confirm_totp_use_case
+        .execute(auth_user.user_id, payload)
+        .await?;
+
+    Ok(Json(MessageResponse {
+        message: "Two-factor authentication enabled".to_string(),
+    }))
+}
+
+/// GET /api/admin/users
+/// List users, oldest first
+/// Requires the admin role
+///
+/// Query parameters: `q` (email or name search), `page` and `per_page`
+/// (at most 100).
+pub async fn list_users(
+    State(state): State<AppState>,
+    Query(query): Query<ListUsersQuery>,
+) -> Result<Json<Page<UserDto>>, AppError> {
+    let users = state.list_users_use_case.execute(query).await?;
+
+    Ok(Json(users))
+}
+
+/// POST /api/admin/users
+/// Create a user on their behalf (no tokens are issued)
+/// Requires the admin role
+///
+/// Registers into the tenant named by `X-Tenant-Slug`, if any. Unlike
+/// public registration, a duplicate is reported with the conflicting field
+/// in `error.fields`.
+pub async fn create_user(
+    State(state): State<AppState>,
+    tenant: Option<Extension<TenantContext>>,
+    UncheckedJson(mut payload): UncheckedJson<RegisterUserCommand>,
+) -> Result<(StatusCode, Json<UserDto>), AppError> {
+    payload.tenant_id = tenant.map(|Extension(t)| t.organization_id);
+    let user = state.register_user_use_case.execute_as_admin(payload).await?;
+
+    Ok((StatusCode::CREATED, Json(user)))
+}
+
+/// PUT /api/admin/users/{id}/roles/{role}
+/// Grant a role to a user
+/// Requires the admin role
+///
+/// Idempotent; 404 if the user or role doesn't exist. Only a super admin
+/// may grant `super_admin`.
+pub async fn grant_role(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path((user_id, role)): Path<(UserId, String)>,
+) -> Result<Json<MessageResponse>, AppError> {
+    state
+        .manage_roles_use_case
+        .grant(auth_user.user_id, user_id, &role)
+        .await?;
+
+    Ok(Json(MessageResponse {
+        message: "Role granted".to_string(),
+    }))
+}
+
+/// DELETE /api/admin/users/{id}/roles/{role}
+/// Revoke a role from a user
+/// Requires the admin role
+///
+/// Idempotent; admins can't revoke their own admin role. Only a super
+/// admin may revoke `super_admin`.
+pub async fn revoke_role(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path((user_id, role)): Path<(UserId, String)>,
+) -> Result<Json<MessageResponse>, AppError> {
+    state
+        .manage_roles_use_case
+        .revoke(auth_user.user_id, user_id, &role)
+        .await?;
+
+    Ok(Json(MessageResponse {
+        message: "Role revoked".to_string(),
+    }))
+}
+
+/// POST /api/admin/users/{id}/impersonate
+/// Mint a short-lived access token to act as a user
+/// Requires the admin role
+///
+/// No refresh token is issued, and the token can't start another
+/// impersonation.
+pub async fn impersonate_user(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(user_id): Path<UserId>,
+) -> Result<Json<ImpersonationToken>, AppError> {
+    let token = state
+        .impersonate_user_use_case
+        .execute(auth_user.user_id, auth_user.impersonator, user_id)
+        .await?;
+
+    Ok(Json(token))
+}
+
+/// PATCH /api/admin/users/{id}/status
+/// Deactivate or reactivate a user
+/// Requires the admin role
+///
+/// Deactivation signs the user out everywhere at once: sessions, tokens
+/// and API keys are revoked.
+pub async fn set_user_status(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(user_id): Path<UserId>,
+    ValidatedJson(payload): ValidatedJson<SetUserStatusCommand>,
+) -> Result<Json<UserDto>, AppError> {
+    let user = state
+        .set_user_status_use_case
+        .execute(auth_user.user_id, user_id, payload)
+        .await?;
+
+    Ok(Json(user))
+}
+
+/// DELETE /api/admin/users/{id}/lock
+/// Lift a lockout after repeated failed logins before it runs out
+/// Requires the admin role
+pub async fn unlock_user(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(user_id): Path<UserId>,
+) -> Result<Json<UserDto>, AppError> {
+    let user = state
+        .account_lifecycle_use_case
+        .unlock(auth_user.user_id, user_id)
+        .await?;
+
+    Ok(Json(user))
+}
+
+/// PUT /api/admin/users/{id}/expiry
+/// Set when a user's account stops working, or clear it with `null`
+/// Requires the admin role
+///
+/// Expired accounts can't log in, and their tokens are refused.
+pub async fn set_user_expiry(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(user_id): Path<UserId>,
+    ValidatedJson(payload): ValidatedJson<SetExpiryCommand>,
+) -> Result<Json<UserDto>, AppError> {
+    let user = state
+        .account_lifecycle_use_case
+        .set_expiry(auth_user.user_id, user_id, payload)
+        .await?;
+
+    Ok(Json(user))
+}
+
+/// PUT /api/admin/users/{id}/deletion
+/// Schedule a user's account for deletion
+/// Requires the admin role
+///
+/// The account is deleted by a background job once due; until then the
+/// user can still log in.
+pub async fn schedule_user_deletion(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(user_id): Path<UserId>,
+    ValidatedJson(payload): ValidatedJson<ScheduleDeletionCommand>,
+) -> Result<Json<UserDto>, AppError> {
+    let user = state
+        .account_lifecycle_use_case
+        .schedule_deletion(auth_user.user_id, user_id, payload)
+        .await?;
+
+    Ok(Json(user))
+}
+
+/// DELETE /api/admin/users/{id}/deletion
+/// Cancel a user's scheduled deletion
+/// Requires the admin role
+pub async fn cancel_user_deletion(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(user_id): Path<UserId>,
+) -> Result<Json<UserDto>, AppError> {
+    let user = state
+        .account_lifecycle_use_case
+        .cancel_deletion(auth_user.user_id, user_id)
+        .await?;
+
+    Ok(Json(user))
+}
+
+/// POST /api/admin/tenants/{id}/revoke-all
+/// Revoke every token and session of a tenant's users
+/// Requires the super_admin role
+pub async fn revoke_tenant_credentials(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(tenant_id): Path<OrganizationId>,
+) -> Result<Json<RevokedCredentials>, AppError> {
+    let revoked = state
+        .revoke_tenant_credentials_use_case
+        .execute(auth_user.user_id, tenant_id)
+        .await?;
+
+    Ok(Json(revoked))
+}
+
+/// POST /api/admin/password-hashes/migrations
+/// Start flagging users whose password hash has outdated parameters
+/// Requires the super_admin role
+///
+/// The run continues in the background; flagged users must reset their
+/// password before logging in again.
+pub async fn start_hash_migration(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> Result<(StatusCode, Json<HashMigrationRun>), AppError> {
+    let run = state.hash_migration_use_case.start(auth_user.user_id).await?;
+
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+/// GET /api/admin/password-hashes/migrations
+/// Latest password hash migration run and the users still to migrate
+/// Requires the super_admin role
+pub async fn hash_migration_progress(
+    State(state): State<AppState>,
+) -> Result<Json<HashMigrationProgress>, AppError> {
+    let progress = state.hash_migration_use_case.progress().await?;
+
+    Ok(Json(progress))
+}
+
+/// GET /.well-known/jwks.json
+/// Public keys for verifying access tokens (empty unless RS256 is used)
+///
+/// Lists every active `kid`, so tokens signed before a key rotation still
+/// verify downstream.
+pub async fn jwks(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(state.jwt_keys.jwks()),
+    )
 }
 
 /// POST /api/auth/logout
 /// Logout and revoke all tokens
 /// Requires authentication (JWT middleware)
+///
+/// Revokes every access and refresh token of the user, so the presented
+/// token fails the middleware's revocation check afterwards. In refresh
+/// cookie mode the cookie is expired too.
 pub async fn logout(
-    State(_state): State<AppState>,
-    // TODO: Extract user from JWT middleware
-    // AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> Result<(HeaderMap, StatusCode), AppError> {
+    state
+        .logout_user_use_case
+        .logout_api(auth_user.user_id)
+        .await?;
+
+    let mut headers = HeaderMap::new();
+    refresh_cookie::clear(&mut headers, &state.config.jwt);
+
+    Ok((headers, StatusCode::NO_CONTENT))
+}
+
+/// POST /api/auth/revoke
+/// Revoke an access or refresh token (RFC 7009)
+/// Requires authentication (JWT or API key)
+///
+/// Answers 200 for invalid, expired and unknown tokens too, as the spec
+/// asks. Admins may revoke any user's tokens.
+pub async fn revoke_token(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<RevokeTokenCommand>,
 ) -> Result<StatusCode, AppError> {
-    // For now, this is a placeholder
-    // Will need to extract user_id from JWT token in middleware
-    // state.logout_user_use_case.logout_api(user.id).await?;
+    let privileged = auth_user.has_role(Role::ADMIN);
+    state
+        .revoke_token_use_case
+        .execute(auth_user.user_id, privileged, payload)
+        .await?;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok(StatusCode::OK)
 }
 
 /// GET /api/auth/me
-/// Get current authenticated user
+/// Get current authenticated user with recent login activity
 /// Requires authentication (JWT middleware)
 pub async fn me(
-    // TODO: Extract user from JWT middleware
-    // AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
 ) -> Result<Json<UserResponse>, AppError> {
-    // Placeholder - will be implemented with JWT middleware
-    Err(AppError::authentication("Not implemented yet"))
+    let result = state
+        .get_current_user_use_case
+        .execute(auth_user.user_id)
+        .await?;
+
+    Ok(Json(UserResponse {
+        user: result.user,
+        security: result.security,
+    }))
 }
@@ -1,22 +1,56 @@
-// JWT authentication middleware
+// JWT and API key authentication middleware
+//
+// Status policy, followed by every guard and extractor here:
+// - 401 `AUTHENTICATION_ERROR`: the credential is missing, malformed,
+//   expired, revoked or otherwise invalid (`REAUTH_REQUIRED` when it is
+//   valid but too old for the action)
+// - 403 `AUTHORIZATION_ERROR`: the credential is valid, but its user may
+//   not do this (e.g. lacks a role)
 
 use crate::bootstrap::AppState;
 use crate::moduls::auth::domain::token_pair::TokenPair;
-use crate::moduls::auth::infra::postgres_token_repository::TokenRepository;
+use crate::moduls::auth::domain::{ApiKey, Role};
+use crate::moduls::auth::infra::{ApiKeyRepository, RoleRepository};
+use crate::moduls::auth::web::middleware::AuthenticatedSession;
 use crate::shared::error::AppError;
-use crate::shared::types::UserId;
+use crate::shared::types::{now, OrganizationId, Timestamp, TokenId, UserId};
+use crate::shared::AppResult;
 use axum::{
     extract::{Request, State},
-    http::StatusCode,
+    http::{HeaderMap, HeaderName, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Header carrying an API key, as an alternative to `Authorization: ApiKey`
+pub const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-api-key");
 
 /// Authenticated user extension
-/// Add to request extensions after successful JWT validation
+/// Add to request extensions after successful JWT or API key validation
 #[derive(Clone, Debug)]
 pub struct AuthenticatedUser {
     pub user_id: UserId,
+    /// Tenant selected at login, if the user belongs to any
+    pub tenant_id: Option<OrganizationId>,
+    /// When the user presented credentials (`auth_time`); unlike `iat`,
+    /// this survives token refresh. The Unix epoch for API keys, which
+    /// never count as a fresh login
+    pub authenticated_at: Timestamp,
+    /// Roles from the token's `roles` claim, or the database for tokens
+    /// without one and for API keys
+    pub roles: Vec<Role>,
+    /// Admin acting as this user (impersonation token `act` claim)
+    pub impersonator: Option<UserId>,
+}
+
+impl AuthenticatedUser {
+    /// Check whether the user holds `role`
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r.as_str() == role)
+    }
 }
 
 /// JWT authentication middleware
@@ -24,21 +58,100 @@ pub struct AuthenticatedUser {
 /// Validates JWT tokens from Authorization header
 /// Checks token signature, expiration, and revocation status
 /// Adds AuthenticatedUser to request extensions on success
+/// (and to response extensions, for the access log)
 ///
 /// # Flow
-/// 1. Extract Authorization: Bearer <token> header
+/// 1. Extract Authorization: Bearer <token> header (rejecting tokens
+///    longer than `JWT_MAX_TOKEN_LENGTH` before decoding)
 /// 2. Decode and validate JWT signature
-/// 3. Check token not revoked in database
-/// 4. Add user_id to request extensions
+/// 3. Check token not issued before the watermark and not revoked
+/// 4. Add user_id and roles to request extensions
 /// 5. Return 401 if any step fails
 pub async fn jwt_auth_middleware(
     State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let authenticated_user = authenticate_bearer(&state, request.headers()).await?;
+
+    Ok(run_authenticated(authenticated_user, request, next).await)
+}
+
+/// API key authentication middleware
+///
+/// Accepts `Authorization: ApiKey <key>` or `X-API-Key: <key>`, looks the
+/// key up by hash and rejects unknown, revoked and expired keys with 401.
+/// Adds AuthenticatedUser to request extensions on success, like
+/// `jwt_auth_middleware`.
+pub async fn api_key_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let authenticated_user = authenticate_api_key(&state, request.headers()).await?;
+
+    Ok(run_authenticated(authenticated_user, request, next).await)
+}
+
+/// Middleware accepting either credential
+///
+/// Requests presenting an API key are checked as in `api_key_middleware`;
+/// all others as in `jwt_auth_middleware`.
+pub async fn jwt_or_api_key_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let authenticated_user = if api_key_from(request.headers()).is_some() {
+        authenticate_api_key(&state, request.headers()).await?
+    } else {
+        authenticate_bearer(&state, request.headers()).await?
+    };
+
+    Ok(run_authenticated(authenticated_user, request, next).await)
+}
+
+/// Run the rest of the stack as `authenticated_user`
+///
+/// The user goes into the request extensions for handlers, and into the
+/// response extensions for the access log.
+async fn run_authenticated(
+    authenticated_user: AuthenticatedUser,
     mut request: Request,
     next: Next,
+) -> Response {
+    request.extensions_mut().insert(authenticated_user.clone());
+
+    let mut response = next.run(request).await;
+    response.extensions_mut().insert(authenticated_user);
+    response
+}
+
+/// JWT middleware for logout
+///
+/// Same as `jwt_auth_middleware`, except that with `LENIENT_LOGOUT=true` a
+/// request presenting no credential is answered with 204 (already logged
+/// out) instead of 401. A credential that is present must still be valid.
+pub async fn logout_auth_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
 ) -> Result<Response, AppError> {
+    if state.config.security.lenient_logout && !request.headers().contains_key("Authorization") {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    }
+
+    jwt_auth_middleware(State(state), request, next).await
+}
+
+/// Validate the bearer token in the request headers
+///
+/// Shared by `jwt_auth_middleware` and `OptionalAuthenticatedUser`
+/// so both apply the same signature, expiration, watermark, and
+/// revocation checks.
+async fn authenticate_bearer(state: &AppState, headers: &HeaderMap) -> AppResult<AuthenticatedUser> {
     // Extract Authorization header
-    let auth_header = request
-        .headers()
+    let auth_header = headers
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
         .ok_or_else(|| AppError::authentication("Missing Authorization header"))?;
@@ -48,11 +161,21 @@ pub async fn jwt_auth_middleware(
         .strip_prefix("Bearer ")
         .ok_or_else(|| AppError::authentication("Invalid Authorization header format"))?;
 
+    // Don't spend base64/JSON parsing on absurdly large tokens
+    if token.len() > state.config.jwt.max_token_length {
+        return Err(AppError::authentication("Token too long"));
+    }
+
     // Decode and validate JWT
-    let claims = TokenPair::decode(token, &state.jwt_secret)?;
+    let claims = TokenPair::decode(token, &state.jwt_keys)?;
+
+    // Reject tokens issued before the global watermark
+    state.token_watermark.check(&claims).await?;
 
     // Extract JTI and check revocation status
-    let jti = uuid::Uuid::parse_str(&claims.jti)
+    let jti: TokenId = claims
+        .jti
+        .parse()
         .map_err(|_| AppError::authentication("Invalid token ID"))?;
 
     // Check if token is revoked by finding it in database
@@ -66,23 +189,184 @@ pub async fn jwt_auth_middleware(
     }
 
     // Extract user ID
-    let user_id = uuid::Uuid::parse_str(&claims.sub)
+    let user_id: UserId = claims
+        .sub
+        .parse()
         .map_err(|_| AppError::authentication("Invalid user ID in token"))?;
 
-    // Add authenticated user to request extensions
-    let authenticated_user = AuthenticatedUser { user_id };
-    request.extensions_mut().insert(authenticated_user);
+    let authenticated_at = chrono::DateTime::from_timestamp(claims.authenticated_at(), 0)
+        .ok_or_else(|| AppError::authentication("Invalid auth time in token"))?;
+
+    // Roles come with the token; older tokens fall back to the database
+    let roles = match claims.roles()? {
+        Some(roles) => roles,
+        None => state.role_repo.find_by_user_id(user_id).await?,
+    };
 
-    // Continue to next middleware/handler
+    Ok(AuthenticatedUser {
+        user_id,
+        tenant_id: claims.tenant_id()?,
+        authenticated_at,
+        roles,
+        impersonator: claims.impersonator()?,
+    })
+}
+
+/// API key presented in the request headers, if any
+fn api_key_from(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get(API_KEY_HEADER).and_then(|h| h.to_str().ok()) {
+        return Some(key.trim());
+    }
+
+    headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("ApiKey "))
+        .map(str::trim)
+}
+
+/// Validate the API key in the request headers
+async fn authenticate_api_key(state: &AppState, headers: &HeaderMap) -> AppResult<AuthenticatedUser> {
+    let key = api_key_from(headers).ok_or_else(|| AppError::authentication("Missing API key"))?;
+
+    if !key.starts_with(ApiKey::PREFIX) {
+        return Err(AppError::authentication("Invalid API key"));
+    }
+
+    let api_key = state
+        .api_key_repo
+        .find_by_hash(&ApiKey::hash(key))
+        .await?
+        .ok_or_else(|| AppError::authentication("Invalid API key"))?;
+
+    if api_key.is_revoked() {
+        return Err(AppError::authentication("API key has been revoked"));
+    }
+    if api_key.is_expired() {
+        return Err(AppError::authentication("API key has expired"));
+    }
+
+    // Bookkeeping only; don't fail the request over it
+    if let Err(e) = state.api_key_repo.touch(api_key.id).await {
+        tracing::warn!("Failed to record use of API key {}: {}", api_key.id, e);
+    }
+
+    Ok(AuthenticatedUser {
+        user_id: api_key.user_id,
+        tenant_id: api_key.tenant_id,
+        authenticated_at: chrono::DateTime::UNIX_EPOCH,
+        roles: state.role_repo.find_by_user_id(api_key.user_id).await?,
+        impersonator: None,
+    })
+}
+
+/// 403 if the request carries an impersonation token
+fn reject_impersonation(request: &Request) -> Result<(), AppError> {
+    if request
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .is_some_and(|user| user.impersonator.is_some())
+    {
+        return Err(AppError::authorization(
+            "This action is not available while impersonating",
+        ));
+    }
+    Ok(())
+}
+
+/// Guard for routes minting credentials (e.g. API keys, TOTP secrets)
+///
+/// An admin acting as a user must not be able to leave behind a way back
+/// into the account once the impersonation token expires; returns 403
+/// `AUTHORIZATION_ERROR` for impersonation tokens. Must run after
+/// `jwt_auth_middleware`.
+pub async fn forbid_impersonation(request: Request, next: Next) -> Result<Response, AppError> {
+    reject_impersonation(&request)?;
     Ok(next.run(request).await)
 }
 
+/// Guard for sensitive actions (e.g. password change)
+///
+/// Requires the caller's token (`auth_time`) or web session (login time) to be
+/// younger than `security.fresh_auth_window`; otherwise returns 401 with
+/// `REAUTH_REQUIRED` so the client can re-prompt for credentials. Refuses
+/// impersonation tokens like `forbid_impersonation`. Must run after
+/// `jwt_auth_middleware` or `session_auth_middleware`.
+pub async fn require_fresh_auth(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    reject_impersonation(&request)?;
+
+    let extensions = request.extensions();
+    let authenticated_at = extensions
+        .get::<AuthenticatedUser>()
+        .map(|user| user.authenticated_at)
+        .or_else(|| {
+            extensions
+                .get::<AuthenticatedSession>()
+                .map(|session| session.authenticated_at)
+        })
+        .ok_or_else(unauthenticated)?;
+
+    let window = chrono::Duration::seconds(state.config.security.fresh_auth_window as i64);
+    if now() - authenticated_at > window {
+        return Err(AppError::reauth_required(
+            "Please sign in again to continue",
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Middleware future returned by `require_role`
+type RoleGuardFuture = Pin<Box<dyn Future<Output = Result<Response, AppError>> + Send>>;
+
+/// Guard for role-restricted routes
+///
+/// Returns a middleware rejecting requests whose `AuthenticatedUser` lacks
+/// `role` with 403 `AUTHORIZATION_ERROR`. Must run after
+/// `jwt_auth_middleware`:
+///
+/// ```ignore
+/// .route_layer(middleware::from_fn(require_role(Role::ADMIN)))
+/// .route_layer(middleware::from_fn_with_state(state, jwt_auth_middleware))
+/// ```
+pub fn require_role(role: &str) -> impl Fn(Request, Next) -> RoleGuardFuture + Clone + Send + Sync + 'static {
+    let role: std::sync::Arc<str> = role.into();
+
+    move |request: Request, next: Next| {
+        let role = role.clone();
+        Box::pin(async move {
+            let user = request
+                .extensions()
+                .get::<AuthenticatedUser>()
+                .ok_or_else(unauthenticated)?;
+
+            if !user.has_role(&role) {
+                tracing::warn!("User {} lacks role {} for {}", user.user_id, role, request.uri().path());
+                return Err(AppError::authorization(format!("The {} role is required", role)));
+            }
+
+            Ok(next.run(request).await)
+        })
+    }
+}
+
+/// Error for requests reaching a guard without an authenticated user
+///
+/// A route missing its authentication middleware fails closed with 401.
+fn unauthenticated() -> AppError {
+    AppError::authentication("Unauthorized - no valid authentication")
+}
+
 /// Axum extractor for authenticated user
 ///
 /// Use this in handler parameters to get the authenticated user
-/// Will return 401 if user not found in extensions
+/// Will return 401 `AUTHENTICATION_ERROR` if user not found in extensions
 impl axum::extract::FromRequestParts<AppState> for AuthenticatedUser {
-    type Rejection = (StatusCode, String);
+    type Rejection = AppError;
 
     async fn from_request_parts(
         parts: &mut axum::http::request::Parts,
@@ -92,9 +376,529 @@ impl axum::extract::FromRequestParts<AppState> for AuthenticatedUser {
             .extensions
             .get::<AuthenticatedUser>()
             .cloned()
-            .ok_or((
-                StatusCode::UNAUTHORIZED,
-                "Unauthorized - no valid authentication".to_string(),
-            ))
+            .ok_or_else(unauthenticated)
+    }
+}
+
+/// Axum extractor for an optionally authenticated user
+///
+/// Yields `Some` when a valid bearer token is present and `None` when the
+/// token is absent or invalid, without rejecting the request. Use this on
+/// routes that serve both anonymous and authenticated callers.
+#[derive(Clone, Debug)]
+pub struct OptionalAuthenticatedUser(pub Option<AuthenticatedUser>);
+
+impl axum::extract::FromRequestParts<AppState> for OptionalAuthenticatedUser {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        // Reuse the result of jwt_auth_middleware if it already ran
+        if let Some(user) = parts.extensions.get::<AuthenticatedUser>() {
+            return Ok(Self(Some(user.clone())));
+        }
+
+        if !parts.headers.contains_key("Authorization") {
+            return Ok(Self(None));
+        }
+
+        match authenticate_bearer(state, &parts.headers).await {
+            Ok(user) => Ok(Self(Some(user))),
+            Err(e) => {
+                tracing::debug!("Ignoring invalid credentials on optional auth route: {}", e);
+                Ok(Self(None))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::FromRequestParts;
+    use axum::http::Request as HttpRequest;
+
+    fn parts_with_header(value: Option<&str>) -> axum::http::request::Parts {
+        let mut builder = HttpRequest::builder().uri("/");
+        if let Some(value) = value {
+            builder = builder.header("Authorization", value);
+        }
+        builder.body(()).unwrap().into_parts().0
+    }
+
+    #[tokio::test]
+    async fn test_optional_user_absent() {
+        let state = AppState::for_tests();
+        let mut parts = parts_with_header(None);
+
+        let OptionalAuthenticatedUser(user) =
+            OptionalAuthenticatedUser::from_request_parts(&mut parts, &state).await.unwrap();
+
+        assert!(user.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_optional_user_present_but_invalid() {
+        let state = AppState::for_tests();
+
+        // Token signed with a different secret fails before any DB lookup
+        let other_keys = crate::moduls::auth::domain::JwtKeys::hmac("another_secret_that_is_long_enough!!");
+        let (token_pair, _, _) =
+            TokenPair::generate(UserId::new(), &other_keys, 900, 3600).unwrap();
+        let header = format!("Bearer {}", token_pair.access_token);
+
+        for value in [header.as_str(), "Bearer not-a-jwt", "Basic dXNlcjpwYXNz"] {
+            let mut parts = parts_with_header(Some(value));
+            let OptionalAuthenticatedUser(user) =
+                OptionalAuthenticatedUser::from_request_parts(&mut parts, &state).await.unwrap();
+            assert!(user.is_none(), "expected None for {}", value);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_token_rejected_before_decoding() {
+        let state = AppState::for_tests();
+        let header = format!("Bearer {}", "a".repeat(1024 * 1024));
+        let headers = parts_with_header(Some(&header)).headers;
+
+        let started = std::time::Instant::now();
+        let result = authenticate_bearer(&state, &headers).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(ref m)) if m == "Token too long"));
+        assert!(started.elapsed() < std::time::Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_optional_user_present_and_valid() {
+        let f = StatusPolicyFixture::new();
+        let token = f.token(&[]);
+
+        assert_eq!(f.optional_user(Some(&token)).await, Some(f.user_id));
+    }
+
+    #[tokio::test]
+    async fn test_optional_user_with_invalid_or_revoked_token_is_anonymous() {
+        let f = StatusPolicyFixture::new();
+        let revoked = f.token(&[]);
+        f.token_repo.tokens.lock().unwrap()[0].revoke();
+        let other_keys = crate::moduls::auth::domain::JwtKeys::hmac("another_secret_that_is_long_enough!!");
+        let (forged, _, _) = TokenPair::generate(f.user_id, &other_keys, 900, 3600).unwrap();
+        let forged = format!("Bearer {}", forged.access_token);
+
+        for authorization in [
+            None,
+            Some("Bearer not-a-jwt"),
+            Some(forged.as_str()),
+            Some(revoked.as_str()),
+        ] {
+            assert_eq!(f.optional_user(authorization).await, None, "for {:?}", authorization);
+        }
+
+        // A valid token still works alongside
+        let token = f.token(&[]);
+        assert_eq!(f.optional_user(Some(&token)).await, Some(f.user_id));
+    }
+
+    async fn fresh_auth_status(authenticated_at: Timestamp) -> axum::http::StatusCode {
+        fresh_auth_status_for(AuthenticatedUser {
+            user_id: UserId::new(),
+            tenant_id: None,
+            authenticated_at,
+            roles: Vec::new(),
+            impersonator: None,
+        })
+        .await
+    }
+
+    async fn fresh_auth_status_for(user: AuthenticatedUser) -> axum::http::StatusCode {
+        use axum::{body::Body, middleware, routing::put, Extension, Router};
+        use tower::ServiceExt;
+
+        let state = AppState::for_tests();
+        let app = Router::new()
+            .route("/password", put(|| async { "changed" }))
+            .route_layer(middleware::from_fn_with_state(state.clone(), require_fresh_auth))
+            .layer(Extension(user))
+            .with_state(state);
+
+        let request = HttpRequest::builder()
+            .method("PUT")
+            .uri("/password")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_require_fresh_auth_rejects_stale_token() {
+        let window = crate::config::Config::for_tests().security.fresh_auth_window as i64;
+        let stale = now() - chrono::Duration::seconds(window + 60);
+
+        assert_eq!(fresh_auth_status(stale).await, axum::http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_require_fresh_auth_allows_recent_token() {
+        assert_eq!(fresh_auth_status(now()).await, axum::http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_require_fresh_auth_rejects_impersonation() {
+        let status = fresh_auth_status_for(AuthenticatedUser {
+            user_id: UserId::new(),
+            tenant_id: None,
+            authenticated_at: now(),
+            roles: Vec::new(),
+            impersonator: Some(UserId::new()),
+        })
+        .await;
+
+        assert_eq!(status, axum::http::StatusCode::FORBIDDEN);
+    }
+
+    async fn admin_route_status(user: Option<AuthenticatedUser>) -> axum::http::StatusCode {
+        use axum::{body::Body, middleware, routing::get, Router};
+        use tower::ServiceExt;
+
+        let mut app = Router::new()
+            .route("/admin", get(|| async { "secret" }))
+            .route_layer(middleware::from_fn(require_role(Role::ADMIN)));
+        if let Some(user) = user {
+            app = app.layer(axum::Extension(user));
+        }
+
+        let request = HttpRequest::builder().uri("/admin").body(Body::empty()).unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    fn user_with_roles(roles: Vec<Role>) -> AuthenticatedUser {
+        AuthenticatedUser {
+            user_id: UserId::new(),
+            tenant_id: None,
+            authenticated_at: now(),
+            roles,
+            impersonator: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_require_role_rejects_user_without_role() {
+        let status = admin_route_status(Some(user_with_roles(vec![]))).await;
+        assert_eq!(status, axum::http::StatusCode::FORBIDDEN);
+
+        let status = admin_route_status(Some(user_with_roles(vec![Role::new("support").unwrap()]))).await;
+        assert_eq!(status, axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_require_role_allows_admin() {
+        let status = admin_route_status(Some(user_with_roles(vec![Role::admin()]))).await;
+        assert_eq!(status, axum::http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_require_role_without_authentication() {
+        assert_eq!(admin_route_status(None).await, axum::http::StatusCode::UNAUTHORIZED);
+    }
+
+    /// `GET /admin` behind `jwt_auth_middleware` and `require_role(admin)`,
+    /// with in-memory token storage and a single user
+    struct StatusPolicyFixture {
+        state: AppState,
+        token_repo: std::sync::Arc<crate::moduls::auth::infra::in_memory::InMemoryTokenRepository>,
+        user_id: UserId,
+    }
+
+    impl StatusPolicyFixture {
+        fn new() -> Self {
+            use crate::moduls::auth::application::TokenWatermark;
+            use crate::moduls::auth::domain::{Email, PasswordHasher, User};
+            use crate::moduls::auth::infra::in_memory::*;
+            use std::sync::Arc;
+
+            let user = User::with_hasher(
+                Email::new("policy@example.com").unwrap(),
+                "password123",
+                "Policy User".to_string(),
+                &PasswordHasher::Bcrypt { cost: 4 },
+            )
+            .unwrap();
+            let user_id = user.id;
+            let token_repo = Arc::new(InMemoryTokenRepository::default());
+            let mut state = AppState::for_tests();
+            state.token_repo = token_repo.clone();
+            state.token_watermark = Arc::new(TokenWatermark::new(
+                Arc::new(InMemoryTokenWatermarkRepository::default()),
+                Arc::new(InMemoryUserRepository::with_user(user)),
+                None,
+                std::time::Duration::ZERO,
+            ));
+
+            Self {
+                state,
+                token_repo,
+                user_id,
+            }
+        }
+
+        /// Stored access token of the user, with `roles` in its claims
+        fn token(&self, roles: &[Role]) -> String {
+            let (token_pair, access, _) = TokenPair::generate_with_auth_time(
+                self.user_id,
+                None,
+                None,
+                Some(roles),
+                None,
+                Default::default(),
+                &self.state.jwt_keys,
+                900,
+                3600,
+            )
+            .unwrap();
+            self.token_repo.tokens.lock().unwrap().push(access);
+            format!("Bearer {}", token_pair.access_token)
+        }
+
+        /// Stored access token of the user, from a login older than the
+        /// fresh auth window
+        fn stale_token(&self) -> String {
+            let window = self.state.config.security.fresh_auth_window as i64;
+            let login = now() - chrono::Duration::seconds(window + 60);
+            let (token_pair, access, _) = TokenPair::generate_with_auth_time(
+                self.user_id,
+                None,
+                Some(login.timestamp()),
+                Some(&[]),
+                None,
+                Default::default(),
+                &self.state.jwt_keys,
+                900,
+                3600,
+            )
+            .unwrap();
+            self.token_repo.tokens.lock().unwrap().push(access);
+            format!("Bearer {}", token_pair.access_token)
+        }
+
+        /// Stored impersonation token of the user, acting for another admin
+        fn impersonation_token(&self) -> String {
+            let (token, access) = TokenPair::generate_impersonation(
+                self.user_id,
+                None,
+                UserId::new(),
+                &[],
+                Default::default(),
+                &self.state.jwt_keys,
+                900,
+                1800,
+            )
+            .unwrap();
+            self.token_repo.tokens.lock().unwrap().push(access);
+            format!("Bearer {}", token.access_token)
+        }
+
+        /// User seen by an `OptionalAuthenticatedUser` handler for the
+        /// given Authorization header (the request is never rejected)
+        async fn optional_user(&self, authorization: Option<&str>) -> Option<UserId> {
+            use axum::{body::Body, routing::get, Router};
+            use tower::ServiceExt;
+
+            let app = Router::new()
+                .route(
+                    "/feed",
+                    get(|OptionalAuthenticatedUser(user): OptionalAuthenticatedUser| async move {
+                        user.map(|user| user.user_id.to_string()).unwrap_or_default()
+                    }),
+                )
+                .with_state(self.state.clone());
+            let mut request = HttpRequest::builder().uri("/feed");
+            if let Some(value) = authorization {
+                request = request.header("Authorization", value);
+            }
+
+            let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            std::str::from_utf8(&body).unwrap().parse().ok()
+        }
+
+        /// Status and error code for the given Authorization header
+        async fn call(&self, authorization: Option<&str>) -> (axum::http::StatusCode, String) {
+            use axum::{body::Body, middleware, routing::get, Router};
+            use tower::ServiceExt;
+
+            let app = Router::new()
+                .route("/admin", get(|| async { "secret" }))
+                .route_layer(middleware::from_fn(require_role(Role::ADMIN)))
+                .route_layer(middleware::from_fn_with_state(self.state.clone(), jwt_auth_middleware))
+                .with_state(self.state.clone());
+            let mut request = HttpRequest::builder().uri("/admin");
+            if let Some(value) = authorization {
+                request = request.header("Authorization", value);
+            }
+
+            let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let code = serde_json::from_slice::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|body| body["error"]["code"].as_str().map(str::to_string))
+                .unwrap_or_default();
+            (status, code)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_credentials_are_401() {
+        let f = StatusPolicyFixture::new();
+        let revoked = f.token(&[Role::admin()]);
+        f.token_repo.tokens.lock().unwrap()[0].revoke();
+        let other_keys = crate::moduls::auth::domain::JwtKeys::hmac("another_secret_that_is_long_enough!!");
+        let (forged, _, _) = TokenPair::generate(f.user_id, &other_keys, 900, 3600).unwrap();
+        let forged = format!("Bearer {}", forged.access_token);
+
+        for authorization in [
+            None,
+            Some("Basic dXNlcjpwYXNz"),
+            Some("Bearer not-a-jwt"),
+            Some(forged.as_str()),
+            Some(revoked.as_str()),
+        ] {
+            assert_eq!(
+                f.call(authorization).await,
+                (axum::http::StatusCode::UNAUTHORIZED, "AUTHENTICATION_ERROR".to_string()),
+                "for {:?}",
+                authorization
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_valid_credentials_without_privilege_are_403() {
+        let f = StatusPolicyFixture::new();
+
+        for roles in [vec![], vec![Role::new("support").unwrap()]] {
+            let token = f.token(&roles);
+            assert_eq!(
+                f.call(Some(&token)).await,
+                (axum::http::StatusCode::FORBIDDEN, "AUTHORIZATION_ERROR".to_string())
+            );
+        }
+
+        let token = f.token(&[Role::admin()]);
+        assert_eq!(f.call(Some(&token)).await.0, axum::http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_impersonation_token_cannot_create_api_key() {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let f = StatusPolicyFixture::new();
+        let app = crate::moduls::user::api::user_api_routes(f.state.clone()).with_state(f.state.clone());
+        let request = HttpRequest::builder()
+            .method("POST")
+            .uri("/api-keys")
+            .header("Authorization", f.impersonation_token())
+            .header("Content-Type", "application/json")
+            .body(Body::from(r#"{"name":"backdoor"}"#))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_oauth_link_requires_fresh_own_login() {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let f = StatusPolicyFixture::new();
+        let app = crate::moduls::oauth::oauth_link_api_routes(f.state.clone())
+            .with_state(f.state.clone());
+
+        for (method, uri) in [("POST", "/google/link"), ("DELETE", "/google")] {
+            for (authorization, expected, code) in [
+                (f.stale_token(), axum::http::StatusCode::UNAUTHORIZED, "REAUTH_REQUIRED"),
+                (f.impersonation_token(), axum::http::StatusCode::FORBIDDEN, "AUTHORIZATION_ERROR"),
+            ] {
+                let request = HttpRequest::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("Authorization", authorization)
+                    .body(Body::empty())
+                    .unwrap();
+                let response = app.clone().oneshot(request).await.unwrap();
+                assert_eq!(response.status(), expected, "{} {}", method, uri);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                assert_eq!(body["error"]["code"], code, "{} {}", method, uri);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forbid_impersonation_allows_own_token() {
+        use axum::{body::Body, middleware, routing::post, Router};
+        use tower::ServiceExt;
+
+        let f = StatusPolicyFixture::new();
+        let app = Router::new()
+            .route("/mint", post(|| async { "minted" }))
+            .route_layer(middleware::from_fn(forbid_impersonation))
+            .route_layer(middleware::from_fn_with_state(f.state.clone(), jwt_auth_middleware))
+            .with_state(f.state.clone());
+
+        for (authorization, expected) in [
+            (f.token(&[]), axum::http::StatusCode::OK),
+            (f.impersonation_token(), axum::http::StatusCode::FORBIDDEN),
+        ] {
+            let request = HttpRequest::builder()
+                .method("POST")
+                .uri("/mint")
+                .header("Authorization", authorization)
+                .body(Body::empty())
+                .unwrap();
+            assert_eq!(app.clone().oneshot(request).await.unwrap().status(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extractor_without_middleware_is_401_json() {
+        use axum::response::IntoResponse;
+
+        let state = AppState::for_tests();
+        let mut parts = parts_with_header(None);
+
+        let rejection = AuthenticatedUser::from_request_parts(&mut parts, &state)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(rejection, AppError::Authentication(_)));
+        assert_eq!(rejection.into_response().status(), axum::http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_api_key_from_either_header() {
+        let headers = parts_with_header(Some("ApiKey mt_abc")).headers;
+        assert_eq!(api_key_from(&headers), Some("mt_abc"));
+
+        let mut headers = parts_with_header(Some("Bearer eyJ")).headers;
+        assert_eq!(api_key_from(&headers), None);
+
+        headers.insert(API_KEY_HEADER, "mt_def".parse().unwrap());
+        assert_eq!(api_key_from(&headers), Some("mt_def"));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_without_prefix_rejected_before_lookup() {
+        let state = AppState::for_tests();
+        let headers = parts_with_header(Some("ApiKey not-one-of-ours")).headers;
+
+        let result = authenticate_api_key(&state, &headers).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(ref m)) if m == "Invalid API key"));
     }
 }
@@ -1,10 +1,13 @@
-/// API layer for authentication module
-///
-/// This layer provides JSON-based REST API endpoints
-/// with JWT authentication.
+//! API layer for authentication module
+//!
+//! This layer provides JSON-based REST API endpoints
+//! with JWT authentication.
 
 pub mod routes;
 pub mod handlers;
 pub mod middleware;
+pub mod refresh_cookie;
 
-pub use routes::auth_api_routes;
+pub use routes::{admin_api_routes, auth_api_routes};
+#[cfg(feature = "dev-tools")]
+pub use routes::dev_api_routes;
@@ -0,0 +1,127 @@
+//! HttpOnly refresh token cookie
+//!
+//! Lets browser clients keep the refresh token out of JS-accessible
+//! storage. Enabled with `REFRESH_TOKEN_COOKIE=true`; the JSON body stays
+//! supported for non-browser clients.
+
+use crate::config::JwtConfig;
+use crate::shared::cookies::read_cookie;
+use axum::http::{header, HeaderMap, HeaderValue};
+
+/// Cookie name carrying the refresh token
+pub const REFRESH_COOKIE_NAME: &str = "refresh_token";
+
+/// Path the cookie is scoped to (refresh and logout live under it)
+const REFRESH_COOKIE_PATH: &str = "/api/auth";
+
+/// Read the refresh token from the request's `Cookie` headers
+pub fn read(headers: &HeaderMap) -> Option<String> {
+    read_cookie(headers, REFRESH_COOKIE_NAME)
+}
+
+/// Append a `Set-Cookie` header for `refresh_token` when cookies are enabled
+pub fn set(headers: &mut HeaderMap, config: &JwtConfig, refresh_token: &str) {
+    append(headers, config, refresh_token, config.refresh_expiry);
+}
+
+/// Append a `Set-Cookie` header expiring the cookie when cookies are enabled
+pub fn clear(headers: &mut HeaderMap, config: &JwtConfig) {
+    append(headers, config, "", 0);
+}
+
+fn append(headers: &mut HeaderMap, config: &JwtConfig, value: &str, max_age: u64) {
+    if !config.refresh_cookie {
+        return;
+    }
+
+    let mut cookie = format!(
+        "{}={}; Path={}; Max-Age={}; HttpOnly; SameSite=Strict",
+        REFRESH_COOKIE_NAME, value, REFRESH_COOKIE_PATH, max_age
+    );
+    if config.refresh_cookie_secure {
+        cookie.push_str("; Secure");
+    }
+
+    match HeaderValue::from_str(&cookie) {
+        Ok(value) => {
+            headers.append(header::SET_COOKIE, value);
+        }
+        Err(e) => tracing::error!("Failed to build refresh token cookie: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(secure: bool) -> JwtConfig {
+        JwtConfig {
+            secret: "test_jwt_secret_key_minimum_32_characters_long".to_string(),
+            access_expiry: 900,
+            refresh_expiry: 3600,
+            refresh_cookie: true,
+            refresh_cookie_secure: secure,
+            minimal_claims: false,
+            algorithm: jsonwebtoken::Algorithm::HS256,
+            private_key_path: None,
+            public_key_paths: Vec::new(),
+            refresh_grace: 0,
+            max_token_length: 4096,
+            issuer: None,
+            audience: None,
+            revocation_cache: false,
+            revocation_cache_ttl: 5,
+            impersonation_ttl: 900,
+        }
+    }
+
+    #[test]
+    fn test_read_refresh_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; refresh_token=abc.def.ghi; lang=en"),
+        );
+
+        assert_eq!(read(&headers).as_deref(), Some("abc.def.ghi"));
+        assert_eq!(read(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_set_refresh_cookie_attributes() {
+        let mut headers = HeaderMap::new();
+
+        set(&mut headers, &config(true), "abc.def.ghi");
+
+        assert_eq!(
+            headers[header::SET_COOKIE],
+            "refresh_token=abc.def.ghi; Path=/api/auth; Max-Age=3600; HttpOnly; SameSite=Strict; Secure"
+        );
+    }
+
+    #[test]
+    fn test_clear_expires_refresh_cookie() {
+        let mut headers = HeaderMap::new();
+
+        clear(&mut headers, &config(false));
+
+        assert_eq!(
+            headers[header::SET_COOKIE],
+            "refresh_token=; Path=/api/auth; Max-Age=0; HttpOnly; SameSite=Strict"
+        );
+    }
+
+    #[test]
+    fn test_set_is_noop_when_disabled() {
+        let mut headers = HeaderMap::new();
+        let config = JwtConfig {
+            refresh_cookie: false,
+            ..config(true)
+        };
+
+        set(&mut headers, &config, "abc.def.ghi");
+        clear(&mut headers, &config);
+
+        assert!(headers.is_empty());
+    }
+}
@@ -1,25 +1,158 @@
-use crate::bootstrap::AppState;
+use crate::bootstrap::{idempotency::idempotency, rate_limit::rate_limit, AppState};
 use super::handlers;
+use super::middleware::{
+    forbid_impersonation, jwt_auth_middleware, jwt_or_api_key_middleware, logout_auth_middleware,
+    require_fresh_auth, require_role,
+};
+use crate::moduls::auth::domain::Role;
+use crate::moduls::organization::api::handlers as organization_handlers;
 use axum::{
-    routing::{get, post},
+    handler::Handler,
+    middleware,
+    routing::{delete, get, patch, post, put},
     Router,
 };
 
 /// Create API authentication routes
 ///
 /// Routes:
-/// - POST /api/auth/register - Register new user
-/// - POST /api/auth/login - Login and get JWT tokens
+/// - POST /api/auth/register - Register new user [rate limited per IP, Idempotency-Key]
+/// - POST /api/auth/login - Login and get JWT tokens [rate limited per IP]
+/// - POST /api/auth/password-params - How to send a user's password (client-side hashing)
 /// - POST /api/auth/refresh - Refresh access token
-/// - POST /api/auth/logout - Logout (revoke tokens) [requires auth]
+/// - POST /api/auth/forgot-password - Request a password reset token [rate limited per IP]
+/// - POST /api/auth/reset-password - Set a new password with a reset token
+/// - POST /api/auth/logout - Logout (revoke tokens) [requires auth unless LENIENT_LOGOUT]
+/// - POST /api/auth/revoke - Revoke a single token, RFC 7009 [requires auth or API key]
+/// - POST /api/auth/send-verification - Send an email verification token [requires auth]
+/// - PUT /api/auth/recovery-email - Set the recovery email [requires recent auth]
+/// - POST /api/auth/resend-verification - Send an email verification token by email
+/// - GET /api/auth/verify-email?token=... - Verify email with a token
+/// - POST /api/auth/mfa/verify - Complete a login with a TOTP code
+/// - POST /api/auth/mfa/totp/enable - Start TOTP setup [requires recent auth]
+/// - POST /api/auth/mfa/totp/confirm - Confirm TOTP setup with a code [requires auth, not impersonating]
 /// - GET /api/auth/me - Get current user [requires auth]
-pub fn auth_api_routes() -> Router<AppState> {
-    Router::new()
-        .route("/register", post(handlers::register))
+pub fn auth_api_routes(state: AppState) -> Router<AppState> {
+    // Routes that require a valid access token
+    let protected = Router::new()
+        .route("/me", get(handlers::me))
+        .route("/send-verification", post(handlers::send_verification))
+        .route(
+            "/recovery-email",
+            put(handlers::set_recovery_email.layer(middleware::from_fn_with_state(
+                state.clone(),
+                require_fresh_auth,
+            ))),
+        )
+        .route(
+            "/mfa/totp/enable",
+            post(handlers::enable_totp.layer(middleware::from_fn_with_state(
+                state.clone(),
+                require_fresh_auth,
+            ))),
+        )
+        .route(
+            "/mfa/totp/confirm",
+            post(handlers::confirm_totp.layer(middleware::from_fn(forbid_impersonation))),
+        )
+        .route_layer(middleware::from_fn_with_state(state.clone(), jwt_auth_middleware));
+
+    // Logout may tolerate a missing credential, depending on config
+    let logout = Router::new()
+        .route("/logout", post(handlers::logout))
+        .route_layer(middleware::from_fn_with_state(state.clone(), logout_auth_middleware));
+
+    // API keys let clients revoke tokens they can no longer authenticate with
+    let revoke = Router::new()
+        .route("/revoke", post(handlers::revoke_token))
+        .route_layer(middleware::from_fn_with_state(state.clone(), jwt_or_api_key_middleware));
+
+    // Unauthenticated entry points open to credential stuffing and spam
+    let rate_limited = Router::new()
+        .route(
+            "/register",
+            post(handlers::register.layer(middleware::from_fn_with_state(
+                state.clone(),
+                idempotency,
+            ))),
+        )
         .route("/login", post(handlers::login))
+        .route("/forgot-password", post(handlers::forgot_password))
+        .route_layer(middleware::from_fn_with_state(state, rate_limit));
+
+    Router::new()
+        .route("/password-params", post(handlers::password_params))
         .route("/refresh", post(handlers::refresh))
-        .route("/logout", post(handlers::logout))
-        .route("/me", get(handlers::me))
-    // TODO: Add JWT middleware for protected routes (logout, me)
-    // .route_layer(middleware::from_fn_with_state(state.clone(), jwt_auth_middleware))
+        .route("/reset-password", post(handlers::reset_password))
+        .route("/resend-verification", post(handlers::resend_verification))
+        .route("/verify-email", get(handlers::verify_email))
+        .route("/mfa/verify", post(handlers::verify_mfa))
+        .merge(rate_limited)
+        .merge(logout)
+        .merge(revoke)
+        .merge(protected)
+}
+
+/// Create admin API routes
+///
+/// Routes:
+/// - GET /api/admin/users - List users, paginated and searchable by email or name [requires admin]
+/// - POST /api/admin/users - Create a user, reporting duplicates per field [requires admin, Idempotency-Key]
+/// - PUT /api/admin/users/{id}/roles/{role} - Grant a role [requires admin]
+/// - DELETE /api/admin/users/{id}/roles/{role} - Revoke a role [requires admin]
+/// - PATCH /api/admin/users/{id}/status - Deactivate (signing out everywhere) or reactivate a user [requires admin]
+/// - DELETE /api/admin/users/{id}/lock - Lift a lockout after failed logins [requires admin]
+/// - PUT /api/admin/users/{id}/expiry - Set or clear when the account expires [requires admin]
+/// - PUT /api/admin/users/{id}/deletion - Schedule the account's deletion [requires admin]
+/// - DELETE /api/admin/users/{id}/deletion - Cancel a scheduled deletion [requires admin]
+/// - POST /api/admin/users/{id}/impersonate - Mint a short-lived token to act as a user [requires admin]
+/// - GET /api/admin/tenants - List tenants with user counts, paginated and filterable [requires super_admin]
+/// - POST /api/admin/tenants/{id}/revoke-all - Revoke all tokens and sessions of a tenant [requires super_admin]
+/// - POST /api/admin/password-hashes/migrations - Start flagging users with outdated password hashes [requires super_admin]
+/// - GET /api/admin/password-hashes/migrations - Progress of the password hash migration [requires super_admin]
+pub fn admin_api_routes(state: AppState) -> Router<AppState> {
+    // Layers run bottom-up: authenticate first, then check the role
+    let users = Router::new()
+        .route(
+            "/users",
+            get(handlers::list_users).post(handlers::create_user.layer(
+                middleware::from_fn_with_state(state.clone(), idempotency),
+            )),
+        )
+        .route(
+            "/users/{id}/roles/{role}",
+            put(handlers::grant_role).delete(handlers::revoke_role),
+        )
+        .route("/users/{id}/status", patch(handlers::set_user_status))
+        .route("/users/{id}/lock", delete(handlers::unlock_user))
+        .route("/users/{id}/expiry", put(handlers::set_user_expiry))
+        .route(
+            "/users/{id}/deletion",
+            put(handlers::schedule_user_deletion).delete(handlers::cancel_user_deletion),
+        )
+        .route("/users/{id}/impersonate", post(handlers::impersonate_user))
+        .route_layer(middleware::from_fn(require_role(Role::ADMIN)))
+        .route_layer(middleware::from_fn_with_state(state.clone(), jwt_auth_middleware));
+
+    let super_admin = Router::new()
+        .route("/tenants", get(organization_handlers::list_tenants))
+        .route("/tenants/{id}/revoke-all", post(handlers::revoke_tenant_credentials))
+        .route(
+            "/password-hashes/migrations",
+            get(handlers::hash_migration_progress).post(handlers::start_hash_migration),
+        )
+        .route_layer(middleware::from_fn(require_role(Role::SUPER_ADMIN)))
+        .route_layer(middleware::from_fn_with_state(state, jwt_auth_middleware));
+
+    users.merge(super_admin)
+}
+
+/// Create development API routes (`dev-tools` builds, mounted only with
+/// `DEV_MODE=true`)
+///
+/// Routes:
+/// - POST /api/dev/token - Mint tokens for an existing user without a password
+#[cfg(feature = "dev-tools")]
+pub fn dev_api_routes() -> Router<AppState> {
+    Router::new().route("/token", post(handlers::dev_token))
 }
@@ -0,0 +1,318 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::{User, UserDto};
+use crate::moduls::auth::infra::{
+    ApiKeyRepository, SessionRepository, TokenRepository, UserRepository,
+};
+use crate::shared::{types::*, AppError, AppResult};
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+/// Set Expiry Command (DTO)
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct SetExpiryCommand {
+    /// When the account stops working; `null` makes it permanent
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Schedule Deletion Command (DTO)
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ScheduleDeletionCommand {
+    /// When the account is deleted
+    pub at: Timestamp,
+}
+
+/// Account Lifecycle Use Case
+/// Lets admins manage locks, expiry and scheduled deletion, and carries
+/// out deletions once due
+///
+/// Business Logic:
+/// 1. Admins can't expire or schedule the deletion of their own account
+/// 2. The user must exist and not be deleted
+/// 3. Expiry takes effect on its own: expired accounts can't log in and
+///    their tokens are refused, without revocation
+/// 4. Due deletions are carried out like a user's own (`User::delete`,
+///    anonymized when configured), then the user is logged out everywhere
+/// 5. Every change is recorded in the user's audit trail
+pub struct AccountLifecycleUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    session_repo: Arc<dyn SessionRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    api_key_repo: Arc<dyn ApiKeyRepository>,
+    audit_log: Arc<AuditLog>,
+    anonymize: bool,
+}
+
+impl AccountLifecycleUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        session_repo: Arc<dyn SessionRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        api_key_repo: Arc<dyn ApiKeyRepository>,
+        audit_log: Arc<AuditLog>,
+        anonymize: bool,
+    ) -> Self {
+        Self {
+            user_repo,
+            session_repo,
+            token_repo,
+            api_key_repo,
+            audit_log,
+            anonymize,
+        }
+    }
+
+    /// Lift a lockout before it runs out
+    ///
+    /// # Errors
+    /// - NotFound if the user doesn't exist
+    /// - Database errors
+    pub async fn unlock(&self, actor_id: UserId, user_id: UserId) -> AppResult<UserDto> {
+        let mut user = self.find(user_id).await?;
+        user.unlock();
+        self.save(actor_id, user, AuditAction::AccountUnlocked).await
+    }
+
+    /// Set or clear the account's expiry
+    ///
+    /// # Errors
+    /// - Validation if the caller expires themselves
+    /// - NotFound if the user doesn't exist
+    /// - Conflict if the account is deleted
+    /// - Database errors
+    pub async fn set_expiry(
+        &self,
+        actor_id: UserId,
+        user_id: UserId,
+        cmd: SetExpiryCommand,
+    ) -> AppResult<UserDto> {
+        if cmd.expires_at.is_some() && actor_id == user_id {
+            return Err(AppError::validation("You cannot set an expiry on your own account"));
+        }
+
+        let mut user = self.find(user_id).await?;
+        user.set_expiry(cmd.expires_at)?;
+        self.save(actor_id, user, AuditAction::AccountExpiryChanged).await
+    }
+
+    /// Schedule the account's deletion
+    ///
+    /// # Errors
+    /// - Validation if the caller schedules their own deletion
+    /// - NotFound if the user doesn't exist
+    /// - Conflict if the account is deleted
+    /// - Database errors
+    pub async fn schedule_deletion(
+        &self,
+        actor_id: UserId,
+        user_id: UserId,
+        cmd: ScheduleDeletionCommand,
+    ) -> AppResult<UserDto> {
+        if actor_id == user_id {
+            return Err(AppError::validation("You cannot schedule the deletion of your own account"));
+        }
+
+        let mut user = self.find(user_id).await?;
+        user.schedule_deletion(cmd.at)?;
+        self.save(actor_id, user, AuditAction::AccountDeletionScheduled).await
+    }
+
+    /// Cancel a scheduled deletion
+    ///
+    /// # Errors
+    /// - NotFound if the user doesn't exist
+    /// - Conflict if the account is already deleted
+    /// - Database errors
+    pub async fn cancel_deletion(&self, actor_id: UserId, user_id: UserId) -> AppResult<UserDto> {
+        let mut user = self.find(user_id).await?;
+        user.cancel_deletion()?;
+        self.save(actor_id, user, AuditAction::AccountDeletionCancelled).await
+    }
+
+    /// Delete up to `limit` accounts whose scheduled deletion is due
+    ///
+    /// Returns how many were deleted; one that fails is logged and left
+    /// for the next run.
+    ///
+    /// # Errors
+    /// - Database errors finding the due accounts
+    pub async fn delete_due(&self, limit: u32) -> AppResult<u64> {
+        let mut deleted = 0;
+        for user in self.user_repo.find_due_for_deletion(limit).await? {
+            let user_id = user.id;
+            match self.delete(user).await {
+                Ok(()) => deleted += 1,
+                Err(e) => tracing::error!("Scheduled deletion of user {} failed: {}", user_id, e),
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn delete(&self, mut user: User) -> AppResult<()> {
+        user.delete(self.anonymize)?;
+        self.user_repo.update(&user).await?;
+
+        self.session_repo.delete_by_user_id(user.id).await?;
+        self.token_repo.revoke_all_user_tokens(user.id).await?;
+        self.api_key_repo.revoke_all_for_user(user.id).await?;
+
+        tracing::info!("Deleted user {} as scheduled", user.id);
+        self.audit_log
+            .record(
+                AuditEvent::new(AuditAction::AccountDeleted, Some(user.id))
+                    .with_tenant(user.tenant_id),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    async fn find(&self, user_id: UserId) -> AppResult<User> {
+        self.user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))
+    }
+
+    async fn save(&self, actor_id: UserId, user: User, action: AuditAction) -> AppResult<UserDto> {
+        let user = self.user_repo.update(&user).await?;
+
+        tracing::warn!("User {} set user {} to {}", actor_id, user.id, action.as_str());
+        self.audit_log
+            .record(AuditEvent::new(action, Some(user.id)).with_tenant(user.tenant_id))
+            .await;
+
+        Ok(UserDto::from(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{
+        AccountStatus, ApiKey, Email, JwtKeys, JwtToken, Session, TokenPair,
+    };
+    use crate::moduls::auth::infra::in_memory::*;
+
+    struct Fixture {
+        use_case: AccountLifecycleUseCase,
+        user_repo: Arc<InMemoryUserRepository>,
+        session_repo: Arc<InMemorySessionRepository>,
+        token_repo: Arc<InMemoryTokenRepository>,
+        api_key_repo: Arc<InMemoryApiKeyRepository>,
+        user_id: UserId,
+    }
+
+    async fn fixture() -> Fixture {
+        let email = Email::new("user@example.com").unwrap();
+        let user = User::new(email, "password123", "Some User".to_string()).unwrap();
+        let user_id = user.id;
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(user));
+        let session_repo = Arc::new(InMemorySessionRepository::default());
+        let token_repo = Arc::new(InMemoryTokenRepository::default());
+        let api_key_repo = Arc::new(InMemoryApiKeyRepository::default());
+
+        session_repo.save(&Session::new(user_id, None, None, 3600)).await.unwrap();
+        let keys = JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars");
+        let (_, access, refresh) = TokenPair::generate(user_id, &keys, 900, 3600).unwrap();
+        token_repo.save(&access).await.unwrap();
+        token_repo.save(&refresh).await.unwrap();
+        let (key, _) = ApiKey::issue(user_id, None, "CI".to_string(), None);
+        api_key_repo.save(&key).await.unwrap();
+
+        let use_case = AccountLifecycleUseCase::new(
+            user_repo.clone(),
+            session_repo.clone(),
+            token_repo.clone(),
+            api_key_repo.clone(),
+            Arc::new(AuditLog::for_tests()),
+            true,
+        );
+
+        Fixture {
+            use_case,
+            user_repo,
+            session_repo,
+            token_repo,
+            api_key_repo,
+            user_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unlock_lifts_lock() {
+        let f = fixture().await;
+        let mut user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        user.verify_email();
+        user.lock(now() + chrono::Duration::hours(1)).unwrap();
+        f.user_repo.update(&user).await.unwrap();
+
+        let dto = f.use_case.unlock(UserId::new(), f.user_id).await.unwrap();
+
+        assert_eq!(dto.status, AccountStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_set_and_clear_expiry() {
+        let f = fixture().await;
+        let past = SetExpiryCommand {
+            expires_at: Some(now() - chrono::Duration::seconds(1)),
+        };
+
+        let dto = f.use_case.set_expiry(UserId::new(), f.user_id, past).await.unwrap();
+        assert_eq!(dto.status, AccountStatus::Expired);
+
+        let never = SetExpiryCommand { expires_at: None };
+        let dto = f.use_case.set_expiry(UserId::new(), f.user_id, never).await.unwrap();
+        assert_ne!(dto.status, AccountStatus::Expired);
+    }
+
+    #[tokio::test]
+    async fn test_admin_cannot_expire_or_schedule_own_deletion() {
+        let f = fixture().await;
+        let expiry = SetExpiryCommand { expires_at: Some(now()) };
+        let deletion = ScheduleDeletionCommand { at: now() };
+
+        let expired = f.use_case.set_expiry(f.user_id, f.user_id, expiry).await;
+        let scheduled = f.use_case.schedule_deletion(f.user_id, f.user_id, deletion).await;
+
+        assert!(matches!(expired, Err(AppError::Validation(_))));
+        assert!(matches!(scheduled, Err(AppError::Validation(_))));
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert_eq!(user.expires_at, None);
+        assert_eq!(user.deletion_scheduled_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_due_deletion_deletes_and_logs_out() {
+        let f = fixture().await;
+        let at = ScheduleDeletionCommand { at: now() - chrono::Duration::seconds(1) };
+        f.use_case.schedule_deletion(UserId::new(), f.user_id, at).await.unwrap();
+
+        assert_eq!(f.use_case.delete_due(100).await.unwrap(), 1);
+
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert_eq!(user.status(), AccountStatus::Deleted);
+        assert!(f.session_repo.find_all_by_user_id(f.user_id).await.unwrap().is_empty());
+        assert!(f.token_repo.tokens.lock().unwrap().iter().all(JwtToken::is_revoked));
+        assert!(f.api_key_repo.keys.lock().unwrap().iter().all(ApiKey::is_revoked));
+        assert_eq!(f.use_case.delete_due(100).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_future_or_cancelled_deletion_is_not_carried_out() {
+        let f = fixture().await;
+        let later = ScheduleDeletionCommand { at: now() + chrono::Duration::days(30) };
+        f.use_case.schedule_deletion(UserId::new(), f.user_id, later).await.unwrap();
+        assert_eq!(f.use_case.delete_due(100).await.unwrap(), 0);
+
+        let due = ScheduleDeletionCommand { at: now() - chrono::Duration::seconds(1) };
+        f.use_case.schedule_deletion(UserId::new(), f.user_id, due).await.unwrap();
+        let dto = f.use_case.cancel_deletion(UserId::new(), f.user_id).await.unwrap();
+        assert_ne!(dto.status, AccountStatus::PendingDeletion);
+
+        assert_eq!(f.use_case.delete_due(100).await.unwrap(), 0);
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert_ne!(user.status(), AccountStatus::Deleted);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::User;
+use crate::moduls::auth::infra::{LoginAttemptRepository, UserRepository};
+use crate::shared::{types::*, AppResult};
+use std::sync::Arc;
+
+/// When repeated failures lock an account
+#[derive(Debug, Clone, Copy)]
+pub struct LockoutPolicy {
+    /// Failed attempts within `duration_seconds` that lock the account
+    pub threshold: u32,
+    /// How long the account stays locked, and how far back failures count
+    pub duration_seconds: i64,
+}
+
+/// Locks accounts after repeated failed password checks
+///
+/// Counts the failures recorded with the login attempts (failed logins and
+/// failed password verifications alike) since the later of the window
+/// start and the end of the previous lock, so an expired or lifted lock
+/// isn't renewed by the failures that caused it.
+pub struct AccountLockout {
+    user_repo: Arc<dyn UserRepository>,
+    login_attempt_repo: Arc<dyn LoginAttemptRepository>,
+    audit_log: Arc<AuditLog>,
+    policy: LockoutPolicy,
+}
+
+impl AccountLockout {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        login_attempt_repo: Arc<dyn LoginAttemptRepository>,
+        audit_log: Arc<AuditLog>,
+        policy: LockoutPolicy,
+    ) -> Self {
+        Self {
+            user_repo,
+            login_attempt_repo,
+            audit_log,
+            policy,
+        }
+    }
+
+    /// Lock `user` if the failure just recorded reaches the threshold
+    ///
+    /// Errors are logged: a failed lock must not change the response to
+    /// the failed attempt.
+    pub async fn after_failure(&self, user: &User) {
+        if let Err(e) = self.lock_if_due(user).await {
+            tracing::warn!("Failed to apply lockout to user {}: {}", user.id, e);
+        }
+    }
+
+    async fn lock_if_due(&self, user: &User) -> AppResult<()> {
+        let now = now();
+        let window_start = now - chrono::Duration::seconds(self.policy.duration_seconds);
+        let since = user.locked_until.map_or(window_start, |until| until.max(window_start));
+        let summary = self.login_attempt_repo.security_summary(user.id, since).await?;
+        if summary.recent_failed_logins < i64::from(self.policy.threshold) {
+            return Ok(());
+        }
+
+        // Reload: `user` may predate a concurrent update
+        let Some(mut user) = self.user_repo.find_by_id(user.id).await? else {
+            return Ok(());
+        };
+        let until = now + chrono::Duration::seconds(self.policy.duration_seconds);
+        user.lock(until)?;
+        let user = self.user_repo.update(&user).await?;
+
+        tracing::warn!(
+            "Locked user {} until {} after {} failed attempts",
+            user.id,
+            until,
+            summary.recent_failed_logins
+        );
+        self.audit_log
+            .record(AuditEvent::new(AuditAction::AccountLocked, Some(user.id)).with_tenant(user.tenant_id))
+            .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{AccountStatus, Email};
+    use crate::moduls::auth::infra::in_memory::*;
+
+    struct Fixture {
+        lockout: AccountLockout,
+        user_repo: Arc<InMemoryUserRepository>,
+        login_attempt_repo: Arc<InMemoryLoginAttemptRepository>,
+        user: User,
+    }
+
+    fn fixture(threshold: u32) -> Fixture {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(user.clone()));
+        let login_attempt_repo = Arc::new(InMemoryLoginAttemptRepository::default());
+
+        let lockout = AccountLockout::new(
+            user_repo.clone(),
+            login_attempt_repo.clone(),
+            Arc::new(AuditLog::for_tests()),
+            LockoutPolicy {
+                threshold,
+                duration_seconds: 900,
+            },
+        );
+
+        Fixture {
+            lockout,
+            user_repo,
+            login_attempt_repo,
+            user,
+        }
+    }
+
+    async fn fail(f: &Fixture) {
+        f.login_attempt_repo.record(f.user.id, false, None).await.unwrap();
+        let user = f.user_repo.find_by_id(f.user.id).await.unwrap().unwrap();
+        f.lockout.after_failure(&user).await;
+    }
+
+    async fn status(f: &Fixture) -> AccountStatus {
+        f.user_repo.find_by_id(f.user.id).await.unwrap().unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_locks_at_threshold() {
+        let f = fixture(3);
+
+        fail(&f).await;
+        fail(&f).await;
+        assert_ne!(status(&f).await, AccountStatus::Locked);
+
+        fail(&f).await;
+        assert_eq!(status(&f).await, AccountStatus::Locked);
+        let user = f.user_repo.find_by_id(f.user.id).await.unwrap().unwrap();
+        assert!(user.locked_until.unwrap() > now() + chrono::Duration::seconds(890));
+    }
+
+    #[tokio::test]
+    async fn test_failures_before_unlock_do_not_relock() {
+        let f = fixture(2);
+        fail(&f).await;
+        fail(&f).await;
+        let mut user = f.user_repo.find_by_id(f.user.id).await.unwrap().unwrap();
+        user.unlock();
+        f.user_repo.update(&user).await.unwrap();
+
+        fail(&f).await;
+
+        assert_ne!(status(&f).await, AccountStatus::Locked);
+    }
+}
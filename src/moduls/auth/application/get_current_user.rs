@@ -0,0 +1,57 @@
+use crate::moduls::auth::domain::{LoginSecuritySummary, UserDto};
+use crate::moduls::auth::infra::{LoginAttemptRepository, UserRepository};
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+
+/// Current user with their recent login activity
+pub struct CurrentUserResult {
+    pub user: UserDto,
+    pub security: LoginSecuritySummary,
+}
+
+/// Use case for loading the authenticated user (`/me`)
+///
+/// Business Logic:
+/// 1. Load user by ID
+/// 2. Summarize failed logins within the activity window
+/// 3. Return user DTO with security summary
+pub struct GetCurrentUserUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    login_attempt_repo: Arc<dyn LoginAttemptRepository>,
+    activity_window_seconds: i64,
+}
+
+impl GetCurrentUserUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        login_attempt_repo: Arc<dyn LoginAttemptRepository>,
+        activity_window_seconds: i64,
+    ) -> Self {
+        Self {
+            user_repo,
+            login_attempt_repo,
+            activity_window_seconds,
+        }
+    }
+
+    /// Execute the use case
+    ///
+    /// # Errors
+    /// - NotFound if the user was deleted after the token was issued
+    /// - Database errors
+    pub async fn execute(&self, user_id: UserId) -> AppResult<CurrentUserResult> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        let since = now() - chrono::Duration::seconds(self.activity_window_seconds);
+        let security = self.login_attempt_repo.security_summary(user_id, since).await?;
+
+        Ok(CurrentUserResult {
+            user: UserDto::from(user),
+            security,
+        })
+    }
+}
@@ -0,0 +1,292 @@
+use crate::bootstrap::BackgroundTasks;
+use crate::moduls::auth::domain::{HashMigrationRun, PasswordHasher};
+use crate::moduls::auth::infra::HashMigrationRepository;
+use crate::shared::{types::*, AppError, AppResult};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Progress of the password hash migration
+#[derive(Debug, Clone, Serialize)]
+pub struct HashMigrationProgress {
+    /// Latest run, if the job ever ran
+    pub run: Option<HashMigrationRun>,
+    /// Flagged users who have not changed their password yet
+    pub pending_users: i64,
+}
+
+/// Password Hash Migration Use Case
+/// Flags dormant accounts whose password hash has outdated parameters
+///
+/// Hashes are upgraded at login, which dormant accounts never reach. The
+/// plain password is needed to re-hash, so instead the job sets
+/// `must_change_password` on users whose hash the configured hasher would
+/// redo; their logins are refused until the password is reset, which
+/// clears the flag.
+///
+/// Business Logic:
+/// 1. One run at a time per instance; a run left `running` by a previous
+///    process is marked failed
+/// 2. The run is recorded and continues in the background
+/// 3. Users are scanned `batch_size` at a time in ID order, and the run's
+///    counters are stored after every batch
+pub struct HashMigrationUseCase {
+    repo: Arc<dyn HashMigrationRepository>,
+    hasher: PasswordHasher,
+    batch_size: u32,
+    background_tasks: BackgroundTasks,
+    running: Arc<AtomicBool>,
+}
+
+impl HashMigrationUseCase {
+    pub fn new(
+        repo: Arc<dyn HashMigrationRepository>,
+        hasher: PasswordHasher,
+        batch_size: u32,
+        background_tasks: BackgroundTasks,
+    ) -> Self {
+        Self {
+            repo,
+            hasher,
+            batch_size,
+            background_tasks,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Start a run on behalf of `admin_id`
+    ///
+    /// Returns the run as recorded; its progress is read with `progress`.
+    ///
+    /// # Errors
+    /// - Conflict if a run is already in progress
+    /// - Internal if the server is shutting down
+    /// - Database errors
+    pub async fn start(&self, admin_id: UserId) -> AppResult<HashMigrationRun> {
+        // 1. Claim the job
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err(AppError::conflict("A password hash migration is already running"));
+        }
+
+        let run = match self.record_run(admin_id).await {
+            Ok(run) => run,
+            Err(e) => {
+                self.running.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+        };
+
+        // 2. Continue in the background
+        let repo = self.repo.clone();
+        let hasher = self.hasher;
+        let batch_size = self.batch_size as usize;
+        let running = self.running.clone();
+        let started = run.clone();
+        let spawned = self.background_tasks.spawn("password_hash_migration", async move {
+            let run = flag_outdated(repo.as_ref(), &hasher, batch_size, started).await;
+            tracing::info!(
+                "Password hash migration {} {:?}: {} user(s) scanned, {} flagged",
+                run.id,
+                run.status,
+                run.scanned_users,
+                run.flagged_users
+            );
+            running.store(false, Ordering::SeqCst);
+        });
+        if !spawned {
+            // Shutting down or saturated; the next start marks the run interrupted
+            self.running.store(false, Ordering::SeqCst);
+            return Err(AppError::internal("Password hash migration could not be started"));
+        }
+
+        Ok(run)
+    }
+
+    /// Latest run and the users still to change their password
+    pub async fn progress(&self) -> AppResult<HashMigrationProgress> {
+        Ok(HashMigrationProgress {
+            run: self.repo.latest_run().await?,
+            pending_users: self.repo.count_flagged().await?,
+        })
+    }
+
+    async fn record_run(&self, admin_id: UserId) -> AppResult<HashMigrationRun> {
+        // The process running it is gone; the flags it set are kept
+        if let Some(mut stale) = self.repo.latest_run().await?.filter(HashMigrationRun::is_running) {
+            stale.fail("Interrupted");
+            self.repo.update_run(&stale).await?;
+        }
+
+        let run = HashMigrationRun::start(Some(admin_id));
+        self.repo.save_run(&run).await?;
+        Ok(run)
+    }
+}
+
+/// Scan every user and flag outdated hashes, storing progress per batch
+///
+/// Returns the finished run, completed or failed.
+async fn flag_outdated(
+    repo: &dyn HashMigrationRepository,
+    hasher: &PasswordHasher,
+    batch_size: usize,
+    mut run: HashMigrationRun,
+) -> HashMigrationRun {
+    // 3. Batch by batch
+    let mut after = None;
+    let result = loop {
+        let batch = match repo.password_hashes_after(after, batch_size).await {
+            Ok(batch) => batch,
+            Err(e) => break Err(e),
+        };
+        let Some((last, _)) = batch.last() else {
+            break Ok(());
+        };
+        after = Some(*last);
+
+        let outdated: Vec<UserId> = batch
+            .iter()
+            .filter(|(_, hash)| hasher.needs_rehash(hash))
+            .map(|(id, _)| *id)
+            .collect();
+        let flagged = if outdated.is_empty() {
+            0
+        } else {
+            match repo.flag_users(&outdated).await {
+                Ok(flagged) => flagged,
+                Err(e) => break Err(e),
+            }
+        };
+
+        run.record_batch(batch.len(), flagged);
+        if let Err(e) = repo.update_run(&run).await {
+            break Err(e);
+        }
+    };
+
+    match result {
+        Ok(()) => run.complete(),
+        Err(e) => {
+            tracing::error!("Password hash migration {} failed: {}", run.id, e);
+            run.fail(e.to_string());
+        }
+    }
+    if let Err(e) = repo.update_run(&run).await {
+        tracing::error!("Failed to record the end of password hash migration {}: {}", run.id, e);
+    }
+
+    run
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{Email, HashMigrationStatus, User};
+    use crate::moduls::auth::infra::in_memory::{
+        InMemoryHashMigrationRepository, InMemoryUserRepository,
+    };
+    use crate::moduls::auth::infra::UserRepository;
+    use std::time::Duration;
+
+    const OLD: PasswordHasher = PasswordHasher::Bcrypt { cost: 4 };
+    const CURRENT: PasswordHasher = PasswordHasher::Bcrypt { cost: 5 };
+
+    struct Fixture {
+        use_case: HashMigrationUseCase,
+        user_repo: Arc<InMemoryUserRepository>,
+        repo: Arc<InMemoryHashMigrationRepository>,
+        tasks: BackgroundTasks,
+    }
+
+    fn fixture(batch_size: u32) -> Fixture {
+        let user_repo = Arc::new(InMemoryUserRepository::default());
+        let repo = Arc::new(InMemoryHashMigrationRepository::new(user_repo.clone()));
+        let tasks = BackgroundTasks::new();
+        Fixture {
+            use_case: HashMigrationUseCase::new(repo.clone(), CURRENT, batch_size, tasks.clone()),
+            user_repo,
+            repo,
+            tasks,
+        }
+    }
+
+    async fn add_user(f: &Fixture, email: &str, hasher: &PasswordHasher) -> UserId {
+        let email = Email::new(email).unwrap();
+        let user = User::with_hasher(email, "password123", "User".to_string(), hasher).unwrap();
+        f.user_repo.save(&user).await.unwrap().id
+    }
+
+    async fn flagged(f: &Fixture, id: UserId) -> bool {
+        f.user_repo.find_by_id(id).await.unwrap().unwrap().must_change_password
+    }
+
+    #[tokio::test]
+    async fn test_users_with_outdated_hashes_are_flagged() {
+        let f = fixture(2);
+        let outdated = add_user(&f, "old1@example.com", &OLD).await;
+        let other_outdated = add_user(&f, "old2@example.com", &OLD).await;
+        let current = add_user(&f, "new@example.com", &CURRENT).await;
+
+        let run = f.use_case.start(UserId::new()).await.unwrap();
+        assert!(run.is_running());
+        f.tasks.drain(Duration::from_secs(5)).await;
+
+        assert!(flagged(&f, outdated).await);
+        assert!(flagged(&f, other_outdated).await);
+        assert!(!flagged(&f, current).await);
+
+        let progress = f.use_case.progress().await.unwrap();
+        let run = progress.run.unwrap();
+        assert_eq!(run.status, HashMigrationStatus::Completed);
+        assert_eq!((run.scanned_users, run.flagged_users), (3, 2));
+        assert_eq!(progress.pending_users, 2);
+    }
+
+    #[tokio::test]
+    async fn test_changed_password_clears_flag() {
+        let f = fixture(100);
+        let id = add_user(&f, "old@example.com", &OLD).await;
+        f.use_case.start(UserId::new()).await.unwrap();
+        f.tasks.drain(Duration::from_secs(5)).await;
+        assert!(flagged(&f, id).await);
+
+        let mut user = f.user_repo.find_by_id(id).await.unwrap().unwrap();
+        user.change_password("newpassword123", &CURRENT).unwrap();
+        f.user_repo.update(&user).await.unwrap();
+
+        assert!(!flagged(&f, id).await);
+        assert_eq!(f.use_case.progress().await.unwrap().pending_users, 0);
+
+        // The new hash is current, so another run leaves the user alone
+        f.use_case.start(UserId::new()).await.unwrap();
+        f.tasks.drain(Duration::from_secs(5)).await;
+        assert!(!flagged(&f, id).await);
+        let run = f.use_case.progress().await.unwrap().run.unwrap();
+        assert_eq!((run.scanned_users, run.flagged_users), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_only_one_run_at_a_time() {
+        let f = fixture(100);
+        f.use_case.running.store(true, Ordering::SeqCst);
+
+        let result = f.use_case.start(UserId::new()).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert!(f.repo.runs.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_run_is_marked_failed() {
+        let f = fixture(100);
+        let stale = HashMigrationRun::start(None);
+        f.repo.save_run(&stale).await.unwrap();
+
+        f.use_case.start(UserId::new()).await.unwrap();
+        f.tasks.drain(Duration::from_secs(5)).await;
+
+        let runs = f.repo.runs.lock().unwrap();
+        assert_eq!(runs[0].status, HashMigrationStatus::Failed);
+        assert_eq!(runs[1].status, HashMigrationStatus::Completed);
+    }
+}
@@ -0,0 +1,273 @@
+use crate::config::MAX_IMPERSONATION_TTL;
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::{ClaimsFormat, ImpersonationToken, JwtKeys, TokenPair};
+use crate::moduls::auth::infra::{RoleRepository, TokenRepository, UserRepository};
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+
+/// Use case for admins acting as another user (support, debugging)
+///
+/// Business Logic:
+/// 1. The caller must not be impersonating already: an impersonation
+///    token can't start another impersonation
+/// 2. The user must exist, be active and not be the caller
+/// 3. The user may not hold a role the caller lacks: impersonation never
+///    grants more than the caller already has
+/// 4. Mint an access token naming the admin in its `act` claim, with the
+///    user's roles embedded. It lives at most `MAX_IMPERSONATION_TTL`
+///    seconds, comes without a refresh token, so the impersonation ends
+///    when it expires, and never counts as a fresh login
+/// 5. Record the impersonation in the user's audit trail
+pub struct ImpersonateUserUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    role_repo: Arc<dyn RoleRepository>,
+    audit_log: Arc<AuditLog>,
+    jwt_keys: Arc<JwtKeys>,
+    claims_format: ClaimsFormat,
+    ttl_seconds: i64,
+}
+
+impl ImpersonateUserUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        role_repo: Arc<dyn RoleRepository>,
+        audit_log: Arc<AuditLog>,
+        jwt_keys: Arc<JwtKeys>,
+        claims_format: ClaimsFormat,
+        ttl_seconds: i64,
+    ) -> Self {
+        Self {
+            user_repo,
+            token_repo,
+            role_repo,
+            audit_log,
+            jwt_keys,
+            claims_format,
+            ttl_seconds,
+        }
+    }
+
+    /// Mint a token for `actor_id` to act as `user_id`
+    ///
+    /// `actor_impersonator` is the caller's own impersonator, when the
+    /// caller authenticated with an impersonation token.
+    ///
+    /// # Errors
+    /// - Authorization if the caller is impersonating already
+    /// - Authorization if the user holds a role the caller lacks
+    /// - Validation if the caller targets themselves
+    /// - NotFound if the user doesn't exist or is inactive
+    /// - Database errors
+    pub async fn execute(
+        &self,
+        actor_id: UserId,
+        actor_impersonator: Option<UserId>,
+        user_id: UserId,
+    ) -> AppResult<ImpersonationToken> {
+        // 1. No chaining
+        if actor_impersonator.is_some() {
+            return Err(AppError::authorization(
+                "Impersonation tokens cannot start another impersonation",
+            ));
+        }
+
+        // 2. Check the user
+        if actor_id == user_id {
+            return Err(AppError::validation("You cannot impersonate yourself"));
+        }
+
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .filter(|u| u.is_active)
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        // 3. No escalation
+        let actor_roles = self.role_repo.find_by_user_id(actor_id).await?;
+        let roles = self.role_repo.find_by_user_id(user.id).await?;
+        if roles.iter().any(|role| !actor_roles.contains(role)) {
+            return Err(AppError::authorization(
+                "You cannot impersonate a user with roles you don't hold",
+            ));
+        }
+
+        // 4. Mint the token, capped whatever the configuration says
+        let (token, jwt_token) = TokenPair::generate_impersonation(
+            user.id,
+            user.tenant_id,
+            actor_id,
+            &roles,
+            self.claims_format,
+            &self.jwt_keys,
+            self.ttl_seconds,
+            MAX_IMPERSONATION_TTL as i64,
+        )?;
+        self.token_repo.save(&jwt_token).await?;
+
+        // 5. Audit
+        tracing::warn!(
+            "User {} started impersonating user {} until {}",
+            actor_id,
+            user.id,
+            token.access_expires_at
+        );
+        self.audit_log
+            .record(
+                AuditEvent::new(AuditAction::ImpersonationStarted, Some(user.id))
+                    .with_tenant(user.tenant_id),
+            )
+            .await;
+
+        Ok(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::application::{
+        RefreshConfig, RefreshTokenCommand, RefreshTokenUseCase, TokenWatermark,
+    };
+    use crate::moduls::auth::domain::{Email, Role, User};
+    use crate::moduls::auth::infra::in_memory::{
+        InMemoryRoleRepository, InMemoryTokenRepository, InMemoryTokenWatermarkRepository,
+        InMemoryUserRepository,
+    };
+
+    struct Fixture {
+        use_case: ImpersonateUserUseCase,
+        token_repo: Arc<InMemoryTokenRepository>,
+        role_repo: Arc<InMemoryRoleRepository>,
+        keys: Arc<JwtKeys>,
+        user_id: UserId,
+    }
+
+    fn fixture(ttl_seconds: i64) -> Fixture {
+        let user = User::new(
+            Email::new("target@example.com").unwrap(),
+            "password123",
+            "Target User".to_string(),
+        )
+        .unwrap();
+        let user_id = user.id;
+        let token_repo = Arc::new(InMemoryTokenRepository::default());
+        let keys = Arc::new(JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars"));
+        let role_repo = Arc::new(InMemoryRoleRepository::default());
+
+        Fixture {
+            use_case: ImpersonateUserUseCase::new(
+                Arc::new(InMemoryUserRepository::with_user(user)),
+                token_repo.clone(),
+                role_repo.clone(),
+                Arc::new(AuditLog::for_tests()),
+                keys.clone(),
+                ClaimsFormat::Verbose,
+                ttl_seconds,
+            ),
+            token_repo,
+            role_repo,
+            keys,
+            user_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_expires_within_cap() {
+        let f = fixture(86400);
+        let admin_id = UserId::new();
+
+        let token = f.use_case.execute(admin_id, None, f.user_id).await.unwrap();
+
+        let claims = TokenPair::decode(&token.access_token, &f.keys).unwrap();
+        assert_eq!(claims.sub, f.user_id.to_string());
+        assert_eq!(claims.impersonator().unwrap(), Some(admin_id));
+        assert_eq!(token.expires_in, MAX_IMPERSONATION_TTL as i64);
+        assert!(claims.exp - claims.iat <= MAX_IMPERSONATION_TTL as i64);
+        // Stored, so it can be revoked like any access token
+        assert_eq!(f.token_repo.tokens.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_cannot_be_refreshed() {
+        let f = fixture(600);
+        let token = f.use_case.execute(UserId::new(), None, f.user_id).await.unwrap();
+        let refresh = RefreshTokenUseCase::new(
+            f.token_repo.clone(),
+            Arc::new(TokenWatermark::new(
+                Arc::new(InMemoryTokenWatermarkRepository::default()),
+                Arc::new(InMemoryUserRepository::default()),
+                None,
+                std::time::Duration::ZERO,
+            )),
+            Arc::new(InMemoryRoleRepository::default()),
+            Arc::new(AuditLog::for_tests()),
+            RefreshConfig {
+                jwt_keys: f.keys.clone(),
+                access_ttl_seconds: 900,
+                refresh_ttl_seconds: 604800,
+                claims_format: ClaimsFormat::Verbose,
+                grace_seconds: 0,
+            },
+        );
+
+        let result = refresh
+            .execute(RefreshTokenCommand {
+                refresh_token: token.access_token,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_impersonation_cannot_be_chained() {
+        let f = fixture(600);
+
+        let result = f.use_case.execute(UserId::new(), Some(UserId::new()), f.user_id).await;
+
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+        assert!(f.token_repo.tokens.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_or_self_is_rejected() {
+        let f = fixture(600);
+
+        let result = f.use_case.execute(UserId::new(), None, UserId::new()).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        let result = f.use_case.execute(f.user_id, None, f.user_id).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_token_is_never_fresh_and_carries_user_roles() {
+        let f = fixture(600);
+        let admin_id = UserId::new();
+        let admin = Role::new(Role::ADMIN).unwrap();
+        f.role_repo.grant(admin_id, &admin).await.unwrap();
+        f.role_repo.grant(f.user_id, &admin).await.unwrap();
+
+        let token = f.use_case.execute(admin_id, None, f.user_id).await.unwrap();
+
+        let claims = TokenPair::decode(&token.access_token, &f.keys).unwrap();
+        assert_eq!(claims.authenticated_at(), 0);
+        assert_eq!(claims.roles().unwrap(), Some(vec![admin]));
+    }
+
+    #[tokio::test]
+    async fn test_user_with_roles_the_caller_lacks_is_rejected() {
+        let f = fixture(600);
+        let admin_id = UserId::new();
+        f.role_repo.grant(admin_id, &Role::new(Role::ADMIN).unwrap()).await.unwrap();
+        f.role_repo.grant(f.user_id, &Role::new(Role::SUPER_ADMIN).unwrap()).await.unwrap();
+
+        let result = f.use_case.execute(admin_id, None, f.user_id).await;
+
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+        assert!(f.token_repo.tokens.lock().unwrap().is_empty());
+    }
+}
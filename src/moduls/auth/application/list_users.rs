@@ -0,0 +1,118 @@
+use crate::moduls::auth::domain::UserDto;
+use crate::moduls::auth::infra::{UserFilter, UserRepository};
+use crate::shared::pagination::{Page, PageRequest};
+use crate::shared::AppResult;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Largest page the admin users listing returns
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// Query of the admin users listing, from query parameters
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ListUsersQuery {
+    /// Case-insensitive substring of the email or name
+    pub q: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+/// List Users Use Case
+/// Lists users for admins
+///
+/// Users are ordered by creation time; `per_page` is capped at
+/// `MAX_PER_PAGE`.
+pub struct ListUsersUseCase {
+    user_repo: Arc<dyn UserRepository>,
+}
+
+impl ListUsersUseCase {
+    pub fn new(user_repo: Arc<dyn UserRepository>) -> Self {
+        Self { user_repo }
+    }
+
+    /// Execute the use case to list a page of users
+    pub async fn execute(&self, query: ListUsersQuery) -> AppResult<Page<UserDto>> {
+        let filter = UserFilter {
+            search: query
+                .q
+                .map(|q| q.trim().to_string())
+                .filter(|q| !q.is_empty()),
+        };
+        let page = PageRequest::new(query.page, query.per_page, MAX_PER_PAGE);
+
+        let users = self.user_repo.list_paginated(&filter, page).await?;
+
+        Ok(users.map(UserDto::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{Email, User};
+    use crate::moduls::auth::infra::in_memory::InMemoryUserRepository;
+
+    async fn use_case() -> ListUsersUseCase {
+        let repo = Arc::new(InMemoryUserRepository::default());
+        for (email, name) in [
+            ("alice@acme.com", "Alice"),
+            ("bob@example.com", "Bob Acme"),
+            ("carol@example.com", "Carol"),
+        ] {
+            let user = User::new(Email::new(email).unwrap(), "password123", name.to_string()).unwrap();
+            repo.save(&user).await.unwrap();
+        }
+        ListUsersUseCase::new(repo)
+    }
+
+    fn emails(page: &Page<UserDto>) -> Vec<&str> {
+        page.items.iter().map(|u| u.email.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_email_or_name() {
+        let page = use_case()
+            .await
+            .execute(ListUsersQuery {
+                q: Some(" ACME ".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(emails(&page), ["alice@acme.com", "bob@example.com"]);
+        assert_eq!(page.total, 2);
+    }
+
+    #[tokio::test]
+    async fn test_pages_in_creation_order() {
+        let page = use_case()
+            .await
+            .execute(ListUsersQuery {
+                page: Some(2),
+                per_page: Some(2),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(emails(&page), ["carol@example.com"]);
+        assert_eq!(page.total, 3);
+    }
+
+    #[tokio::test]
+    async fn test_page_size_is_capped() {
+        let page = use_case()
+            .await
+            .execute(ListUsersQuery {
+                per_page: Some(500),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(page.per_page, MAX_PER_PAGE);
+        assert_eq!(page.items.len(), 3);
+    }
+}
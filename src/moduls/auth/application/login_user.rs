@@ -1,6 +1,16 @@
-use crate::moduls::auth::domain::{Email, Session, TokenPair, UserDto};
-use crate::moduls::auth::infra::{UserRepository, SessionRepository, TokenRepository};
-use crate::shared::{AppError, AppResult};
+use super::{AccountLockout, SecurityNotifier};
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::{
+    AccountStatus, ClaimsFormat, ClientHashParams, ClientHashing, Email, JwtKeys, MfaChallenge,
+    NotificationCategory, PasswordHash, PasswordHasher, Session, TokenPair, User, UserDto,
+};
+use crate::moduls::auth::infra::{
+    LoginAttemptRepository, MfaChallengeRepository, RoleRepository, SessionRepository,
+    TokenRepository, TotpRepository, UserRepository,
+};
+use crate::moduls::organization::domain::{Organization, OrganizationDto};
+use crate::moduls::organization::infra::MembershipRepository;
+use crate::shared::{types::OrganizationId, AppError, AppResult};
 use std::sync::Arc;
 
 /// Command for web-based login (session)
@@ -10,13 +20,96 @@ pub struct LoginWebCommand {
     pub password: String,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
+    /// Label for the session in the user's device list (e.g. "John's iPhone")
+    #[serde(default)]
+    pub device_name: Option<String>,
+    /// Keep the session for `SESSION_REMEMBER_EXPIRY` instead of `SESSION_EXPIRY`
+    #[serde(default)]
+    pub remember_me: bool,
+    /// Tenant of the request (from `TenantContext`, never the body)
+    #[serde(skip)]
+    pub tenant_id: Option<OrganizationId>,
 }
 
 /// Command for API-based login (JWT)
 #[derive(Debug, serde::Deserialize)]
 pub struct LoginApiCommand {
     pub email: String,
+    /// The password, or its client hash when `client_hash` is set
     pub password: String,
+    /// Parameters `password` was hashed with client-side
+    #[serde(default)]
+    pub client_hash: Option<ClientHashParams>,
+    /// Tenant to log into; required when the user belongs to several
+    #[serde(default)]
+    pub tenant_slug: Option<String>,
+    /// Tenant of the request (from `TenantContext`, never the body);
+    /// takes precedence over `tenant_slug`
+    #[serde(skip)]
+    pub tenant_id: Option<OrganizationId>,
+    /// Client address, for the login history and new-device emails
+    #[serde(skip)]
+    pub ip_address: Option<String>,
+}
+
+/// Command minting tokens for a user without a password (`dev-tools`)
+#[cfg(feature = "dev-tools")]
+#[derive(Debug, serde::Deserialize, validator::Validate)]
+pub struct DevLoginCommand {
+    #[validate(length(min = 1, message = "Email is required"))]
+    pub email: String,
+    /// Tenant to mint the tokens for; required when the user belongs to several
+    #[serde(default)]
+    pub tenant_slug: Option<String>,
+}
+
+/// Command asking how to send a user's password
+#[derive(Debug, serde::Deserialize, validator::Validate)]
+pub struct PasswordParamsCommand {
+    #[validate(length(min = 1, message = "Email is required"))]
+    pub email: String,
+    /// Tenant of the request (from `TenantContext`, never the body)
+    #[serde(skip)]
+    pub tenant_id: Option<OrganizationId>,
+}
+
+/// How a client must send a user's password
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum PasswordParams {
+    /// As typed
+    Plaintext,
+    /// Pre-hashed with these parameters
+    ClientHash(ClientHashParams),
+}
+
+/// Command completing an API login with a TOTP code
+#[derive(Debug, serde::Deserialize, validator::Validate)]
+pub struct VerifyMfaCommand {
+    #[validate(length(min = 1, message = "MFA token is required"))]
+    pub mfa_token: String,
+    #[validate(length(equal = 6, message = "Code must be 6 digits"))]
+    pub code: String,
+}
+
+/// Command completing a web login with a TOTP code
+///
+/// The session options are sent again with the code: nothing but the
+/// user and tenant is kept with the challenge.
+#[derive(Debug, serde::Deserialize, validator::Validate)]
+pub struct VerifyWebMfaCommand {
+    #[validate(length(min = 1, message = "MFA token is required"))]
+    pub mfa_token: String,
+    #[validate(length(equal = 6, message = "Code must be 6 digits"))]
+    pub code: String,
+    #[serde(default)]
+    pub device_name: Option<String>,
+    #[serde(default)]
+    pub remember_me: bool,
+    #[serde(skip)]
+    pub ip_address: Option<String>,
+    #[serde(skip)]
+    pub user_agent: Option<String>,
 }
 
 /// Login result for web authentication
@@ -25,26 +118,112 @@ pub struct WebLoginResult {
     pub session: Session,
 }
 
+/// Outcome of a web login attempt with valid credentials
+pub enum WebLoginOutcome {
+    /// The session was created
+    LoggedIn(Box<WebLoginResult>),
+    /// The user has 2FA enabled; the session is created once the challenge
+    /// is completed with a TOTP code (`verify_mfa_web`)
+    MfaRequired { mfa_token: String, expires_in: i64 },
+}
+
 /// Login result for API authentication
 pub struct ApiLoginResult {
     pub user: UserDto,
     pub token_pair: TokenPair,
+    pub tenant: Option<OrganizationDto>,
+}
+
+/// Outcome of an API login attempt with valid credentials
+pub enum ApiLoginOutcome {
+    /// Tokens were minted (for the resolved tenant, if any)
+    LoggedIn(Box<ApiLoginResult>),
+    /// The user belongs to several tenants and must pick one
+    TenantSelectionRequired { tenants: Vec<OrganizationDto> },
+    /// The user has 2FA enabled; tokens are minted once the challenge is
+    /// completed with a TOTP code (`verify_mfa`)
+    MfaRequired { mfa_token: String, expires_in: i64 },
 }
 
 /// Configuration for authentication
 pub struct AuthConfig {
     pub session_ttl_seconds: i64,
+    /// Session TTL for "remember me" logins
+    pub session_remember_ttl_seconds: i64,
+    /// Bounds both session TTLs are clamped to at login
+    pub session_min_ttl_seconds: i64,
+    pub session_max_ttl_seconds: i64,
     pub jwt_access_ttl_seconds: i64,
     pub jwt_refresh_ttl_seconds: i64,
+    pub claims_format: ClaimsFormat,
+    /// Reject logins for accounts that have not verified their email
+    pub require_verified_email: bool,
+    /// Longest accepted password; longer input is rejected before verifying
+    pub max_password_length: usize,
+    /// Time allowed between password and TOTP code at API login
+    pub mfa_challenge_ttl_seconds: i64,
+    /// Longest accepted session device name, in characters
+    pub device_name_max_length: usize,
+    /// Drop zero-width characters from the login email
+    /// (see `Email::from_login_input`)
+    pub strip_zero_width_identifier: bool,
+    /// Client-side password hashing, if enabled
+    pub client_hashing: Option<ClientHashing>,
+    /// Hasher outdated password hashes are upgraded to at login
+    pub password_hasher: PasswordHasher,
 }
 
 impl Default for AuthConfig {
     fn default() -> Self {
         Self {
             session_ttl_seconds: 86400,      // 24 hours
+            session_remember_ttl_seconds: 2592000, // 30 days
+            session_min_ttl_seconds: 300,    // 5 minutes
+            session_max_ttl_seconds: 2592000, // 30 days
             jwt_access_ttl_seconds: 900,     // 15 minutes
             jwt_refresh_ttl_seconds: 604800, // 7 days
+            claims_format: ClaimsFormat::Verbose,
+            require_verified_email: false,
+            max_password_length: PasswordHash::DEFAULT_MAX_LENGTH,
+            mfa_challenge_ttl_seconds: 300,  // 5 minutes
+            device_name_max_length: 64,
+            strip_zero_width_identifier: true,
+            client_hashing: None,
+            password_hasher: PasswordHasher::default(),
+        }
+    }
+}
+
+impl AuthConfig {
+    /// Session TTL used at login, clamped to the configured bounds
+    ///
+    /// `remember_me` selects the long-lived TTL. A non-positive TTL would
+    /// create already-expired sessions, so it is rejected rather than clamped.
+    pub fn effective_session_ttl(&self, remember_me: bool) -> AppResult<i64> {
+        let configured = if remember_me {
+            self.session_remember_ttl_seconds
+        } else {
+            self.session_ttl_seconds
+        };
+        if configured <= 0 {
+            return Err(AppError::Config(format!(
+                "Session TTL must be positive, got {} seconds",
+                configured
+            )));
         }
+
+        let ttl = configured.clamp(self.session_min_ttl_seconds, self.session_max_ttl_seconds);
+        if ttl != configured {
+            tracing::warn!(
+                "Session TTL {}s is outside [{}, {}], using {}s",
+                configured,
+                self.session_min_ttl_seconds,
+                self.session_max_ttl_seconds,
+                ttl
+            );
+        }
+
+        Ok(ttl)
     }
 }
 
@@ -57,145 +236,1684 @@ pub struct LoginUserUseCase {
     user_repo: Arc<dyn UserRepository>,
     session_repo: Arc<dyn SessionRepository>,
     token_repo: Arc<dyn TokenRepository>,
-    jwt_secret: String,
+    login_attempt_repo: Arc<dyn LoginAttemptRepository>,
+    membership_repo: Arc<dyn MembershipRepository>,
+    totp_repo: Arc<dyn TotpRepository>,
+    mfa_challenge_repo: Arc<dyn MfaChallengeRepository>,
+    role_repo: Arc<dyn RoleRepository>,
+    jwt_keys: Arc<JwtKeys>,
+    audit_log: Arc<AuditLog>,
     config: AuthConfig,
+    notifier: Option<SecurityNotifier>,
+    lockout: Option<Arc<AccountLockout>>,
 }
 
 impl LoginUserUseCase {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         user_repo: Arc<dyn UserRepository>,
         session_repo: Arc<dyn SessionRepository>,
         token_repo: Arc<dyn TokenRepository>,
-        jwt_secret: String,
+        login_attempt_repo: Arc<dyn LoginAttemptRepository>,
+        membership_repo: Arc<dyn MembershipRepository>,
+        totp_repo: Arc<dyn TotpRepository>,
+        mfa_challenge_repo: Arc<dyn MfaChallengeRepository>,
+        role_repo: Arc<dyn RoleRepository>,
+        jwt_keys: Arc<JwtKeys>,
+        audit_log: Arc<AuditLog>,
         config: AuthConfig,
     ) -> Self {
         Self {
             user_repo,
             session_repo,
             token_repo,
-            jwt_secret,
+            login_attempt_repo,
+            membership_repo,
+            totp_repo,
+            mfa_challenge_repo,
+            role_repo,
+            jwt_keys,
+            audit_log,
             config,
+            notifier: None,
+            lockout: None,
+        }
+    }
+
+    /// Email users about logins from addresses they never logged in from
+    pub fn with_notifier(mut self, notifier: SecurityNotifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Lock accounts after repeated failed logins
+    pub fn with_lockout(mut self, lockout: Option<Arc<AccountLockout>>) -> Self {
+        self.lockout = lockout;
+        self
+    }
+
+    /// Verify credentials shared by web and API login
+    ///
+    /// Business Logic:
+    /// 1. Find user by email, normalized per `Email::from_login_input` (the
+    ///    password is used exactly as given; oversized ones are rejected
+    ///    first); with
+    ///    a tenant, that tenant's users are searched before global users, so
+    ///    another tenant's user can never match
+    /// 2. Refuse locked accounts without checking the password; otherwise
+    ///    verify it, or its client hash when `client_hash` is given
+    ///    (failures are recorded for the security summary and count toward
+    ///    the lockout)
+    /// 3. Check the account status allows login (active, and email verified
+    ///    when enforcement is enabled)
+    /// 4. With client-side hashing enabled, migrate a plaintext-based
+    ///    account to it; otherwise re-hash an outdated password hash
+    ///    (other algorithm or cost than `password_hasher`)
+    async fn authenticate(
+        &self,
+        email: &str,
+        password: &str,
+        client_hash: Option<&ClientHashParams>,
+        ip_address: Option<String>,
+        tenant_id: Option<OrganizationId>,
+    ) -> AppResult<User> {
+        // 1. Find user by email
+        PasswordHash::ensure_max_length(password, self.config.max_password_length)?;
+        let hashing = self.config.client_hashing.as_ref();
+        if client_hash.is_some() && hashing.is_none() {
+            return Err(AppError::validation("Client-side password hashing is not enabled"));
+        }
+        let email = Email::from_login_input(email, self.config.strip_zero_width_identifier)?;
+        let Some(mut user) = self.find_login_user(&email, tenant_id).await? else {
+            // Unknown emails answer parameter mismatches like accounts do
+            if let (Some(params), Some(hashing)) = (client_hash, hashing) {
+                if *params != hashing.params_for(&email) {
+                    return Err(AppError::client_hash_mismatch(
+                        "Fetch the password parameters again and re-hash",
+                    ));
+                }
+            }
+            return Err(AppError::authentication("Invalid email or password"));
+        };
+
+        // 2. Verify password; a lock must stop guessing, not only logging in
+        if user.status() == AccountStatus::Locked {
+            return Err(AccountStatus::Locked.login_error());
+        }
+        let password_valid = match client_hash {
+            Some(params) => user.verify_client_hash(password, params)?,
+            None => user.verify_password(password)?,
+        };
+        if !password_valid {
+            self.record_attempt(&user, false, ip_address).await;
+            if let Some(lockout) = &self.lockout {
+                lockout.after_failure(&user).await;
+            }
+            return Err(AppError::authentication("Invalid email or password"));
+        }
+
+        // 3. Check account status
+        if let Some(status) = user.login_blocker(self.config.require_verified_email) {
+            return Err(status.login_error());
+        }
+        // Flagged by the outdated hash job: the password must be reset
+        if user.must_change_password {
+            return Err(AppError::password_change_required(
+                "Your password must be changed; reset it to log in again",
+            ));
+        }
+
+        // Before recording the attempt, which makes the address known
+        self.notify_new_device(&user, ip_address.as_deref()).await;
+        self.record_attempt(&user, true, ip_address).await;
+
+        // 4. The plain password is at hand only now; keep the login if
+        //    re-hashing fails, the next one retries
+        let hasher = &self.config.password_hasher;
+        let rehashed = match (hashing, user.client_hash_params()) {
+            (Some(hashing), None) => {
+                let params = hashing.params_for(&user.email);
+                user.migrate_to_client_hash(password, params, hasher).map(|()| true)
+            }
+            (_, Some(params)) if client_hash.is_none() => {
+                user.upgrade_password_hash(&params.derive(password), hasher)
+            }
+            _ => user.upgrade_password_hash(password, hasher),
+        };
+        let saved = match rehashed {
+            Ok(true) => self.user_repo.update(&user).await.map(Some),
+            Ok(false) => Ok(None),
+            Err(e) => Err(e),
+        };
+        match saved {
+            Ok(Some(updated)) => user = updated,
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to re-hash password of user {}: {}", user.id, e),
+        }
+
+        Ok(user)
+    }
+
+    /// Find the user logging in by email
+    ///
+    /// With a tenant, that tenant's users are searched before global users.
+    async fn find_login_user(
+        &self,
+        email: &Email,
+        tenant_id: Option<OrganizationId>,
+    ) -> AppResult<Option<User>> {
+        if let Some(tenant_id) = tenant_id {
+            if let Some(user) = self.user_repo.find_by_email_in_tenant(email, tenant_id).await? {
+                return Ok(Some(user));
+            }
+        }
+
+        self.user_repo.find_by_email(email).await
+    }
+
+    /// How the client must send the password of `cmd.email`
+    ///
+    /// Plaintext unless client-side hashing is enabled. Then accounts get
+    /// their stored parameters, and unknown emails the ones they would be
+    /// registered with, so the answer doesn't reveal whether an account
+    /// exists. Plaintext-based accounts (not migrated yet) send the
+    /// password as typed once, to be migrated at that login.
+    pub async fn password_params(&self, cmd: PasswordParamsCommand) -> AppResult<PasswordParams> {
+        let Some(hashing) = &self.config.client_hashing else {
+            return Ok(PasswordParams::Plaintext);
+        };
+
+        let email = Email::from_login_input(&cmd.email, self.config.strip_zero_width_identifier)?;
+        let params = match self.find_login_user(&email, cmd.tenant_id).await? {
+            Some(user) => user.client_hash_params(),
+            None => Some(hashing.params_for(&email)),
+        };
+
+        Ok(params.map_or(PasswordParams::Plaintext, PasswordParams::ClientHash))
+    }
+
+    /// Record a login attempt (and audit it) without failing the login if
+    /// recording fails
+    async fn record_attempt(&self, user: &User, succeeded: bool, ip_address: Option<String>) {
+        let action = if succeeded {
+            AuditAction::LoginSucceeded
+        } else {
+            AuditAction::LoginFailed
+        };
+        let event = AuditEvent::new(action, Some(user.id))
+            .with_tenant(user.tenant_id)
+            .with_ip_address(ip_address.clone());
+
+        if let Err(e) = self.login_attempt_repo.record(user.id, succeeded, ip_address).await {
+            tracing::warn!("Failed to record login attempt for user {}: {}", user.id, e);
+        }
+        self.audit_log.record(event).await;
+    }
+
+    /// Email the user about a login from an address they never logged in
+    /// from, without failing the login if that fails
+    async fn notify_new_device(&self, user: &User, ip_address: Option<&str>) {
+        let (Some(notifier), Some(ip_address)) = (&self.notifier, ip_address) else {
+            return;
+        };
+        // Spare the lookup when the email would be skipped anyway
+        if !user.notification_preferences.allows(NotificationCategory::NewDeviceLogin) {
+            return;
+        }
+
+        match self.login_attempt_repo.is_new_ip(user.id, ip_address).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                tracing::warn!("Failed to check login address of user {}: {}", user.id, e);
+                return;
+            }
+        }
+
+        let body = format!(
+            "Hi {},\n\n\
+             Your password was just used to sign in from a new device \
+             (IP address {}).\n\n\
+             If this was you, you can ignore this email. If not, change your \
+             password right away.\n",
+            user.name, ip_address
+        );
+        let sent = notifier
+            .notify(user, NotificationCategory::NewDeviceLogin, "New sign-in to your account", &body)
+            .await;
+        if let Err(e) = sent {
+            tracing::error!("Failed to send new device email to user {}: {}", user.id, e);
+        }
+    }
+
+    /// Reject the login if one of `tenants` requires 2FA the user lacks
+    ///
+    /// There is no enrollment step in the login flow, so the client gets
+    /// `TWO_FACTOR_SETUP_REQUIRED` and must send the user to set it up.
+    pub(crate) fn ensure_two_factor<'a>(
+        user: &User,
+        tenants: impl IntoIterator<Item = &'a Organization>,
+    ) -> AppResult<()> {
+        if user.two_factor_enabled {
+            return Ok(());
+        }
+
+        match tenants.into_iter().find(|t| t.requires_two_factor(user.id)) {
+            Some(tenant) => Err(AppError::two_factor_setup_required(format!(
+                "{} requires two-factor authentication; set it up before logging in",
+                tenant.name
+            ))),
+            None => Ok(()),
         }
     }
 
     /// Login for web (session-based authentication)
     ///
     /// Business Logic:
-    /// 1. Find user by email
-    /// 2. Verify password
-    /// 3. Check user is active
-    /// 4. Delete existing session (single session per user)
-    /// 5. Create new session
-    /// 6. Return session
+    /// 1-3. Authenticate credentials (see `authenticate`)
+    /// 4. Check membership of the request's tenant, if any, and enforce 2FA
+    ///    policies (sessions are not tenant-bound, so every tenant the user
+    ///    belongs to applies)
+    /// 5. With 2FA enabled, issue an MFA challenge instead of a session
+    /// 6. Create new session (TTL clamped to the configured bounds); the
+    ///    user's oldest sessions beyond the concurrent limit are evicted
     ///
     /// # Arguments
     /// * `cmd` - Command containing email, password, and client info
     ///
     /// # Returns
-    /// WebLoginResult with user and session
+    /// WebLoginOutcome with user and session, or the MFA challenge to complete
     ///
     /// # Errors
     /// - Authentication error if credentials invalid
     /// - Authentication error if user inactive
-    pub async fn login_web(&self, cmd: LoginWebCommand) -> AppResult<WebLoginResult> {
-        // 1. Find user by email
-        let email = Email::new(&cmd.email)?;
-        let user = self.user_repo.find_by_email(&email)
-            .await?
-            .ok_or_else(|| AppError::authentication("Invalid email or password"))?;
+    /// - PasswordChangeRequired if the user was flagged for an outdated hash
+    /// - Authorization error if the user is not a member of the request's tenant
+    /// - TwoFactorSetupRequired if a tenant requires 2FA the user lacks
+    /// - Config error if the configured session TTL is not positive
+    /// - Validation error if the device name is too long or malformed
+    pub async fn login_web(&self, cmd: LoginWebCommand) -> AppResult<WebLoginOutcome> {
+        // Reject a bad device name before touching the credentials
+        let device_name =
+            Session::normalize_device_name(cmd.device_name, self.config.device_name_max_length)?;
 
-        // 2. Verify password
-        let password_valid = user.verify_password(&cmd.password)?;
-        if !password_valid {
-            return Err(AppError::authentication("Invalid email or password"));
+        // 1-3. Authenticate credentials
+        let user = self
+            .authenticate(&cmd.email, &cmd.password, None, cmd.ip_address.clone(), cmd.tenant_id)
+            .await?;
+
+        // 4. Tenant membership and 2FA policies
+        let tenants = self.membership_repo.list_organizations_for_user(user.id).await?;
+        if let Some(tenant_id) = cmd.tenant_id {
+            if !tenants.iter().any(|t| t.id == tenant_id) {
+                return Err(not_a_member());
+            }
         }
+        Self::ensure_two_factor(&user, &tenants)?;
+
+        let ttl_seconds = self.config.effective_session_ttl(cmd.remember_me)?;
 
-        // 3. Check user is active
-        if !user.can_login() {
-            return Err(AppError::authentication("Account is not active"));
+        // 5. Second factor
+        if user.two_factor_enabled {
+            let mfa_token = self.issue_mfa_challenge(&user, cmd.tenant_id).await?;
+            return Ok(WebLoginOutcome::MfaRequired {
+                mfa_token,
+                expires_in: self.config.mfa_challenge_ttl_seconds,
+            });
         }
 
-        // 4. Delete existing sessions (single session per user)
-        self.session_repo.delete_by_user_id(user.id).await?;
+        // 6. Create new session
+        let result = self
+            .create_session(user, cmd.ip_address, cmd.user_agent, device_name, ttl_seconds)
+            .await?;
+        Ok(WebLoginOutcome::LoggedIn(Box::new(result)))
+    }
 
-        // 5. Create new session
-        let session = Session::new(
-            user.id,
-            cmd.ip_address,
-            cmd.user_agent,
-            self.config.session_ttl_seconds,
-        );
+    /// Complete a web login with a TOTP code
+    ///
+    /// Checks the code as `verify_mfa` does, then creates the session as
+    /// `login_web` would have.
+    ///
+    /// # Errors
+    /// - Validation error if the device name is too long or malformed
+    /// - Authentication error if the challenge is invalid or the code wrong
+    /// - Authentication error if the user was deactivated meanwhile
+    /// - Authorization error if the user left the tenant meanwhile
+    pub async fn verify_mfa_web(&self, cmd: VerifyWebMfaCommand) -> AppResult<WebLoginResult> {
+        let device_name =
+            Session::normalize_device_name(cmd.device_name, self.config.device_name_max_length)?;
+        let ttl_seconds = self.config.effective_session_ttl(cmd.remember_me)?;
+
+        let (user, _tenant) = self.complete_mfa_challenge(&cmd.mfa_token, &cmd.code).await?;
+
+        self.create_session(user, cmd.ip_address, cmd.user_agent, device_name, ttl_seconds)
+            .await
+    }
+
+    /// Create a session for `user` (the repository evicts the oldest ones
+    /// beyond `SESSION_MAX_CONCURRENT`)
+    async fn create_session(
+        &self,
+        user: User,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+        device_name: Option<String>,
+        ttl_seconds: i64,
+    ) -> AppResult<WebLoginResult> {
+        let session = Session::new(user.id, ip_address, user_agent, ttl_seconds)
+            .with_device_name(device_name);
 
         let saved_session = self.session_repo.save(&session).await?;
 
-        // 6. Return result
         Ok(WebLoginResult {
             user: UserDto::from(user),
             session: saved_session,
         })
     }
 
+    /// Save an MFA challenge for `user` and return its plain token
+    async fn issue_mfa_challenge(
+        &self,
+        user: &User,
+        tenant_id: Option<OrganizationId>,
+    ) -> AppResult<String> {
+        let (challenge, mfa_token) =
+            MfaChallenge::issue(user.id, tenant_id, self.config.mfa_challenge_ttl_seconds);
+        self.mfa_challenge_repo.save(&challenge).await?;
+        Ok(mfa_token)
+    }
+
+    /// Check a TOTP code against the user's confirmed secret
+    ///
+    /// A code is accepted once; a wrong code is audited.
+    async fn verify_totp_code(
+        &self,
+        user: &User,
+        tenant_id: Option<OrganizationId>,
+        code: &str,
+    ) -> AppResult<bool> {
+        let Some(totp) = self
+            .totp_repo
+            .find_by_user_id(user.id)
+            .await?
+            .filter(|t| t.is_confirmed())
+        else {
+            return Ok(false);
+        };
+
+        let accepted = match totp.secret()?.verify(code) {
+            Some(step) => self.totp_repo.record_used_step(user.id, step).await?,
+            None => false,
+        };
+        if !accepted {
+            self.audit_log
+                .record(AuditEvent::new(AuditAction::MfaFailed, Some(user.id)).with_tenant(tenant_id))
+                .await;
+        }
+        Ok(accepted)
+    }
+
     /// Login for API (JWT-based authentication)
     ///
     /// Business Logic:
-    /// 1. Find user by email
-    /// 2. Verify password
-    /// 3. Check user is active
-    /// 4. Generate TokenPair (access + refresh)
-    /// 5. Save JwtTokens to repository (for revocation tracking)
-    /// 6. Return TokenPair
+    /// 1-3. Authenticate credentials (see `authenticate`)
+    /// 4. Resolve tenant (see `resolve_tenant`)
+    /// 5. Enforce the tenant's 2FA policy
+    /// 6. With 2FA enabled, issue an MFA challenge instead of tokens
+    /// 7. Generate TokenPair (access + refresh) for the tenant
+    /// 8. Save JwtTokens to repository (for revocation tracking)
+    /// 9. Return TokenPair
     ///
     /// # Arguments
-    /// * `cmd` - Command containing email and password
+    /// * `cmd` - Command containing email, password, and optional tenant
     ///
     /// # Returns
-    /// ApiLoginResult with user and token pair
+    /// ApiLoginOutcome with user and token pair, the tenants to choose
+    /// from, or the MFA challenge to complete
     ///
     /// # Errors
     /// - Authentication error if credentials invalid
     /// - Authentication error if user inactive
-    pub async fn login_api(&self, cmd: LoginApiCommand) -> AppResult<ApiLoginResult> {
-        // 1. Find user by email
-        let email = Email::new(&cmd.email)?;
-        let user = self.user_repo.find_by_email(&email)
+    /// - PasswordChangeRequired if the user was flagged for an outdated hash
+    /// - Authorization error if the user is not a member of the chosen tenant
+    /// - TwoFactorSetupRequired if the tenant requires 2FA the user lacks
+    pub async fn login_api(&self, cmd: LoginApiCommand) -> AppResult<ApiLoginOutcome> {
+        // 1-3. Authenticate credentials
+        let user = self
+            .authenticate(
+                &cmd.email,
+                &cmd.password,
+                cmd.client_hash.as_ref(),
+                cmd.ip_address.clone(),
+                cmd.tenant_id,
+            )
+            .await?;
+
+        // 4. Resolve tenant
+        let tenant = match self
+            .resolve_tenant(&user, cmd.tenant_id, cmd.tenant_slug.as_deref())
             .await?
-            .ok_or_else(|| AppError::authentication("Invalid email or password"))?;
+        {
+            TenantResolution::Resolved(tenant) => tenant,
+            TenantResolution::Ambiguous(tenants) => {
+                return Ok(ApiLoginOutcome::TenantSelectionRequired {
+                    tenants: tenants.into_iter().map(OrganizationDto::from).collect(),
+                });
+            }
+        };
 
-        // 2. Verify password
-        let password_valid = user.verify_password(&cmd.password)?;
-        if !password_valid {
-            return Err(AppError::authentication("Invalid email or password"));
+        // 5. Enforce the tenant's 2FA policy
+        Self::ensure_two_factor(&user, &tenant)?;
+
+        // 6. Second factor
+        if user.two_factor_enabled {
+            let mfa_token = self.issue_mfa_challenge(&user, tenant.as_ref().map(|t| t.id)).await?;
+            return Ok(ApiLoginOutcome::MfaRequired {
+                mfa_token,
+                expires_in: self.config.mfa_challenge_ttl_seconds,
+            });
+        }
+
+        // 7-9. Generate and save tokens
+        Ok(ApiLoginOutcome::LoggedIn(Box::new(self.issue_tokens(user, tenant, None).await?)))
+    }
+
+    /// Complete an API login with a TOTP code
+    ///
+    /// Business Logic:
+    /// 1. Find the challenge (pending, unexpired, under the failure limit)
+    /// 2. Verify the code against the user's confirmed secret; a code is
+    ///    accepted once, and wrong codes count against the challenge
+    /// 3. Complete the challenge (single use)
+    /// 4. Re-check the account and the tenant membership resolved at login
+    /// 5. Generate and save tokens for that tenant
+    ///
+    /// # Errors
+    /// - Authentication error if the challenge is invalid or the code wrong
+    /// - Authentication error if the user was deactivated meanwhile
+    /// - Authorization error if the user left the tenant meanwhile
+    pub async fn verify_mfa(&self, cmd: VerifyMfaCommand) -> AppResult<ApiLoginResult> {
+        // 1-4. Check the code and re-check account and tenant
+        let (user, tenant) = self.complete_mfa_challenge(&cmd.mfa_token, &cmd.code).await?;
+
+        // 5. Generate and save tokens
+        self.issue_tokens(user, tenant, None).await
+    }
+
+    /// Steps 1-4 of `verify_mfa`, shared with `verify_mfa_web`
+    async fn complete_mfa_challenge(
+        &self,
+        mfa_token: &str,
+        code: &str,
+    ) -> AppResult<(User, Option<Organization>)> {
+        // 1. Find the challenge
+        let challenge = self
+            .mfa_challenge_repo
+            .find_by_hash(&MfaChallenge::hash(mfa_token))
+            .await?
+            .filter(|c| c.is_usable())
+            .ok_or_else(invalid_challenge)?;
+
+        // 2. Verify the code
+        let user = self
+            .user_repo
+            .find_by_id(challenge.user_id)
+            .await?
+            .ok_or_else(invalid_challenge)?;
+        if !self.verify_totp_code(&user, challenge.tenant_id, code).await? {
+            self.mfa_challenge_repo.record_failure(challenge.id).await?;
+            return Err(AppError::authentication("Invalid authentication code"));
+        }
+
+        // 3. Complete the challenge
+        if !self.mfa_challenge_repo.mark_used(challenge.id).await? {
+            return Err(invalid_challenge());
+        }
+
+        // 4. Re-check account and tenant
+        if let Some(status) = user.login_blocker(self.config.require_verified_email) {
+            return Err(status.login_error());
+        }
+
+        let tenant = match challenge.tenant_id {
+            Some(tenant_id) => Some(
+                self.membership_repo
+                    .list_organizations_for_user(user.id)
+                    .await?
+                    .into_iter()
+                    .find(|t| t.id == tenant_id)
+                    .ok_or_else(not_a_member)?,
+            ),
+            None => None,
+        };
+
+        Ok((user, tenant))
+    }
+
+    /// Mint API tokens for a signed-in web session
+    ///
+    /// The tokens carry the session as their `sid` claim (kept on refresh)
+    /// and its creation as `auth_time`, so revoking the session revokes
+    /// them too. The tenant is resolved, and its 2FA policy enforced, as at
+    /// login. A user with 2FA enabled must send a current TOTP `code`: the
+    /// session may predate enrollment.
+    ///
+    /// # Errors
+    /// - Authentication error if the user was deactivated meanwhile
+    /// - Authentication error if the TOTP code is missing or wrong
+    /// - Authorization error if the user is not a member of the chosen tenant
+    /// - TwoFactorSetupRequired if the tenant requires 2FA the user lacks
+    pub async fn exchange_session(
+        &self,
+        session: &Session,
+        tenant_id: Option<OrganizationId>,
+        tenant_slug: Option<&str>,
+        code: Option<&str>,
+    ) -> AppResult<ApiLoginOutcome> {
+        let user = self
+            .user_repo
+            .find_by_id(session.user_id)
+            .await?
+            .ok_or_else(|| AppError::authentication("Session user not found"))?;
+        if let Some(status) = user.login_blocker(self.config.require_verified_email) {
+            return Err(status.login_error());
         }
 
-        // 3. Check user is active
-        if !user.can_login() {
-            return Err(AppError::authentication("Account is not active"));
+        let tenant = match self.resolve_tenant(&user, tenant_id, tenant_slug).await? {
+            TenantResolution::Resolved(tenant) => tenant,
+            TenantResolution::Ambiguous(tenants) => {
+                return Ok(ApiLoginOutcome::TenantSelectionRequired {
+                    tenants: tenants.into_iter().map(OrganizationDto::from).collect(),
+                });
+            }
+        };
+
+        Self::ensure_two_factor(&user, &tenant)?;
+        if user.two_factor_enabled {
+            let code = code.ok_or_else(|| {
+                AppError::authentication("Two-factor authentication code required")
+            })?;
+            if !self.verify_totp_code(&user, tenant.as_ref().map(|t| t.id), code).await? {
+                return Err(AppError::authentication("Invalid authentication code"));
+            }
         }
 
-        // 4. Generate TokenPair
-        let (token_pair, access_token, refresh_token) = TokenPair::generate(
+        let result = self.issue_tokens(user, tenant, Some(session)).await?;
+        Ok(ApiLoginOutcome::LoggedIn(Box::new(result)))
+    }
+
+    /// Mint tokens for an existing user without checking credentials
+    ///
+    /// Development only: compiled with the `dev-tools` feature and routed
+    /// only with `DEV_MODE` on. Skips the password, account status and 2FA
+    /// checks; the tenant is resolved as at login.
+    ///
+    /// # Errors
+    /// - NotFound if no user has the email
+    /// - Authorization error if the user is not a member of the chosen tenant
+    #[cfg(feature = "dev-tools")]
+    pub async fn login_dev(&self, cmd: DevLoginCommand) -> AppResult<ApiLoginOutcome> {
+        let email = Email::new(&cmd.email)?;
+        let user = self
+            .user_repo
+            .find_by_email(&email)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        let tenant = match self.resolve_tenant(&user, None, cmd.tenant_slug.as_deref()).await? {
+            TenantResolution::Resolved(tenant) => tenant,
+            TenantResolution::Ambiguous(tenants) => {
+                return Ok(ApiLoginOutcome::TenantSelectionRequired {
+                    tenants: tenants.into_iter().map(OrganizationDto::from).collect(),
+                });
+            }
+        };
+
+        tracing::warn!("Minting development tokens for user {} without a password", user.id);
+        Ok(ApiLoginOutcome::LoggedIn(Box::new(self.issue_tokens(user, tenant, None).await?)))
+    }
+
+    /// Mint a token pair for `user` in `tenant` and save it for revocation
+    ///
+    /// The user's roles are embedded, sparing the JWT middleware a lookup.
+    /// With a `session`, the tokens are linked to it (`sid`).
+    async fn issue_tokens(
+        &self,
+        user: User,
+        tenant: Option<Organization>,
+        session: Option<&Session>,
+    ) -> AppResult<ApiLoginResult> {
+        let roles = self.role_repo.find_by_user_id(user.id).await?;
+        let (token_pair, access_token, refresh_token) = TokenPair::generate_with_auth_time(
             user.id,
-            &self.jwt_secret,
+            tenant.as_ref().map(|t| t.id),
+            session.map(|s| s.created_at.timestamp()),
+            Some(&roles),
+            session.map(|s| s.id),
+            self.config.claims_format,
+            &self.jwt_keys,
             self.config.jwt_access_ttl_seconds,
             self.config.jwt_refresh_ttl_seconds,
         )?;
 
-        // 5. Save tokens to repository (for revocation tracking)
         self.token_repo.save(&access_token).await?;
         self.token_repo.save(&refresh_token).await?;
 
-        // 6. Return result
         Ok(ApiLoginResult {
             user: UserDto::from(user),
             token_pair,
+            tenant: tenant.map(OrganizationDto::from),
         })
     }
+
+    /// Decide which tenant the tokens are minted for
+    ///
+    /// - Request tenant (`TenantContext`): user must be a member of it
+    /// - Explicit slug: user must be a member of that tenant
+    /// - No slug, no memberships: no tenant
+    /// - No slug, one membership: that tenant
+    /// - No slug, several memberships: ambiguous, the client must choose
+    async fn resolve_tenant(
+        &self,
+        user: &User,
+        tenant_id: Option<OrganizationId>,
+        tenant_slug: Option<&str>,
+    ) -> AppResult<TenantResolution> {
+        let mut tenants = self.membership_repo.list_organizations_for_user(user.id).await?;
+
+        if let Some(tenant_id) = tenant_id {
+            return tenants
+                .into_iter()
+                .find(|t| t.id == tenant_id)
+                .map(|t| TenantResolution::Resolved(Some(t)))
+                .ok_or_else(not_a_member);
+        }
+
+        if let Some(slug) = tenant_slug {
+            let slug = slug.trim().to_lowercase();
+            return tenants
+                .into_iter()
+                .find(|t| t.slug == slug)
+                .map(|t| TenantResolution::Resolved(Some(t)))
+                .ok_or_else(not_a_member);
+        }
+
+        match tenants.len() {
+            0 => Ok(TenantResolution::Resolved(None)),
+            1 => Ok(TenantResolution::Resolved(tenants.pop())),
+            _ => Ok(TenantResolution::Ambiguous(tenants)),
+        }
+    }
+}
+
+fn not_a_member() -> AppError {
+    AppError::authorization("You are not a member of this organization")
+}
+
+fn invalid_challenge() -> AppError {
+    AppError::authentication("Invalid or expired MFA challenge")
+}
+
+/// Result of tenant resolution during API login
+enum TenantResolution {
+    Resolved(Option<Organization>),
+    Ambiguous(Vec<Organization>),
 }
 
 #[cfg(test)]
 mod tests {
-    
+    use super::*;
+    use crate::bootstrap::BackgroundTasks;
+    use crate::moduls::audit::infra::in_memory::CapturingAuditSink;
+    use crate::moduls::auth::application::{GetCurrentUserUseCase, LockoutPolicy};
+    use crate::moduls::auth::domain::{LoginSecuritySummary, Role, TotpSecret, UserTotp};
+    use crate::moduls::auth::infra::in_memory::*;
+    use crate::moduls::organization::domain::{TenantMembership, TwoFactorPolicy};
+    use crate::moduls::organization::infra::in_memory::{
+        InMemoryMembershipRepository, InMemoryOrganizationRepository,
+    };
+    use crate::moduls::organization::infra::OrganizationRepository;
+
+    struct Fixture {
+        login: LoginUserUseCase,
+        current_user: GetCurrentUserUseCase,
+        user_id: crate::shared::types::UserId,
+        user_repo: Arc<InMemoryUserRepository>,
+        login_attempt_repo: Arc<InMemoryLoginAttemptRepository>,
+        org_repo: Arc<InMemoryOrganizationRepository>,
+        membership_repo: Arc<InMemoryMembershipRepository>,
+        totp_repo: Arc<InMemoryTotpRepository>,
+        role_repo: Arc<InMemoryRoleRepository>,
+        audit_sink: Arc<CapturingAuditSink>,
+    }
+
+    impl Fixture {
+        /// Enable 2FA for the fixture user with a confirmed secret
+        async fn enroll_totp(&self) -> TotpSecret {
+            let secret = TotpSecret::generate();
+            self.totp_repo.save_pending(&UserTotp::pending(self.user_id, &secret)).await.unwrap();
+            self.totp_repo.confirm(self.user_id, 0).await.unwrap();
+
+            let mut user = self.user_repo.find_by_id(self.user_id).await.unwrap().unwrap();
+            user.enable_two_factor();
+            self.user_repo.update(&user).await.unwrap();
+            secret
+        }
+
+        /// Create an organization and add the fixture user to it
+        async fn join(&self, slug: &str) -> Organization {
+            let org = Organization::new(slug.to_string(), slug).unwrap();
+            self.org_repo.save(&org).await.unwrap();
+            self.membership_repo
+                .add(&TenantMembership::new(org.id, self.user_id))
+                .await
+                .unwrap();
+            org
+        }
+
+        /// Join an organization with the given 2FA policy, optionally as its owner
+        async fn join_with_policy(&self, slug: &str, policy: TwoFactorPolicy, owner: bool) {
+            let mut org = self.join(slug).await;
+            if owner {
+                org.owner_id = Some(self.user_id);
+            }
+            org.set_two_factor_policy(policy);
+            self.org_repo.update(&org).await.unwrap();
+        }
+
+        /// Create an organization with its own `test@example.com` user
+        async fn tenant_user(&self, slug: &str, password: &str) -> Organization {
+            let org = Organization::new(slug.to_string(), slug).unwrap();
+            self.org_repo.save(&org).await.unwrap();
+
+            let email = Email::new("test@example.com").unwrap();
+            let mut user = User::new(email, password, "Tenant User".to_string()).unwrap();
+            user.tenant_id = Some(org.id);
+            self.user_repo.save(&user).await.unwrap();
+            self.membership_repo
+                .add(&TenantMembership::new(org.id, user.id))
+                .await
+                .unwrap();
+            org
+        }
+    }
+
+    fn fixture() -> Fixture {
+        fixture_with(AuthConfig::default(), false)
+    }
+
+    fn fixture_with(config: AuthConfig, email_verified: bool) -> Fixture {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        if email_verified {
+            user.verify_email();
+        }
+        let user_id = user.id;
+
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(user));
+        let login_attempt_repo = Arc::new(InMemoryLoginAttemptRepository::default());
+        let org_repo = Arc::new(InMemoryOrganizationRepository::default());
+        let membership_repo = Arc::new(InMemoryMembershipRepository::new(org_repo.clone()));
+        let totp_repo = Arc::new(InMemoryTotpRepository::default());
+        let audit_sink = Arc::new(CapturingAuditSink::default());
+        let role_repo = Arc::new(InMemoryRoleRepository::default());
+
+        let login = LoginUserUseCase::new(
+            user_repo.clone(),
+            Arc::new(InMemorySessionRepository::default()),
+            Arc::new(InMemoryTokenRepository::default()),
+            login_attempt_repo.clone(),
+            membership_repo.clone(),
+            totp_repo.clone(),
+            Arc::new(InMemoryMfaChallengeRepository::default()),
+            role_repo.clone(),
+            Arc::new(JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars")),
+            Arc::new(AuditLog::new(audit_sink.clone(), BackgroundTasks::new())),
+            config,
+        );
+        let current_user =
+            GetCurrentUserUseCase::new(user_repo.clone(), login_attempt_repo.clone(), 3600);
+
+        Fixture {
+            login,
+            current_user,
+            user_id,
+            user_repo,
+            login_attempt_repo,
+            org_repo,
+            membership_repo,
+            totp_repo,
+            role_repo,
+            audit_sink,
+        }
+    }
+
+    fn api_command(password: &str) -> LoginApiCommand {
+        LoginApiCommand {
+            email: "test@example.com".to_string(),
+            password: password.to_string(),
+            client_hash: None,
+            tenant_slug: None,
+            tenant_id: None,
+            ip_address: None,
+        }
+    }
+
+    fn tenant_command(tenant_slug: &str) -> LoginApiCommand {
+        LoginApiCommand {
+            tenant_slug: Some(tenant_slug.to_string()),
+            ..api_command("password123")
+        }
+    }
+
+    fn logged_in(outcome: ApiLoginOutcome) -> ApiLoginResult {
+        match outcome {
+            ApiLoginOutcome::LoggedIn(result) => *result,
+            _ => panic!("expected tokens"),
+        }
+    }
+
+    fn web_session(outcome: WebLoginOutcome) -> Session {
+        match outcome {
+            WebLoginOutcome::LoggedIn(result) => result.session,
+            _ => panic!("expected a session"),
+        }
+    }
+
+    fn mfa_token(outcome: ApiLoginOutcome) -> String {
+        match outcome {
+            ApiLoginOutcome::MfaRequired { mfa_token, .. } => mfa_token,
+            _ => panic!("expected an MFA challenge"),
+        }
+    }
+
+    fn verify_mfa(mfa_token: &str, code: &str) -> VerifyMfaCommand {
+        VerifyMfaCommand {
+            mfa_token: mfa_token.to_string(),
+            code: code.to_string(),
+        }
+    }
+
+    /// A six-digit code other than `code`
+    fn wrong_code(code: &str) -> &'static str {
+        if code == "000000" {
+            "111111"
+        } else {
+            "000000"
+        }
+    }
+
+    fn token_tenant(result: &ApiLoginResult) -> Option<crate::shared::types::OrganizationId> {
+        TokenPair::decode(
+            &result.token_pair.access_token,
+            &JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars"),
+        )
+        .unwrap()
+        .tenant_id()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failed_logins_increment_security_summary() {
+        let f = fixture();
+
+        assert!(f.login.login_api(api_command("wrongpassword")).await.is_err());
+        assert!(f.login.login_api(api_command("wrongpassword")).await.is_err());
+
+        let result = f.current_user.execute(f.user_id).await.unwrap();
+        assert_eq!(result.security.recent_failed_logins, 2);
+        assert!(result.security.last_failed_at.is_some());
+    }
 
-    // Tests would require mock repositories
-    // Skipping for brevity - similar to RegisterUser tests
+    fn login_from(ip_address: &str) -> LoginApiCommand {
+        LoginApiCommand {
+            ip_address: Some(ip_address.to_string()),
+            ..api_command("password123")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_from_new_address_is_emailed() {
+        let f = fixture();
+        let mailer = Arc::new(crate::shared::mailer::MockMailer::default());
+        let login = f.login.with_notifier(SecurityNotifier::new(mailer.clone()));
+
+        // Neither the first login nor a known address is news
+        login.login_api(login_from("203.0.113.1")).await.unwrap();
+        login.login_api(login_from("203.0.113.1")).await.unwrap();
+        assert_eq!(mailer.count(), 0);
+
+        login.login_api(login_from("198.51.100.7")).await.unwrap();
+        let mail = mailer.last().unwrap();
+        assert_eq!(mail.to, "test@example.com");
+        assert!(mail.body.contains("198.51.100.7"));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_new_device_email_is_not_sent() {
+        let f = fixture();
+        let mut user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        user.notification_preferences
+            .set(NotificationCategory::NewDeviceLogin, false)
+            .unwrap();
+        f.user_repo.update(&user).await.unwrap();
+        let mailer = Arc::new(crate::shared::mailer::MockMailer::default());
+        let login = f.login.with_notifier(SecurityNotifier::new(mailer.clone()));
+
+        login.login_api(login_from("203.0.113.1")).await.unwrap();
+        login.login_api(login_from("198.51.100.7")).await.unwrap();
+
+        assert_eq!(mailer.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_login_attempts_are_audited() {
+        let f = fixture();
+
+        assert!(f.login.login_api(api_command("wrongpassword")).await.is_err());
+        assert!(f.login.login_api(api_command("password123")).await.is_ok());
+
+        assert_eq!(f.audit_sink.actions(), vec!["login_failed", "login_succeeded"]);
+        let events = f.audit_sink.events.lock().unwrap();
+        assert_eq!(events[0]["user_id"], f.user_id.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_successful_login_keeps_last_failed_timestamp() {
+        let f = fixture();
+
+        assert!(f.login.login_api(api_command("wrongpassword")).await.is_err());
+        let after_failure = f.current_user.execute(f.user_id).await.unwrap();
+        let last_failed_at = after_failure.security.last_failed_at.unwrap();
+
+        assert!(f.login.login_api(api_command("password123")).await.is_ok());
+
+        let result = f.current_user.execute(f.user_id).await.unwrap();
+        assert_eq!(result.security.recent_failed_logins, 1);
+        assert_eq!(result.security.last_failed_at, Some(last_failed_at));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_failures_lock_the_account() {
+        let f = fixture();
+        let lockout = AccountLockout::new(
+            f.user_repo.clone(),
+            f.login_attempt_repo.clone(),
+            Arc::new(AuditLog::for_tests()),
+            LockoutPolicy {
+                threshold: 2,
+                duration_seconds: 900,
+            },
+        );
+        let login = f.login.with_lockout(Some(Arc::new(lockout)));
+
+        assert!(login.login_api(api_command("wrongpassword")).await.is_err());
+        assert!(login.login_api(api_command("wrongpassword")).await.is_err());
+
+        // Even the right password is refused, and not checked or counted
+        let result = login.login_api(api_command("password123")).await;
+        assert!(matches!(result, Err(AppError::Authentication(msg)) if msg.contains("locked")));
+        let summary = f.current_user.execute(f.user_id).await.unwrap();
+        assert_eq!(summary.security.recent_failed_logins, 2);
+
+        let mut user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        user.unlock();
+        f.user_repo.update(&user).await.unwrap();
+        assert!(login.login_api(api_command("password123")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_password_rejected_without_recording_attempt() {
+        let f = fixture();
+
+        let result = f.login.login_api(api_command(&"a".repeat(100 * 1024))).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let summary = f.current_user.execute(f.user_id).await.unwrap();
+        assert_eq!(summary.security.recent_failed_logins, 0);
+    }
+
+    fn web_command() -> LoginWebCommand {
+        LoginWebCommand {
+            email: "test@example.com".to_string(),
+            password: "password123".to_string(),
+            ip_address: None,
+            user_agent: None,
+            device_name: None,
+            remember_me: false,
+            tenant_id: None,
+        }
+    }
+
+    fn session_ttl(session_ttl_seconds: i64) -> AuthConfig {
+        AuthConfig {
+            session_ttl_seconds,
+            session_min_ttl_seconds: 300,
+            session_max_ttl_seconds: 86400,
+            ..AuthConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_session_ttl_is_clamped() {
+        for (configured, expected) in [(10 * 365 * 86400, 86400), (5, 300), (3600, 3600)] {
+            let f = fixture_with(session_ttl(configured), false);
+
+            let session = f.login.login_web(web_command()).await.map(web_session).unwrap();
+
+            let ttl = (session.expires_at - session.created_at).num_seconds();
+            assert!((ttl - expected).abs() <= 1, "configured {}, got {}", configured, ttl);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_negative_session_ttl_is_rejected() {
+        for configured in [-60, 0] {
+            let f = fixture_with(session_ttl(configured), false);
+
+            let result = f.login.login_web(web_command()).await;
+
+            assert!(matches!(result, Err(AppError::Config(_))), "configured {}", configured);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remember_me_extends_session_ttl() {
+        let f = fixture();
+
+        let session = f
+            .login
+            .login_web(LoginWebCommand {
+                remember_me: true,
+                ..web_command()
+            })
+            .await
+            .map(web_session)
+            .unwrap();
+
+        let remaining = session.expires_at - chrono::Utc::now();
+        assert!(remaining > chrono::Duration::days(29), "got {}", remaining);
+
+        let session = f.login.login_web(web_command()).await.map(web_session).unwrap();
+        let remaining = session.expires_at - chrono::Utc::now();
+        assert!(remaining <= chrono::Duration::days(1), "got {}", remaining);
+    }
+
+    #[tokio::test]
+    async fn test_remember_me_ttl_is_clamped() {
+        let f = fixture_with(
+            AuthConfig {
+                session_remember_ttl_seconds: 365 * 86400,
+                ..session_ttl(3600)
+            },
+            false,
+        );
+
+        let session = f
+            .login
+            .login_web(LoginWebCommand {
+                remember_me: true,
+                ..web_command()
+            })
+            .await
+            .map(web_session)
+            .unwrap();
+
+        let ttl = (session.expires_at - session.created_at).num_seconds();
+        assert!((ttl - 86400).abs() <= 1, "got {}", ttl);
+    }
+
+    #[tokio::test]
+    async fn test_web_login_stores_device_name() {
+        let f = fixture();
+
+        let session = f
+            .login
+            .login_web(LoginWebCommand {
+                device_name: Some(" John's iPhone ".to_string()),
+                ..web_command()
+            })
+            .await
+            .map(web_session)
+            .unwrap();
+
+        assert_eq!(session.device_name.as_deref(), Some("John's iPhone"));
+    }
+
+    #[tokio::test]
+    async fn test_web_login_rejects_long_device_name() {
+        let f = fixture();
+
+        let result = f
+            .login
+            .login_web(LoginWebCommand {
+                device_name: Some("x".repeat(65)),
+                ..web_command()
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_login_trims_pasted_email() {
+        let f = fixture();
+
+        for email in [" test@example.com ", "\u{200B}test@example.com\u{FEFF}"] {
+            let cmd = LoginApiCommand {
+                email: email.to_string(),
+                ..api_command("password123")
+            };
+            assert!(f.login.login_api(cmd).await.is_ok(), "email {:?}", email);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zero_width_stripping_can_be_disabled() {
+        let f = fixture_with(
+            AuthConfig {
+                strip_zero_width_identifier: false,
+                ..AuthConfig::default()
+            },
+            false,
+        );
+
+        let cmd = LoginApiCommand {
+            email: "\u{200B}test@example.com".to_string(),
+            ..api_command("password123")
+        };
+
+        assert!(f.login.login_api(cmd).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_login_password_is_not_trimmed() {
+        let f = fixture();
+        let email = Email::new("spaces@example.com").unwrap();
+        let user = User::new(email, "  padded password ", "Spaces".to_string()).unwrap();
+        f.user_repo.save(&user).await.unwrap();
+        let login = |password: &str| LoginApiCommand {
+            email: "spaces@example.com".to_string(),
+            ..api_command(password)
+        };
+
+        assert!(f.login.login_api(login("  padded password ")).await.is_ok());
+        let result = f.login.login_api(login("padded password")).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    fn hasher_fixture(password_hasher: PasswordHasher) -> Fixture {
+        fixture_with(
+            AuthConfig {
+                password_hasher,
+                ..AuthConfig::default()
+            },
+            false,
+        )
+    }
+
+    async fn stored_hash(f: &Fixture) -> String {
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        user.password_hash.as_str().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_old_cost_hash_is_rehashed_on_login() {
+        let f = hasher_fixture(PasswordHasher::Bcrypt { cost: 4 });
+        assert!(stored_hash(&f).await.starts_with("$2b$12$"));
+
+        let result = f.login.login_api(api_command("wrong-password")).await;
+        assert!(result.is_err());
+        assert!(stored_hash(&f).await.starts_with("$2b$12$"));
+
+        f.login.login_api(api_command("password123")).await.unwrap();
+        assert!(stored_hash(&f).await.starts_with("$2b$04$"));
+        assert!(f.login.login_api(api_command("password123")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bcrypt_hash_is_upgraded_to_argon2_on_login() {
+        let f = hasher_fixture(PasswordHasher::Argon2);
+
+        f.login.login_api(api_command("password123")).await.unwrap();
+
+        assert!(stored_hash(&f).await.starts_with("$argon2id$"));
+        assert!(f.login.login_api(api_command("password123")).await.is_ok());
+    }
+
+    fn client_hashing_fixture() -> Fixture {
+        fixture_with(
+            AuthConfig {
+                client_hashing: Some(ClientHashing::new("client_hash_secret", 1000)),
+                ..AuthConfig::default()
+            },
+            false,
+        )
+    }
+
+    async fn params_of(f: &Fixture, email: &str) -> PasswordParams {
+        let cmd = PasswordParamsCommand {
+            email: email.to_string(),
+            tenant_id: None,
+        };
+        f.login.password_params(cmd).await.unwrap()
+    }
+
+    fn client_hash_command(password: &str, params: &ClientHashParams) -> LoginApiCommand {
+        LoginApiCommand {
+            client_hash: Some(params.clone()),
+            ..api_command(&params.derive(password))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_login_migrates_account_to_client_hash() {
+        let f = client_hashing_fixture();
+        assert_eq!(params_of(&f, "test@example.com").await, PasswordParams::Plaintext);
+
+        f.login.login_api(api_command("password123")).await.unwrap();
+
+        let PasswordParams::ClientHash(params) = params_of(&f, "test@example.com").await else {
+            panic!("expected the account to be migrated");
+        };
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert_eq!(user.client_hash_params(), Some(params.clone()));
+        assert!(f.login.login_api(client_hash_command("password123", &params)).await.is_ok());
+        // Clients that still send the password as typed keep working
+        assert!(f.login.login_api(api_command("password123")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_client_hash_with_mismatched_params_is_rejected() {
+        let f = client_hashing_fixture();
+        f.login.login_api(api_command("password123")).await.unwrap();
+        let PasswordParams::ClientHash(params) = params_of(&f, "test@example.com").await else {
+            panic!("expected the account to be migrated");
+        };
+        let stale = ClientHashParams::new(params.salt.clone(), params.iterations + 1);
+
+        let result = f.login.login_api(client_hash_command("password123", &stale)).await;
+        assert!(matches!(result, Err(AppError::ClientHashMismatch(_))));
+
+        let wrong = f.login.login_api(client_hash_command("wrong-password", &params)).await;
+        assert!(matches!(wrong, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_email_gets_client_hash_params() {
+        let f = client_hashing_fixture();
+
+        let PasswordParams::ClientHash(params) = params_of(&f, "nobody@example.com").await else {
+            panic!("expected client hash parameters");
+        };
+
+        let cmd = LoginApiCommand {
+            email: "nobody@example.com".to_string(),
+            ..client_hash_command("password123", &params)
+        };
+        let result = f.login.login_api(cmd).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_client_hash_is_rejected_when_disabled() {
+        let f = fixture();
+        assert_eq!(params_of(&f, "test@example.com").await, PasswordParams::Plaintext);
+        let params = ClientHashParams::new("c2FsdA".to_string(), 1000);
+
+        let result = f.login.login_api(client_hash_command("password123", &params)).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_login_embeds_roles() {
+        let f = fixture();
+        let keys = JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars");
+
+        let plain = logged_in(f.login.login_api(api_command("password123")).await.unwrap());
+        let claims = TokenPair::decode(&plain.token_pair.access_token, &keys).unwrap();
+        assert_eq!(claims.roles().unwrap(), Some(vec![]));
+
+        f.role_repo.grant(f.user_id, &Role::admin()).await.unwrap();
+        let admin = logged_in(f.login.login_api(api_command("password123")).await.unwrap());
+        let claims = TokenPair::decode(&admin.token_pair.access_token, &keys).unwrap();
+        assert_eq!(claims.roles().unwrap(), Some(vec![Role::admin()]));
+    }
+
+    #[tokio::test]
+    async fn test_no_failed_logins() {
+        let f = fixture();
+
+        assert!(f.login.login_api(api_command("password123")).await.is_ok());
+
+        let result = f.current_user.execute(f.user_id).await.unwrap();
+        assert_eq!(result.security, LoginSecuritySummary::default());
+    }
+
+    fn enforcing() -> AuthConfig {
+        AuthConfig {
+            require_verified_email: true,
+            ..AuthConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unverified_email_rejected_when_enforced() {
+        let f = fixture_with(enforcing(), false);
+
+        let result = f.login.login_api(api_command("password123")).await;
+
+        assert!(matches!(result, Err(AppError::EmailNotVerified(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unverified_email_allowed_when_not_enforced() {
+        let f = fixture_with(AuthConfig::default(), false);
+
+        assert!(f.login.login_api(api_command("password123")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_verified_email_allowed_when_enforced() {
+        let f = fixture_with(enforcing(), true);
+
+        assert!(f.login.login_api(api_command("password123")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_flagged_user_must_change_password() {
+        let f = fixture();
+        let mut user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        user.must_change_password = true;
+        f.user_repo.update(&user).await.unwrap();
+
+        let result = f.login.login_api(api_command("password123")).await;
+        assert!(matches!(result, Err(AppError::PasswordChangeRequired(_))));
+
+        // A wrong password doesn't reveal the flag
+        let result = f.login.login_api(api_command("wrongpassword")).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_password_on_unverified_account_is_authentication_error() {
+        let f = fixture_with(enforcing(), false);
+
+        let result = f.login.login_api(api_command("wrongpassword")).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_login_without_tenants_mints_untenanted_tokens() {
+        let f = fixture();
+
+        let result = logged_in(f.login.login_api(api_command("password123")).await.unwrap());
+
+        assert!(result.tenant.is_none());
+        assert_eq!(token_tenant(&result), None);
+    }
+
+    #[tokio::test]
+    async fn test_single_tenant_is_selected_automatically() {
+        let f = fixture();
+        let acme = f.join("acme").await;
+
+        let result = logged_in(f.login.login_api(api_command("password123")).await.unwrap());
+
+        assert_eq!(result.tenant.as_ref().map(|t| t.id), Some(acme.id));
+        assert_eq!(token_tenant(&result), Some(acme.id));
+    }
+
+    #[tokio::test]
+    async fn test_multiple_tenants_require_selection() {
+        let f = fixture();
+        f.join("acme").await;
+        f.join("globex").await;
+
+        let outcome = f.login.login_api(api_command("password123")).await.unwrap();
+
+        match outcome {
+            ApiLoginOutcome::TenantSelectionRequired { tenants } => {
+                let slugs: Vec<_> = tenants.iter().map(|t| t.slug.as_str()).collect();
+                assert_eq!(slugs, vec!["acme", "globex"]);
+            }
+            _ => panic!("expected tenant selection"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_explicit_tenant_choice() {
+        let f = fixture();
+        f.join("acme").await;
+        let globex = f.join("globex").await;
+
+        let result = logged_in(f.login.login_api(tenant_command("globex")).await.unwrap());
+
+        assert_eq!(token_tenant(&result), Some(globex.id));
+    }
+
+    #[tokio::test]
+    async fn test_explicit_tenant_choice_must_be_a_membership() {
+        let f = fixture();
+        f.join("acme").await;
+        let other = Organization::new("Initech".to_string(), "initech").unwrap();
+        f.org_repo.save(&other).await.unwrap();
+
+        let result = f.login.login_api(tenant_command("initech")).await;
+
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_required_two_factor_policy_blocks_login_without_2fa() {
+        let f = fixture();
+        f.join_with_policy("acme", TwoFactorPolicy::Required, false).await;
+
+        let result = f.login.login_api(api_command("password123")).await;
+        assert!(matches!(result, Err(AppError::TwoFactorSetupRequired(_))));
+
+        let result = f
+            .login
+            .login_web(LoginWebCommand {
+                email: "test@example.com".to_string(),
+                password: "password123".to_string(),
+                ip_address: None,
+                user_agent: None,
+                device_name: None,
+                remember_me: false,
+                tenant_id: None,
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::TwoFactorSetupRequired(_))));
+    }
+
+    #[tokio::test]
+    async fn test_optional_two_factor_policy_allows_login() {
+        let f = fixture();
+        f.join_with_policy("acme", TwoFactorPolicy::Optional, true).await;
+
+        let result = logged_in(f.login.login_api(api_command("password123")).await.unwrap());
+
+        assert_eq!(result.tenant.unwrap().slug, "acme");
+    }
+
+    #[tokio::test]
+    async fn test_two_factor_required_for_admins_only_applies_to_owner() {
+        let f = fixture();
+        f.join_with_policy("acme", TwoFactorPolicy::RequiredForAdmins, false).await;
+        f.join_with_policy("globex", TwoFactorPolicy::RequiredForAdmins, true).await;
+
+        assert!(f.login.login_api(tenant_command("acme")).await.is_ok());
+        assert!(matches!(
+            f.login.login_api(tenant_command("globex")).await,
+            Err(AppError::TwoFactorSetupRequired(_))
+        ));
+    }
+
+    fn in_tenant(tenant_id: crate::shared::types::OrganizationId, password: &str) -> LoginApiCommand {
+        LoginApiCommand {
+            tenant_id: Some(tenant_id),
+            ..api_command(password)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tenant_login_only_matches_that_tenants_user() {
+        let f = fixture();
+        let acme = f.tenant_user("acme", "acme-password").await;
+        let globex = f.tenant_user("globex", "globex-password").await;
+
+        let result = logged_in(f.login.login_api(in_tenant(acme.id, "acme-password")).await.unwrap());
+        assert_ne!(result.user.id, f.user_id);
+        assert_eq!(token_tenant(&result), Some(acme.id));
+
+        // Globex's user cannot authenticate through acme
+        let result = f.login.login_api(in_tenant(acme.id, "globex-password")).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+
+        let result = logged_in(f.login.login_api(in_tenant(globex.id, "globex-password")).await.unwrap());
+        assert_eq!(token_tenant(&result), Some(globex.id));
+    }
+
+    #[tokio::test]
+    async fn test_tenant_user_cannot_log_in_without_tenant() {
+        let f = fixture();
+        f.tenant_user("acme", "acme-password").await;
+
+        // Without a tenant only the global user matches
+        let result = f.login.login_api(api_command("acme-password")).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_global_user_needs_membership_of_request_tenant() {
+        let f = fixture();
+        let acme = f.join("acme").await;
+        let initech = Organization::new("Initech".to_string(), "initech").unwrap();
+        f.org_repo.save(&initech).await.unwrap();
+
+        let result = logged_in(f.login.login_api(in_tenant(acme.id, "password123")).await.unwrap());
+        assert_eq!(result.user.id, f.user_id);
+
+        let result = f.login.login_api(in_tenant(initech.id, "password123")).await;
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+
+        let result = f
+            .login
+            .login_web(LoginWebCommand {
+                tenant_id: Some(initech.id),
+                ..web_command()
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_two_factor_login_requires_code() {
+        let f = fixture();
+        let acme = f.join("acme").await;
+        let secret = f.enroll_totp().await;
+
+        let token = mfa_token(f.login.login_api(api_command("password123")).await.unwrap());
+        let result = f.login.verify_mfa(verify_mfa(&token, &secret.current_code())).await.unwrap();
+
+        assert_eq!(result.user.id, f.user_id);
+        assert_eq!(token_tenant(&result), Some(acme.id));
+    }
+
+    #[tokio::test]
+    async fn test_mfa_challenge_and_code_are_single_use() {
+        let f = fixture();
+        let secret = f.enroll_totp().await;
+        let code = secret.current_code();
+
+        let token = mfa_token(f.login.login_api(api_command("password123")).await.unwrap());
+        assert!(f.login.verify_mfa(verify_mfa(&token, &code)).await.is_ok());
+
+        // Same challenge again
+        let result = f.login.verify_mfa(verify_mfa(&token, &code)).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+
+        // Same code on a new challenge
+        let token = mfa_token(f.login.login_api(api_command("password123")).await.unwrap());
+        let result = f.login.verify_mfa(verify_mfa(&token, &code)).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_codes_exhaust_mfa_challenge() {
+        let f = fixture();
+        let secret = f.enroll_totp().await;
+        let token = mfa_token(f.login.login_api(api_command("password123")).await.unwrap());
+        let code = secret.current_code();
+
+        for _ in 0..crate::moduls::auth::domain::mfa_challenge::MAX_FAILED_ATTEMPTS {
+            let result = f.login.verify_mfa(verify_mfa(&token, wrong_code(&code))).await;
+            assert!(matches!(result, Err(AppError::Authentication(_))));
+        }
+
+        let result = f.login.verify_mfa(verify_mfa(&token, &code)).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+        assert!(f.audit_sink.actions().contains(&"mfa_failed".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_two_factor_web_login_requires_code() {
+        let f = fixture();
+        let secret = f.enroll_totp().await;
+
+        let outcome = f.login.login_web(web_command()).await.unwrap();
+        let WebLoginOutcome::MfaRequired { mfa_token, .. } = outcome else {
+            panic!("expected an MFA challenge");
+        };
+
+        let verify = |code: &str| VerifyWebMfaCommand {
+            mfa_token: mfa_token.clone(),
+            code: code.to_string(),
+            device_name: Some("Laptop".to_string()),
+            remember_me: true,
+            ip_address: None,
+            user_agent: None,
+        };
+        let code = secret.current_code();
+        let result = f.login.verify_mfa_web(verify(wrong_code(&code))).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+
+        let session = f.login.verify_mfa_web(verify(&code)).await.unwrap().session;
+        assert_eq!(session.user_id, f.user_id);
+        assert_eq!(session.device_name.as_deref(), Some("Laptop"));
+        assert!(session.expires_at - chrono::Utc::now() > chrono::Duration::days(29));
+    }
+
+    #[tokio::test]
+    async fn test_session_exchange_requires_code_with_2fa() {
+        let f = fixture();
+        // Signed in before enrolling
+        let session = f.login.login_web(web_command()).await.map(web_session).unwrap();
+        let secret = f.enroll_totp().await;
+
+        let result = f.login.exchange_session(&session, None, None, None).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+
+        let code = secret.current_code();
+        let result = f.login.exchange_session(&session, None, None, Some(wrong_code(&code))).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+
+        let outcome = f.login.exchange_session(&session, None, None, Some(&code)).await.unwrap();
+        assert_eq!(logged_in(outcome).user.id, f.user_id);
+    }
+
+    #[tokio::test]
+    async fn test_session_exchange_enforces_two_factor_policy() {
+        let f = fixture();
+        let session = f.login.login_web(web_command()).await.map(web_session).unwrap();
+        f.join_with_policy("acme", TwoFactorPolicy::Required, false).await;
+
+        let result = f.login.exchange_session(&session, None, Some("acme"), None).await;
+
+        assert!(matches!(result, Err(AppError::TwoFactorSetupRequired(_))));
+    }
 }
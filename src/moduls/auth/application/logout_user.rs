@@ -1,3 +1,5 @@
+use super::TokenWatermark;
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
 use crate::moduls::auth::infra::{SessionRepository, TokenRepository};
 use crate::shared::{types::*, AppResult};
 use std::sync::Arc;
@@ -10,16 +12,22 @@ use std::sync::Arc;
 pub struct LogoutUserUseCase {
     session_repo: Arc<dyn SessionRepository>,
     token_repo: Arc<dyn TokenRepository>,
+    token_watermark: Arc<TokenWatermark>,
+    audit_log: Arc<AuditLog>,
 }
 
 impl LogoutUserUseCase {
     pub fn new(
         session_repo: Arc<dyn SessionRepository>,
         token_repo: Arc<dyn TokenRepository>,
+        token_watermark: Arc<TokenWatermark>,
+        audit_log: Arc<AuditLog>,
     ) -> Self {
         Self {
             session_repo,
             token_repo,
+            token_watermark,
+            audit_log,
         }
     }
 
@@ -27,6 +35,7 @@ impl LogoutUserUseCase {
     ///
     /// Business Logic:
     /// - Delete session by ID
+    /// - Revoke the API tokens minted under it (`sid`)
     ///
     /// # Arguments
     /// * `session_id` - ID of session to delete
@@ -35,6 +44,7 @@ impl LogoutUserUseCase {
     /// - Database errors (not finding session is not an error)
     pub async fn logout_web(&self, session_id: SessionId) -> AppResult<()> {
         self.session_repo.delete(session_id).await?;
+        self.token_repo.revoke_session_tokens(session_id).await?;
         Ok(())
     }
 
@@ -56,6 +66,9 @@ impl LogoutUserUseCase {
     /// token revocation status before allowing access.
     pub async fn logout_api(&self, user_id: UserId) -> AppResult<()> {
         self.token_repo.revoke_all_user_tokens(user_id).await?;
+        self.audit_log
+            .record(AuditEvent::new(AuditAction::Logout, Some(user_id)))
+            .await;
         Ok(())
     }
 
@@ -75,6 +88,13 @@ impl LogoutUserUseCase {
         // Revoke all tokens
         self.token_repo.revoke_all_user_tokens(user_id).await?;
 
+        // Also reject any token issued so far, persisted or not
+        self.token_watermark.invalidate_user_tokens(user_id).await?;
+
+        self.audit_log
+            .record(AuditEvent::new(AuditAction::Logout, Some(user_id)))
+            .await;
+
         Ok(())
     }
 }
@@ -0,0 +1,193 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::Role;
+use crate::moduls::auth::infra::{RoleRepository, UserRepository};
+use crate::shared::{types::UserId, AppError, AppResult};
+use std::sync::Arc;
+
+/// Use case for granting and revoking user roles
+///
+/// Exposed to administrators only (`require_role`). Both operations are
+/// idempotent. Only a super admin may grant or revoke `Role::SUPER_ADMIN`,
+/// so an admin can't promote themselves past their own privileges. Tokens carry the roles they were minted with, so a change
+/// reaches API clients at their next login or token refresh.
+pub struct ManageRolesUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    role_repo: Arc<dyn RoleRepository>,
+    audit_log: Arc<AuditLog>,
+}
+
+impl ManageRolesUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        role_repo: Arc<dyn RoleRepository>,
+        audit_log: Arc<AuditLog>,
+    ) -> Self {
+        Self {
+            user_repo,
+            role_repo,
+            audit_log,
+        }
+    }
+
+    /// Grant `role` to `user_id` on behalf of `actor_id`
+    ///
+    /// # Errors
+    /// - Validation error if the role name is malformed
+    /// - Authorization if the role is super_admin and the actor isn't one
+    /// - NotFound if the user or the role doesn't exist
+    pub async fn grant(&self, actor_id: UserId, user_id: UserId, role: &str) -> AppResult<()> {
+        let role = Role::new(role)?;
+        self.ensure_can_manage(actor_id, &role).await?;
+        self.ensure_user_exists(user_id).await?;
+
+        if self.role_repo.grant(user_id, &role).await? {
+            tracing::info!("Granted role {} to user {}", role, user_id);
+            self.audit_log
+                .record(AuditEvent::new(AuditAction::RoleGranted, Some(user_id)))
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Revoke `role` from `user_id` on behalf of `actor_id`
+    ///
+    /// # Errors
+    /// - Validation error if the role name is malformed, or if an admin
+    ///   tries to drop their own admin role (which could leave no admin)
+    /// - Authorization if the role is super_admin and the actor isn't one
+    /// - NotFound if the user doesn't exist
+    pub async fn revoke(&self, actor_id: UserId, user_id: UserId, role: &str) -> AppResult<()> {
+        let role = Role::new(role)?;
+        if actor_id == user_id && role.as_str() == Role::ADMIN {
+            return Err(AppError::Validation("You cannot revoke your own admin role".into()));
+        }
+        self.ensure_can_manage(actor_id, &role).await?;
+        self.ensure_user_exists(user_id).await?;
+
+        if self.role_repo.revoke(user_id, &role).await? {
+            tracing::info!("Revoked role {} from user {}", role, user_id);
+            self.audit_log
+                .record(AuditEvent::new(AuditAction::RoleRevoked, Some(user_id)))
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Checked against the stored roles rather than the token's, so a
+    /// freshly revoked super admin can't act on stale claims
+    async fn ensure_can_manage(&self, actor_id: UserId, role: &Role) -> AppResult<()> {
+        if role.as_str() != Role::SUPER_ADMIN {
+            return Ok(());
+        }
+        let actor_roles = self.role_repo.find_by_user_id(actor_id).await?;
+        if actor_roles.iter().any(|r| r.as_str() == Role::SUPER_ADMIN) {
+            Ok(())
+        } else {
+            Err(AppError::authorization(
+                "Only a super admin can grant or revoke the super_admin role",
+            ))
+        }
+    }
+
+    async fn ensure_user_exists(&self, user_id: UserId) -> AppResult<()> {
+        self.user_repo
+            .find_by_id(user_id)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| AppError::NotFound("User not found".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{Email, User};
+    use crate::moduls::auth::infra::in_memory::{InMemoryRoleRepository, InMemoryUserRepository};
+
+    struct Fixture {
+        use_case: ManageRolesUseCase,
+        role_repo: Arc<InMemoryRoleRepository>,
+        user_id: UserId,
+    }
+
+    fn fixture() -> Fixture {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        let user_id = user.id;
+        let role_repo = Arc::new(InMemoryRoleRepository::default());
+
+        Fixture {
+            use_case: ManageRolesUseCase::new(
+                Arc::new(InMemoryUserRepository::with_user(user)),
+                role_repo.clone(),
+                Arc::new(AuditLog::for_tests()),
+            ),
+            role_repo,
+            user_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_grant_and_revoke() {
+        let f = fixture();
+
+        f.use_case.grant(UserId::new(), f.user_id, "Admin").await.unwrap();
+        f.use_case.grant(UserId::new(), f.user_id, "admin").await.unwrap();
+        assert_eq!(f.role_repo.find_by_user_id(f.user_id).await.unwrap(), vec![Role::admin()]);
+
+        f.use_case.revoke(UserId::new(), f.user_id, "admin").await.unwrap();
+        assert!(f.role_repo.find_by_user_id(f.user_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_or_role_is_not_found() {
+        let f = fixture();
+
+        let result = f.use_case.grant(UserId::new(), UserId::new(), "admin").await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        let result = f.use_case.grant(UserId::new(), f.user_id, "superuser").await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_admin_cannot_revoke_own_admin_role() {
+        let f = fixture();
+        f.use_case.grant(UserId::new(), f.user_id, "admin").await.unwrap();
+
+        let result = f.use_case.revoke(f.user_id, f.user_id, "admin").await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        assert_eq!(f.role_repo.find_by_user_id(f.user_id).await.unwrap(), vec![Role::admin()]);
+    }
+
+    #[tokio::test]
+    async fn test_admin_cannot_grant_or_revoke_super_admin() {
+        let f = fixture();
+        let admin_id = UserId::new();
+        f.role_repo.grant(admin_id, &Role::admin()).await.unwrap();
+
+        let result = f.use_case.grant(admin_id, admin_id, Role::SUPER_ADMIN).await;
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+        assert_eq!(f.role_repo.find_by_user_id(admin_id).await.unwrap(), vec![Role::admin()]);
+
+        let super_admin = Role::new(Role::SUPER_ADMIN).unwrap();
+        f.role_repo.grant(f.user_id, &super_admin).await.unwrap();
+        let result = f.use_case.revoke(admin_id, f.user_id, Role::SUPER_ADMIN).await;
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_super_admin_can_grant_super_admin() {
+        let f = fixture();
+        let actor_id = UserId::new();
+        let super_admin = Role::new(Role::SUPER_ADMIN).unwrap();
+        f.role_repo.grant(actor_id, &super_admin).await.unwrap();
+
+        f.use_case.grant(actor_id, f.user_id, Role::SUPER_ADMIN).await.unwrap();
+
+        assert_eq!(f.role_repo.find_by_user_id(f.user_id).await.unwrap(), vec![super_admin]);
+    }
+}
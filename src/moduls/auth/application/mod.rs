@@ -1,13 +1,30 @@
-/// Application layer for authentication module
-///
-/// This layer contains use cases that orchestrate domain entities
-/// and infrastructure services. Use cases are the entry points for
-/// all authentication business logic.
+//! Application layer for authentication module
+//!
+//! This layer contains use cases that orchestrate domain entities
+//! and infrastructure services. Use cases are the entry points for
+//! all authentication business logic.
 
 pub mod register_user;
 pub mod login_user;
 pub mod logout_user;
 pub mod refresh_token;
+pub mod get_current_user;
+pub mod token_watermark;
+pub mod reset_password;
+pub mod verify_email;
+pub mod send_throttle;
+pub mod totp;
+pub mod manage_roles;
+pub mod revoke_token;
+pub mod revoke_tenant_credentials;
+pub mod impersonate_user;
+pub mod password_history;
+pub mod security_notifier;
+pub mod hash_migration;
+pub mod list_users;
+pub mod user_status;
+pub mod account_lockout;
+pub mod account_lifecycle;
 
 // Re-export use cases and commands
 pub use register_user::{RegisterUserCommand, RegisterUserUseCase};
@@ -15,7 +32,42 @@ pub use login_user::{
     LoginUserUseCase,
     LoginWebCommand,
     LoginApiCommand,
+    ApiLoginOutcome,
+    ApiLoginResult,
     AuthConfig,
+    PasswordParams,
+    PasswordParamsCommand,
+    VerifyMfaCommand,
+    VerifyWebMfaCommand,
+    WebLoginOutcome,
+    WebLoginResult,
 };
+#[cfg(feature = "dev-tools")]
+pub use login_user::DevLoginCommand;
 pub use logout_user::LogoutUserUseCase;
 pub use refresh_token::{RefreshTokenCommand, RefreshTokenUseCase, RefreshConfig};
+pub use get_current_user::{CurrentUserResult, GetCurrentUserUseCase};
+pub use token_watermark::TokenWatermark;
+pub use reset_password::{
+    ForgotPasswordCommand,
+    ResetPasswordCommand,
+    ResetDelivery,
+    ResetPasswordConfig,
+    ResetPasswordUseCase,
+};
+pub use verify_email::{
+    ResendVerificationCommand, SetRecoveryEmailCommand, VerifyEmailCommand, VerifyEmailUseCase,
+};
+pub use send_throttle::{SendLimits, SendThrottle};
+pub use totp::{ConfirmTotpCommand, ConfirmTotpUseCase, EnableTotpResult, EnableTotpUseCase};
+pub use manage_roles::ManageRolesUseCase;
+pub use revoke_token::{RevokeTokenCommand, RevokeTokenUseCase};
+pub use revoke_tenant_credentials::{RevokeTenantCredentialsUseCase, RevokedCredentials};
+pub use impersonate_user::ImpersonateUserUseCase;
+pub use password_history::PasswordHistory;
+pub use security_notifier::SecurityNotifier;
+pub use hash_migration::{HashMigrationProgress, HashMigrationUseCase};
+pub use list_users::{ListUsersQuery, ListUsersUseCase};
+pub use user_status::{SetUserStatusCommand, SetUserStatusUseCase};
+pub use account_lockout::{AccountLockout, LockoutPolicy};
+pub use account_lifecycle::{AccountLifecycleUseCase, ScheduleDeletionCommand, SetExpiryCommand};
@@ -0,0 +1,114 @@
+use crate::moduls::auth::domain::{PasswordPolicy, User};
+use crate::moduls::auth::infra::PasswordHistoryRepository;
+use crate::shared::AppResult;
+use std::sync::Arc;
+
+/// Password reuse check shared by the change and reset flows
+///
+/// `PasswordPolicy::history` counts the current password, so only the
+/// `history - 1` hashes before it are stored. Does nothing when the policy
+/// has no history.
+#[derive(Clone)]
+pub struct PasswordHistory {
+    repo: Arc<dyn PasswordHistoryRepository>,
+}
+
+impl PasswordHistory {
+    pub fn new(repo: Arc<dyn PasswordHistoryRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// Reject `password` if it is the user's current or a recent password
+    ///
+    /// # Errors
+    /// - PasswordPolicy (`not_reused`) on a match
+    /// - Database errors
+    pub async fn ensure_not_reused(
+        &self,
+        user: &User,
+        password: &str,
+        policy: &PasswordPolicy,
+        field: &'static str,
+    ) -> AppResult<()> {
+        let size = policy.history.unwrap_or_default();
+        if size == 0 {
+            return Ok(());
+        }
+
+        // A user who never changed their password has no history yet
+        let previous = match size - 1 {
+            0 => Vec::new(),
+            limit => self.repo.recent(user.id, limit).await?,
+        };
+        if user.is_recent_password(password, &previous)? {
+            return Err(policy.reused(field));
+        }
+
+        Ok(())
+    }
+
+    /// Remember the password `user` is about to replace
+    ///
+    /// # Errors
+    /// - Database errors
+    pub async fn remember(&self, user: &User, policy: &PasswordPolicy) -> AppResult<()> {
+        let size = policy.history.unwrap_or_default();
+        if size <= 1 {
+            return Ok(());
+        }
+
+        self.repo.push(user.id, &user.password_hash, size - 1).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{Email, PasswordHasher};
+    use crate::moduls::auth::infra::in_memory::InMemoryPasswordHistoryRepository;
+    use crate::shared::AppError;
+
+    const HASHER: PasswordHasher = PasswordHasher::Bcrypt { cost: 4 };
+
+    fn policy(history: usize) -> PasswordPolicy {
+        PasswordPolicy {
+            history: Some(history),
+            ..PasswordPolicy::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_the_last_passwords_are_refused() {
+        let history = PasswordHistory::new(Arc::new(InMemoryPasswordHistoryRepository::default()));
+        let policy = policy(3);
+        let mut user = User::new(Email::new("h@example.com").unwrap(), "password-1", "H".to_string()).unwrap();
+        for next in ["password-2", "password-3", "password-4"] {
+            history.remember(&user, &policy).await.unwrap();
+            user.change_password(next, &HASHER).unwrap();
+        }
+
+        for reused in ["password-2", "password-3", "password-4"] {
+            let result = history.ensure_not_reused(&user, reused, &policy, "new_password").await;
+            assert!(matches!(result, Err(AppError::PasswordPolicy(_))), "{reused}");
+        }
+        assert!(history
+            .ensure_not_reused(&user, "password-1", &policy, "new_password")
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_no_history_allows_reuse() {
+        let repo = Arc::new(InMemoryPasswordHistoryRepository::default());
+        let history = PasswordHistory::new(repo.clone());
+        let user = User::new(Email::new("h@example.com").unwrap(), "password-1", "H".to_string()).unwrap();
+
+        history.remember(&user, &PasswordPolicy::default()).await.unwrap();
+
+        assert!(history
+            .ensure_not_reused(&user, "password-1", &PasswordPolicy::default(), "new_password")
+            .await
+            .is_ok());
+        assert!(repo.entries.lock().unwrap().is_empty());
+    }
+}
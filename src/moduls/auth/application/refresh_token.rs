@@ -1,6 +1,8 @@
-use crate::moduls::auth::domain::TokenPair;
-use crate::moduls::auth::infra::TokenRepository;
-use crate::shared::{AppError, AppResult};
+use super::TokenWatermark;
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::{ClaimsFormat, JwtKeys, TokenPair};
+use crate::moduls::auth::infra::{RoleRepository, TokenRepository};
+use crate::shared::{types::{TokenId, UserId}, AppError, AppResult};
 use std::sync::Arc;
 
 /// Command for refreshing access token
@@ -11,9 +13,13 @@ pub struct RefreshTokenCommand {
 
 /// Configuration for token refresh
 pub struct RefreshConfig {
-    pub jwt_secret: String,
+    pub jwt_keys: Arc<JwtKeys>,
     pub access_ttl_seconds: i64,
     pub refresh_ttl_seconds: i64,
+    pub claims_format: ClaimsFormat,
+    /// How long after expiry a refresh token is still accepted (clock skew,
+    /// slow clients); independent of the access-token leeway
+    pub grace_seconds: u64,
 }
 
 /// Use case for refreshing access tokens
@@ -21,29 +27,42 @@ pub struct RefreshConfig {
 /// Business Logic:
 /// 1. Decode refresh token
 /// 2. Extract JTI
-/// 3. Check token not revoked in database
-/// 4. Check token not expired
-/// 5. Revoke old refresh token (token rotation)
-/// 6. Generate new TokenPair
-/// 7. Save new tokens to database
-/// 8. Return new TokenPair
+/// 3. Check token not revoked in database (a revoked one revokes its
+///    whole family, see below)
+/// 4. Check token not expired (beyond the grace period) or issued before
+///    the watermark
+/// 5. Generate new TokenPair, with the user's current roles
+/// 6. Save new tokens to database, in the old token's family
+/// 7. Revoke old refresh token (token rotation); losing a race to revoke
+///    it counts as reuse
+/// 8. Audit the refresh and return new TokenPair
 ///
 /// Security:
 /// - Implements refresh token rotation (old token revoked)
-/// - Prevents token reuse attacks
+/// - Reuse detection: tokens of one login form a family; replaying an
+///   already-rotated refresh token revokes the entire family
 /// - Checks JTI blacklist
 pub struct RefreshTokenUseCase {
     token_repo: Arc<dyn TokenRepository>,
+    token_watermark: Arc<TokenWatermark>,
+    role_repo: Arc<dyn RoleRepository>,
+    audit_log: Arc<AuditLog>,
     config: RefreshConfig,
 }
 
 impl RefreshTokenUseCase {
     pub fn new(
         token_repo: Arc<dyn TokenRepository>,
+        token_watermark: Arc<TokenWatermark>,
+        role_repo: Arc<dyn RoleRepository>,
+        audit_log: Arc<AuditLog>,
         config: RefreshConfig,
     ) -> Self {
         Self {
             token_repo,
+            token_watermark,
+            role_repo,
+            audit_log,
             config,
         }
     }
@@ -61,15 +80,27 @@ impl RefreshTokenUseCase {
     /// - Database errors
     pub async fn execute(&self, cmd: RefreshTokenCommand) -> AppResult<TokenPair> {
         // 1. Decode refresh token and validate signature
-        let claims = TokenPair::decode(&cmd.refresh_token, &self.config.jwt_secret)?;
+        let claims = TokenPair::decode_with_leeway(
+            &cmd.refresh_token,
+            &self.config.jwt_keys,
+            self.config.grace_seconds,
+        )?;
 
         // 2. Verify this is a refresh token
         if claims.token_type != "refresh" {
             return Err(AppError::authentication("Invalid token type, expected refresh token"));
         }
 
+        // Impersonation is time-boxed: never extend it (none are issued as
+        // refresh tokens, this guards against that changing)
+        if claims.act.is_some() {
+            return Err(AppError::authentication("Impersonation tokens cannot be refreshed"));
+        }
+
         // 3. Extract JTI
-        let jti = uuid::Uuid::parse_str(&claims.jti)
+        let jti: TokenId = claims
+            .jti
+            .parse()
             .map_err(|e| AppError::internal(format!("Invalid JTI: {}", e)))?;
 
         // 4. Check token exists in database and not revoked
@@ -78,40 +109,354 @@ impl RefreshTokenUseCase {
             .ok_or_else(|| AppError::authentication("Token not found"))?;
 
         if stored_token.is_revoked() {
+            // A rotated refresh token came back: either it was stolen or the
+            // legitimate client is replaying it, and we can't tell which.
+            // Kill the whole chain so the attacker's copy dies too
+            let revoked = self.token_repo.revoke_family(stored_token.family_id).await?;
+            tracing::warn!(
+                "Revoked refresh token {} of user {} was reused; revoked {} token(s) of family {}",
+                jti,
+                stored_token.user_id,
+                revoked,
+                stored_token.family_id
+            );
             return Err(AppError::authentication("Token has been revoked"));
         }
 
         if stored_token.is_expired() {
-            return Err(AppError::authentication("Token has expired"));
+            let expired_for = stored_token.expired_for_seconds();
+            if expired_for > self.config.grace_seconds as i64 {
+                return Err(AppError::authentication("Token has expired"));
+            }
+            tracing::info!(
+                "Accepting refresh token {} expired {}s ago (grace {}s)",
+                jti,
+                expired_for,
+                self.config.grace_seconds
+            );
         }
 
-        // 5. Revoke old refresh token (token rotation for security)
-        self.token_repo.revoke(jti).await?;
+        self.token_watermark.check(&claims).await?;
 
-        // 6. Extract user ID and generate new TokenPair
-        let user_id = uuid::Uuid::parse_str(&claims.sub)
+        // 5. Extract user ID and generate new TokenPair
+        let user_id: UserId = claims
+            .sub
+            .parse()
             .map_err(|e| AppError::internal(format!("Invalid user ID: {}", e)))?;
 
-        let (token_pair, access_token, refresh_token) = TokenPair::generate(
+        // Keep the tenant selected at login, when the user signed in and
+        // the session minted under; roles are reloaded so grants and
+        // revocations take effect
+        let roles = self.role_repo.find_by_user_id(user_id).await?;
+        let (token_pair, access_token, refresh_token) = TokenPair::generate_with_auth_time(
             user_id,
-            &self.config.jwt_secret,
+            claims.tenant_id()?,
+            Some(claims.authenticated_at()),
+            Some(&roles),
+            claims.session_id()?,
+            self.config.claims_format,
+            &self.config.jwt_keys,
             self.config.access_ttl_seconds,
             self.config.refresh_ttl_seconds,
         )?;
 
-        // 7. Save new tokens to database
-        self.token_repo.save(&access_token).await?;
-        self.token_repo.save(&refresh_token).await?;
+        // 6. Save new tokens to database, in the rotated token's family.
+        // Before the rotation, so that a family revocation by a concurrent
+        // refresh losing the race below catches them too
+        self.token_repo
+            .save(&access_token.in_family(stored_token.family_id))
+            .await?;
+        self.token_repo
+            .save(&refresh_token.in_family(stored_token.family_id))
+            .await?;
+
+        // 7. Revoke old refresh token (token rotation for security). The
+        // check above can race with a concurrent refresh of the same token:
+        // only one of them revokes it, the other is a reuse like any other
+        if !self.token_repo.revoke(jti).await? {
+            let revoked = self.token_repo.revoke_family(stored_token.family_id).await?;
+            tracing::warn!(
+                "Refresh token {} of user {} was rotated concurrently; revoked {} token(s) of family {}",
+                jti,
+                stored_token.user_id,
+                revoked,
+                stored_token.family_id
+            );
+            return Err(AppError::authentication("Token has been revoked"));
+        }
+
+        // 8. Audit and return new TokenPair
+        self.audit_log
+            .record(
+                AuditEvent::new(AuditAction::TokenRefreshed, Some(user_id))
+                    .with_tenant(claims.tenant_id()?),
+            )
+            .await;
 
-        // 8. Return new TokenPair
         Ok(token_pair)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    
+    use super::*;
+    use crate::moduls::auth::domain::{Email, JwtKeys, JwtToken, Role, User};
+    use crate::moduls::auth::infra::in_memory::*;
+    use crate::shared::types::{now, OrganizationId, SessionId};
+
+    const SECRET: &str = "test_secret_key_for_jwt_signing_minimum_32_chars";
+
+    struct Fixture {
+        use_case: RefreshTokenUseCase,
+        token_repo: Arc<InMemoryTokenRepository>,
+        role_repo: Arc<InMemoryRoleRepository>,
+        user_id: crate::shared::types::UserId,
+    }
+
+    fn fixture(grace_seconds: u64) -> Fixture {
+        fixture_with(grace_seconds, |repo| repo)
+    }
+
+    /// Fixture whose use case reaches `token_repo` through `wrap`
+    fn fixture_with(
+        grace_seconds: u64,
+        wrap: impl FnOnce(Arc<InMemoryTokenRepository>) -> Arc<dyn TokenRepository>,
+    ) -> Fixture {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        let user_id = user.id;
+        let token_repo = Arc::new(InMemoryTokenRepository::default());
+        let token_watermark = Arc::new(TokenWatermark::new(
+            Arc::new(InMemoryTokenWatermarkRepository::default()),
+            Arc::new(InMemoryUserRepository::with_user(user)),
+            None,
+            std::time::Duration::ZERO,
+        ));
+
+        let role_repo = Arc::new(InMemoryRoleRepository::default());
+
+        let use_case = RefreshTokenUseCase::new(
+            wrap(token_repo.clone()),
+            token_watermark,
+            role_repo.clone(),
+            Arc::new(AuditLog::for_tests()),
+            RefreshConfig {
+                jwt_keys: Arc::new(JwtKeys::hmac(SECRET)),
+                access_ttl_seconds: 900,
+                refresh_ttl_seconds: 3600,
+                claims_format: ClaimsFormat::Verbose,
+                grace_seconds,
+            },
+        );
+
+        Fixture {
+            use_case,
+            token_repo,
+            role_repo,
+            user_id,
+        }
+    }
+
+    /// Refresh token that expired `seconds_ago`
+    async fn expired_refresh_token(f: &Fixture, seconds_ago: i64) -> String {
+        let (pair, _, refresh) =
+            TokenPair::generate(f.user_id, &JwtKeys::hmac(SECRET), 900, -seconds_ago).unwrap();
+        f.token_repo.save(&refresh).await.unwrap();
+        pair.refresh_token
+    }
+
+    fn command(refresh_token: String) -> RefreshTokenCommand {
+        RefreshTokenCommand { refresh_token }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_within_grace_succeeds() {
+        let f = fixture(30);
+        let token = expired_refresh_token(&f, 5).await;
+
+        let pair = f.use_case.execute(command(token.clone())).await.unwrap();
+
+        assert_ne!(pair.refresh_token, token);
+        // Rotation still applies: the old token is revoked
+        assert!(f.use_case.execute(command(token)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_past_grace_fails() {
+        let f = fixture(30);
+        let token = expired_refresh_token(&f, 45).await;
+
+        let result = f.use_case.execute(command(token)).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_no_grace_rejects_any_expired_refresh_token() {
+        let f = fixture(0);
+        let token = expired_refresh_token(&f, 5).await;
+
+        assert!(f.use_case.execute(command(token)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_auth_time() {
+        let f = fixture(0);
+        let keys = JwtKeys::hmac(SECRET);
+        let signed_in = now().timestamp() - 3600;
+        let (mut pair, _, refresh) = TokenPair::generate_with_auth_time(
+            f.user_id,
+            None,
+            Some(signed_in),
+            None,
+            None,
+            ClaimsFormat::Verbose,
+            &keys,
+            900,
+            3600,
+        )
+        .unwrap();
+        f.token_repo.save(&refresh).await.unwrap();
+
+        for _ in 0..2 {
+            pair = f.use_case.execute(command(pair.refresh_token)).await.unwrap();
+
+            for token in [&pair.access_token, &pair.refresh_token] {
+                let claims = TokenPair::decode(token, &keys).unwrap();
+                assert_eq!(claims.authenticated_at(), signed_in);
+                assert!(claims.iat >= now().timestamp() - 1, "iat is the refresh time");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replayed_refresh_token_revokes_family() {
+        let f = fixture(0);
+        let (pair, access, refresh) =
+            TokenPair::generate(f.user_id, &JwtKeys::hmac(SECRET), 900, 3600).unwrap();
+        f.token_repo.save(&access).await.unwrap();
+        f.token_repo.save(&refresh).await.unwrap();
+        // Another login of the same user is a separate family
+        let (_, other_access, _) =
+            TokenPair::generate(f.user_id, &JwtKeys::hmac(SECRET), 900, 3600).unwrap();
+        f.token_repo.save(&other_access).await.unwrap();
+
+        let current = f.use_case.execute(command(pair.refresh_token.clone())).await.unwrap();
+
+        // The stolen, already-rotated token is replayed
+        let result = f.use_case.execute(command(pair.refresh_token)).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+
+        let tokens = f.token_repo.tokens.lock().unwrap().clone();
+        let family: Vec<_> = tokens.iter().filter(|t| t.family_id == refresh.family_id).collect();
+        assert_eq!(family.len(), 4, "Rotation should keep the family");
+        assert!(family.iter().all(|t| t.is_revoked()));
+        assert!(tokens.iter().any(|t| t.jti == other_access.jti && !t.is_revoked()));
+
+        // The legitimate client's current token is dead too
+        assert!(f.use_case.execute(command(current.refresh_token)).await.is_err());
+    }
+
+    /// Lets both of two concurrent refreshes look their token up before
+    /// either rotates it
+    struct RacingTokenRepository {
+        inner: Arc<InMemoryTokenRepository>,
+        lookups: tokio::sync::Barrier,
+    }
+
+    #[async_trait::async_trait]
+    impl TokenRepository for RacingTokenRepository {
+        async fn save(&self, token: &JwtToken) -> AppResult<JwtToken> {
+            self.inner.save(token).await
+        }
+
+        async fn find_by_jti(&self, jti: TokenId) -> AppResult<Option<JwtToken>> {
+            let token = self.inner.find_by_jti(jti).await;
+            self.lookups.wait().await;
+            token
+        }
+
+        async fn revoke(&self, jti: TokenId) -> AppResult<bool> {
+            self.inner.revoke(jti).await
+        }
+
+        async fn revoke_all_user_tokens(&self, user_id: UserId) -> AppResult<()> {
+            self.inner.revoke_all_user_tokens(user_id).await
+        }
+
+        async fn revoke_family(&self, family_id: uuid::Uuid) -> AppResult<u64> {
+            self.inner.revoke_family(family_id).await
+        }
+
+        async fn revoke_session_tokens(&self, session_id: SessionId) -> AppResult<u64> {
+            self.inner.revoke_session_tokens(session_id).await
+        }
+
+        async fn revoke_tenant_tokens(&self, tenant_id: OrganizationId, batch_size: u32) -> AppResult<u64> {
+            self.inner.revoke_tenant_tokens(tenant_id, batch_size).await
+        }
+
+        async fn delete_expired(&self) -> AppResult<u64> {
+            self.inner.delete_expired().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_refresh_counts_as_reuse() {
+        let f = fixture_with(0, |inner| {
+            Arc::new(RacingTokenRepository {
+                inner,
+                lookups: tokio::sync::Barrier::new(2),
+            })
+        });
+        let (pair, _, refresh) =
+            TokenPair::generate(f.user_id, &JwtKeys::hmac(SECRET), 900, 3600).unwrap();
+        f.token_repo.save(&refresh).await.unwrap();
+
+        let (first, second) = tokio::join!(
+            f.use_case.execute(command(pair.refresh_token.clone())),
+            f.use_case.execute(command(pair.refresh_token)),
+        );
 
-    // Tests would require mock repositories and token generation
-    // Skipping for brevity
+        // Both passed the revocation check; only one rotated the token
+        let (winner, loser) = match (first, second) {
+            (Ok(winner), Err(loser)) | (Err(loser), Ok(winner)) => (winner, loser),
+            (first, second) => panic!("expected one success, got {:?} and {:?}", first.is_ok(), second.is_ok()),
+        };
+        assert!(matches!(loser, AppError::Authentication(_)));
+
+        // The other is a reuse: the whole family is gone, winner's tokens too
+        let tokens = f.token_repo.tokens.lock().unwrap().clone();
+        assert!(tokens
+            .iter()
+            .filter(|t| t.family_id == refresh.family_id)
+            .all(|t| t.is_revoked()));
+        let claims = TokenPair::decode(&winner.refresh_token, &JwtKeys::hmac(SECRET)).unwrap();
+        let jti: TokenId = claims.jti.parse().unwrap();
+        assert!(tokens.iter().any(|t| t.jti == jti && t.is_revoked()));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_picks_up_granted_roles() {
+        let f = fixture(0);
+        let keys = JwtKeys::hmac(SECRET);
+        let (pair, _, refresh) = TokenPair::generate(f.user_id, &keys, 900, 3600).unwrap();
+        f.token_repo.save(&refresh).await.unwrap();
+        f.role_repo.grant(f.user_id, &Role::admin()).await.unwrap();
+
+        let pair = f.use_case.execute(command(pair.refresh_token)).await.unwrap();
+
+        let claims = TokenPair::decode(&pair.access_token, &keys).unwrap();
+        assert_eq!(claims.roles().unwrap(), Some(vec![Role::admin()]));
+    }
+
+    #[tokio::test]
+    async fn test_valid_refresh_token_succeeds() {
+        let f = fixture(0);
+        let (pair, _, refresh) =
+            TokenPair::generate(f.user_id, &JwtKeys::hmac(SECRET), 900, 3600).unwrap();
+        f.token_repo.save(&refresh).await.unwrap();
+
+        assert!(f.use_case.execute(command(pair.refresh_token)).await.is_ok());
+    }
 }
@@ -1,6 +1,11 @@
-use crate::moduls::auth::domain::{User, Email, UserDto};
+use crate::moduls::auth::domain::{
+    ClientHashParams, ClientHashing, Email, PasswordHash, PasswordHasher, PasswordPolicy, User,
+    UserDto,
+};
 use crate::moduls::auth::infra::UserRepository;
-use crate::shared::AppResult;
+use crate::moduls::organization::domain::TenantMembership;
+use crate::moduls::organization::infra::MembershipRepository;
+use crate::shared::{types::OrganizationId, AppError, AppResult};
 use std::sync::Arc;
 use validator::Validate;
 
@@ -10,33 +15,81 @@ pub struct RegisterUserCommand {
     #[validate(email)]
     pub email: String,
 
-    #[validate(length(min = 8))]
+    /// Strength rules live in `PasswordPolicy`, so every failure is
+    /// reported at once; with `client_hash`, the client hash instead
     pub password: String,
 
+    /// Parameters from `/password-params` the password was hashed with
+    #[serde(default)]
+    pub client_hash: Option<ClientHashParams>,
+
     #[validate(length(min = 1))]
     pub name: String,
+
+    /// Tenant to register into (from `TenantContext`, never the body)
+    #[serde(skip)]
+    pub tenant_id: Option<OrganizationId>,
 }
 
 /// Use case for user registration
 ///
 /// Business Logic:
-/// 1. Validate input (email format, password length, name)
-/// 2. Check email uniqueness
-/// 3. Create User entity (hashes password)
-/// 4. Save to repository
+/// 1. Parse email and check uniqueness (within the tenant, if any)
+/// 2. Create User entity (hashes password, validates name); with client-side
+///    hashing enabled it is stored as a client hash, whether the client sent
+///    one or the plain password
+/// 3. Save to repository
+/// 4. Add tenant users as members of their tenant
 /// 5. Return created user
 ///
+/// Request-level rules on `RegisterUserCommand` are checked together with
+/// the password policy, so every invalid field is reported at once; the
+/// domain re-checks the invariants.
+///
 /// Error Cases:
-/// - Email already exists → Conflict error
-/// - Invalid email format → Validation error
-/// - Password too short → Validation error
+/// - Email already exists → Conflict error (naming the `email` field for
+///   `execute_as_admin`)
+/// - Invalid fields → FieldValidation error, or InvalidFields if the
+///   password also fails the policy
+/// - Password too long → Validation error
+/// - Password fails the policy → PasswordPolicy error (all failed rules)
+/// - Client hash with other parameters than negotiated → ClientHashMismatch
 pub struct RegisterUserUseCase {
     user_repo: Arc<dyn UserRepository>,
+    membership_repo: Arc<dyn MembershipRepository>,
+    max_password_length: usize,
+    password_policy: PasswordPolicy,
+    client_hashing: Option<ClientHashing>,
+    password_hasher: PasswordHasher,
 }
 
 impl RegisterUserUseCase {
-    pub fn new(user_repo: Arc<dyn UserRepository>) -> Self {
-        Self { user_repo }
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        membership_repo: Arc<dyn MembershipRepository>,
+        max_password_length: usize,
+        password_policy: PasswordPolicy,
+    ) -> Self {
+        Self {
+            user_repo,
+            membership_repo,
+            max_password_length,
+            password_policy,
+            client_hashing: None,
+            password_hasher: PasswordHasher::default(),
+        }
+    }
+
+    /// Enable client-side password hashing (`CLIENT_PASSWORD_HASHING`)
+    pub fn with_client_hashing(mut self, client_hashing: Option<ClientHashing>) -> Self {
+        self.client_hashing = client_hashing;
+        self
+    }
+
+    /// Hash new passwords with `password_hasher` instead of the default
+    pub fn with_password_hasher(mut self, password_hasher: PasswordHasher) -> Self {
+        self.password_hasher = password_hasher;
+        self
     }
 
     /// Execute registration use case
@@ -52,24 +105,81 @@ impl RegisterUserUseCase {
     /// - Conflict error if email already exists
     /// - Database errors
     pub async fn execute(&self, cmd: RegisterUserCommand) -> AppResult<UserDto> {
-        // 1. Validate input
-        cmd.validate()
-            .map_err(|e| crate::shared::AppError::validation(format!("Validation failed: {}", e)))?;
+        self.register(cmd).await
+    }
+
+    /// Register a user on behalf of an admin
+    ///
+    /// Same as `execute`, except a duplicate is reported as a
+    /// `FieldConflict` naming the field, so admin tooling can react to it.
+    /// Public registration keeps the generic conflict.
+    pub async fn execute_as_admin(&self, cmd: RegisterUserCommand) -> AppResult<UserDto> {
+        self.register(cmd).await.map_err(|e| match e {
+            AppError::Conflict(message) => AppError::field_conflict("email", message),
+            e => e,
+        })
+    }
 
-        // 2. Parse and validate email
+    async fn register(&self, cmd: RegisterUserCommand) -> AppResult<UserDto> {
+        // 1. Validate the fields and the password (reject oversized passwords
+        //    before any work); a client hash hides the password, so only the
+        //    client can check it
+        PasswordHash::ensure_max_length(&cmd.password, self.max_password_length)?;
+        let policy = match cmd.client_hash {
+            None => self.password_policy.enforce(&cmd.password, "password"),
+            Some(_) => Ok(()),
+        };
+        AppError::check_fields(cmd.validate().err().unwrap_or_default(), policy)?;
         let email = Email::new(&cmd.email)?;
+        match (&cmd.client_hash, &self.client_hashing) {
+            (None, _) => {}
+            (Some(_), None) => {
+                return Err(AppError::validation("Client-side password hashing is not enabled"));
+            }
+            (Some(params), Some(hashing)) => {
+                if *params != hashing.params_for(&email) {
+                    return Err(AppError::client_hash_mismatch(
+                        "Fetch the password parameters again and re-hash",
+                    ));
+                }
+            }
+        }
 
-        // 3. Check email uniqueness
-        if let Some(_existing_user) = self.user_repo.find_by_email(&email).await? {
-            return Err(crate::shared::AppError::conflict("Email already exists"));
+        // 2. Check email uniqueness
+        let existing = match cmd.tenant_id {
+            Some(tenant_id) => self.user_repo.find_by_email_in_tenant(&email, tenant_id).await?,
+            None => self.user_repo.find_by_email(&email).await?,
+        };
+        if existing.is_some() {
+            return Err(AppError::conflict("Email already exists"));
         }
 
-        // 4. Create User entity (password is hashed in User::new)
-        let user = User::new(email, &cmd.password, cmd.name)?;
+        // 3. Create User entity (password is hashed in User::new)
+        let hasher = &self.password_hasher;
+        let mut user = match (cmd.client_hash, &self.client_hashing) {
+            (Some(params), _) => {
+                User::with_client_hash(email, &cmd.password, params, cmd.name, hasher)?
+            }
+            (None, Some(hashing)) => {
+                let params = hashing.params_for(&email);
+                PasswordHash::ensure_min_length(&cmd.password)?;
+                let client_hash = params.derive(&cmd.password);
+                User::with_client_hash(email, &client_hash, params, cmd.name, hasher)?
+            }
+            (None, None) => User::with_hasher(email, &cmd.password, cmd.name, hasher)?,
+        };
+        user.tenant_id = cmd.tenant_id;
 
-        // 5. Save to repository
+        // 4. Save to repository
         let saved_user = self.user_repo.save(&user).await?;
 
+        // 5. Tenant users belong to their tenant
+        if let Some(tenant_id) = saved_user.tenant_id {
+            self.membership_repo
+                .add(&TenantMembership::new(tenant_id, saved_user.id))
+                .await?;
+        }
+
         // 6. Return DTO (excludes password hash)
         Ok(UserDto::from(saved_user))
     }
@@ -80,6 +190,10 @@ mod tests {
     use super::*;
     use crate::moduls::auth::domain::User;
     use crate::shared::AppResult;
+    use crate::moduls::auth::infra::in_memory::InMemoryUserRepository;
+    use crate::moduls::organization::infra::in_memory::{
+        InMemoryMembershipRepository, InMemoryOrganizationRepository,
+    };
     use async_trait::async_trait;
 
     // Mock repository for testing
@@ -113,6 +227,14 @@ mod tests {
             Ok(users.iter().find(|u| u.email.as_str() == email.as_str()).cloned())
         }
 
+        async fn find_by_email_in_tenant(
+            &self,
+            _email: &Email,
+            _organization_id: OrganizationId,
+        ) -> AppResult<Option<User>> {
+            Ok(None)
+        }
+
         async fn update(&self, user: &User) -> AppResult<User> {
             Ok(user.clone())
         }
@@ -120,17 +242,48 @@ mod tests {
         async fn delete(&self, _id: crate::shared::types::UserId) -> AppResult<()> {
             Ok(())
         }
+
+        async fn list_paginated(
+            &self,
+            _filter: &crate::moduls::auth::infra::UserFilter,
+            page: crate::shared::pagination::PageRequest,
+        ) -> AppResult<crate::shared::pagination::Page<User>> {
+            Ok(crate::shared::pagination::Page::new(Vec::new(), page, 0))
+        }
+
+        async fn find_due_for_deletion(&self, _limit: u32) -> AppResult<Vec<User>> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn use_case_with(repo: Arc<dyn UserRepository>) -> RegisterUserUseCase {
+        use_case_with_policy(repo, PasswordPolicy::default())
+    }
+
+    fn use_case_with_policy(
+        repo: Arc<dyn UserRepository>,
+        password_policy: PasswordPolicy,
+    ) -> RegisterUserUseCase {
+        let org_repo = Arc::new(InMemoryOrganizationRepository::default());
+        RegisterUserUseCase::new(
+            repo,
+            Arc::new(InMemoryMembershipRepository::new(org_repo)),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            password_policy,
+        )
     }
 
     #[tokio::test]
     async fn test_register_user_success() {
         let repo = Arc::new(MockUserRepository::new());
-        let use_case = RegisterUserUseCase::new(repo);
+        let use_case = use_case_with(repo);
 
         let cmd = RegisterUserCommand {
             email: "test@example.com".to_string(),
             password: "password123".to_string(),
+            client_hash: None,
             name: "Test User".to_string(),
+            tenant_id: None,
         };
 
         let result = use_case.execute(cmd).await;
@@ -141,15 +294,36 @@ mod tests {
         assert_eq!(user_dto.name, "Test User");
     }
 
+    #[tokio::test]
+    async fn test_duplicate_names_the_field_only_for_admins() {
+        let use_case = use_case_with(Arc::new(MockUserRepository::new()));
+        let cmd = || RegisterUserCommand {
+            email: "taken@example.com".to_string(),
+            password: "password123".to_string(),
+            client_hash: None,
+            name: "Test User".to_string(),
+            tenant_id: None,
+        };
+        use_case.execute(cmd()).await.unwrap();
+
+        let public = use_case.execute(cmd()).await;
+        assert!(matches!(public, Err(AppError::Conflict(_))));
+
+        let admin = use_case.execute_as_admin(cmd()).await;
+        assert!(matches!(admin, Err(AppError::FieldConflict { field, .. }) if field == "email"));
+    }
+
     #[tokio::test]
     async fn test_register_user_invalid_email() {
         let repo = Arc::new(MockUserRepository::new());
-        let use_case = RegisterUserUseCase::new(repo);
+        let use_case = use_case_with(repo);
 
         let cmd = RegisterUserCommand {
             email: "invalid-email".to_string(),
             password: "password123".to_string(),
+            client_hash: None,
             name: "Test User".to_string(),
+            tenant_id: None,
         };
 
         let result = use_case.execute(cmd).await;
@@ -159,15 +333,141 @@ mod tests {
     #[tokio::test]
     async fn test_register_user_password_too_short() {
         let repo = Arc::new(MockUserRepository::new());
-        let use_case = RegisterUserUseCase::new(repo);
+        let use_case = use_case_with(repo);
 
         let cmd = RegisterUserCommand {
             email: "test@example.com".to_string(),
             password: "short".to_string(),
+            client_hash: None,
             name: "Test User".to_string(),
+            tenant_id: None,
         };
 
         let result = use_case.execute(cmd).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_register_reports_every_failed_password_rule() {
+        use crate::moduls::auth::domain::PasswordRequirement;
+
+        let repo = Arc::new(MockUserRepository::new());
+        let use_case = use_case_with_policy(
+            repo.clone(),
+            PasswordPolicy {
+                min_length: 12,
+                require_digit: true,
+                ..PasswordPolicy::default()
+            },
+        );
+
+        let cmd = RegisterUserCommand {
+            email: "test@example.com".to_string(),
+            password: "shortpass".to_string(),
+            client_hash: None,
+            name: "Test User".to_string(),
+            tenant_id: None,
+        };
+
+        let Err(crate::shared::AppError::PasswordPolicy(violation)) = use_case.execute(cmd).await
+        else {
+            panic!("expected a password policy violation");
+        };
+        assert_eq!(
+            violation.failed,
+            vec![PasswordRequirement::MinLength, PasswordRequirement::Digit]
+        );
+        assert_eq!(violation.policy.min_length, 12);
+        assert!(repo.users.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_register_reports_invalid_fields_with_password_policy() {
+        let repo = Arc::new(MockUserRepository::new());
+        let use_case = use_case_with(repo.clone());
+
+        let cmd = RegisterUserCommand {
+            email: "not-an-email".to_string(),
+            password: "short".to_string(),
+            client_hash: None,
+            name: "Test User".to_string(),
+            tenant_id: None,
+        };
+
+        let result = use_case.execute(cmd).await;
+        let Err(AppError::InvalidFields(errors, violation)) = result else {
+            panic!("expected field errors with a password policy violation, got {result:?}");
+        };
+        assert!(errors.field_errors().contains_key("email"));
+        assert_eq!(violation.field, "password");
+        assert!(repo.users.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_register_user_password_too_long() {
+        let repo = Arc::new(MockUserRepository::new());
+        let use_case = use_case_with(repo.clone());
+
+        let cmd = RegisterUserCommand {
+            email: "test@example.com".to_string(),
+            password: "a".repeat(100 * 1024),
+            client_hash: None,
+            name: "Test User".to_string(),
+            tenant_id: None,
+        };
+
+        let result = use_case.execute(cmd).await;
+        assert!(matches!(result, Err(crate::shared::AppError::Validation(_))));
+        assert!(repo.users.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_same_email_in_two_tenants() {
+        let repo = Arc::new(InMemoryUserRepository::default());
+        let use_case = use_case_with(repo.clone());
+        let command = |tenant_id| RegisterUserCommand {
+            email: "same@example.com".to_string(),
+            password: "password123".to_string(),
+            client_hash: None,
+            name: "Test User".to_string(),
+            tenant_id,
+        };
+        let (tenant_a, tenant_b) = (OrganizationId::new(), OrganizationId::new());
+
+        let user_a = use_case.execute(command(Some(tenant_a))).await.unwrap();
+        let user_b = use_case.execute(command(Some(tenant_b))).await.unwrap();
+        assert_ne!(user_a.id, user_b.id);
+
+        // Unique within a tenant
+        let result = use_case.execute(command(Some(tenant_a))).await;
+        assert!(matches!(result, Err(crate::shared::AppError::Conflict(_))));
+
+        // Tenant users don't block a global user with the same email
+        assert!(use_case.execute(command(None)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_register_with_client_hash() {
+        let repo = Arc::new(InMemoryUserRepository::default());
+        let hashing = ClientHashing::new("client_hash_secret", 1000);
+        let use_case = use_case_with(repo.clone()).with_client_hashing(Some(hashing.clone()));
+        let email = Email::new("hashed@example.com").unwrap();
+        let params = hashing.params_for(&email);
+        let command = |params: ClientHashParams| RegisterUserCommand {
+            email: email.as_str().to_string(),
+            password: params.derive("password123"),
+            client_hash: Some(params),
+            name: "Test User".to_string(),
+            tenant_id: None,
+        };
+
+        let stale = ClientHashParams::new(params.salt.clone(), 2000);
+        let result = use_case.execute(command(stale)).await;
+        assert!(matches!(result, Err(crate::shared::AppError::ClientHashMismatch(_))));
+
+        use_case.execute(command(params.clone())).await.unwrap();
+        let user = repo.find_by_email(&email).await.unwrap().unwrap();
+        assert_eq!(user.client_hash_params(), Some(params));
+        assert!(user.verify_password("password123").unwrap());
+    }
 }
@@ -0,0 +1,585 @@
+use super::{PasswordHistory, SecurityNotifier, SendLimits, SendThrottle};
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::{
+    Email, NotificationCategory, PasswordHash, PasswordHasher, PasswordPolicy,
+    PasswordResetToken,
+};
+use crate::moduls::auth::infra::{
+    PasswordResetRepository, SessionRepository, TokenRepository, UserRepository,
+};
+use crate::shared::{mailer::Mailer, types::*, AppError, AppResult};
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+/// Address a reset link is sent to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResetDelivery {
+    /// The login email
+    #[default]
+    Primary,
+    /// The recovery email, if verified
+    Recovery,
+}
+
+/// Forgot password command (DTO)
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ForgotPasswordCommand {
+    /// Login email of the account
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+    #[serde(default)]
+    pub deliver_to: ResetDelivery,
+    /// Tenant of the request (from `TenantContext`, never the body)
+    #[serde(skip)]
+    pub tenant_id: Option<OrganizationId>,
+    /// Client address, for throttling
+    #[serde(skip)]
+    pub ip_address: Option<String>,
+}
+
+/// Reset password command (DTO)
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ResetPasswordCommand {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+
+    /// Checked against `PasswordPolicy` by the use case
+    pub new_password: String,
+
+    #[serde(default)]
+    pub new_password_confirmation: Option<String>,
+}
+
+/// Configuration for the reset flow
+#[derive(Debug, Clone)]
+pub struct ResetPasswordConfig {
+    /// Page the emailed link points to; the token is appended as `?token=`
+    pub reset_url: String,
+    pub token_ttl_seconds: i64,
+    pub max_password_length: usize,
+    pub password_policy: PasswordPolicy,
+    pub password_hasher: PasswordHasher,
+    pub send_limits: SendLimits,
+}
+
+/// Use case for recovering a forgotten password
+///
+/// Business Logic:
+/// 1. `request_reset` issues a single-use token and emails a reset link
+///    to the user's login or verified recovery email; unknown emails,
+///    missing recovery emails and throttled requests are silently ignored
+///    (no user enumeration)
+/// 2. `reset_password` redeems the token, sets the new password and logs
+///    the user out everywhere (sessions, JWTs, other reset tokens)
+pub struct ResetPasswordUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    reset_repo: Arc<dyn PasswordResetRepository>,
+    session_repo: Arc<dyn SessionRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    notifier: SecurityNotifier,
+    audit_log: Arc<AuditLog>,
+    throttle: SendThrottle,
+    config: ResetPasswordConfig,
+    password_history: Option<PasswordHistory>,
+}
+
+impl ResetPasswordUseCase {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        reset_repo: Arc<dyn PasswordResetRepository>,
+        session_repo: Arc<dyn SessionRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        mailer: Arc<dyn Mailer>,
+        audit_log: Arc<AuditLog>,
+        config: ResetPasswordConfig,
+    ) -> Self {
+        Self {
+            user_repo,
+            reset_repo,
+            session_repo,
+            token_repo,
+            notifier: SecurityNotifier::new(mailer),
+            audit_log,
+            throttle: SendThrottle::new(config.send_limits),
+            config,
+            password_history: None,
+        }
+    }
+
+    /// Refuse the user's recent passwords (`PasswordPolicy::history`)
+    pub fn with_password_history(mut self, password_history: PasswordHistory) -> Self {
+        self.password_history = Some(password_history);
+        self
+    }
+
+    /// Issue a reset token for the account behind `email`, if any
+    ///
+    /// Succeeds whether or not the account exists, so callers can answer
+    /// the same way in both cases.
+    ///
+    /// # Errors
+    /// - Database errors
+    pub async fn request_reset(&self, cmd: ForgotPasswordCommand) -> AppResult<()> {
+        let Ok(email) = Email::new(&cmd.email) else {
+            return Ok(());
+        };
+
+        // Counted before the lookup, so unknown addresses are throttled alike
+        if !self.throttle.try_acquire(email.as_str(), cmd.ip_address.as_deref()) {
+            tracing::warn!("Password reset request throttled (ip: {:?})", cmd.ip_address);
+            return Ok(());
+        }
+
+        let tenant_user = match cmd.tenant_id {
+            Some(tenant_id) => self.user_repo.find_by_email_in_tenant(&email, tenant_id).await?,
+            None => None,
+        };
+        let user = match tenant_user {
+            Some(user) => Some(user),
+            None => self.user_repo.find_by_email(&email).await?,
+        };
+
+        let Some(user) = user.filter(|u| u.is_active) else {
+            return Ok(());
+        };
+
+        let recipient = match cmd.deliver_to {
+            ResetDelivery::Primary => &user.email,
+            ResetDelivery::Recovery => match user.verified_recovery_email() {
+                Some(email) => email,
+                None => return Ok(()),
+            },
+        };
+
+        let (token, plain) = PasswordResetToken::issue(user.id, self.config.token_ttl_seconds);
+        self.reset_repo.save(&token).await?;
+
+        // Delivery problems must not reveal that the account exists
+        let body = format!(
+            "Hi {},\n\n\
+             Use the link below to choose a new password. It expires in {} minutes.\n\n\
+             {}?token={}\n\n\
+             If you didn't ask for a reset, you can ignore this email.\n",
+            user.name,
+            self.config.token_ttl_seconds / 60,
+            self.config.reset_url,
+            plain
+        );
+        let sent = self
+            .notifier
+            .notify_at(
+                &user,
+                recipient,
+                NotificationCategory::PasswordReset,
+                "Reset your password",
+                &body,
+            )
+            .await;
+        if let Err(e) = sent {
+            tracing::error!("Failed to deliver password reset token to user {}: {}", user.id, e);
+        }
+
+        Ok(())
+    }
+
+    /// Redeem a reset token and set a new password
+    ///
+    /// # Errors
+    /// - Validation if the token is unknown, expired or already used
+    /// - Validation if the new password is invalid or the confirmation differs
+    /// - Database errors
+    pub async fn reset_password(&self, cmd: ResetPasswordCommand) -> AppResult<()> {
+        // 1. Reject oversized or weak passwords before the token is spent
+        PasswordHash::ensure_max_length(&cmd.new_password, self.config.max_password_length)?;
+        self.config.password_policy.enforce(&cmd.new_password, "new_password")?;
+
+        if let Some(ref confirmation) = cmd.new_password_confirmation {
+            if &cmd.new_password != confirmation {
+                return Err(AppError::validation("Passwords do not match"));
+            }
+        }
+
+        // 2. Look up the token and its user
+        let token = self
+            .reset_repo
+            .find_by_hash(&PasswordResetToken::hash(&cmd.token))
+            .await?
+            .filter(|t| t.is_usable())
+            .ok_or_else(invalid_token)?;
+
+        let mut user = self
+            .user_repo
+            .find_by_id(token.user_id)
+            .await?
+            .ok_or_else(invalid_token)?;
+
+        // 3. Refuse recent passwords, still before the token is spent
+        if let Some(history) = &self.password_history {
+            history
+                .ensure_not_reused(&user, &cmd.new_password, &self.config.password_policy, "new_password")
+                .await?;
+        }
+
+        // 4. Redeem the token (atomically, so it works only once)
+        if !self.reset_repo.mark_used(token.id).await? {
+            return Err(invalid_token());
+        }
+
+        // 5. Change password (also moves the user's token watermark),
+        //    remembering the one being replaced
+        if let Some(history) = &self.password_history {
+            history.remember(&user, &self.config.password_policy).await?;
+        }
+        user.change_password(&cmd.new_password, &self.config.password_hasher)?;
+        self.user_repo.update(&user).await?;
+
+        // 6. Log out everywhere and drop any other outstanding reset tokens
+        self.session_repo.delete_by_user_id(user.id).await?;
+        self.token_repo.revoke_all_user_tokens(user.id).await?;
+        self.reset_repo.invalidate_user_tokens(user.id).await?;
+
+        self.audit_log
+            .record(
+                AuditEvent::new(AuditAction::PasswordReset, Some(user.id))
+                    .with_tenant(user.tenant_id),
+            )
+            .await;
+
+        Ok(())
+    }
+}
+
+fn invalid_token() -> AppError {
+    AppError::validation("Invalid or expired reset token")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::token_pair::TokenType;
+    use crate::moduls::auth::domain::{JwtToken, Session, User};
+    use crate::moduls::auth::domain::PasswordRequirement;
+    use crate::moduls::auth::infra::in_memory::{
+        InMemoryPasswordHistoryRepository, InMemoryPasswordResetRepository,
+        InMemorySessionRepository, InMemoryTokenRepository, InMemoryUserRepository,
+    };
+    use crate::shared::mailer::MockMailer;
+
+    struct Fixture {
+        user_id: UserId,
+        user_repo: Arc<InMemoryUserRepository>,
+        reset_repo: Arc<InMemoryPasswordResetRepository>,
+        session_repo: Arc<InMemorySessionRepository>,
+        token_repo: Arc<InMemoryTokenRepository>,
+        mailer: Arc<MockMailer>,
+        use_case: ResetPasswordUseCase,
+    }
+
+    fn fixture() -> Fixture {
+        fixture_with_limit(5)
+    }
+
+    fn fixture_with_limit(per_email: u32) -> Fixture {
+        fixture_with(per_email, PasswordPolicy::default())
+    }
+
+    fn fixture_with_history(history: usize) -> Fixture {
+        fixture_with(
+            5,
+            PasswordPolicy {
+                history: Some(history),
+                ..PasswordPolicy::default()
+            },
+        )
+    }
+
+    fn fixture_with(per_email: u32, password_policy: PasswordPolicy) -> Fixture {
+        let user = User::new(
+            Email::new("reset@example.com").unwrap(),
+            "oldpassword123",
+            "Reset User".to_string(),
+        )
+        .unwrap();
+        let user_id = user.id;
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(user));
+        let reset_repo = Arc::new(InMemoryPasswordResetRepository::default());
+        let session_repo = Arc::new(InMemorySessionRepository::default());
+        let token_repo = Arc::new(InMemoryTokenRepository::default());
+        let mailer = Arc::new(MockMailer::default());
+        let use_case = ResetPasswordUseCase::new(
+            user_repo.clone(),
+            reset_repo.clone(),
+            session_repo.clone(),
+            token_repo.clone(),
+            mailer.clone(),
+            Arc::new(AuditLog::for_tests()),
+            ResetPasswordConfig {
+                reset_url: "http://app.test/reset-password".to_string(),
+                token_ttl_seconds: 1800,
+                max_password_length: PasswordHash::DEFAULT_MAX_LENGTH,
+                password_policy,
+                password_hasher: PasswordHasher::Bcrypt { cost: 4 },
+                send_limits: SendLimits {
+                    per_email,
+                    per_ip: 10,
+                    window_seconds: 300,
+                },
+            },
+        )
+        .with_password_history(PasswordHistory::new(Arc::new(
+            InMemoryPasswordHistoryRepository::default(),
+        )));
+
+        Fixture {
+            user_id,
+            user_repo,
+            reset_repo,
+            session_repo,
+            token_repo,
+            mailer,
+            use_case,
+        }
+    }
+
+    fn forgot(email: &str) -> ForgotPasswordCommand {
+        ForgotPasswordCommand {
+            email: email.to_string(),
+            deliver_to: ResetDelivery::Primary,
+            tenant_id: None,
+            ip_address: None,
+        }
+    }
+
+    fn reset(token: &str) -> ResetPasswordCommand {
+        reset_to(token, "newpassword123")
+    }
+
+    fn reset_to(token: &str, new_password: &str) -> ResetPasswordCommand {
+        ResetPasswordCommand {
+            token: token.to_string(),
+            new_password: new_password.to_string(),
+            new_password_confirmation: None,
+        }
+    }
+
+    fn assert_reused(result: AppResult<()>) {
+        let Err(AppError::PasswordPolicy(violation)) = result else {
+            panic!("expected a password policy violation");
+        };
+        assert_eq!(violation.field, "new_password");
+        assert_eq!(violation.failed, vec![PasswordRequirement::NotReused]);
+    }
+
+    fn forgot_via_recovery(email: &str) -> ForgotPasswordCommand {
+        ForgotPasswordCommand {
+            deliver_to: ResetDelivery::Recovery,
+            ..forgot(email)
+        }
+    }
+
+    async fn issued_token(f: &Fixture) -> String {
+        f.use_case.request_reset(forgot("reset@example.com")).await.unwrap();
+        let mail = f.mailer.last().unwrap();
+        assert_eq!(mail.to, "reset@example.com");
+        token_in(&mail.body)
+    }
+
+    fn token_in(body: &str) -> String {
+        let link = body
+            .lines()
+            .find(|line| line.starts_with("http://app.test/reset-password?token="))
+            .unwrap();
+        link.rsplit('=').next().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_request_stores_hashed_token() {
+        let f = fixture();
+
+        let plain = issued_token(&f).await;
+
+        let tokens = f.reset_repo.tokens.lock().unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].user_id, f.user_id);
+        assert_ne!(tokens[0].token_hash, plain);
+        assert_eq!(tokens[0].token_hash, PasswordResetToken::hash(&plain));
+    }
+
+    #[tokio::test]
+    async fn test_request_for_unknown_email_succeeds_silently() {
+        let f = fixture();
+
+        f.use_case.request_reset(forgot("nobody@example.com")).await.unwrap();
+
+        assert!(f.reset_repo.tokens.lock().unwrap().is_empty());
+        assert_eq!(f.mailer.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_request_via_verified_recovery_email() {
+        let f = fixture();
+        let mut user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        user.set_recovery_email(Some(Email::new("backup@example.com").unwrap())).unwrap();
+        user.verify_recovery_email();
+        f.user_repo.update(&user).await.unwrap();
+
+        f.use_case.request_reset(forgot_via_recovery("reset@example.com")).await.unwrap();
+
+        let mail = f.mailer.last().unwrap();
+        assert_eq!(mail.to, "backup@example.com");
+        f.use_case.reset_password(reset(&token_in(&mail.body))).await.unwrap();
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.verify_password("newpassword123").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reset_is_sent_whatever_the_preferences() {
+        let f = fixture();
+        let mut user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        user.notification_preferences.new_device_login = false;
+        user.notification_preferences.password_changed = false;
+        f.user_repo.update(&user).await.unwrap();
+
+        issued_token(&f).await;
+
+        assert_eq!(f.mailer.count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_request_via_unverified_recovery_email_succeeds_silently() {
+        let f = fixture();
+        let mut user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        user.set_recovery_email(Some(Email::new("backup@example.com").unwrap())).unwrap();
+        f.user_repo.update(&user).await.unwrap();
+
+        f.use_case.request_reset(forgot_via_recovery("reset@example.com")).await.unwrap();
+
+        assert!(f.reset_repo.tokens.lock().unwrap().is_empty());
+        assert_eq!(f.mailer.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_requests_are_throttled() {
+        let f = fixture_with_limit(1);
+
+        for _ in 0..3 {
+            f.use_case.request_reset(forgot("reset@example.com")).await.unwrap();
+        }
+
+        assert_eq!(f.mailer.count(), 1);
+        assert_eq!(f.reset_repo.tokens.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reset_changes_password_and_logs_out_everywhere() {
+        let f = fixture();
+        f.session_repo
+            .save(&Session::new(f.user_id, None, None, 3600))
+            .await
+            .unwrap();
+        f.token_repo
+            .save(&JwtToken {
+                id: TokenId::new(),
+                user_id: f.user_id,
+                token_type: TokenType::Refresh,
+                jti: TokenId::new(),
+                family_id: new_id(),
+                session_id: None,
+                expires_at: now() + chrono::Duration::seconds(3600),
+                revoked: false,
+                revoked_at: None,
+                created_at: now(),
+            })
+            .await
+            .unwrap();
+        let first = issued_token(&f).await;
+        let plain = issued_token(&f).await;
+
+        f.use_case.reset_password(reset(&plain)).await.unwrap();
+
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.verify_password("newpassword123").unwrap());
+        assert!(user.tokens_valid_after.is_some());
+        assert!(f.session_repo.find_by_user_id(f.user_id).await.unwrap().is_none());
+        assert!(f.token_repo.tokens.lock().unwrap().iter().all(|t| t.revoked));
+        // The older outstanding token was invalidated too
+        assert!(f.use_case.reset_password(reset(&first)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reused_token_is_rejected() {
+        let f = fixture();
+        let plain = issued_token(&f).await;
+        f.use_case.reset_password(reset(&plain)).await.unwrap();
+
+        let result = f.use_case.reset_password(reset(&plain)).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_is_rejected() {
+        let f = fixture();
+        let plain = issued_token(&f).await;
+        f.reset_repo.tokens.lock().unwrap()[0].expires_at = now() - chrono::Duration::seconds(1);
+
+        let result = f.use_case.reset_password(reset(&plain)).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.verify_password("oldpassword123").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_token_is_rejected() {
+        let f = fixture();
+
+        let result = f.use_case.reset_password(reset("not-a-token")).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_first_reset_to_current_password_is_rejected() {
+        // No history yet: only the current password counts
+        let f = fixture_with_history(3);
+        let plain = issued_token(&f).await;
+
+        assert_reused(f.use_case.reset_password(reset_to(&plain, "oldpassword123")).await);
+
+        // The token wasn't spent
+        f.use_case.reset_password(reset(&plain)).await.unwrap();
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.verify_password("newpassword123").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reset_to_recent_password_is_rejected() {
+        let f = fixture_with_history(3);
+        let plain = issued_token(&f).await;
+        f.use_case.reset_password(reset(&plain)).await.unwrap();
+
+        let plain = issued_token(&f).await;
+        assert_reused(f.use_case.reset_password(reset_to(&plain, "oldpassword123")).await);
+        assert_reused(f.use_case.reset_password(reset_to(&plain, "newpassword123")).await);
+
+        f.use_case.reset_password(reset_to(&plain, "novelpassword123")).await.unwrap();
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.verify_password("novelpassword123").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_password_older_than_history_can_be_reused() {
+        let f = fixture_with_history(2);
+        let plain = issued_token(&f).await;
+        f.use_case.reset_password(reset(&plain)).await.unwrap();
+        let plain = issued_token(&f).await;
+        f.use_case.reset_password(reset_to(&plain, "novelpassword123")).await.unwrap();
+
+        let plain = issued_token(&f).await;
+        f.use_case.reset_password(reset_to(&plain, "oldpassword123")).await.unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::infra::{SessionRepository, TokenRepository};
+use crate::moduls::organization::infra::OrganizationRepository;
+use crate::shared::{types::*, AppError, AppResult};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Credentials invalidated by a tenant-wide revocation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RevokedCredentials {
+    pub tokens_revoked: u64,
+    pub sessions_revoked: u64,
+}
+
+/// Revoke Tenant Credentials Use Case
+/// Signs every user of a tenant out at once (security incident response)
+///
+/// Business Logic:
+/// 1. The tenant must exist
+/// 2. Revoke the live JWT tokens and delete the live sessions of the
+///    tenant's users: those scoped to it and its members. Tokens and
+///    sessions aren't tenant-scoped, so members lose their credentials for
+///    other tenants too
+pub struct RevokeTenantCredentialsUseCase {
+    org_repo: Arc<dyn OrganizationRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    session_repo: Arc<dyn SessionRepository>,
+    audit_log: Arc<AuditLog>,
+    /// Rows updated per statement
+    batch_size: u32,
+}
+
+impl RevokeTenantCredentialsUseCase {
+    pub fn new(
+        org_repo: Arc<dyn OrganizationRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        session_repo: Arc<dyn SessionRepository>,
+        audit_log: Arc<AuditLog>,
+        batch_size: u32,
+    ) -> Self {
+        Self {
+            org_repo,
+            token_repo,
+            session_repo,
+            audit_log,
+            batch_size,
+        }
+    }
+
+    /// Execute the use case on behalf of `actor_id`
+    ///
+    /// # Errors
+    /// - NotFound if the tenant doesn't exist
+    /// - Database errors
+    pub async fn execute(
+        &self,
+        actor_id: UserId,
+        tenant_id: OrganizationId,
+    ) -> AppResult<RevokedCredentials> {
+        // 1. Check the tenant
+        self.org_repo
+            .find_by_id(tenant_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Organization not found"))?;
+
+        // 2. Revoke
+        let revoked = RevokedCredentials {
+            tokens_revoked: self
+                .token_repo
+                .revoke_tenant_tokens(tenant_id, self.batch_size)
+                .await?,
+            sessions_revoked: self
+                .session_repo
+                .delete_by_tenant(tenant_id, self.batch_size)
+                .await?,
+        };
+
+        tracing::warn!(
+            "User {} revoked all credentials of tenant {}: {} token(s), {} session(s)",
+            actor_id,
+            tenant_id,
+            revoked.tokens_revoked,
+            revoked.sessions_revoked
+        );
+        self.audit_log
+            .record(
+                AuditEvent::new(AuditAction::TenantCredentialsRevoked, Some(actor_id))
+                    .with_tenant(Some(tenant_id)),
+            )
+            .await;
+
+        Ok(revoked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{JwtKeys, Session, TokenPair};
+    use crate::moduls::auth::infra::in_memory::{
+        InMemorySessionRepository, InMemoryTokenRepository,
+    };
+    use crate::moduls::organization::domain::Organization;
+    use crate::moduls::organization::infra::in_memory::InMemoryOrganizationRepository;
+
+    #[tokio::test]
+    async fn test_only_the_tenants_credentials_are_revoked() {
+        let org_repo = Arc::new(InMemoryOrganizationRepository::default());
+        let acme = org_repo
+            .save(&Organization::new("Acme".to_string(), "acme").unwrap())
+            .await
+            .unwrap();
+        let (insider, outsider) = (UserId::new(), UserId::new());
+        let tenant_users = vec![(acme.id, insider)];
+        let token_repo = Arc::new(InMemoryTokenRepository {
+            tenant_users: tenant_users.clone(),
+            ..Default::default()
+        });
+        let session_repo = Arc::new(InMemorySessionRepository {
+            tenant_users,
+            ..Default::default()
+        });
+        let keys = JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars");
+        for user_id in [insider, outsider] {
+            let (_, access, refresh) = TokenPair::generate(user_id, &keys, 900, 604800).unwrap();
+            token_repo.save(&access).await.unwrap();
+            token_repo.save(&refresh).await.unwrap();
+            session_repo.save(&Session::new(user_id, None, None, 3600)).await.unwrap();
+        }
+        let use_case = RevokeTenantCredentialsUseCase::new(
+            org_repo,
+            token_repo.clone(),
+            session_repo.clone(),
+            Arc::new(AuditLog::for_tests()),
+            1000,
+        );
+
+        let revoked = use_case.execute(UserId::new(), acme.id).await.unwrap();
+
+        assert_eq!(
+            revoked,
+            RevokedCredentials {
+                tokens_revoked: 2,
+                sessions_revoked: 1
+            }
+        );
+        assert!(session_repo.find_by_user_id(outsider).await.unwrap().is_some());
+        let tokens = token_repo.tokens.lock().unwrap();
+        assert!(tokens.iter().all(|t| t.revoked == (t.user_id == insider)));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tenant_is_not_found() {
+        let use_case = RevokeTenantCredentialsUseCase::new(
+            Arc::new(InMemoryOrganizationRepository::default()),
+            Arc::new(InMemoryTokenRepository::default()),
+            Arc::new(InMemorySessionRepository::default()),
+            Arc::new(AuditLog::for_tests()),
+            1000,
+        );
+
+        let result = use_case.execute(UserId::new(), OrganizationId::new()).await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
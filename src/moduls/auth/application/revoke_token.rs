@@ -0,0 +1,188 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::{JwtKeys, TokenPair};
+use crate::moduls::auth::infra::TokenRepository;
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Token revocation request (RFC 7009)
+#[derive(Debug, serde::Deserialize, Validate)]
+pub struct RevokeTokenCommand {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+    /// `access_token` or `refresh_token`; only a hint, the token's own
+    /// type claim decides
+    #[serde(default)]
+    pub token_type_hint: Option<String>,
+}
+
+/// Revoke Token Use Case
+/// Revokes a single access or refresh token (RFC 7009)
+///
+/// Business Logic:
+/// 1. Decode the token; invalid, expired and unknown tokens need no
+///    revoking, so they succeed without doing anything
+/// 2. Only the token's owner, or a privileged caller, may revoke it
+/// 3. An access token is revoked alone; a refresh token together with
+///    every token descending from the same login (RFC 7009 §2.1)
+pub struct RevokeTokenUseCase {
+    token_repo: Arc<dyn TokenRepository>,
+    jwt_keys: Arc<JwtKeys>,
+    /// Refresh tokens are still accepted this long after expiry
+    grace_seconds: u64,
+    audit_log: Arc<AuditLog>,
+}
+
+impl RevokeTokenUseCase {
+    pub fn new(
+        token_repo: Arc<dyn TokenRepository>,
+        jwt_keys: Arc<JwtKeys>,
+        grace_seconds: u64,
+        audit_log: Arc<AuditLog>,
+    ) -> Self {
+        Self {
+            token_repo,
+            jwt_keys,
+            grace_seconds,
+            audit_log,
+        }
+    }
+
+    /// Execute the use case on behalf of `requester`
+    ///
+    /// # Errors
+    /// - Authorization if the token belongs to another user and the
+    ///   requester isn't `privileged`
+    /// - Database errors
+    pub async fn execute(
+        &self,
+        requester: UserId,
+        privileged: bool,
+        cmd: RevokeTokenCommand,
+    ) -> AppResult<()> {
+        // 1. Tokens that don't decode are already unusable
+        let Ok(claims) = TokenPair::decode_with_leeway(&cmd.token, &self.jwt_keys, self.grace_seconds)
+        else {
+            return Ok(());
+        };
+        let Ok(jti) = claims.jti.parse::<TokenId>() else {
+            return Ok(());
+        };
+        let Some(stored) = self.token_repo.find_by_jti(jti).await? else {
+            return Ok(());
+        };
+
+        // 2. Check ownership
+        if stored.user_id != requester && !privileged {
+            return Err(AppError::authorization("Token belongs to another user"));
+        }
+        if stored.is_revoked() {
+            return Ok(());
+        }
+
+        // 3. Revoke
+        if claims.token_type == "refresh" {
+            self.token_repo.revoke_family(stored.family_id).await?;
+        } else {
+            self.token_repo.revoke(jti).await?;
+        }
+
+        self.audit_log
+            .record(AuditEvent::new(AuditAction::TokenRevoked, Some(stored.user_id)))
+            .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::infra::in_memory::InMemoryTokenRepository;
+
+    struct Fixture {
+        use_case: RevokeTokenUseCase,
+        token_repo: Arc<InMemoryTokenRepository>,
+        user_id: UserId,
+        pair: TokenPair,
+    }
+
+    const TEST_SECRET: &str = "test_secret_key_for_jwt_signing_minimum_32_chars";
+
+    async fn fixture() -> Fixture {
+        let keys = Arc::new(JwtKeys::hmac(TEST_SECRET));
+        let token_repo = Arc::new(InMemoryTokenRepository::default());
+        let user_id = UserId::new();
+        let (pair, access, refresh) = TokenPair::generate(user_id, &keys, 900, 604800).unwrap();
+        token_repo.save(&access).await.unwrap();
+        token_repo.save(&refresh).await.unwrap();
+
+        Fixture {
+            use_case: RevokeTokenUseCase::new(
+                token_repo.clone(),
+                keys,
+                0,
+                Arc::new(AuditLog::for_tests()),
+            ),
+            token_repo,
+            user_id,
+            pair,
+        }
+    }
+
+    fn command(token: &str) -> RevokeTokenCommand {
+        RevokeTokenCommand {
+            token: token.to_string(),
+            token_type_hint: None,
+        }
+    }
+
+    fn revoked(f: &Fixture) -> Vec<bool> {
+        f.token_repo.tokens.lock().unwrap().iter().map(|t| t.revoked).collect()
+    }
+
+    #[tokio::test]
+    async fn test_revoking_access_token_keeps_refresh_token() {
+        let f = fixture().await;
+
+        f.use_case.execute(f.user_id, false, command(&f.pair.access_token)).await.unwrap();
+
+        assert_eq!(revoked(&f), vec![true, false]);
+    }
+
+    #[tokio::test]
+    async fn test_revoking_refresh_token_revokes_family() {
+        let f = fixture().await;
+
+        f.use_case.execute(f.user_id, false, command(&f.pair.refresh_token)).await.unwrap();
+
+        assert_eq!(revoked(&f), vec![true, true]);
+    }
+
+    #[tokio::test]
+    async fn test_other_users_token_needs_privilege() {
+        let f = fixture().await;
+
+        let result = f.use_case.execute(UserId::new(), false, command(&f.pair.access_token)).await;
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+        assert_eq!(revoked(&f), vec![false, false]);
+
+        f.use_case.execute(UserId::new(), true, command(&f.pair.access_token)).await.unwrap();
+        assert_eq!(revoked(&f), vec![true, false]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_tokens_succeed() {
+        let f = fixture().await;
+        let other_keys = JwtKeys::hmac("another_secret_key_for_jwt_signing_32_chars");
+        let (forged, _, _) = TokenPair::generate(f.user_id, &other_keys, 900, 604800).unwrap();
+        // Signed, but never stored
+        let (unknown, _, _) =
+            TokenPair::generate(f.user_id, &JwtKeys::hmac(TEST_SECRET), 900, 604800).unwrap();
+
+        for token in ["not-a-jwt", &forged.access_token, &unknown.access_token] {
+            assert!(f.use_case.execute(f.user_id, false, command(token)).await.is_ok());
+        }
+        assert_eq!(revoked(&f), vec![false, false]);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::moduls::auth::domain::{Email, NotificationCategory, User};
+use crate::shared::{mailer::Mailer, AppResult};
+use std::sync::Arc;
+
+/// Sends security emails, skipping categories the user disabled
+///
+/// Mandatory categories (`NotificationCategory::is_mandatory`) are always
+/// sent.
+#[derive(Clone)]
+pub struct SecurityNotifier {
+    mailer: Arc<dyn Mailer>,
+}
+
+impl SecurityNotifier {
+    pub fn new(mailer: Arc<dyn Mailer>) -> Self {
+        Self { mailer }
+    }
+
+    /// Email `user` at their login email, if they receive `category`
+    pub async fn notify(
+        &self,
+        user: &User,
+        category: NotificationCategory,
+        subject: &str,
+        body: &str,
+    ) -> AppResult<()> {
+        self.notify_at(user, &user.email, category, subject, body).await
+    }
+
+    /// Email `user` at `to`, if they receive `category`
+    ///
+    /// # Errors
+    /// - Internal if delivery fails
+    pub async fn notify_at(
+        &self,
+        user: &User,
+        to: &Email,
+        category: NotificationCategory,
+        subject: &str,
+        body: &str,
+    ) -> AppResult<()> {
+        if !user.notification_preferences.allows(category) {
+            tracing::debug!("User {} opted out of {:?} emails", user.id, category);
+            return Ok(());
+        }
+
+        self.mailer.send(to, subject, body).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::mailer::MockMailer;
+
+    fn user_without_new_device_emails() -> User {
+        let mut user = User::new(
+            Email::new("notify@example.com").unwrap(),
+            "password123",
+            "Notify User".to_string(),
+        )
+        .unwrap();
+        user.notification_preferences
+            .set(NotificationCategory::NewDeviceLogin, false)
+            .unwrap();
+        user
+    }
+
+    #[tokio::test]
+    async fn test_disabled_category_is_skipped_but_mandatory_is_sent() {
+        let mailer = Arc::new(MockMailer::default());
+        let notifier = SecurityNotifier::new(mailer.clone());
+        let user = user_without_new_device_emails();
+
+        notifier
+            .notify(&user, NotificationCategory::NewDeviceLogin, "New login", "...")
+            .await
+            .unwrap();
+        assert_eq!(mailer.count(), 0);
+
+        notifier
+            .notify(&user, NotificationCategory::PasswordReset, "Reset your password", "...")
+            .await
+            .unwrap();
+        assert_eq!(mailer.count(), 1);
+        assert_eq!(mailer.last().unwrap().subject, "Reset your password");
+    }
+
+    #[tokio::test]
+    async fn test_enabled_category_is_sent_to_given_address() {
+        let mailer = Arc::new(MockMailer::default());
+        let notifier = SecurityNotifier::new(mailer.clone());
+        let user = user_without_new_device_emails();
+        let backup = Email::new("backup@example.com").unwrap();
+
+        notifier
+            .notify_at(&user, &backup, NotificationCategory::PasswordChanged, "Changed", "...")
+            .await
+            .unwrap();
+
+        assert_eq!(mailer.last().unwrap().to, "backup@example.com");
+    }
+}
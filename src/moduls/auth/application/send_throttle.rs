@@ -0,0 +1,126 @@
+use crate::shared::types::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Limits on account emails (password reset, email verification)
+#[derive(Debug, Clone, Copy)]
+pub struct SendLimits {
+    /// Emails allowed per address within the window
+    pub per_email: u32,
+    /// Emails allowed per client IP within the window
+    pub per_ip: u32,
+    pub window_seconds: i64,
+}
+
+/// Sliding-window throttle for account emails
+///
+/// Keyed by recipient address and by client IP; a send is allowed only
+/// while both are under their limit, and only allowed sends count. State
+/// is per process, so with several instances the limits apply to each.
+pub struct SendThrottle {
+    limits: SendLimits,
+    sends: Mutex<HashMap<String, Vec<Timestamp>>>,
+}
+
+/// Keys kept before expired entries are swept from the whole map
+const SWEEP_THRESHOLD: usize = 10_000;
+
+impl SendThrottle {
+    pub fn new(limits: SendLimits) -> Self {
+        Self {
+            limits,
+            sends: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a send to `email` from `ip` if both are under their limit
+    ///
+    /// Returns false (recording nothing) when the send must be skipped.
+    pub fn try_acquire(&self, email: &str, ip: Option<&str>) -> bool {
+        self.try_acquire_at(email, ip, now())
+    }
+
+    fn try_acquire_at(&self, email: &str, ip: Option<&str>, at: Timestamp) -> bool {
+        let since = at - chrono::Duration::seconds(self.limits.window_seconds);
+        let mut sends = self.sends.lock().unwrap_or_else(|e| e.into_inner());
+
+        if sends.len() > SWEEP_THRESHOLD {
+            sends.retain(|_, times| {
+                times.retain(|t| *t > since);
+                !times.is_empty()
+            });
+        }
+
+        let mut keys = vec![(format!("email:{}", email.to_lowercase()), self.limits.per_email)];
+        if let Some(ip) = ip {
+            keys.push((format!("ip:{}", ip), self.limits.per_ip));
+        }
+
+        let allowed = keys.iter().all(|(key, limit)| {
+            let recent = sends
+                .get(key)
+                .map_or(0, |times| times.iter().filter(|t| **t > since).count());
+            recent < *limit as usize
+        });
+
+        if allowed {
+            for (key, _) in keys {
+                let times = sends.entry(key).or_default();
+                times.retain(|t| *t > since);
+                times.push(at);
+            }
+        }
+
+        allowed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle(per_email: u32, per_ip: u32) -> SendThrottle {
+        SendThrottle::new(SendLimits {
+            per_email,
+            per_ip,
+            window_seconds: 300,
+        })
+    }
+
+    #[test]
+    fn test_per_email_limit() {
+        let throttle = throttle(1, 10);
+
+        assert!(throttle.try_acquire("a@example.com", Some("10.0.0.1")));
+        assert!(!throttle.try_acquire("A@Example.com", Some("10.0.0.2")));
+        assert!(throttle.try_acquire("b@example.com", Some("10.0.0.1")));
+    }
+
+    #[test]
+    fn test_per_ip_limit() {
+        let throttle = throttle(5, 2);
+
+        assert!(throttle.try_acquire("a@example.com", Some("10.0.0.1")));
+        assert!(throttle.try_acquire("b@example.com", Some("10.0.0.1")));
+        assert!(!throttle.try_acquire("c@example.com", Some("10.0.0.1")));
+        assert!(throttle.try_acquire("c@example.com", Some("10.0.0.2")));
+    }
+
+    #[test]
+    fn test_window_slides() {
+        let throttle = throttle(1, 10);
+        let start = now();
+
+        assert!(throttle.try_acquire_at("a@example.com", None, start));
+        assert!(!throttle.try_acquire_at(
+            "a@example.com",
+            None,
+            start + chrono::Duration::seconds(299)
+        ));
+        assert!(throttle.try_acquire_at(
+            "a@example.com",
+            None,
+            start + chrono::Duration::seconds(301)
+        ));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::moduls::auth::domain::token_pair::Claims;
+use crate::moduls::auth::domain::AccountStatus;
+use crate::moduls::auth::infra::{TokenWatermarkRepository, UserRepository};
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// "Tokens valid after" watermarks
+///
+/// The global watermark combines configuration (`TOKENS_VALID_AFTER`) with
+/// the one stored in the database; the later of the two applies. Moving
+/// it to now logs every user out without per-token revocation.
+///
+/// Each user additionally has their own watermark (`users.tokens_valid_after`),
+/// moved on password change or when logging out of all devices.
+///
+/// The stored global watermark is cached for `cache_ttl`, sparing a query
+/// per request. Bumps through this process refresh the cache at once; one
+/// made by another instance goes unnoticed for at most `cache_ttl`.
+pub struct TokenWatermark {
+    repo: Arc<dyn TokenWatermarkRepository>,
+    user_repo: Arc<dyn UserRepository>,
+    configured: Option<Timestamp>,
+    cache_ttl: Duration,
+    /// Stored watermark and when it was read
+    cached: RwLock<Option<(Option<Timestamp>, Instant)>>,
+}
+
+impl TokenWatermark {
+    pub fn new(
+        repo: Arc<dyn TokenWatermarkRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        configured: Option<Timestamp>,
+        cache_ttl: Duration,
+    ) -> Self {
+        Self {
+            repo,
+            user_repo,
+            configured,
+            cache_ttl,
+            cached: RwLock::new(None),
+        }
+    }
+
+    /// Get the effective global watermark, if any
+    pub async fn valid_after(&self) -> AppResult<Option<Timestamp>> {
+        Ok(self.stored().await?.max(self.configured))
+    }
+
+    /// Stored watermark, from the cache while it is fresh
+    async fn stored(&self) -> AppResult<Option<Timestamp>> {
+        let cached = *self.cached.read().unwrap();
+        if let Some((stored, read_at)) = cached {
+            if read_at.elapsed() < self.cache_ttl {
+                return Ok(stored);
+            }
+        }
+
+        let stored = self.repo.get().await?;
+        *self.cached.write().unwrap() = Some((stored, Instant::now()));
+        Ok(stored)
+    }
+
+    /// Reject tokens issued before the global or the user's watermark, and
+    /// those of expired accounts (expiry needs no revocation to take effect)
+    pub async fn check(&self, claims: &Claims) -> AppResult<()> {
+        if let Some(valid_after) = self.valid_after().await? {
+            if claims.issued_before(valid_after) {
+                return Err(AppError::authentication("Token has been invalidated"));
+            }
+        }
+
+        let user_id: UserId = claims
+            .sub
+            .parse()
+            .map_err(|_| AppError::authentication("Invalid user ID in token"))?;
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::authentication("User not found"))?;
+        if user.status() == AccountStatus::Expired {
+            return Err(AccountStatus::Expired.login_error());
+        }
+
+        if let Some(valid_after) = user.tokens_valid_after {
+            if claims.issued_before(valid_after) {
+                return Err(AppError::authentication("Token has been invalidated"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Invalidate every token issued before `at`
+    pub async fn invalidate_before(&self, at: Timestamp) -> AppResult<()> {
+        tracing::warn!("Moving global token watermark to {}", at);
+        self.repo.set(at).await?;
+        // Re-read rather than cache `at`: the stored value may be later
+        *self.cached.write().unwrap() = None;
+        Ok(())
+    }
+
+    /// Invalidate every token issued to one user before now
+    pub async fn invalidate_user_tokens(&self, user_id: UserId) -> AppResult<()> {
+        let mut user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        user.invalidate_tokens();
+        self.user_repo.update(&user).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{Email, User};
+    use crate::moduls::auth::infra::in_memory::{
+        InMemoryTokenWatermarkRepository, InMemoryUserRepository,
+    };
+
+    const USER_ID: UserId = UserId::from_uuid(uuid::Uuid::from_u128(1));
+
+    fn watermark(configured: Option<Timestamp>) -> TokenWatermark {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        user.id = USER_ID;
+
+        TokenWatermark::new(
+            Arc::new(InMemoryTokenWatermarkRepository::default()),
+            Arc::new(InMemoryUserRepository::with_user(user)),
+            configured,
+            Duration::from_secs(60),
+        )
+    }
+
+    fn claims_issued_at(iat: Timestamp) -> Claims {
+        claims_for(USER_ID, iat)
+    }
+
+    fn claims_for(user_id: UserId, iat: Timestamp) -> Claims {
+        Claims {
+            sub: user_id.to_string(),
+            jti: new_id().to_string(),
+            exp: iat.timestamp() + 900,
+            iat: iat.timestamp(),
+            token_type: "access".to_string(),
+            tid: None,
+            auth_time: None,
+            roles: None,
+            iss: None,
+            aud: None,
+            sid: None,
+            act: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_watermark_accepts_tokens() {
+        let watermark = watermark(None);
+
+        assert!(watermark.check(&claims_issued_at(now())).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bumping_watermark_invalidates_older_tokens() {
+        let watermark = watermark(None);
+        let old = claims_issued_at(now() - chrono::Duration::minutes(5));
+
+        assert!(watermark.check(&old).await.is_ok());
+
+        watermark.invalidate_before(now()).await.unwrap();
+
+        assert!(matches!(watermark.check(&old).await, Err(AppError::Authentication(_))));
+        assert!(watermark.check(&claims_issued_at(now())).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_stored_watermark_is_cached_until_ttl() {
+        let repo = Arc::new(InMemoryTokenWatermarkRepository::default());
+        let user_repo = Arc::new(InMemoryUserRepository::default());
+        let cached = TokenWatermark::new(repo.clone(), user_repo.clone(), None, Duration::from_secs(60));
+        let uncached = TokenWatermark::new(repo.clone(), user_repo, None, Duration::ZERO);
+        assert_eq!(cached.valid_after().await.unwrap(), None);
+
+        // A bump by another instance: served from cache until it expires
+        let at = now();
+        uncached.invalidate_before(at).await.unwrap();
+        assert_eq!(cached.valid_after().await.unwrap(), None);
+        assert_eq!(uncached.valid_after().await.unwrap(), Some(at));
+
+        // A bump through this instance applies at once
+        let later = now();
+        cached.invalidate_before(later).await.unwrap();
+        assert_eq!(cached.valid_after().await.unwrap(), Some(later));
+    }
+
+    #[tokio::test]
+    async fn test_later_of_configured_and_stored_watermark_applies() {
+        let configured = now() - chrono::Duration::minutes(10);
+        let watermark = watermark(Some(configured));
+        let token = claims_issued_at(now() - chrono::Duration::minutes(5));
+
+        assert_eq!(watermark.valid_after().await.unwrap(), Some(configured));
+        assert!(watermark.check(&token).await.is_ok());
+
+        // An older stored watermark does not override the configured one
+        watermark.invalidate_before(now() - chrono::Duration::minutes(20)).await.unwrap();
+        assert_eq!(watermark.valid_after().await.unwrap(), Some(configured));
+
+        watermark.invalidate_before(now()).await.unwrap();
+        assert!(watermark.check(&token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_user_watermark_only_affects_that_user() {
+        let email = Email::new("other@example.com").unwrap();
+        let other = User::new(email, "password123", "Other User".to_string()).unwrap();
+        let other_id = other.id;
+
+        let watermark = watermark(None);
+        watermark.user_repo.save(&other).await.unwrap();
+
+        let issued = now() - chrono::Duration::minutes(5);
+        watermark.invalidate_user_tokens(USER_ID).await.unwrap();
+
+        assert!(watermark.check(&claims_for(USER_ID, issued)).await.is_err());
+        assert!(watermark.check(&claims_for(other_id, issued)).await.is_ok());
+        assert!(watermark.check(&claims_for(USER_ID, now())).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_is_rejected() {
+        let watermark = watermark(None);
+
+        let result = watermark.check(&claims_for(UserId::new(), now())).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_expired_account_tokens_are_rejected() {
+        let watermark = watermark(None);
+        let token = claims_issued_at(now() - chrono::Duration::minutes(5));
+        let mut user = watermark.user_repo.find_by_id(USER_ID).await.unwrap().unwrap();
+        user.set_expiry(Some(now() - chrono::Duration::seconds(1))).unwrap();
+        watermark.user_repo.update(&user).await.unwrap();
+
+        let result = watermark.check(&token).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(msg)) if msg.contains("expired")));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::{TotpSecret, UserTotp};
+use crate::moduls::auth::infra::{TotpRepository, UserRepository};
+use crate::shared::{types::*, AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Result of starting TOTP setup
+#[derive(Debug, Clone, Serialize)]
+pub struct EnableTotpResult {
+    /// Base32 secret, for entering into an authenticator app by hand
+    pub secret: String,
+    /// `otpauth://` URI to render as a QR code
+    pub otpauth_uri: String,
+}
+
+/// Confirm TOTP command (DTO)
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ConfirmTotpCommand {
+    #[validate(length(equal = 6, message = "Code must be 6 digits"))]
+    pub code: String,
+}
+
+/// Use case for starting TOTP setup
+///
+/// Business Logic:
+/// 1. Reject users who already have 2FA enabled
+/// 2. Generate a secret and store it as a pending enrollment (replacing an
+///    earlier pending one)
+/// 3. Return the secret and its `otpauth://` URI
+///
+/// The secret is not used at login until `ConfirmTotpUseCase` succeeds.
+pub struct EnableTotpUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    totp_repo: Arc<dyn TotpRepository>,
+    issuer: String,
+}
+
+impl EnableTotpUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        totp_repo: Arc<dyn TotpRepository>,
+        issuer: String,
+    ) -> Self {
+        Self {
+            user_repo,
+            totp_repo,
+            issuer,
+        }
+    }
+
+    /// Start TOTP setup for a user
+    ///
+    /// # Errors
+    /// - NotFound if the user doesn't exist
+    /// - Conflict if 2FA is already enabled
+    /// - Database errors
+    pub async fn execute(&self, user_id: UserId) -> AppResult<EnableTotpResult> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        if user.two_factor_enabled {
+            return Err(already_enabled());
+        }
+
+        let secret = TotpSecret::generate();
+        if !self.totp_repo.save_pending(&UserTotp::pending(user.id, &secret)).await? {
+            return Err(already_enabled());
+        }
+
+        Ok(EnableTotpResult {
+            otpauth_uri: secret.otpauth_uri(&self.issuer, user.email.as_str()),
+            secret: secret.as_base32().to_string(),
+        })
+    }
+}
+
+/// Use case for finishing TOTP setup
+///
+/// Business Logic:
+/// 1. Find the user's pending enrollment
+/// 2. Verify the first code (30 s steps, one step of drift either way)
+/// 3. Confirm the enrollment and enable 2FA on the user
+pub struct ConfirmTotpUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    totp_repo: Arc<dyn TotpRepository>,
+    audit_log: Arc<AuditLog>,
+}
+
+impl ConfirmTotpUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        totp_repo: Arc<dyn TotpRepository>,
+        audit_log: Arc<AuditLog>,
+    ) -> Self {
+        Self {
+            user_repo,
+            totp_repo,
+            audit_log,
+        }
+    }
+
+    /// Confirm TOTP setup with a code from the authenticator app
+    ///
+    /// # Errors
+    /// - NotFound if the user doesn't exist
+    /// - Conflict if 2FA is already enabled
+    /// - Validation if setup wasn't started or the code is wrong
+    /// - Database errors
+    pub async fn execute(&self, user_id: UserId, cmd: ConfirmTotpCommand) -> AppResult<()> {
+        let mut user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        if user.two_factor_enabled {
+            return Err(already_enabled());
+        }
+
+        let totp = self
+            .totp_repo
+            .find_by_user_id(user.id)
+            .await?
+            .filter(|t| !t.is_confirmed())
+            .ok_or_else(|| AppError::validation("Two-factor setup has not been started"))?;
+
+        let step = totp
+            .secret()?
+            .verify(&cmd.code)
+            .ok_or_else(|| AppError::validation("Invalid authentication code"))?;
+
+        if !self.totp_repo.confirm(user.id, step).await? {
+            return Err(already_enabled());
+        }
+
+        user.enable_two_factor();
+        self.user_repo.update(&user).await?;
+
+        self.audit_log
+            .record(AuditEvent::new(AuditAction::TwoFactorEnabled, Some(user.id)).with_tenant(user.tenant_id))
+            .await;
+
+        Ok(())
+    }
+}
+
+fn already_enabled() -> AppError {
+    AppError::conflict("Two-factor authentication is already enabled")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{Email, User};
+    use crate::moduls::auth::infra::in_memory::{InMemoryTotpRepository, InMemoryUserRepository};
+
+    struct Fixture {
+        user_id: UserId,
+        user_repo: Arc<InMemoryUserRepository>,
+        totp_repo: Arc<InMemoryTotpRepository>,
+        enable: EnableTotpUseCase,
+        confirm: ConfirmTotpUseCase,
+    }
+
+    fn fixture() -> Fixture {
+        let user = User::new(
+            Email::new("totp@example.com").unwrap(),
+            "password123",
+            "Totp User".to_string(),
+        )
+        .unwrap();
+        let user_id = user.id;
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(user));
+        let totp_repo = Arc::new(InMemoryTotpRepository::default());
+
+        Fixture {
+            user_id,
+            enable: EnableTotpUseCase::new(user_repo.clone(), totp_repo.clone(), "Acme".to_string()),
+            confirm: ConfirmTotpUseCase::new(
+                user_repo.clone(),
+                totp_repo.clone(),
+                Arc::new(AuditLog::for_tests()),
+            ),
+            user_repo,
+            totp_repo,
+        }
+    }
+
+    fn current_code(secret: &str) -> String {
+        TotpSecret::from_base32(secret).unwrap().current_code()
+    }
+
+    fn confirm(code: String) -> ConfirmTotpCommand {
+        ConfirmTotpCommand { code }
+    }
+
+    #[tokio::test]
+    async fn test_enable_returns_otpauth_uri() {
+        let f = fixture();
+
+        let result = f.enable.execute(f.user_id).await.unwrap();
+
+        assert!(result
+            .otpauth_uri
+            .starts_with("otpauth://totp/Acme:totp%40example.com?secret="));
+        assert!(result.otpauth_uri.contains(&result.secret));
+        let pending = f.totp_repo.find_by_user_id(f.user_id).await.unwrap().unwrap();
+        assert!(!pending.is_confirmed());
+    }
+
+    #[tokio::test]
+    async fn test_confirm_enables_two_factor() {
+        let f = fixture();
+        let result = f.enable.execute(f.user_id).await.unwrap();
+
+        f.confirm
+            .execute(f.user_id, confirm(current_code(&result.secret)))
+            .await
+            .unwrap();
+
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.two_factor_enabled);
+        let totp = f.totp_repo.find_by_user_id(f.user_id).await.unwrap().unwrap();
+        assert!(totp.is_confirmed());
+        assert!(matches!(f.enable.execute(f.user_id).await, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_confirm_rejects_wrong_code() {
+        let f = fixture();
+        let result = f.enable.execute(f.user_id).await.unwrap();
+        let code = current_code(&result.secret);
+        let wrong = if code == "000000" { "111111" } else { "000000" };
+
+        let outcome = f.confirm.execute(f.user_id, confirm(wrong.to_string())).await;
+
+        assert!(matches!(outcome, Err(AppError::Validation(_))));
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(!user.two_factor_enabled);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_without_enable_is_rejected() {
+        let f = fixture();
+
+        let outcome = f.confirm.execute(f.user_id, confirm("123456".to_string())).await;
+
+        assert!(matches!(outcome, Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::UserDto;
+use crate::moduls::auth::infra::{
+    ApiKeyRepository, SessionRepository, TokenRepository, UserRepository,
+};
+use crate::shared::{types::UserId, AppError, AppResult};
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+/// Set User Status Command (DTO)
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct SetUserStatusCommand {
+    pub active: bool,
+}
+
+/// Set User Status Use Case
+/// Lets admins deactivate and reactivate accounts
+///
+/// Business Logic:
+/// 1. Admins can't deactivate themselves
+/// 2. The user must exist; deleted accounts can't be reactivated
+/// 3. Deactivating signs the user out everywhere: web sessions, JWTs and
+///    API keys. Revoked API keys stay revoked after reactivation
+/// 4. Record the change in the user's audit trail
+pub struct SetUserStatusUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    session_repo: Arc<dyn SessionRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    api_key_repo: Arc<dyn ApiKeyRepository>,
+    audit_log: Arc<AuditLog>,
+}
+
+impl SetUserStatusUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        session_repo: Arc<dyn SessionRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        api_key_repo: Arc<dyn ApiKeyRepository>,
+        audit_log: Arc<AuditLog>,
+    ) -> Self {
+        Self {
+            user_repo,
+            session_repo,
+            token_repo,
+            api_key_repo,
+            audit_log,
+        }
+    }
+
+    /// Execute the use case on behalf of `actor_id`
+    ///
+    /// # Errors
+    /// - Validation if the caller deactivates themselves
+    /// - NotFound if the user doesn't exist
+    /// - Conflict if reactivating a deleted account
+    /// - Database errors
+    pub async fn execute(
+        &self,
+        actor_id: UserId,
+        user_id: UserId,
+        cmd: SetUserStatusCommand,
+    ) -> AppResult<UserDto> {
+        // 1. Don't lock the caller out
+        if !cmd.active && actor_id == user_id {
+            return Err(AppError::validation("You cannot deactivate yourself"));
+        }
+
+        // 2. Update the flag
+        let mut user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+        if cmd.active {
+            user.reactivate()?;
+        } else {
+            user.deactivate();
+            user.invalidate_tokens();
+        }
+        let user = self.user_repo.update(&user).await?;
+
+        // 3. Log out everywhere
+        if !cmd.active {
+            self.session_repo.delete_by_user_id(user.id).await?;
+            self.token_repo.revoke_all_user_tokens(user.id).await?;
+            self.api_key_repo.revoke_all_for_user(user.id).await?;
+        }
+
+        // 4. Audit
+        let action = if cmd.active {
+            AuditAction::AccountReactivated
+        } else {
+            AuditAction::AccountDeactivated
+        };
+        tracing::warn!("User {} set user {} to {}", actor_id, user.id, action.as_str());
+        self.audit_log
+            .record(AuditEvent::new(action, Some(user.id)).with_tenant(user.tenant_id))
+            .await;
+
+        Ok(UserDto::from(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{
+        AccountStatus, ApiKey, Email, JwtKeys, JwtToken, Session, TokenPair, User,
+    };
+    use crate::moduls::auth::infra::in_memory::*;
+
+    struct Fixture {
+        use_case: SetUserStatusUseCase,
+        user_repo: Arc<InMemoryUserRepository>,
+        session_repo: Arc<InMemorySessionRepository>,
+        token_repo: Arc<InMemoryTokenRepository>,
+        api_key_repo: Arc<InMemoryApiKeyRepository>,
+        user_id: UserId,
+    }
+
+    async fn fixture() -> Fixture {
+        let email = Email::new("user@example.com").unwrap();
+        let user = User::new(email, "password123", "Some User".to_string()).unwrap();
+        let user_id = user.id;
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(user));
+        let session_repo = Arc::new(InMemorySessionRepository::default());
+        let token_repo = Arc::new(InMemoryTokenRepository::default());
+        let api_key_repo = Arc::new(InMemoryApiKeyRepository::default());
+
+        session_repo.save(&Session::new(user_id, None, None, 3600)).await.unwrap();
+        let keys = JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars");
+        let (_, access, refresh) = TokenPair::generate(user_id, &keys, 900, 3600).unwrap();
+        token_repo.save(&access).await.unwrap();
+        token_repo.save(&refresh).await.unwrap();
+        let (key, _) = ApiKey::issue(user_id, None, "CI".to_string(), None);
+        api_key_repo.save(&key).await.unwrap();
+
+        let use_case = SetUserStatusUseCase::new(
+            user_repo.clone(),
+            session_repo.clone(),
+            token_repo.clone(),
+            api_key_repo.clone(),
+            Arc::new(AuditLog::for_tests()),
+        );
+
+        Fixture {
+            use_case,
+            user_repo,
+            session_repo,
+            token_repo,
+            api_key_repo,
+            user_id,
+        }
+    }
+
+    fn set_active(active: bool) -> SetUserStatusCommand {
+        SetUserStatusCommand { active }
+    }
+
+    #[tokio::test]
+    async fn test_deactivation_logs_out_everywhere() {
+        let f = fixture().await;
+
+        let dto = f.use_case.execute(UserId::new(), f.user_id, set_active(false)).await.unwrap();
+
+        assert!(!dto.is_active);
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(!user.is_active);
+        assert!(user.tokens_valid_after.is_some());
+        assert!(f.session_repo.find_all_by_user_id(f.user_id).await.unwrap().is_empty());
+        assert!(f.token_repo.tokens.lock().unwrap().iter().all(JwtToken::is_revoked));
+        assert!(f.api_key_repo.keys.lock().unwrap().iter().all(ApiKey::is_revoked));
+    }
+
+    #[tokio::test]
+    async fn test_reactivation_keeps_credentials() {
+        let f = fixture().await;
+
+        let dto = f.use_case.execute(UserId::new(), f.user_id, set_active(true)).await.unwrap();
+
+        assert!(dto.is_active);
+        assert_eq!(f.session_repo.find_all_by_user_id(f.user_id).await.unwrap().len(), 1);
+        assert!(!f.token_repo.tokens.lock().unwrap().iter().any(JwtToken::is_revoked));
+    }
+
+    #[tokio::test]
+    async fn test_deactivate_then_reactivate() {
+        let f = fixture().await;
+
+        f.use_case.execute(UserId::new(), f.user_id, set_active(false)).await.unwrap();
+        f.use_case.execute(UserId::new(), f.user_id, set_active(true)).await.unwrap();
+
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.is_active);
+    }
+
+    #[tokio::test]
+    async fn test_deleted_account_cannot_be_reactivated() {
+        let f = fixture().await;
+        let mut user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        user.delete(false).unwrap();
+        f.user_repo.update(&user).await.unwrap();
+
+        let result = f.use_case.execute(UserId::new(), f.user_id, set_active(true)).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(!user.is_active);
+        assert_eq!(user.status(), AccountStatus::Deleted);
+    }
+
+    #[tokio::test]
+    async fn test_admin_cannot_deactivate_themselves() {
+        let f = fixture().await;
+
+        let result = f.use_case.execute(f.user_id, f.user_id, set_active(false)).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.is_active);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_is_not_found() {
+        let f = fixture().await;
+
+        let result = f.use_case.execute(UserId::new(), UserId::new(), set_active(false)).await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
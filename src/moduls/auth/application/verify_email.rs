@@ -0,0 +1,472 @@
+use super::{SendLimits, SendThrottle};
+use crate::moduls::auth::domain::{Email, EmailVerificationToken, User};
+use crate::moduls::auth::infra::{EmailVerificationRepository, UserRepository};
+use crate::shared::{mailer::Mailer, types::*, AppError, AppResult};
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+/// Verify email command (DTO), from the `token` query parameter
+#[derive(Debug, Clone, Deserialize)]
+pub struct VerifyEmailCommand {
+    pub token: String,
+}
+
+/// Resend verification command (DTO), for users who can't log in yet
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ResendVerificationCommand {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+    /// Tenant of the request (from `TenantContext`, never the body)
+    #[serde(skip)]
+    pub tenant_id: Option<OrganizationId>,
+    /// Client address, for throttling
+    #[serde(skip)]
+    pub ip_address: Option<String>,
+}
+
+/// Set recovery email command (DTO); `null` removes the recovery email
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct SetRecoveryEmailCommand {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: Option<String>,
+}
+
+/// Use case for confirming ownership of a user's email
+///
+/// Business Logic:
+/// 1. `send_verification` issues a single-use token for the authenticated
+///    user and emails a verification link; `resend_verification` does the
+///    same by email, silently ignoring unknown addresses
+/// 2. `set_recovery_email` stores a recovery email and sends a link to it,
+///    as it can't be used for password resets until verified
+/// 3. `verify` redeems the token and marks the address it was sent to
+///    (login or recovery email) as verified
+///
+/// Sends are throttled per address and per client IP.
+pub struct VerifyEmailUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    verification_repo: Arc<dyn EmailVerificationRepository>,
+    mailer: Arc<dyn Mailer>,
+    throttle: SendThrottle,
+    verify_url: String,
+    token_ttl_seconds: i64,
+}
+
+impl VerifyEmailUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        verification_repo: Arc<dyn EmailVerificationRepository>,
+        mailer: Arc<dyn Mailer>,
+        verify_url: String,
+        token_ttl_seconds: i64,
+        send_limits: SendLimits,
+    ) -> Self {
+        Self {
+            user_repo,
+            verification_repo,
+            mailer,
+            throttle: SendThrottle::new(send_limits),
+            verify_url,
+            token_ttl_seconds,
+        }
+    }
+
+    /// Send a verification token to the user's email
+    ///
+    /// # Errors
+    /// - NotFound if the user doesn't exist
+    /// - Conflict if the email is already verified
+    /// - TooManyRequests if sends to the address are throttled
+    /// - Database errors
+    pub async fn send_verification(&self, user_id: UserId, ip_address: Option<String>) -> AppResult<()> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        if user.email_verified {
+            return Err(AppError::conflict("Email already verified"));
+        }
+
+        if !self.throttle.try_acquire(user.email.as_str(), ip_address.as_deref()) {
+            return Err(AppError::too_many_requests(
+                "Verification email sent recently, try again later",
+            ));
+        }
+
+        self.issue_and_send(&user).await
+    }
+
+    /// Send a verification token to `email`, if it names an unverified account
+    ///
+    /// Succeeds whether or not the account exists or the send was
+    /// throttled, so callers can answer the same way in every case.
+    ///
+    /// # Errors
+    /// - Database errors
+    pub async fn resend_verification(&self, cmd: ResendVerificationCommand) -> AppResult<()> {
+        let Ok(email) = Email::new(&cmd.email) else {
+            return Ok(());
+        };
+
+        // Counted before the lookup, so unknown addresses are throttled alike
+        if !self.throttle.try_acquire(email.as_str(), cmd.ip_address.as_deref()) {
+            tracing::warn!("Verification resend throttled (ip: {:?})", cmd.ip_address);
+            return Ok(());
+        }
+
+        let tenant_user = match cmd.tenant_id {
+            Some(tenant_id) => self.user_repo.find_by_email_in_tenant(&email, tenant_id).await?,
+            None => None,
+        };
+        let user = match tenant_user {
+            Some(user) => Some(user),
+            None => self.user_repo.find_by_email(&email).await?,
+        };
+
+        let Some(user) = user.filter(|u| u.is_active && !u.email_verified) else {
+            return Ok(());
+        };
+
+        // Delivery problems must not reveal that the account exists
+        if let Err(e) = self.issue_and_send(&user).await {
+            tracing::error!("Failed to resend verification to user {}: {}", user.id, e);
+        }
+
+        Ok(())
+    }
+
+    /// Set or remove the user's recovery email
+    ///
+    /// A new, unverified address is sent a verification link.
+    ///
+    /// # Errors
+    /// - NotFound if the user doesn't exist
+    /// - Validation if the address is invalid or the login email
+    /// - TooManyRequests if sends to the address are throttled
+    /// - Database errors
+    pub async fn set_recovery_email(
+        &self,
+        user_id: UserId,
+        cmd: SetRecoveryEmailCommand,
+        ip_address: Option<String>,
+    ) -> AppResult<User> {
+        let email = cmd.email.as_deref().map(Email::new).transpose()?;
+
+        let mut user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        user.set_recovery_email(email)?;
+
+        let pending = user.recovery_email.clone().filter(|_| !user.recovery_email_verified);
+        if let Some(email) = &pending {
+            if !self.throttle.try_acquire(email.as_str(), ip_address.as_deref()) {
+                return Err(AppError::too_many_requests(
+                    "Verification email sent recently, try again later",
+                ));
+            }
+        }
+
+        let user = self.user_repo.update(&user).await?;
+
+        if let Some(email) = pending {
+            let (token, plain) =
+                EmailVerificationToken::issue_for_recovery(user.id, email.clone(), self.token_ttl_seconds);
+            self.verification_repo.save(&token).await?;
+
+            let body = format!(
+                "Hi {},\n\n\
+                 Confirm this address as the recovery email of your account by opening \
+                 the link below. Password resets can then be sent here.\n\n\
+                 {}?token={}\n\n\
+                 If you didn't ask for this, you can ignore this email.\n",
+                user.name, self.verify_url, plain
+            );
+            self.mailer.send(&email, "Verify your recovery email", &body).await?;
+        }
+
+        Ok(user)
+    }
+
+    async fn issue_and_send(&self, user: &User) -> AppResult<()> {
+        let (token, plain) = EmailVerificationToken::issue(user.id, self.token_ttl_seconds);
+        self.verification_repo.save(&token).await?;
+
+        let body = format!(
+            "Hi {},\n\n\
+             Confirm your email address by opening the link below.\n\n\
+             {}?token={}\n\n\
+             If you didn't create an account, you can ignore this email.\n",
+            user.name, self.verify_url, plain
+        );
+        self.mailer.send(&user.email, "Verify your email address", &body).await
+    }
+
+    /// Redeem a verification token
+    ///
+    /// # Errors
+    /// - Validation if the token is unknown, expired or already used
+    /// - Database errors
+    pub async fn verify(&self, cmd: VerifyEmailCommand) -> AppResult<()> {
+        // Redeem atomically, so a token works only once
+        let token = self
+            .verification_repo
+            .find_by_hash(&EmailVerificationToken::hash(&cmd.token))
+            .await?
+            .filter(|t| t.is_usable())
+            .ok_or_else(invalid_token)?;
+
+        if !self.verification_repo.mark_used(token.id).await? {
+            return Err(invalid_token());
+        }
+
+        let mut user = self
+            .user_repo
+            .find_by_id(token.user_id)
+            .await?
+            .ok_or_else(invalid_token)?;
+
+        match token.recovery_email {
+            // The recovery email may have changed since the link was sent
+            Some(email) => {
+                if user.recovery_email.as_ref() != Some(&email) {
+                    return Err(invalid_token());
+                }
+                user.verify_recovery_email();
+                self.user_repo.update(&user).await?;
+            }
+            None => {
+                user.verify_email();
+                self.user_repo.update(&user).await?;
+                self.verification_repo.invalidate_user_tokens(user.id).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid_token() -> AppError {
+    AppError::validation("Invalid or expired verification token")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::infra::in_memory::{
+        InMemoryEmailVerificationRepository, InMemoryUserRepository,
+    };
+    use crate::shared::mailer::MockMailer;
+
+    struct Fixture {
+        user_id: UserId,
+        user_repo: Arc<InMemoryUserRepository>,
+        verification_repo: Arc<InMemoryEmailVerificationRepository>,
+        mailer: Arc<MockMailer>,
+        use_case: VerifyEmailUseCase,
+    }
+
+    fn fixture() -> Fixture {
+        let user = User::new(
+            Email::new("verify@example.com").unwrap(),
+            "password123",
+            "Verify User".to_string(),
+        )
+        .unwrap();
+        let user_id = user.id;
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(user));
+        let verification_repo = Arc::new(InMemoryEmailVerificationRepository::default());
+        let mailer = Arc::new(MockMailer::default());
+        let use_case = VerifyEmailUseCase::new(
+            user_repo.clone(),
+            verification_repo.clone(),
+            mailer.clone(),
+            "http://app.test/api/auth/verify-email".to_string(),
+            86400,
+            SendLimits {
+                per_email: 1,
+                per_ip: 10,
+                window_seconds: 300,
+            },
+        );
+
+        Fixture {
+            user_id,
+            user_repo,
+            verification_repo,
+            mailer,
+            use_case,
+        }
+    }
+
+    async fn issued_token(f: &Fixture) -> String {
+        f.use_case.send_verification(f.user_id, None).await.unwrap();
+        let mail = f.mailer.last().unwrap();
+        assert_eq!(mail.to, "verify@example.com");
+        token_in(&mail.body)
+    }
+
+    fn token_in(body: &str) -> String {
+        let link = body
+            .lines()
+            .find(|line| line.starts_with("http://app.test/api/auth/verify-email?token="))
+            .unwrap();
+        link.rsplit('=').next().unwrap().to_string()
+    }
+
+    fn recovery(email: Option<&str>) -> SetRecoveryEmailCommand {
+        SetRecoveryEmailCommand {
+            email: email.map(str::to_string),
+        }
+    }
+
+    fn verify(token: &str) -> VerifyEmailCommand {
+        VerifyEmailCommand {
+            token: token.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_marks_email_verified() {
+        let f = fixture();
+        let plain = issued_token(&f).await;
+
+        f.use_case.verify(verify(&plain)).await.unwrap();
+
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.email_verified);
+    }
+
+    #[tokio::test]
+    async fn test_recovery_email_is_verified_by_its_link() {
+        let f = fixture();
+        let user = f
+            .use_case
+            .set_recovery_email(f.user_id, recovery(Some("backup@example.com")), None)
+            .await
+            .unwrap();
+        assert_eq!(user.verified_recovery_email(), None);
+
+        let mail = f.mailer.last().unwrap();
+        assert_eq!(mail.to, "backup@example.com");
+        f.use_case.verify(verify(&token_in(&mail.body))).await.unwrap();
+
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert_eq!(user.verified_recovery_email().unwrap().as_str(), "backup@example.com");
+        // The login email is confirmed separately
+        assert!(!user.email_verified);
+    }
+
+    #[tokio::test]
+    async fn test_link_for_replaced_recovery_email_is_rejected() {
+        let f = fixture();
+        f.use_case
+            .set_recovery_email(f.user_id, recovery(Some("old@example.com")), None)
+            .await
+            .unwrap();
+        let stale = token_in(&f.mailer.last().unwrap().body);
+        f.use_case
+            .set_recovery_email(f.user_id, recovery(Some("new@example.com")), None)
+            .await
+            .unwrap();
+
+        let result = f.use_case.verify(verify(&stale)).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert_eq!(user.verified_recovery_email(), None);
+    }
+
+    #[tokio::test]
+    async fn test_recovery_email_equal_to_login_email_is_rejected() {
+        let f = fixture();
+
+        let result = f
+            .use_case
+            .set_recovery_email(f.user_id, recovery(Some("Verify@Example.com")), None)
+            .await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        assert_eq!(f.mailer.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_removing_recovery_email_sends_nothing() {
+        let f = fixture();
+
+        let user = f.use_case.set_recovery_email(f.user_id, recovery(None), None).await.unwrap();
+
+        assert!(user.recovery_email.is_none());
+        assert_eq!(f.mailer.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reused_token_is_rejected() {
+        let f = fixture();
+        let plain = issued_token(&f).await;
+        f.use_case.verify(verify(&plain)).await.unwrap();
+
+        let result = f.use_case.verify(verify(&plain)).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_is_rejected() {
+        let f = fixture();
+        let plain = issued_token(&f).await;
+        f.verification_repo.tokens.lock().unwrap()[0].expires_at =
+            now() - chrono::Duration::seconds(1);
+
+        let result = f.use_case.verify(verify(&plain)).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(!user.email_verified);
+    }
+
+    #[tokio::test]
+    async fn test_send_to_verified_user_conflicts() {
+        let f = fixture();
+        let plain = issued_token(&f).await;
+        f.use_case.verify(verify(&plain)).await.unwrap();
+
+        let result = f.use_case.send_verification(f.user_id, None).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_sends_are_throttled() {
+        let f = fixture();
+        f.use_case.send_verification(f.user_id, None).await.unwrap();
+
+        let result = f.use_case.send_verification(f.user_id, None).await;
+
+        assert!(matches!(result, Err(AppError::TooManyRequests(_))));
+        assert_eq!(f.mailer.count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resend_is_silent_and_throttled() {
+        let f = fixture();
+        let resend = |email: &str| ResendVerificationCommand {
+            email: email.to_string(),
+            tenant_id: None,
+            ip_address: Some("10.0.0.1".to_string()),
+        };
+
+        f.use_case.resend_verification(resend("nobody@example.com")).await.unwrap();
+        for _ in 0..3 {
+            f.use_case.resend_verification(resend("verify@example.com")).await.unwrap();
+        }
+
+        assert_eq!(f.mailer.count(), 1);
+        assert_eq!(f.mailer.last().unwrap().to, "verify@example.com");
+    }
+}
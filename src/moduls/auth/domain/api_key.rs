@@ -0,0 +1,105 @@
+use super::one_time_token;
+use crate::shared::types::*;
+
+/// API key entity
+///
+/// Long-lived credential for service-to-service callers, presented as
+/// `Authorization: ApiKey <key>` or `X-API-Key: <key>`. Like the one-time
+/// tokens, only the SHA-256 hash is stored; the plain key is shown once.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: uuid::Uuid,
+    pub user_id: UserId,
+    /// Tenant selected by the token that minted the key
+    pub tenant_id: Option<OrganizationId>,
+    pub name: String,
+    pub key_hash: String,
+    pub last_used_at: Option<Timestamp>,
+    pub expires_at: Option<Timestamp>,
+    pub revoked_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
+
+impl ApiKey {
+    /// Prefix of every plain key, so leaked keys are easy to recognise
+    pub const PREFIX: &'static str = "mt_";
+
+    /// Maximum length of the key name
+    pub const MAX_NAME_LENGTH: u64 = 100;
+
+    /// Issue a new key for a user
+    ///
+    /// Returns the entity together with the plain key (`mt_` followed by
+    /// 43 URL-safe characters) to show to the user.
+    pub fn issue(
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+        name: String,
+        expires_at: Option<Timestamp>,
+    ) -> (Self, String) {
+        let plain = format!("{}{}", Self::PREFIX, one_time_token::generate());
+
+        let key = Self {
+            id: new_id(),
+            user_id,
+            tenant_id,
+            name,
+            key_hash: Self::hash(&plain),
+            last_used_at: None,
+            expires_at,
+            revoked_at: None,
+            created_at: now(),
+        };
+
+        (key, plain)
+    }
+
+    /// Hash a plain key for storage and lookup
+    pub fn hash(plain: &str) -> String {
+        one_time_token::hash(plain)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| now() > expires_at)
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Whether the key still authenticates
+    pub fn is_usable(&self) -> bool {
+        !self.is_revoked() && !self.is_expired()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_stores_only_hash() {
+        let (key, plain) = ApiKey::issue(UserId::new(), None, "ci".to_string(), None);
+
+        assert!(plain.starts_with("mt_"));
+        assert_eq!(plain.len(), 46);
+        assert_eq!(key.key_hash, ApiKey::hash(&plain));
+        assert!(key.is_usable());
+    }
+
+    #[test]
+    fn test_expired_and_revoked_keys_are_unusable() {
+        let (mut key, _) = ApiKey::issue(
+            UserId::new(),
+            None,
+            "ci".to_string(),
+            Some(now() - chrono::Duration::seconds(1)),
+        );
+        assert!(key.is_expired());
+        assert!(!key.is_usable());
+
+        key.expires_at = None;
+        key.revoked_at = Some(now());
+        assert!(!key.is_usable());
+    }
+}
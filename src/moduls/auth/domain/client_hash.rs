@@ -0,0 +1,154 @@
+//! Client-side password pre-hashing (opt-in, `CLIENT_PASSWORD_HASHING`)
+//!
+//! Clients derive `PBKDF2-HMAC-SHA256(password, salt, iterations)` (32
+//! bytes, lowercase hex) and send that instead of the password; the server
+//! bcrypts the derived value like any password, so the plaintext never
+//! reaches it. The salt is per user and handed out by the negotiation
+//! endpoint.
+
+use super::Email;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Parameters a password was (or must be) pre-hashed with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientHashParams {
+    pub algorithm: String,
+    /// Used as its UTF-8 bytes
+    pub salt: String,
+    pub iterations: u32,
+}
+
+impl ClientHashParams {
+    /// The only supported algorithm
+    pub const ALGORITHM: &'static str = "pbkdf2-sha256";
+
+    /// Length of a client hash in hex characters (32 bytes)
+    pub const HASH_LENGTH: usize = 64;
+
+    pub fn new(salt: String, iterations: u32) -> Self {
+        Self {
+            algorithm: Self::ALGORITHM.to_string(),
+            salt,
+            iterations,
+        }
+    }
+
+    /// Derive the client hash of `password`, as a client would
+    ///
+    /// Used for callers that can't pre-hash (web forms, password change)
+    /// and to migrate plaintext-based accounts.
+    pub fn derive(&self, password: &str) -> String {
+        pbkdf2_sha256(password.as_bytes(), self.salt.as_bytes(), self.iterations)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Whether `value` looks like a client hash (64 lowercase hex characters)
+    pub fn is_client_hash(value: &str) -> bool {
+        value.len() == Self::HASH_LENGTH
+            && value.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    }
+}
+
+/// Server side of client-side pre-hashing
+///
+/// Salts are derived from the email with a server secret, so they are
+/// stable and unknown emails get plausible parameters too (the negotiation
+/// endpoint doesn't reveal which accounts exist). Accounts keep the
+/// parameters they were hashed with, so changing the iteration count or the
+/// secret only affects new accounts.
+#[derive(Debug, Clone)]
+pub struct ClientHashing {
+    salt_key: String,
+    iterations: u32,
+}
+
+impl ClientHashing {
+    pub fn new(salt_key: impl Into<String>, iterations: u32) -> Self {
+        Self {
+            salt_key: salt_key.into(),
+            iterations,
+        }
+    }
+
+    /// Parameters for a new client hash of `email`'s password
+    pub fn params_for(&self, email: &Email) -> ClientHashParams {
+        let mut mac = HmacSha256::new_from_slice(self.salt_key.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(b"client-hash-salt:");
+        mac.update(email.as_str().as_bytes());
+        let salt = URL_SAFE_NO_PAD.encode(&mac.finalize().into_bytes()[..16]);
+
+        ClientHashParams::new(salt, self.iterations)
+    }
+}
+
+/// PBKDF2 with HMAC-SHA256, one output block (RFC 8018)
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    // Keys longer than the block size are hashed first, as HMAC would
+    let prf = if password.len() > 64 {
+        HmacSha256::new_from_slice(&Sha256::digest(password))
+    } else {
+        HmacSha256::new_from_slice(password)
+    }
+    .expect("HMAC accepts keys of any length");
+
+    let mut mac = prf.clone();
+    mac.update(salt);
+    mac.update(&1u32.to_be_bytes());
+    let mut block: [u8; 32] = mac.finalize().into_bytes().into();
+    let mut output = block;
+
+    for _ in 1..iterations {
+        let mut mac = prf.clone();
+        mac.update(&block);
+        block = mac.finalize().into_bytes().into();
+        output.iter_mut().zip(block).for_each(|(o, b)| *o ^= b);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pbkdf2_matches_rfc_7914_vector() {
+        let params = ClientHashParams::new("salt".to_string(), 1);
+
+        assert_eq!(
+            params.derive("passwd"),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+    }
+
+    #[test]
+    fn test_client_hash_format() {
+        let hash = ClientHashParams::new("salt".to_string(), 2).derive("password123");
+
+        assert!(ClientHashParams::is_client_hash(&hash));
+        assert!(!ClientHashParams::is_client_hash("password123"));
+        assert!(!ClientHashParams::is_client_hash(&hash.to_uppercase()));
+    }
+
+    #[test]
+    fn test_salts_are_stable_per_email() {
+        let hashing = ClientHashing::new("secret", 1000);
+        let alice = Email::new("alice@example.com").unwrap();
+        let bob = Email::new("bob@example.com").unwrap();
+
+        assert_eq!(hashing.params_for(&alice), hashing.params_for(&alice));
+        assert_ne!(hashing.params_for(&alice).salt, hashing.params_for(&bob).salt);
+        assert_ne!(
+            hashing.params_for(&alice).salt,
+            ClientHashing::new("other", 1000).params_for(&alice).salt
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use super::{one_time_token, Email};
+use crate::shared::types::*;
+
+/// Email verification token entity
+///
+/// Like password reset tokens, only the hash is stored and a token is
+/// single-use. A token for a recovery email records that address, so it
+/// confirms only the address it was sent to.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct EmailVerificationToken {
+    pub id: uuid::Uuid,
+    pub user_id: UserId,
+    pub token_hash: String,
+    pub expires_at: Timestamp,
+    pub used_at: Option<Timestamp>,
+    /// Recovery address being confirmed (`None` for the login email)
+    pub recovery_email: Option<Email>,
+    pub created_at: Timestamp,
+}
+
+impl EmailVerificationToken {
+    /// Issue a new token for a user
+    ///
+    /// Returns the entity together with the plain token to deliver.
+    pub fn issue(user_id: UserId, ttl_seconds: i64) -> (Self, String) {
+        Self::issue_for(user_id, None, ttl_seconds)
+    }
+
+    /// Issue a new token confirming a user's recovery email
+    pub fn issue_for_recovery(user_id: UserId, email: Email, ttl_seconds: i64) -> (Self, String) {
+        Self::issue_for(user_id, Some(email), ttl_seconds)
+    }
+
+    fn issue_for(user_id: UserId, recovery_email: Option<Email>, ttl_seconds: i64) -> (Self, String) {
+        let plain = one_time_token::generate();
+        let now = now();
+
+        let token = Self {
+            id: new_id(),
+            user_id,
+            token_hash: Self::hash(&plain),
+            expires_at: now + chrono::Duration::seconds(ttl_seconds),
+            used_at: None,
+            recovery_email,
+            created_at: now,
+        };
+
+        (token, plain)
+    }
+
+    /// Hash a plain token for storage and lookup
+    pub fn hash(plain: &str) -> String {
+        one_time_token::hash(plain)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        now() > self.expires_at
+    }
+
+    /// Whether the token can still be redeemed
+    pub fn is_usable(&self) -> bool {
+        self.used_at.is_none() && !self.is_expired()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_stores_only_hash() {
+        let (token, plain) = EmailVerificationToken::issue(UserId::new(), 86400);
+
+        assert_eq!(token.token_hash, EmailVerificationToken::hash(&plain));
+        assert_ne!(token.token_hash, plain);
+        assert!(token.is_usable());
+    }
+
+    #[test]
+    fn test_expired_token_is_unusable() {
+        let (token, _) = EmailVerificationToken::issue(UserId::new(), -1);
+
+        assert!(!token.is_usable());
+    }
+}
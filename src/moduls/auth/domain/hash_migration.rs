@@ -0,0 +1,101 @@
+use crate::shared::types::*;
+use serde::Serialize;
+
+/// State of a password hash migration run
+#[derive(Debug, Clone, Copy, sqlx::Type, Serialize, PartialEq, Eq)]
+#[sqlx(type_name = "password_hash_migration_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum HashMigrationStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Run of the job flagging users whose password hash is outdated
+///
+/// Without the plain password a hash can't be upgraded, so the job marks
+/// users whose hash the configured hasher would redo (see
+/// `PasswordHasher::needs_rehash`) with `must_change_password` instead.
+/// The counters grow batch by batch while the run is in progress.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct HashMigrationRun {
+    pub id: uuid::Uuid,
+    /// Admin who started the run
+    pub started_by: Option<UserId>,
+    pub status: HashMigrationStatus,
+    /// Users whose hash was checked
+    pub scanned_users: i64,
+    /// Users flagged because their hash was outdated
+    pub flagged_users: i64,
+    /// Why the run failed
+    pub error: Option<String>,
+    pub started_at: Timestamp,
+    pub finished_at: Option<Timestamp>,
+}
+
+impl HashMigrationRun {
+    /// Start a new run
+    pub fn start(started_by: Option<UserId>) -> Self {
+        Self {
+            id: new_id(),
+            started_by,
+            status: HashMigrationStatus::Running,
+            scanned_users: 0,
+            flagged_users: 0,
+            error: None,
+            started_at: now(),
+            finished_at: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == HashMigrationStatus::Running
+    }
+
+    /// Count a processed batch
+    pub fn record_batch(&mut self, scanned: usize, flagged: u64) {
+        self.scanned_users += scanned as i64;
+        self.flagged_users += flagged as i64;
+    }
+
+    /// Mark the run as done
+    pub fn complete(&mut self) {
+        self.status = HashMigrationStatus::Completed;
+        self.finished_at = Some(now());
+    }
+
+    /// Mark the run as failed with `error`
+    pub fn fail(&mut self, error: impl Into<String>) {
+        self.status = HashMigrationStatus::Failed;
+        self.error = Some(error.into());
+        self.finished_at = Some(now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_counts_batches_until_completed() {
+        let mut run = HashMigrationRun::start(None);
+        assert!(run.is_running());
+
+        run.record_batch(100, 3);
+        run.record_batch(40, 0);
+        run.complete();
+
+        assert_eq!((run.scanned_users, run.flagged_users), (140, 3));
+        assert_eq!(run.status, HashMigrationStatus::Completed);
+        assert!(run.finished_at.is_some());
+    }
+
+    #[test]
+    fn test_failed_run_keeps_error() {
+        let mut run = HashMigrationRun::start(None);
+        run.fail("database unavailable");
+
+        assert!(!run.is_running());
+        assert_eq!(run.error.as_deref(), Some("database unavailable"));
+    }
+}
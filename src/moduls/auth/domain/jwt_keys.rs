@@ -0,0 +1,322 @@
+use crate::shared::{metrics, AppError, AppResult};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, Jwk, JwkSet, KeyAlgorithm, PublicKeyUse,
+    RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPublicKey;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Keys used to sign and verify JWTs
+///
+/// HS256 signs and verifies with one shared secret. RS256 signs with the
+/// current private key and verifies by the token's `kid` header, so tokens
+/// signed with a retired key stay valid during rotation as long as its
+/// public key is still listed.
+///
+/// Also carries the optional issuer and audience: written into issued
+/// tokens as `iss`/`aud` and, when set, required of decoded ones.
+#[derive(Clone)]
+pub struct JwtKeys {
+    signing: SigningKeys,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+#[derive(Clone)]
+enum SigningKeys {
+    Hmac(String),
+    Rsa {
+        encoding: EncodingKey,
+        /// Public keys by `kid` (the current key and any retired ones)
+        decoding: HashMap<String, DecodingKey>,
+        /// The same public keys as JWKs, current key first
+        public: Vec<Jwk>,
+        /// `kid` of the current signing key
+        kid: String,
+    },
+}
+
+impl JwtKeys {
+    /// HS256 keys from a shared secret
+    pub fn hmac(secret: impl Into<String>) -> Self {
+        Self::new(SigningKeys::Hmac(secret.into()))
+    }
+
+    fn new(signing: SigningKeys) -> Self {
+        Self {
+            signing,
+            issuer: None,
+            audience: None,
+        }
+    }
+
+    /// Issue tokens with `iss` set to `issuer`, and require it when decoding
+    pub fn with_issuer(mut self, issuer: Option<String>) -> Self {
+        self.issuer = issuer;
+        self
+    }
+
+    /// Issue tokens with `aud` set to `audience`, and require it when decoding
+    pub fn with_audience(mut self, audience: Option<String>) -> Self {
+        self.audience = audience;
+        self
+    }
+
+    pub fn issuer(&self) -> Option<&str> {
+        self.issuer.as_deref()
+    }
+
+    pub fn audience(&self) -> Option<&str> {
+        self.audience.as_deref()
+    }
+
+    /// RS256 keys from a PEM private key and its PEM public key
+    ///
+    /// # Errors
+    /// - Config error if a key cannot be parsed or the keys don't form a pair
+    pub fn rsa(private_pem: &[u8], public_pem: &[u8]) -> AppResult<Self> {
+        let encoding = EncodingKey::from_rsa_pem(private_pem)
+            .map_err(|e| AppError::Config(format!("Invalid JWT private key: {}", e)))?;
+        let (kid, decoding_key, jwk) = rsa_public_key(public_pem)?;
+
+        // Refuse to start with a public key that can't verify our own tokens
+        let probe = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &ProbeClaims::new(), &encoding)
+            .map_err(|e| AppError::Config(format!("Invalid JWT private key: {}", e)))?;
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.required_spec_claims.clear();
+        validation.validate_exp = false;
+        jsonwebtoken::decode::<ProbeClaims>(&probe, &decoding_key, &validation).map_err(|_| {
+            AppError::Config("JWT public key does not match the private key".to_string())
+        })?;
+
+        Ok(Self::new(SigningKeys::Rsa {
+            encoding,
+            decoding: HashMap::from([(kid.clone(), decoding_key)]),
+            public: vec![jwk],
+            kid,
+        }))
+    }
+
+    /// Keep accepting tokens signed with a retired key (RS256 only)
+    ///
+    /// # Errors
+    /// - Config error if the key cannot be parsed or the keys are HS256
+    pub fn with_retired_key(mut self, public_pem: &[u8]) -> AppResult<Self> {
+        let SigningKeys::Rsa {
+            decoding, public, ..
+        } = &mut self.signing
+        else {
+            return Err(AppError::Config(
+                "Retired JWT keys require JWT_ALGORITHM=RS256".to_string(),
+            ));
+        };
+
+        let (kid, key, jwk) = rsa_public_key(public_pem)?;
+        if decoding.insert(kid, key).is_none() {
+            public.push(jwk);
+        }
+        Ok(self)
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        match &self.signing {
+            SigningKeys::Hmac(_) => Algorithm::HS256,
+            SigningKeys::Rsa { .. } => Algorithm::RS256,
+        }
+    }
+
+    /// `kid` written into the header of issued tokens
+    pub fn kid(&self) -> Option<&str> {
+        match &self.signing {
+            SigningKeys::Hmac(_) => None,
+            SigningKeys::Rsa { kid, .. } => Some(kid),
+        }
+    }
+
+    /// Public verification keys as a JWKS document (empty for HS256)
+    pub fn jwks(&self) -> JwkSet {
+        let keys = match &self.signing {
+            SigningKeys::Hmac(_) => Vec::new(),
+            SigningKeys::Rsa { public, .. } => public.clone(),
+        };
+        JwkSet { keys }
+    }
+
+    pub(crate) fn encoding_key(&self) -> Cow<'_, EncodingKey> {
+        match &self.signing {
+            SigningKeys::Hmac(secret) => Cow::Owned(EncodingKey::from_secret(secret.as_bytes())),
+            SigningKeys::Rsa { encoding, .. } => Cow::Borrowed(encoding),
+        }
+    }
+
+    /// Verification key for a token with the given `kid`
+    ///
+    /// # Errors
+    /// - Authentication error if no RS256 key has that `kid`
+    pub(crate) fn decoding_key(&self, kid: Option<&str>) -> AppResult<Cow<'_, DecodingKey>> {
+        match &self.signing {
+            SigningKeys::Hmac(secret) => Ok(Cow::Owned(DecodingKey::from_secret(secret.as_bytes()))),
+            SigningKeys::Rsa { decoding, .. } => kid
+                .and_then(|kid| decoding.get(kid))
+                .map(Cow::Borrowed)
+                .ok_or_else(|| {
+                    metrics::JWT_SIGNATURE_FAILURES.increment();
+                    AppError::authentication("Unknown token signing key")
+                }),
+        }
+    }
+}
+
+impl std::fmt::Debug for JwtKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print key material
+        f.debug_struct("JwtKeys")
+            .field("algorithm", &self.algorithm())
+            .field("kid", &self.kid())
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .finish()
+    }
+}
+
+/// Parse a PEM public key (SPKI or PKCS#1); its `kid` is derived from the
+/// key itself
+fn rsa_public_key(public_pem: &[u8]) -> AppResult<(String, DecodingKey, Jwk)> {
+    let invalid = |e: String| AppError::Config(format!("Invalid JWT public key: {}", e));
+    let pem = std::str::from_utf8(public_pem).map_err(|e| invalid(e.to_string()))?;
+    let key = RsaPublicKey::from_public_key_pem(pem)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let kid = key_id(public_pem);
+    let jwk = Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(KeyAlgorithm::RS256),
+            key_id: Some(kid.clone()),
+            ..Default::default()
+        },
+        algorithm: AlgorithmParameters::RSA(RSAKeyParameters {
+            key_type: RSAKeyType::RSA,
+            n: URL_SAFE_NO_PAD.encode(key.n().to_bytes_be()),
+            e: URL_SAFE_NO_PAD.encode(key.e().to_bytes_be()),
+        }),
+    };
+    let decoding = DecodingKey::from_jwk(&jwk).map_err(|e| invalid(e.to_string()))?;
+
+    Ok((kid, decoding, jwk))
+}
+
+/// Stable `kid`: first 16 hex chars of the SHA-256 of the trimmed PEM
+fn key_id(public_pem: &[u8]) -> String {
+    Sha256::digest(public_pem.trim_ascii())
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ProbeClaims {
+    probe: bool,
+}
+
+impl ProbeClaims {
+    fn new() -> Self {
+        Self { probe: true }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const PRIVATE_A: &[u8] =
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwt_rsa_a.pem"));
+    pub(crate) const PUBLIC_A: &[u8] =
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwt_rsa_a.pub.pem"));
+    pub(crate) const PRIVATE_B: &[u8] =
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwt_rsa_b.pem"));
+    pub(crate) const PUBLIC_B: &[u8] =
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwt_rsa_b.pub.pem"));
+
+    #[test]
+    fn test_rsa_kid_is_derived_from_public_key() {
+        let a = JwtKeys::rsa(PRIVATE_A, PUBLIC_A).unwrap();
+        let b = JwtKeys::rsa(PRIVATE_B, PUBLIC_B).unwrap();
+
+        assert_eq!(a.algorithm(), Algorithm::RS256);
+        assert_eq!(a.kid().unwrap().len(), 16);
+        assert_ne!(a.kid(), b.kid());
+        assert_eq!(a.kid(), JwtKeys::rsa(PRIVATE_A, PUBLIC_A).unwrap().kid());
+    }
+
+    #[test]
+    fn test_rsa_rejects_mismatched_key_pair() {
+        let result = JwtKeys::rsa(PRIVATE_A, PUBLIC_B);
+
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
+
+    #[test]
+    fn test_retired_keys_need_rsa() {
+        let result = JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars")
+            .with_retired_key(PUBLIC_A);
+
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
+
+    #[test]
+    fn test_jwks_lists_active_rsa_keys() {
+        let keys = JwtKeys::rsa(PRIVATE_B, PUBLIC_B)
+            .unwrap()
+            .with_retired_key(PUBLIC_A)
+            .unwrap()
+            .with_retired_key(PUBLIC_A)
+            .unwrap();
+
+        let jwks = keys.jwks();
+
+        let kids: Vec<_> = jwks.keys.iter().map(|k| k.common.key_id.clone().unwrap()).collect();
+        assert_eq!(kids.len(), 2);
+        assert_eq!(Some(kids[0].as_str()), keys.kid());
+        assert!(JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars")
+            .jwks()
+            .keys
+            .is_empty());
+    }
+
+    #[test]
+    fn test_jwk_modulus_and_exponent_match_public_key() {
+        let keys = JwtKeys::rsa(PRIVATE_A, PUBLIC_A).unwrap();
+        let jwk = keys.jwks().keys.remove(0);
+        let expected =
+            RsaPublicKey::from_public_key_pem(std::str::from_utf8(PUBLIC_A).unwrap()).unwrap();
+
+        let AlgorithmParameters::RSA(rsa) = &jwk.algorithm else {
+            panic!("expected an RSA key");
+        };
+        assert_eq!(URL_SAFE_NO_PAD.decode(&rsa.n).unwrap(), expected.n().to_bytes_be());
+        assert_eq!(URL_SAFE_NO_PAD.decode(&rsa.e).unwrap(), expected.e().to_bytes_be());
+
+        let json = serde_json::to_value(&jwk).unwrap();
+        assert_eq!(json["kty"], "RSA");
+        assert_eq!(json["use"], "sig");
+        assert_eq!(json["alg"], "RS256");
+        assert_eq!(json["kid"].as_str(), keys.kid());
+    }
+
+    #[test]
+    fn test_debug_hides_key_material() {
+        let keys = JwtKeys::hmac("super_secret_value_minimum_32_characters");
+
+        assert!(!format!("{:?}", keys).contains("super_secret"));
+    }
+}
@@ -0,0 +1,13 @@
+use crate::shared::types::*;
+use serde::Serialize;
+
+/// Recent login activity for a user
+///
+/// Surfaced to the account owner so they can notice attacks on their account.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, sqlx::FromRow)]
+pub struct LoginSecuritySummary {
+    /// Failed login attempts within the activity window
+    pub recent_failed_logins: i64,
+    /// Timestamp of the most recent failed attempt (not bounded by the window)
+    pub last_failed_at: Option<Timestamp>,
+}
@@ -0,0 +1,88 @@
+use super::one_time_token;
+use crate::shared::types::*;
+
+/// Wrong codes a challenge tolerates before it becomes unusable
+pub const MAX_FAILED_ATTEMPTS: i32 = 5;
+
+/// Pending second factor of an API login
+///
+/// Issued once the password checked out for a user with 2FA enabled; the
+/// client trades it, together with a TOTP code, for tokens. Like the other
+/// one-time tokens, only the hash is stored and a challenge is single-use.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MfaChallenge {
+    pub id: uuid::Uuid,
+    pub user_id: UserId,
+    /// Tenant resolved at password login, which the tokens are minted for
+    pub tenant_id: Option<OrganizationId>,
+    pub token_hash: String,
+    pub expires_at: Timestamp,
+    pub used_at: Option<Timestamp>,
+    pub failed_attempts: i32,
+    pub created_at: Timestamp,
+}
+
+impl MfaChallenge {
+    /// Issue a new challenge
+    ///
+    /// Returns the entity together with the plain token for the client.
+    pub fn issue(
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+        ttl_seconds: i64,
+    ) -> (Self, String) {
+        let plain = one_time_token::generate();
+        let now = now();
+
+        let challenge = Self {
+            id: new_id(),
+            user_id,
+            tenant_id,
+            token_hash: Self::hash(&plain),
+            expires_at: now + chrono::Duration::seconds(ttl_seconds),
+            used_at: None,
+            failed_attempts: 0,
+            created_at: now,
+        };
+
+        (challenge, plain)
+    }
+
+    /// Hash a plain token for storage and lookup
+    pub fn hash(plain: &str) -> String {
+        one_time_token::hash(plain)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        now() > self.expires_at
+    }
+
+    /// Whether a code can still be submitted for the challenge
+    pub fn is_usable(&self) -> bool {
+        self.used_at.is_none()
+            && !self.is_expired()
+            && self.failed_attempts < MAX_FAILED_ATTEMPTS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenge_is_unusable_after_too_many_failures() {
+        let (mut challenge, plain) = MfaChallenge::issue(UserId::new(), None, 300);
+        assert_eq!(challenge.token_hash, MfaChallenge::hash(&plain));
+        assert!(challenge.is_usable());
+
+        challenge.failed_attempts = MAX_FAILED_ATTEMPTS;
+        assert!(!challenge.is_usable());
+    }
+
+    #[test]
+    fn test_expired_challenge_is_unusable() {
+        let (challenge, _) = MfaChallenge::issue(UserId::new(), None, -1);
+
+        assert!(!challenge.is_usable());
+    }
+}
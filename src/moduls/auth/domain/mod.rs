@@ -1,15 +1,44 @@
-/// Domain layer for authentication module
-///
-/// This layer contains pure business logic with no external dependencies.
-/// Following DDD principles, domain entities enforce business rules and invariants.
+//! Domain layer for authentication module
+//!
+//! This layer contains pure business logic with no external dependencies.
+//! Following DDD principles, domain entities enforce business rules and invariants.
 
 pub mod user;
 pub mod session;
 pub mod token_pair;
+pub mod jwt_keys;
 pub mod value_objects;
+pub mod password_policy;
+pub mod login_activity;
+pub mod one_time_token;
+pub mod password_reset;
+pub mod email_verification;
+pub mod totp;
+pub mod mfa_challenge;
+pub mod role;
+pub mod api_key;
+pub mod client_hash;
+pub mod password_hasher;
+pub mod notification;
+pub mod hash_migration;
 
 // Re-export main types for convenience
-pub use user::{User, UserDto};
+pub use user::{AccountStatus, User, UserDto};
 pub use session::Session;
-pub use token_pair::{TokenPair, JwtToken};
-pub use value_objects::Email;
+pub use token_pair::{ClaimsFormat, ImpersonationToken, TokenPair, JwtToken};
+pub use jwt_keys::JwtKeys;
+pub use value_objects::{Email, PasswordHash};
+pub use password_policy::{
+    PasswordDenylist, PasswordPolicy, PasswordPolicyViolation, PasswordRequirement,
+};
+pub use login_activity::LoginSecuritySummary;
+pub use password_reset::PasswordResetToken;
+pub use email_verification::EmailVerificationToken;
+pub use totp::{TotpSecret, UserTotp};
+pub use mfa_challenge::MfaChallenge;
+pub use role::Role;
+pub use api_key::ApiKey;
+pub use client_hash::{ClientHashParams, ClientHashing};
+pub use password_hasher::PasswordHasher;
+pub use notification::{NotificationCategory, NotificationPreferences};
+pub use hash_migration::{HashMigrationRun, HashMigrationStatus};
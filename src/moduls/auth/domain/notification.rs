@@ -0,0 +1,106 @@
+use crate::shared::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+
+/// Kind of security email sent to a user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    /// Successful login from an IP address the user never logged in from
+    NewDeviceLogin,
+    /// Password changed from the account settings
+    PasswordChanged,
+    /// Password reset link (mandatory)
+    PasswordReset,
+}
+
+impl NotificationCategory {
+    /// Whether the email is sent whatever the user's preferences
+    ///
+    /// Mandatory emails are the ones the user acts on, not just informs them.
+    pub fn is_mandatory(self) -> bool {
+        matches!(self, NotificationCategory::PasswordReset)
+    }
+}
+
+/// Security emails a user chose to receive (`users.notification_preferences`)
+///
+/// Stored as JSONB; missing keys take their default (enabled), so
+/// categories added later are on for existing users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    pub new_device_login: bool,
+    pub password_changed: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            new_device_login: true,
+            password_changed: true,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    /// Whether emails of `category` should be sent
+    pub fn allows(&self, category: NotificationCategory) -> bool {
+        match category {
+            NotificationCategory::NewDeviceLogin => self.new_device_login,
+            NotificationCategory::PasswordChanged => self.password_changed,
+            NotificationCategory::PasswordReset => true,
+        }
+    }
+
+    /// Turn emails of `category` on or off
+    ///
+    /// # Errors
+    /// - Validation if disabling a mandatory category
+    pub fn set(&mut self, category: NotificationCategory, enabled: bool) -> AppResult<()> {
+        match category {
+            NotificationCategory::NewDeviceLogin => self.new_device_login = enabled,
+            NotificationCategory::PasswordChanged => self.password_changed = enabled,
+            NotificationCategory::PasswordReset if !enabled => {
+                return Err(AppError::validation("Password reset emails cannot be disabled"));
+            }
+            NotificationCategory::PasswordReset => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_everything_enabled_by_default() {
+        let preferences: NotificationPreferences = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(preferences, NotificationPreferences::default());
+        assert!(preferences.allows(NotificationCategory::NewDeviceLogin));
+        assert!(preferences.allows(NotificationCategory::PasswordChanged));
+    }
+
+    #[test]
+    fn test_disabled_category_is_not_allowed() {
+        let mut preferences = NotificationPreferences::default();
+
+        preferences.set(NotificationCategory::NewDeviceLogin, false).unwrap();
+
+        assert!(!preferences.allows(NotificationCategory::NewDeviceLogin));
+        assert!(preferences.allows(NotificationCategory::PasswordChanged));
+    }
+
+    #[test]
+    fn test_mandatory_category_cannot_be_disabled() {
+        let mut preferences = NotificationPreferences::default();
+
+        let result = preferences.set(NotificationCategory::PasswordReset, false);
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        assert!(preferences.allows(NotificationCategory::PasswordReset));
+        assert!(NotificationCategory::PasswordReset.is_mandatory());
+    }
+}
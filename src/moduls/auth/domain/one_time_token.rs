@@ -0,0 +1,41 @@
+//! Plain/hashed one-time tokens (password reset, email verification, MFA
+//! challenges)
+//!
+//! The plain value goes to the user once; only its hash is stored, so a
+//! database leak does not expose usable tokens.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// Generate a plain token (256 random bits, 43 URL-safe characters)
+pub fn generate() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Hash a plain token for storage and lookup (SHA-256, base64url)
+pub fn hash(plain: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(plain.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_is_random_and_url_safe() {
+        let a = generate();
+        let b = generate();
+
+        assert_eq!(a.len(), 43);
+        assert_ne!(a, b);
+        assert!(a.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn test_hash_is_deterministic() {
+        assert_eq!(hash("token"), hash("token"));
+        assert_ne!(hash("token"), "token");
+    }
+}
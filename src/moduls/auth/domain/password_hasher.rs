@@ -0,0 +1,114 @@
+//! Password hashing algorithms
+//!
+//! New hashes use the configured `PasswordHasher`; verification picks the
+//! algorithm from the stored hash (see `PasswordHash::verify`), so switching
+//! algorithm or cost keeps existing hashes working. Outdated hashes are
+//! upgraded at the next successful login.
+
+use super::PasswordHash;
+use crate::shared::{AppError, AppResult};
+use argon2::password_hash::{rand_core::OsRng, PasswordHash as PhcHash, SaltString};
+use argon2::{Argon2, PasswordHasher as _, PasswordVerifier as _};
+
+/// Algorithm (and cost) for new password hashes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordHasher {
+    Bcrypt { cost: u32 },
+    /// Argon2id with the crate's default (OWASP) parameters
+    Argon2,
+}
+
+impl Default for PasswordHasher {
+    fn default() -> Self {
+        Self::Bcrypt {
+            cost: bcrypt::DEFAULT_COST,
+        }
+    }
+}
+
+impl PasswordHasher {
+    /// Hash `secret` (a password or client hash) without validating it
+    pub fn hash(&self, secret: &str) -> AppResult<PasswordHash> {
+        let hash = match self {
+            Self::Bcrypt { cost } => bcrypt::hash(secret, *cost)
+                .map_err(|e| AppError::internal(format!("Failed to hash password: {}", e)))?,
+            Self::Argon2 => Argon2::default()
+                .hash_password(secret.as_bytes(), &SaltString::generate(&mut OsRng))
+                .map_err(|e| AppError::internal(format!("Failed to hash password: {}", e)))?
+                .to_string(),
+        };
+
+        Ok(PasswordHash::from_hash(hash))
+    }
+
+    /// Whether `hash` was made with another algorithm or cost than this
+    /// hasher would use now
+    pub fn needs_rehash(&self, hash: &PasswordHash) -> bool {
+        match self {
+            Self::Bcrypt { cost } => bcrypt_cost(hash.as_str()) != Some(*cost),
+            Self::Argon2 => {
+                let current = argon2::Params::default();
+                !PhcHash::new(hash.as_str())
+                    .ok()
+                    .filter(|phc| phc.algorithm == argon2::Algorithm::Argon2id.ident())
+                    .and_then(|phc| argon2::Params::try_from(&phc).ok())
+                    .is_some_and(|params| {
+                        (params.m_cost(), params.t_cost(), params.p_cost())
+                            == (current.m_cost(), current.t_cost(), current.p_cost())
+                    })
+            }
+        }
+    }
+}
+
+/// Verify `secret` against a stored bcrypt or argon2 hash
+pub(super) fn verify(secret: &str, hash: &str) -> AppResult<bool> {
+    if !hash.starts_with("$argon2") {
+        return bcrypt::verify(secret, hash)
+            .map_err(|e| AppError::internal(format!("Failed to verify password: {}", e)));
+    }
+
+    let phc = PhcHash::new(hash)
+        .map_err(|e| AppError::internal(format!("Failed to verify password: {}", e)))?;
+    match Argon2::default().verify_password(secret.as_bytes(), &phc) {
+        Ok(()) => Ok(true),
+        Err(argon2::password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(AppError::internal(format!("Failed to verify password: {}", e))),
+    }
+}
+
+/// Cost of a bcrypt hash (`None` for other algorithms)
+fn bcrypt_cost(hash: &str) -> Option<u32> {
+    hash.parse::<bcrypt::HashParts>().ok().map(|parts| parts.get_cost())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOW_COST: PasswordHasher = PasswordHasher::Bcrypt { cost: 4 };
+
+    #[test]
+    fn test_bcrypt_and_argon2_hashes_both_verify() {
+        let bcrypt = LOW_COST.hash("password123").unwrap();
+        let argon2 = PasswordHasher::Argon2.hash("password123").unwrap();
+
+        assert!(argon2.as_str().starts_with("$argon2id$"));
+        for hash in [&bcrypt, &argon2] {
+            assert!(hash.verify("password123").unwrap());
+            assert!(!hash.verify("password124").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_outdated_hashes_need_rehash() {
+        let bcrypt = LOW_COST.hash("password123").unwrap();
+        let argon2 = PasswordHasher::Argon2.hash("password123").unwrap();
+
+        assert!(!LOW_COST.needs_rehash(&bcrypt));
+        assert!(PasswordHasher::Bcrypt { cost: 5 }.needs_rehash(&bcrypt));
+        assert!(LOW_COST.needs_rehash(&argon2));
+        assert!(PasswordHasher::Argon2.needs_rehash(&bcrypt));
+        assert!(!PasswordHasher::Argon2.needs_rehash(&argon2));
+    }
+}
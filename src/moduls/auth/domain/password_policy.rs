@@ -0,0 +1,325 @@
+use super::PasswordHash;
+use crate::shared::{AppError, AppResult};
+use serde::{Serialize, Serializer};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A single rule of the password policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordRequirement {
+    MinLength,
+    MaxLength,
+    Uppercase,
+    Lowercase,
+    Digit,
+    Symbol,
+    /// Not on the common-password denylist
+    NotCommon,
+    /// None of the user's recent passwords (see `PasswordPolicy::history`)
+    NotReused,
+}
+
+impl PasswordRequirement {
+    /// English description of the rule under `policy`
+    pub fn message(&self, policy: &PasswordPolicy) -> String {
+        match self {
+            PasswordRequirement::MinLength => {
+                format!("Password must be at least {} characters", policy.min_length)
+            }
+            PasswordRequirement::MaxLength => format!(
+                "Password must be at most {} characters",
+                policy.max_length.unwrap_or_default()
+            ),
+            PasswordRequirement::Uppercase => "Password must contain an uppercase letter".to_string(),
+            PasswordRequirement::Lowercase => "Password must contain a lowercase letter".to_string(),
+            PasswordRequirement::Digit => "Password must contain a digit".to_string(),
+            PasswordRequirement::Symbol => "Password must contain a symbol".to_string(),
+            PasswordRequirement::NotCommon => "Password is too common".to_string(),
+            PasswordRequirement::NotReused => format!(
+                "Password must differ from your last {} passwords",
+                policy.history.unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Password strength rules for new passwords
+///
+/// Applied at registration, password change and password reset. Existing
+/// passwords are never re-checked, so tightening the policy doesn't lock
+/// anyone out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PasswordPolicy {
+    /// Minimum length in characters
+    pub min_length: usize,
+    /// Maximum length in characters (independent of the byte limit
+    /// checked before hashing, see `PasswordHash::ensure_max_length`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    /// Any character that is neither alphanumeric nor whitespace
+    pub require_symbol: bool,
+    /// Shown to clients as `reject_common: true` when not empty
+    #[serde(rename = "reject_common", skip_serializing_if = "PasswordDenylist::is_empty")]
+    pub denylist: PasswordDenylist,
+    /// Recent passwords, the current one included, a new password may not
+    /// match; checked against the user's password history when changing
+    /// or resetting the password, never at registration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history: Option<usize>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: PasswordHash::MIN_LENGTH,
+            max_length: None,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            denylist: PasswordDenylist::default(),
+            history: None,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Every rule `password` fails, in policy order (empty if it passes)
+    pub fn check(&self, password: &str) -> Vec<PasswordRequirement> {
+        let has = |pred: fn(char) -> bool| password.chars().any(pred);
+        let length = password.chars().count();
+
+        [
+            (length < self.min_length, PasswordRequirement::MinLength),
+            (self.max_length.is_some_and(|max| length > max), PasswordRequirement::MaxLength),
+            (self.require_uppercase && !has(char::is_uppercase), PasswordRequirement::Uppercase),
+            (self.require_lowercase && !has(char::is_lowercase), PasswordRequirement::Lowercase),
+            (self.require_digit && !has(|c| c.is_ascii_digit()), PasswordRequirement::Digit),
+            (
+                self.require_symbol && !has(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+                PasswordRequirement::Symbol,
+            ),
+            (self.denylist.contains(password), PasswordRequirement::NotCommon),
+        ]
+        .into_iter()
+        .filter_map(|(failed, requirement)| failed.then_some(requirement))
+        .collect()
+    }
+
+    /// Check `password`, reporting failures against the request `field`
+    ///
+    /// # Errors
+    /// - PasswordPolicy listing every failed rule and the full policy
+    pub fn enforce(&self, password: &str, field: &'static str) -> AppResult<()> {
+        let failed = self.check(password);
+        if failed.is_empty() {
+            return Ok(());
+        }
+
+        Err(self.violation(field, failed))
+    }
+
+    /// Violation for a new password matching one of the user's recent ones
+    pub fn reused(&self, field: &'static str) -> AppError {
+        self.violation(field, vec![PasswordRequirement::NotReused])
+    }
+
+    fn violation(&self, field: &'static str, failed: Vec<PasswordRequirement>) -> AppError {
+        AppError::PasswordPolicy(PasswordPolicyViolation {
+            field,
+            failed,
+            policy: self.clone(),
+        })
+    }
+}
+
+/// Common passwords the policy rejects, compared case-insensitively
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PasswordDenylist(Arc<HashSet<String>>);
+
+impl PasswordDenylist {
+    pub fn new<I, S>(passwords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self(Arc::new(
+            passwords.into_iter().map(|p| p.as_ref().to_lowercase()).collect(),
+        ))
+    }
+
+    /// Parse a list with one password per line; blank lines and lines
+    /// starting with `#` are skipped
+    pub fn parse(list: &str) -> Self {
+        Self::new(
+            list.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#')),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn contains(&self, password: &str) -> bool {
+        !self.is_empty() && self.0.contains(&password.to_lowercase())
+    }
+}
+
+impl Serialize for PasswordDenylist {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bool(!self.is_empty())
+    }
+}
+
+/// Failed password rules together with the policy, so clients can render
+/// the whole checklist at once
+#[derive(Debug, Clone, Serialize)]
+pub struct PasswordPolicyViolation {
+    /// Request field holding the password
+    #[serde(skip)]
+    pub field: &'static str,
+    pub failed: Vec<PasswordRequirement>,
+    pub policy: PasswordPolicy,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 12,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+            ..PasswordPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_default_policy_only_checks_length() {
+        let policy = PasswordPolicy::default();
+
+        assert!(policy.check("password").is_empty());
+        assert_eq!(policy.check("short"), vec![PasswordRequirement::MinLength]);
+    }
+
+    #[test]
+    fn test_short_password_without_digit_reports_both() {
+        let policy = PasswordPolicy {
+            min_length: 12,
+            require_digit: true,
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(
+            policy.check("abcdef"),
+            vec![PasswordRequirement::MinLength, PasswordRequirement::Digit]
+        );
+    }
+
+    #[test]
+    fn test_every_class_is_checked() {
+        let policy = strict();
+
+        assert_eq!(
+            policy.check("ABCDEFGHIJKLM"),
+            vec![
+                PasswordRequirement::Lowercase,
+                PasswordRequirement::Digit,
+                PasswordRequirement::Symbol
+            ]
+        );
+        assert!(policy.check("Correct-Horse-42").is_empty());
+    }
+
+    #[test]
+    fn test_each_rule_fires_independently() {
+        let policy = PasswordPolicy {
+            max_length: Some(20),
+            denylist: PasswordDenylist::new(["Correct-Horse-42"]),
+            ..strict()
+        };
+        let cases = [
+            ("Short-Pw-1", PasswordRequirement::MinLength),
+            ("Much-Too-Long-Password-42", PasswordRequirement::MaxLength),
+            ("lowercase-only-42", PasswordRequirement::Uppercase),
+            ("UPPERCASE-ONLY-42", PasswordRequirement::Lowercase),
+            ("No-Digits-Here", PasswordRequirement::Digit),
+            ("NoSymbolsHere42", PasswordRequirement::Symbol),
+        ];
+
+        for (password, requirement) in cases {
+            assert_eq!(policy.check(password), vec![requirement], "{}", password);
+        }
+        assert_eq!(policy.check("Correct-Horse-42"), vec![PasswordRequirement::NotCommon]);
+        assert!(policy.check("Correct-Horse-43").is_empty());
+    }
+
+    #[test]
+    fn test_denylist_ignores_case() {
+        let policy = PasswordPolicy {
+            denylist: PasswordDenylist::parse("# common passwords\npassword123\n\n  qwertyuiop  \n"),
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(policy.denylist.len(), 2);
+        assert_eq!(policy.check("PassWord123"), vec![PasswordRequirement::NotCommon]);
+        assert_eq!(policy.check("qwertyuiop"), vec![PasswordRequirement::NotCommon]);
+        assert!(policy.check("# common passwords").is_empty());
+    }
+
+    #[test]
+    fn test_optional_rules_are_only_serialized_when_set() {
+        let json = serde_json::to_value(PasswordPolicy::default()).unwrap();
+        assert!(json.get("max_length").is_none() && json.get("reject_common").is_none());
+        assert!(json.get("history").is_none());
+
+        let policy = PasswordPolicy {
+            max_length: Some(64),
+            denylist: PasswordDenylist::new(["password"]),
+            history: Some(5),
+            ..PasswordPolicy::default()
+        };
+        let json = serde_json::to_value(policy).unwrap();
+        assert_eq!(json["max_length"], 64);
+        assert_eq!(json["reject_common"], true);
+        assert_eq!(json["history"], 5);
+    }
+
+    #[test]
+    fn test_length_counts_characters() {
+        let policy = PasswordPolicy::default();
+
+        // 8 characters, 16 bytes
+        assert!(policy.check("ääääääää").is_empty());
+    }
+
+    #[test]
+    fn test_enforce_reports_violation() {
+        let policy = strict();
+
+        let Err(AppError::PasswordPolicy(violation)) = policy.enforce("abc", "password") else {
+            panic!("expected a password policy violation");
+        };
+
+        assert_eq!(violation.field, "password");
+        assert_eq!(violation.failed.len(), 4);
+        assert_eq!(
+            violation.failed[0].message(&violation.policy),
+            "Password must be at least 12 characters"
+        );
+        assert_eq!(violation.policy, policy);
+    }
+}
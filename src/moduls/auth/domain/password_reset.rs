@@ -0,0 +1,82 @@
+use super::one_time_token;
+use crate::shared::types::*;
+
+/// Password reset token entity
+///
+/// Only the SHA-256 hash of the token is stored; the plain token is handed
+/// to the user once and never persisted. A token is single-use.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PasswordResetToken {
+    pub id: uuid::Uuid,
+    pub user_id: UserId,
+    pub token_hash: String,
+    pub expires_at: Timestamp,
+    pub used_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
+
+impl PasswordResetToken {
+    /// Issue a new token for a user
+    ///
+    /// Returns the entity together with the plain token (43 URL-safe
+    /// characters) to deliver to the user.
+    pub fn issue(user_id: UserId, ttl_seconds: i64) -> (Self, String) {
+        let plain = one_time_token::generate();
+        let now = now();
+
+        let token = Self {
+            id: new_id(),
+            user_id,
+            token_hash: Self::hash(&plain),
+            expires_at: now + chrono::Duration::seconds(ttl_seconds),
+            used_at: None,
+            created_at: now,
+        };
+
+        (token, plain)
+    }
+
+    /// Hash a plain token for storage and lookup
+    pub fn hash(plain: &str) -> String {
+        one_time_token::hash(plain)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        now() > self.expires_at
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used_at.is_some()
+    }
+
+    /// Whether the token can still be redeemed
+    pub fn is_usable(&self) -> bool {
+        !self.is_used() && !self.is_expired()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_stores_only_hash() {
+        let (token, plain) = PasswordResetToken::issue(UserId::new(), 1800);
+
+        assert_eq!(plain.len(), 43);
+        assert_ne!(token.token_hash, plain);
+        assert_eq!(token.token_hash, PasswordResetToken::hash(&plain));
+        assert!(token.is_usable());
+    }
+
+    #[test]
+    fn test_expired_and_used_tokens_are_unusable() {
+        let (mut token, _) = PasswordResetToken::issue(UserId::new(), -1);
+        assert!(token.is_expired());
+        assert!(!token.is_usable());
+
+        token.expires_at = now() + chrono::Duration::seconds(60);
+        token.used_at = Some(now());
+        assert!(!token.is_usable());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::shared::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+
+/// Role value object
+///
+/// Roles gate privileged endpoints (`require_role`). A role is a lowercase
+/// name; which roles exist is decided by the `roles` table, so granting an
+/// unknown role fails in the repository.
+#[derive(Debug, Clone, sqlx::Type, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct Role(String);
+
+impl Role {
+    /// Administrators manage users and their roles
+    pub const ADMIN: &'static str = "admin";
+
+    /// Super admins act across tenants (e.g. revoke a tenant's credentials)
+    pub const SUPER_ADMIN: &'static str = "super_admin";
+
+    /// Create a Role, normalizing the name to lowercase
+    ///
+    /// Business Rules:
+    /// - 1-50 characters of lowercase letters, digits, `_` and `-`
+    pub fn new(name: &str) -> AppResult<Self> {
+        let name = name.trim().to_lowercase();
+
+        if name.is_empty() || name.len() > 50 {
+            return Err(AppError::validation("Role must be between 1 and 50 characters"));
+        }
+
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        {
+            return Err(AppError::validation(
+                "Role may only contain lowercase letters, digits, underscores, and hyphens",
+            ));
+        }
+
+        Ok(Self(name))
+    }
+
+    /// The administrator role
+    pub fn admin() -> Self {
+        Self(Self::ADMIN.to_string())
+    }
+
+    /// Get role name as str
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_is_normalized() {
+        assert_eq!(Role::new(" Admin ").unwrap(), Role::admin());
+        assert_eq!(Role::new("support_agent-2").unwrap().as_str(), "support_agent-2");
+    }
+
+    #[test]
+    fn test_invalid_role_rejected() {
+        assert!(Role::new("").is_err());
+        assert!(Role::new("super admin").is_err());
+        assert!(Role::new(&"a".repeat(51)).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::shared::types::*;
+use crate::shared::{types::*, AppError, AppResult};
 use super::value_objects::CsrfToken;
 
 /// Session entity for web authentication
@@ -10,6 +10,8 @@ pub struct Session {
     pub csrf_token: CsrfToken,
     pub ip_address: Option<String>,  // Store IP as string for SQLx compatibility
     pub user_agent: Option<String>,
+    /// Label chosen by the client at login (e.g. "John's iPhone")
+    pub device_name: Option<String>,
     pub expires_at: Timestamp,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
@@ -21,7 +23,8 @@ impl Session {
     /// Business Rules:
     /// - Session expires after TTL (configurable, typically 24 hours)
     /// - CSRF token generated on creation
-    /// - One session per user for web (enforced in repository)
+    /// - Concurrent sessions per user are capped by the repository
+    ///   (`SESSION_MAX_CONCURRENT`)
     ///
     /// # Arguments
     /// * `user_id` - ID of the user this session belongs to
@@ -34,21 +37,74 @@ impl Session {
         user_agent: Option<String>,
         ttl_seconds: i64,
     ) -> Self {
-        let now = now();
+        Self::new_with(user_id, ip_address, user_agent, ttl_seconds, &SystemClock, &SystemIdGenerator)
+    }
+
+    /// Create new Session taking the time and ID from `clock` and `ids`
+    pub fn new_with(
+        user_id: UserId,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+        ttl_seconds: i64,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+    ) -> Self {
+        let now = clock.now();
         let expires_at = now + chrono::Duration::seconds(ttl_seconds);
 
         Self {
-            id: new_id(),
+            id: SessionId::from_uuid(ids.new_id()),
             user_id,
             csrf_token: CsrfToken::generate(),
             ip_address,
             user_agent,
+            device_name: None,
             expires_at,
             created_at: now,
             updated_at: now,
         }
     }
 
+    /// Label the session's device
+    ///
+    /// `device_name` is expected to be normalized (see `normalize_device_name`)
+    pub fn with_device_name(mut self, device_name: Option<String>) -> Self {
+        self.device_name = device_name;
+        self
+    }
+
+    /// Normalize a client-supplied device name
+    ///
+    /// Business Rules:
+    /// - Surrounding whitespace is trimmed; a blank name means no name
+    /// - At most `max_length` characters
+    /// - No control characters (the name is shown back in device lists)
+    ///
+    /// # Errors
+    /// - Validation error if the name is too long or has control characters
+    pub fn normalize_device_name(
+        device_name: Option<String>,
+        max_length: usize,
+    ) -> AppResult<Option<String>> {
+        let Some(name) = device_name.as_deref().map(str::trim).filter(|n| !n.is_empty()) else {
+            return Ok(None);
+        };
+
+        if name.chars().count() > max_length {
+            return Err(AppError::Validation(format!(
+                "Device name cannot exceed {} characters",
+                max_length
+            )));
+        }
+        if name.chars().any(char::is_control) {
+            return Err(AppError::Validation(
+                "Device name cannot contain control characters".into(),
+            ));
+        }
+
+        Ok(Some(name.to_string()))
+    }
+
     /// Check if session is expired
     ///
     /// Returns true if current time is past expiration time
@@ -66,6 +122,21 @@ impl Session {
         self.updated_at = now;
     }
 
+    /// Lifetime given at creation or the last refresh
+    pub fn ttl(&self) -> chrono::Duration {
+        self.expires_at - self.updated_at
+    }
+
+    /// Check if activity should extend the session (sliding expiration)
+    ///
+    /// True once more than half of its TTL has elapsed since creation or the
+    /// last refresh, and at least `min_interval_seconds` have passed, which
+    /// bounds how often an active session is written back.
+    pub fn needs_refresh(&self, min_interval_seconds: i64) -> bool {
+        let elapsed = now() - self.updated_at;
+        elapsed * 2 > self.ttl() && elapsed >= chrono::Duration::seconds(min_interval_seconds)
+    }
+
     /// Verify CSRF token
     ///
     /// Uses constant-time comparison to prevent timing attacks
@@ -116,7 +187,7 @@ mod tests {
 
     #[test]
     fn test_create_session() {
-        let user_id = new_id();
+        let user_id = UserId::new();
         let ip = Some("127.0.0.1".to_string());
         let user_agent = Some("Mozilla/5.0".to_string());
         let ttl = 3600; // 1 hour
@@ -129,9 +200,47 @@ mod tests {
         assert!(!session.is_expired());
     }
 
+    #[test]
+    fn test_create_session_with_fixed_clock_and_ids() {
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let clock = FixedClock::new(start);
+        let ids = SequentialIdGenerator::new(start);
+
+        let session = Session::new_with(UserId::new(), None, None, 3600, &clock, &ids);
+
+        assert_eq!(session.id, SessionId::from_uuid(ids.nth(1)));
+        assert_eq!(session.created_at, start);
+        assert_eq!(session.updated_at, start);
+        assert_eq!(session.expires_at, start + chrono::Duration::seconds(3600));
+    }
+
+    #[test]
+    fn test_device_name_is_trimmed_and_blank_is_none() {
+        assert_eq!(
+            Session::normalize_device_name(Some("  John's iPhone ".to_string()), 64).unwrap(),
+            Some("John's iPhone".to_string())
+        );
+        assert_eq!(Session::normalize_device_name(Some("   ".to_string()), 64).unwrap(), None);
+        assert_eq!(Session::normalize_device_name(None, 64).unwrap(), None);
+    }
+
+    #[test]
+    fn test_device_name_is_validated() {
+        // Length is counted in characters: 4 characters, 8 bytes
+        assert!(Session::normalize_device_name(Some("ääää".to_string()), 4).is_ok());
+        assert!(matches!(
+            Session::normalize_device_name(Some("ääääa".to_string()), 4),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            Session::normalize_device_name(Some("Phone\nAdmin".to_string()), 64),
+            Err(AppError::Validation(_))
+        ));
+    }
+
     #[test]
     fn test_session_expiration() {
-        let user_id = new_id();
+        let user_id = UserId::new();
         let ttl = -1; // Expired 1 second ago
 
         let session = Session::new(user_id, None, None, ttl);
@@ -142,7 +251,7 @@ mod tests {
 
     #[test]
     fn test_session_refresh() {
-        let user_id = new_id();
+        let user_id = UserId::new();
         let ttl = -1; // Start expired
 
         let mut session = Session::new(user_id, None, None, ttl);
@@ -155,9 +264,27 @@ mod tests {
         assert!(session.is_valid());
     }
 
+    #[test]
+    fn test_session_needs_refresh_past_half_ttl() {
+        let mut session = Session::new(UserId::new(), None, None, 3600);
+        assert!(!session.needs_refresh(60));
+
+        // 40 of 60 minutes elapsed
+        let forty_minutes = chrono::Duration::minutes(40);
+        session.created_at -= forty_minutes;
+        session.updated_at -= forty_minutes;
+        session.expires_at -= forty_minutes;
+        assert!(session.needs_refresh(60));
+        assert!(!session.needs_refresh(3600));
+
+        session.refresh(session.ttl().num_seconds());
+        assert_eq!(session.ttl(), chrono::Duration::seconds(3600));
+        assert!(!session.needs_refresh(60));
+    }
+
     #[test]
     fn test_csrf_verification() {
-        let user_id = new_id();
+        let user_id = UserId::new();
         let session = Session::new(user_id, None, None, 3600);
 
         let valid_token = session.csrf_token.as_str();
@@ -169,7 +296,7 @@ mod tests {
 
     #[test]
     fn test_session_cookie() {
-        let user_id = new_id();
+        let user_id = UserId::new();
         let session = Session::new(user_id, None, None, 3600);
 
         let cookie = SessionCookie::from_session(&session);
@@ -1,5 +1,7 @@
-use crate::shared::{types::*, AppError, AppResult};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use super::{JwtKeys, Role};
+use crate::shared::{metrics, types::*, AppError, AppResult};
+use jsonwebtoken::{decode, decode_header, encode, Header, Validation};
+use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 
 /// Token pair response for API authentication
@@ -10,6 +12,10 @@ pub struct TokenPair {
     pub refresh_token: String,
     pub token_type: String,  // Always "Bearer"
     pub expires_in: i64,     // Access token expiry in seconds
+    /// Absolute access token expiry (`exp`), unaffected by network delay
+    pub access_expires_at: Timestamp,
+    /// Absolute refresh token expiry
+    pub refresh_expires_at: Timestamp,
 }
 
 /// JWT Token entity stored in database for revocation tracking
@@ -19,7 +25,12 @@ pub struct JwtToken {
     pub id: TokenId,
     pub user_id: UserId,
     pub token_type: TokenType,
-    pub jti: uuid::Uuid,  // JWT ID for revocation
+    pub jti: TokenId,  // JWT ID for revocation
+    /// Login this token descends from, kept across refresh rotations
+    pub family_id: uuid::Uuid,
+    /// Web session the token was minted under (`sid`); revoking the
+    /// session revokes the token
+    pub session_id: Option<SessionId>,
     pub expires_at: Timestamp,
     pub revoked: bool,
     pub revoked_at: Option<Timestamp>,
@@ -43,6 +54,48 @@ impl std::fmt::Display for TokenType {
     }
 }
 
+/// Encoding used for issued tokens
+///
+/// `Minimal` trades readability for size (mobile/IoT clients): no `typ`
+/// header, a one-letter token type claim (`t`: `a`/`r`) and UUIDs without
+/// hyphens. `decode` accepts both formats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClaimsFormat {
+    #[default]
+    Verbose,
+    Minimal,
+}
+
+impl ClaimsFormat {
+    /// Format selected by the `JWT_MINIMAL_CLAIMS` flag
+    pub fn from_minimal_flag(minimal: bool) -> Self {
+        if minimal {
+            Self::Minimal
+        } else {
+            Self::Verbose
+        }
+    }
+}
+
+/// Access token minted for admin impersonation
+///
+/// Comes without a refresh token: once it expires, the admin has to
+/// impersonate again.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpersonationToken {
+    pub access_token: String,
+    pub token_type: String, // Always "Bearer"
+    pub expires_in: i64,
+    pub access_expires_at: Timestamp,
+}
+
+/// Party acting on behalf of the subject (RFC 8693 `act` claim)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Actor {
+    /// Impersonating admin's user ID
+    pub sub: String,
+}
+
 /// JWT Claims structure
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
@@ -50,9 +103,65 @@ pub struct Claims {
     pub jti: String,        // JWT ID (for revocation)
     pub exp: i64,           // Expiration time (unix timestamp)
     pub iat: i64,           // Issued at (unix timestamp)
+    #[serde(alias = "t")]
     pub token_type: String, // "access" or "refresh"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tid: Option<String>, // Tenant (organization) ID, if selected at login
+    /// When the user last presented credentials (unix timestamp); carried
+    /// over on refresh. Absent in tokens minted before the claim existed,
+    /// and in minimal tokens where it equals `iat`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<i64>,
+    /// Roles of the user when the token was minted. Absent when they
+    /// weren't loaded (e.g. at registration); readers then ask the database
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<String>>,
+    /// Issuer (`JWT_ISSUER`), if configured when the token was minted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    /// Audience (`JWT_AUDIENCE`), if configured when the token was minted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    /// Web session the token was minted under, carried over on refresh
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    /// Admin impersonating the subject; such tokens are never refreshed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>,
 }
 
+/// Claims as encoded in `ClaimsFormat::Minimal`
+#[derive(Serialize)]
+struct MinimalClaims {
+    sub: String,
+    jti: String,
+    exp: i64,
+    iat: i64,
+    t: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auth_time: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    roles: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aud: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    act: Option<Actor>,
+}
+
+/// Clock-skew leeway for `exp` when decoding (jsonwebtoken's default)
+///
+/// Refresh tokens use `decode_with_leeway` with their own, tighter grace.
+pub const ACCESS_LEEWAY_SECONDS: u64 = 60;
+
+/// Short token type names used by `ClaimsFormat::Minimal`
+const MINIMAL_TOKEN_TYPES: [(&str, &str); 2] = [("a", "access"), ("r", "refresh")];
+
 impl TokenPair {
     /// Generate new token pair for user
     ///
@@ -64,7 +173,7 @@ impl TokenPair {
     ///
     /// # Arguments
     /// * `user_id` - User ID to encode in token
-    /// * `jwt_secret` - Secret key for signing tokens
+    /// * `keys` - Keys for signing tokens
     /// * `access_ttl` - Access token TTL in seconds
     /// * `refresh_ttl` - Refresh token TTL in seconds
     ///
@@ -72,63 +181,186 @@ impl TokenPair {
     /// Tuple of (TokenPair, AccessJwtToken, RefreshJwtToken) for persistence
     pub fn generate(
         user_id: UserId,
-        jwt_secret: &str,
+        keys: &JwtKeys,
         access_ttl: i64,
         refresh_ttl: i64,
     ) -> AppResult<(Self, JwtToken, JwtToken)> {
-        let now = now();
+        Self::generate_for_tenant(user_id, None, keys, access_ttl, refresh_ttl)
+    }
+
+    /// Generate new token pair taking `iat` from `clock` and the IDs
+    /// (`jti`s, family, rows) from `ids`
+    pub fn generate_with_sources(
+        user_id: UserId,
+        keys: &JwtKeys,
+        access_ttl: i64,
+        refresh_ttl: i64,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+    ) -> AppResult<(Self, JwtToken, JwtToken)> {
+        Self::issue(
+            user_id,
+            None,
+            None,
+            None,
+            None,
+            ClaimsFormat::Verbose,
+            keys,
+            access_ttl,
+            refresh_ttl,
+            clock,
+            ids,
+        )
+    }
+
+    /// Generate new token pair scoped to a tenant
+    ///
+    /// Same as `generate`, but both tokens carry the `tid` claim so the
+    /// tenant survives token refresh.
+    pub fn generate_for_tenant(
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+        keys: &JwtKeys,
+        access_ttl: i64,
+        refresh_ttl: i64,
+    ) -> AppResult<(Self, JwtToken, JwtToken)> {
+        Self::generate_with_format(
+            user_id,
+            tenant_id,
+            ClaimsFormat::Verbose,
+            keys,
+            access_ttl,
+            refresh_ttl,
+        )
+    }
+
+    /// Generate new token pair in the given claims format
+    ///
+    /// For a fresh authentication: `auth_time` is set to `iat`. No `roles`
+    /// claim is embedded.
+    pub fn generate_with_format(
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+        format: ClaimsFormat,
+        keys: &JwtKeys,
+        access_ttl: i64,
+        refresh_ttl: i64,
+    ) -> AppResult<(Self, JwtToken, JwtToken)> {
+        Self::generate_with_auth_time(
+            user_id,
+            tenant_id,
+            None,
+            None,
+            None,
+            format,
+            keys,
+            access_ttl,
+            refresh_ttl,
+        )
+    }
+
+    /// Generate new token pair keeping an earlier authentication time
+    ///
+    /// Used on refresh, so `auth_time` keeps recording when the user
+    /// actually signed in while `iat` moves on. `None` means now.
+    /// `roles`, when given, are embedded as the `roles` claim, and
+    /// `session_id` as the `sid` claim.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_with_auth_time(
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+        auth_time: Option<i64>,
+        roles: Option<&[Role]>,
+        session_id: Option<SessionId>,
+        format: ClaimsFormat,
+        keys: &JwtKeys,
+        access_ttl: i64,
+        refresh_ttl: i64,
+    ) -> AppResult<(Self, JwtToken, JwtToken)> {
+        Self::issue(
+            user_id,
+            tenant_id,
+            auth_time,
+            roles,
+            session_id,
+            format,
+            keys,
+            access_ttl,
+            refresh_ttl,
+            &SystemClock,
+            &SystemIdGenerator,
+        )
+    }
+
+    /// `generate_with_auth_time` with explicit time and ID sources
+    #[allow(clippy::too_many_arguments)]
+    fn issue(
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+        auth_time: Option<i64>,
+        roles: Option<&[Role]>,
+        session_id: Option<SessionId>,
+        format: ClaimsFormat,
+        keys: &JwtKeys,
+        access_ttl: i64,
+        refresh_ttl: i64,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+    ) -> AppResult<(Self, JwtToken, JwtToken)> {
+        let now = clock.now();
         let iat = now.timestamp();
+        let auth_time = auth_time.unwrap_or(iat);
+        let roles: Option<Vec<String>> =
+            roles.map(|roles| roles.iter().map(|r| r.as_str().to_string()).collect());
 
         // Generate access token
-        let access_jti = new_id();
+        let access_jti = TokenId::from_uuid(ids.new_id());
         let access_exp = iat + access_ttl;
-        let access_claims = Claims {
-            sub: user_id.to_string(),
-            jti: access_jti.to_string(),
-            exp: access_exp,
+        let access_token = encode_claims(
+            format,
+            user_id,
+            access_jti,
+            access_exp,
             iat,
-            token_type: "access".to_string(),
-        };
-
-        let access_token = encode(
-            &Header::default(),
-            &access_claims,
-            &EncodingKey::from_secret(jwt_secret.as_bytes()),
+            auth_time,
+            roles.clone(),
+            TokenType::Access,
+            tenant_id,
+            session_id,
+            None,
+            keys,
         )
         .map_err(|e| AppError::internal(format!("Failed to encode access token: {}", e)))?;
 
         // Generate refresh token
-        let refresh_jti = new_id();
+        let refresh_jti = TokenId::from_uuid(ids.new_id());
         let refresh_exp = iat + refresh_ttl;
-        let refresh_claims = Claims {
-            sub: user_id.to_string(),
-            jti: refresh_jti.to_string(),
-            exp: refresh_exp,
+        let refresh_token = encode_claims(
+            format,
+            user_id,
+            refresh_jti,
+            refresh_exp,
             iat,
-            token_type: "refresh".to_string(),
-        };
-
-        let refresh_token = encode(
-            &Header::default(),
-            &refresh_claims,
-            &EncodingKey::from_secret(jwt_secret.as_bytes()),
+            auth_time,
+            roles,
+            TokenType::Refresh,
+            tenant_id,
+            session_id,
+            None,
+            keys,
         )
         .map_err(|e| AppError::internal(format!("Failed to encode refresh token: {}", e)))?;
 
-        // Create token pair response
-        let token_pair = TokenPair {
-            access_token,
-            refresh_token,
-            token_type: "Bearer".to_string(),
-            expires_in: access_ttl,
-        };
-
-        // Create JwtToken entities for persistence
+        // Create JwtToken entities for persistence; a pair starts a new
+        // family, the refresh flow moves it into the rotated token's one
+        let family_id = ids.new_id();
         let access_jwt_token = JwtToken {
-            id: new_id(),
+            id: TokenId::from_uuid(ids.new_id()),
             user_id,
             token_type: TokenType::Access,
             jti: access_jti,
+            family_id,
+            session_id,
             expires_at: chrono::DateTime::from_timestamp(access_exp, 0)
                 .ok_or_else(|| AppError::internal("Invalid access token expiration"))?,
             revoked: false,
@@ -137,10 +369,12 @@ impl TokenPair {
         };
 
         let refresh_jwt_token = JwtToken {
-            id: new_id(),
+            id: TokenId::from_uuid(ids.new_id()),
             user_id,
             token_type: TokenType::Refresh,
             jti: refresh_jti,
+            family_id,
+            session_id,
             expires_at: chrono::DateTime::from_timestamp(refresh_exp, 0)
                 .ok_or_else(|| AppError::internal("Invalid refresh token expiration"))?,
             revoked: false,
@@ -148,28 +382,122 @@ impl TokenPair {
             created_at: now,
         };
 
+        // Create token pair response
+        let token_pair = TokenPair {
+            access_token,
+            refresh_token,
+            token_type: "Bearer".to_string(),
+            expires_in: access_ttl,
+            access_expires_at: access_jwt_token.expires_at,
+            refresh_expires_at: refresh_jwt_token.expires_at,
+        };
+
         Ok((token_pair, access_jwt_token, refresh_jwt_token))
     }
 
+    /// Generate an access token for `actor_id` impersonating `user_id`
+    ///
+    /// Access only, with the `act` claim naming the admin. `ttl` is capped
+    /// at `max_ttl`, whatever the caller asks for. `roles` are embedded as
+    /// given, so the token never picks up roles from the database. Like API
+    /// keys, `auth_time` is the Unix epoch: the token never counts as a
+    /// fresh login.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_impersonation(
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+        actor_id: UserId,
+        roles: &[Role],
+        format: ClaimsFormat,
+        keys: &JwtKeys,
+        ttl: i64,
+        max_ttl: i64,
+    ) -> AppResult<(ImpersonationToken, JwtToken)> {
+        let now = now();
+        let iat = now.timestamp();
+        let ttl = ttl.min(max_ttl).max(1);
+
+        let jti = TokenId::new();
+        let exp = iat + ttl;
+        let access_token = encode_claims(
+            format,
+            user_id,
+            jti,
+            exp,
+            iat,
+            0,
+            Some(roles.iter().map(|r| r.as_str().to_string()).collect()),
+            TokenType::Access,
+            tenant_id,
+            None,
+            Some(actor_id),
+            keys,
+        )
+        .map_err(|e| AppError::internal(format!("Failed to encode impersonation token: {}", e)))?;
+
+        let jwt_token = JwtToken {
+            id: TokenId::new(),
+            user_id,
+            token_type: TokenType::Access,
+            jti,
+            family_id: new_id(),
+            session_id: None,
+            expires_at: chrono::DateTime::from_timestamp(exp, 0)
+                .ok_or_else(|| AppError::internal("Invalid impersonation token expiration"))?,
+            revoked: false,
+            revoked_at: None,
+            created_at: now,
+        };
+
+        let token = ImpersonationToken {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: ttl,
+            access_expires_at: jwt_token.expires_at,
+        };
+
+        Ok((token, jwt_token))
+    }
+
     /// Decode and validate JWT token
     ///
-    /// Validates signature, expiration, and token structure
+    /// Validates signature, expiration, and token structure, plus `iss`
+    /// and `aud` when the keys have an issuer or audience (tokens without
+    /// them are rejected then; without, any are accepted)
     /// Does NOT check revocation - caller must check against database
     ///
+    /// Only the configured algorithm is accepted; with RS256 the
+    /// verification key is picked by the token's `kid` header.
+    ///
     /// # Arguments
     /// * `token` - JWT token string to decode
-    /// * `jwt_secret` - Secret key for validation
+    /// * `keys` - Keys for validation
     ///
     /// # Returns
     /// Decoded Claims if valid
-    pub fn decode(token: &str, jwt_secret: &str) -> AppResult<Claims> {
-        let validation = Validation::default();
+    pub fn decode(token: &str, keys: &JwtKeys) -> AppResult<Claims> {
+        Self::decode_with_leeway(token, keys, ACCESS_LEEWAY_SECONDS)
+    }
 
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(jwt_secret.as_bytes()),
-            &validation,
-        )
+    /// Decode and validate, accepting tokens up to `leeway_seconds` past `exp`
+    pub fn decode_with_leeway(token: &str, keys: &JwtKeys, leeway_seconds: u64) -> AppResult<Claims> {
+        let header = decode_header(token).map_err(|_| AppError::authentication("Invalid token"))?;
+        let key = keys.decoding_key(header.kid.as_deref())?;
+        let mut validation = Validation::new(keys.algorithm());
+        validation.leeway = leeway_seconds;
+        if let Some(issuer) = keys.issuer() {
+            validation.set_issuer(&[issuer]);
+            validation.required_spec_claims.insert("iss".to_string());
+        }
+        match keys.audience() {
+            Some(audience) => {
+                validation.set_audience(&[audience]);
+                validation.required_spec_claims.insert("aud".to_string());
+            }
+            None => validation.validate_aud = false,
+        }
+
+        let token_data = decode::<Claims>(token, &key, &validation)
         .map_err(|e| match e.kind() {
             jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
                 AppError::authentication("Token has expired")
@@ -177,35 +505,197 @@ impl TokenPair {
             jsonwebtoken::errors::ErrorKind::InvalidToken => {
                 AppError::authentication("Invalid token")
             }
-            jsonwebtoken::errors::ErrorKind::InvalidSignature => {
+            jsonwebtoken::errors::ErrorKind::InvalidIssuer => {
+                AppError::authentication("Invalid token issuer")
+            }
+            jsonwebtoken::errors::ErrorKind::InvalidAudience => {
+                AppError::authentication("Invalid token audience")
+            }
+            jsonwebtoken::errors::ErrorKind::InvalidSignature
+            | jsonwebtoken::errors::ErrorKind::InvalidAlgorithm => {
+                // Counted separately so a secret change is attributable
+                metrics::JWT_SIGNATURE_FAILURES.increment();
                 AppError::authentication("Invalid token signature")
             }
             _ => AppError::authentication(format!("Token validation failed: {}", e)),
         })?;
 
-        Ok(token_data.claims)
+        Ok(token_data.claims.normalized())
     }
 
     /// Extract JTI from token without full validation
     ///
     /// Used for quick JTI lookup before full validation
     /// Still validates signature and basic structure
-    pub fn extract_jti(token: &str, jwt_secret: &str) -> AppResult<uuid::Uuid> {
-        let claims = Self::decode(token, jwt_secret)?;
+    pub fn extract_jti(token: &str, keys: &JwtKeys) -> AppResult<TokenId> {
+        let claims = Self::decode(token, keys)?;
 
-        uuid::Uuid::parse_str(&claims.jti)
+        claims
+            .jti
+            .parse()
             .map_err(|e| AppError::internal(format!("Invalid JTI in token: {}", e)))
     }
 
     /// Extract user ID from token
-    pub fn extract_user_id(token: &str, jwt_secret: &str) -> AppResult<UserId> {
-        let claims = Self::decode(token, jwt_secret)?;
+    pub fn extract_user_id(token: &str, keys: &JwtKeys) -> AppResult<UserId> {
+        let claims = Self::decode(token, keys)?;
 
-        uuid::Uuid::parse_str(&claims.sub)
+        claims
+            .sub
+            .parse()
             .map_err(|e| AppError::internal(format!("Invalid user ID in token: {}", e)))
     }
 }
 
+/// Sign one token's claims in the given format
+#[allow(clippy::too_many_arguments)]
+fn encode_claims(
+    format: ClaimsFormat,
+    user_id: UserId,
+    jti: TokenId,
+    exp: i64,
+    iat: i64,
+    auth_time: i64,
+    roles: Option<Vec<String>>,
+    token_type: TokenType,
+    tenant_id: Option<OrganizationId>,
+    session_id: Option<SessionId>,
+    actor_id: Option<UserId>,
+    keys: &JwtKeys,
+) -> jsonwebtoken::errors::Result<String> {
+    let key = keys.encoding_key();
+    let header = Header {
+        kid: keys.kid().map(str::to_string),
+        ..Header::new(keys.algorithm())
+    };
+
+    match format {
+        ClaimsFormat::Verbose => {
+            let claims = Claims {
+                sub: user_id.to_string(),
+                jti: jti.to_string(),
+                exp,
+                iat,
+                token_type: token_type.to_string(),
+                tid: tenant_id.map(|id| id.to_string()),
+                auth_time: Some(auth_time),
+                roles,
+                iss: keys.issuer().map(str::to_string),
+                aud: keys.audience().map(str::to_string),
+                sid: session_id.map(|id| id.to_string()),
+                act: actor_id.map(|id| Actor { sub: id.to_string() }),
+            };
+            encode(&header, &claims, &key)
+        }
+        ClaimsFormat::Minimal => {
+            let claims = MinimalClaims {
+                sub: user_id.into_inner().simple().to_string(),
+                jti: jti.into_inner().simple().to_string(),
+                exp,
+                iat,
+                t: match token_type {
+                    TokenType::Access => "a",
+                    TokenType::Refresh => "r",
+                },
+                tid: tenant_id.map(|id| id.into_inner().simple().to_string()),
+                auth_time: (auth_time != iat).then_some(auth_time),
+                roles,
+                iss: keys.issuer().map(str::to_string),
+                aud: keys.audience().map(str::to_string),
+                sid: session_id.map(|id| id.into_inner().simple().to_string()),
+                act: actor_id.map(|id| Actor {
+                    sub: id.into_inner().simple().to_string(),
+                }),
+            };
+            let header = Header { typ: None, ..header };
+            encode(&header, &claims, &key)
+        }
+    }
+}
+
+/// Rewrite a UUID claim in canonical hyphenated form (no-op if not a UUID)
+fn hyphenated(value: String) -> String {
+    Uuid::parse_str(&value).map_or(value, |id| id.to_string())
+}
+
+impl Claims {
+    /// Map minimal-format claim values to their verbose equivalents
+    fn normalized(self) -> Self {
+        let token_type = MINIMAL_TOKEN_TYPES
+            .iter()
+            .find(|(short, _)| *short == self.token_type)
+            .map_or(self.token_type, |(_, long)| long.to_string());
+
+        Self {
+            sub: hyphenated(self.sub),
+            jti: hyphenated(self.jti),
+            token_type,
+            tid: self.tid.map(hyphenated),
+            sid: self.sid.map(hyphenated),
+            act: self.act.map(|act| Actor {
+                sub: hyphenated(act.sub),
+            }),
+            ..self
+        }
+    }
+
+    /// Check if the token was issued before the given watermark
+    ///
+    /// `iat` has one-second resolution, so tokens issued within the same
+    /// second as the watermark are still accepted.
+    pub fn issued_before(&self, watermark: Timestamp) -> bool {
+        self.iat < watermark.timestamp()
+    }
+
+    /// When the user authenticated (`auth_time`, falling back to `iat`)
+    pub fn authenticated_at(&self) -> i64 {
+        self.auth_time.unwrap_or(self.iat)
+    }
+
+    /// Roles embedded in the token, or None if it carries no `roles` claim
+    pub fn roles(&self) -> AppResult<Option<Vec<Role>>> {
+        self.roles
+            .as_ref()
+            .map(|roles| {
+                roles
+                    .iter()
+                    .map(|r| Role::new(r).map_err(|_| AppError::authentication("Invalid role in token")))
+                    .collect()
+            })
+            .transpose()
+    }
+
+    /// Tenant the token was minted for, if any
+    pub fn tenant_id(&self) -> AppResult<Option<OrganizationId>> {
+        self.tid
+            .as_deref()
+            .map(|tid| {
+                tid.parse().map_err(|_| AppError::authentication("Invalid tenant ID in token"))
+            })
+            .transpose()
+    }
+
+    /// Admin impersonating the subject, if this is an impersonation token
+    pub fn impersonator(&self) -> AppResult<Option<UserId>> {
+        self.act
+            .as_ref()
+            .map(|act| {
+                act.sub.parse().map_err(|_| AppError::authentication("Invalid actor in token"))
+            })
+            .transpose()
+    }
+
+    /// Web session the token was minted under, if any
+    pub fn session_id(&self) -> AppResult<Option<SessionId>> {
+        self.sid
+            .as_deref()
+            .map(|sid| {
+                sid.parse().map_err(|_| AppError::authentication("Invalid session ID in token"))
+            })
+            .transpose()
+    }
+}
+
 impl JwtToken {
     /// Check if token is expired
     pub fn is_expired(&self) -> bool {
@@ -217,11 +707,22 @@ impl JwtToken {
         self.revoked
     }
 
+    /// Seconds since the token expired (zero or negative while still valid)
+    pub fn expired_for_seconds(&self) -> i64 {
+        (now() - self.expires_at).num_seconds()
+    }
+
     /// Check if token is valid (not expired and not revoked)
     pub fn is_valid(&self) -> bool {
         !self.is_expired() && !self.is_revoked()
     }
 
+    /// Move the token into an existing family (refresh rotation)
+    pub fn in_family(mut self, family_id: uuid::Uuid) -> Self {
+        self.family_id = family_id;
+        self
+    }
+
     /// Revoke token
     pub fn revoke(&mut self) {
         self.revoked = true;
@@ -233,15 +734,21 @@ impl JwtToken {
 mod tests {
     use super::*;
 
+    use crate::moduls::auth::domain::jwt_keys::tests::{PRIVATE_A, PRIVATE_B, PUBLIC_A, PUBLIC_B};
+
     const TEST_SECRET: &str = "test_secret_key_for_jwt_signing_minimum_32_chars";
 
+    fn keys() -> JwtKeys {
+        JwtKeys::hmac(TEST_SECRET)
+    }
+
     #[test]
     fn test_generate_token_pair() {
-        let user_id = new_id();
+        let user_id = UserId::new();
         let access_ttl = 900; // 15 min
         let refresh_ttl = 604800; // 7 days
 
-        let result = TokenPair::generate(user_id, TEST_SECRET, access_ttl, refresh_ttl);
+        let result = TokenPair::generate(user_id, &keys(), access_ttl, refresh_ttl);
         assert!(result.is_ok());
 
         let (token_pair, access_token, refresh_token) = result.unwrap();
@@ -254,12 +761,204 @@ mod tests {
         assert_eq!(refresh_token.user_id, user_id);
     }
 
+    #[test]
+    fn test_generate_with_fixed_clock_and_ids() {
+        // In the future, so the tokens still decode
+        let start = chrono::DateTime::from_timestamp(4_102_444_800, 0).unwrap();
+        let clock = FixedClock::new(start);
+        let ids = SequentialIdGenerator::new(start);
+        let user_id = UserId::new();
+
+        let (token_pair, access, refresh) =
+            TokenPair::generate_with_sources(user_id, &keys(), 900, 3600, &clock, &ids).unwrap();
+
+        assert_eq!(access.jti, TokenId::from_uuid(ids.nth(1)));
+        assert_eq!(refresh.jti, TokenId::from_uuid(ids.nth(2)));
+        assert_eq!(access.family_id, ids.nth(3));
+        assert_eq!(refresh.family_id, ids.nth(3));
+        assert_eq!(access.created_at, start);
+        assert_eq!(access.expires_at, start + chrono::Duration::seconds(900));
+        assert_eq!(refresh.expires_at, start + chrono::Duration::seconds(3600));
+
+        let claims = TokenPair::decode(&token_pair.access_token, &keys()).unwrap();
+        assert_eq!(claims.jti, ids.nth(1).to_string());
+        assert_eq!(claims.iat, 4_102_444_800);
+        assert_eq!(claims.exp, 4_102_444_800 + 900);
+        assert_eq!(claims.auth_time, Some(4_102_444_800));
+        assert_eq!(claims.sub, user_id.to_string());
+    }
+
+    #[test]
+    fn test_absolute_expiry_matches_claims() {
+        let (token_pair, _, _) = TokenPair::generate(UserId::new(), &keys(), 900, 604800).unwrap();
+
+        let access = TokenPair::decode(&token_pair.access_token, &keys()).unwrap();
+        let refresh = TokenPair::decode(&token_pair.refresh_token, &keys()).unwrap();
+
+        assert_eq!(token_pair.access_expires_at.timestamp(), access.iat + 900);
+        assert_eq!(token_pair.access_expires_at.timestamp(), access.exp);
+        assert_eq!(token_pair.refresh_expires_at.timestamp(), refresh.iat + 604800);
+    }
+
+    #[test]
+    fn test_claims_issued_before_watermark() {
+        let user_id = UserId::new();
+        let (token_pair, _, _) = TokenPair::generate(user_id, &keys(), 900, 604800).unwrap();
+        let claims = TokenPair::decode(&token_pair.access_token, &keys()).unwrap();
+
+        assert!(claims.issued_before(now() + chrono::Duration::seconds(2)));
+        assert!(!claims.issued_before(now() - chrono::Duration::seconds(2)));
+    }
+
+    #[test]
+    fn test_tenant_claim_round_trip() {
+        let user_id = UserId::new();
+        let tenant_id = OrganizationId::new();
+        let (token_pair, _, _) =
+            TokenPair::generate_for_tenant(user_id, Some(tenant_id), &keys(), 900, 604800).unwrap();
+
+        let access = TokenPair::decode(&token_pair.access_token, &keys()).unwrap();
+        let refresh = TokenPair::decode(&token_pair.refresh_token, &keys()).unwrap();
+
+        assert_eq!(access.tenant_id().unwrap(), Some(tenant_id));
+        assert_eq!(refresh.tenant_id().unwrap(), Some(tenant_id));
+
+        let (untenanted, _, _) = TokenPair::generate(user_id, &keys(), 900, 604800).unwrap();
+        let claims = TokenPair::decode(&untenanted.access_token, &keys()).unwrap();
+        assert_eq!(claims.tenant_id().unwrap(), None);
+    }
+
+    #[test]
+    fn test_minimal_claims_round_trip() {
+        let user_id = UserId::new();
+        let tenant_id = OrganizationId::new();
+
+        let (minimal, access, refresh) = TokenPair::generate_with_format(
+            user_id,
+            Some(tenant_id),
+            ClaimsFormat::Minimal,
+            &keys(),
+            900,
+            604800,
+        )
+        .unwrap();
+        let (verbose, _, _) =
+            TokenPair::generate_for_tenant(user_id, Some(tenant_id), &keys(), 900, 604800).unwrap();
+
+        let access_claims = TokenPair::decode(&minimal.access_token, &keys()).unwrap();
+        assert_eq!(access_claims.sub, user_id.to_string());
+        assert_eq!(access_claims.jti, access.jti.to_string());
+        assert_eq!(access_claims.token_type, "access");
+        assert_eq!(access_claims.tenant_id().unwrap(), Some(tenant_id));
+
+        let refresh_claims = TokenPair::decode(&minimal.refresh_token, &keys()).unwrap();
+        assert_eq!(refresh_claims.jti, refresh.jti.to_string());
+        assert_eq!(refresh_claims.token_type, "refresh");
+
+        assert!(minimal.access_token.len() < verbose.access_token.len());
+        assert!(minimal.refresh_token.len() < verbose.refresh_token.len());
+    }
+
+    #[test]
+    fn test_auth_time_defaults_to_iat() {
+        for format in [ClaimsFormat::Verbose, ClaimsFormat::Minimal] {
+            let (token_pair, _, _) =
+                TokenPair::generate_with_format(UserId::new(), None, format, &keys(), 900, 604800).unwrap();
+
+            let claims = TokenPair::decode(&token_pair.access_token, &keys()).unwrap();
+            assert_eq!(claims.authenticated_at(), claims.iat);
+        }
+    }
+
+    #[test]
+    fn test_auth_time_is_kept_when_given() {
+        let signed_in = now().timestamp() - 3600;
+
+        for format in [ClaimsFormat::Verbose, ClaimsFormat::Minimal] {
+            let (token_pair, _, _) = TokenPair::generate_with_auth_time(
+                UserId::new(),
+                None,
+                Some(signed_in),
+                None,
+                None,
+                format,
+                &keys(),
+                900,
+                604800,
+            )
+            .unwrap();
+
+            for token in [&token_pair.access_token, &token_pair.refresh_token] {
+                let claims = TokenPair::decode(token, &keys()).unwrap();
+                assert_eq!(claims.auth_time, Some(signed_in));
+                assert!(claims.iat > signed_in);
+            }
+        }
+    }
+
+    #[test]
+    fn test_session_claim_round_trip() {
+        let session_id = SessionId::new();
+
+        for format in [ClaimsFormat::Verbose, ClaimsFormat::Minimal] {
+            let (pair, access, refresh) = TokenPair::generate_with_auth_time(
+                UserId::new(),
+                None,
+                None,
+                None,
+                Some(session_id),
+                format,
+                &keys(),
+                900,
+                604800,
+            )
+            .unwrap();
+
+            let claims = TokenPair::decode(&pair.refresh_token, &keys()).unwrap();
+            assert_eq!(claims.session_id().unwrap(), Some(session_id));
+            assert_eq!(access.session_id, Some(session_id));
+            assert_eq!(refresh.session_id, Some(session_id));
+        }
+
+        let (standalone, access, _) = TokenPair::generate(UserId::new(), &keys(), 900, 604800).unwrap();
+        let claims = TokenPair::decode(&standalone.access_token, &keys()).unwrap();
+        assert_eq!(claims.session_id().unwrap(), None);
+        assert_eq!(access.session_id, None);
+    }
+
+    #[test]
+    fn test_roles_claim_round_trip() {
+        let roles = [Role::admin()];
+
+        for format in [ClaimsFormat::Verbose, ClaimsFormat::Minimal] {
+            let (with_roles, _, _) = TokenPair::generate_with_auth_time(
+                UserId::new(),
+                None,
+                None,
+                Some(&roles),
+                None,
+                format,
+                &keys(),
+                900,
+                604800,
+            )
+            .unwrap();
+            let claims = TokenPair::decode(&with_roles.access_token, &keys()).unwrap();
+            assert_eq!(claims.roles().unwrap(), Some(roles.to_vec()));
+
+            let (without, _, _) =
+                TokenPair::generate_with_format(UserId::new(), None, format, &keys(), 900, 604800).unwrap();
+            let claims = TokenPair::decode(&without.access_token, &keys()).unwrap();
+            assert_eq!(claims.roles().unwrap(), None, "No claim means roles weren't loaded");
+        }
+    }
+
     #[test]
     fn test_decode_valid_token() {
-        let user_id = new_id();
-        let (token_pair, _, _) = TokenPair::generate(user_id, TEST_SECRET, 900, 604800).unwrap();
+        let user_id = UserId::new();
+        let (token_pair, _, _) = TokenPair::generate(user_id, &keys(), 900, 604800).unwrap();
 
-        let claims = TokenPair::decode(&token_pair.access_token, TEST_SECRET);
+        let claims = TokenPair::decode(&token_pair.access_token, &keys());
         assert!(claims.is_ok());
 
         let claims = claims.unwrap();
@@ -269,35 +968,37 @@ mod tests {
 
     #[test]
     fn test_decode_invalid_signature() {
-        let user_id = new_id();
-        let (token_pair, _, _) = TokenPair::generate(user_id, TEST_SECRET, 900, 604800).unwrap();
+        let user_id = UserId::new();
+        let (token_pair, _, _) = TokenPair::generate(user_id, &keys(), 900, 604800).unwrap();
 
-        let result = TokenPair::decode(&token_pair.access_token, "wrong_secret");
+        let failures_before = metrics::JWT_SIGNATURE_FAILURES.get();
+        let result = TokenPair::decode(&token_pair.access_token, &JwtKeys::hmac("wrong_secret"));
         assert!(result.is_err());
+        assert!(metrics::JWT_SIGNATURE_FAILURES.get() > failures_before);
     }
 
     #[test]
     fn test_extract_jti() {
-        let user_id = new_id();
-        let (token_pair, access_token, _) = TokenPair::generate(user_id, TEST_SECRET, 900, 604800).unwrap();
+        let user_id = UserId::new();
+        let (token_pair, access_token, _) = TokenPair::generate(user_id, &keys(), 900, 604800).unwrap();
 
-        let jti = TokenPair::extract_jti(&token_pair.access_token, TEST_SECRET).unwrap();
+        let jti = TokenPair::extract_jti(&token_pair.access_token, &keys()).unwrap();
         assert_eq!(jti, access_token.jti);
     }
 
     #[test]
     fn test_extract_user_id() {
-        let user_id = new_id();
-        let (token_pair, _, _) = TokenPair::generate(user_id, TEST_SECRET, 900, 604800).unwrap();
+        let user_id = UserId::new();
+        let (token_pair, _, _) = TokenPair::generate(user_id, &keys(), 900, 604800).unwrap();
 
-        let extracted_user_id = TokenPair::extract_user_id(&token_pair.access_token, TEST_SECRET).unwrap();
+        let extracted_user_id = TokenPair::extract_user_id(&token_pair.access_token, &keys()).unwrap();
         assert_eq!(extracted_user_id, user_id);
     }
 
     #[test]
     fn test_jwt_token_expiration() {
-        let user_id = new_id();
-        let (_, access_token, _) = TokenPair::generate(user_id, TEST_SECRET, -1, 604800).unwrap();
+        let user_id = UserId::new();
+        let (_, access_token, _) = TokenPair::generate(user_id, &keys(), -1, 604800).unwrap();
 
         // Token should be expired (TTL = -1 second)
         assert!(access_token.is_expired());
@@ -306,8 +1007,8 @@ mod tests {
 
     #[test]
     fn test_jwt_token_revocation() {
-        let user_id = new_id();
-        let (_, mut access_token, _) = TokenPair::generate(user_id, TEST_SECRET, 900, 604800).unwrap();
+        let user_id = UserId::new();
+        let (_, mut access_token, _) = TokenPair::generate(user_id, &keys(), 900, 604800).unwrap();
 
         assert!(!access_token.is_revoked());
         assert!(access_token.is_valid());
@@ -321,11 +1022,154 @@ mod tests {
 
     #[test]
     fn test_token_types() {
-        let user_id = new_id();
-        let (_, access_token, refresh_token) = TokenPair::generate(user_id, TEST_SECRET, 900, 604800).unwrap();
+        let user_id = UserId::new();
+        let (_, access_token, refresh_token) = TokenPair::generate(user_id, &keys(), 900, 604800).unwrap();
 
         assert_eq!(access_token.token_type, TokenType::Access);
         assert_eq!(refresh_token.token_type, TokenType::Refresh);
         assert_ne!(access_token.jti, refresh_token.jti);
     }
+
+    #[test]
+    fn test_rsa_token_carries_kid() {
+        let keys = JwtKeys::rsa(PRIVATE_A, PUBLIC_A).unwrap();
+        let (token_pair, _, _) = TokenPair::generate(UserId::new(), &keys, 900, 604800).unwrap();
+
+        let header = decode_header(&token_pair.access_token).unwrap();
+        assert_eq!(header.alg, jsonwebtoken::Algorithm::RS256);
+        assert_eq!(header.kid.as_deref(), keys.kid());
+        assert!(TokenPair::decode(&token_pair.access_token, &keys).is_ok());
+    }
+
+    #[test]
+    fn test_token_signed_with_old_key_verifies_after_rotation() {
+        let user_id = UserId::new();
+        let key_a = JwtKeys::rsa(PRIVATE_A, PUBLIC_A).unwrap();
+        let (old, _, _) = TokenPair::generate(user_id, &key_a, 900, 604800).unwrap();
+
+        // Key B becomes the signing key, key A is kept for verification
+        let rotated = JwtKeys::rsa(PRIVATE_B, PUBLIC_B)
+            .unwrap()
+            .with_retired_key(PUBLIC_A)
+            .unwrap();
+        let (new, _, _) = TokenPair::generate(user_id, &rotated, 900, 604800).unwrap();
+
+        assert_eq!(TokenPair::decode(&old.access_token, &rotated).unwrap().sub, user_id.to_string());
+        assert!(TokenPair::decode(&new.access_token, &rotated).is_ok());
+
+        // Without key B, its tokens are rejected
+        assert!(TokenPair::decode(&new.access_token, &key_a).is_err());
+        // Once key A is dropped, so are its tokens
+        let b_only = JwtKeys::rsa(PRIVATE_B, PUBLIC_B).unwrap();
+        assert!(TokenPair::decode(&old.access_token, &b_only).is_err());
+    }
+
+    #[test]
+    fn test_algorithm_mismatch_rejected() {
+        let rsa = JwtKeys::rsa(PRIVATE_A, PUBLIC_A).unwrap();
+        let (hs256, _, _) = TokenPair::generate(UserId::new(), &keys(), 900, 604800).unwrap();
+        let (rs256, _, _) = TokenPair::generate(UserId::new(), &rsa, 900, 604800).unwrap();
+
+        assert!(TokenPair::decode(&hs256.access_token, &rsa).is_err());
+        assert!(TokenPair::decode(&rs256.access_token, &keys()).is_err());
+    }
+
+    fn scoped_keys(issuer: &str, audience: &str) -> JwtKeys {
+        keys()
+            .with_issuer(Some(issuer.to_string()))
+            .with_audience(Some(audience.to_string()))
+    }
+
+    #[test]
+    fn test_issuer_and_audience_round_trip() {
+        let keys = scoped_keys("https://auth.example.com", "api");
+        for format in [ClaimsFormat::Verbose, ClaimsFormat::Minimal] {
+            let (pair, _, _) =
+                TokenPair::generate_with_format(UserId::new(), None, format, &keys, 900, 604800).unwrap();
+
+            let claims = TokenPair::decode(&pair.access_token, &keys).unwrap();
+            assert_eq!(claims.iss.as_deref(), Some("https://auth.example.com"));
+            assert_eq!(claims.aud.as_deref(), Some("api"));
+        }
+    }
+
+    #[test]
+    fn test_audience_mismatch_rejected() {
+        let (pair, _, _) = TokenPair::generate(UserId::new(), &scoped_keys("issuer", "other-api"), 900, 604800).unwrap();
+
+        let result = TokenPair::decode(&pair.access_token, &scoped_keys("issuer", "api"));
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[test]
+    fn test_issuer_mismatch_rejected() {
+        let (pair, _, _) = TokenPair::generate(UserId::new(), &scoped_keys("other", "api"), 900, 604800).unwrap();
+
+        let result = TokenPair::decode(&pair.access_token, &scoped_keys("issuer", "api"));
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[test]
+    fn test_configured_audience_requires_the_claim() {
+        let (pair, _, _) = TokenPair::generate(UserId::new(), &keys(), 900, 604800).unwrap();
+
+        let result = TokenPair::decode(&pair.access_token, &scoped_keys("issuer", "api"));
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[test]
+    fn test_unconfigured_audience_is_lenient() {
+        let (pair, _, _) = TokenPair::generate(UserId::new(), &scoped_keys("issuer", "api"), 900, 604800).unwrap();
+
+        assert!(TokenPair::decode(&pair.access_token, &keys()).is_ok());
+    }
+
+    #[test]
+    fn test_impersonation_token_names_the_admin() {
+        let (user_id, admin_id) = (UserId::new(), UserId::new());
+
+        for format in [ClaimsFormat::Verbose, ClaimsFormat::Minimal] {
+            let (token, stored) =
+                TokenPair::generate_impersonation(user_id, None, admin_id, &[], format, &keys(), 600, 1800)
+                    .unwrap();
+
+            let claims = TokenPair::decode(&token.access_token, &keys()).unwrap();
+            assert_eq!(claims.sub, user_id.to_string());
+            assert_eq!(claims.token_type, "access");
+            assert_eq!(claims.impersonator().unwrap(), Some(admin_id));
+            // Never a fresh login, and no roles to fall back on
+            assert_eq!(claims.authenticated_at(), 0);
+            assert_eq!(claims.roles().unwrap(), Some(vec![]));
+            assert_eq!(stored.token_type, TokenType::Access);
+            assert_eq!(token.expires_in, 600);
+        }
+    }
+
+    #[test]
+    fn test_impersonation_ttl_is_capped() {
+        let (token, stored) = TokenPair::generate_impersonation(
+            UserId::new(),
+            None,
+            UserId::new(),
+            &[],
+            ClaimsFormat::Verbose,
+            &keys(),
+            86400,
+            1800,
+        )
+        .unwrap();
+
+        let claims = TokenPair::decode(&token.access_token, &keys()).unwrap();
+        assert_eq!(token.expires_in, 1800);
+        assert_eq!(claims.exp - claims.iat, 1800);
+        assert!(stored.expires_at <= now() + chrono::Duration::seconds(1800));
+    }
+
+    #[test]
+    fn test_regular_tokens_have_no_impersonator() {
+        let (pair, _, _) = TokenPair::generate(UserId::new(), &keys(), 900, 604800).unwrap();
+
+        let claims = TokenPair::decode(&pair.access_token, &keys()).unwrap();
+        assert_eq!(claims.impersonator().unwrap(), None);
+    }
 }
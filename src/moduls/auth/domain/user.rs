@@ -1,18 +1,92 @@
 use crate::shared::{types::*, AppError, AppResult};
+use super::client_hash::ClientHashParams;
+use super::notification::NotificationPreferences;
+use super::password_hasher::PasswordHasher;
 use super::value_objects::{Email, PasswordHash};
 use serde::{Deserialize, Serialize};
 
+/// Effective account status, derived from the user's fields
+///
+/// When several apply, the most restrictive wins, in declaration order
+/// after `Active`: `Deleted`, `Expired`, `Inactive`, `Locked`,
+/// `PendingDeletion`, then `Unverified`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountStatus {
+    Active,
+    /// Deleted at the user's request (`deleted_at` is set); final
+    Deleted,
+    /// Past `expires_at` (e.g. a guest account); cannot log in
+    Expired,
+    /// Deactivated; cannot log in
+    Inactive,
+    /// Locked until `locked_until`; cannot log in meanwhile
+    Locked,
+    /// Deletion scheduled at `deletion_scheduled_at`; logging in is still
+    /// allowed so the user can cancel it
+    PendingDeletion,
+    /// Email not verified; blocks login only when verification is required
+    Unverified,
+}
+
+impl AccountStatus {
+    /// Error returned to a login blocked by this status
+    pub fn login_error(self) -> AppError {
+        match self {
+            AccountStatus::Unverified => AppError::email_not_verified(
+                "Please verify your email address before logging in",
+            ),
+            AccountStatus::Locked => {
+                AppError::authentication("Account is temporarily locked")
+            }
+            AccountStatus::Expired => AppError::authentication("Account has expired"),
+            _ => AppError::authentication("Account is not active"),
+        }
+    }
+}
+
 /// User aggregate root for authentication context
 /// Represents a user in the system with authentication capabilities
 #[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct User {
     pub id: UserId,
+    /// Organization the user is scoped to (`None` for global users)
+    pub tenant_id: Option<OrganizationId>,
     pub email: Email,
     #[serde(skip_serializing)]
     pub password_hash: PasswordHash,
     pub name: String,
     pub email_verified: bool,
     pub is_active: bool,
+    /// Whether the user completed two-factor setup
+    pub two_factor_enabled: bool,
+    /// Tokens issued before this time are rejected (per-user watermark)
+    #[serde(skip_serializing)]
+    pub tokens_valid_after: Option<Timestamp>,
+    /// Client-side hashing parameters, when `password_hash` is a hash of
+    /// the client hash rather than of the password (see `client_hash_params`)
+    #[serde(skip_serializing)]
+    pub client_hash_salt: Option<String>,
+    #[serde(skip_serializing)]
+    pub client_hash_iterations: Option<i32>,
+    /// Secondary address password resets can be sent to, once verified
+    pub recovery_email: Option<Email>,
+    pub recovery_email_verified: bool,
+    /// Optional security emails the user receives
+    #[sqlx(json)]
+    pub notification_preferences: NotificationPreferences,
+    /// Set when the password hash uses outdated parameters; login is
+    /// refused until the password is changed
+    #[serde(skip_serializing)]
+    pub must_change_password: bool,
+    /// When the user deleted their account (soft deletion)
+    pub deleted_at: Option<Timestamp>,
+    /// Logins are refused until this time
+    pub locked_until: Option<Timestamp>,
+    /// When the account is due to be deleted
+    pub deletion_scheduled_at: Option<Timestamp>,
+    /// When a time-limited (e.g. guest) account stops working
+    pub expires_at: Option<Timestamp>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
 }
@@ -24,9 +98,94 @@ impl User {
     /// - Email must be unique (enforced by repository)
     /// - Password must be min 8 chars (enforced by PasswordHash)
     /// - New users: email_verified=false, is_active=true
-    /// - Password is hashed with bcrypt
+    /// - Password is hashed with the default hasher (bcrypt)
     pub fn new(email: Email, password: &str, name: String) -> AppResult<Self> {
+        Self::with_hasher(email, password, name, &PasswordHasher::default())
+    }
+
+    /// Create new User entity, hashing the password with `hasher`
+    pub fn with_hasher(
+        email: Email,
+        password: &str,
+        name: String,
+        hasher: &PasswordHasher,
+    ) -> AppResult<Self> {
+        Self::with_sources(email, password, name, hasher, &SystemClock, &SystemIdGenerator)
+    }
+
+    /// Create new User entity, taking the time and ID from `clock` and `ids`
+    pub fn with_sources(
+        email: Email,
+        password: &str,
+        name: String,
+        hasher: &PasswordHasher,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+    ) -> AppResult<Self> {
         // Validate name
+        let name = Self::normalize_name(&name)?;
+
+        // Hash password (validation happens in PasswordHash::from_plain)
+        let password_hash = PasswordHash::from_plain(password, hasher)?;
+
+        Ok(Self::with_password_hash(email, password_hash, name, clock, ids))
+    }
+
+    /// Create new User from a password hashed client-side with `params`
+    ///
+    /// The password itself is never seen, so its strength can't be checked.
+    pub fn with_client_hash(
+        email: Email,
+        client_hash: &str,
+        params: ClientHashParams,
+        name: String,
+        hasher: &PasswordHasher,
+    ) -> AppResult<Self> {
+        let name = Self::normalize_name(&name)?;
+        let password_hash = PasswordHash::from_client_hash(client_hash, hasher)?;
+
+        let mut user =
+            Self::with_password_hash(email, password_hash, name, &SystemClock, &SystemIdGenerator);
+        user.set_client_hash_params(params);
+        Ok(user)
+    }
+
+    fn with_password_hash(
+        email: Email,
+        password_hash: PasswordHash,
+        name: String,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+    ) -> Self {
+        let now = clock.now();
+
+        Self {
+            id: UserId::from_uuid(ids.new_id()),
+            tenant_id: None,
+            email,
+            password_hash,
+            name,
+            email_verified: false,
+            is_active: true,
+            two_factor_enabled: false,
+            tokens_valid_after: None,
+            client_hash_salt: None,
+            client_hash_iterations: None,
+            recovery_email: None,
+            recovery_email_verified: false,
+            notification_preferences: NotificationPreferences::default(),
+            must_change_password: false,
+            deleted_at: None,
+            locked_until: None,
+            deletion_scheduled_at: None,
+            expires_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Trimmed name, rejecting empty and overlong ones
+    fn normalize_name(name: &str) -> AppResult<String> {
         let name = name.trim();
         if name.is_empty() {
             return Err(AppError::validation("Name cannot be empty"));
@@ -36,43 +195,144 @@ impl User {
             return Err(AppError::validation("Name must be 255 characters or less"));
         }
 
-        // Hash password (validation happens in PasswordHash::from_plain)
-        let password_hash = PasswordHash::from_plain(password)?;
+        Ok(name.to_string())
+    }
 
-        let now = now();
+    /// Client-side hashing parameters of the password, if it was migrated
+    /// to (or created with) client-side hashing
+    pub fn client_hash_params(&self) -> Option<ClientHashParams> {
+        match (&self.client_hash_salt, self.client_hash_iterations) {
+            (Some(salt), Some(iterations)) => {
+                Some(ClientHashParams::new(salt.clone(), iterations as u32))
+            }
+            _ => None,
+        }
+    }
 
-        Ok(Self {
-            id: new_id(),
-            email,
-            password_hash,
-            name: name.to_string(),
-            email_verified: false,
-            is_active: true,
-            created_at: now,
-            updated_at: now,
-        })
+    fn set_client_hash_params(&mut self, params: ClientHashParams) {
+        self.client_hash_salt = Some(params.salt);
+        self.client_hash_iterations = Some(params.iterations as i32);
     }
 
     /// Verify provided password against user's password hash
     ///
+    /// For client-hashed accounts the client hash is derived here, so
+    /// callers sending the plain password keep working.
+    ///
     /// Returns true if password matches, false otherwise
     pub fn verify_password(&self, password: &str) -> AppResult<bool> {
-        self.password_hash.verify(password)
+        match self.client_hash_params() {
+            Some(params) => self.password_hash.verify(&params.derive(password)),
+            None => self.password_hash.verify(password),
+        }
+    }
+
+    /// Whether `password` is the current password or matches one of the
+    /// `previous` password hashes
+    ///
+    /// Previous hashes may predate the switch to client-side hashing, so
+    /// both forms of the password are tried against them.
+    pub fn is_recent_password(&self, password: &str, previous: &[PasswordHash]) -> AppResult<bool> {
+        if self.verify_password(password)? {
+            return Ok(true);
+        }
+
+        let client_hash = self.client_hash_params().map(|params| params.derive(password));
+        for hash in previous {
+            if hash.verify(password)? {
+                return Ok(true);
+            }
+            if let Some(client_hash) = &client_hash {
+                if hash.verify(client_hash)? {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Verify a client hash made with `params`
+    ///
+    /// # Errors
+    /// - ClientHashMismatch if `params` aren't the account's (including
+    ///   plaintext-based accounts, which have none)
+    pub fn verify_client_hash(&self, client_hash: &str, params: &ClientHashParams) -> AppResult<bool> {
+        if self.client_hash_params().as_ref() != Some(params) {
+            return Err(AppError::client_hash_mismatch(
+                "Fetch the password parameters again and re-hash",
+            ));
+        }
+
+        self.password_hash.verify(client_hash)
+    }
+
+    /// Switch a plaintext-based account to client-side hashing
+    ///
+    /// Needs the plain password, so it happens at a successful login.
+    /// Tokens stay valid: the password itself is unchanged.
+    pub fn migrate_to_client_hash(
+        &mut self,
+        password: &str,
+        params: ClientHashParams,
+        hasher: &PasswordHasher,
+    ) -> AppResult<()> {
+        self.password_hash = PasswordHash::from_client_hash(&params.derive(password), hasher)?;
+        self.set_client_hash_params(params);
+        self.updated_at = now();
+
+        Ok(())
+    }
+
+    /// Re-hash the password with `hasher` if the stored hash uses another
+    /// algorithm or cost
+    ///
+    /// `secret` is what the hash is of: the password, or the client hash for
+    /// accounts using client-side hashing. Returns whether it was re-hashed.
+    /// Tokens stay valid: the password itself is unchanged.
+    pub fn upgrade_password_hash(&mut self, secret: &str, hasher: &PasswordHasher) -> AppResult<bool> {
+        if !hasher.needs_rehash(&self.password_hash) {
+            return Ok(false);
+        }
+
+        self.password_hash = hasher.hash(secret)?;
+        self.updated_at = now();
+
+        Ok(true)
     }
 
     /// Change user's password
     ///
-    /// Validates new password and updates password_hash
-    pub fn change_password(&mut self, new_password: &str) -> AppResult<()> {
+    /// Validates new password and updates password_hash (keeping the
+    /// account's client-side hashing parameters, if any), which clears
+    /// `must_change_password`. Tokens issued before the change are
+    /// invalidated.
+    pub fn change_password(&mut self, new_password: &str, hasher: &PasswordHasher) -> AppResult<()> {
         // Validate and hash new password
-        let new_hash = PasswordHash::from_plain(new_password)?;
+        let new_hash = match self.client_hash_params() {
+            Some(params) => {
+                PasswordHash::ensure_min_length(new_password)?;
+                PasswordHash::from_client_hash(&params.derive(new_password), hasher)?
+            }
+            None => PasswordHash::from_plain(new_password, hasher)?,
+        };
 
         self.password_hash = new_hash;
-        self.updated_at = now();
+        self.must_change_password = false;
+        self.invalidate_tokens();
 
         Ok(())
     }
 
+    /// Invalidate all tokens issued before now
+    ///
+    /// Used for password changes and "log out all devices"
+    pub fn invalidate_tokens(&mut self) {
+        let now = now();
+        self.tokens_valid_after = Some(now);
+        self.updated_at = now;
+    }
+
     /// Mark email as verified
     ///
     /// Called after user confirms email verification link
@@ -81,6 +341,71 @@ impl User {
         self.updated_at = now();
     }
 
+    /// Replace the login email with a confirmed new address
+    ///
+    /// The new address starts unverified. If it was the recovery email,
+    /// the recovery email is removed.
+    ///
+    /// # Errors
+    /// - Validation if it is the current login email
+    pub fn change_email(&mut self, email: Email) -> AppResult<()> {
+        if email == self.email {
+            return Err(AppError::validation("New email must differ from the current email"));
+        }
+
+        if self.recovery_email.as_ref() == Some(&email) {
+            self.recovery_email = None;
+            self.recovery_email_verified = false;
+        }
+        self.email = email;
+        self.email_verified = false;
+        self.updated_at = now();
+        Ok(())
+    }
+
+    /// Set (or with `None`, remove) the recovery email
+    ///
+    /// A new address starts unverified; setting the current one again
+    /// keeps its state.
+    ///
+    /// # Errors
+    /// - Validation if it is the login email
+    pub fn set_recovery_email(&mut self, email: Option<Email>) -> AppResult<()> {
+        if email.as_ref() == Some(&self.email) {
+            return Err(AppError::validation(
+                "Recovery email must differ from the login email",
+            ));
+        }
+
+        if email != self.recovery_email {
+            self.recovery_email = email;
+            self.recovery_email_verified = false;
+            self.updated_at = now();
+        }
+        Ok(())
+    }
+
+    /// Mark the recovery email as verified
+    ///
+    /// Called after the user confirms the link sent to it
+    pub fn verify_recovery_email(&mut self) {
+        self.recovery_email_verified = true;
+        self.updated_at = now();
+    }
+
+    /// The recovery email, if it is usable for recovery (verified)
+    pub fn verified_recovery_email(&self) -> Option<&Email> {
+        self.recovery_email.as_ref().filter(|_| self.recovery_email_verified)
+    }
+
+    /// Mark two-factor authentication as enabled
+    ///
+    /// Called once the user confirmed their second factor
+    pub fn enable_two_factor(&mut self) {
+        self.two_factor_enabled = true;
+        self.updated_at = now();
+    }
+
     /// Deactivate user account
     ///
     /// Deactivated users cannot login
@@ -89,36 +414,141 @@ impl User {
         self.updated_at = now();
     }
 
-    /// Reactivate user account
+    /// Soft-delete the account at the user's request
     ///
-    /// Allows deactivated user to login again
-    pub fn reactivate(&mut self) {
-        self.is_active = true;
+    /// The user is deactivated and their tokens invalidated. With
+    /// `anonymize`, the email becomes `deleted+{id}@deleted.invalid` (so the
+    /// address can register again) and the name and recovery email are
+    /// dropped.
+    pub fn delete(&mut self, anonymize: bool) -> AppResult<()> {
+        if anonymize {
+            self.email = Email::new(&format!("deleted+{}@deleted.invalid", self.id))?;
+            self.name = "Deleted User".to_string();
+            self.recovery_email = None;
+            self.recovery_email_verified = false;
+        }
+
+        self.is_active = false;
+        self.invalidate_tokens();
+        self.deleted_at = Some(self.updated_at);
+        self.deletion_scheduled_at = None;
+
+        Ok(())
+    }
+
+    /// Refuse logins until `until`
+    pub fn lock(&mut self, until: Timestamp) -> AppResult<()> {
+        self.ensure_not_deleted("locked")?;
+        self.locked_until = Some(until);
         self.updated_at = now();
+        Ok(())
     }
 
-    /// Update user's name
-    pub fn update_name(&mut self, name: String) -> AppResult<()> {
-        let name = name.trim();
-        if name.is_empty() {
-            return Err(AppError::validation("Name cannot be empty"));
+    /// Lift a lock before it runs out
+    ///
+    /// The lock is ended rather than cleared: failures before its end
+    /// don't count toward the next lockout.
+    pub fn unlock(&mut self) {
+        let now = now();
+        if self.locked_until.is_some_and(|until| until > now) {
+            self.locked_until = Some(now);
         }
+        self.updated_at = now;
+    }
 
-        if name.len() > 255 {
-            return Err(AppError::validation("Name must be 255 characters or less"));
+    /// Schedule the account's deletion at `at`
+    ///
+    /// The user can still log in, and cancel it, until then.
+    pub fn schedule_deletion(&mut self, at: Timestamp) -> AppResult<()> {
+        self.ensure_not_deleted("scheduled for deletion")?;
+        self.deletion_scheduled_at = Some(at);
+        self.updated_at = now();
+        Ok(())
+    }
+
+    /// Cancel a scheduled deletion
+    pub fn cancel_deletion(&mut self) -> AppResult<()> {
+        self.ensure_not_deleted("restored")?;
+        self.deletion_scheduled_at = None;
+        self.updated_at = now();
+        Ok(())
+    }
+
+    /// Make the account stop working at `at`, or never with `None`
+    pub fn set_expiry(&mut self, at: Option<Timestamp>) -> AppResult<()> {
+        self.ensure_not_deleted("extended")?;
+        self.expires_at = at;
+        self.updated_at = now();
+        Ok(())
+    }
+
+    fn ensure_not_deleted(&self, action: &str) -> AppResult<()> {
+        if self.deleted_at.is_some() {
+            return Err(AppError::conflict(format!("Deleted accounts cannot be {}", action)));
         }
+        Ok(())
+    }
+
+    /// Reactivate user account
+    ///
+    /// Allows deactivated user to login again. Deleted accounts stay
+    /// deleted: their owner asked for it, and their data may already be
+    /// anonymized.
+    pub fn reactivate(&mut self) -> AppResult<()> {
+        self.ensure_not_deleted("reactivated")?;
+
+        self.is_active = true;
+        self.updated_at = now();
+        Ok(())
+    }
 
-        self.name = name.to_string();
+    /// Update user's name
+    pub fn update_name(&mut self, name: String) -> AppResult<()> {
+        self.name = Self::normalize_name(&name)?;
         self.updated_at = now();
 
         Ok(())
     }
 
-    /// Check if user can login
+    /// Effective account status
+    pub fn status(&self) -> AccountStatus {
+        let now = now();
+        if self.deleted_at.is_some() {
+            AccountStatus::Deleted
+        } else if self.expires_at.is_some_and(|at| at <= now) {
+            AccountStatus::Expired
+        } else if !self.is_active {
+            AccountStatus::Inactive
+        } else if self.locked_until.is_some_and(|until| until > now) {
+            AccountStatus::Locked
+        } else if self.deletion_scheduled_at.is_some() {
+            AccountStatus::PendingDeletion
+        } else if !self.email_verified {
+            AccountStatus::Unverified
+        } else {
+            AccountStatus::Active
+        }
+    }
+
+    /// Status that blocks this user from logging in, if any
     ///
-    /// User must be active to login
+    /// This is the single login-eligibility decision. `Unverified` only
+    /// blocks when `require_verified_email` is set; `PendingDeletion` never
+    /// does, though the unverified email underneath it still may.
+    pub fn login_blocker(&self, require_verified_email: bool) -> Option<AccountStatus> {
+        let unverified_blocks = require_verified_email && !self.email_verified;
+        match self.status() {
+            AccountStatus::Active => None,
+            AccountStatus::PendingDeletion | AccountStatus::Unverified => {
+                unverified_blocks.then_some(AccountStatus::Unverified)
+            }
+            status => Some(status),
+        }
+    }
+
+    /// Check if user can login (without email verification enforcement)
     pub fn can_login(&self) -> bool {
-        self.is_active
+        self.login_blocker(false).is_none()
     }
 }
 
@@ -138,17 +568,25 @@ pub struct UserDto {
     pub name: String,
     pub email_verified: bool,
     pub is_active: bool,
+    pub two_factor_enabled: bool,
+    pub recovery_email: Option<String>,
+    pub recovery_email_verified: bool,
+    pub status: AccountStatus,
     pub created_at: Timestamp,
 }
 
 impl From<User> for UserDto {
     fn from(user: User) -> Self {
         Self {
+            status: user.status(),
             id: user.id,
             email: user.email.into_inner(),
             name: user.name,
             email_verified: user.email_verified,
             is_active: user.is_active,
+            two_factor_enabled: user.two_factor_enabled,
+            recovery_email: user.recovery_email.map(Email::into_inner),
+            recovery_email_verified: user.recovery_email_verified,
             created_at: user.created_at,
         }
     }
@@ -169,6 +607,28 @@ mod tests {
         assert!(user.is_active);
     }
 
+    #[test]
+    fn test_create_user_with_fixed_clock_and_ids() {
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let clock = FixedClock::new(start);
+        let ids = SequentialIdGenerator::new(start);
+        let email = Email::new("test@example.com").unwrap();
+
+        let user = User::with_sources(
+            email,
+            "password123",
+            "Test User".to_string(),
+            &PasswordHasher::default(),
+            &clock,
+            &ids,
+        )
+        .unwrap();
+
+        assert_eq!(user.id, UserId::from_uuid(ids.nth(1)));
+        assert_eq!(user.created_at, start);
+        assert_eq!(user.updated_at, start);
+    }
+
     #[test]
     fn test_create_user_empty_name() {
         let email = Email::new("test@example.com").unwrap();
@@ -191,8 +651,13 @@ mod tests {
         let email = Email::new("test@example.com").unwrap();
         let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
 
+        assert!(user.tokens_valid_after.is_none());
+
         // Change password
-        user.change_password("newpassword456").unwrap();
+        user.change_password("newpassword456", &PasswordHasher::default()).unwrap();
+
+        // Existing tokens are invalidated
+        assert!(user.tokens_valid_after.is_some());
 
         // Old password should not work
         assert!(!user.verify_password("password123").unwrap());
@@ -226,12 +691,229 @@ mod tests {
         assert!(!user.is_active);
         assert!(!user.can_login());
 
-        user.reactivate();
+        user.reactivate().unwrap();
 
         assert!(user.is_active);
         assert!(user.can_login());
     }
 
+    #[test]
+    fn test_account_status_from_flags() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+
+        assert_eq!(user.status(), AccountStatus::Unverified);
+
+        user.verify_email();
+        assert_eq!(user.status(), AccountStatus::Active);
+
+        user.deactivate();
+        assert_eq!(user.status(), AccountStatus::Inactive);
+
+        // Inactive wins over unverified
+        user.email_verified = false;
+        assert_eq!(user.status(), AccountStatus::Inactive);
+
+        // Deleted wins over everything
+        user.delete(false).unwrap();
+        assert_eq!(user.status(), AccountStatus::Deleted);
+        assert_eq!(user.login_blocker(false), Some(AccountStatus::Deleted));
+    }
+
+    #[test]
+    fn test_account_status_precedence() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        let past = now() - chrono::Duration::hours(1);
+        let future = now() + chrono::Duration::hours(1);
+
+        user.schedule_deletion(future).unwrap();
+        assert_eq!(user.status(), AccountStatus::PendingDeletion);
+
+        user.lock(future).unwrap();
+        assert_eq!(user.status(), AccountStatus::Locked);
+
+        user.deactivate();
+        assert_eq!(user.status(), AccountStatus::Inactive);
+
+        user.set_expiry(Some(past)).unwrap();
+        assert_eq!(user.status(), AccountStatus::Expired);
+
+        user.delete(false).unwrap();
+        assert_eq!(user.status(), AccountStatus::Deleted);
+    }
+
+    #[test]
+    fn test_account_status_transitions() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        user.verify_email();
+
+        // Locks and expiry take effect with time
+        user.lock(now() - chrono::Duration::seconds(1)).unwrap();
+        assert_eq!(user.status(), AccountStatus::Active);
+        user.lock(now() + chrono::Duration::hours(1)).unwrap();
+        assert_eq!(user.status(), AccountStatus::Locked);
+        user.unlock();
+        assert_eq!(user.status(), AccountStatus::Active);
+
+        user.set_expiry(Some(now() + chrono::Duration::hours(1))).unwrap();
+        assert_eq!(user.status(), AccountStatus::Active);
+        user.set_expiry(Some(now() - chrono::Duration::seconds(1))).unwrap();
+        assert_eq!(user.status(), AccountStatus::Expired);
+        user.set_expiry(None).unwrap();
+        assert_eq!(user.status(), AccountStatus::Active);
+
+        user.schedule_deletion(now() + chrono::Duration::days(30)).unwrap();
+        assert_eq!(user.status(), AccountStatus::PendingDeletion);
+        user.cancel_deletion().unwrap();
+        assert_eq!(user.status(), AccountStatus::Active);
+
+        // Deleting ends a pending deletion, and nothing revives the account
+        user.schedule_deletion(now()).unwrap();
+        user.delete(false).unwrap();
+        assert_eq!(user.deletion_scheduled_at, None);
+        assert!(matches!(user.lock(now()), Err(AppError::Conflict(_))));
+        assert!(matches!(user.cancel_deletion(), Err(AppError::Conflict(_))));
+        assert!(matches!(user.set_expiry(None), Err(AppError::Conflict(_))));
+    }
+
+    #[test]
+    fn test_login_blocker_for_lifecycle_states() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+
+        // A pending deletion doesn't block, an unverified email under it may
+        user.schedule_deletion(now() + chrono::Duration::days(30)).unwrap();
+        assert_eq!(user.login_blocker(false), None);
+        assert_eq!(user.login_blocker(true), Some(AccountStatus::Unverified));
+        user.verify_email();
+        assert_eq!(user.login_blocker(true), None);
+
+        user.lock(now() + chrono::Duration::hours(1)).unwrap();
+        assert_eq!(user.login_blocker(false), Some(AccountStatus::Locked));
+        assert!(matches!(
+            AccountStatus::Locked.login_error(),
+            AppError::Authentication(_)
+        ));
+
+        user.set_expiry(Some(now())).unwrap();
+        assert_eq!(user.login_blocker(false), Some(AccountStatus::Expired));
+        assert!(!user.can_login());
+    }
+
+    #[test]
+    fn test_deleted_account_cannot_be_reactivated() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        user.delete(true).unwrap();
+
+        assert!(matches!(user.reactivate(), Err(AppError::Conflict(_))));
+        assert!(!user.is_active);
+        assert_eq!(user.status(), AccountStatus::Deleted);
+    }
+
+    #[test]
+    fn test_login_blocker() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+
+        assert_eq!(user.login_blocker(false), None);
+        assert_eq!(user.login_blocker(true), Some(AccountStatus::Unverified));
+        assert!(matches!(
+            AccountStatus::Unverified.login_error(),
+            AppError::EmailNotVerified(_)
+        ));
+
+        user.deactivate();
+        assert_eq!(user.login_blocker(false), Some(AccountStatus::Inactive));
+        assert!(matches!(
+            AccountStatus::Inactive.login_error(),
+            AppError::Authentication(_)
+        ));
+    }
+
+    #[test]
+    fn test_user_dto_includes_status() {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, "password123", "Test User".to_string()).unwrap();
+
+        let json = serde_json::to_value(UserDto::from(user)).unwrap();
+
+        assert_eq!(json["status"], "unverified");
+    }
+
+    #[test]
+    fn test_recovery_email_needs_verification() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+
+        user.set_recovery_email(Some(Email::new("backup@example.com").unwrap())).unwrap();
+        assert_eq!(user.verified_recovery_email(), None);
+
+        user.verify_recovery_email();
+        assert_eq!(user.verified_recovery_email().unwrap().as_str(), "backup@example.com");
+
+        // A different address has to be verified again
+        user.set_recovery_email(Some(Email::new("other@example.com").unwrap())).unwrap();
+        assert_eq!(user.verified_recovery_email(), None);
+    }
+
+    #[test]
+    fn test_recovery_email_must_differ_from_login_email() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+
+        let result = user.set_recovery_email(Some(Email::new("Test@Example.com").unwrap()));
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        assert!(user.recovery_email.is_none());
+    }
+
+    #[test]
+    fn test_change_email_resets_verification() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        user.verify_email();
+        user.set_recovery_email(Some(Email::new("backup@example.com").unwrap())).unwrap();
+
+        let result = user.change_email(Email::new("Test@Example.com").unwrap());
+        assert!(matches!(result, Err(AppError::Validation(_))));
+
+        user.change_email(Email::new("backup@example.com").unwrap()).unwrap();
+        assert_eq!(user.email.as_str(), "backup@example.com");
+        assert!(!user.email_verified);
+        assert!(user.recovery_email.is_none(), "The recovery email became the login email");
+    }
+
+    #[test]
+    fn test_delete_anonymizes_and_deactivates() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        user.set_recovery_email(Some(Email::new("backup@example.com").unwrap())).unwrap();
+
+        user.delete(true).unwrap();
+
+        assert_eq!(user.email.as_str(), format!("deleted+{}@deleted.invalid", user.id));
+        assert_eq!(user.name, "Deleted User");
+        assert!(user.recovery_email.is_none());
+        assert!(!user.is_active);
+        assert!(user.deleted_at.is_some());
+        assert!(user.tokens_valid_after.is_some());
+    }
+
+    #[test]
+    fn test_delete_without_anonymizing_keeps_email() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+
+        user.delete(false).unwrap();
+
+        assert_eq!(user.email.as_str(), "test@example.com");
+        assert!(!user.is_active);
+        assert!(user.deleted_at.is_some());
+    }
+
     #[test]
     fn test_update_name() {
         let email = Email::new("test@example.com").unwrap();
@@ -241,4 +923,57 @@ mod tests {
 
         assert_eq!(user.name, "New Name");
     }
+
+    #[test]
+    fn test_migrated_account_verifies_both_forms() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        let params = ClientHashParams::new("c2FsdA".to_string(), 1000);
+
+        user.migrate_to_client_hash("password123", params.clone(), &PasswordHasher::default())
+            .unwrap();
+
+        assert_eq!(user.client_hash_params(), Some(params.clone()));
+        assert!(user.verify_password("password123").unwrap());
+        assert!(user.verify_client_hash(&params.derive("password123"), &params).unwrap());
+        assert!(!user.verify_client_hash(&params.derive("wrong-password"), &params).unwrap());
+    }
+
+    #[test]
+    fn test_recent_password_covers_current_and_previous_hashes() {
+        let hasher = PasswordHasher::Bcrypt { cost: 4 };
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::with_hasher(email, "password123", "Test User".to_string(), &hasher).unwrap();
+        let params = ClientHashParams::new("c2FsdA".to_string(), 1000);
+        // One hash from before the switch to client-side hashing, one after
+        let previous = vec![
+            PasswordHash::from_plain("oldpassword1", &hasher).unwrap(),
+            PasswordHash::from_client_hash(&params.derive("olderpassword2"), &hasher).unwrap(),
+        ];
+        user.migrate_to_client_hash("password123", params, &hasher).unwrap();
+
+        for password in ["password123", "oldpassword1", "olderpassword2"] {
+            assert!(user.is_recent_password(password, &previous).unwrap(), "{}", password);
+        }
+        assert!(!user.is_recent_password("brandnew123", &previous).unwrap());
+    }
+
+    #[test]
+    fn test_client_hash_with_other_params_is_rejected() {
+        let email = Email::new("test@example.com").unwrap();
+        let params = ClientHashParams::new("c2FsdA".to_string(), 1000);
+        let user = User::with_client_hash(
+            email,
+            &params.derive("password123"),
+            params.clone(),
+            "Test User".to_string(),
+            &PasswordHasher::default(),
+        )
+        .unwrap();
+        let other = ClientHashParams::new("c2FsdA".to_string(), 2000);
+
+        let result = user.verify_client_hash(&other.derive("password123"), &other);
+
+        assert!(matches!(result, Err(AppError::ClientHashMismatch(_))));
+    }
 }
@@ -1,6 +1,8 @@
-use crate::shared::{AppError, AppResult};
-use bcrypt::{hash, verify, DEFAULT_COST};
+use crate::shared::{types::*, AppError, AppResult};
+use super::{password_hasher, ClientHashParams, PasswordHasher};
+use hmac::{Hmac, Mac};
 use rand::Rng;
+use sha2::Sha256;
 use serde::{Deserialize, Serialize};
 use validator::ValidateEmail;
 
@@ -33,6 +35,25 @@ impl Email {
         Ok(Self(email))
     }
 
+    /// Zero-width characters that come along with copy-pasted addresses
+    const ZERO_WIDTH: [char; 5] = ['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
+    /// Parse an email entered as a login identifier
+    ///
+    /// Normalization policy for login input:
+    /// - The identifier is always trimmed and lowercased (as by `new`)
+    /// - With `strip_zero_width`, zero-width characters are dropped from
+    ///   the identifier wherever they appear
+    /// - The password is never normalized: spaces are part of it
+    pub fn from_login_input(input: &str, strip_zero_width: bool) -> AppResult<Self> {
+        if strip_zero_width && input.contains(Self::ZERO_WIDTH) {
+            let stripped: String = input.chars().filter(|c| !Self::ZERO_WIDTH.contains(c)).collect();
+            return Self::new(&stripped);
+        }
+
+        Self::new(input)
+    }
+
     /// Get email as str
     pub fn as_str(&self) -> &str {
         &self.0
@@ -68,10 +89,26 @@ impl PasswordHash {
     /// Minimum password length requirement
     pub const MIN_LENGTH: usize = 8;
 
-    /// Create PasswordHash from plain text password
-    /// Validates minimum length and hashes with bcrypt
-    pub fn from_plain(password: &str) -> AppResult<Self> {
-        // Validate minimum length
+    /// Default maximum password length (bytes)
+    pub const DEFAULT_MAX_LENGTH: usize = 256;
+
+    /// Reject passwords longer than `max_length` bytes
+    ///
+    /// Called before hashing or verifying so oversized input never reaches
+    /// the hasher (bcrypt ignores everything past 72 bytes anyway).
+    pub fn ensure_max_length(password: &str, max_length: usize) -> AppResult<()> {
+        if password.len() > max_length {
+            return Err(AppError::validation(format!(
+                "Password must be at most {} characters",
+                max_length
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Reject passwords shorter than `MIN_LENGTH`
+    pub fn ensure_min_length(password: &str) -> AppResult<()> {
         if password.len() < Self::MIN_LENGTH {
             return Err(AppError::validation(format!(
                 "Password must be at least {} characters",
@@ -79,12 +116,28 @@ impl PasswordHash {
             )));
         }
 
-        // Hash password with bcrypt (cost 12)
-        let hash = hash(password, DEFAULT_COST).map_err(|e| {
-            AppError::internal(format!("Failed to hash password: {}", e))
-        })?;
+        Ok(())
+    }
+
+    /// Create PasswordHash from plain text password
+    /// Validates minimum length and hashes with `hasher`
+    pub fn from_plain(password: &str, hasher: &PasswordHasher) -> AppResult<Self> {
+        Self::ensure_min_length(password)?;
+        hasher.hash(password)
+    }
+
+    /// Create PasswordHash from a client-side hash (see `ClientHashParams`)
+    ///
+    /// The client hash is treated like a password and hashed with `hasher`;
+    /// only its format can be validated.
+    pub fn from_client_hash(client_hash: &str, hasher: &PasswordHasher) -> AppResult<Self> {
+        if !ClientHashParams::is_client_hash(client_hash) {
+            return Err(AppError::validation(
+                "Client-hashed password must be 64 lowercase hex characters",
+            ));
+        }
 
-        Ok(Self(hash))
+        hasher.hash(client_hash)
     }
 
     /// Create PasswordHash from existing hash (e.g., from database)
@@ -95,10 +148,10 @@ impl PasswordHash {
 
     /// Verify plain text password against this hash
     /// Uses constant-time comparison to prevent timing attacks
+    ///
+    /// The algorithm (bcrypt or argon2) is read from the hash itself.
     pub fn verify(&self, password: &str) -> AppResult<bool> {
-        verify(password, &self.0).map_err(|e| {
-            AppError::internal(format!("Failed to verify password: {}", e))
-        })
+        password_hasher::verify(password, &self.0)
     }
 
     /// Get hash as str (for serialization)
@@ -147,6 +200,49 @@ impl CsrfToken {
         self.0.as_bytes().ct_eq(token.as_bytes()).into()
     }
 
+    /// Generate a stateless CSRF token bound to a session
+    ///
+    /// Format: `<issued_at>.<signature>` where the signature is an
+    /// HMAC-SHA256 of the session ID and timestamp. It can be verified
+    /// with `verify_signed` without loading the session.
+    pub fn generate_signed(session_id: SessionId, secret: &str) -> Self {
+        Self::sign(session_id, now().timestamp(), secret)
+    }
+
+    /// Verify a stateless CSRF token for the given session
+    ///
+    /// Rejects tokens with an invalid signature, tokens for another
+    /// session, and tokens older than `max_age_seconds`.
+    pub fn verify_signed(token: &str, session_id: SessionId, secret: &str, max_age_seconds: i64) -> bool {
+        let Some((issued_at, _)) = token.split_once('.') else {
+            return false;
+        };
+        let Ok(issued_at) = issued_at.parse::<i64>() else {
+            return false;
+        };
+
+        let age = now().timestamp() - issued_at;
+        if !(0..=max_age_seconds).contains(&age) {
+            return false;
+        }
+
+        Self::sign(session_id, issued_at, secret).verify(token)
+    }
+
+    fn sign(session_id: SessionId, issued_at: i64, secret: &str) -> Self {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(session_id.into_inner().as_bytes());
+        mac.update(&issued_at.to_be_bytes());
+
+        let signature = base64::Engine::encode(
+            &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+            mac.finalize().into_bytes(),
+        );
+
+        Self(format!("{}.{}", issued_at, signature))
+    }
+
     /// Get token as str
     pub fn as_str(&self) -> &str {
         &self.0
@@ -187,19 +283,42 @@ mod tests {
         assert_eq!(email.as_str(), "test@example.com");
     }
 
+    #[test]
+    fn test_login_input_strips_zero_width_characters() {
+        let pasted = "\u{FEFF} Test\u{200B}@example.com\u{200D} ";
+
+        let email = Email::from_login_input(pasted, true).unwrap();
+        assert_eq!(email.as_str(), "test@example.com");
+
+        assert!(Email::from_login_input(pasted, false).is_err());
+        assert_eq!(
+            Email::from_login_input(" test@example.com ", false).unwrap().as_str(),
+            "test@example.com"
+        );
+    }
+
     #[test]
     fn test_password_hash_valid() {
-        let hash = PasswordHash::from_plain("password123").unwrap();
+        let hash = PasswordHash::from_plain("password123", &PasswordHasher::default()).unwrap();
         assert!(hash.verify("password123").unwrap());
         assert!(!hash.verify("wrongpassword").unwrap());
     }
 
     #[test]
     fn test_password_hash_too_short() {
-        let result = PasswordHash::from_plain("short");
+        let result = PasswordHash::from_plain("short", &PasswordHasher::default());
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_password_max_length() {
+        assert!(PasswordHash::ensure_max_length(&"a".repeat(256), 256).is_ok());
+        assert!(matches!(
+            PasswordHash::ensure_max_length(&"a".repeat(257), 256),
+            Err(AppError::Validation(_))
+        ));
+    }
+
     #[test]
     fn test_csrf_token_generation() {
         let token1 = CsrfToken::generate();
@@ -223,4 +342,42 @@ mod tests {
         assert!(token.verify(valid));
         assert!(!token.verify(invalid));
     }
+
+    const CSRF_SECRET: &str = "test_csrf_secret_key_minimum_32_characters_long";
+
+    #[test]
+    fn test_signed_csrf_token_valid() {
+        let session_id = SessionId::new();
+        let token = CsrfToken::generate_signed(session_id, CSRF_SECRET);
+
+        assert!(CsrfToken::verify_signed(token.as_str(), session_id, CSRF_SECRET, 3600));
+    }
+
+    #[test]
+    fn test_signed_csrf_token_tampered() {
+        let session_id = SessionId::new();
+        let token = CsrfToken::generate_signed(session_id, CSRF_SECRET);
+
+        // Different session, different secret
+        assert!(!CsrfToken::verify_signed(token.as_str(), SessionId::new(), CSRF_SECRET, 3600));
+        assert!(!CsrfToken::verify_signed(token.as_str(), session_id, "another_secret", 3600));
+
+        // Timestamp moved forward to extend the lifetime
+        let (issued_at, signature) = token.as_str().split_once('.').unwrap();
+        let forged = format!("{}.{}", issued_at.parse::<i64>().unwrap() + 60, signature);
+        assert!(!CsrfToken::verify_signed(&forged, session_id, CSRF_SECRET, 3600));
+
+        // Malformed
+        assert!(!CsrfToken::verify_signed("not-a-token", session_id, CSRF_SECRET, 3600));
+    }
+
+    #[test]
+    fn test_signed_csrf_token_expired() {
+        let session_id = SessionId::new();
+        let issued_at = now().timestamp() - 7200;
+        let token = CsrfToken::sign(session_id, issued_at, CSRF_SECRET);
+
+        assert!(!CsrfToken::verify_signed(token.as_str(), session_id, CSRF_SECRET, 3600));
+        assert!(CsrfToken::verify_signed(token.as_str(), session_id, CSRF_SECRET, 7300));
+    }
 }
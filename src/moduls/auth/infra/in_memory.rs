@@ -0,0 +1,754 @@
+//! In-memory repository implementations for unit tests
+//!
+//! These mirror the PostgreSQL repositories closely enough to exercise
+//! use cases without a database.
+
+use super::{
+    ApiKeyRepository, EmailVerificationRepository, HashMigrationRepository, LoginAttemptRepository,
+    MfaChallengeRepository, PasswordHistoryRepository, PasswordResetRepository, RoleRepository, SessionRepository,
+    TokenRepository, TokenWatermarkRepository, TotpRepository, UserFilter, UserRepository,
+};
+use crate::moduls::auth::domain::{
+    ApiKey, Email, EmailVerificationToken, HashMigrationRun, JwtToken, LoginSecuritySummary, MfaChallenge,
+    PasswordHash, PasswordResetToken, Role, Session, User, UserTotp,
+};
+use crate::shared::pagination::{Page, PageRequest};
+use crate::shared::{types::*, AppError, AppResult};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// In-memory UserRepository
+#[derive(Default)]
+pub struct InMemoryUserRepository {
+    pub users: Mutex<Vec<User>>,
+}
+
+impl InMemoryUserRepository {
+    pub fn with_user(user: User) -> Self {
+        Self {
+            users: Mutex::new(vec![user]),
+        }
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn save(&self, user: &User) -> AppResult<User> {
+        let mut users = self.users.lock().unwrap();
+        if users.iter().any(|u| u.email == user.email && u.tenant_id == user.tenant_id) {
+            return Err(AppError::conflict("Email already exists"));
+        }
+        users.push(user.clone());
+        Ok(user.clone())
+    }
+
+    async fn find_by_id(&self, id: UserId) -> AppResult<Option<User>> {
+        let users = self.users.lock().unwrap();
+        Ok(users.iter().find(|u| u.id == id).cloned())
+    }
+
+    async fn find_by_email(&self, email: &Email) -> AppResult<Option<User>> {
+        let users = self.users.lock().unwrap();
+        Ok(users
+            .iter()
+            .find(|u| &u.email == email && u.tenant_id.is_none())
+            .cloned())
+    }
+
+    async fn find_by_email_in_tenant(
+        &self,
+        email: &Email,
+        organization_id: OrganizationId,
+    ) -> AppResult<Option<User>> {
+        let users = self.users.lock().unwrap();
+        Ok(users
+            .iter()
+            .find(|u| &u.email == email && u.tenant_id == Some(organization_id))
+            .cloned())
+    }
+
+    async fn update(&self, user: &User) -> AppResult<User> {
+        let mut users = self.users.lock().unwrap();
+        let existing = users
+            .iter_mut()
+            .find(|u| u.id == user.id)
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+        *existing = user.clone();
+        Ok(user.clone())
+    }
+
+    async fn delete(&self, id: UserId) -> AppResult<()> {
+        let mut users = self.users.lock().unwrap();
+        let before = users.len();
+        users.retain(|u| u.id != id);
+        if users.len() == before {
+            return Err(AppError::not_found("User not found"));
+        }
+        Ok(())
+    }
+
+    async fn list_paginated(&self, filter: &UserFilter, page: PageRequest) -> AppResult<Page<User>> {
+        let search = filter.search.as_ref().map(|s| s.to_lowercase());
+        let mut matching: Vec<_> = self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|u| {
+                search.as_ref().is_none_or(|s| {
+                    u.email.as_str().to_lowercase().contains(s.as_str())
+                        || u.name.to_lowercase().contains(s.as_str())
+                })
+            })
+            .cloned()
+            .collect();
+        matching.sort_by_key(|u| (u.created_at, u.id));
+
+        let total = matching.len() as u64;
+        let items = matching
+            .into_iter()
+            .skip(page.offset() as usize)
+            .take(page.per_page as usize)
+            .collect();
+
+        Ok(Page::new(items, page, total))
+    }
+
+    async fn find_due_for_deletion(&self, limit: u32) -> AppResult<Vec<User>> {
+        let now = now();
+        let mut due: Vec<_> = self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|u| u.deleted_at.is_none() && u.deletion_scheduled_at.is_some_and(|at| at <= now))
+            .cloned()
+            .collect();
+        due.sort_by_key(|u| (u.deletion_scheduled_at, u.id));
+        due.truncate(limit as usize);
+
+        Ok(due)
+    }
+}
+
+/// In-memory SessionRepository
+#[derive(Default)]
+pub struct InMemorySessionRepository {
+    pub sessions: Mutex<Vec<Session>>,
+    /// Live sessions kept per user; `None` is unlimited
+    pub max_concurrent: Option<usize>,
+    /// Users in each tenant, for `delete_by_tenant`
+    pub tenant_users: Vec<(OrganizationId, UserId)>,
+}
+
+#[async_trait]
+impl SessionRepository for InMemorySessionRepository {
+    async fn save(&self, session: &Session) -> AppResult<Session> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.push(session.clone());
+
+        if let Some(limit) = self.max_concurrent {
+            let mut live: Vec<_> = sessions
+                .iter()
+                .filter(|s| s.user_id == session.user_id && !s.is_expired())
+                .map(|s| (s.created_at, s.id))
+                .collect();
+            live.sort_unstable_by(|a, b| b.cmp(a));
+            let evicted: Vec<_> = live.into_iter().skip(limit).map(|(_, id)| id).collect();
+            sessions.retain(|s| !evicted.contains(&s.id));
+        }
+
+        Ok(session.clone())
+    }
+
+    async fn find_by_id(&self, id: SessionId) -> AppResult<Option<Session>> {
+        let sessions = self.sessions.lock().unwrap();
+        Ok(sessions.iter().find(|s| s.id == id).cloned())
+    }
+
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<Session>> {
+        let sessions = self.sessions.lock().unwrap();
+        Ok(sessions
+            .iter()
+            .filter(|s| s.user_id == user_id)
+            .max_by_key(|s| s.created_at)
+            .cloned())
+    }
+
+    async fn find_all_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Session>> {
+        let mut sessions: Vec<_> = self
+            .sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| s.user_id == user_id && !s.is_expired())
+            .cloned()
+            .collect();
+        sessions.sort_by_key(|s| std::cmp::Reverse((s.updated_at, s.id)));
+        Ok(sessions)
+    }
+
+    async fn touch(&self, id: SessionId, expires_at: Timestamp) -> AppResult<()> {
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(session) = sessions.iter_mut().find(|s| s.id == id && s.expires_at < expires_at) {
+            session.expires_at = expires_at;
+            session.updated_at = now();
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, id: SessionId) -> AppResult<()> {
+        self.sessions.lock().unwrap().retain(|s| s.id != id);
+        Ok(())
+    }
+
+    async fn delete_by_user_id(&self, user_id: UserId) -> AppResult<()> {
+        self.sessions.lock().unwrap().retain(|s| s.user_id != user_id);
+        Ok(())
+    }
+
+    async fn delete_by_tenant(&self, tenant_id: OrganizationId, _batch_size: u32) -> AppResult<u64> {
+        let in_tenant = |user_id: UserId| self.tenant_users.contains(&(tenant_id, user_id));
+        let mut sessions = self.sessions.lock().unwrap();
+        let before = sessions.len();
+        sessions.retain(|s| s.is_expired() || !in_tenant(s.user_id));
+        Ok((before - sessions.len()) as u64)
+    }
+
+    async fn delete_expired(&self) -> AppResult<u64> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let before = sessions.len();
+        sessions.retain(|s| !s.is_expired());
+        Ok((before - sessions.len()) as u64)
+    }
+}
+
+/// In-memory TokenRepository
+#[derive(Default)]
+pub struct InMemoryTokenRepository {
+    pub tokens: Mutex<Vec<JwtToken>>,
+    /// Users in each tenant, for `revoke_tenant_tokens`
+    pub tenant_users: Vec<(OrganizationId, UserId)>,
+}
+
+#[async_trait]
+impl TokenRepository for InMemoryTokenRepository {
+    async fn save(&self, token: &JwtToken) -> AppResult<JwtToken> {
+        self.tokens.lock().unwrap().push(token.clone());
+        Ok(token.clone())
+    }
+
+    async fn find_by_jti(&self, jti: TokenId) -> AppResult<Option<JwtToken>> {
+        let tokens = self.tokens.lock().unwrap();
+        Ok(tokens.iter().find(|t| t.jti == jti).cloned())
+    }
+
+    async fn revoke(&self, jti: TokenId) -> AppResult<bool> {
+        let mut tokens = self.tokens.lock().unwrap();
+        match tokens.iter_mut().find(|t| t.jti == jti && !t.revoked) {
+            Some(token) => {
+                token.revoke();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn revoke_all_user_tokens(&self, user_id: UserId) -> AppResult<()> {
+        let mut tokens = self.tokens.lock().unwrap();
+        for token in tokens.iter_mut().filter(|t| t.user_id == user_id && !t.revoked) {
+            token.revoke();
+        }
+        Ok(())
+    }
+
+    async fn revoke_family(&self, family_id: Uuid) -> AppResult<u64> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let mut revoked = 0;
+        for token in tokens.iter_mut().filter(|t| t.family_id == family_id && !t.revoked) {
+            token.revoke();
+            revoked += 1;
+        }
+        Ok(revoked)
+    }
+
+    async fn revoke_session_tokens(&self, session_id: SessionId) -> AppResult<u64> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let mut revoked = 0;
+        for token in tokens.iter_mut().filter(|t| t.session_id == Some(session_id) && !t.revoked) {
+            token.revoke();
+            revoked += 1;
+        }
+        Ok(revoked)
+    }
+
+    async fn revoke_tenant_tokens(&self, tenant_id: OrganizationId, _batch_size: u32) -> AppResult<u64> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let mut revoked = 0;
+        for token in tokens.iter_mut().filter(|t| {
+            !t.revoked && !t.is_expired() && self.tenant_users.contains(&(tenant_id, t.user_id))
+        }) {
+            token.revoke();
+            revoked += 1;
+        }
+        Ok(revoked)
+    }
+
+    async fn delete_expired(&self) -> AppResult<u64> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let before = tokens.len();
+        tokens.retain(|t| !t.is_expired());
+        Ok((before - tokens.len()) as u64)
+    }
+}
+
+/// A recorded login attempt
+#[derive(Debug, Clone)]
+pub struct RecordedLoginAttempt {
+    pub user_id: UserId,
+    pub succeeded: bool,
+    pub ip_address: Option<String>,
+    pub created_at: Timestamp,
+}
+
+/// In-memory LoginAttemptRepository
+#[derive(Default)]
+pub struct InMemoryLoginAttemptRepository {
+    pub attempts: Mutex<Vec<RecordedLoginAttempt>>,
+}
+
+#[async_trait]
+impl LoginAttemptRepository for InMemoryLoginAttemptRepository {
+    async fn record(&self, user_id: UserId, succeeded: bool, ip_address: Option<String>) -> AppResult<()> {
+        self.attempts.lock().unwrap().push(RecordedLoginAttempt {
+            user_id,
+            succeeded,
+            ip_address,
+            created_at: now(),
+        });
+        Ok(())
+    }
+
+    async fn security_summary(&self, user_id: UserId, since: Timestamp) -> AppResult<LoginSecuritySummary> {
+        let attempts = self.attempts.lock().unwrap();
+        let failures: Vec<_> = attempts
+            .iter()
+            .filter(|a| a.user_id == user_id && !a.succeeded)
+            .collect();
+
+        Ok(LoginSecuritySummary {
+            recent_failed_logins: failures.iter().filter(|a| a.created_at >= since).count() as i64,
+            last_failed_at: failures.iter().map(|a| a.created_at).max(),
+        })
+    }
+
+    async fn is_new_ip(&self, user_id: UserId, ip_address: &str) -> AppResult<bool> {
+        let attempts = self.attempts.lock().unwrap();
+        let mut logins = attempts.iter().filter(|a| a.user_id == user_id && a.succeeded);
+        let first_login = logins.clone().next().is_none();
+
+        Ok(!first_login && logins.all(|a| a.ip_address.as_deref() != Some(ip_address)))
+    }
+}
+
+/// In-memory TokenWatermarkRepository
+#[derive(Default)]
+pub struct InMemoryTokenWatermarkRepository {
+    pub valid_after: Mutex<Option<Timestamp>>,
+}
+
+#[async_trait]
+impl TokenWatermarkRepository for InMemoryTokenWatermarkRepository {
+    async fn get(&self) -> AppResult<Option<Timestamp>> {
+        Ok(*self.valid_after.lock().unwrap())
+    }
+
+    async fn set(&self, valid_after: Timestamp) -> AppResult<()> {
+        *self.valid_after.lock().unwrap() = Some(valid_after);
+        Ok(())
+    }
+}
+
+/// In-memory PasswordResetRepository
+#[derive(Default)]
+pub struct InMemoryPasswordResetRepository {
+    pub tokens: Mutex<Vec<PasswordResetToken>>,
+}
+
+#[async_trait]
+impl PasswordResetRepository for InMemoryPasswordResetRepository {
+    async fn save(&self, token: &PasswordResetToken) -> AppResult<PasswordResetToken> {
+        self.tokens.lock().unwrap().push(token.clone());
+        Ok(token.clone())
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<PasswordResetToken>> {
+        let tokens = self.tokens.lock().unwrap();
+        Ok(tokens.iter().find(|t| t.token_hash == token_hash).cloned())
+    }
+
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool> {
+        let mut tokens = self.tokens.lock().unwrap();
+        match tokens.iter_mut().find(|t| t.id == id && t.used_at.is_none()) {
+            Some(token) => {
+                token.used_at = Some(now());
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn invalidate_user_tokens(&self, user_id: UserId) -> AppResult<()> {
+        let mut tokens = self.tokens.lock().unwrap();
+        for token in tokens.iter_mut().filter(|t| t.user_id == user_id && t.used_at.is_none()) {
+            token.used_at = Some(now());
+        }
+        Ok(())
+    }
+}
+
+/// In-memory EmailVerificationRepository
+#[derive(Default)]
+pub struct InMemoryEmailVerificationRepository {
+    pub tokens: Mutex<Vec<EmailVerificationToken>>,
+}
+
+#[async_trait]
+impl EmailVerificationRepository for InMemoryEmailVerificationRepository {
+    async fn save(&self, token: &EmailVerificationToken) -> AppResult<EmailVerificationToken> {
+        self.tokens.lock().unwrap().push(token.clone());
+        Ok(token.clone())
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<EmailVerificationToken>> {
+        let tokens = self.tokens.lock().unwrap();
+        Ok(tokens.iter().find(|t| t.token_hash == token_hash).cloned())
+    }
+
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool> {
+        let mut tokens = self.tokens.lock().unwrap();
+        match tokens.iter_mut().find(|t| t.id == id && t.used_at.is_none()) {
+            Some(token) => {
+                token.used_at = Some(now());
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn invalidate_user_tokens(&self, user_id: UserId) -> AppResult<()> {
+        let mut tokens = self.tokens.lock().unwrap();
+        for token in tokens.iter_mut().filter(|t| t.user_id == user_id && t.used_at.is_none()) {
+            token.used_at = Some(now());
+        }
+        Ok(())
+    }
+}
+
+/// In-memory TotpRepository
+#[derive(Default)]
+pub struct InMemoryTotpRepository {
+    pub enrollments: Mutex<Vec<UserTotp>>,
+}
+
+#[async_trait]
+impl TotpRepository for InMemoryTotpRepository {
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<UserTotp>> {
+        let enrollments = self.enrollments.lock().unwrap();
+        Ok(enrollments.iter().find(|t| t.user_id == user_id).cloned())
+    }
+
+    async fn save_pending(&self, totp: &UserTotp) -> AppResult<bool> {
+        let mut enrollments = self.enrollments.lock().unwrap();
+        match enrollments.iter_mut().find(|t| t.user_id == totp.user_id) {
+            Some(existing) if existing.is_confirmed() => Ok(false),
+            Some(existing) => {
+                *existing = totp.clone();
+                Ok(true)
+            }
+            None => {
+                enrollments.push(totp.clone());
+                Ok(true)
+            }
+        }
+    }
+
+    async fn confirm(&self, user_id: UserId, step: i64) -> AppResult<bool> {
+        let mut enrollments = self.enrollments.lock().unwrap();
+        match enrollments
+            .iter_mut()
+            .find(|t| t.user_id == user_id && !t.is_confirmed())
+        {
+            Some(totp) => {
+                totp.confirmed_at = Some(now());
+                totp.last_used_step = Some(step);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn record_used_step(&self, user_id: UserId, step: i64) -> AppResult<bool> {
+        let mut enrollments = self.enrollments.lock().unwrap();
+        match enrollments.iter_mut().find(|t| {
+            t.user_id == user_id && t.is_confirmed() && t.last_used_step.is_none_or(|last| last < step)
+        }) {
+            Some(totp) => {
+                totp.last_used_step = Some(step);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// In-memory MfaChallengeRepository
+#[derive(Default)]
+pub struct InMemoryMfaChallengeRepository {
+    pub challenges: Mutex<Vec<MfaChallenge>>,
+}
+
+#[async_trait]
+impl MfaChallengeRepository for InMemoryMfaChallengeRepository {
+    async fn save(&self, challenge: &MfaChallenge) -> AppResult<MfaChallenge> {
+        self.challenges.lock().unwrap().push(challenge.clone());
+        Ok(challenge.clone())
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<MfaChallenge>> {
+        let challenges = self.challenges.lock().unwrap();
+        Ok(challenges.iter().find(|c| c.token_hash == token_hash).cloned())
+    }
+
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool> {
+        let mut challenges = self.challenges.lock().unwrap();
+        match challenges.iter_mut().find(|c| c.id == id && c.used_at.is_none()) {
+            Some(challenge) => {
+                challenge.used_at = Some(now());
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn record_failure(&self, id: Uuid) -> AppResult<()> {
+        let mut challenges = self.challenges.lock().unwrap();
+        if let Some(challenge) = challenges.iter_mut().find(|c| c.id == id) {
+            challenge.failed_attempts += 1;
+        }
+        Ok(())
+    }
+}
+
+/// In-memory RoleRepository
+///
+/// Only `Role::ADMIN` and `Role::SUPER_ADMIN` exist, as seeded by the
+/// migrations.
+#[derive(Default)]
+pub struct InMemoryRoleRepository {
+    pub grants: Mutex<Vec<(UserId, Role)>>,
+}
+
+#[async_trait]
+impl RoleRepository for InMemoryRoleRepository {
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Role>> {
+        let grants = self.grants.lock().unwrap();
+        let mut roles: Vec<Role> = grants
+            .iter()
+            .filter(|(id, _)| *id == user_id)
+            .map(|(_, role)| role.clone())
+            .collect();
+        roles.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        Ok(roles)
+    }
+
+    async fn grant(&self, user_id: UserId, role: &Role) -> AppResult<bool> {
+        if role.as_str() != Role::ADMIN && role.as_str() != Role::SUPER_ADMIN {
+            return Err(AppError::NotFound(format!("Role '{}' not found", role)));
+        }
+        let mut grants = self.grants.lock().unwrap();
+        if grants.iter().any(|(id, r)| *id == user_id && r == role) {
+            return Ok(false);
+        }
+        grants.push((user_id, role.clone()));
+        Ok(true)
+    }
+
+    async fn revoke(&self, user_id: UserId, role: &Role) -> AppResult<bool> {
+        let mut grants = self.grants.lock().unwrap();
+        let before = grants.len();
+        grants.retain(|(id, r)| !(*id == user_id && r == role));
+        Ok(grants.len() < before)
+    }
+}
+
+/// In-memory ApiKeyRepository
+#[derive(Default)]
+pub struct InMemoryApiKeyRepository {
+    pub keys: Mutex<Vec<ApiKey>>,
+}
+
+#[async_trait]
+impl ApiKeyRepository for InMemoryApiKeyRepository {
+    async fn save(&self, key: &ApiKey) -> AppResult<ApiKey> {
+        self.keys.lock().unwrap().push(key.clone());
+        Ok(key.clone())
+    }
+
+    async fn find_by_hash(&self, key_hash: &str) -> AppResult<Option<ApiKey>> {
+        let keys = self.keys.lock().unwrap();
+        Ok(keys.iter().find(|k| k.key_hash == key_hash).cloned())
+    }
+
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<ApiKey>> {
+        let keys = self.keys.lock().unwrap();
+        let mut found: Vec<ApiKey> = keys
+            .iter()
+            .filter(|k| k.user_id == user_id && !k.is_revoked())
+            .cloned()
+            .collect();
+        found.sort_by_key(|k| std::cmp::Reverse((k.created_at, k.id)));
+        Ok(found)
+    }
+
+    async fn revoke(&self, id: Uuid, user_id: UserId) -> AppResult<bool> {
+        let mut keys = self.keys.lock().unwrap();
+        match keys
+            .iter_mut()
+            .find(|k| k.id == id && k.user_id == user_id && !k.is_revoked())
+        {
+            Some(key) => {
+                key.revoked_at = Some(now());
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn revoke_all_for_user(&self, user_id: UserId) -> AppResult<u64> {
+        let mut keys = self.keys.lock().unwrap();
+        let mut revoked = 0;
+        for key in keys.iter_mut().filter(|k| k.user_id == user_id && !k.is_revoked()) {
+            key.revoked_at = Some(now());
+            revoked += 1;
+        }
+        Ok(revoked)
+    }
+
+    async fn touch(&self, id: Uuid) -> AppResult<()> {
+        let mut keys = self.keys.lock().unwrap();
+        if let Some(key) = keys.iter_mut().find(|k| k.id == id) {
+            key.last_used_at = Some(now());
+        }
+        Ok(())
+    }
+}
+
+/// In-memory PasswordHistoryRepository
+#[derive(Default)]
+pub struct InMemoryPasswordHistoryRepository {
+    /// Oldest first
+    pub entries: Mutex<Vec<(UserId, PasswordHash)>>,
+}
+
+#[async_trait]
+impl PasswordHistoryRepository for InMemoryPasswordHistoryRepository {
+    async fn recent(&self, user_id: UserId, limit: usize) -> AppResult<Vec<PasswordHash>> {
+        let entries = self.entries.lock().unwrap();
+        Ok(entries
+            .iter()
+            .rev()
+            .filter(|(id, _)| *id == user_id)
+            .take(limit)
+            .map(|(_, hash)| hash.clone())
+            .collect())
+    }
+
+    async fn push(&self, user_id: UserId, password_hash: &PasswordHash, keep: usize) -> AppResult<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.push((user_id, password_hash.clone()));
+
+        let excess = entries.iter().filter(|(id, _)| *id == user_id).count().saturating_sub(keep);
+        let mut dropped = 0;
+        entries.retain(|(id, _)| {
+            let drop = *id == user_id && dropped < excess;
+            dropped += usize::from(drop);
+            !drop
+        });
+        Ok(())
+    }
+}
+
+/// In-memory HashMigrationRepository over an in-memory user store
+pub struct InMemoryHashMigrationRepository {
+    pub users: Arc<InMemoryUserRepository>,
+    /// Oldest first
+    pub runs: Mutex<Vec<HashMigrationRun>>,
+}
+
+impl InMemoryHashMigrationRepository {
+    pub fn new(users: Arc<InMemoryUserRepository>) -> Self {
+        Self {
+            users,
+            runs: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl HashMigrationRepository for InMemoryHashMigrationRepository {
+    async fn save_run(&self, run: &HashMigrationRun) -> AppResult<()> {
+        self.runs.lock().unwrap().push(run.clone());
+        Ok(())
+    }
+
+    async fn update_run(&self, run: &HashMigrationRun) -> AppResult<()> {
+        let mut runs = self.runs.lock().unwrap();
+        if let Some(existing) = runs.iter_mut().find(|r| r.id == run.id) {
+            *existing = run.clone();
+        }
+        Ok(())
+    }
+
+    async fn latest_run(&self) -> AppResult<Option<HashMigrationRun>> {
+        Ok(self.runs.lock().unwrap().last().cloned())
+    }
+
+    async fn password_hashes_after(
+        &self,
+        after: Option<UserId>,
+        limit: usize,
+    ) -> AppResult<Vec<(UserId, PasswordHash)>> {
+        let users = self.users.users.lock().unwrap();
+        let mut hashes: Vec<_> = users
+            .iter()
+            .filter(|u| after.is_none_or(|after| u.id > after))
+            .filter(|u| u.deleted_at.is_none() && !u.must_change_password)
+            .map(|u| (u.id, u.password_hash.clone()))
+            .collect();
+        hashes.sort_by_key(|(id, _)| *id);
+        hashes.truncate(limit);
+        Ok(hashes)
+    }
+
+    async fn flag_users(&self, user_ids: &[UserId]) -> AppResult<u64> {
+        let mut users = self.users.users.lock().unwrap();
+        let mut flagged = 0;
+        for user in users.iter_mut() {
+            if user_ids.contains(&user.id) && !user.must_change_password {
+                user.must_change_password = true;
+                flagged += 1;
+            }
+        }
+        Ok(flagged)
+    }
+
+    async fn count_flagged(&self) -> AppResult<i64> {
+        let users = self.users.users.lock().unwrap();
+        Ok(users
+            .iter()
+            .filter(|u| u.must_change_password && u.deleted_at.is_none())
+            .count() as i64)
+    }
+}
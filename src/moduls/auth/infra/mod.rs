@@ -1,13 +1,38 @@
-/// Infrastructure layer for authentication module
-///
-/// This layer contains concrete implementations of repository interfaces
-/// and external service integrations (database, etc).
+//! Infrastructure layer for authentication module
+//!
+//! This layer contains concrete implementations of repository interfaces
+//! and external service integrations (database, etc).
 
 pub mod postgres_user_repository;
 pub mod postgres_session_repository;
 pub mod postgres_token_repository;
+pub mod postgres_login_attempt_repository;
+pub mod postgres_token_watermark_repository;
+pub mod postgres_password_reset_repository;
+pub mod postgres_email_verification_repository;
+pub mod postgres_totp_repository;
+pub mod postgres_mfa_challenge_repository;
+pub mod postgres_role_repository;
+pub mod postgres_api_key_repository;
+pub mod postgres_password_history_repository;
+pub mod postgres_hash_migration_repository;
+pub mod revocation_cache;
+
+#[cfg(test)]
+pub mod in_memory;
 
 // Re-export repository traits and implementations
-pub use postgres_user_repository::{UserRepository, PostgresUserRepository};
+pub use postgres_user_repository::{UserFilter, UserRepository, PostgresUserRepository};
 pub use postgres_session_repository::{SessionRepository, PostgresSessionRepository};
 pub use postgres_token_repository::{TokenRepository, PostgresTokenRepository};
+pub use postgres_login_attempt_repository::{LoginAttemptRepository, PostgresLoginAttemptRepository};
+pub use postgres_token_watermark_repository::{TokenWatermarkRepository, PostgresTokenWatermarkRepository};
+pub use postgres_password_reset_repository::{PasswordResetRepository, PostgresPasswordResetRepository};
+pub use postgres_email_verification_repository::{EmailVerificationRepository, PostgresEmailVerificationRepository};
+pub use postgres_totp_repository::{TotpRepository, PostgresTotpRepository};
+pub use postgres_mfa_challenge_repository::{MfaChallengeRepository, PostgresMfaChallengeRepository};
+pub use postgres_role_repository::{RoleRepository, PostgresRoleRepository};
+pub use postgres_api_key_repository::{ApiKeyRepository, PostgresApiKeyRepository};
+pub use postgres_password_history_repository::{PasswordHistoryRepository, PostgresPasswordHistoryRepository};
+pub use postgres_hash_migration_repository::{HashMigrationRepository, PostgresHashMigrationRepository};
+pub use revocation_cache::{CachedTokenRepository, RevocationCache};
@@ -0,0 +1,158 @@
+use crate::moduls::auth::domain::ApiKey;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// ApiKeyRepository trait defining API key persistence
+///
+/// Keys are looked up by hash. Revoked keys are kept (with `revoked_at`)
+/// rather than deleted.
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    /// Save new key
+    async fn save(&self, key: &ApiKey) -> AppResult<ApiKey>;
+
+    /// Find key by the hash of its plain value
+    ///
+    /// Returns None if key not found
+    async fn find_by_hash(&self, key_hash: &str) -> AppResult<Option<ApiKey>>;
+
+    /// Unrevoked keys of a user (including expired ones), newest first
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<ApiKey>>;
+
+    /// Revoke a key owned by `user_id`
+    ///
+    /// Returns false if no such unrevoked key exists for the user
+    async fn revoke(&self, id: Uuid, user_id: UserId) -> AppResult<bool>;
+
+    /// Revoke every unrevoked key of `user_id`, returning how many
+    async fn revoke_all_for_user(&self, user_id: UserId) -> AppResult<u64>;
+
+    /// Record a successful authentication with the key
+    async fn touch(&self, id: Uuid) -> AppResult<()>;
+}
+
+/// PostgreSQL implementation of ApiKeyRepository
+///
+/// Reads stay on the primary: a revoked key must not keep working on a lagging replica.
+pub struct PostgresApiKeyRepository {
+    db: DbPools,
+}
+
+impl PostgresApiKeyRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PostgresApiKeyRepository {
+    async fn save(&self, key: &ApiKey) -> AppResult<ApiKey> {
+        let result = sqlx::query_as::<_, ApiKey>(
+            r#"
+            INSERT INTO api_keys (id, user_id, tenant_id, name, key_hash, last_used_at, expires_at, revoked_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, user_id, tenant_id, name, key_hash, last_used_at, expires_at, revoked_at, created_at
+            "#,
+        )
+        .bind(key.id)
+        .bind(key.user_id)
+        .bind(key.tenant_id)
+        .bind(&key.name)
+        .bind(&key.key_hash)
+        .bind(key.last_used_at)
+        .bind(key.expires_at)
+        .bind(key.revoked_at)
+        .bind(key.created_at)
+        .fetch_one(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to save API key: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn find_by_hash(&self, key_hash: &str) -> AppResult<Option<ApiKey>> {
+        let result = sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, user_id, tenant_id, name, key_hash, last_used_at, expires_at, revoked_at, created_at
+            FROM api_keys
+            WHERE key_hash = $1
+            "#,
+        )
+        .bind(key_hash)
+        .fetch_optional(self.db.primary())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find API key: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<ApiKey>> {
+        let result = sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, user_id, tenant_id, name, key_hash, last_used_at, expires_at, revoked_at, created_at
+            FROM api_keys
+            WHERE user_id = $1 AND revoked_at IS NULL
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.db.primary())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find API keys: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn revoke(&self, id: Uuid, user_id: UserId) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET revoked_at = $3
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to revoke API key: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn revoke_all_for_user(&self, user_id: UserId) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET revoked_at = $2
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to revoke API keys: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn touch(&self, id: Uuid) -> AppResult<()> {
+        // At most one write per key and minute, however busy the caller
+        sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET last_used_at = NOW()
+            WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')
+            "#,
+        )
+        .bind(id)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to record API key use: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use crate::moduls::auth::domain::EmailVerificationToken;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// EmailVerificationRepository trait defining verification token persistence
+///
+/// Tokens are looked up by hash; redeeming one is a conditional update so
+/// the same token cannot be used twice, even by concurrent requests.
+#[async_trait]
+pub trait EmailVerificationRepository: Send + Sync {
+    /// Save new verification token
+    async fn save(&self, token: &EmailVerificationToken) -> AppResult<EmailVerificationToken>;
+
+    /// Find token by the hash of its plain value
+    ///
+    /// Returns None if token not found
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<EmailVerificationToken>>;
+
+    /// Mark a token as used
+    ///
+    /// Returns false if it had already been used
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool>;
+
+    /// Mark every outstanding token of a user as used
+    async fn invalidate_user_tokens(&self, user_id: UserId) -> AppResult<()>;
+}
+
+/// PostgreSQL implementation of EmailVerificationRepository
+///
+/// Reads stay on the primary: a redeemed token must not look unused on a lagging replica.
+pub struct PostgresEmailVerificationRepository {
+    db: DbPools,
+}
+
+impl PostgresEmailVerificationRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl EmailVerificationRepository for PostgresEmailVerificationRepository {
+    async fn save(&self, token: &EmailVerificationToken) -> AppResult<EmailVerificationToken> {
+        let result = sqlx::query_as::<_, EmailVerificationToken>(
+            r#"
+            INSERT INTO email_verification_tokens (id, user_id, token_hash, expires_at, used_at, recovery_email, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, user_id, token_hash, expires_at, used_at, recovery_email, created_at
+            "#,
+        )
+        .bind(token.id)
+        .bind(token.user_id)
+        .bind(&token.token_hash)
+        .bind(token.expires_at)
+        .bind(token.used_at)
+        .bind(&token.recovery_email)
+        .bind(token.created_at)
+        .fetch_one(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to save email verification token: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<EmailVerificationToken>> {
+        let result = sqlx::query_as::<_, EmailVerificationToken>(
+            r#"
+            SELECT id, user_id, token_hash, expires_at, used_at, recovery_email, created_at
+            FROM email_verification_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find email verification token: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE email_verification_tokens
+            SET used_at = $2
+            WHERE id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to redeem email verification token: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn invalidate_user_tokens(&self, user_id: UserId) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE email_verification_tokens
+            SET used_at = $2
+            WHERE user_id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to invalidate email verification tokens: {}", e)))?;
+
+        Ok(())
+    }
+}
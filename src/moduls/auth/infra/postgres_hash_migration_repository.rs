@@ -0,0 +1,164 @@
+use crate::moduls::auth::domain::{HashMigrationRun, PasswordHash};
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// HashMigrationRepository trait for the outdated password hash job
+///
+/// Tracks the job's runs and scans and flags users' password hashes.
+/// Deleted users and users already flagged are left out of the scan.
+#[async_trait]
+pub trait HashMigrationRepository: Send + Sync {
+    /// Record a new run
+    async fn save_run(&self, run: &HashMigrationRun) -> AppResult<()>;
+
+    /// Store a run's status and counters
+    async fn update_run(&self, run: &HashMigrationRun) -> AppResult<()>;
+
+    /// Most recently started run
+    async fn latest_run(&self) -> AppResult<Option<HashMigrationRun>>;
+
+    /// Up to `limit` unflagged users with an ID after `after`, in ID order,
+    /// with their password hash
+    async fn password_hashes_after(
+        &self,
+        after: Option<UserId>,
+        limit: usize,
+    ) -> AppResult<Vec<(UserId, PasswordHash)>>;
+
+    /// Set `must_change_password` on `user_ids`, returning how many users
+    /// were newly flagged
+    async fn flag_users(&self, user_ids: &[UserId]) -> AppResult<u64>;
+
+    /// Users that still have to change their password
+    async fn count_flagged(&self) -> AppResult<i64>;
+}
+
+/// PostgreSQL implementation of HashMigrationRepository
+pub struct PostgresHashMigrationRepository {
+    db: DbPools,
+}
+
+impl PostgresHashMigrationRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl HashMigrationRepository for PostgresHashMigrationRepository {
+    async fn save_run(&self, run: &HashMigrationRun) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO password_hash_migrations
+                (id, started_by, status, scanned_users, flagged_users, error, started_at, finished_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(run.id)
+        .bind(run.started_by)
+        .bind(run.status)
+        .bind(run.scanned_users)
+        .bind(run.flagged_users)
+        .bind(&run.error)
+        .bind(run.started_at)
+        .bind(run.finished_at)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to save hash migration: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn update_run(&self, run: &HashMigrationRun) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE password_hash_migrations
+            SET status = $2, scanned_users = $3, flagged_users = $4, error = $5, finished_at = $6
+            WHERE id = $1
+            "#,
+        )
+        .bind(run.id)
+        .bind(run.status)
+        .bind(run.scanned_users)
+        .bind(run.flagged_users)
+        .bind(&run.error)
+        .bind(run.finished_at)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to update hash migration: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn latest_run(&self) -> AppResult<Option<HashMigrationRun>> {
+        let result = sqlx::query_as::<_, HashMigrationRun>(
+            r#"
+            SELECT id, started_by, status, scanned_users, flagged_users, error, started_at, finished_at
+            FROM password_hash_migrations
+            ORDER BY started_at DESC, id DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find hash migration: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn password_hashes_after(
+        &self,
+        after: Option<UserId>,
+        limit: usize,
+    ) -> AppResult<Vec<(UserId, PasswordHash)>> {
+        let result = sqlx::query_as::<_, (UserId, PasswordHash)>(
+            r#"
+            SELECT id, password_hash
+            FROM users
+            WHERE ($1::uuid IS NULL OR id > $1)
+              AND deleted_at IS NULL
+              AND NOT must_change_password
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(after)
+        .bind(limit as i64)
+        .fetch_all(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to scan password hashes: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn flag_users(&self, user_ids: &[UserId]) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET must_change_password = TRUE, updated_at = NOW()
+            WHERE id = ANY($1) AND NOT must_change_password
+            "#,
+        )
+        .bind(user_ids)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to flag users: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn count_flagged(&self) -> AppResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM users
+            WHERE must_change_password AND deleted_at IS NULL
+            "#,
+        )
+        .fetch_one(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to count flagged users: {}", e)))?;
+
+        Ok(count)
+    }
+}
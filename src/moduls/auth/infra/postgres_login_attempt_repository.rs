@@ -0,0 +1,96 @@
+use crate::moduls::auth::domain::LoginSecuritySummary;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// LoginAttemptRepository trait defining login activity persistence
+///
+/// Records per-user login outcomes and computes the security summary
+/// shown to the account owner.
+#[async_trait]
+pub trait LoginAttemptRepository: Send + Sync {
+    /// Record a login attempt for a known user
+    async fn record(&self, user_id: UserId, succeeded: bool, ip_address: Option<String>) -> AppResult<()>;
+
+    /// Summarize failed attempts since the given timestamp
+    ///
+    /// `last_failed_at` reports the most recent failure regardless of `since`
+    async fn security_summary(&self, user_id: UserId, since: Timestamp) -> AppResult<LoginSecuritySummary>;
+
+    /// Whether `ip_address` is new to the user: they logged in successfully
+    /// before, but never from that address
+    ///
+    /// A user's first login is not from a new address.
+    async fn is_new_ip(&self, user_id: UserId, ip_address: &str) -> AppResult<bool>;
+}
+
+/// PostgreSQL implementation of LoginAttemptRepository
+pub struct PostgresLoginAttemptRepository {
+    db: DbPools,
+}
+
+impl PostgresLoginAttemptRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl LoginAttemptRepository for PostgresLoginAttemptRepository {
+    async fn record(&self, user_id: UserId, succeeded: bool, ip_address: Option<String>) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO login_attempts (id, user_id, succeeded, ip_address, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(new_id())
+        .bind(user_id)
+        .bind(succeeded)
+        .bind(ip_address)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to record login attempt: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn security_summary(&self, user_id: UserId, since: Timestamp) -> AppResult<LoginSecuritySummary> {
+        let result = sqlx::query_as::<_, LoginSecuritySummary>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE created_at >= $2) AS recent_failed_logins,
+                MAX(created_at) AS last_failed_at
+            FROM login_attempts
+            WHERE user_id = $1 AND NOT succeeded
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_one(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to load login activity: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn is_new_ip(&self, user_id: UserId, ip_address: &str) -> AppResult<bool> {
+        let result = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT
+                EXISTS (SELECT 1 FROM login_attempts WHERE user_id = $1 AND succeeded)
+                AND NOT EXISTS (
+                    SELECT 1 FROM login_attempts
+                    WHERE user_id = $1 AND succeeded AND ip_address = $2
+                )
+            "#,
+        )
+        .bind(user_id)
+        .bind(ip_address)
+        .fetch_one(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to load login activity: {}", e)))?;
+
+        Ok(result)
+    }
+}
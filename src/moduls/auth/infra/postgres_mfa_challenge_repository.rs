@@ -0,0 +1,115 @@
+use crate::moduls::auth::domain::MfaChallenge;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// MfaChallengeRepository trait defining MFA challenge persistence
+///
+/// Challenges are looked up by hash; completing one is a conditional
+/// update so the same challenge cannot be completed twice.
+#[async_trait]
+pub trait MfaChallengeRepository: Send + Sync {
+    /// Save new challenge
+    async fn save(&self, challenge: &MfaChallenge) -> AppResult<MfaChallenge>;
+
+    /// Find challenge by the hash of its plain token
+    ///
+    /// Returns None if challenge not found
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<MfaChallenge>>;
+
+    /// Mark a challenge as completed
+    ///
+    /// Returns false if it had already been completed
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool>;
+
+    /// Count a wrong code against a challenge
+    async fn record_failure(&self, id: Uuid) -> AppResult<()>;
+}
+
+/// PostgreSQL implementation of MfaChallengeRepository
+///
+/// Reads stay on the primary: a completed challenge must not look pending on a lagging replica.
+pub struct PostgresMfaChallengeRepository {
+    db: DbPools,
+}
+
+impl PostgresMfaChallengeRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl MfaChallengeRepository for PostgresMfaChallengeRepository {
+    async fn save(&self, challenge: &MfaChallenge) -> AppResult<MfaChallenge> {
+        let result = sqlx::query_as::<_, MfaChallenge>(
+            r#"
+            INSERT INTO mfa_challenges (id, user_id, tenant_id, token_hash, expires_at, used_at, failed_attempts, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, tenant_id, token_hash, expires_at, used_at, failed_attempts, created_at
+            "#,
+        )
+        .bind(challenge.id)
+        .bind(challenge.user_id)
+        .bind(challenge.tenant_id)
+        .bind(&challenge.token_hash)
+        .bind(challenge.expires_at)
+        .bind(challenge.used_at)
+        .bind(challenge.failed_attempts)
+        .bind(challenge.created_at)
+        .fetch_one(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to save MFA challenge: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<MfaChallenge>> {
+        let result = sqlx::query_as::<_, MfaChallenge>(
+            r#"
+            SELECT id, user_id, tenant_id, token_hash, expires_at, used_at, failed_attempts, created_at
+            FROM mfa_challenges
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find MFA challenge: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE mfa_challenges
+            SET used_at = $2
+            WHERE id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to complete MFA challenge: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn record_failure(&self, id: Uuid) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE mfa_challenges
+            SET failed_attempts = failed_attempts + 1
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to record MFA failure: {}", e)))?;
+
+        Ok(())
+    }
+}
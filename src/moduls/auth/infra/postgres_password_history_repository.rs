@@ -0,0 +1,80 @@
+use crate::moduls::auth::domain::PasswordHash;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// PasswordHistoryRepository trait defining password history persistence
+///
+/// Keeps the hashes of a user's previous passwords, newest first.
+#[async_trait]
+pub trait PasswordHistoryRepository: Send + Sync {
+    /// Newest `limit` previous password hashes of a user
+    async fn recent(&self, user_id: UserId, limit: usize) -> AppResult<Vec<PasswordHash>>;
+
+    /// Remember a replaced password hash, keeping the user's newest `keep`
+    /// entries (this one included)
+    async fn push(&self, user_id: UserId, password_hash: &PasswordHash, keep: usize) -> AppResult<()>;
+}
+
+/// PostgreSQL implementation of PasswordHistoryRepository
+///
+/// Reads stay on the primary: a just-replaced password must not be reusable
+/// because of a lagging replica.
+pub struct PostgresPasswordHistoryRepository {
+    db: DbPools,
+}
+
+impl PostgresPasswordHistoryRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl PasswordHistoryRepository for PostgresPasswordHistoryRepository {
+    async fn recent(&self, user_id: UserId, limit: usize) -> AppResult<Vec<PasswordHash>> {
+        let result = sqlx::query_scalar::<_, PasswordHash>(
+            r#"
+            SELECT password_hash
+            FROM password_history
+            WHERE user_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit as i64)
+        .fetch_all(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to load password history: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn push(&self, user_id: UserId, password_hash: &PasswordHash, keep: usize) -> AppResult<()> {
+        // The DELETE doesn't see the row inserted by the same statement, so
+        // it keeps the newest `keep - 1` existing entries
+        sqlx::query(
+            r#"
+            WITH inserted AS (
+                INSERT INTO password_history (user_id, password_hash)
+                VALUES ($1, $2)
+            )
+            DELETE FROM password_history
+            WHERE user_id = $1 AND id NOT IN (
+                SELECT id FROM password_history
+                WHERE user_id = $1
+                ORDER BY created_at DESC, id DESC
+                LIMIT $3
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(password_hash)
+        .bind(keep.saturating_sub(1) as i64)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to save password history: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use crate::moduls::auth::domain::PasswordResetToken;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// PasswordResetRepository trait defining reset token persistence
+///
+/// Tokens are looked up by hash; redeeming one is a conditional update so
+/// the same token cannot be used twice, even by concurrent requests.
+#[async_trait]
+pub trait PasswordResetRepository: Send + Sync {
+    /// Save new reset token
+    async fn save(&self, token: &PasswordResetToken) -> AppResult<PasswordResetToken>;
+
+    /// Find token by the hash of its plain value
+    ///
+    /// Returns None if token not found
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<PasswordResetToken>>;
+
+    /// Mark a token as used
+    ///
+    /// Returns false if it had already been used
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool>;
+
+    /// Mark every outstanding token of a user as used
+    async fn invalidate_user_tokens(&self, user_id: UserId) -> AppResult<()>;
+}
+
+/// PostgreSQL implementation of PasswordResetRepository
+///
+/// Reads stay on the primary: a redeemed token must not look unused on a lagging replica.
+pub struct PostgresPasswordResetRepository {
+    db: DbPools,
+}
+
+impl PostgresPasswordResetRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for PostgresPasswordResetRepository {
+    async fn save(&self, token: &PasswordResetToken) -> AppResult<PasswordResetToken> {
+        let result = sqlx::query_as::<_, PasswordResetToken>(
+            r#"
+            INSERT INTO password_reset_tokens (id, user_id, token_hash, expires_at, used_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, token_hash, expires_at, used_at, created_at
+            "#,
+        )
+        .bind(token.id)
+        .bind(token.user_id)
+        .bind(&token.token_hash)
+        .bind(token.expires_at)
+        .bind(token.used_at)
+        .bind(token.created_at)
+        .fetch_one(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to save password reset token: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<PasswordResetToken>> {
+        let result = sqlx::query_as::<_, PasswordResetToken>(
+            r#"
+            SELECT id, user_id, token_hash, expires_at, used_at, created_at
+            FROM password_reset_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find password reset token: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE password_reset_tokens
+            SET used_at = $2
+            WHERE id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to redeem password reset token: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn invalidate_user_tokens(&self, user_id: UserId) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE password_reset_tokens
+            SET used_at = $2
+            WHERE user_id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to invalidate password reset tokens: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::moduls::auth::domain::Role;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// RoleRepository trait defining role grant persistence
+///
+/// Only roles listed in the `roles` table can be granted.
+#[async_trait]
+pub trait RoleRepository: Send + Sync {
+    /// Roles granted to a user, sorted by name
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Role>>;
+
+    /// Grant a role to a user
+    ///
+    /// Returns false if the user already had it
+    ///
+    /// # Errors
+    /// - NotFound if the role doesn't exist
+    async fn grant(&self, user_id: UserId, role: &Role) -> AppResult<bool>;
+
+    /// Take a role away from a user
+    ///
+    /// Returns false if the user didn't have it
+    async fn revoke(&self, user_id: UserId, role: &Role) -> AppResult<bool>;
+}
+
+/// PostgreSQL implementation of RoleRepository
+///
+/// Reads stay on the primary: a revoked role must not linger on a lagging replica.
+pub struct PostgresRoleRepository {
+    db: DbPools,
+}
+
+impl PostgresRoleRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl RoleRepository for PostgresRoleRepository {
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Role>> {
+        let result = sqlx::query_scalar::<_, Role>(
+            r#"
+            SELECT r.name
+            FROM user_roles ur
+            JOIN roles r ON r.id = ur.role_id
+            WHERE ur.user_id = $1
+            ORDER BY r.name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.db.primary())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find user roles: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn grant(&self, user_id: UserId, role: &Role) -> AppResult<bool> {
+        let role_id: Option<uuid::Uuid> = sqlx::query_scalar("SELECT id FROM roles WHERE name = $1")
+            .bind(role)
+            .fetch_optional(self.db.writer())
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to find role: {}", e)))?;
+        let role_id = role_id.ok_or_else(|| AppError::NotFound(format!("Role '{}' not found", role)))?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_roles (user_id, role_id)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, role_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(role_id)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to grant role: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn revoke(&self, user_id: UserId, role: &Role) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM user_roles
+            WHERE user_id = $1
+              AND role_id = (SELECT id FROM roles WHERE name = $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(role)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to revoke role: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -1,7 +1,6 @@
 use crate::moduls::auth::domain::Session;
-use crate::shared::{types::*, AppError, AppResult};
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
 use async_trait::async_trait;
-use sqlx::PgPool;
 
 /// SessionRepository trait defining session persistence operations
 ///
@@ -12,7 +11,8 @@ pub trait SessionRepository: Send + Sync {
     /// Save new session to database
     ///
     /// # Business Rules
-    /// - Enforces single session per user (deletes existing sessions)
+    /// - With a concurrent session limit, the user's oldest sessions beyond
+    ///   it are evicted (a limit of 1 keeps a single session per user)
     async fn save(&self, session: &Session) -> AppResult<Session>;
 
     /// Find session by ID
@@ -23,49 +23,149 @@ pub trait SessionRepository: Send + Sync {
     /// Find session by user ID
     ///
     /// Returns most recent session for user
-    /// Used to enforce single session per user
     async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<Session>>;
 
+    /// Find all live (not revoked or expired) sessions of a user
+    ///
+    /// Most recently active first
+    async fn find_all_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Session>>;
+
+    /// Move a live session's expiration forward to `expires_at`
+    ///
+    /// Used for sliding expiration; only `expires_at` and `updated_at` are
+    /// written, and a session already expiring later is left untouched.
+    async fn touch(&self, id: SessionId, expires_at: Timestamp) -> AppResult<()>;
+
     /// Delete session by ID
     ///
-    /// Used for logout
+    /// Used for logout. In soft-delete mode the session is marked revoked
+    /// and kept, but no longer found.
     async fn delete(&self, id: SessionId) -> AppResult<()>;
 
     /// Delete all sessions for a user
     ///
-    /// Used when enforcing single session per user
+    /// Used to log a user out everywhere (soft-deleted like `delete`)
     async fn delete_by_user_id(&self, user_id: UserId) -> AppResult<()>;
 
+    /// Delete the live sessions of every user in a tenant
+    ///
+    /// Same users and batching as `TokenRepository::revoke_tenant_tokens`
+    /// (soft-deleted like `delete`). Returns the number of sessions deleted
+    async fn delete_by_tenant(&self, tenant_id: OrganizationId, batch_size: u32) -> AppResult<u64>;
+
     /// Delete all expired sessions
     ///
-    /// Cleanup job to remove old sessions
+    /// Cleanup job to remove old sessions. In soft-delete mode, expired and
+    /// revoked sessions are kept until the retention window has passed.
     /// Returns number of sessions deleted
     async fn delete_expired(&self) -> AppResult<u64>;
 }
 
 /// PostgreSQL implementation of SessionRepository
+///
+/// Reads stay on the primary: a revoked session must not look valid on a lagging replica.
 pub struct PostgresSessionRepository {
-    pool: PgPool,
+    db: DbPools,
+    /// Retention (seconds) of revoked/expired sessions; `None` hard-deletes
+    soft_delete_retention: Option<i64>,
+    /// Live sessions kept per user; `None` is unlimited
+    max_concurrent: Option<u32>,
 }
 
 impl PostgresSessionRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(db: DbPools) -> Self {
+        Self {
+            db,
+            soft_delete_retention: None,
+            max_concurrent: None,
+        }
+    }
+
+    /// Soft-delete sessions, keeping them for `retention_seconds`
+    pub fn with_soft_delete(mut self, retention_seconds: i64) -> Self {
+        self.soft_delete_retention = Some(retention_seconds);
+        self
+    }
+
+    /// Keep at most `limit` live sessions per user, evicting the oldest
+    pub fn with_max_concurrent(mut self, limit: Option<u32>) -> Self {
+        self.max_concurrent = limit;
+        self
+    }
+
+    /// Evict the user's live sessions beyond the newest `limit`
+    ///
+    /// The tokens minted under an evicted session (`sid` claim) are revoked
+    /// in the same statement, so an evicted device can't keep refreshing
+    async fn evict_beyond(&self, user_id: UserId, limit: u32) -> AppResult<()> {
+        let query = if self.soft_delete_retention.is_some() {
+            r#"
+            WITH evicted AS (
+                UPDATE sessions SET revoked_at = NOW()
+                WHERE id IN (
+                    SELECT id FROM sessions
+                    WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+                    ORDER BY created_at DESC, id DESC
+                    OFFSET $2
+                )
+                RETURNING id
+            ), revoked AS (
+                UPDATE jwt_tokens SET revoked = true, revoked_at = NOW()
+                WHERE session_id IN (SELECT id FROM evicted) AND revoked = false
+                RETURNING id
+            )
+            SELECT (SELECT COUNT(*) FROM evicted), (SELECT COUNT(*) FROM revoked)
+            "#
+        } else {
+            r#"
+            WITH evicted AS (
+                DELETE FROM sessions
+                WHERE id IN (
+                    SELECT id FROM sessions
+                    WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+                    ORDER BY created_at DESC, id DESC
+                    OFFSET $2
+                )
+                RETURNING id
+            ), revoked AS (
+                UPDATE jwt_tokens SET revoked = true, revoked_at = NOW()
+                WHERE session_id IN (SELECT id FROM evicted) AND revoked = false
+                RETURNING id
+            )
+            SELECT (SELECT COUNT(*) FROM evicted), (SELECT COUNT(*) FROM revoked)
+            "#
+        };
+
+        let (evicted, revoked): (i64, i64) = sqlx::query_as(query)
+            .bind(user_id)
+            .bind(limit as i64)
+            .fetch_one(self.db.writer())
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to evict sessions: {}", e)))?;
+
+        if evicted > 0 {
+            tracing::debug!(
+                "Evicted {} session(s) of user {} beyond the limit of {}, revoking {} token(s)",
+                evicted,
+                user_id,
+                limit,
+                revoked
+            );
+        }
+
+        Ok(())
     }
 }
 
 #[async_trait]
 impl SessionRepository for PostgresSessionRepository {
     async fn save(&self, session: &Session) -> AppResult<Session> {
-        // First, delete any existing sessions for this user (single session per user)
-        self.delete_by_user_id(session.user_id).await?;
-
         // Insert new session
         let result = sqlx::query_as::<_, Session>(
             r#"
-            INSERT INTO sessions (id, user_id, csrf_token, ip_address, user_agent, expires_at, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, user_id, csrf_token, ip_address, user_agent, expires_at, created_at, updated_at
+            INSERT INTO sessions (id, user_id, csrf_token, ip_address, user_agent, device_name, expires_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4::inet, $5, $6, $7, $8, $9)
+            RETURNING id, user_id, csrf_token, host(ip_address) AS ip_address, user_agent, device_name, expires_at, created_at, updated_at
             "#,
         )
         .bind(session.id)
@@ -73,26 +173,32 @@ impl SessionRepository for PostgresSessionRepository {
         .bind(session.csrf_token.as_str())
         .bind(&session.ip_address)
         .bind(&session.user_agent)
+        .bind(&session.device_name)
         .bind(session.expires_at)
         .bind(session.created_at)
         .bind(session.updated_at)
-        .fetch_one(&self.pool)
+        .fetch_one(self.db.writer())
         .await
         .map_err(|e| AppError::internal(format!("Failed to save session: {}", e)))?;
 
+        // Then make room: the new session counts towards the limit
+        if let Some(limit) = self.max_concurrent {
+            self.evict_beyond(session.user_id, limit).await?;
+        }
+
         Ok(result)
     }
 
     async fn find_by_id(&self, id: SessionId) -> AppResult<Option<Session>> {
         let result = sqlx::query_as::<_, Session>(
             r#"
-            SELECT id, user_id, csrf_token, ip_address, user_agent, expires_at, created_at, updated_at
+            SELECT id, user_id, csrf_token, host(ip_address) AS ip_address, user_agent, device_name, expires_at, created_at, updated_at
             FROM sessions
-            WHERE id = $1
+            WHERE id = $1 AND revoked_at IS NULL
             "#,
         )
         .bind(id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.db.primary())
         .await
         .map_err(|e| AppError::internal(format!("Failed to find session: {}", e)))?;
 
@@ -102,33 +208,67 @@ impl SessionRepository for PostgresSessionRepository {
     async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<Session>> {
         let result = sqlx::query_as::<_, Session>(
             r#"
-            SELECT id, user_id, csrf_token, ip_address, user_agent, expires_at, created_at, updated_at
+            SELECT id, user_id, csrf_token, host(ip_address) AS ip_address, user_agent, device_name, expires_at, created_at, updated_at
             FROM sessions
-            WHERE user_id = $1
+            WHERE user_id = $1 AND revoked_at IS NULL
             ORDER BY created_at DESC
             LIMIT 1
             "#,
         )
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.db.primary())
         .await
         .map_err(|e| AppError::internal(format!("Failed to find session: {}", e)))?;
 
         Ok(result)
     }
 
-    async fn delete(&self, id: SessionId) -> AppResult<()> {
-        let rows_affected = sqlx::query(
+    async fn find_all_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Session>> {
+        let result = sqlx::query_as::<_, Session>(
             r#"
-            DELETE FROM sessions
-            WHERE id = $1
+            SELECT id, user_id, csrf_token, host(ip_address) AS ip_address, user_agent, device_name, expires_at, created_at, updated_at
+            FROM sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            ORDER BY updated_at DESC, id DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.db.primary())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to list sessions: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn touch(&self, id: SessionId, expires_at: Timestamp) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE sessions SET expires_at = $2, updated_at = NOW()
+            WHERE id = $1 AND revoked_at IS NULL AND expires_at < $2
             "#,
         )
         .bind(id)
-        .execute(&self.pool)
+        .bind(expires_at)
+        .execute(self.db.writer())
         .await
-        .map_err(|e| AppError::internal(format!("Failed to delete session: {}", e)))?
-        .rows_affected();
+        .map_err(|e| AppError::internal(format!("Failed to touch session: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: SessionId) -> AppResult<()> {
+        let query = if self.soft_delete_retention.is_some() {
+            "UPDATE sessions SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL"
+        } else {
+            "DELETE FROM sessions WHERE id = $1"
+        };
+
+        let rows_affected = sqlx::query(query)
+            .bind(id)
+            .execute(self.db.writer())
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to delete session: {}", e)))?
+            .rows_affected();
 
         if rows_affected == 0 {
             // Not necessarily an error - session might already be deleted
@@ -139,28 +279,80 @@ impl SessionRepository for PostgresSessionRepository {
     }
 
     async fn delete_by_user_id(&self, user_id: UserId) -> AppResult<()> {
-        sqlx::query(
+        let query = if self.soft_delete_retention.is_some() {
+            "UPDATE sessions SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL"
+        } else {
+            "DELETE FROM sessions WHERE user_id = $1"
+        };
+
+        sqlx::query(query)
+            .bind(user_id)
+            .execute(self.db.writer())
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to delete sessions: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn delete_by_tenant(&self, tenant_id: OrganizationId, batch_size: u32) -> AppResult<u64> {
+        let query = if self.soft_delete_retention.is_some() {
+            r#"
+            UPDATE sessions SET revoked_at = NOW()
+            WHERE id IN (
+                SELECT id FROM sessions
+                WHERE revoked_at IS NULL AND expires_at > NOW() AND user_id IN (
+                    SELECT id FROM users WHERE tenant_id = $1
+                    UNION
+                    SELECT user_id FROM tenant_memberships WHERE organization_id = $1
+                )
+                LIMIT $2
+            )
+            "#
+        } else {
             r#"
             DELETE FROM sessions
-            WHERE user_id = $1
-            "#,
-        )
-        .bind(user_id)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| AppError::internal(format!("Failed to delete sessions: {}", e)))?;
+            WHERE id IN (
+                SELECT id FROM sessions
+                WHERE expires_at > NOW() AND user_id IN (
+                    SELECT id FROM users WHERE tenant_id = $1
+                    UNION
+                    SELECT user_id FROM tenant_memberships WHERE organization_id = $1
+                )
+                LIMIT $2
+            )
+            "#
+        };
+        let mut total = 0;
 
-        Ok(())
+        loop {
+            let rows_affected = sqlx::query(query)
+                .bind(tenant_id)
+                .bind(batch_size as i64)
+                .execute(self.db.writer())
+                .await
+                .map_err(|e| AppError::internal(format!("Failed to delete tenant sessions: {}", e)))?
+                .rows_affected();
+
+            total += rows_affected;
+            if rows_affected < u64::from(batch_size) {
+                return Ok(total);
+            }
+        }
     }
 
     async fn delete_expired(&self) -> AppResult<u64> {
+        // Revoked rows are only left over from soft-delete mode; without a
+        // retention they are purged right away
+        let retention = self.soft_delete_retention.unwrap_or(0);
+
         let rows_affected = sqlx::query(
             r#"
             DELETE FROM sessions
-            WHERE expires_at < NOW()
+            WHERE LEAST(expires_at, revoked_at) < NOW() - make_interval(secs => $1)
             "#,
         )
-        .execute(&self.pool)
+        .bind(retention as f64)
+        .execute(self.db.writer())
         .await
         .map_err(|e| AppError::internal(format!("Failed to delete expired sessions: {}", e)))?
         .rows_affected();
@@ -1,7 +1,6 @@
 use crate::moduls::auth::domain::JwtToken;
-use crate::shared::{types::*, AppError, AppResult};
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
 use async_trait::async_trait;
-use sqlx::PgPool;
 use uuid::Uuid;
 
 /// TokenRepository trait defining JWT token persistence operations
@@ -19,13 +18,15 @@ pub trait TokenRepository: Send + Sync {
     ///
     /// Returns None if token not found
     /// Used for revocation checking
-    async fn find_by_jti(&self, jti: Uuid) -> AppResult<Option<JwtToken>>;
+    async fn find_by_jti(&self, jti: TokenId) -> AppResult<Option<JwtToken>>;
 
     /// Revoke token by JTI
     ///
-    /// Sets revoked=true and revoked_at=NOW()
+    /// Sets revoked=true and revoked_at=NOW() if not revoked yet, in one
+    /// statement. Returns whether this call revoked it: of two concurrent
+    /// rotations of one refresh token, only one gets `true`
     /// Used for logout and token rotation
-    async fn revoke(&self, jti: Uuid) -> AppResult<()>;
+    async fn revoke(&self, jti: TokenId) -> AppResult<bool>;
 
     /// Revoke all tokens for a user
     ///
@@ -33,6 +34,25 @@ pub trait TokenRepository: Send + Sync {
     /// Sets revoked=true for all non-revoked tokens
     async fn revoke_all_user_tokens(&self, user_id: UserId) -> AppResult<()>;
 
+    /// Revoke every token descending from one login
+    ///
+    /// Used when a rotated refresh token is replayed: the chain is assumed
+    /// stolen. Returns the number of tokens revoked
+    async fn revoke_family(&self, family_id: Uuid) -> AppResult<u64>;
+
+    /// Revoke every token minted under a web session (`sid` claim)
+    ///
+    /// Used when the session is revoked. Returns the number of tokens revoked
+    async fn revoke_session_tokens(&self, session_id: SessionId) -> AppResult<u64>;
+
+    /// Revoke the live tokens of every user in a tenant
+    ///
+    /// Tenant users are those scoped to it and those holding a membership.
+    /// Updates at most `batch_size` rows per statement, so an incident
+    /// response on a large tenant doesn't hold one huge transaction.
+    /// Returns the number of tokens revoked
+    async fn revoke_tenant_tokens(&self, tenant_id: OrganizationId, batch_size: u32) -> AppResult<u64>;
+
     /// Delete all expired tokens
     ///
     /// Cleanup job to remove old tokens from database
@@ -41,13 +61,15 @@ pub trait TokenRepository: Send + Sync {
 }
 
 /// PostgreSQL implementation of TokenRepository
+///
+/// Reads stay on the primary: a revoked token must not look valid on a lagging replica.
 pub struct PostgresTokenRepository {
-    pool: PgPool,
+    db: DbPools,
 }
 
 impl PostgresTokenRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
     }
 }
 
@@ -56,43 +78,45 @@ impl TokenRepository for PostgresTokenRepository {
     async fn save(&self, token: &JwtToken) -> AppResult<JwtToken> {
         let result = sqlx::query_as::<_, JwtToken>(
             r#"
-            INSERT INTO jwt_tokens (id, user_id, token_type, jti, expires_at, revoked, revoked_at, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, user_id, token_type, jti, expires_at, revoked, revoked_at, created_at
+            INSERT INTO jwt_tokens (id, user_id, token_type, jti, family_id, session_id, expires_at, revoked, revoked_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, user_id, token_type, jti, family_id, session_id, expires_at, revoked, revoked_at, created_at
             "#,
         )
         .bind(token.id)
         .bind(token.user_id)
         .bind(token.token_type)
         .bind(token.jti)
+        .bind(token.family_id)
+        .bind(token.session_id)
         .bind(token.expires_at)
         .bind(token.revoked)
         .bind(token.revoked_at)
         .bind(token.created_at)
-        .fetch_one(&self.pool)
+        .fetch_one(self.db.writer())
         .await
         .map_err(|e| AppError::internal(format!("Failed to save token: {}", e)))?;
 
         Ok(result)
     }
 
-    async fn find_by_jti(&self, jti: Uuid) -> AppResult<Option<JwtToken>> {
+    async fn find_by_jti(&self, jti: TokenId) -> AppResult<Option<JwtToken>> {
         let result = sqlx::query_as::<_, JwtToken>(
             r#"
-            SELECT id, user_id, token_type, jti, expires_at, revoked, revoked_at, created_at
+            SELECT id, user_id, token_type, jti, family_id, session_id, expires_at, revoked, revoked_at, created_at
             FROM jwt_tokens
             WHERE jti = $1
             "#,
         )
         .bind(jti)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.db.primary())
         .await
         .map_err(|e| AppError::internal(format!("Failed to find token: {}", e)))?;
 
         Ok(result)
     }
 
-    async fn revoke(&self, jti: Uuid) -> AppResult<()> {
+    async fn revoke(&self, jti: TokenId) -> AppResult<bool> {
         let rows_affected = sqlx::query(
             r#"
             UPDATE jwt_tokens
@@ -101,7 +125,7 @@ impl TokenRepository for PostgresTokenRepository {
             "#,
         )
         .bind(jti)
-        .execute(&self.pool)
+        .execute(self.db.writer())
         .await
         .map_err(|e| AppError::internal(format!("Failed to revoke token: {}", e)))?
         .rows_affected();
@@ -110,7 +134,7 @@ impl TokenRepository for PostgresTokenRepository {
             tracing::warn!("Attempted to revoke non-existent or already revoked token: {}", jti);
         }
 
-        Ok(())
+        Ok(rows_affected > 0)
     }
 
     async fn revoke_all_user_tokens(&self, user_id: UserId) -> AppResult<()> {
@@ -122,13 +146,80 @@ impl TokenRepository for PostgresTokenRepository {
             "#,
         )
         .bind(user_id)
-        .execute(&self.pool)
+        .execute(self.db.writer())
         .await
         .map_err(|e| AppError::internal(format!("Failed to revoke user tokens: {}", e)))?;
 
         Ok(())
     }
 
+    async fn revoke_family(&self, family_id: Uuid) -> AppResult<u64> {
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE jwt_tokens
+            SET revoked = true, revoked_at = NOW()
+            WHERE family_id = $1 AND revoked = false
+            "#,
+        )
+        .bind(family_id)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to revoke token family: {}", e)))?
+        .rows_affected();
+
+        Ok(rows_affected)
+    }
+
+    async fn revoke_session_tokens(&self, session_id: SessionId) -> AppResult<u64> {
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE jwt_tokens
+            SET revoked = true, revoked_at = NOW()
+            WHERE session_id = $1 AND revoked = false
+            "#,
+        )
+        .bind(session_id)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to revoke session tokens: {}", e)))?
+        .rows_affected();
+
+        Ok(rows_affected)
+    }
+
+    async fn revoke_tenant_tokens(&self, tenant_id: OrganizationId, batch_size: u32) -> AppResult<u64> {
+        let mut total = 0;
+
+        loop {
+            let rows_affected = sqlx::query(
+                r#"
+                UPDATE jwt_tokens
+                SET revoked = true, revoked_at = NOW()
+                WHERE id IN (
+                    SELECT id FROM jwt_tokens
+                    WHERE revoked = false AND expires_at > NOW() AND user_id IN (
+                        SELECT id FROM users WHERE tenant_id = $1
+                        UNION
+                        SELECT user_id FROM tenant_memberships WHERE organization_id = $1
+                    )
+                    LIMIT $2
+                )
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(batch_size as i64)
+            .execute(self.db.writer())
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to revoke tenant tokens: {}", e)))?
+            .rows_affected();
+
+            total += rows_affected;
+            if rows_affected < u64::from(batch_size) {
+                return Ok(total);
+            }
+        }
+    }
+
     async fn delete_expired(&self) -> AppResult<u64> {
         let rows_affected = sqlx::query(
             r#"
@@ -136,7 +227,7 @@ impl TokenRepository for PostgresTokenRepository {
             WHERE expires_at < NOW()
             "#,
         )
-        .execute(&self.pool)
+        .execute(self.db.writer())
         .await
         .map_err(|e| AppError::internal(format!("Failed to delete expired tokens: {}", e)))?
         .rows_affected();
@@ -0,0 +1,64 @@
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// TokenWatermarkRepository trait for the global token watermark
+///
+/// The watermark is a single timestamp; tokens issued before it are
+/// treated as invalid regardless of their revocation status.
+#[async_trait]
+pub trait TokenWatermarkRepository: Send + Sync {
+    /// Get the stored watermark, if one has been set
+    async fn get(&self) -> AppResult<Option<Timestamp>>;
+
+    /// Store a new watermark, replacing the previous one
+    async fn set(&self, valid_after: Timestamp) -> AppResult<()>;
+}
+
+/// PostgreSQL implementation of TokenWatermarkRepository
+///
+/// Reads stay on the primary: a raised watermark must apply immediately.
+pub struct PostgresTokenWatermarkRepository {
+    db: DbPools,
+}
+
+impl PostgresTokenWatermarkRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TokenWatermarkRepository for PostgresTokenWatermarkRepository {
+    async fn get(&self) -> AppResult<Option<Timestamp>> {
+        let result = sqlx::query_scalar::<_, Timestamp>(
+            r#"
+            SELECT tokens_valid_after
+            FROM token_watermark
+            WHERE id
+            "#,
+        )
+        .fetch_optional(self.db.primary())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to load token watermark: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn set(&self, valid_after: Timestamp) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO token_watermark (id, tokens_valid_after, updated_at)
+            VALUES (TRUE, $1, $2)
+            ON CONFLICT (id) DO UPDATE
+            SET tokens_valid_after = EXCLUDED.tokens_valid_after, updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(valid_after)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to update token watermark: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use crate::moduls::auth::domain::UserTotp;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// TotpRepository trait defining TOTP enrollment persistence
+///
+/// Confirming an enrollment and accepting a code are conditional updates,
+/// so concurrent requests can't confirm twice or reuse a code.
+#[async_trait]
+pub trait TotpRepository: Send + Sync {
+    /// Find the enrollment of a user
+    ///
+    /// Returns None if the user never started TOTP setup
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<UserTotp>>;
+
+    /// Save a pending enrollment, replacing an earlier pending one
+    ///
+    /// Returns false (saving nothing) if the user's enrollment is confirmed
+    async fn save_pending(&self, totp: &UserTotp) -> AppResult<bool>;
+
+    /// Confirm a pending enrollment with the step of its first code
+    ///
+    /// Returns false if there is no pending enrollment
+    async fn confirm(&self, user_id: UserId, step: i64) -> AppResult<bool>;
+
+    /// Accept a code of a confirmed enrollment
+    ///
+    /// Returns false if a code of this or a later step was already accepted
+    async fn record_used_step(&self, user_id: UserId, step: i64) -> AppResult<bool>;
+}
+
+/// PostgreSQL implementation of TotpRepository
+///
+/// Reads stay on the primary: replay protection needs the latest `last_used_step`.
+pub struct PostgresTotpRepository {
+    db: DbPools,
+}
+
+impl PostgresTotpRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TotpRepository for PostgresTotpRepository {
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<UserTotp>> {
+        let result = sqlx::query_as::<_, UserTotp>(
+            r#"
+            SELECT user_id, secret, confirmed_at, last_used_step, created_at, updated_at
+            FROM user_totp
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find TOTP enrollment: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn save_pending(&self, totp: &UserTotp) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_totp (user_id, secret, confirmed_at, last_used_step, created_at, updated_at)
+            VALUES ($1, $2, NULL, NULL, $3, $4)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, created_at = EXCLUDED.created_at, updated_at = EXCLUDED.updated_at
+            WHERE user_totp.confirmed_at IS NULL
+            "#,
+        )
+        .bind(totp.user_id)
+        .bind(&totp.secret)
+        .bind(totp.created_at)
+        .bind(totp.updated_at)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to save TOTP enrollment: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn confirm(&self, user_id: UserId, step: i64) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_totp
+            SET confirmed_at = $2, last_used_step = $3, updated_at = $2
+            WHERE user_id = $1 AND confirmed_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(now())
+        .bind(step)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to confirm TOTP enrollment: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn record_used_step(&self, user_id: UserId, step: i64) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_totp
+            SET last_used_step = $2, updated_at = $3
+            WHERE user_id = $1
+              AND confirmed_at IS NOT NULL
+              AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(step)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to record TOTP code use: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
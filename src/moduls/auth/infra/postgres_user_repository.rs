@@ -1,7 +1,18 @@
 use crate::moduls::auth::domain::{User, Email};
-use crate::shared::{types::*, AppError, AppResult};
+use crate::shared::pagination::{contains_pattern, Page, PageRequest};
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
 use async_trait::async_trait;
-use sqlx::PgPool;
+
+/// Columns selected into `User`
+const USER_COLUMNS: &str =
+    "id, tenant_id, email, password_hash, name, email_verified, is_active, two_factor_enabled, tokens_valid_after, client_hash_salt, client_hash_iterations, recovery_email, recovery_email_verified, notification_preferences, must_change_password, deleted_at, locked_until, deletion_scheduled_at, expires_at, created_at, updated_at";
+
+/// Filters of the admin users listing; `None` matches every user
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    /// Case-insensitive substring of the email or name
+    pub search: Option<String>,
+}
 
 /// UserRepository trait defining user persistence operations
 ///
@@ -21,11 +32,20 @@ pub trait UserRepository: Send + Sync {
     /// Returns None if user not found
     async fn find_by_id(&self, id: UserId) -> AppResult<Option<User>>;
 
-    /// Find user by email
+    /// Find a global (tenant-less) user by email
     ///
-    /// Returns None if user not found
+    /// Returns None if user not found; tenant users are never returned
     async fn find_by_email(&self, email: &Email) -> AppResult<Option<User>>;
 
+    /// Find a user scoped to `organization_id` by email
+    ///
+    /// Returns None if the tenant has no user with that email
+    async fn find_by_email_in_tenant(
+        &self,
+        email: &Email,
+        organization_id: OrganizationId,
+    ) -> AppResult<Option<User>>;
+
     /// Update existing user
     ///
     /// # Errors
@@ -39,38 +59,63 @@ pub trait UserRepository: Send + Sync {
     /// - NotFound if user doesn't exist
     /// - Database errors
     async fn delete(&self, id: UserId) -> AppResult<()>;
+
+    /// One page of the users matching `filter`, oldest first, with the
+    /// total number of matches
+    async fn list_paginated(&self, filter: &UserFilter, page: PageRequest) -> AppResult<Page<User>>;
+
+    /// Up to `limit` undeleted users whose scheduled deletion is due,
+    /// longest overdue first
+    async fn find_due_for_deletion(&self, limit: u32) -> AppResult<Vec<User>>;
 }
 
 /// PostgreSQL implementation of UserRepository
+///
+/// Lookups stay on the primary: login, token validation and revocation
+/// checks must not see a stale password, status or watermark on a lagging
+/// replica. Only the admin listing reads from the replica.
 pub struct PostgresUserRepository {
-    pool: PgPool,
+    db: DbPools,
 }
 
 impl PostgresUserRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
     }
 }
 
 #[async_trait]
 impl UserRepository for PostgresUserRepository {
     async fn save(&self, user: &User) -> AppResult<User> {
-        let result = sqlx::query_as::<_, User>(
+        let result = sqlx::query_as::<_, User>(&format!(
             r#"
-            INSERT INTO users (id, email, password_hash, name, email_verified, is_active, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, email, password_hash, name, email_verified, is_active, created_at, updated_at
+            INSERT INTO users ({USER_COLUMNS})
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+            RETURNING {USER_COLUMNS}
             "#,
-        )
+        ))
         .bind(user.id)
+        .bind(user.tenant_id)
         .bind(user.email.as_str())
         .bind(user.password_hash.as_str())
         .bind(&user.name)
         .bind(user.email_verified)
         .bind(user.is_active)
+        .bind(user.two_factor_enabled)
+        .bind(user.tokens_valid_after)
+        .bind(&user.client_hash_salt)
+        .bind(user.client_hash_iterations)
+        .bind(&user.recovery_email)
+        .bind(user.recovery_email_verified)
+        .bind(sqlx::types::Json(user.notification_preferences))
+        .bind(user.must_change_password)
+        .bind(user.deleted_at)
+        .bind(user.locked_until)
+        .bind(user.deletion_scheduled_at)
+        .bind(user.expires_at)
         .bind(user.created_at)
         .bind(user.updated_at)
-        .fetch_one(&self.pool)
+        .fetch_one(self.db.writer())
         .await
         .map_err(|e| {
             // Check for unique constraint violation (email already exists)
@@ -86,15 +131,15 @@ impl UserRepository for PostgresUserRepository {
     }
 
     async fn find_by_id(&self, id: UserId) -> AppResult<Option<User>> {
-        let result = sqlx::query_as::<_, User>(
+        let result = sqlx::query_as::<_, User>(&format!(
             r#"
-            SELECT id, email, password_hash, name, email_verified, is_active, created_at, updated_at
+            SELECT {USER_COLUMNS}
             FROM users
             WHERE id = $1
             "#,
-        )
+        ))
         .bind(id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.db.primary())
         .await
         .map_err(|e| AppError::internal(format!("Failed to find user: {}", e)))?;
 
@@ -102,15 +147,36 @@ impl UserRepository for PostgresUserRepository {
     }
 
     async fn find_by_email(&self, email: &Email) -> AppResult<Option<User>> {
-        let result = sqlx::query_as::<_, User>(
+        let result = sqlx::query_as::<_, User>(&format!(
             r#"
-            SELECT id, email, password_hash, name, email_verified, is_active, created_at, updated_at
+            SELECT {USER_COLUMNS}
             FROM users
-            WHERE email = $1
+            WHERE email = $1 AND tenant_id IS NULL
             "#,
-        )
+        ))
+        .bind(email.as_str())
+        .fetch_optional(self.db.primary())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find user: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn find_by_email_in_tenant(
+        &self,
+        email: &Email,
+        organization_id: OrganizationId,
+    ) -> AppResult<Option<User>> {
+        let result = sqlx::query_as::<_, User>(&format!(
+            r#"
+            SELECT {USER_COLUMNS}
+            FROM users
+            WHERE email = $1 AND tenant_id = $2
+            "#,
+        ))
         .bind(email.as_str())
-        .fetch_optional(&self.pool)
+        .bind(organization_id)
+        .fetch_optional(self.db.primary())
         .await
         .map_err(|e| AppError::internal(format!("Failed to find user: {}", e)))?;
 
@@ -118,22 +184,38 @@ impl UserRepository for PostgresUserRepository {
     }
 
     async fn update(&self, user: &User) -> AppResult<User> {
-        let result = sqlx::query_as::<_, User>(
+        let result = sqlx::query_as::<_, User>(&format!(
             r#"
             UPDATE users
-            SET email = $2, password_hash = $3, name = $4, email_verified = $5, is_active = $6, updated_at = $7
+            SET email = $2, password_hash = $3, name = $4, email_verified = $5, is_active = $6,
+                two_factor_enabled = $7, tokens_valid_after = $8, client_hash_salt = $9,
+                client_hash_iterations = $10, recovery_email = $11, recovery_email_verified = $12,
+                notification_preferences = $13, must_change_password = $14, deleted_at = $15,
+                locked_until = $16, deletion_scheduled_at = $17, expires_at = $18, updated_at = $19
             WHERE id = $1
-            RETURNING id, email, password_hash, name, email_verified, is_active, created_at, updated_at
+            RETURNING {USER_COLUMNS}
             "#,
-        )
+        ))
         .bind(user.id)
         .bind(user.email.as_str())
         .bind(user.password_hash.as_str())
         .bind(&user.name)
         .bind(user.email_verified)
         .bind(user.is_active)
+        .bind(user.two_factor_enabled)
+        .bind(user.tokens_valid_after)
+        .bind(&user.client_hash_salt)
+        .bind(user.client_hash_iterations)
+        .bind(&user.recovery_email)
+        .bind(user.recovery_email_verified)
+        .bind(sqlx::types::Json(user.notification_preferences))
+        .bind(user.must_change_password)
+        .bind(user.deleted_at)
+        .bind(user.locked_until)
+        .bind(user.deletion_scheduled_at)
+        .bind(user.expires_at)
         .bind(user.updated_at)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.db.writer())
         .await
         .map_err(|e| AppError::internal(format!("Failed to update user: {}", e)))?
         .ok_or_else(|| AppError::not_found("User not found"))?;
@@ -149,7 +231,7 @@ impl UserRepository for PostgresUserRepository {
             "#,
         )
         .bind(id)
-        .execute(&self.pool)
+        .execute(self.db.writer())
         .await
         .map_err(|e| AppError::internal(format!("Failed to delete user: {}", e)))?
         .rows_affected();
@@ -160,6 +242,58 @@ impl UserRepository for PostgresUserRepository {
 
         Ok(())
     }
+
+    async fn list_paginated(&self, filter: &UserFilter, page: PageRequest) -> AppResult<Page<User>> {
+        let pattern = filter.search.as_deref().map(contains_pattern);
+
+        let total = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM users
+            WHERE ($1::text IS NULL OR email ILIKE $1 OR name ILIKE $1)
+            "#,
+        )
+        .bind(&pattern)
+        .fetch_one(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to count users: {}", e)))?;
+
+        let users = sqlx::query_as::<_, User>(&format!(
+            r#"
+            SELECT {USER_COLUMNS}
+            FROM users
+            WHERE ($1::text IS NULL OR email ILIKE $1 OR name ILIKE $1)
+            ORDER BY created_at, id
+            LIMIT $2 OFFSET $3
+            "#,
+        ))
+        .bind(&pattern)
+        .bind(page.limit())
+        .bind(page.offset())
+        .fetch_all(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to list users: {}", e)))?;
+
+        Ok(Page::new(users, page, total as u64))
+    }
+
+    async fn find_due_for_deletion(&self, limit: u32) -> AppResult<Vec<User>> {
+        let users = sqlx::query_as::<_, User>(&format!(
+            r#"
+            SELECT {USER_COLUMNS}
+            FROM users
+            WHERE deletion_scheduled_at <= NOW() AND deleted_at IS NULL
+            ORDER BY deletion_scheduled_at, id
+            LIMIT $1
+            "#,
+        ))
+        .bind(i64::from(limit))
+        .fetch_all(self.db.primary())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find users due for deletion: {}", e)))?;
+
+        Ok(users)
+    }
 }
 
 #[cfg(test)]
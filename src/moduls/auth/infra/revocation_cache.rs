@@ -0,0 +1,263 @@
+use super::TokenRepository;
+use crate::moduls::auth::domain::JwtToken;
+use crate::shared::{types::*, AppResult};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Most tokens kept; beyond it new lookups go uncached until entries age out
+const MAX_ENTRIES: usize = 100_000;
+
+/// Recently looked-up, non-revoked tokens, trusted for `ttl`
+///
+/// Only revocations made through this process evict entries right away;
+/// one made by another instance goes unnoticed for at most `ttl`.
+pub struct RevocationCache {
+    ttl: Duration,
+    entries: RwLock<HashMap<TokenId, (JwtToken, Instant)>>,
+}
+
+impl RevocationCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// The cached token, unless its entry is older than the TTL
+    pub fn get(&self, jti: TokenId) -> Option<JwtToken> {
+        let entries = self.entries.read().unwrap();
+        entries
+            .get(&jti)
+            .filter(|(_, cached_at)| cached_at.elapsed() < self.ttl)
+            .map(|(token, _)| token.clone())
+    }
+
+    /// Remember a token as not revoked (revoked tokens aren't cached)
+    pub fn insert(&self, token: &JwtToken) {
+        if token.is_revoked() {
+            return;
+        }
+
+        let mut entries = self.entries.write().unwrap();
+        if entries.len() >= MAX_ENTRIES {
+            entries.retain(|_, (_, cached_at)| cached_at.elapsed() < self.ttl);
+            if entries.len() >= MAX_ENTRIES {
+                return;
+            }
+        }
+        entries.insert(token.jti, (token.clone(), Instant::now()));
+    }
+
+    /// Forget the tokens matching `revoked`
+    pub fn evict(&self, revoked: impl Fn(&JwtToken) -> bool) {
+        self.entries.write().unwrap().retain(|_, (token, _)| !revoked(token));
+    }
+
+    /// Forget every token
+    pub fn clear(&self) {
+        self.entries.write().unwrap().clear();
+    }
+}
+
+/// TokenRepository answering `find_by_jti` from a `RevocationCache`
+///
+/// Spares the JWT middleware a query per request (`REVOCATION_CACHE`).
+/// Every revocation evicts the affected tokens before returning.
+pub struct CachedTokenRepository {
+    inner: Arc<dyn TokenRepository>,
+    cache: RevocationCache,
+}
+
+impl CachedTokenRepository {
+    pub fn new(inner: Arc<dyn TokenRepository>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            cache: RevocationCache::new(ttl),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenRepository for CachedTokenRepository {
+    async fn save(&self, token: &JwtToken) -> AppResult<JwtToken> {
+        self.inner.save(token).await
+    }
+
+    async fn find_by_jti(&self, jti: TokenId) -> AppResult<Option<JwtToken>> {
+        if let Some(token) = self.cache.get(jti) {
+            return Ok(Some(token));
+        }
+
+        let token = self.inner.find_by_jti(jti).await?;
+        if let Some(token) = &token {
+            self.cache.insert(token);
+        }
+        Ok(token)
+    }
+
+    async fn revoke(&self, jti: TokenId) -> AppResult<bool> {
+        let revoked = self.inner.revoke(jti).await?;
+        self.cache.evict(|t| t.jti == jti);
+        Ok(revoked)
+    }
+
+    async fn revoke_all_user_tokens(&self, user_id: UserId) -> AppResult<()> {
+        self.inner.revoke_all_user_tokens(user_id).await?;
+        self.cache.evict(|t| t.user_id == user_id);
+        Ok(())
+    }
+
+    async fn revoke_family(&self, family_id: Uuid) -> AppResult<u64> {
+        let revoked = self.inner.revoke_family(family_id).await?;
+        self.cache.evict(|t| t.family_id == family_id);
+        Ok(revoked)
+    }
+
+    async fn revoke_session_tokens(&self, session_id: SessionId) -> AppResult<u64> {
+        let revoked = self.inner.revoke_session_tokens(session_id).await?;
+        self.cache.evict(|t| t.session_id == Some(session_id));
+        Ok(revoked)
+    }
+
+    async fn revoke_tenant_tokens(&self, tenant_id: OrganizationId, batch_size: u32) -> AppResult<u64> {
+        // Tenant users aren't known here; start over
+        let revoked = self.inner.revoke_tenant_tokens(tenant_id, batch_size).await?;
+        self.cache.clear();
+        Ok(revoked)
+    }
+
+    async fn delete_expired(&self) -> AppResult<u64> {
+        let deleted = self.inner.delete_expired().await?;
+        self.cache.evict(|t| t.is_expired());
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{JwtKeys, TokenPair};
+    use crate::moduls::auth::infra::in_memory::InMemoryTokenRepository;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the lookups reaching the database
+    #[derive(Default)]
+    struct CountingTokenRepository {
+        inner: InMemoryTokenRepository,
+        lookups: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TokenRepository for CountingTokenRepository {
+        async fn save(&self, token: &JwtToken) -> AppResult<JwtToken> {
+            self.inner.save(token).await
+        }
+
+        async fn find_by_jti(&self, jti: TokenId) -> AppResult<Option<JwtToken>> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            self.inner.find_by_jti(jti).await
+        }
+
+        async fn revoke(&self, jti: TokenId) -> AppResult<bool> {
+            self.inner.revoke(jti).await
+        }
+
+        async fn revoke_all_user_tokens(&self, user_id: UserId) -> AppResult<()> {
+            self.inner.revoke_all_user_tokens(user_id).await
+        }
+
+        async fn revoke_family(&self, family_id: Uuid) -> AppResult<u64> {
+            self.inner.revoke_family(family_id).await
+        }
+
+        async fn revoke_session_tokens(&self, session_id: SessionId) -> AppResult<u64> {
+            self.inner.revoke_session_tokens(session_id).await
+        }
+
+        async fn revoke_tenant_tokens(&self, tenant_id: OrganizationId, batch_size: u32) -> AppResult<u64> {
+            self.inner.revoke_tenant_tokens(tenant_id, batch_size).await
+        }
+
+        async fn delete_expired(&self) -> AppResult<u64> {
+            self.inner.delete_expired().await
+        }
+    }
+
+    impl CountingTokenRepository {
+        fn lookups(&self) -> usize {
+            self.lookups.load(Ordering::SeqCst)
+        }
+    }
+
+    async fn fixture(ttl: Duration) -> (CachedTokenRepository, Arc<CountingTokenRepository>, JwtToken) {
+        let db = Arc::new(CountingTokenRepository::default());
+        let keys = JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars");
+        let (_, access, _) = TokenPair::generate(UserId::new(), &keys, 900, 3600).unwrap();
+        db.save(&access).await.unwrap();
+
+        (CachedTokenRepository::new(db.clone(), ttl), db, access)
+    }
+
+    #[tokio::test]
+    async fn test_second_lookup_is_served_from_cache() {
+        let (repo, db, token) = fixture(Duration::from_secs(60)).await;
+
+        for _ in 0..100 {
+            let found = repo.find_by_jti(token.jti).await.unwrap().unwrap();
+            assert!(!found.is_revoked());
+        }
+
+        assert_eq!(db.lookups(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_evicts_token() {
+        let (repo, db, token) = fixture(Duration::from_secs(60)).await;
+        repo.find_by_jti(token.jti).await.unwrap();
+
+        repo.revoke(token.jti).await.unwrap();
+
+        assert!(repo.find_by_jti(token.jti).await.unwrap().unwrap().is_revoked());
+        assert_eq!(db.lookups(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_user_tokens_evicts_them() {
+        let (repo, _, token) = fixture(Duration::from_secs(60)).await;
+        repo.find_by_jti(token.jti).await.unwrap();
+
+        repo.revoke_all_user_tokens(token.user_id).await.unwrap();
+
+        assert!(repo.find_by_jti(token.jti).await.unwrap().unwrap().is_revoked());
+    }
+
+    #[tokio::test]
+    async fn test_revocation_elsewhere_is_seen_after_ttl() {
+        let (repo, db, token) = fixture(Duration::from_millis(50)).await;
+        repo.find_by_jti(token.jti).await.unwrap();
+
+        // Another instance revokes the token, bypassing this cache
+        db.revoke(token.jti).await.unwrap();
+        assert!(!repo.find_by_jti(token.jti).await.unwrap().unwrap().is_revoked());
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(repo.find_by_jti(token.jti).await.unwrap().unwrap().is_revoked());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_and_revoked_tokens_are_not_cached() {
+        let (repo, db, token) = fixture(Duration::from_secs(60)).await;
+        db.revoke(token.jti).await.unwrap();
+
+        repo.find_by_jti(token.jti).await.unwrap();
+        repo.find_by_jti(token.jti).await.unwrap();
+        repo.find_by_jti(TokenId::new()).await.unwrap();
+        repo.find_by_jti(TokenId::new()).await.unwrap();
+
+        assert_eq!(db.lookups(), 4);
+    }
+}
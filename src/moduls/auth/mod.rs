@@ -1,11 +1,11 @@
-/// Authentication module
-///
-/// This module implements user authentication following DDD and Clean Architecture:
-/// - Domain: Business entities and rules (User, Session, TokenPair, value objects)
-/// - Application: Use cases (register, login, logout, refresh)
-/// - Infrastructure: Repositories (PostgreSQL implementations)
-/// - Web: Inertia.js handlers with session-based auth
-/// - API: JSON handlers with JWT-based auth
+//! Authentication module
+//!
+//! This module implements user authentication following DDD and Clean Architecture:
+//! - Domain: Business entities and rules (User, Session, TokenPair, value objects)
+//! - Application: Use cases (register, login, logout, refresh)
+//! - Infrastructure: Repositories (PostgreSQL implementations)
+//! - Web: Inertia.js handlers with session-based auth
+//! - API: JSON handlers with JWT-based auth
 
 pub mod domain;
 pub mod application;
@@ -15,4 +15,6 @@ pub mod api;
 
 // Re-export routes for easy mounting
 pub use web::auth_web_routes;
-pub use api::auth_api_routes;
+pub use api::{admin_api_routes, auth_api_routes};
+#[cfg(feature = "dev-tools")]
+pub use api::dev_api_routes;
@@ -1,10 +1,18 @@
 use crate::bootstrap::AppState;
-use crate::moduls::auth::application::{RegisterUserCommand, LoginWebCommand};
+use crate::moduls::auth::api::handlers::MfaChallengeResponse;
+use crate::moduls::auth::application::{
+    LoginWebCommand, RegisterUserCommand, VerifyWebMfaCommand, WebLoginOutcome, WebLoginResult,
+};
+use crate::moduls::organization::api::TenantContext;
+use crate::shared::cookies::{build_session_cookie, clear_session_cookie, read_cookie, SESSION_COOKIE_NAME};
+use crate::shared::types::SessionId;
+use crate::shared::ValidatedJson;
 use crate::shared::AppError;
 use axum::{
     extract::State,
-    http::StatusCode,
-    Json,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
 use serde::Deserialize;
 
@@ -13,6 +21,12 @@ use serde::Deserialize;
 pub struct LoginForm {
     pub email: String,
     pub password: String,
+    /// Optional label for the new session (e.g. "John's iPhone")
+    #[serde(default)]
+    pub device_name: Option<String>,
+    /// Keep the session (and its cookie) for `SESSION_REMEMBER_EXPIRY`
+    #[serde(default)]
+    pub remember_me: bool,
 }
 
 /// Form data for web registration
@@ -33,23 +47,70 @@ pub async fn show_login() -> Result<String, AppError> {
 
 /// POST /web/auth/login
 /// Process login form
+///
+/// Sets the session cookie: with `remember_me` it lasts as long as the
+/// session, otherwise it is a browser-session cookie dropped on close.
+/// Users with 2FA enabled get 202 with an MFA challenge instead, to
+/// complete at `/web/auth/mfa/verify`.
 pub async fn handle_login(
     State(state): State<AppState>,
+    tenant: Option<Extension<TenantContext>>,
     Json(form): Json<LoginForm>,
-) -> Result<StatusCode, AppError> {
+) -> Result<Response, AppError> {
+    let remember_me = form.remember_me;
     let cmd = LoginWebCommand {
         email: form.email,
         password: form.password,
         ip_address: None, // TODO: Extract from request
         user_agent: None,  // TODO: Extract from headers
+        device_name: form.device_name,
+        remember_me,
+        tenant_id: tenant.map(|Extension(t)| t.organization_id),
     };
 
-    let _result = state.login_user_use_case.login_web(cmd).await?;
+    match state.login_user_use_case.login_web(cmd).await? {
+        WebLoginOutcome::LoggedIn(result) => Ok(session_response(&state, &result, remember_me)),
+        WebLoginOutcome::MfaRequired { mfa_token, expires_in } => {
+            let response = MfaChallengeResponse {
+                message: "Two-factor authentication required, submit a code to /web/auth/mfa/verify"
+                    .to_string(),
+                mfa_required: true,
+                mfa_token,
+                expires_in,
+            };
+
+            Ok((StatusCode::ACCEPTED, Json(response)).into_response())
+        }
+    }
+}
+
+/// POST /web/auth/mfa/verify
+/// Complete a login that answered `mfa_required` with a TOTP code
+///
+/// Sets the session cookie like a login without 2FA.
+pub async fn handle_verify_mfa(
+    State(state): State<AppState>,
+    ValidatedJson(cmd): ValidatedJson<VerifyWebMfaCommand>,
+) -> Result<Response, AppError> {
+    let remember_me = cmd.remember_me;
+    let result = state.login_user_use_case.verify_mfa_web(cmd).await?;
+
+    Ok(session_response(&state, &result, remember_me))
+}
+
+/// 200 with the cookie of the new session
+fn session_response(state: &AppState, result: &WebLoginResult, remember_me: bool) -> Response {
+    let session_config = &state.config.session;
+    let cookie = build_session_cookie(
+        result.session.id,
+        session_config.cookie_secure,
+        session_config.cookie_same_site,
+        remember_me.then(|| result.session.ttl().num_seconds()),
+    );
 
-    // TODO: Set session cookie
     // TODO: Redirect to dashboard
 
-    Ok(StatusCode::OK)
+    (StatusCode::OK, set_cookie(&cookie)).into_response()
 }
 
 /// GET /web/auth/register
@@ -63,12 +124,15 @@ pub async fn show_register() -> Result<String, AppError> {
 /// Process registration form
 pub async fn handle_register(
     State(state): State<AppState>,
+    tenant: Option<Extension<TenantContext>>,
     Json(form): Json<RegisterForm>,
 ) -> Result<StatusCode, AppError> {
     let cmd = RegisterUserCommand {
         email: form.email,
         password: form.password,
+        client_hash: None,
         name: form.name,
+        tenant_id: tenant.map(|Extension(t)| t.organization_id),
     };
 
     let _user = state.register_user_use_case.execute(cmd).await?;
@@ -81,15 +145,67 @@ pub async fn handle_register(
 
 /// POST /web/auth/logout
 /// Logout user (delete session)
+///
+/// Clears the session cookie, whether or not it named a live session.
 pub async fn handle_logout(
-    State(_state): State<AppState>,
-    // TODO: Extract session from middleware
-) -> Result<StatusCode, AppError> {
-    // TODO: Get session_id from authenticated session
-    // state.logout_user_use_case.logout_web(session_id).await?;
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, HeaderMap), AppError> {
+    let session_id = read_cookie(&headers, SESSION_COOKIE_NAME)
+        .and_then(|id| id.parse::<SessionId>().ok());
+    if let Some(session_id) = session_id {
+        state.logout_user_use_case.logout_web(session_id).await?;
+    }
+
+    let session_config = &state.config.session;
+    let cookie = clear_session_cookie(session_config.cookie_secure, session_config.cookie_same_site);
 
-    // TODO: Clear session cookie
     // TODO: Redirect to login
 
-    Ok(StatusCode::OK)
+    Ok((StatusCode::OK, set_cookie(&cookie)))
+}
+
+/// Headers carrying a `Set-Cookie` for `cookie`
+fn set_cookie(cookie: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    match HeaderValue::from_str(cookie) {
+        Ok(value) => {
+            headers.append(header::SET_COOKIE, value);
+        }
+        Err(e) => tracing::error!("Failed to build session cookie: {}", e),
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::web::auth_web_routes;
+    use crate::shared::cookies::SameSite;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_logout_clears_session_cookie() {
+        let mut state = AppState::for_tests();
+        state.config.session.cookie_secure = true;
+        state.config.session.cookie_same_site = SameSite::Strict;
+        let app = auth_web_routes().with_state(state);
+        let request = Request::builder()
+            .method("POST")
+            .uri("/logout")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        assert!(cookie.starts_with("session_id=;"));
+        assert!(cookie.contains("; Max-Age=0"));
+        assert!(cookie.contains("; HttpOnly"));
+        assert!(cookie.contains("; SameSite=Strict"));
+        assert!(cookie.ends_with("; Secure"));
+    }
 }
@@ -1,11 +1,419 @@
 // Session and CSRF middleware for web routes
-//
-// TODO: Implement session middleware
-// - Extract session_id cookie
-// - Load session from database
-// - Check not expired
-// - Add session to request extensions
-//
-// TODO: Implement CSRF middleware
-// - Generate CSRF token on GET requests
-// - Validate CSRF token on POST requests
+
+use crate::bootstrap::AppState;
+use crate::config::CsrfConfig;
+use crate::moduls::auth::domain::{value_objects::CsrfToken, Session};
+use crate::moduls::auth::infra::SessionRepository;
+use crate::shared::cookies::{read_cookie, SESSION_COOKIE_NAME};
+use crate::shared::types::{SessionId, Timestamp, UserId};
+use crate::shared::{AppError, AppResult};
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, header::HeaderName, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+/// Where unauthenticated web requests are sent
+const LOGIN_PATH: &str = "/web/auth/login";
+
+/// Header carrying the CSRF token (request and response)
+pub const CSRF_HEADER: HeaderName = HeaderName::from_static("x-csrf-token");
+
+/// Largest form body buffered while looking for the CSRF field (2 MB)
+const MAX_CSRF_FORM_BYTES: usize = 2 * 1024 * 1024;
+
+/// Authenticated web session extension
+/// Added to request extensions after a valid session cookie was found
+#[derive(Clone, Debug)]
+pub struct AuthenticatedSession {
+    pub user_id: UserId,
+    pub session_id: SessionId,
+    /// When the user logged in (session creation)
+    pub authenticated_at: Timestamp,
+}
+
+/// Session authentication middleware
+///
+/// # Flow
+/// 1. Extract `session_id` cookie
+/// 2. Load session from database
+/// 3. Check session not expired
+/// 4. With `SESSION_SLIDING`, extend the session past half its TTL
+/// 5. Add AuthenticatedSession (and the Session itself) to request extensions
+/// 6. Redirect (302) to the login page if any step fails
+pub async fn session_auth_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match load_session(&state, request.headers()).await {
+        Ok(Some(mut session)) => {
+            if state.config.session.sliding {
+                extend_session(&state, &mut session).await;
+            }
+
+            request.extensions_mut().insert(AuthenticatedSession {
+                user_id: session.user_id,
+                session_id: session.id,
+                authenticated_at: session.created_at,
+            });
+            request.extensions_mut().insert(session);
+            next.run(request).await
+        }
+        Ok(None) => redirect_to_login(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Load the active session referenced by the request's cookie
+///
+/// Returns `None` for a missing, malformed, unknown, or expired session.
+async fn load_session(state: &AppState, headers: &HeaderMap) -> AppResult<Option<Session>> {
+    let Some(session_id) = read_cookie(headers, SESSION_COOKIE_NAME)
+        .and_then(|id| id.parse::<SessionId>().ok())
+    else {
+        return Ok(None);
+    };
+
+    let Some(session) = state.session_repo.find_by_id(session_id).await? else {
+        return Ok(None);
+    };
+
+    if session.is_expired() {
+        tracing::debug!("Rejecting expired session {}", session.id);
+        return Ok(None);
+    }
+
+    Ok(Some(session))
+}
+
+/// Record activity on `session`, moving its expiration forward when due
+///
+/// A failed write is logged and the request served anyway: the session is
+/// still valid, just not extended.
+async fn extend_session(state: &AppState, session: &mut Session) {
+    if !session.needs_refresh(state.config.session.sliding_interval as i64) {
+        return;
+    }
+
+    session.refresh(session.ttl().num_seconds());
+    if let Err(e) = state.session_repo.touch(session.id, session.expires_at).await {
+        tracing::warn!("Failed to extend session {}: {}", session.id, e);
+    }
+}
+
+/// 302 Found to the login page
+fn redirect_to_login() -> Response {
+    (StatusCode::FOUND, [(header::LOCATION, LOGIN_PATH)]).into_response()
+}
+
+/// CSRF protection middleware
+///
+/// Must run after `session_auth_middleware`, which provides the `Session`.
+///
+/// # Flow
+/// - Safe methods (GET/HEAD/OPTIONS): expose a token to the handler as an
+///   `Extension<CsrfToken>` and in the `X-CSRF-Token` response header so
+///   forms can embed it
+/// - State-changing methods (POST/PUT/PATCH/DELETE): read the token from
+///   the `X-CSRF-Token` header or the `_csrf` form field and check it; a
+///   missing or wrong token is rejected (403: the session is valid, the
+///   request isn't allowed)
+///
+/// The token is the session's stored one, or with `CSRF_STATELESS` an
+/// HMAC-signed token bound to the session ID (`CsrfToken::generate_signed`)
+/// that expires after `CSRF_TOKEN_TTL` seconds.
+pub async fn csrf_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(session) = request.extensions().get::<Session>().cloned() else {
+        tracing::warn!("csrf_middleware ran without a session; is session_auth_middleware missing?");
+        return Err(AppError::authentication("Unauthorized - no valid session"));
+    };
+    let config = &state.config.csrf;
+
+    let is_state_changing = matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+
+    if !is_state_changing {
+        let token = issue_csrf_token(&session, config);
+        let mut request = request;
+        request.extensions_mut().insert(token.clone());
+
+        let mut response = next.run(request).await;
+        if let Ok(value) = HeaderValue::from_str(token.as_str()) {
+            response.headers_mut().insert(CSRF_HEADER, value);
+        }
+        return Ok(response);
+    }
+
+    let (token, request) = extract_csrf_token(request).await?;
+    match token {
+        Some(token) if verify_csrf_token(&session, config, &token) => Ok(next.run(request).await),
+        _ => {
+            tracing::debug!("CSRF validation failed for session {}", session.id);
+            Err(csrf_failed())
+        }
+    }
+}
+
+/// Token for forms of `session`
+fn issue_csrf_token(session: &Session, config: &CsrfConfig) -> CsrfToken {
+    if config.stateless {
+        CsrfToken::generate_signed(session.id, &config.secret)
+    } else {
+        session.csrf_token.clone()
+    }
+}
+
+/// Whether `token` was issued for `session` (and, if signed, is still fresh)
+fn verify_csrf_token(session: &Session, config: &CsrfConfig, token: &str) -> bool {
+    if config.stateless {
+        CsrfToken::verify_signed(token, session.id, &config.secret, config.signed_token_ttl as i64)
+    } else {
+        session.verify_csrf(token)
+    }
+}
+
+/// `_csrf` field of a urlencoded form (other fields are ignored)
+#[derive(Deserialize)]
+struct CsrfFormField {
+    #[serde(rename = "_csrf")]
+    csrf: Option<String>,
+}
+
+/// Token from the header, else from the form body
+///
+/// The form body is buffered and put back so the handler can still read it.
+async fn extract_csrf_token(request: Request) -> AppResult<(Option<String>, Request)> {
+    if let Some(token) = request.headers().get(&CSRF_HEADER).and_then(|v| v.to_str().ok()) {
+        return Ok((Some(token.to_string()), request));
+    }
+
+    let is_form = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/x-www-form-urlencoded"));
+    if !is_form {
+        return Ok((None, request));
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = axum::body::to_bytes(body, MAX_CSRF_FORM_BYTES)
+        .await
+        .map_err(|_| AppError::payload_too_large("Form body too large"))?;
+    let token = serde_urlencoded::from_bytes::<CsrfFormField>(&bytes)
+        .ok()
+        .and_then(|form| form.csrf);
+
+    Ok((token, Request::from_parts(parts, Body::from(bytes))))
+}
+
+fn csrf_failed() -> AppError {
+    AppError::authorization("CSRF validation failed")
+}
+
+/// Axum extractor for the authenticated web session
+///
+/// Use this in web handler parameters; redirects to the login page
+/// if `session_auth_middleware` did not run or rejected the request.
+impl axum::extract::FromRequestParts<AppState> for AuthenticatedSession {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<AuthenticatedSession>()
+            .cloned()
+            .ok_or_else(redirect_to_login)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let state = AppState::for_tests();
+        Router::new()
+            .route("/private", get(|session: AuthenticatedSession| async move {
+                session.user_id.to_string()
+            }))
+            .route_layer(middleware::from_fn_with_state(state.clone(), session_auth_middleware))
+            .with_state(state)
+    }
+
+    #[tokio::test]
+    async fn test_missing_session_cookie_redirects_to_login() {
+        for cookie in [None, Some("session_id=not-a-uuid")] {
+            let mut request = Request::builder().uri("/private");
+            if let Some(cookie) = cookie {
+                request = request.header(header::COOKIE, cookie);
+            }
+
+            let response = app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+
+            assert_eq!(response.status(), StatusCode::FOUND);
+            assert_eq!(response.headers()[header::LOCATION], LOGIN_PATH);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extractor_without_middleware_redirects() {
+        use axum::extract::FromRequestParts;
+
+        let state = AppState::for_tests();
+        let (mut parts, _) = Request::builder().uri("/").body(()).unwrap().into_parts();
+
+        let rejection = AuthenticatedSession::from_request_parts(&mut parts, &state)
+            .await
+            .unwrap_err();
+
+        assert_eq!(rejection.status(), StatusCode::FOUND);
+    }
+
+    /// Router guarded by `csrf_middleware` with `session` already loaded
+    fn csrf_app(session: Session) -> Router {
+        csrf_app_with(session, AppState::for_tests())
+    }
+
+    fn csrf_app_with(session: Session, state: AppState) -> Router {
+        use axum::{routing::post, Extension};
+
+        Router::new()
+            .route(
+                "/form",
+                get(|Extension(token): Extension<CsrfToken>| async move { token.into_inner() })
+                    .merge(post(|body: String| async move { body })),
+            )
+            .route_layer(middleware::from_fn_with_state(state.clone(), csrf_middleware))
+            .layer(Extension(session))
+            .with_state(state)
+    }
+
+    fn stateless_state() -> AppState {
+        let mut state = AppState::for_tests();
+        state.config.csrf.stateless = true;
+        state
+    }
+
+    fn post_form(header_token: Option<&str>, body: &str) -> Request {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri("/form")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+        if let Some(token) = header_token {
+            request = request.header(&CSRF_HEADER, token);
+        }
+        request.body(Body::from(body.to_string())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_csrf_token_exposed_on_get() {
+        let session = Session::new(UserId::new(), None, None, 3600);
+        let token = session.csrf_token.as_str().to_string();
+
+        let request = Request::builder().uri("/form").body(Body::empty()).unwrap();
+        let response = csrf_app(session).oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[&CSRF_HEADER], token.as_str());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, token.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_csrf_correct_token_passes() {
+        let session = Session::new(UserId::new(), None, None, 3600);
+        let token = session.csrf_token.as_str().to_string();
+
+        let response = csrf_app(session.clone())
+            .oneshot(post_form(Some(&token), "name=x"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Form field works too, and the handler still sees the full body
+        let form = format!("name=x&_csrf={}", token);
+        let response = csrf_app(session).oneshot(post_form(None, &form)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, form.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_csrf_tampered_or_missing_token_rejected() {
+        let session = Session::new(UserId::new(), None, None, 3600);
+        let mut tampered = session.csrf_token.as_str().to_string();
+        let last = if tampered.ends_with('A') { "B" } else { "A" };
+        tampered.replace_range(tampered.len() - 1.., last);
+
+        let form = format!("_csrf={}", tampered);
+        for request in [
+            post_form(Some(&tampered), "name=x"),
+            post_form(None, &form),
+            post_form(None, "name=x"),
+        ] {
+            let response = csrf_app(session.clone()).oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stateless_csrf_issues_and_accepts_signed_tokens() {
+        let session = Session::new(UserId::new(), None, None, 3600);
+        let state = stateless_state();
+
+        let request = Request::builder().uri("/form").body(Body::empty()).unwrap();
+        let response = csrf_app_with(session.clone(), state.clone()).oneshot(request).await.unwrap();
+        let token = response.headers()[&CSRF_HEADER].to_str().unwrap().to_string();
+        assert_ne!(token, session.csrf_token.as_str());
+        assert!(CsrfToken::verify_signed(&token, session.id, &state.config.csrf.secret, 60));
+
+        let response = csrf_app_with(session, state)
+            .oneshot(post_form(Some(&token), "name=x"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_stateless_csrf_rejects_stored_expired_or_foreign_tokens() {
+        let session = Session::new(UserId::new(), None, None, 3600);
+        let mut state = stateless_state();
+        let secret = state.config.csrf.secret.clone();
+        let foreign = CsrfToken::generate_signed(SessionId::new(), &secret);
+        let stored = session.csrf_token.clone();
+
+        for token in [stored.as_str(), foreign.as_str()] {
+            let response = csrf_app_with(session.clone(), state.clone())
+                .oneshot(post_form(Some(token), "name=x"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        // Older than CSRF_TOKEN_TTL
+        state.config.csrf.signed_token_ttl = 0;
+        let issued = CsrfToken::generate_signed(session.id, &secret);
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        let response = csrf_app_with(session, state)
+            .oneshot(post_form(Some(issued.as_str()), "name=x"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
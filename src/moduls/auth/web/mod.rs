@@ -1,7 +1,7 @@
-/// Web layer for authentication module
-///
-/// This layer provides web routes with Inertia.js integration
-/// and session-based authentication.
+//! Web layer for authentication module
+//!
+//! This layer provides web routes with Inertia.js integration
+//! and session-based authentication.
 
 pub mod routes;
 pub mod handlers;
@@ -10,12 +10,14 @@ use axum::{
 /// Routes:
 /// - GET /web/auth/login - Show login page
 /// - POST /web/auth/login - Process login
+/// - POST /web/auth/mfa/verify - Complete a login with a TOTP code
 /// - GET /web/auth/register - Show registration page
 /// - POST /web/auth/register - Process registration
 /// - POST /web/auth/logout - Logout user
 pub fn auth_web_routes() -> Router<AppState> {
     Router::new()
         .route("/login", get(handlers::show_login).post(handlers::handle_login))
+        .route("/mfa/verify", post(handlers::handle_verify_mfa))
         .route("/register", get(handlers::show_register).post(handlers::handle_register))
         .route("/logout", post(handlers::handle_logout))
     // TODO: Add CSRF middleware
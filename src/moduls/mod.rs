@@ -1,11 +1,11 @@
-/// Modules root
-///
-/// Contains all feature modules organized following DDD principles.
-/// Each module contains its own domain, application, infrastructure,
-/// and interface layers (web/api).
+//! Modules root
+//!
+//! Contains all feature modules organized following DDD principles.
+//! Each module contains its own domain, application, infrastructure,
+//! and interface layers (web/api).
 
+pub mod audit;
 pub mod auth;
+pub mod oauth;
+pub mod organization;
 pub mod user;
-
-// Future modules:
-// pub mod organization;
@@ -0,0 +1,119 @@
+use crate::bootstrap::AppState;
+use crate::moduls::auth::api::handlers::{login_outcome_response, TokenResponse};
+use crate::moduls::auth::application::ApiLoginOutcome;
+use crate::moduls::auth::api::middleware::AuthenticatedUser;
+use crate::moduls::oauth::application::OAuthCallbackOutcome;
+use crate::moduls::oauth::domain::OAuthAccount;
+use crate::shared::AppError;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+/// Query parameters the provider appends to the callback URL
+#[derive(Debug, Deserialize)]
+pub struct CallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Set when the user denied consent or the provider failed
+    pub error: Option<String>,
+}
+
+/// Response for starting a link flow
+#[derive(Debug, Serialize)]
+pub struct LinkStartResponse {
+    pub authorization_url: String,
+}
+
+/// Response after a link flow completed
+#[derive(Debug, Serialize)]
+pub struct LinkedAccountResponse {
+    pub linked: OAuthAccount,
+}
+
+/// GET /api/auth/oauth/{provider}/start
+/// Redirect to the provider's consent screen
+pub async fn start(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+) -> Result<Redirect, AppError> {
+    let request = state.oauth_login_use_case.start(&provider).await?;
+
+    Ok(Redirect::to(&request.url))
+}
+
+/// GET /api/auth/oauth/{provider}/callback
+/// Complete social login and get JWT token pair
+///
+/// Link flows return the linked account instead of tokens; users with 2FA
+/// enabled get an MFA challenge (202) like at password login.
+pub async fn callback(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<CallbackQuery>,
+) -> Result<Response, AppError> {
+    if let Some(error) = query.error {
+        return Err(AppError::authentication(format!("OAuth login failed: {}", error)));
+    }
+
+    let (Some(code), Some(oauth_state)) = (query.code, query.state) else {
+        return Err(AppError::bad_request("Missing code or state"));
+    };
+
+    match state
+        .oauth_login_use_case
+        .callback(&provider, &code, &oauth_state)
+        .await?
+    {
+        OAuthCallbackOutcome::LoggedIn(result) => {
+            let mut response = TokenResponse::from(result.token_pair);
+            response.user = result.user;
+
+            Ok(Json(response).into_response())
+        }
+        OAuthCallbackOutcome::MfaRequired { mfa_token, expires_in } => Ok(login_outcome_response(
+            &state,
+            ApiLoginOutcome::MfaRequired { mfa_token, expires_in },
+        )),
+        OAuthCallbackOutcome::Linked(account) => {
+            Ok(Json(LinkedAccountResponse { linked: account }).into_response())
+        }
+    }
+}
+
+/// POST /api/user/oauth/{provider}/link
+/// Start linking a provider identity to the current user
+/// Requires JWT authentication
+pub async fn start_link(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(provider): Path<String>,
+) -> Result<Json<LinkStartResponse>, AppError> {
+    let request = state
+        .oauth_login_use_case
+        .start_link(&provider, auth_user.user_id)
+        .await?;
+
+    Ok(Json(LinkStartResponse {
+        authorization_url: request.url,
+    }))
+}
+
+/// DELETE /api/user/oauth/{provider}
+/// Unlink the current user's identity for a provider
+/// Requires JWT authentication
+pub async fn unlink(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(provider): Path<String>,
+) -> Result<StatusCode, AppError> {
+    state
+        .unlink_oauth_account_use_case
+        .execute(auth_user.user_id, &provider)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,8 @@
+//! API layer for OAuth module
+//!
+//! Redirect and callback endpoints for social login.
+
+pub mod routes;
+pub mod handlers;
+
+pub use routes::{oauth_api_routes, oauth_link_api_routes};
@@ -0,0 +1,34 @@
+use crate::bootstrap::AppState;
+use crate::moduls::auth::api::middleware::{jwt_auth_middleware, require_fresh_auth};
+use super::handlers;
+use axum::{
+    middleware,
+    routing::{delete, get, post},
+    Router,
+};
+
+/// Create OAuth API routes
+///
+/// Routes:
+/// - GET /api/auth/oauth/{provider}/start - Redirect to the provider
+/// - GET /api/auth/oauth/{provider}/callback - Exchange code and get JWT tokens
+pub fn oauth_api_routes() -> Router<AppState> {
+    Router::new()
+        .route("/{provider}/start", get(handlers::start))
+        .route("/{provider}/callback", get(handlers::callback))
+}
+
+/// Create OAuth account linking routes
+/// All routes require authentication via JWT middleware and a recent login:
+/// a linked identity is a way to sign in, an unlinked one may be the last
+///
+/// Routes:
+/// - POST /api/user/oauth/{provider}/link - Start linking an identity [requires recent auth]
+/// - DELETE /api/user/oauth/{provider} - Unlink an identity [requires recent auth]
+pub fn oauth_link_api_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/{provider}/link", post(handlers::start_link))
+        .route("/{provider}", delete(handlers::unlink))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_fresh_auth))
+        .route_layer(middleware::from_fn_with_state(state, jwt_auth_middleware))
+}
@@ -0,0 +1,12 @@
+//! Application layer for OAuth module
+//!
+//! Use cases driving the authorization-code flow.
+
+pub mod oauth_login;
+pub mod unlink_account;
+
+// Re-export use cases
+pub use oauth_login::{
+    AuthorizationRequest, OAuthCallbackOutcome, OAuthLoginConfig, OAuthLoginUseCase,
+};
+pub use unlink_account::UnlinkOAuthAccountUseCase;
@@ -0,0 +1,581 @@
+use crate::config::OAuthProviderConfig;
+use crate::moduls::auth::application::{ApiLoginResult, LoginUserUseCase};
+use crate::moduls::auth::domain::{ClaimsFormat, JwtKeys, MfaChallenge, TokenPair, User, UserDto};
+use crate::moduls::auth::infra::{MfaChallengeRepository, TokenRepository, UserRepository};
+use crate::moduls::auth::domain::value_objects::CsrfToken;
+use crate::moduls::oauth::domain::{FlowState, OAuthAccount, OAuthUserInfo, PkceVerifier};
+use crate::moduls::oauth::infra::{FlowStateStore, OAuthAccountRepository, OAuthClient};
+use crate::moduls::organization::infra::MembershipRepository;
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+
+/// Token settings for users logging in through a provider
+pub struct OAuthLoginConfig {
+    pub jwt_keys: Arc<JwtKeys>,
+    pub access_ttl_seconds: i64,
+    pub refresh_ttl_seconds: i64,
+    pub claims_format: ClaimsFormat,
+    /// How long a started flow waits for its callback
+    pub state_ttl_seconds: i64,
+    /// Time allowed for the TOTP code of users with 2FA enabled
+    pub mfa_challenge_ttl_seconds: i64,
+}
+
+/// Redirect to the provider's consent screen
+#[derive(Debug)]
+pub struct AuthorizationRequest {
+    pub url: String,
+    pub state: String,
+}
+
+/// Result of a completed callback
+pub enum OAuthCallbackOutcome {
+    /// Login flow: tokens issued
+    LoggedIn(ApiLoginResult),
+    /// Login flow for a user with 2FA enabled: tokens are minted once the
+    /// challenge is completed at `/api/auth/mfa/verify`
+    MfaRequired { mfa_token: String, expires_in: i64 },
+    /// Link flow started by `start_link`: identity attached to the user
+    Linked(OAuthAccount),
+}
+
+/// Use case for social login with the OAuth2 authorization-code flow
+///
+/// Business Logic:
+/// 1. `start`: generate state + PKCE verifier, redirect to the provider
+///    (`start_link` binds the flow to an authenticated user)
+/// 2. `callback`: consume the state, exchange the code, fetch userinfo
+/// 3. Link flows attach the identity to the bound user and stop here
+/// 4. Find the user by linked account, else link by verified email,
+///    else create a new user
+/// 5. Enforce the 2FA policies of the user's tenants; with 2FA enabled,
+///    issue an MFA challenge instead of tokens
+/// 6. Issue a JWT token pair
+pub struct OAuthLoginUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    membership_repo: Arc<dyn MembershipRepository>,
+    mfa_challenge_repo: Arc<dyn MfaChallengeRepository>,
+    account_repo: Arc<dyn OAuthAccountRepository>,
+    client: Arc<dyn OAuthClient>,
+    flow_store: Arc<dyn FlowStateStore>,
+    providers: Vec<OAuthProviderConfig>,
+    config: OAuthLoginConfig,
+}
+
+impl OAuthLoginUseCase {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        membership_repo: Arc<dyn MembershipRepository>,
+        mfa_challenge_repo: Arc<dyn MfaChallengeRepository>,
+        account_repo: Arc<dyn OAuthAccountRepository>,
+        client: Arc<dyn OAuthClient>,
+        flow_store: Arc<dyn FlowStateStore>,
+        providers: Vec<OAuthProviderConfig>,
+        config: OAuthLoginConfig,
+    ) -> Self {
+        Self {
+            user_repo,
+            token_repo,
+            membership_repo,
+            mfa_challenge_repo,
+            account_repo,
+            client,
+            flow_store,
+            providers,
+            config,
+        }
+    }
+
+    /// Start the flow for a provider
+    ///
+    /// # Errors
+    /// - NotFound if the provider is not configured
+    /// - Flow state store errors
+    pub async fn start(&self, provider_name: &str) -> AppResult<AuthorizationRequest> {
+        self.begin(provider_name, None).await
+    }
+
+    /// Start a flow linking a provider identity to an existing user
+    ///
+    /// # Errors
+    /// - NotFound if the provider is not configured
+    /// - Flow state store errors
+    pub async fn start_link(
+        &self,
+        provider_name: &str,
+        user_id: UserId,
+    ) -> AppResult<AuthorizationRequest> {
+        self.begin(provider_name, Some(user_id)).await
+    }
+
+    async fn begin(
+        &self,
+        provider_name: &str,
+        link_user_id: Option<UserId>,
+    ) -> AppResult<AuthorizationRequest> {
+        let provider = self.provider(provider_name)?;
+
+        let state = CsrfToken::generate().into_inner();
+        let verifier = PkceVerifier::generate();
+
+        let url = reqwest::Url::parse_with_params(
+            &provider.authorize_url,
+            &[
+                ("response_type", "code"),
+                ("client_id", provider.client_id.as_str()),
+                ("redirect_uri", provider.redirect_url.as_str()),
+                ("scope", provider.scopes.join(" ").as_str()),
+                ("state", state.as_str()),
+                ("code_challenge", verifier.challenge().as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| AppError::internal(format!("Invalid OAuth authorize URL: {}", e)))?;
+
+        let mut flow = FlowState::new(&provider.name, verifier, self.config.state_ttl_seconds);
+        if let Some(user_id) = link_user_id {
+            flow = flow.linking(user_id);
+        }
+        self.flow_store.put(&state, &flow).await?;
+
+        Ok(AuthorizationRequest {
+            url: url.into(),
+            state,
+        })
+    }
+
+    /// Complete the flow and log the user in (or link the identity)
+    ///
+    /// # Errors
+    /// - NotFound if the provider is not configured
+    /// - Authentication if the state is unknown, expired or already used,
+    ///   or the provider rejects the code
+    /// - Conflict if an account with the email exists and the provider
+    ///   does not vouch for the email, or a linked identity is already in use
+    /// - TwoFactorSetupRequired if a tenant of the user requires 2FA the
+    ///   user lacks
+    /// - Database errors
+    pub async fn callback(
+        &self,
+        provider_name: &str,
+        code: &str,
+        state: &str,
+    ) -> AppResult<OAuthCallbackOutcome> {
+        let provider = self.provider(provider_name)?;
+
+        // 1. Consume the state (single use)
+        let flow = self
+            .flow_store
+            .take(state)
+            .await?
+            .filter(|flow| flow.provider == provider.name)
+            .ok_or_else(|| AppError::authentication("Invalid OAuth state"))?;
+
+        // 2. Exchange the code and fetch the identity
+        let access_token = self.client.exchange_code(provider, code, &flow.verifier).await?;
+        let info = self.client.fetch_user_info(provider, &access_token).await?;
+
+        // 3. Link flow: attach to the bound user
+        if let Some(user_id) = flow.link_user_id {
+            let account = self.link_account(&provider.name, user_id, &info).await?;
+            return Ok(OAuthCallbackOutcome::Linked(account));
+        }
+
+        // 4. Resolve the local user
+        let user = self.find_or_create_user(&provider.name, &info).await?;
+        // The provider vouches for the email, so verification isn't enforced
+        if let Some(status) = user.login_blocker(false) {
+            return Err(status.login_error());
+        }
+
+        // 5. Second factor: the tokens are not tenant-bound, so every
+        //    tenant the user belongs to applies, as for web sessions
+        let tenants = self.membership_repo.list_organizations_for_user(user.id).await?;
+        LoginUserUseCase::ensure_two_factor(&user, &tenants)?;
+        if user.two_factor_enabled {
+            let (challenge, mfa_token) =
+                MfaChallenge::issue(user.id, None, self.config.mfa_challenge_ttl_seconds);
+            self.mfa_challenge_repo.save(&challenge).await?;
+
+            return Ok(OAuthCallbackOutcome::MfaRequired {
+                mfa_token,
+                expires_in: self.config.mfa_challenge_ttl_seconds,
+            });
+        }
+
+        // 6. Issue tokens
+        let (token_pair, access_token, refresh_token) = TokenPair::generate_with_format(
+            user.id,
+            None,
+            self.config.claims_format,
+            &self.config.jwt_keys,
+            self.config.access_ttl_seconds,
+            self.config.refresh_ttl_seconds,
+        )?;
+        self.token_repo.save(&access_token).await?;
+        self.token_repo.save(&refresh_token).await?;
+
+        Ok(OAuthCallbackOutcome::LoggedIn(ApiLoginResult {
+            user: UserDto::from(user),
+            token_pair,
+            tenant: None,
+        }))
+    }
+
+    fn provider(&self, name: &str) -> AppResult<&OAuthProviderConfig> {
+        self.providers
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| AppError::not_found("OAuth provider not found"))
+    }
+
+    async fn link_account(
+        &self,
+        provider: &str,
+        user_id: UserId,
+        info: &OAuthUserInfo,
+    ) -> AppResult<OAuthAccount> {
+        if let Some(existing) = self
+            .account_repo
+            .find_by_provider_user_id(provider, &info.provider_user_id)
+            .await?
+        {
+            return Err(if existing.user_id == user_id {
+                AppError::conflict("This identity is already linked to your account")
+            } else {
+                AppError::conflict("This identity is already linked to another account")
+            });
+        }
+
+        self.account_repo
+            .save(&OAuthAccount::new(user_id, provider, info))
+            .await
+    }
+
+    async fn find_or_create_user(&self, provider: &str, info: &OAuthUserInfo) -> AppResult<User> {
+        // Returning user
+        if let Some(account) = self
+            .account_repo
+            .find_by_provider_user_id(provider, &info.provider_user_id)
+            .await?
+        {
+            return self
+                .user_repo
+                .find_by_id(account.user_id)
+                .await?
+                .ok_or_else(|| AppError::authentication("User not found"));
+        }
+
+        // Existing password account: only link when the provider vouches
+        // for the email, otherwise anyone could claim the address
+        if let Some(user) = self.user_repo.find_by_email(&info.email).await? {
+            if !info.email_verified {
+                return Err(AppError::conflict(
+                    "An account with this email already exists, log in with your password to link it",
+                ));
+            }
+            self.account_repo
+                .save(&OAuthAccount::new(user.id, provider, info))
+                .await?;
+            return Ok(user);
+        }
+
+        // New user with an unusable random password
+        let mut user = User::new(
+            info.email.clone(),
+            &CsrfToken::generate().into_inner(),
+            info.name.clone(),
+        )?;
+        if info.email_verified {
+            user.verify_email();
+        }
+        let user = self.user_repo.save(&user).await?;
+        self.account_repo
+            .save(&OAuthAccount::new(user.id, provider, info))
+            .await?;
+
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::Email;
+    use crate::moduls::auth::infra::in_memory::*;
+    use crate::moduls::oauth::infra::in_memory::*;
+    use crate::moduls::oauth::infra::InMemoryFlowStateStore;
+    use crate::moduls::organization::domain::{Organization, TenantMembership, TwoFactorPolicy};
+    use crate::moduls::organization::infra::in_memory::{
+        InMemoryMembershipRepository, InMemoryOrganizationRepository,
+    };
+    use crate::moduls::organization::infra::OrganizationRepository;
+    use std::collections::HashMap;
+
+    struct Fixture {
+        use_case: OAuthLoginUseCase,
+        user_repo: Arc<InMemoryUserRepository>,
+        account_repo: Arc<InMemoryOAuthAccountRepository>,
+        org_repo: Arc<InMemoryOrganizationRepository>,
+        membership_repo: Arc<InMemoryMembershipRepository>,
+        client: Arc<FakeOAuthClient>,
+    }
+
+    fn provider() -> OAuthProviderConfig {
+        OAuthProviderConfig {
+            name: "google".to_string(),
+            client_id: "client-id".to_string(),
+            client_secret: "client-secret".to_string(),
+            authorize_url: "https://provider.test/authorize".to_string(),
+            token_url: "https://provider.test/token".to_string(),
+            userinfo_url: "https://provider.test/userinfo".to_string(),
+            redirect_url: "http://localhost:3000/api/auth/oauth/google/callback".to_string(),
+            scopes: vec!["openid".to_string(), "email".to_string()],
+        }
+    }
+
+    fn user_info(email_verified: bool) -> OAuthUserInfo {
+        OAuthUserInfo {
+            provider_user_id: "google-123".to_string(),
+            email: Email::new("oauth@example.com").unwrap(),
+            name: "OAuth User".to_string(),
+            email_verified,
+        }
+    }
+
+    fn fixture_with(user_repo: InMemoryUserRepository, info: OAuthUserInfo) -> Fixture {
+        fixture_with_ttl(user_repo, info, 600)
+    }
+
+    fn fixture_with_ttl(
+        user_repo: InMemoryUserRepository,
+        info: OAuthUserInfo,
+        state_ttl_seconds: i64,
+    ) -> Fixture {
+        let user_repo = Arc::new(user_repo);
+        let account_repo = Arc::new(InMemoryOAuthAccountRepository::default());
+        let client = Arc::new(FakeOAuthClient::new("valid-code", info));
+        let org_repo = Arc::new(InMemoryOrganizationRepository::default());
+        let membership_repo = Arc::new(InMemoryMembershipRepository::new(org_repo.clone()));
+
+        let use_case = OAuthLoginUseCase::new(
+            user_repo.clone(),
+            Arc::new(InMemoryTokenRepository::default()),
+            membership_repo.clone(),
+            Arc::new(InMemoryMfaChallengeRepository::default()),
+            account_repo.clone(),
+            client.clone(),
+            Arc::new(InMemoryFlowStateStore::new()),
+            vec![provider()],
+            OAuthLoginConfig {
+                jwt_keys: Arc::new(JwtKeys::hmac("test_jwt_secret_key_minimum_32_characters_long")),
+                access_ttl_seconds: 900,
+                refresh_ttl_seconds: 604800,
+                claims_format: ClaimsFormat::Verbose,
+                state_ttl_seconds,
+                mfa_challenge_ttl_seconds: 300,
+            },
+        );
+
+        Fixture {
+            use_case,
+            user_repo,
+            account_repo,
+            org_repo,
+            membership_repo,
+            client,
+        }
+    }
+
+    async fn login(f: &Fixture) -> ApiLoginResult {
+        let state = f.use_case.start("google").await.unwrap().state;
+        match f.use_case.callback("google", "valid-code", &state).await.unwrap() {
+            OAuthCallbackOutcome::LoggedIn(result) => result,
+            _ => panic!("expected login"),
+        }
+    }
+
+    fn existing_user() -> User {
+        User::new(
+            Email::new("oauth@example.com").unwrap(),
+            "SecurePassword123!",
+            "Existing User".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_start_builds_pkce_redirect() {
+        let f = fixture_with(InMemoryUserRepository::default(), user_info(true));
+
+        let request = f.use_case.start("google").await.unwrap();
+        let url = reqwest::Url::parse(&request.url).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(url.host_str(), Some("provider.test"));
+        assert_eq!(params["state"], request.state);
+        assert_eq!(params["client_id"], "client-id");
+        assert_eq!(params["scope"], "openid email");
+        assert_eq!(params["code_challenge_method"], "S256");
+    }
+
+    #[tokio::test]
+    async fn test_start_unknown_provider() {
+        let f = fixture_with(InMemoryUserRepository::default(), user_info(true));
+
+        let result = f.use_case.start("myspace").await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_callback_creates_user_and_issues_tokens() {
+        let f = fixture_with(InMemoryUserRepository::default(), user_info(true));
+        let request = f.use_case.start("google").await.unwrap();
+
+        let result = match f.use_case.callback("google", "valid-code", &request.state).await {
+            Ok(OAuthCallbackOutcome::LoggedIn(result)) => result,
+            _ => panic!("expected login"),
+        };
+
+        assert_eq!(result.user.email, "oauth@example.com");
+        assert!(result.user.email_verified);
+        assert!(!result.token_pair.access_token.is_empty());
+        assert_eq!(f.user_repo.users.lock().unwrap().len(), 1);
+        assert_eq!(f.account_repo.accounts.lock().unwrap().len(), 1);
+
+        // The verifier whose challenge was sent is used for the exchange
+        let url = reqwest::Url::parse(&request.url).unwrap();
+        let challenge = url
+            .query_pairs()
+            .find(|(k, _)| k == "code_challenge")
+            .map(|(_, v)| v.into_owned())
+            .unwrap();
+        let verifier = f.client.last_verifier.lock().unwrap().clone().unwrap();
+        assert_eq!(verifier.challenge(), challenge);
+    }
+
+    #[tokio::test]
+    async fn test_callback_returning_user_reuses_account() {
+        let f = fixture_with(InMemoryUserRepository::default(), user_info(true));
+
+        let first = login(&f).await;
+        let second = login(&f).await;
+
+        assert_eq!(first.user.id, second.user.id);
+        assert_eq!(f.account_repo.accounts.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_callback_links_existing_user_with_verified_email() {
+        let user = existing_user();
+        let f = fixture_with(InMemoryUserRepository::with_user(user.clone()), user_info(true));
+
+        let result = login(&f).await;
+
+        assert_eq!(result.user.id, user.id);
+        assert_eq!(f.account_repo.accounts.lock().unwrap()[0].user_id, user.id);
+    }
+
+    #[tokio::test]
+    async fn test_callback_unverified_email_does_not_link() {
+        let f = fixture_with(InMemoryUserRepository::with_user(existing_user()), user_info(false));
+        let state = f.use_case.start("google").await.unwrap().state;
+
+        let result = f.use_case.callback("google", "valid-code", &state).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert!(f.account_repo.accounts.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_callback_rejects_unknown_and_reused_state() {
+        let f = fixture_with(InMemoryUserRepository::default(), user_info(true));
+
+        let result = f.use_case.callback("google", "valid-code", "forged-state").await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+
+        let state = f.use_case.start("google").await.unwrap().state;
+        f.use_case.callback("google", "valid-code", &state).await.unwrap();
+        let result = f.use_case.callback("google", "valid-code", &state).await;
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_callback_rejects_invalid_code() {
+        let f = fixture_with(InMemoryUserRepository::default(), user_info(true));
+        let state = f.use_case.start("google").await.unwrap().state;
+
+        let result = f.use_case.callback("google", "wrong-code", &state).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+        assert!(f.user_repo.users.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_callback_rejects_expired_state() {
+        let f = fixture_with_ttl(InMemoryUserRepository::default(), user_info(true), 0);
+        let state = f.use_case.start("google").await.unwrap().state;
+
+        let result = f.use_case.callback("google", "valid-code", &state).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+
+    #[tokio::test]
+    async fn test_link_attaches_identity_to_current_user() {
+        let user = existing_user();
+        let f = fixture_with(InMemoryUserRepository::with_user(user.clone()), user_info(false));
+        let state = f.use_case.start_link("google", user.id).await.unwrap().state;
+
+        let outcome = f.use_case.callback("google", "valid-code", &state).await.unwrap();
+
+        assert!(matches!(outcome, OAuthCallbackOutcome::Linked(ref a) if a.user_id == user.id));
+        assert_eq!(f.user_repo.users.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_link_rejects_identity_linked_elsewhere() {
+        let f = fixture_with(InMemoryUserRepository::default(), user_info(true));
+        let owner = login(&f).await.user.id;
+
+        let other = existing_user();
+        let state = f.use_case.start_link("google", other.id).await.unwrap().state;
+        let result = f.use_case.callback("google", "valid-code", &state).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        let accounts = f.account_repo.accounts.lock().unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].user_id, owner);
+    }
+
+    #[tokio::test]
+    async fn test_callback_with_2fa_issues_mfa_challenge() {
+        let mut user = existing_user();
+        user.enable_two_factor();
+        let f = fixture_with(InMemoryUserRepository::with_user(user), user_info(true));
+        let state = f.use_case.start("google").await.unwrap().state;
+
+        let outcome = f.use_case.callback("google", "valid-code", &state).await.unwrap();
+
+        assert!(matches!(outcome, OAuthCallbackOutcome::MfaRequired { expires_in: 300, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_callback_enforces_tenant_two_factor_policy() {
+        let user = existing_user();
+        let f = fixture_with(InMemoryUserRepository::with_user(user.clone()), user_info(true));
+        let mut org = Organization::new("Acme".to_string(), "acme").unwrap();
+        org.set_two_factor_policy(TwoFactorPolicy::Required);
+        f.org_repo.save(&org).await.unwrap();
+        f.membership_repo.add(&TenantMembership::new(org.id, user.id)).await.unwrap();
+        let state = f.use_case.start("google").await.unwrap().state;
+
+        let result = f.use_case.callback("google", "valid-code", &state).await;
+
+        assert!(matches!(result, Err(AppError::TwoFactorSetupRequired(_))));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::moduls::oauth::infra::OAuthAccountRepository;
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+
+/// Use case for removing a linked provider identity
+///
+/// Business Logic:
+/// 1. Delete the user's linked accounts for the provider
+/// 2. Report NotFound if nothing was linked
+pub struct UnlinkOAuthAccountUseCase {
+    account_repo: Arc<dyn OAuthAccountRepository>,
+}
+
+impl UnlinkOAuthAccountUseCase {
+    pub fn new(account_repo: Arc<dyn OAuthAccountRepository>) -> Self {
+        Self { account_repo }
+    }
+
+    /// Execute the use case
+    ///
+    /// # Errors
+    /// - NotFound if the user has no account linked for the provider
+    /// - Database errors
+    pub async fn execute(&self, user_id: UserId, provider: &str) -> AppResult<()> {
+        let removed = self.account_repo.delete_for_user(user_id, provider).await?;
+        if removed == 0 {
+            return Err(AppError::not_found("No linked account for this provider"));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::Email;
+    use crate::moduls::oauth::domain::{OAuthAccount, OAuthUserInfo};
+    use crate::moduls::oauth::infra::in_memory::InMemoryOAuthAccountRepository;
+
+    fn linked_repo(user_id: UserId) -> Arc<InMemoryOAuthAccountRepository> {
+        let info = OAuthUserInfo {
+            provider_user_id: "github-1".to_string(),
+            email: Email::new("linked@example.com").unwrap(),
+            name: "Linked".to_string(),
+            email_verified: false,
+        };
+        let repo = InMemoryOAuthAccountRepository::default();
+        repo.accounts
+            .lock()
+            .unwrap()
+            .push(OAuthAccount::new(user_id, "github", &info));
+        Arc::new(repo)
+    }
+
+    #[tokio::test]
+    async fn test_unlink_removes_account() {
+        let user_id = UserId::new();
+        let repo = linked_repo(user_id);
+        let use_case = UnlinkOAuthAccountUseCase::new(repo.clone());
+
+        use_case.execute(user_id, "github").await.unwrap();
+
+        assert!(repo.accounts.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unlink_only_touches_own_accounts() {
+        let repo = linked_repo(UserId::new());
+        let use_case = UnlinkOAuthAccountUseCase::new(repo.clone());
+
+        let result = use_case.execute(UserId::new(), "github").await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        assert_eq!(repo.accounts.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,39 @@
+use super::PkceVerifier;
+use crate::shared::types::*;
+use serde::{Deserialize, Serialize};
+
+/// Authorization flow awaiting its callback
+///
+/// Stored under the `state` parameter between `start` and `callback`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlowState {
+    pub provider: String,
+    pub verifier: PkceVerifier,
+    /// Set when an authenticated user is linking an identity instead of logging in
+    #[serde(default)]
+    pub link_user_id: Option<UserId>,
+    pub expires_at: Timestamp,
+}
+
+impl FlowState {
+    /// Create a flow state valid for `ttl_seconds`
+    pub fn new(provider: &str, verifier: PkceVerifier, ttl_seconds: i64) -> Self {
+        Self {
+            provider: provider.to_string(),
+            verifier,
+            link_user_id: None,
+            expires_at: now() + chrono::Duration::seconds(ttl_seconds),
+        }
+    }
+
+    /// Bind the flow to a user linking an identity to their account
+    pub fn linking(mut self, user_id: UserId) -> Self {
+        self.link_user_id = Some(user_id);
+        self
+    }
+
+    /// Check if the flow has expired
+    pub fn is_expired(&self) -> bool {
+        now() >= self.expires_at
+    }
+}
@@ -0,0 +1,12 @@
+//! Domain layer for OAuth module
+//!
+//! Contains linked provider accounts and the identity reported by a provider.
+
+pub mod oauth_account;
+pub mod flow_state;
+pub mod pkce;
+
+// Re-export commonly used types
+pub use oauth_account::{OAuthAccount, OAuthUserInfo};
+pub use pkce::PkceVerifier;
+pub use flow_state::FlowState;
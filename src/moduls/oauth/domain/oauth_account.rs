@@ -0,0 +1,129 @@
+use crate::moduls::auth::domain::Email;
+use crate::shared::{types::*, AppError, AppResult};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// External provider account linked to a user
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow, Serialize)]
+pub struct OAuthAccount {
+    pub id: Uuid,
+    pub user_id: UserId,
+    pub provider: String,
+    pub provider_user_id: String,
+    pub email: String,
+    pub created_at: Timestamp,
+}
+
+impl OAuthAccount {
+    /// Link a provider identity to a user
+    pub fn new(user_id: UserId, provider: &str, info: &OAuthUserInfo) -> Self {
+        Self {
+            id: new_id(),
+            user_id,
+            provider: provider.to_string(),
+            provider_user_id: info.provider_user_id.clone(),
+            email: info.email.as_str().to_string(),
+            created_at: now(),
+        }
+    }
+}
+
+/// Identity returned by a provider's userinfo endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthUserInfo {
+    /// Stable subject identifier (`sub` for OIDC, `id` for GitHub)
+    pub provider_user_id: String,
+    pub email: Email,
+    pub name: String,
+    /// Whether the provider vouches for the email address
+    pub email_verified: bool,
+}
+
+impl OAuthUserInfo {
+    /// Parse a userinfo response
+    ///
+    /// Accepts the OIDC shape (`sub`, `email`, `email_verified`, `name`) and
+    /// the GitHub shape (numeric `id`, `login`). Missing `email_verified` is
+    /// treated as unverified.
+    ///
+    /// # Errors
+    /// - Authentication if the subject or email is missing or invalid
+    pub fn from_userinfo(value: &serde_json::Value) -> AppResult<Self> {
+        let provider_user_id = match value.get("sub").or_else(|| value.get("id")) {
+            Some(serde_json::Value::String(id)) if !id.is_empty() => id.clone(),
+            Some(serde_json::Value::Number(id)) => id.to_string(),
+            _ => return Err(AppError::authentication("OAuth provider did not return a user id")),
+        };
+
+        let email = value
+            .get("email")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| AppError::authentication("OAuth provider did not return an email"))?;
+        let email = Email::new(email)
+            .map_err(|_| AppError::authentication("OAuth provider returned an invalid email"))?;
+
+        let name = ["name", "login"]
+            .iter()
+            .filter_map(|key| value.get(*key).and_then(|v| v.as_str()))
+            .map(str::trim)
+            .find(|name| !name.is_empty())
+            .unwrap_or_else(|| email.as_str().split('@').next().unwrap_or_default())
+            .to_string();
+
+        let email_verified = value
+            .get("email_verified")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        Ok(Self {
+            provider_user_id,
+            email,
+            name,
+            email_verified,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_userinfo_oidc_shape() {
+        let info = OAuthUserInfo::from_userinfo(&json!({
+            "sub": "1098765",
+            "email": "jane@example.com",
+            "email_verified": true,
+            "name": "Jane Doe"
+        }))
+        .unwrap();
+
+        assert_eq!(info.provider_user_id, "1098765");
+        assert_eq!(info.email.as_str(), "jane@example.com");
+        assert_eq!(info.name, "Jane Doe");
+        assert!(info.email_verified);
+    }
+
+    #[test]
+    fn test_userinfo_github_shape() {
+        let info = OAuthUserInfo::from_userinfo(&json!({
+            "id": 583231,
+            "login": "octocat",
+            "name": null,
+            "email": "octocat@github.com"
+        }))
+        .unwrap();
+
+        assert_eq!(info.provider_user_id, "583231");
+        assert_eq!(info.name, "octocat");
+        assert!(!info.email_verified);
+    }
+
+    #[test]
+    fn test_userinfo_requires_email() {
+        let result = OAuthUserInfo::from_userinfo(&json!({ "sub": "1", "email": null }));
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::moduls::oauth::domain::FlowState;
+use crate::shared::AppResult;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// FlowStateStore trait for short-lived OAuth state and PKCE verifiers
+///
+/// Keyed on the `state` parameter. Entries are single use so a callback
+/// cannot be replayed.
+#[async_trait]
+pub trait FlowStateStore: Send + Sync {
+    /// Store a flow until it expires
+    async fn put(&self, state: &str, flow: &FlowState) -> AppResult<()>;
+
+    /// Remove and return a flow
+    ///
+    /// Returns None if the state is unknown, expired or already consumed
+    async fn take(&self, state: &str) -> AppResult<Option<FlowState>>;
+
+    /// Check that the backing store is reachable (startup dependency check)
+    async fn ping(&self) -> AppResult<()> {
+        Ok(())
+    }
+}
+
+/// Process-local FlowStateStore (default)
+///
+/// Only suitable for a single instance: a callback landing on another
+/// replica will not find the state. Use the Redis store when scaling out.
+#[derive(Default)]
+pub struct InMemoryFlowStateStore {
+    flows: Mutex<HashMap<String, FlowState>>,
+}
+
+impl InMemoryFlowStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl FlowStateStore for InMemoryFlowStateStore {
+    async fn put(&self, state: &str, flow: &FlowState) -> AppResult<()> {
+        let mut flows = self.flows.lock().unwrap();
+        // Abandoned flows never reach `take`
+        flows.retain(|_, f| !f.is_expired());
+        flows.insert(state.to_string(), flow.clone());
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> AppResult<Option<FlowState>> {
+        let flow = self.flows.lock().unwrap().remove(state);
+        Ok(flow.filter(|f| !f.is_expired()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::oauth::domain::PkceVerifier;
+
+    #[tokio::test]
+    async fn test_flow_state_round_trip() {
+        let store = InMemoryFlowStateStore::new();
+        let flow = FlowState::new("google", PkceVerifier::generate(), 600);
+
+        store.put("state-1", &flow).await.unwrap();
+
+        assert_eq!(store.take("state-1").await.unwrap(), Some(flow));
+    }
+
+    #[tokio::test]
+    async fn test_flow_state_is_single_use() {
+        let store = InMemoryFlowStateStore::new();
+        let flow = FlowState::new("google", PkceVerifier::generate(), 600);
+
+        store.put("state-1", &flow).await.unwrap();
+        store.take("state-1").await.unwrap();
+
+        assert_eq!(store.take("state-1").await.unwrap(), None);
+        assert_eq!(store.take("unknown").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_flow_state_expires() {
+        let store = InMemoryFlowStateStore::new();
+        let flow = FlowState::new("google", PkceVerifier::generate(), 0);
+
+        store.put("state-1", &flow).await.unwrap();
+
+        assert_eq!(store.take("state-1").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::config::OAuthProviderConfig;
+use crate::moduls::oauth::domain::{OAuthUserInfo, PkceVerifier};
+use crate::shared::{AppError, AppResult};
+use async_trait::async_trait;
+use serde::Deserialize;
+
+/// OAuthClient trait for talking to an identity provider
+#[async_trait]
+pub trait OAuthClient: Send + Sync {
+    /// Exchange an authorization code for a provider access token
+    ///
+    /// # Errors
+    /// - Authentication if the provider rejects the code
+    async fn exchange_code(
+        &self,
+        provider: &OAuthProviderConfig,
+        code: &str,
+        verifier: &PkceVerifier,
+    ) -> AppResult<String>;
+
+    /// Fetch the user's identity with a provider access token
+    async fn fetch_user_info(
+        &self,
+        provider: &OAuthProviderConfig,
+        access_token: &str,
+    ) -> AppResult<OAuthUserInfo>;
+}
+
+/// Token endpoint response (only the fields we use)
+#[derive(Debug, Deserialize)]
+struct TokenExchangeResponse {
+    access_token: String,
+}
+
+/// reqwest implementation of OAuthClient
+pub struct HttpOAuthClient {
+    http: reqwest::Client,
+}
+
+impl HttpOAuthClient {
+    pub fn new() -> Self {
+        Self {
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+impl Default for HttpOAuthClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl OAuthClient for HttpOAuthClient {
+    async fn exchange_code(
+        &self,
+        provider: &OAuthProviderConfig,
+        code: &str,
+        verifier: &PkceVerifier,
+    ) -> AppResult<String> {
+        let response = self
+            .http
+            .post(&provider.token_url)
+            // GitHub answers form-encoded unless JSON is requested
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", provider.redirect_url.as_str()),
+                ("client_id", provider.client_id.as_str()),
+                ("client_secret", provider.client_secret.as_str()),
+                ("code_verifier", verifier.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| AppError::internal(format!("OAuth token request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            tracing::warn!(
+                "OAuth code exchange with {} failed: {}",
+                provider.name,
+                response.status()
+            );
+            return Err(AppError::authentication("OAuth code exchange failed"));
+        }
+
+        let token = response
+            .json::<TokenExchangeResponse>()
+            .await
+            .map_err(|_| AppError::authentication("OAuth code exchange failed"))?;
+
+        Ok(token.access_token)
+    }
+
+    async fn fetch_user_info(
+        &self,
+        provider: &OAuthProviderConfig,
+        access_token: &str,
+    ) -> AppResult<OAuthUserInfo> {
+        let response = self
+            .http
+            .get(&provider.userinfo_url)
+            .bearer_auth(access_token)
+            .header(reqwest::header::ACCEPT, "application/json")
+            // GitHub rejects requests without a User-Agent
+            .header(reqwest::header::USER_AGENT, env!("CARGO_PKG_NAME"))
+            .send()
+            .await
+            .map_err(|e| AppError::internal(format!("OAuth userinfo request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            tracing::warn!(
+                "OAuth userinfo from {} failed: {}",
+                provider.name,
+                response.status()
+            );
+            return Err(AppError::authentication("Failed to fetch OAuth user info"));
+        }
+
+        let value = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|_| AppError::authentication("Failed to fetch OAuth user info"))?;
+
+        OAuthUserInfo::from_userinfo(&value)
+    }
+}
@@ -0,0 +1,92 @@
+//! In-memory implementations for unit tests
+
+use super::{OAuthAccountRepository, OAuthClient};
+use crate::config::OAuthProviderConfig;
+use crate::moduls::oauth::domain::{OAuthAccount, OAuthUserInfo, PkceVerifier};
+use crate::shared::{types::*, AppError, AppResult};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// In-memory OAuthAccountRepository
+#[derive(Default)]
+pub struct InMemoryOAuthAccountRepository {
+    pub accounts: Mutex<Vec<OAuthAccount>>,
+}
+
+#[async_trait]
+impl OAuthAccountRepository for InMemoryOAuthAccountRepository {
+    async fn save(&self, account: &OAuthAccount) -> AppResult<OAuthAccount> {
+        let mut accounts = self.accounts.lock().unwrap();
+        if accounts.iter().any(|a| {
+            a.provider == account.provider && a.provider_user_id == account.provider_user_id
+        }) {
+            return Err(AppError::conflict("OAuth account is already linked"));
+        }
+        accounts.push(account.clone());
+        Ok(account.clone())
+    }
+
+    async fn find_by_provider_user_id(
+        &self,
+        provider: &str,
+        provider_user_id: &str,
+    ) -> AppResult<Option<OAuthAccount>> {
+        let accounts = self.accounts.lock().unwrap();
+        Ok(accounts
+            .iter()
+            .find(|a| a.provider == provider && a.provider_user_id == provider_user_id)
+            .cloned())
+    }
+
+    async fn delete_for_user(&self, user_id: UserId, provider: &str) -> AppResult<u64> {
+        let mut accounts = self.accounts.lock().unwrap();
+        let before = accounts.len();
+        accounts.retain(|a| !(a.user_id == user_id && a.provider == provider));
+        Ok((before - accounts.len()) as u64)
+    }
+}
+
+/// Fake provider accepting a single authorization code
+pub struct FakeOAuthClient {
+    pub code: String,
+    pub user_info: OAuthUserInfo,
+    /// Verifier received by the last code exchange
+    pub last_verifier: Mutex<Option<PkceVerifier>>,
+}
+
+impl FakeOAuthClient {
+    pub fn new(code: &str, user_info: OAuthUserInfo) -> Self {
+        Self {
+            code: code.to_string(),
+            user_info,
+            last_verifier: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl OAuthClient for FakeOAuthClient {
+    async fn exchange_code(
+        &self,
+        _provider: &OAuthProviderConfig,
+        code: &str,
+        verifier: &PkceVerifier,
+    ) -> AppResult<String> {
+        if code != self.code {
+            return Err(AppError::authentication("OAuth code exchange failed"));
+        }
+        *self.last_verifier.lock().unwrap() = Some(verifier.clone());
+        Ok("provider-access-token".to_string())
+    }
+
+    async fn fetch_user_info(
+        &self,
+        _provider: &OAuthProviderConfig,
+        access_token: &str,
+    ) -> AppResult<OAuthUserInfo> {
+        if access_token != "provider-access-token" {
+            return Err(AppError::authentication("Failed to fetch OAuth user info"));
+        }
+        Ok(self.user_info.clone())
+    }
+}
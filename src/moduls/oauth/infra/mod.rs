@@ -0,0 +1,21 @@
+//! Infrastructure layer for OAuth module
+//!
+//! Contains the PostgreSQL repository for linked accounts, the HTTP
+//! client talking to identity providers and flow state storage.
+//! The Redis flow state store requires the `redis` feature.
+
+pub mod postgres_oauth_account_repository;
+pub mod http_oauth_client;
+pub mod flow_state_store;
+#[cfg(feature = "redis")]
+pub mod redis_flow_state_store;
+
+#[cfg(test)]
+pub mod in_memory;
+
+// Re-export repository traits and implementations
+pub use postgres_oauth_account_repository::{OAuthAccountRepository, PostgresOAuthAccountRepository};
+pub use http_oauth_client::{HttpOAuthClient, OAuthClient};
+pub use flow_state_store::{FlowStateStore, InMemoryFlowStateStore};
+#[cfg(feature = "redis")]
+pub use redis_flow_state_store::RedisFlowStateStore;
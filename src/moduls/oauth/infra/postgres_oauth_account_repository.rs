@@ -0,0 +1,105 @@
+use crate::moduls::oauth::domain::OAuthAccount;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// OAuthAccountRepository trait defining linked account persistence
+#[async_trait]
+pub trait OAuthAccountRepository: Send + Sync {
+    /// Link a provider account to a user
+    ///
+    /// # Errors
+    /// - Conflict if the provider account is already linked
+    /// - Database errors
+    async fn save(&self, account: &OAuthAccount) -> AppResult<OAuthAccount>;
+
+    /// Find a linked account by provider and provider user ID
+    async fn find_by_provider_user_id(
+        &self,
+        provider: &str,
+        provider_user_id: &str,
+    ) -> AppResult<Option<OAuthAccount>>;
+
+    /// Unlink a user's accounts for a provider
+    ///
+    /// Returns the number of unlinked accounts
+    async fn delete_for_user(&self, user_id: UserId, provider: &str) -> AppResult<u64>;
+}
+
+/// PostgreSQL implementation of OAuthAccountRepository
+pub struct PostgresOAuthAccountRepository {
+    db: DbPools,
+}
+
+impl PostgresOAuthAccountRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl OAuthAccountRepository for PostgresOAuthAccountRepository {
+    async fn save(&self, account: &OAuthAccount) -> AppResult<OAuthAccount> {
+        let result = sqlx::query_as::<_, OAuthAccount>(
+            r#"
+            INSERT INTO oauth_accounts (id, user_id, provider, provider_user_id, email, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, provider, provider_user_id, email, created_at
+            "#,
+        )
+        .bind(account.id)
+        .bind(account.user_id)
+        .bind(&account.provider)
+        .bind(&account.provider_user_id)
+        .bind(&account.email)
+        .bind(account.created_at)
+        .fetch_one(self.db.writer())
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(db_err) = &e {
+                if db_err.is_unique_violation() {
+                    return AppError::conflict("OAuth account is already linked");
+                }
+            }
+            AppError::internal(format!("Failed to save OAuth account: {}", e))
+        })?;
+
+        Ok(result)
+    }
+
+    async fn find_by_provider_user_id(
+        &self,
+        provider: &str,
+        provider_user_id: &str,
+    ) -> AppResult<Option<OAuthAccount>> {
+        let result = sqlx::query_as::<_, OAuthAccount>(
+            r#"
+            SELECT id, user_id, provider, provider_user_id, email, created_at
+            FROM oauth_accounts
+            WHERE provider = $1 AND provider_user_id = $2
+            "#,
+        )
+        .bind(provider)
+        .bind(provider_user_id)
+        .fetch_optional(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find OAuth account: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn delete_for_user(&self, user_id: UserId, provider: &str) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM oauth_accounts
+            WHERE user_id = $1 AND provider = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider)
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to unlink OAuth account: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,91 @@
+use super::FlowStateStore;
+use crate::moduls::oauth::domain::FlowState;
+use crate::shared::{types::*, AppError, AppResult};
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use tokio::sync::OnceCell;
+
+const KEY_PREFIX: &str = "oauth_state:";
+
+/// Redis implementation of FlowStateStore
+///
+/// Shares flow state across replicas. Expiry is enforced by Redis (`EX`)
+/// and single use by `GETDEL` (Redis 6.2+).
+pub struct RedisFlowStateStore {
+    client: redis::Client,
+    connection: OnceCell<MultiplexedConnection>,
+}
+
+impl RedisFlowStateStore {
+    /// Create a store for the given `redis://` URL
+    ///
+    /// The connection is opened on first use.
+    pub fn new(url: &str) -> AppResult<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| AppError::Config(format!("Invalid Redis URL: {}", e)))?;
+
+        Ok(Self {
+            client,
+            connection: OnceCell::new(),
+        })
+    }
+
+    async fn connection(&self) -> AppResult<MultiplexedConnection> {
+        self.connection
+            .get_or_try_init(|| self.client.get_multiplexed_async_connection())
+            .await
+            .cloned()
+            .map_err(|e| AppError::internal(format!("Failed to connect to Redis: {}", e)))
+    }
+}
+
+#[async_trait]
+impl FlowStateStore for RedisFlowStateStore {
+    async fn put(&self, state: &str, flow: &FlowState) -> AppResult<()> {
+        let ttl = (flow.expires_at - now()).num_seconds();
+        if ttl <= 0 {
+            return Ok(());
+        }
+
+        let value = serde_json::to_string(flow)
+            .map_err(|e| AppError::internal(format!("Failed to encode OAuth state: {}", e)))?;
+
+        let mut conn = self.connection().await?;
+        let _: () = redis::cmd("SET")
+            .arg(format!("{}{}", KEY_PREFIX, state))
+            .arg(value)
+            .arg("EX")
+            .arg(ttl)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to store OAuth state: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> AppResult<Option<FlowState>> {
+        let mut conn = self.connection().await?;
+        let value: Option<String> = redis::cmd("GETDEL")
+            .arg(format!("{}{}", KEY_PREFIX, state))
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to load OAuth state: {}", e)))?;
+
+        let flow = value
+            .map(|v| serde_json::from_str::<FlowState>(&v))
+            .transpose()
+            .map_err(|e| AppError::internal(format!("Failed to decode OAuth state: {}", e)))?;
+
+        Ok(flow.filter(|f| !f.is_expired()))
+    }
+
+    async fn ping(&self) -> AppResult<()> {
+        let mut conn = self.connection().await?;
+        let _: String = redis::cmd("PING")
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::internal(format!("Redis PING failed: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+//! OAuth module
+//!
+//! Social login ("Sign in with Google/GitHub") using the OAuth2
+//! authorization-code flow with PKCE:
+//! - Domain: Linked provider accounts, provider user info, PKCE
+//! - Application: Use cases (start flow, handle callback, link/unlink)
+//! - Infrastructure: Repositories and the provider HTTP client
+//! - API: JSON/redirect handlers under `/api/auth/oauth`
+
+pub mod domain;
+pub mod application;
+pub mod infra;
+pub mod api;
+
+// Re-export routes for easy mounting
+pub use api::{oauth_api_routes, oauth_link_api_routes};
@@ -0,0 +1,116 @@
+use crate::bootstrap::AppState;
+use crate::moduls::auth::api::middleware::AuthenticatedUser;
+use crate::moduls::organization::application::{
+    AddMemberCommand, CreateOrganizationCommand, ListTenantsQuery, UpdateOrganizationCommand,
+};
+use crate::moduls::organization::domain::{Organization, OrganizationDto, TenantDto, TenantMembership};
+use crate::moduls::organization::infra::{MembershipRepository, OrganizationRepository};
+use crate::shared::pagination::Page;
+use crate::shared::{AppError, ValidatedJson};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+/// POST /api/organizations
+/// Create an organization; the caller becomes its owner and first member
+pub async fn create_organization(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateOrganizationCommand>,
+) -> Result<(StatusCode, Json<Organization>), AppError> {
+    let org = state
+        .create_organization_use_case
+        .execute(auth_user.user_id, payload)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(org)))
+}
+
+/// GET /api/organizations
+/// List the organizations the caller belongs to
+pub async fn list_organizations(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> Result<Json<Vec<OrganizationDto>>, AppError> {
+    let organizations = state
+        .membership_repo
+        .list_organizations_for_user(auth_user.user_id)
+        .await?;
+
+    Ok(Json(organizations.into_iter().map(OrganizationDto::from).collect()))
+}
+
+/// GET /api/organizations/{slug}
+/// Get an organization the caller belongs to
+///
+/// Organizations the caller is not a member of are reported as not found,
+/// so slugs of other tenants are not disclosed.
+pub async fn get_organization(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(slug): Path<String>,
+) -> Result<Json<Organization>, AppError> {
+    let not_found = || AppError::not_found("Organization not found");
+
+    let org = state
+        .org_repo
+        .find_by_slug(&slug.to_lowercase())
+        .await?
+        .ok_or_else(not_found)?;
+
+    if !state.membership_repo.exists(org.id, auth_user.user_id).await? {
+        return Err(not_found());
+    }
+
+    Ok(Json(org))
+}
+
+/// PATCH /api/organizations/{slug}
+/// Update organization settings such as the two-factor policy (owner only)
+pub async fn update_organization(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(slug): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateOrganizationCommand>,
+) -> Result<Json<Organization>, AppError> {
+    let org = state
+        .update_organization_use_case
+        .execute(auth_user.user_id, &slug, payload)
+        .await?;
+
+    Ok(Json(org))
+}
+
+/// POST /api/organizations/{slug}/members
+/// Add an existing user to the organization (owner only)
+///
+/// Subject to the per-user membership cap (`MAX_TENANTS_PER_USER`).
+pub async fn add_member(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(slug): Path<String>,
+    ValidatedJson(payload): ValidatedJson<AddMemberCommand>,
+) -> Result<(StatusCode, Json<TenantMembership>), AppError> {
+    let membership = state
+        .join_organization_use_case
+        .execute(auth_user.user_id, &slug, payload)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(membership)))
+}
+
+/// GET /api/admin/tenants
+/// List all tenants with their user counts [requires super_admin]
+///
+/// Query parameters: `q` (slug or name search), `is_active`, `page` and
+/// `per_page`.
+pub async fn list_tenants(
+    State(state): State<AppState>,
+    Query(query): Query<ListTenantsQuery>,
+) -> Result<Json<Page<TenantDto>>, AppError> {
+    let tenants = state.list_tenants_use_case.execute(query).await?;
+
+    Ok(Json(tenants))
+}
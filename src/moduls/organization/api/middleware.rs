@@ -0,0 +1,146 @@
+// Tenant resolution middleware
+
+use crate::bootstrap::AppState;
+use crate::moduls::organization::domain::Organization;
+use crate::moduls::organization::infra::OrganizationRepository;
+use crate::shared::types::OrganizationId;
+use crate::shared::AppError;
+use axum::{
+    extract::{Request, State},
+    http::{header, header::HeaderName, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+/// Header naming the tenant of a request explicitly
+pub const TENANT_SLUG_HEADER: HeaderName = HeaderName::from_static("x-tenant-slug");
+
+/// Response header carrying the ID of the tenant a response was scoped to
+pub const TENANT_ID_HEADER: HeaderName = HeaderName::from_static("x-tenant-id");
+
+/// Tenant the request is addressed to
+/// Added to request extensions by `tenant_middleware`
+#[derive(Clone, Debug)]
+pub struct TenantContext {
+    pub organization_id: OrganizationId,
+}
+
+/// Tenant resolution middleware
+///
+/// # Flow
+/// 1. Take the slug from `X-Tenant-Slug`, else from the `Host` subdomain
+///    (only when `TENANT_BASE_DOMAIN` is configured)
+/// 2. Load the organization by slug (404 if unknown)
+/// 3. Add TenantContext to request extensions
+/// 4. With `TENANT_RESPONSE_HEADERS`, echo the tenant in `X-Tenant-Id` and
+///    `X-Tenant-Slug` response headers
+///
+/// Requests that name no tenant pass through without a context (and
+/// without tenant response headers).
+pub async fn tenant_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(slug) = tenant_slug(
+        request.headers(),
+        state.config.tenancy.base_domain.as_deref(),
+    ) else {
+        return Ok(next.run(request).await);
+    };
+
+    let organization = state
+        .org_repo
+        .find_by_slug(&slug)
+        .await?
+        .ok_or_else(|| AppError::not_found("Organization not found"))?;
+
+    request.extensions_mut().insert(TenantContext {
+        organization_id: organization.id,
+    });
+
+    let mut response = next.run(request).await;
+    if state.config.tenancy.response_headers {
+        insert_tenant_headers(response.headers_mut(), &organization);
+    }
+
+    Ok(response)
+}
+
+/// Echo the resolved tenant, overriding headers set by the handler
+fn insert_tenant_headers(headers: &mut HeaderMap, organization: &Organization) {
+    if let Ok(id) = HeaderValue::from_str(&organization.id.to_string()) {
+        headers.insert(TENANT_ID_HEADER, id);
+    }
+    if let Ok(slug) = HeaderValue::from_str(&organization.slug) {
+        headers.insert(TENANT_SLUG_HEADER, slug);
+    }
+}
+
+/// Tenant slug named by the request, lowercased
+fn tenant_slug(headers: &HeaderMap, base_domain: Option<&str>) -> Option<String> {
+    let from_header = headers
+        .get(&TENANT_SLUG_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    if let Some(slug) = from_header {
+        return Some(slug.to_lowercase());
+    }
+
+    let host = headers.get(header::HOST).and_then(|v| v.to_str().ok())?;
+    subdomain(host, base_domain?)
+}
+
+/// Single-label subdomain of `base_domain` in `host` (`www` is not a tenant)
+fn subdomain(host: &str, base_domain: &str) -> Option<String> {
+    let host = host.split(':').next()?.to_lowercase();
+    let label = host.strip_suffix(base_domain)?.strip_suffix('.')?;
+
+    if label.is_empty() || label.contains('.') || label == "www" {
+        return None;
+    }
+
+    Some(label.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_subdomain() {
+        assert_eq!(subdomain("acme.example.com", "example.com").as_deref(), Some("acme"));
+        assert_eq!(subdomain("ACME.Example.com:8080", "example.com").as_deref(), Some("acme"));
+        assert_eq!(subdomain("example.com", "example.com"), None);
+        assert_eq!(subdomain("www.example.com", "example.com"), None);
+        assert_eq!(subdomain("a.b.example.com", "example.com"), None);
+        assert_eq!(subdomain("acme.notexample.com", "example.com"), None);
+        assert_eq!(subdomain("acmeexample.com", "example.com"), None);
+    }
+
+    #[test]
+    fn test_header_takes_precedence_over_host() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_static("acme.example.com"));
+
+        assert_eq!(tenant_slug(&headers, Some("example.com")).as_deref(), Some("acme"));
+        // Host is ignored without a base domain
+        assert_eq!(tenant_slug(&headers, None), None);
+
+        headers.insert(&TENANT_SLUG_HEADER, HeaderValue::from_static(" Globex "));
+        assert_eq!(tenant_slug(&headers, Some("example.com")).as_deref(), Some("globex"));
+    }
+
+    #[test]
+    fn test_tenant_headers_name_the_organization() {
+        let organization = Organization::new("Acme".to_string(), "acme").unwrap();
+        let mut headers = HeaderMap::new();
+
+        insert_tenant_headers(&mut headers, &organization);
+
+        assert_eq!(headers[&TENANT_ID_HEADER], organization.id.to_string().as_str());
+        assert_eq!(headers[&TENANT_SLUG_HEADER], "acme");
+    }
+}
@@ -0,0 +1,11 @@
+//! API layer for organization module
+//!
+//! JSON endpoints for creating and listing the caller's organizations,
+//! behind JWT authentication, and the middleware resolving a request's tenant.
+
+pub mod routes;
+pub mod handlers;
+pub mod middleware;
+
+pub use middleware::{tenant_middleware, TenantContext};
+pub use routes::organization_api_routes;
@@ -0,0 +1,35 @@
+use crate::bootstrap::{idempotency::idempotency, AppState};
+use crate::moduls::auth::api::middleware::jwt_auth_middleware;
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, post},
+    Router,
+};
+
+use super::handlers;
+
+/// Organization API routes (JSON / JWT-based authentication)
+///
+/// Routes:
+/// - POST /api/organizations - Create an organization owned by the caller [Idempotency-Key]
+/// - GET /api/organizations - List the caller's organizations
+/// - GET /api/organizations/{slug} - Get one of the caller's organizations
+/// - PATCH /api/organizations/{slug} - Update settings (owner only)
+/// - POST /api/organizations/{slug}/members - Add a user, within their membership cap (owner only)
+pub fn organization_api_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(handlers::list_organizations).post(handlers::create_organization.layer(
+                middleware::from_fn_with_state(state.clone(), idempotency),
+            )),
+        )
+        .route(
+            "/{slug}",
+            get(handlers::get_organization).patch(handlers::update_organization),
+        )
+        .route("/{slug}/members", post(handlers::add_member))
+        // Add JWT authentication middleware to all routes
+        .route_layer(middleware::from_fn_with_state(state, jwt_auth_middleware))
+}
@@ -0,0 +1,158 @@
+use crate::moduls::organization::domain::{Organization, TenantMembership};
+use crate::moduls::organization::infra::{MembershipRepository, OrganizationRepository};
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Command for creating an organization
+#[derive(Debug, serde::Deserialize, Validate)]
+pub struct CreateOrganizationCommand {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    #[validate(length(min = 3, max = 63))]
+    pub slug: String,
+}
+
+/// Use case for creating an organization
+///
+/// Business Logic:
+/// 1. Create Organization entity (validates name and slug)
+/// 2. Slug must not be taken
+/// 3. Owner must be below the membership cap
+/// 4. Save organization
+/// 5. Add the owner as its first member
+///
+/// Request-level rules on `CreateOrganizationCommand` are enforced by the
+/// `ValidatedJson` extractor; the domain re-checks the invariants.
+pub struct CreateOrganizationUseCase {
+    org_repo: Arc<dyn OrganizationRepository>,
+    membership_repo: Arc<dyn MembershipRepository>,
+    max_memberships_per_user: u32,
+}
+
+impl CreateOrganizationUseCase {
+    pub fn new(
+        org_repo: Arc<dyn OrganizationRepository>,
+        membership_repo: Arc<dyn MembershipRepository>,
+        max_memberships_per_user: u32,
+    ) -> Self {
+        Self {
+            org_repo,
+            membership_repo,
+            max_memberships_per_user,
+        }
+    }
+
+    /// Execute the use case
+    ///
+    /// # Errors
+    /// - Validation error for an invalid name or slug
+    /// - Conflict if the slug is taken or the owner's membership cap is reached
+    /// - Database errors
+    pub async fn execute(
+        &self,
+        owner_id: UserId,
+        cmd: CreateOrganizationCommand,
+    ) -> AppResult<Organization> {
+        // 1. Create entity
+        let org = Organization::with_owner(cmd.name, &cmd.slug, owner_id)?;
+
+        // 2. Slug uniqueness (the unique index still guards races)
+        if self.org_repo.find_by_slug(&org.slug).await?.is_some() {
+            return Err(AppError::conflict("Organization slug already exists"));
+        }
+
+        // 3. The owner becomes a member, so the cap applies
+        let count = self.membership_repo.count_for_user(owner_id).await?;
+        if count >= i64::from(self.max_memberships_per_user) {
+            return Err(AppError::conflict(format!(
+                "User cannot belong to more than {} organizations",
+                self.max_memberships_per_user
+            )));
+        }
+
+        // 4. Save organization
+        let org = self.org_repo.save(&org).await?;
+
+        // 5. Owner membership
+        self.membership_repo
+            .add(&TenantMembership::new(org.id, owner_id))
+            .await?;
+
+        tracing::info!("Organization {} created by user {}", org.slug, owner_id);
+
+        Ok(org)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::organization::infra::in_memory::*;
+
+    struct Fixture {
+        use_case: CreateOrganizationUseCase,
+        membership_repo: Arc<InMemoryMembershipRepository>,
+    }
+
+    fn fixture(max: u32) -> Fixture {
+        let org_repo = Arc::new(InMemoryOrganizationRepository::default());
+        let membership_repo = Arc::new(InMemoryMembershipRepository::new(org_repo.clone()));
+
+        Fixture {
+            use_case: CreateOrganizationUseCase::new(org_repo, membership_repo.clone(), max),
+            membership_repo,
+        }
+    }
+
+    fn command(slug: &str) -> CreateOrganizationCommand {
+        CreateOrganizationCommand {
+            name: format!("{} Inc", slug),
+            slug: slug.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_organization_adds_owner_membership() {
+        let f = fixture(5);
+        let owner_id = UserId::new();
+
+        let org = f.use_case.execute(owner_id, command("Acme")).await.unwrap();
+
+        assert_eq!(org.slug, "acme");
+        assert_eq!(org.owner_id, Some(owner_id));
+        assert!(f.membership_repo.exists(org.id, owner_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_create_organization_duplicate_slug() {
+        let f = fixture(5);
+
+        f.use_case.execute(UserId::new(), command("acme")).await.unwrap();
+        let result = f.use_case.execute(UserId::new(), command("ACME")).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_organization_respects_membership_cap() {
+        let f = fixture(1);
+        let owner_id = UserId::new();
+
+        f.use_case.execute(owner_id, command("acme")).await.unwrap();
+        let result = f.use_case.execute(owner_id, command("globex")).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert_eq!(f.membership_repo.count_for_user(owner_id).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_organization_invalid_slug() {
+        let f = fixture(5);
+
+        let result = f.use_case.execute(UserId::new(), command("acme corp")).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+}
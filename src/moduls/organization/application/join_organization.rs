@@ -0,0 +1,178 @@
+use crate::moduls::organization::domain::TenantMembership;
+use crate::moduls::organization::infra::{MembershipRepository, OrganizationRepository};
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Command for adding a member to an organization
+#[derive(Debug, Clone, serde::Deserialize, Validate)]
+pub struct AddMemberCommand {
+    pub user_id: UserId,
+}
+
+/// Use case for adding a user to an organization
+///
+/// Business Logic:
+/// 1. Organization must exist
+/// 2. Only the owner may add members
+/// 3. The user must exist and not already be a member
+/// 4. The user must be below the membership cap; the check and the insert
+///    are atomic, so concurrent joins can't exceed it
+pub struct JoinOrganizationUseCase {
+    org_repo: Arc<dyn OrganizationRepository>,
+    membership_repo: Arc<dyn MembershipRepository>,
+    max_memberships_per_user: u32,
+}
+
+impl JoinOrganizationUseCase {
+    pub fn new(
+        org_repo: Arc<dyn OrganizationRepository>,
+        membership_repo: Arc<dyn MembershipRepository>,
+        max_memberships_per_user: u32,
+    ) -> Self {
+        Self {
+            org_repo,
+            membership_repo,
+            max_memberships_per_user,
+        }
+    }
+
+    /// Execute the use case on behalf of `actor_id`
+    ///
+    /// # Errors
+    /// - NotFound if the organization or the user doesn't exist
+    /// - Authorization error if the caller is not the owner
+    /// - Conflict if already a member or the membership cap is reached
+    /// - Database errors
+    pub async fn execute(
+        &self,
+        actor_id: UserId,
+        slug: &str,
+        cmd: AddMemberCommand,
+    ) -> AppResult<TenantMembership> {
+        // 1. Organization must exist
+        let org = self
+            .org_repo
+            .find_by_slug(&slug.trim().to_lowercase())
+            .await?
+            .ok_or_else(|| AppError::not_found("Organization not found"))?;
+
+        // 2. Owner only
+        if org.owner_id != Some(actor_id) {
+            return Err(AppError::authorization(
+                "Only the organization owner can add members",
+            ));
+        }
+
+        // 3-4. Create membership within the cap
+        let membership = TenantMembership::new(org.id, cmd.user_id);
+        let membership = self
+            .membership_repo
+            .add_within_limit(&membership, self.max_memberships_per_user)
+            .await?
+            .ok_or_else(|| {
+                AppError::conflict(format!(
+                    "User cannot belong to more than {} organizations",
+                    self.max_memberships_per_user
+                ))
+            })?;
+
+        tracing::info!("User {} added user {} to organization {}", actor_id, cmd.user_id, org.slug);
+
+        Ok(membership)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::organization::domain::Organization;
+    use crate::moduls::organization::infra::in_memory::*;
+
+    const OWNER_ID: UserId = UserId::from_uuid(uuid::Uuid::from_u128(1));
+
+    struct Fixture {
+        use_case: JoinOrganizationUseCase,
+        org_repo: Arc<InMemoryOrganizationRepository>,
+    }
+
+    fn fixture(max: u32) -> Fixture {
+        let org_repo = Arc::new(InMemoryOrganizationRepository::default());
+        let membership_repo = Arc::new(InMemoryMembershipRepository::new(org_repo.clone()));
+
+        Fixture {
+            use_case: JoinOrganizationUseCase::new(org_repo.clone(), membership_repo, max),
+            org_repo,
+        }
+    }
+
+    async fn create_org(f: &Fixture, slug: &str) {
+        let org = Organization::with_owner(slug.to_string(), slug, OWNER_ID).unwrap();
+        f.org_repo.save(&org).await.unwrap();
+    }
+
+    async fn add(f: &Fixture, slug: &str, user_id: UserId) -> AppResult<TenantMembership> {
+        f.use_case.execute(OWNER_ID, slug, AddMemberCommand { user_id }).await
+    }
+
+    #[tokio::test]
+    async fn test_join_up_to_cap() {
+        let f = fixture(2);
+        let user_id = UserId::new();
+
+        for slug in ["acme", "globex"] {
+            create_org(&f, slug).await;
+            assert!(add(&f, slug, user_id).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_join_beyond_cap_rejected() {
+        let f = fixture(2);
+        let user_id = UserId::new();
+
+        for slug in ["acme", "globex"] {
+            create_org(&f, slug).await;
+            add(&f, slug, user_id).await.unwrap();
+        }
+
+        create_org(&f, "initech").await;
+        let result = add(&f, "initech", user_id).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        // Another user is unaffected by the first user's memberships
+        assert!(add(&f, "initech", UserId::new()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_join_twice_rejected() {
+        let f = fixture(5);
+        let user_id = UserId::new();
+        create_org(&f, "acme").await;
+
+        add(&f, "acme", user_id).await.unwrap();
+
+        assert!(matches!(add(&f, "acme", user_id).await, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_only_owner_adds_members() {
+        let f = fixture(5);
+        create_org(&f, "acme").await;
+
+        let result = f
+            .use_case
+            .execute(UserId::new(), "acme", AddMemberCommand { user_id: UserId::new() })
+            .await;
+
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_join_unknown_organization() {
+        let f = fixture(5);
+
+        assert!(matches!(add(&f, "acme", UserId::new()).await, Err(AppError::NotFound(_))));
+    }
+}
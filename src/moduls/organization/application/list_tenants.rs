@@ -0,0 +1,132 @@
+use crate::moduls::organization::domain::TenantDto;
+use crate::moduls::organization::infra::{OrganizationRepository, TenantFilter};
+use crate::shared::pagination::{Page, PageRequest};
+use crate::shared::AppResult;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Query of the admin tenants listing, from query parameters
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ListTenantsQuery {
+    /// Case-insensitive substring of the slug or name
+    pub q: Option<String>,
+    pub is_active: Option<bool>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+/// List Tenants Use Case
+/// Lists every tenant with its user count, for super-admins
+///
+/// Tenants are ordered by slug; `per_page` is capped by
+/// `TENANT_ADMIN_MAX_PER_PAGE`.
+pub struct ListTenantsUseCase {
+    org_repo: Arc<dyn OrganizationRepository>,
+    max_per_page: u32,
+}
+
+impl ListTenantsUseCase {
+    pub fn new(org_repo: Arc<dyn OrganizationRepository>, max_per_page: u32) -> Self {
+        Self {
+            org_repo,
+            max_per_page,
+        }
+    }
+
+    /// Execute the use case to list a page of tenants
+    pub async fn execute(&self, query: ListTenantsQuery) -> AppResult<Page<TenantDto>> {
+        let filter = TenantFilter {
+            search: query
+                .q
+                .map(|q| q.trim().to_string())
+                .filter(|q| !q.is_empty()),
+            is_active: query.is_active,
+        };
+        let page = PageRequest::new(query.page, query.per_page, self.max_per_page);
+
+        self.org_repo.list_tenants(&filter, page).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::organization::domain::Organization;
+    use crate::moduls::organization::infra::in_memory::InMemoryOrganizationRepository;
+
+    fn use_case() -> ListTenantsUseCase {
+        let repo = Arc::new(InMemoryOrganizationRepository::default());
+        {
+            let mut organizations = repo.organizations.lock().unwrap();
+            let mut user_counts = repo.user_counts.lock().unwrap();
+            for (name, slug, users) in [
+                ("Acme Inc", "acme", 3),
+                ("Globex", "globex", 1),
+                ("Acme Labs", "labs", 0),
+            ] {
+                let org = Organization::new(name.to_string(), slug).unwrap();
+                user_counts.insert(org.id, users);
+                organizations.push(org);
+            }
+            organizations[1].is_active = false;
+        }
+        ListTenantsUseCase::new(repo, 2)
+    }
+
+    fn slugs(page: &Page<TenantDto>) -> Vec<&str> {
+        page.items.iter().map(|t| t.slug.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_slug_or_name() {
+        let page = use_case()
+            .execute(ListTenantsQuery {
+                q: Some(" ACME ".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(slugs(&page), ["acme", "labs"]);
+        assert_eq!(page.items[0].user_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_active_filter() {
+        let use_case = use_case();
+
+        let inactive = use_case
+            .execute(ListTenantsQuery {
+                is_active: Some(false),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(slugs(&inactive), ["globex"]);
+
+        let active = use_case
+            .execute(ListTenantsQuery {
+                is_active: Some(true),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(slugs(&active), ["acme", "labs"]);
+    }
+
+    #[tokio::test]
+    async fn test_page_size_is_capped() {
+        let page = use_case()
+            .execute(ListTenantsQuery {
+                page: Some(2),
+                per_page: Some(50),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(page.per_page, 2);
+        assert_eq!(page.total, 3);
+        assert_eq!(slugs(&page), ["labs"]);
+    }
+}
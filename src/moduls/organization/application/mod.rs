@@ -0,0 +1,14 @@
+//! Application layer for organization module
+//!
+//! Use cases orchestrating organizations and tenant memberships.
+
+pub mod create_organization;
+pub mod join_organization;
+pub mod list_tenants;
+pub mod update_organization;
+
+// Re-export use cases
+pub use create_organization::{CreateOrganizationCommand, CreateOrganizationUseCase};
+pub use join_organization::{AddMemberCommand, JoinOrganizationUseCase};
+pub use list_tenants::{ListTenantsQuery, ListTenantsUseCase};
+pub use update_organization::{UpdateOrganizationCommand, UpdateOrganizationUseCase};
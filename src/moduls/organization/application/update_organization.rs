@@ -0,0 +1,120 @@
+use crate::moduls::organization::domain::{Organization, TwoFactorPolicy};
+use crate::moduls::organization::infra::OrganizationRepository;
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Command for a partial organization update (PATCH semantics)
+///
+/// Absent fields are left unchanged.
+#[derive(Debug, Default, serde::Deserialize, Validate)]
+pub struct UpdateOrganizationCommand {
+    #[serde(default)]
+    pub two_factor_policy: Option<TwoFactorPolicy>,
+}
+
+/// Use case for updating an organization's settings
+///
+/// Business Logic:
+/// 1. Organization must exist
+/// 2. Only the owner may change settings
+/// 3. Apply the provided fields
+/// 4. Save organization
+pub struct UpdateOrganizationUseCase {
+    org_repo: Arc<dyn OrganizationRepository>,
+}
+
+impl UpdateOrganizationUseCase {
+    pub fn new(org_repo: Arc<dyn OrganizationRepository>) -> Self {
+        Self { org_repo }
+    }
+
+    /// Execute the use case
+    ///
+    /// # Errors
+    /// - NotFound if the organization doesn't exist
+    /// - Authorization error if the user is not the owner
+    /// - Database errors
+    pub async fn execute(
+        &self,
+        user_id: UserId,
+        slug: &str,
+        cmd: UpdateOrganizationCommand,
+    ) -> AppResult<Organization> {
+        // 1. Organization must exist
+        let mut org = self
+            .org_repo
+            .find_by_slug(&slug.trim().to_lowercase())
+            .await?
+            .ok_or_else(|| AppError::not_found("Organization not found"))?;
+
+        // 2. Owner only
+        if org.owner_id != Some(user_id) {
+            return Err(AppError::authorization(
+                "Only the organization owner can change its settings",
+            ));
+        }
+
+        // 3. Apply changes
+        if let Some(policy) = cmd.two_factor_policy {
+            org.set_two_factor_policy(policy);
+            tracing::info!("Organization {} two-factor policy set to {:?}", org.slug, policy);
+        }
+
+        // 4. Save organization
+        self.org_repo.update(&org).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::organization::infra::in_memory::InMemoryOrganizationRepository;
+
+    async fn fixture(owner_id: UserId) -> UpdateOrganizationUseCase {
+        let org_repo = Arc::new(InMemoryOrganizationRepository::default());
+        let org = Organization::with_owner("Acme".to_string(), "acme", owner_id).unwrap();
+        org_repo.save(&org).await.unwrap();
+        UpdateOrganizationUseCase::new(org_repo)
+    }
+
+    fn set_policy(policy: TwoFactorPolicy) -> UpdateOrganizationCommand {
+        UpdateOrganizationCommand {
+            two_factor_policy: Some(policy),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_owner_updates_two_factor_policy() {
+        let owner_id = UserId::new();
+        let use_case = fixture(owner_id).await;
+
+        let org = use_case
+            .execute(owner_id, "ACME", set_policy(TwoFactorPolicy::Required))
+            .await
+            .unwrap();
+        assert_eq!(org.two_factor_policy, TwoFactorPolicy::Required);
+
+        // Empty patch leaves the policy alone
+        let org = use_case
+            .execute(owner_id, "acme", UpdateOrganizationCommand::default())
+            .await
+            .unwrap();
+        assert_eq!(org.two_factor_policy, TwoFactorPolicy::Required);
+    }
+
+    #[tokio::test]
+    async fn test_non_owner_cannot_update() {
+        let use_case = fixture(UserId::new()).await;
+
+        let result = use_case
+            .execute(UserId::new(), "acme", set_policy(TwoFactorPolicy::Required))
+            .await;
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+
+        let result = use_case
+            .execute(UserId::new(), "globex", set_policy(TwoFactorPolicy::Required))
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
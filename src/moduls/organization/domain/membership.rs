@@ -0,0 +1,21 @@
+use crate::shared::types::*;
+use serde::Serialize;
+
+/// Membership of a user in an organization (tenant)
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow, Serialize)]
+pub struct TenantMembership {
+    pub organization_id: OrganizationId,
+    pub user_id: UserId,
+    pub created_at: Timestamp,
+}
+
+impl TenantMembership {
+    /// Create a new membership
+    pub fn new(organization_id: OrganizationId, user_id: UserId) -> Self {
+        Self {
+            organization_id,
+            user_id,
+            created_at: now(),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Domain layer for organization module
+//!
+//! Contains the Organization aggregate and tenant memberships.
+
+pub mod organization;
+pub mod membership;
+
+// Re-export commonly used types
+pub use organization::{Organization, OrganizationDto, TenantDto, TwoFactorPolicy};
+pub use membership::TenantMembership;
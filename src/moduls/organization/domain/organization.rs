@@ -0,0 +1,224 @@
+use crate::shared::{types::*, AppError, AppResult};
+use serde::{Deserialize, Serialize};
+
+/// Which members must have two-factor authentication to log into a tenant
+#[derive(Debug, Clone, Copy, Default, sqlx::Type, Serialize, Deserialize, PartialEq, Eq)]
+#[sqlx(type_name = "two_factor_policy", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TwoFactorPolicy {
+    /// 2FA is up to each user
+    #[default]
+    Optional,
+    /// Every member must have 2FA enabled
+    Required,
+    /// The organization's owner must have 2FA enabled
+    RequiredForAdmins,
+}
+
+/// Organization aggregate root
+///
+/// An organization is a tenant. Its slug identifies it in URLs and
+/// headers, so it must be unique and URL-safe.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct Organization {
+    pub id: OrganizationId,
+    pub name: String,
+    pub slug: String,
+    /// User who created the organization (`None` for unowned organizations)
+    pub owner_id: Option<UserId>,
+    /// Two-factor mandate enforced at login
+    pub two_factor_policy: TwoFactorPolicy,
+    /// Inactive tenants are kept but hidden from filtered admin listings
+    pub is_active: bool,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+impl Organization {
+    /// Create new Organization entity
+    ///
+    /// Business Rules:
+    /// - Name must not be empty, max 255 chars
+    /// - Slug must be 3-63 chars of lowercase letters, digits, and hyphens,
+    ///   and cannot start or end with a hyphen
+    /// - Slug must be unique (enforced by repository)
+    pub fn new(name: String, slug: &str) -> AppResult<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::validation("Organization name cannot be empty"));
+        }
+
+        if name.len() > 255 {
+            return Err(AppError::validation("Organization name must be 255 characters or less"));
+        }
+
+        let slug = Self::validate_slug(slug)?;
+        let now = now();
+
+        Ok(Self {
+            id: OrganizationId::new(),
+            name: name.to_string(),
+            slug,
+            owner_id: None,
+            two_factor_policy: TwoFactorPolicy::default(),
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Create new Organization entity owned by `owner_id`
+    ///
+    /// Same rules as `new`.
+    pub fn with_owner(name: String, slug: &str, owner_id: UserId) -> AppResult<Self> {
+        Ok(Self {
+            owner_id: Some(owner_id),
+            ..Self::new(name, slug)?
+        })
+    }
+
+    /// Whether `user_id` needs 2FA enabled to log into this organization
+    ///
+    /// The owner is the organization's admin for `RequiredForAdmins`.
+    pub fn requires_two_factor(&self, user_id: UserId) -> bool {
+        match self.two_factor_policy {
+            TwoFactorPolicy::Optional => false,
+            TwoFactorPolicy::Required => true,
+            TwoFactorPolicy::RequiredForAdmins => self.owner_id == Some(user_id),
+        }
+    }
+
+    /// Change the two-factor policy
+    pub fn set_two_factor_policy(&mut self, policy: TwoFactorPolicy) {
+        self.two_factor_policy = policy;
+        self.updated_at = now();
+    }
+
+    /// Validate and normalize an organization slug
+    pub fn validate_slug(slug: &str) -> AppResult<String> {
+        let slug = slug.trim().to_lowercase();
+
+        if slug.len() < 3 || slug.len() > 63 {
+            return Err(AppError::validation("Slug must be between 3 and 63 characters"));
+        }
+
+        if !slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+            return Err(AppError::validation(
+                "Slug may only contain lowercase letters, digits, and hyphens",
+            ));
+        }
+
+        if slug.starts_with('-') || slug.ends_with('-') {
+            return Err(AppError::validation("Slug cannot start or end with a hyphen"));
+        }
+
+        Ok(slug)
+    }
+}
+
+/// DTO for organization response
+#[derive(Debug, Clone, Serialize)]
+pub struct OrganizationDto {
+    pub id: OrganizationId,
+    pub name: String,
+    pub slug: String,
+}
+
+impl From<Organization> for OrganizationDto {
+    fn from(org: Organization) -> Self {
+        Self {
+            id: org.id,
+            name: org.name,
+            slug: org.slug,
+        }
+    }
+}
+
+/// DTO for the admin tenants listing
+///
+/// `user_count` covers users scoped to the tenant and its members, each
+/// counted once.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct TenantDto {
+    pub id: OrganizationId,
+    pub name: String,
+    pub slug: String,
+    pub owner_id: Option<UserId>,
+    pub is_active: bool,
+    pub user_count: i64,
+    pub created_at: Timestamp,
+}
+
+impl TenantDto {
+    pub fn new(org: Organization, user_count: i64) -> Self {
+        Self {
+            id: org.id,
+            name: org.name,
+            slug: org.slug,
+            owner_id: org.owner_id,
+            is_active: org.is_active,
+            user_count,
+            created_at: org.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_organization() {
+        let org = Organization::new("Acme Inc".to_string(), "Acme").unwrap();
+
+        assert_eq!(org.name, "Acme Inc");
+        assert_eq!(org.slug, "acme");
+        assert_eq!(org.owner_id, None);
+        assert!(org.is_active);
+    }
+
+    #[test]
+    fn test_create_organization_with_owner() {
+        let owner_id = UserId::new();
+        let org = Organization::with_owner("Acme Inc".to_string(), "acme", owner_id).unwrap();
+
+        assert_eq!(org.owner_id, Some(owner_id));
+        assert!(Organization::with_owner("Acme".to_string(), "a", owner_id).is_err());
+    }
+
+    #[test]
+    fn test_create_organization_empty_name() {
+        assert!(Organization::new("   ".to_string(), "acme").is_err());
+    }
+
+    #[test]
+    fn test_two_factor_policy() {
+        let owner_id = UserId::new();
+        let member_id = UserId::new();
+        let mut org = Organization::with_owner("Acme".to_string(), "acme", owner_id).unwrap();
+
+        assert!(!org.requires_two_factor(owner_id));
+
+        org.set_two_factor_policy(TwoFactorPolicy::RequiredForAdmins);
+        assert!(org.requires_two_factor(owner_id));
+        assert!(!org.requires_two_factor(member_id));
+
+        org.set_two_factor_policy(TwoFactorPolicy::Required);
+        assert!(org.requires_two_factor(member_id));
+    }
+
+    #[test]
+    fn test_invalid_slugs() {
+        assert!(Organization::validate_slug("ab").is_err());
+        assert!(Organization::validate_slug("acme corp").is_err());
+        assert!(Organization::validate_slug("-acme").is_err());
+        assert!(Organization::validate_slug("acme_corp").is_err());
+        assert!(Organization::validate_slug(&"a".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn test_valid_slugs() {
+        assert_eq!(Organization::validate_slug("acme-42").unwrap(), "acme-42");
+        assert_eq!(Organization::validate_slug(" ACME ").unwrap(), "acme");
+    }
+}
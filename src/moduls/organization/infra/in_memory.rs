@@ -0,0 +1,165 @@
+//! In-memory repository implementations for unit tests
+
+use super::{MembershipRepository, OrganizationRepository, TenantFilter};
+use crate::moduls::organization::domain::{Organization, TenantDto, TenantMembership};
+use crate::shared::pagination::{Page, PageRequest};
+use crate::shared::{types::*, AppError, AppResult};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// In-memory OrganizationRepository
+///
+/// Knows no users; `user_counts` stands in for them in tenant listings.
+#[derive(Default)]
+pub struct InMemoryOrganizationRepository {
+    pub organizations: Mutex<Vec<Organization>>,
+    pub user_counts: Mutex<HashMap<OrganizationId, i64>>,
+}
+
+#[async_trait]
+impl OrganizationRepository for InMemoryOrganizationRepository {
+    async fn save(&self, org: &Organization) -> AppResult<Organization> {
+        let mut organizations = self.organizations.lock().unwrap();
+        if organizations.iter().any(|o| o.slug == org.slug) {
+            return Err(AppError::conflict("Organization slug already exists"));
+        }
+        organizations.push(org.clone());
+        Ok(org.clone())
+    }
+
+    async fn find_by_id(&self, id: OrganizationId) -> AppResult<Option<Organization>> {
+        let organizations = self.organizations.lock().unwrap();
+        Ok(organizations.iter().find(|o| o.id == id).cloned())
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> AppResult<Option<Organization>> {
+        let organizations = self.organizations.lock().unwrap();
+        Ok(organizations.iter().find(|o| o.slug == slug).cloned())
+    }
+
+    async fn update(&self, org: &Organization) -> AppResult<Organization> {
+        let mut organizations = self.organizations.lock().unwrap();
+        let existing = organizations
+            .iter_mut()
+            .find(|o| o.id == org.id)
+            .ok_or_else(|| AppError::not_found("Organization not found"))?;
+        *existing = org.clone();
+        Ok(org.clone())
+    }
+
+    async fn list_tenants(
+        &self,
+        filter: &TenantFilter,
+        page: PageRequest,
+    ) -> AppResult<Page<TenantDto>> {
+        let search = filter.search.as_ref().map(|s| s.to_lowercase());
+        let mut matching: Vec<_> = self
+            .organizations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|o| {
+                search.as_ref().is_none_or(|s| {
+                    o.slug.contains(s.as_str()) || o.name.to_lowercase().contains(s.as_str())
+                })
+            })
+            .filter(|o| filter.is_active.is_none_or(|active| o.is_active == active))
+            .cloned()
+            .collect();
+        matching.sort_by(|a, b| a.slug.cmp(&b.slug));
+
+        let user_counts = self.user_counts.lock().unwrap();
+        let total = matching.len() as u64;
+        let items = matching
+            .into_iter()
+            .skip(page.offset() as usize)
+            .take(page.per_page as usize)
+            .map(|o| {
+                let count = user_counts.get(&o.id).copied().unwrap_or(0);
+                TenantDto::new(o, count)
+            })
+            .collect();
+
+        Ok(Page::new(items, page, total))
+    }
+}
+
+/// In-memory MembershipRepository
+///
+/// Shares the organization store so `list_organizations_for_user` can join.
+pub struct InMemoryMembershipRepository {
+    pub organizations: Arc<InMemoryOrganizationRepository>,
+    pub memberships: Mutex<Vec<TenantMembership>>,
+}
+
+impl InMemoryMembershipRepository {
+    pub fn new(organizations: Arc<InMemoryOrganizationRepository>) -> Self {
+        Self {
+            organizations,
+            memberships: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl MembershipRepository for InMemoryMembershipRepository {
+    async fn add(&self, membership: &TenantMembership) -> AppResult<TenantMembership> {
+        let mut memberships = self.memberships.lock().unwrap();
+        if memberships.iter().any(|m| {
+            m.organization_id == membership.organization_id && m.user_id == membership.user_id
+        }) {
+            return Err(AppError::conflict("User is already a member of this organization"));
+        }
+        memberships.push(membership.clone());
+        Ok(membership.clone())
+    }
+
+    async fn add_within_limit(
+        &self,
+        membership: &TenantMembership,
+        max: u32,
+    ) -> AppResult<Option<TenantMembership>> {
+        let mut memberships = self.memberships.lock().unwrap();
+        if memberships.iter().any(|m| {
+            m.organization_id == membership.organization_id && m.user_id == membership.user_id
+        }) {
+            return Err(AppError::conflict("User is already a member of this organization"));
+        }
+        let count = memberships.iter().filter(|m| m.user_id == membership.user_id).count();
+        if count >= max as usize {
+            return Ok(None);
+        }
+        memberships.push(membership.clone());
+        Ok(Some(membership.clone()))
+    }
+
+    async fn exists(&self, organization_id: OrganizationId, user_id: UserId) -> AppResult<bool> {
+        let memberships = self.memberships.lock().unwrap();
+        Ok(memberships
+            .iter()
+            .any(|m| m.organization_id == organization_id && m.user_id == user_id))
+    }
+
+    async fn count_for_user(&self, user_id: UserId) -> AppResult<i64> {
+        let memberships = self.memberships.lock().unwrap();
+        Ok(memberships.iter().filter(|m| m.user_id == user_id).count() as i64)
+    }
+
+    async fn list_organizations_for_user(&self, user_id: UserId) -> AppResult<Vec<Organization>> {
+        let org_ids: Vec<_> = self
+            .memberships
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.user_id == user_id)
+            .map(|m| m.organization_id)
+            .collect();
+
+        let organizations = self.organizations.organizations.lock().unwrap();
+        Ok(org_ids
+            .iter()
+            .filter_map(|id| organizations.iter().find(|o| o.id == *id).cloned())
+            .collect())
+    }
+}
@@ -0,0 +1,15 @@
+//! Infrastructure layer for organization module
+//!
+//! Contains PostgreSQL implementations of the organization repositories.
+
+pub mod postgres_organization_repository;
+pub mod postgres_membership_repository;
+
+#[cfg(test)]
+pub mod in_memory;
+
+// Re-export repository traits and implementations
+pub use postgres_organization_repository::{
+    OrganizationRepository, PostgresOrganizationRepository, TenantFilter,
+};
+pub use postgres_membership_repository::{MembershipRepository, PostgresMembershipRepository};
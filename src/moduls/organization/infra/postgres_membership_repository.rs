@@ -0,0 +1,176 @@
+use crate::moduls::organization::domain::{Organization, TenantMembership};
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// MembershipRepository trait defining tenant membership persistence
+#[async_trait]
+pub trait MembershipRepository: Send + Sync {
+    /// Add a user to an organization
+    ///
+    /// # Errors
+    /// - Conflict if the user is already a member
+    /// - Database errors
+    async fn add(&self, membership: &TenantMembership) -> AppResult<TenantMembership>;
+
+    /// Add a user to an organization unless they already belong to `max`
+    /// organizations
+    ///
+    /// Checking the count and adding are atomic, so concurrent joins can't
+    /// exceed the cap. Returns None when the cap is reached.
+    ///
+    /// # Errors
+    /// - NotFound if the user doesn't exist
+    /// - Conflict if the user is already a member
+    /// - Database errors
+    async fn add_within_limit(
+        &self,
+        membership: &TenantMembership,
+        max: u32,
+    ) -> AppResult<Option<TenantMembership>>;
+
+    /// Check whether a user belongs to an organization
+    async fn exists(&self, organization_id: OrganizationId, user_id: UserId) -> AppResult<bool>;
+
+    /// Count the organizations a user belongs to
+    async fn count_for_user(&self, user_id: UserId) -> AppResult<i64>;
+
+    /// List the organizations a user belongs to, oldest membership first
+    async fn list_organizations_for_user(&self, user_id: UserId) -> AppResult<Vec<Organization>>;
+}
+
+/// PostgreSQL implementation of MembershipRepository
+pub struct PostgresMembershipRepository {
+    db: DbPools,
+}
+
+impl PostgresMembershipRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl MembershipRepository for PostgresMembershipRepository {
+    async fn add(&self, membership: &TenantMembership) -> AppResult<TenantMembership> {
+        let result = sqlx::query_as::<_, TenantMembership>(
+            r#"
+            INSERT INTO tenant_memberships (organization_id, user_id, created_at)
+            VALUES ($1, $2, $3)
+            RETURNING organization_id, user_id, created_at
+            "#,
+        )
+        .bind(membership.organization_id)
+        .bind(membership.user_id)
+        .bind(membership.created_at)
+        .fetch_one(self.db.writer())
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(db_err) = &e {
+                if db_err.is_unique_violation() {
+                    return AppError::conflict("User is already a member of this organization");
+                }
+            }
+            AppError::internal(format!("Failed to add membership: {}", e))
+        })?;
+
+        Ok(result)
+    }
+
+    async fn add_within_limit(
+        &self,
+        membership: &TenantMembership,
+        max: u32,
+    ) -> AppResult<Option<TenantMembership>> {
+        let db_error = |e: sqlx::Error| AppError::internal(format!("Failed to add membership: {}", e));
+        let mut tx = self.db.writer().begin().await.map_err(db_error)?;
+
+        // Joins of the same user queue on their row; each count then runs
+        // in a statement started after the previous join committed
+        let user = sqlx::query("SELECT 1 FROM users WHERE id = $1 FOR UPDATE")
+            .bind(membership.user_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        if user.is_none() {
+            return Err(AppError::not_found("User not found"));
+        }
+
+        let result = sqlx::query_as::<_, TenantMembership>(
+            r#"
+            INSERT INTO tenant_memberships (organization_id, user_id, created_at)
+            SELECT $1, $2, $3
+            WHERE (SELECT COUNT(*) FROM tenant_memberships WHERE user_id = $2) < $4
+            RETURNING organization_id, user_id, created_at
+            "#,
+        )
+        .bind(membership.organization_id)
+        .bind(membership.user_id)
+        .bind(membership.created_at)
+        .bind(i64::from(max))
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(db_err) = &e {
+                if db_err.is_unique_violation() {
+                    return AppError::conflict("User is already a member of this organization");
+                }
+            }
+            db_error(e)
+        })?;
+
+        tx.commit().await.map_err(db_error)?;
+        Ok(result)
+    }
+
+    async fn exists(&self, organization_id: OrganizationId, user_id: UserId) -> AppResult<bool> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM tenant_memberships
+                WHERE organization_id = $1 AND user_id = $2
+            )
+            "#,
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .fetch_one(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to check membership: {}", e)))?;
+
+        Ok(exists)
+    }
+
+    async fn count_for_user(&self, user_id: UserId) -> AppResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM tenant_memberships
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to count memberships: {}", e)))?;
+
+        Ok(count)
+    }
+
+    async fn list_organizations_for_user(&self, user_id: UserId) -> AppResult<Vec<Organization>> {
+        let result = sqlx::query_as::<_, Organization>(
+            r#"
+            SELECT o.id, o.name, o.slug, o.owner_id, o.two_factor_policy, o.is_active, o.created_at, o.updated_at
+            FROM organizations o
+            JOIN tenant_memberships m ON m.organization_id = o.id
+            WHERE m.user_id = $1
+            ORDER BY m.created_at, o.slug
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to list organizations: {}", e)))?;
+
+        Ok(result)
+    }
+}
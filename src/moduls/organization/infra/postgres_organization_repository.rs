@@ -0,0 +1,214 @@
+use crate::moduls::organization::domain::{Organization, TenantDto};
+use crate::shared::pagination::{contains_pattern, Page, PageRequest};
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+
+/// Filters of the admin tenants listing; `None` matches every tenant
+#[derive(Debug, Clone, Default)]
+pub struct TenantFilter {
+    /// Case-insensitive substring of the slug or name
+    pub search: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+impl TenantFilter {
+    /// `ILIKE` pattern for `search`, with wildcards in it matched literally
+    fn search_pattern(&self) -> Option<String> {
+        self.search.as_deref().map(contains_pattern)
+    }
+}
+
+/// OrganizationRepository trait defining organization persistence operations
+#[async_trait]
+pub trait OrganizationRepository: Send + Sync {
+    /// Save new organization to database
+    ///
+    /// # Errors
+    /// - Conflict if slug already exists
+    /// - Database errors
+    async fn save(&self, org: &Organization) -> AppResult<Organization>;
+
+    /// Find organization by ID
+    async fn find_by_id(&self, id: OrganizationId) -> AppResult<Option<Organization>>;
+
+    /// Find organization by slug
+    async fn find_by_slug(&self, slug: &str) -> AppResult<Option<Organization>>;
+
+    /// Update existing organization
+    ///
+    /// # Errors
+    /// - NotFound if organization doesn't exist
+    /// - Database errors
+    async fn update(&self, org: &Organization) -> AppResult<Organization>;
+
+    /// List tenants matching `filter` by slug, with their user counts
+    async fn list_tenants(
+        &self,
+        filter: &TenantFilter,
+        page: PageRequest,
+    ) -> AppResult<Page<TenantDto>>;
+}
+
+/// PostgreSQL implementation of OrganizationRepository
+pub struct PostgresOrganizationRepository {
+    db: DbPools,
+}
+
+impl PostgresOrganizationRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl OrganizationRepository for PostgresOrganizationRepository {
+    async fn save(&self, org: &Organization) -> AppResult<Organization> {
+        let result = sqlx::query_as::<_, Organization>(
+            r#"
+            INSERT INTO organizations (id, name, slug, owner_id, two_factor_policy, is_active, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, name, slug, owner_id, two_factor_policy, is_active, created_at, updated_at
+            "#,
+        )
+        .bind(org.id)
+        .bind(&org.name)
+        .bind(&org.slug)
+        .bind(org.owner_id)
+        .bind(org.two_factor_policy)
+        .bind(org.is_active)
+        .bind(org.created_at)
+        .bind(org.updated_at)
+        .fetch_one(self.db.writer())
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(db_err) = &e {
+                if db_err.is_unique_violation() {
+                    return AppError::conflict("Organization slug already exists");
+                }
+            }
+            AppError::internal(format!("Failed to save organization: {}", e))
+        })?;
+
+        Ok(result)
+    }
+
+    async fn find_by_id(&self, id: OrganizationId) -> AppResult<Option<Organization>> {
+        let result = sqlx::query_as::<_, Organization>(
+            r#"
+            SELECT id, name, slug, owner_id, two_factor_policy, is_active, created_at, updated_at
+            FROM organizations
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find organization: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> AppResult<Option<Organization>> {
+        let result = sqlx::query_as::<_, Organization>(
+            r#"
+            SELECT id, name, slug, owner_id, two_factor_policy, is_active, created_at, updated_at
+            FROM organizations
+            WHERE slug = $1
+            "#,
+        )
+        .bind(slug)
+        .fetch_optional(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find organization: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn update(&self, org: &Organization) -> AppResult<Organization> {
+        let result = sqlx::query_as::<_, Organization>(
+            r#"
+            UPDATE organizations
+            SET name = $2, two_factor_policy = $3, is_active = $4, updated_at = $5
+            WHERE id = $1
+            RETURNING id, name, slug, owner_id, two_factor_policy, is_active, created_at, updated_at
+            "#,
+        )
+        .bind(org.id)
+        .bind(&org.name)
+        .bind(org.two_factor_policy)
+        .bind(org.is_active)
+        .bind(org.updated_at)
+        .fetch_optional(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to update organization: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Organization not found"))?;
+
+        Ok(result)
+    }
+
+    async fn list_tenants(
+        &self,
+        filter: &TenantFilter,
+        page: PageRequest,
+    ) -> AppResult<Page<TenantDto>> {
+        let pattern = filter.search_pattern();
+
+        let total = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM organizations o
+            WHERE ($1::text IS NULL OR o.slug ILIKE $1 OR o.name ILIKE $1)
+              AND ($2::boolean IS NULL OR o.is_active = $2)
+            "#,
+        )
+        .bind(&pattern)
+        .bind(filter.is_active)
+        .fetch_one(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to count tenants: {}", e)))?;
+
+        // Users scoped to the tenant and members, counted once each
+        let tenants = sqlx::query_as::<_, TenantDto>(
+            r#"
+            SELECT o.id, o.name, o.slug, o.owner_id, o.is_active, o.created_at,
+                (
+                    SELECT COUNT(*) FROM (
+                        SELECT id FROM users WHERE tenant_id = o.id
+                        UNION
+                        SELECT user_id FROM tenant_memberships WHERE organization_id = o.id
+                    ) tenant_users
+                ) AS user_count
+            FROM organizations o
+            WHERE ($1::text IS NULL OR o.slug ILIKE $1 OR o.name ILIKE $1)
+              AND ($2::boolean IS NULL OR o.is_active = $2)
+            ORDER BY o.slug
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(&pattern)
+        .bind(filter.is_active)
+        .bind(page.limit())
+        .bind(page.offset())
+        .fetch_all(self.db.reader())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to list tenants: {}", e)))?;
+
+        Ok(Page::new(tenants, page, total as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_pattern_escapes_wildcards() {
+        let filter = TenantFilter {
+            search: Some("50%_off\\".to_string()),
+            is_active: None,
+        };
+
+        assert_eq!(filter.search_pattern().unwrap(), "%50\\%\\_off\\\\%");
+        assert_eq!(TenantFilter::default().search_pattern(), None);
+    }
+}
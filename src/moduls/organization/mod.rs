@@ -0,0 +1,17 @@
+//! Organization module
+//!
+//! Organizations are the tenants of the application. Users can belong to
+//! more than one organization through tenant memberships.
+//! - Domain: Business entities and rules (Organization, TenantMembership)
+//! - Application: Use cases (create, update, and join organizations;
+//!   list tenants for super-admins)
+//! - Infrastructure: Repositories (PostgreSQL implementations)
+//! - API: JSON handlers for JWT-based auth
+
+pub mod domain;
+pub mod application;
+pub mod infra;
+pub mod api;
+
+// Re-export commonly used items
+pub use api::organization_api_routes;
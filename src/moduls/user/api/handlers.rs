@@ -1,9 +1,22 @@
 use crate::bootstrap::AppState;
 use crate::moduls::auth::api::middleware::AuthenticatedUser;
-use crate::moduls::user::application::{ChangePasswordCommand, UpdateProfileCommand};
-use crate::moduls::user::domain::UserProfile;
-use crate::shared::AppError;
-use axum::{extract::State, Json};
+use crate::moduls::user::application::{
+    ApiKeySummary, AuditEventSummary, ChangePasswordCommand, ConfirmEmailChangeCommand,
+    CreateApiKeyCommand, CreatedApiKey, DeleteAccountCommand, RequestEmailChangeCommand,
+    SessionSummary, UpdateNotificationPreferencesCommand, UpdateProfileCommand,
+    VerifyPasswordCommand,
+};
+use crate::moduls::auth::domain::{NotificationPreferences, UserDto};
+use crate::moduls::user::domain::{Avatar, PublicUserDto, UserProfile};
+use crate::shared::{AppError, ClientIp, UncheckedJson, ValidatedJson};
+use crate::shared::types::{SessionId, UserId};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
 
 /// Response for successful operations with no data
 #[derive(Debug, serde::Serialize)]
@@ -27,13 +40,31 @@ pub async fn get_profile(
     Ok(Json(profile))
 }
 
+/// GET /api/users/{id}/public
+/// Another user's public profile (id, name, avatar)
+/// Requires JWT authentication
+///
+/// 404 if the user is not in the caller's tenant.
+pub async fn get_public_profile(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(user_id): Path<UserId>,
+) -> Result<Json<PublicUserDto>, AppError> {
+    let profile = state
+        .get_public_profile_use_case
+        .execute(user_id, auth_user.tenant_id)
+        .await?;
+
+    Ok(Json(profile))
+}
+
 /// PUT /api/user/profile
 /// Update current user's profile (JSON)
 /// Requires JWT authentication
 pub async fn update_profile(
     State(state): State<AppState>,
     auth_user: AuthenticatedUser,
-    Json(payload): Json<UpdateProfileCommand>,
+    ValidatedJson(payload): ValidatedJson<UpdateProfileCommand>,
 ) -> Result<Json<UserProfile>, AppError> {
     // Use the authenticated user ID from JWT claims
     let profile = state
@@ -44,13 +75,52 @@ pub async fn update_profile(
     Ok(Json(profile))
 }
 
+/// PUT /api/user/avatar
+/// Upload current user's avatar (raw PNG, JPEG or WebP body)
+/// Requires JWT authentication
+///
+/// Bodies over `AVATAR_MAX_BYTES` get 413; the stored image is a PNG
+/// scaled down to fit `AVATAR_MAX_DIMENSION`.
+pub async fn upload_avatar(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    body: Bytes,
+) -> Result<Json<UserProfile>, AppError> {
+    let profile = state
+        .manage_avatar_use_case
+        .upload(auth_user.user_id, &body)
+        .await?;
+
+    Ok(Json(profile))
+}
+
+/// GET /api/users/{id}/avatar
+/// A user's uploaded avatar (PNG)
+/// Public, so it can be used in `<img>` tags
+pub async fn get_avatar(
+    State(state): State<AppState>,
+    Path(user_id): Path<UserId>,
+) -> Result<impl IntoResponse, AppError> {
+    let avatar = state.manage_avatar_use_case.get(user_id).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, Avatar::CONTENT_TYPE),
+            // Uploads get a new versioned URL, so old ones may be cached
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        avatar.data,
+    ))
+}
+
 /// PUT /api/user/password
 /// Change current user's password (JSON)
 /// Requires JWT authentication
 pub async fn change_password(
     State(state): State<AppState>,
     auth_user: AuthenticatedUser,
-    Json(payload): Json<ChangePasswordCommand>,
+    UncheckedJson(payload): UncheckedJson<ChangePasswordCommand>,
 ) -> Result<Json<EmptyResponse>, AppError> {
     // Use the authenticated user ID from JWT claims
     state
@@ -63,6 +133,230 @@ pub async fn change_password(
     }))
 }
 
+/// POST /api/user/email
+/// Start changing the current user's email, confirmed with the password
+/// Requires JWT authentication
+///
+/// The new address is sent a confirmation link; the email changes once
+/// it is opened.
+pub async fn request_email_change(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ClientIp(ip_address): ClientIp,
+    ValidatedJson(mut payload): ValidatedJson<RequestEmailChangeCommand>,
+) -> Result<Json<EmptyResponse>, AppError> {
+    payload.ip_address = ip_address;
+    state
+        .change_email_use_case
+        .request(auth_user.user_id, payload)
+        .await?;
+
+    Ok(Json(EmptyResponse {
+        message: "Confirmation email sent to the new address".to_string(),
+    }))
+}
+
+/// GET /api/user/email/confirm?token=...
+/// Apply an email change with the token from the confirmation link
+///
+/// The token is single-use; no authentication is needed, as the link is
+/// opened from the email.
+pub async fn confirm_email_change(
+    State(state): State<AppState>,
+    Query(cmd): Query<ConfirmEmailChangeCommand>,
+) -> Result<Json<UserDto>, AppError> {
+    let user = state.change_email_use_case.confirm(cmd).await?;
+
+    Ok(Json(UserDto::from(user)))
+}
+
+/// DELETE /api/user/account
+/// Delete the current user's account, confirmed with the password
+/// Requires JWT authentication
+///
+/// 204 on success, 401 on a wrong password. The account is soft-deleted
+/// and logged out everywhere.
+pub async fn delete_account(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<DeleteAccountCommand>,
+) -> Result<StatusCode, AppError> {
+    state
+        .delete_account_use_case
+        .execute(auth_user.user_id, payload)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/user/notification-preferences
+/// Security emails the current user receives
+/// Requires JWT authentication
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> Result<Json<NotificationPreferences>, AppError> {
+    let preferences = state
+        .notification_preferences_use_case
+        .get(auth_user.user_id)
+        .await?;
+
+    Ok(Json(preferences))
+}
+
+/// PUT /api/user/notification-preferences
+/// Turn security emails on or off (password reset emails can't be)
+/// Requires JWT authentication
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<UpdateNotificationPreferencesCommand>,
+) -> Result<Json<NotificationPreferences>, AppError> {
+    let preferences = state
+        .notification_preferences_use_case
+        .update(auth_user.user_id, payload)
+        .await?;
+
+    Ok(Json(preferences))
+}
+
+/// POST /api/user/verify-password
+/// Check the current user's password without issuing tokens
+/// Requires JWT authentication
+///
+/// 204 on match, 401 on mismatch, 429 after too many recent failures.
+pub async fn verify_password(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<VerifyPasswordCommand>,
+) -> Result<StatusCode, AppError> {
+    state
+        .verify_password_use_case
+        .execute(auth_user.user_id, payload)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/user/sessions
+/// List the current user's active sessions (device, IP, last seen)
+/// Requires JWT authentication
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> Result<Json<Vec<SessionSummary>>, AppError> {
+    let sessions = state
+        .list_sessions_use_case
+        .execute(auth_user.user_id)
+        .await?;
+
+    Ok(Json(sessions))
+}
+
+/// GET /api/user/audit
+/// List the current user's recent security events (logins, password
+/// changes, ...), newest first
+/// Requires JWT authentication
+pub async fn list_audit_events(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> Result<Json<Vec<AuditEventSummary>>, AppError> {
+    let events = state
+        .list_audit_events_use_case
+        .execute(auth_user.user_id)
+        .await?;
+
+    Ok(Json(events))
+}
+
+/// DELETE /api/user/sessions/{id}
+/// Sign out one of the current user's sessions
+/// Requires JWT authentication
+///
+/// 404 if the session doesn't belong to the caller.
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(session_id): Path<SessionId>,
+) -> Result<Json<EmptyResponse>, AppError> {
+    state
+        .revoke_session_use_case
+        .execute(auth_user.user_id, session_id)
+        .await?;
+
+    Ok(Json(EmptyResponse {
+        message: "Session revoked".to_string(),
+    }))
+}
+
+/// DELETE /api/user/sessions
+/// Sign out everywhere: every session and every issued JWT, including
+/// the one making this request
+/// Requires JWT authentication
+pub async fn revoke_all_sessions(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> Result<Json<EmptyResponse>, AppError> {
+    state
+        .logout_user_use_case
+        .logout_all(auth_user.user_id)
+        .await?;
+
+    Ok(Json(EmptyResponse {
+        message: "Logged out everywhere".to_string(),
+    }))
+}
+
+/// POST /api/user/api-keys
+/// Mint an API key for service-to-service calls
+/// Requires JWT authentication
+///
+/// The plain key is in this response only; it can't be retrieved later.
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    ValidatedJson(payload): ValidatedJson<CreateApiKeyCommand>,
+) -> Result<(StatusCode, Json<CreatedApiKey>), AppError> {
+    let created = state
+        .manage_api_keys_use_case
+        .create(auth_user.user_id, auth_user.tenant_id, payload)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// GET /api/user/api-keys
+/// List the current user's API keys (without the keys themselves)
+/// Requires JWT authentication
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> Result<Json<Vec<ApiKeySummary>>, AppError> {
+    let keys = state.manage_api_keys_use_case.list(auth_user.user_id).await?;
+
+    Ok(Json(keys))
+}
+
+/// DELETE /api/user/api-keys/{id}
+/// Revoke one of the current user's API keys
+/// Requires JWT authentication
+///
+/// 404 if the key doesn't belong to the caller.
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Path(key_id): Path<uuid::Uuid>,
+) -> Result<Json<EmptyResponse>, AppError> {
+    state
+        .manage_api_keys_use_case
+        .revoke(auth_user.user_id, key_id)
+        .await?;
+
+    Ok(Json(EmptyResponse {
+        message: "API key revoked".to_string(),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
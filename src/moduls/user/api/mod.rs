@@ -1,4 +1,4 @@
 pub mod handlers;
 pub mod routes;
 
-pub use routes::user_api_routes;
+pub use routes::{user_api_routes, users_api_routes};
@@ -1,24 +1,90 @@
 use crate::bootstrap::AppState;
-use crate::moduls::auth::api::middleware::jwt_auth_middleware;
+use crate::moduls::auth::api::middleware::{
+    forbid_impersonation, jwt_auth_middleware, jwt_or_api_key_middleware, require_fresh_auth,
+};
 use axum::{
+    extract::DefaultBodyLimit,
+    handler::Handler,
     middleware,
-    routing::{get, put},
+    routing::{delete, get, post, put},
     Router,
 };
 
 use super::handlers;
 
 /// User API routes (JSON / JWT-based authentication)
-/// All routes require authentication via JWT middleware
+/// All routes except email change confirmation (opened from the emailed
+/// link) require authentication; API key management, email change and
+/// account deletion take a JWT, the rest also accepts an API key
 pub fn user_api_routes(state: AppState) -> Router<AppState> {
+    // A leaked API key must not be able to mint more keys or take over or
+    // delete the account, nor an impersonating admin mint a way back in
+    let jwt_only = Router::new()
+        .route(
+            "/api-keys",
+            get(handlers::list_api_keys)
+                .post(handlers::create_api_key.layer(middleware::from_fn(forbid_impersonation))),
+        )
+        .route("/api-keys/{id}", delete(handlers::revoke_api_key))
+        .route(
+            "/email",
+            post(handlers::request_email_change.layer(middleware::from_fn(forbid_impersonation))),
+        )
+        .route("/account", delete(handlers::delete_account))
+        .route_layer(middleware::from_fn_with_state(state.clone(), jwt_auth_middleware));
+
+    // The token in the link is the credential
+    let public = Router::new().route("/email/confirm", get(handlers::confirm_email_change));
+
     Router::new()
         // Profile operations
         .route(
             "/profile",
             get(handlers::get_profile).put(handlers::update_profile),
         )
-        // Password change
-        .route("/password", put(handlers::change_password))
-        // Add JWT authentication middleware to all routes
-        .route_layer(middleware::from_fn_with_state(state, jwt_auth_middleware))
+        .route(
+            "/avatar",
+            put(handlers::upload_avatar)
+                .layer(DefaultBodyLimit::max(state.config.avatar.max_bytes)),
+        )
+        // Password change (requires a recent login)
+        .route(
+            "/password",
+            put(handlers::change_password.layer(middleware::from_fn_with_state(
+                state.clone(),
+                require_fresh_auth,
+            ))),
+        )
+        // Security email preferences
+        .route(
+            "/notification-preferences",
+            get(handlers::get_notification_preferences)
+                .put(handlers::update_notification_preferences),
+        )
+        // Password check without re-issuing tokens (rate limited)
+        .route("/verify-password", post(handlers::verify_password))
+        // Active sessions across devices
+        .route(
+            "/sessions",
+            get(handlers::list_sessions).delete(handlers::revoke_all_sessions),
+        )
+        .route("/sessions/{id}", delete(handlers::revoke_session))
+        // Own security events
+        .route("/audit", get(handlers::list_audit_events))
+        // Bearer token or API key
+        .route_layer(middleware::from_fn_with_state(state, jwt_or_api_key_middleware))
+        .merge(jwt_only)
+        .merge(public)
+}
+
+/// Routes about other users (JSON / JWT-based authentication)
+/// Bearer token or API key, except avatars (loaded by `<img>` tags);
+/// lookups stay within the caller's tenant
+pub fn users_api_routes(state: AppState) -> Router<AppState> {
+    let public = Router::new().route("/{id}/avatar", get(handlers::get_avatar));
+
+    Router::new()
+        .route("/{id}/public", get(handlers::get_public_profile))
+        .route_layer(middleware::from_fn_with_state(state, jwt_or_api_key_middleware))
+        .merge(public)
 }
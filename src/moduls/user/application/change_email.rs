@@ -0,0 +1,343 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::application::{SendLimits, SendThrottle};
+use crate::moduls::auth::domain::{Email, User};
+use crate::moduls::auth::infra::{EmailVerificationRepository, UserRepository};
+use crate::moduls::user::application::{VerifyPasswordCommand, VerifyPasswordUseCase};
+use crate::moduls::user::domain::EmailChangeRequest;
+use crate::moduls::user::infra::EmailChangeRepository;
+use crate::shared::{mailer::Mailer, types::UserId, AppError, AppResult};
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+/// Request Email Change Command (DTO)
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct RequestEmailChangeCommand {
+    #[validate(email(message = "Invalid email format"))]
+    pub new_email: String,
+    /// Current password, confirming the change
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+    /// Client address, for throttling
+    #[serde(skip)]
+    pub ip_address: Option<String>,
+}
+
+/// Confirm Email Change Command (DTO), from the `token` query parameter
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfirmEmailChangeCommand {
+    pub token: String,
+}
+
+/// Change Email Use Case
+/// Lets users change their login email
+///
+/// Business Logic:
+/// 1. `request` checks the password like `VerifyPasswordUseCase` and that
+///    no other user of the tenant has the new address, then emails a
+///    confirmation link to the new address (superseding earlier links)
+/// 2. `confirm` redeems the link, checks the address is still free and
+///    applies the change; the new address starts unverified and pending
+///    verification links for the old one stop working
+pub struct ChangeEmailUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    email_change_repo: Arc<dyn EmailChangeRepository>,
+    verification_repo: Arc<dyn EmailVerificationRepository>,
+    verify_password: Arc<VerifyPasswordUseCase>,
+    mailer: Arc<dyn Mailer>,
+    audit_log: Arc<AuditLog>,
+    throttle: SendThrottle,
+    confirm_url: String,
+    token_ttl_seconds: i64,
+}
+
+impl ChangeEmailUseCase {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        email_change_repo: Arc<dyn EmailChangeRepository>,
+        verification_repo: Arc<dyn EmailVerificationRepository>,
+        verify_password: Arc<VerifyPasswordUseCase>,
+        mailer: Arc<dyn Mailer>,
+        audit_log: Arc<AuditLog>,
+        confirm_url: String,
+        token_ttl_seconds: i64,
+        send_limits: SendLimits,
+    ) -> Self {
+        Self {
+            user_repo,
+            email_change_repo,
+            verification_repo,
+            verify_password,
+            mailer,
+            audit_log,
+            throttle: SendThrottle::new(send_limits),
+            confirm_url,
+            token_ttl_seconds,
+        }
+    }
+
+    /// Email a confirmation link to the new address
+    ///
+    /// # Errors
+    /// - Authentication error if the password does not match
+    /// - Validation if the address is invalid or the current email
+    /// - Conflict if another user of the tenant has the address
+    /// - TooManyRequests while password failures or sends are throttled
+    /// - Database errors
+    pub async fn request(&self, user_id: UserId, cmd: RequestEmailChangeCommand) -> AppResult<()> {
+        self.verify_password
+            .execute(user_id, VerifyPasswordCommand { password: cmd.password })
+            .await?;
+
+        let new_email = Email::new(&cmd.new_email)?;
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        if new_email == user.email {
+            return Err(AppError::validation("New email must differ from the current email"));
+        }
+        self.ensure_available(&user, &new_email).await?;
+
+        if !self.throttle.try_acquire(new_email.as_str(), cmd.ip_address.as_deref()) {
+            return Err(AppError::too_many_requests(
+                "Confirmation email sent recently, try again later",
+            ));
+        }
+
+        // Only the latest link can confirm a change
+        self.email_change_repo.invalidate_user_requests(user.id).await?;
+        let (request, plain) =
+            EmailChangeRequest::issue(user.id, new_email.clone(), self.token_ttl_seconds);
+        self.email_change_repo.save(&request).await?;
+
+        let body = format!(
+            "Hi {},\n\n\
+             Confirm this address as the new email of your account by opening \
+             the link below. You will sign in with it from then on.\n\n\
+             {}?token={}\n\n\
+             If you didn't ask for this, you can ignore this email.\n",
+            user.name, self.confirm_url, plain
+        );
+        self.mailer.send(&new_email, "Confirm your new email address", &body).await
+    }
+
+    /// Apply the change confirmed by a link
+    ///
+    /// # Errors
+    /// - Validation if the token is unknown, expired or already used
+    /// - Conflict if another user of the tenant took the address meanwhile
+    /// - Database errors
+    pub async fn confirm(&self, cmd: ConfirmEmailChangeCommand) -> AppResult<User> {
+        // Redeem atomically, so a link works only once
+        let request = self
+            .email_change_repo
+            .find_by_hash(&EmailChangeRequest::hash(&cmd.token))
+            .await?
+            .filter(|r| r.is_usable())
+            .ok_or_else(invalid_token)?;
+
+        if !self.email_change_repo.mark_used(request.id).await? {
+            return Err(invalid_token());
+        }
+
+        let mut user = self
+            .user_repo
+            .find_by_id(request.user_id)
+            .await?
+            .ok_or_else(invalid_token)?;
+
+        self.ensure_available(&user, &request.new_email).await?;
+        user.change_email(request.new_email)?;
+        let user = self.user_repo.update(&user).await?;
+
+        // Links sent to the old address must not verify the new one
+        self.verification_repo.invalidate_user_tokens(user.id).await?;
+
+        tracing::info!("User {} changed their email", user.id);
+        self.audit_log
+            .record(
+                AuditEvent::new(AuditAction::EmailChanged, Some(user.id)).with_tenant(user.tenant_id),
+            )
+            .await;
+
+        Ok(user)
+    }
+
+    /// Reject addresses used by another user of the same tenant (or by
+    /// another global user)
+    async fn ensure_available(&self, user: &User, email: &Email) -> AppResult<()> {
+        let existing = match user.tenant_id {
+            Some(tenant_id) => self.user_repo.find_by_email_in_tenant(email, tenant_id).await?,
+            None => self.user_repo.find_by_email(email).await?,
+        };
+
+        if existing.is_some_and(|other| other.id != user.id) {
+            return Err(AppError::conflict("Email already exists"));
+        }
+        Ok(())
+    }
+}
+
+fn invalid_token() -> AppError {
+    AppError::validation("Invalid or expired email change token")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{EmailVerificationToken, PasswordHash};
+    use crate::moduls::auth::infra::in_memory::{
+        InMemoryEmailVerificationRepository, InMemoryLoginAttemptRepository,
+        InMemoryUserRepository,
+    };
+    use crate::moduls::user::application::VerifyPasswordLimits;
+    use crate::moduls::user::infra::in_memory::InMemoryEmailChangeRepository;
+    use crate::shared::mailer::MockMailer;
+
+    struct Fixture {
+        user_id: UserId,
+        user_repo: Arc<InMemoryUserRepository>,
+        verification_repo: Arc<InMemoryEmailVerificationRepository>,
+        mailer: Arc<MockMailer>,
+        use_case: ChangeEmailUseCase,
+    }
+
+    fn user(email: &str) -> User {
+        User::new(Email::new(email).unwrap(), "password123", "Some User".to_string()).unwrap()
+    }
+
+    fn fixture() -> Fixture {
+        let mut me = user("me@example.com");
+        me.verify_email();
+        let user_id = me.id;
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(me));
+        let verification_repo = Arc::new(InMemoryEmailVerificationRepository::default());
+        let mailer = Arc::new(MockMailer::default());
+        let verify_password = Arc::new(VerifyPasswordUseCase::new(
+            user_repo.clone(),
+            Arc::new(InMemoryLoginAttemptRepository::default()),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            VerifyPasswordLimits {
+                max_failures: 5,
+                window_seconds: 900,
+            },
+        ));
+        let use_case = ChangeEmailUseCase::new(
+            user_repo.clone(),
+            Arc::new(InMemoryEmailChangeRepository::default()),
+            verification_repo.clone(),
+            verify_password,
+            mailer.clone(),
+            Arc::new(AuditLog::for_tests()),
+            "http://app.test/api/user/email/confirm".to_string(),
+            86400,
+            SendLimits {
+                per_email: 5,
+                per_ip: 10,
+                window_seconds: 300,
+            },
+        );
+
+        Fixture {
+            user_id,
+            user_repo,
+            verification_repo,
+            mailer,
+            use_case,
+        }
+    }
+
+    fn change_to(new_email: &str) -> RequestEmailChangeCommand {
+        RequestEmailChangeCommand {
+            new_email: new_email.to_string(),
+            password: "password123".to_string(),
+            ip_address: None,
+        }
+    }
+
+    fn confirm(body: &str) -> ConfirmEmailChangeCommand {
+        let link = body
+            .lines()
+            .find(|line| line.starts_with("http://app.test/api/user/email/confirm?token="))
+            .unwrap();
+        ConfirmEmailChangeCommand {
+            token: link.rsplit('=').next().unwrap().to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirm_applies_the_change() {
+        let f = fixture();
+        let (old_link, _) = EmailVerificationToken::issue(f.user_id, 86400);
+        f.verification_repo.tokens.lock().unwrap().push(old_link);
+
+        f.use_case.request(f.user_id, change_to("new@example.com")).await.unwrap();
+        let mail = f.mailer.last().unwrap();
+        assert_eq!(mail.to, "new@example.com");
+
+        // Nothing changes until the link is opened
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert_eq!(user.email.as_str(), "me@example.com");
+
+        let user = f.use_case.confirm(confirm(&mail.body)).await.unwrap();
+        assert_eq!(user.email.as_str(), "new@example.com");
+        assert!(!user.email_verified);
+        assert!(f.verification_repo.tokens.lock().unwrap().iter().all(|t| !t.is_usable()));
+
+        let result = f.use_case.confirm(confirm(&mail.body)).await;
+        assert!(matches!(result, Err(AppError::Validation(_))), "Links work once");
+    }
+
+    #[tokio::test]
+    async fn test_email_of_another_user_is_rejected() {
+        let f = fixture();
+        f.user_repo.save(&user("taken@example.com")).await.unwrap();
+
+        let result = f.use_case.request(f.user_id, change_to("Taken@Example.com")).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert_eq!(f.mailer.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_email_taken_before_confirmation_is_rejected() {
+        let f = fixture();
+        f.use_case.request(f.user_id, change_to("new@example.com")).await.unwrap();
+        let body = f.mailer.last().unwrap().body;
+        f.user_repo.save(&user("new@example.com")).await.unwrap();
+
+        let result = f.use_case.confirm(confirm(&body)).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert_eq!(user.email.as_str(), "me@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_wrong_password_sends_nothing() {
+        let f = fixture();
+        let mut cmd = change_to("new@example.com");
+        cmd.password = "wrongpassword".to_string();
+
+        let result = f.use_case.request(f.user_id, cmd).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+        assert_eq!(f.mailer.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_new_request_supersedes_earlier_link() {
+        let f = fixture();
+        f.use_case.request(f.user_id, change_to("first@example.com")).await.unwrap();
+        let stale = f.mailer.last().unwrap().body;
+        f.use_case.request(f.user_id, change_to("second@example.com")).await.unwrap();
+
+        let result = f.use_case.confirm(confirm(&stale)).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+}
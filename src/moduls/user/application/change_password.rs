@@ -1,15 +1,21 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::application::{PasswordHistory, SecurityNotifier};
+use crate::moduls::auth::domain::{
+    NotificationCategory, PasswordHash, PasswordHasher, PasswordPolicy,
+};
 use crate::moduls::auth::infra::UserRepository;
 use crate::shared::{types::UserId, AppError, AppResult};
 use std::sync::Arc;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 /// Change Password Command (DTO)
 /// Input data for changing user password
 #[derive(Debug, Clone, serde::Deserialize, Validate)]
 pub struct ChangePasswordCommand {
+    #[validate(length(min = 1, message = "Current password is required"))]
     pub current_password: String,
 
-    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    /// Checked against `PasswordPolicy` by the use case
     pub new_password: String,
 
     #[serde(default)]
@@ -20,25 +26,70 @@ pub struct ChangePasswordCommand {
 /// Allows users to change their password with verification
 pub struct ChangePasswordUseCase {
     user_repo: Arc<dyn UserRepository>,
+    audit_log: Arc<AuditLog>,
+    max_password_length: usize,
+    password_policy: PasswordPolicy,
+    password_hasher: PasswordHasher,
+    password_history: Option<PasswordHistory>,
+    notifier: Option<SecurityNotifier>,
 }
 
 impl ChangePasswordUseCase {
-    pub fn new(user_repo: Arc<dyn UserRepository>) -> Self {
-        Self { user_repo }
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        audit_log: Arc<AuditLog>,
+        max_password_length: usize,
+        password_policy: PasswordPolicy,
+    ) -> Self {
+        Self {
+            user_repo,
+            audit_log,
+            max_password_length,
+            password_policy,
+            password_hasher: PasswordHasher::default(),
+            password_history: None,
+            notifier: None,
+        }
+    }
+
+    /// Hash new passwords with `password_hasher` instead of the default
+    pub fn with_password_hasher(mut self, password_hasher: PasswordHasher) -> Self {
+        self.password_hasher = password_hasher;
+        self
+    }
+
+    /// Refuse the user's recent passwords (`PasswordPolicy::history`)
+    pub fn with_password_history(mut self, password_history: PasswordHistory) -> Self {
+        self.password_history = Some(password_history);
+        self
+    }
+
+    /// Email users when their password changes
+    pub fn with_notifier(mut self, notifier: SecurityNotifier) -> Self {
+        self.notifier = Some(notifier);
+        self
     }
 
     /// Execute the use case to change a user's password
     pub async fn execute(&self, user_id: UserId, cmd: ChangePasswordCommand) -> AppResult<()> {
-        // 1. Validate input
-        cmd.validate()
-            .map_err(|e| AppError::Validation(e.to_string()))?;
-
-        // 2. Check password confirmation matches (if provided)
-        if let Some(ref confirmation) = cmd.new_password_confirmation {
-            if &cmd.new_password != confirmation {
-                return Err(AppError::Validation("Passwords do not match".into()));
-            }
+        // 1. Reject oversized passwords before verifying or hashing them
+        PasswordHash::ensure_max_length(&cmd.current_password, self.max_password_length)?;
+        PasswordHash::ensure_max_length(&cmd.new_password, self.max_password_length)?;
+
+        // 2. Check the fields, the confirmation (if provided) and the
+        //    password policy, reporting every invalid field at once
+        let mut errors = cmd.validate().err().unwrap_or_default();
+        if cmd
+            .new_password_confirmation
+            .as_ref()
+            .is_some_and(|confirmation| *confirmation != cmd.new_password)
+        {
+            errors.add(
+                "new_password_confirmation",
+                ValidationError::new("must_match").with_message("Passwords do not match".into()),
+            );
         }
+        AppError::check_fields(errors, self.password_policy.enforce(&cmd.new_password, "new_password"))?;
 
         // 3. Load user
         let mut user = self
@@ -54,12 +105,43 @@ impl ChangePasswordUseCase {
             ));
         }
 
-        // 5. Change password (business rule: password hashing applied)
-        user.change_password(&cmd.new_password)?;
+        // 5. Refuse recent passwords, then remember the one being replaced
+        if let Some(history) = &self.password_history {
+            history
+                .ensure_not_reused(&user, &cmd.new_password, &self.password_policy, "new_password")
+                .await?;
+            history.remember(&user, &self.password_policy).await?;
+        }
 
-        // 6. Save updated user
+        // 6. Change password (business rule: password hashing applied)
+        user.change_password(&cmd.new_password, &self.password_hasher)?;
+
+        // 7. Save updated user
         self.user_repo.update(&user).await?;
 
+        self.audit_log
+            .record(
+                AuditEvent::new(AuditAction::PasswordChanged, Some(user.id))
+                    .with_tenant(user.tenant_id),
+            )
+            .await;
+
+        // 8. Tell the user, in case someone else changed it
+        if let Some(notifier) = &self.notifier {
+            let body = format!(
+                "Hi {},\n\n\
+                 The password of your account was just changed.\n\n\
+                 If this wasn't you, reset your password right away.\n",
+                user.name
+            );
+            let sent = notifier
+                .notify(&user, NotificationCategory::PasswordChanged, "Your password was changed", &body)
+                .await;
+            if let Err(e) = sent {
+                tracing::error!("Failed to send password change email to user {}: {}", user.id, e);
+            }
+        }
+
         Ok(())
     }
 }
@@ -68,6 +150,9 @@ impl ChangePasswordUseCase {
 mod tests {
     use super::*;
     use crate::moduls::auth::domain::{Email, User};
+    use crate::moduls::auth::infra::UserFilter;
+    use crate::shared::pagination::{Page, PageRequest};
+    use crate::shared::types::OrganizationId;
     use async_trait::async_trait;
 
     struct MockUserRepository {
@@ -84,6 +169,14 @@ mod tests {
             Ok(self.user.clone())
         }
 
+        async fn find_by_email_in_tenant(
+            &self,
+            _email: &Email,
+            _organization_id: OrganizationId,
+        ) -> AppResult<Option<User>> {
+            Ok(self.user.clone())
+        }
+
         async fn save(&self, user: &User) -> AppResult<User> {
             Ok(user.clone())
         }
@@ -95,6 +188,14 @@ mod tests {
         async fn delete(&self, _id: UserId) -> AppResult<()> {
             Ok(())
         }
+
+        async fn list_paginated(&self, _filter: &UserFilter, page: PageRequest) -> AppResult<Page<User>> {
+            Ok(Page::new(Vec::new(), page, 0))
+        }
+
+        async fn find_due_for_deletion(&self, _limit: u32) -> AppResult<Vec<User>> {
+            Ok(Vec::new())
+        }
     }
 
     #[tokio::test]
@@ -104,7 +205,12 @@ mod tests {
         let user_id = user.id;
 
         let repo = Arc::new(MockUserRepository { user: Some(user) });
-        let use_case = ChangePasswordUseCase::new(repo);
+        let use_case = ChangePasswordUseCase::new(
+            repo,
+            Arc::new(AuditLog::for_tests()),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            PasswordPolicy::default(),
+        );
 
         let cmd = ChangePasswordCommand {
             current_password: "oldpassword123".to_string(),
@@ -116,6 +222,36 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_change_password_emails_user_unless_disabled() {
+        use crate::shared::mailer::MockMailer;
+
+        for enabled in [true, false] {
+            let email = Email::new("test@example.com").unwrap();
+            let mut user = User::new(email, "oldpassword123", "Test User".to_string()).unwrap();
+            user.notification_preferences.password_changed = enabled;
+            let user_id = user.id;
+
+            let mailer = Arc::new(MockMailer::default());
+            let use_case = ChangePasswordUseCase::new(
+                Arc::new(MockUserRepository { user: Some(user) }),
+                Arc::new(AuditLog::for_tests()),
+                PasswordHash::DEFAULT_MAX_LENGTH,
+                PasswordPolicy::default(),
+            )
+            .with_notifier(SecurityNotifier::new(mailer.clone()));
+
+            let cmd = ChangePasswordCommand {
+                current_password: "oldpassword123".to_string(),
+                new_password: "newpassword123".to_string(),
+                new_password_confirmation: None,
+            };
+            use_case.execute(user_id, cmd).await.unwrap();
+
+            assert_eq!(mailer.count(), usize::from(enabled));
+        }
+    }
+
     #[tokio::test]
     async fn test_change_password_mismatch_fails() {
         let email = Email::new("test@example.com").unwrap();
@@ -123,7 +259,12 @@ mod tests {
         let user_id = user.id;
 
         let repo = Arc::new(MockUserRepository { user: Some(user) });
-        let use_case = ChangePasswordUseCase::new(repo);
+        let use_case = ChangePasswordUseCase::new(
+            repo,
+            Arc::new(AuditLog::for_tests()),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            PasswordPolicy::default(),
+        );
 
         let cmd = ChangePasswordCommand {
             current_password: "oldpassword123".to_string(),
@@ -132,7 +273,10 @@ mod tests {
         };
 
         let result = use_case.execute(user_id, cmd).await;
-        assert!(result.is_err());
+        let Err(AppError::FieldValidation(errors)) = result else {
+            panic!("expected field errors, got {result:?}");
+        };
+        assert!(errors.field_errors().contains_key("new_password_confirmation"));
     }
 
     #[tokio::test]
@@ -142,7 +286,12 @@ mod tests {
         let user_id = user.id;
 
         let repo = Arc::new(MockUserRepository { user: Some(user) });
-        let use_case = ChangePasswordUseCase::new(repo);
+        let use_case = ChangePasswordUseCase::new(
+            repo,
+            Arc::new(AuditLog::for_tests()),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            PasswordPolicy::default(),
+        );
 
         let cmd = ChangePasswordCommand {
             current_password: "wrongpassword".to_string(),
@@ -153,4 +302,96 @@ mod tests {
         let result = use_case.execute(user_id, cmd).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_change_password_to_denied_password_fails() {
+        use crate::moduls::auth::domain::{PasswordDenylist, PasswordRequirement};
+
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, "oldpassword123", "Test User".to_string()).unwrap();
+        let user_id = user.id;
+
+        let repo = Arc::new(MockUserRepository { user: Some(user) });
+        let use_case = ChangePasswordUseCase::new(
+            repo,
+            Arc::new(AuditLog::for_tests()),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            PasswordPolicy {
+                denylist: PasswordDenylist::new(["letmein123"]),
+                ..PasswordPolicy::default()
+            },
+        );
+
+        let cmd = ChangePasswordCommand {
+            current_password: "oldpassword123".to_string(),
+            new_password: "LetMeIn123".to_string(),
+            new_password_confirmation: None,
+        };
+
+        let Err(AppError::PasswordPolicy(violation)) = use_case.execute(user_id, cmd).await else {
+            panic!("expected a password policy violation");
+        };
+        assert_eq!(violation.field, "new_password");
+        assert_eq!(violation.failed, vec![PasswordRequirement::NotCommon]);
+    }
+
+    #[tokio::test]
+    async fn test_change_password_to_recent_password_fails() {
+        use crate::moduls::auth::domain::PasswordRequirement;
+        use crate::moduls::auth::infra::in_memory::InMemoryPasswordHistoryRepository;
+
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, "oldpassword123", "Test User".to_string()).unwrap();
+        let user_id = user.id;
+
+        let repo = Arc::new(MockUserRepository { user: Some(user) });
+        let use_case = ChangePasswordUseCase::new(
+            repo,
+            Arc::new(AuditLog::for_tests()),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            PasswordPolicy {
+                history: Some(3),
+                ..PasswordPolicy::default()
+            },
+        )
+        .with_password_history(PasswordHistory::new(Arc::new(
+            InMemoryPasswordHistoryRepository::default(),
+        )));
+
+        let cmd = ChangePasswordCommand {
+            current_password: "oldpassword123".to_string(),
+            new_password: "oldpassword123".to_string(),
+            new_password_confirmation: None,
+        };
+
+        let Err(AppError::PasswordPolicy(violation)) = use_case.execute(user_id, cmd).await else {
+            panic!("expected a password policy violation");
+        };
+        assert_eq!(violation.failed, vec![PasswordRequirement::NotReused]);
+    }
+
+    #[tokio::test]
+    async fn test_change_password_too_long_fails() {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, "oldpassword123", "Test User".to_string()).unwrap();
+        let user_id = user.id;
+
+        let repo = Arc::new(MockUserRepository { user: Some(user) });
+        let use_case = ChangePasswordUseCase::new(
+            repo,
+            Arc::new(AuditLog::for_tests()),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            PasswordPolicy::default(),
+        );
+
+        let oversized = "a".repeat(100 * 1024);
+        let cmd = ChangePasswordCommand {
+            current_password: "oldpassword123".to_string(),
+            new_password: oversized.clone(),
+            new_password_confirmation: Some(oversized),
+        };
+
+        let result = use_case.execute(user_id, cmd).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
 }
@@ -0,0 +1,207 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::infra::{
+    ApiKeyRepository, SessionRepository, TokenRepository, UserRepository,
+};
+use crate::moduls::user::application::{VerifyPasswordCommand, VerifyPasswordUseCase};
+use crate::shared::{types::UserId, AppError, AppResult};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Delete Account Command (DTO)
+#[derive(Debug, Clone, serde::Deserialize, Validate)]
+pub struct DeleteAccountCommand {
+    /// Current password, confirming the deletion
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+/// Delete Account Use Case
+/// Lets users delete their own account
+///
+/// Business Logic:
+/// 1. Verify the password like `VerifyPasswordUseCase` (failures count
+///    toward its limit, so this can't be used to guess passwords)
+/// 2. Soft-delete the user (`User::delete`), anonymizing it when
+///    configured
+/// 3. Log out everywhere: web sessions, JWTs and API keys
+pub struct DeleteAccountUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    session_repo: Arc<dyn SessionRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    api_key_repo: Arc<dyn ApiKeyRepository>,
+    verify_password: Arc<VerifyPasswordUseCase>,
+    audit_log: Arc<AuditLog>,
+    anonymize: bool,
+}
+
+impl DeleteAccountUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        session_repo: Arc<dyn SessionRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        api_key_repo: Arc<dyn ApiKeyRepository>,
+        verify_password: Arc<VerifyPasswordUseCase>,
+        audit_log: Arc<AuditLog>,
+        anonymize: bool,
+    ) -> Self {
+        Self {
+            user_repo,
+            session_repo,
+            token_repo,
+            api_key_repo,
+            verify_password,
+            audit_log,
+            anonymize,
+        }
+    }
+
+    /// Execute the use case
+    ///
+    /// # Errors
+    /// - Authentication error if the password does not match
+    /// - Too many requests while the password failure limit is reached
+    /// - Not found if the user no longer exists
+    /// - Database errors
+    pub async fn execute(&self, user_id: UserId, cmd: DeleteAccountCommand) -> AppResult<()> {
+        // 1. Confirm the password
+        self.verify_password
+            .execute(user_id, VerifyPasswordCommand { password: cmd.password })
+            .await?;
+
+        // 2. Soft-delete
+        let mut user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+        user.delete(self.anonymize)?;
+        self.user_repo.update(&user).await?;
+
+        // 3. Log out everywhere
+        self.session_repo.delete_by_user_id(user.id).await?;
+        self.token_repo.revoke_all_user_tokens(user.id).await?;
+        self.api_key_repo.revoke_all_for_user(user.id).await?;
+
+        tracing::info!("User {} deleted their account", user.id);
+        self.audit_log
+            .record(
+                AuditEvent::new(AuditAction::AccountDeleted, Some(user.id))
+                    .with_tenant(user.tenant_id),
+            )
+            .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{
+        ApiKey, Email, JwtKeys, JwtToken, PasswordHash, Session, TokenPair, User,
+    };
+    use crate::moduls::auth::infra::in_memory::*;
+    use crate::moduls::user::application::VerifyPasswordLimits;
+
+    struct Fixture {
+        use_case: DeleteAccountUseCase,
+        user_repo: Arc<InMemoryUserRepository>,
+        session_repo: Arc<InMemorySessionRepository>,
+        token_repo: Arc<InMemoryTokenRepository>,
+        api_key_repo: Arc<InMemoryApiKeyRepository>,
+        user_id: UserId,
+    }
+
+    async fn fixture(anonymize: bool) -> Fixture {
+        let email = Email::new("leaving@example.com").unwrap();
+        let user = User::new(email, "password123", "Leaving User".to_string()).unwrap();
+        let user_id = user.id;
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(user));
+        let session_repo = Arc::new(InMemorySessionRepository::default());
+        let token_repo = Arc::new(InMemoryTokenRepository::default());
+        let api_key_repo = Arc::new(InMemoryApiKeyRepository::default());
+
+        session_repo.save(&Session::new(user_id, None, None, 3600)).await.unwrap();
+        let keys = JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars");
+        let (_, access, refresh) = TokenPair::generate(user_id, &keys, 900, 3600).unwrap();
+        token_repo.save(&access).await.unwrap();
+        token_repo.save(&refresh).await.unwrap();
+        let (key, _) = ApiKey::issue(user_id, None, "CI".to_string(), None);
+        api_key_repo.save(&key).await.unwrap();
+
+        let verify_password = Arc::new(VerifyPasswordUseCase::new(
+            user_repo.clone(),
+            Arc::new(InMemoryLoginAttemptRepository::default()),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            VerifyPasswordLimits {
+                max_failures: 5,
+                window_seconds: 900,
+            },
+        ));
+        let use_case = DeleteAccountUseCase::new(
+            user_repo.clone(),
+            session_repo.clone(),
+            token_repo.clone(),
+            api_key_repo.clone(),
+            verify_password,
+            Arc::new(AuditLog::for_tests()),
+            anonymize,
+        );
+
+        Fixture {
+            use_case,
+            user_repo,
+            session_repo,
+            token_repo,
+            api_key_repo,
+            user_id,
+        }
+    }
+
+    fn confirm(password: &str) -> DeleteAccountCommand {
+        DeleteAccountCommand {
+            password: password.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_correct_password_deletes_and_logs_out_everywhere() {
+        let f = fixture(true).await;
+
+        f.use_case.execute(f.user_id, confirm("password123")).await.unwrap();
+
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.deleted_at.is_some());
+        assert!(!user.is_active);
+        assert!(user.email.as_str().starts_with("deleted+"));
+        assert!(f.session_repo.find_all_by_user_id(f.user_id).await.unwrap().is_empty());
+        assert!(f.token_repo.tokens.lock().unwrap().iter().all(JwtToken::is_revoked));
+        assert!(f.api_key_repo.keys.lock().unwrap().iter().all(ApiKey::is_revoked));
+    }
+
+    #[tokio::test]
+    async fn test_email_is_kept_unless_anonymizing() {
+        let f = fixture(false).await;
+
+        f.use_case.execute(f.user_id, confirm("password123")).await.unwrap();
+
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.deleted_at.is_some());
+        assert_eq!(user.email.as_str(), "leaving@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_wrong_password_changes_nothing() {
+        let f = fixture(true).await;
+
+        let result = f.use_case.execute(f.user_id, confirm("wrongpassword")).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+        let user = f.user_repo.find_by_id(f.user_id).await.unwrap().unwrap();
+        assert!(user.deleted_at.is_none());
+        assert!(user.is_active);
+        assert_eq!(f.session_repo.find_all_by_user_id(f.user_id).await.unwrap().len(), 1);
+        assert!(!f.token_repo.tokens.lock().unwrap().iter().any(JwtToken::is_revoked));
+        assert!(!f.api_key_repo.keys.lock().unwrap().iter().any(ApiKey::is_revoked));
+    }
+}
@@ -27,7 +27,6 @@ impl GetProfileUseCase {
 mod tests {
     use super::*;
     use crate::moduls::user::domain::UserProfile;
-    use crate::shared::types::new_id;
     use async_trait::async_trait;
 
     struct MockUserProfileRepository {
@@ -43,11 +42,19 @@ mod tests {
         async fn update(&self, profile: &UserProfile) -> AppResult<UserProfile> {
             Ok(profile.clone())
         }
+
+        async fn find_public(
+            &self,
+            _user_id: UserId,
+            _tenant_id: Option<crate::shared::types::OrganizationId>,
+        ) -> AppResult<Option<crate::moduls::user::domain::PublicUserDto>> {
+            Ok(None)
+        }
     }
 
     #[tokio::test]
     async fn test_get_profile_success() {
-        let user_id = new_id();
+        let user_id = UserId::new();
         let profile = UserProfile {
             user_id,
             name: "Test User".to_string(),
@@ -72,7 +79,7 @@ mod tests {
         let repo = Arc::new(MockUserProfileRepository { profile: None });
         let use_case = GetProfileUseCase::new(repo);
 
-        let result = use_case.execute(new_id()).await;
+        let result = use_case.execute(UserId::new()).await;
         assert!(result.is_err());
     }
 }
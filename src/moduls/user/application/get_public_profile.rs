@@ -0,0 +1,99 @@
+use crate::moduls::user::domain::PublicUserDto;
+use crate::moduls::user::infra::UserProfileRepository;
+use crate::shared::{
+    types::{OrganizationId, UserId},
+    AppError, AppResult,
+};
+use std::sync::Arc;
+
+/// Get Public Profile Use Case
+/// Looks up the public view (id, name, avatar) of another user
+pub struct GetPublicProfileUseCase {
+    profile_repo: Arc<dyn UserProfileRepository>,
+}
+
+impl GetPublicProfileUseCase {
+    pub fn new(profile_repo: Arc<dyn UserProfileRepository>) -> Self {
+        Self { profile_repo }
+    }
+
+    /// Execute the use case for `user_id`, as seen from the caller's tenant
+    ///
+    /// # Errors
+    /// - NotFound if the user doesn't exist or belongs to another tenant
+    ///   (the two are indistinguishable, so user ids don't leak)
+    /// - Database errors
+    pub async fn execute(
+        &self,
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+    ) -> AppResult<PublicUserDto> {
+        self.profile_repo
+            .find_public(user_id, tenant_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::user::domain::UserProfile;
+    use async_trait::async_trait;
+
+    /// Holds one user, visible from one tenant
+    struct MockUserProfileRepository {
+        user: PublicUserDto,
+        tenant_id: Option<OrganizationId>,
+    }
+
+    #[async_trait]
+    impl UserProfileRepository for MockUserProfileRepository {
+        async fn find_by_user_id(&self, _user_id: UserId) -> AppResult<Option<UserProfile>> {
+            Ok(None)
+        }
+
+        async fn update(&self, profile: &UserProfile) -> AppResult<UserProfile> {
+            Ok(profile.clone())
+        }
+
+        async fn find_public(
+            &self,
+            user_id: UserId,
+            tenant_id: Option<OrganizationId>,
+        ) -> AppResult<Option<PublicUserDto>> {
+            Ok((user_id == self.user.id && tenant_id == self.tenant_id).then(|| self.user.clone()))
+        }
+    }
+
+    fn use_case(tenant_id: Option<OrganizationId>) -> (GetPublicProfileUseCase, UserId) {
+        let user = PublicUserDto {
+            id: UserId::new(),
+            name: "Mentioned User".to_string(),
+            avatar_url: Some("https://example.com/avatar.png".to_string()),
+        };
+        let user_id = user.id;
+        let repo = Arc::new(MockUserProfileRepository { user, tenant_id });
+
+        (GetPublicProfileUseCase::new(repo), user_id)
+    }
+
+    #[tokio::test]
+    async fn test_public_profile_in_same_tenant() {
+        let tenant_id = Some(OrganizationId::new());
+        let (use_case, user_id) = use_case(tenant_id);
+
+        let profile = use_case.execute(user_id, tenant_id).await.unwrap();
+
+        assert_eq!(profile.name, "Mentioned User");
+    }
+
+    #[tokio::test]
+    async fn test_public_profile_in_other_tenant_is_not_found() {
+        let (use_case, user_id) = use_case(Some(OrganizationId::new()));
+
+        let result = use_case.execute(user_id, Some(OrganizationId::new())).await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,80 @@
+use crate::moduls::audit::infra::AuditEventRepository;
+use crate::moduls::audit::{AuditAction, AuditEvent};
+use crate::shared::{types::*, AppResult};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Events returned by one listing
+const RECENT_EVENTS: u32 = 50;
+
+/// Audit event, as shown to the user it is about
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEventSummary {
+    pub id: uuid::Uuid,
+    pub action: AuditAction,
+    pub tenant_id: Option<OrganizationId>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub occurred_at: Timestamp,
+}
+
+impl From<AuditEvent> for AuditEventSummary {
+    fn from(event: AuditEvent) -> Self {
+        Self {
+            id: event.id,
+            action: event.action,
+            tenant_id: event.tenant_id,
+            ip_address: event.ip_address,
+            user_agent: event.user_agent,
+            occurred_at: event.occurred_at,
+        }
+    }
+}
+
+/// List Audit Events Use Case
+/// Lists the user's most recent security events, newest first
+pub struct ListAuditEventsUseCase {
+    audit_repo: Arc<dyn AuditEventRepository>,
+}
+
+impl ListAuditEventsUseCase {
+    pub fn new(audit_repo: Arc<dyn AuditEventRepository>) -> Self {
+        Self { audit_repo }
+    }
+
+    /// Execute the use case to list a user's audit events
+    pub async fn execute(&self, user_id: UserId) -> AppResult<Vec<AuditEventSummary>> {
+        let events = self
+            .audit_repo
+            .find_recent_by_user(user_id, RECENT_EVENTS)
+            .await?;
+        Ok(events.into_iter().map(AuditEventSummary::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::audit::infra::in_memory::InMemoryAuditEventRepository;
+
+    #[tokio::test]
+    async fn test_only_the_users_latest_events_are_listed() {
+        let repo = Arc::new(InMemoryAuditEventRepository::default());
+        let user_id = UserId::new();
+        {
+            let mut events = repo.events.lock().unwrap();
+            events.push(AuditEvent::new(AuditAction::LoginFailed, Some(user_id)));
+            events.push(AuditEvent::new(AuditAction::LoginSucceeded, Some(UserId::new())));
+            for _ in 0..RECENT_EVENTS {
+                events.push(AuditEvent::new(AuditAction::LoginSucceeded, Some(user_id)));
+            }
+            events.push(AuditEvent::new(AuditAction::Logout, Some(user_id)));
+        }
+
+        let events = ListAuditEventsUseCase::new(repo).execute(user_id).await.unwrap();
+
+        assert_eq!(events.len(), RECENT_EVENTS as usize);
+        assert_eq!(events[0].action, AuditAction::Logout);
+        assert!(events.iter().all(|e| e.action != AuditAction::LoginFailed));
+    }
+}
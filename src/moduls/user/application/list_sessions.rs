@@ -0,0 +1,127 @@
+use crate::moduls::auth::domain::Session;
+use crate::moduls::auth::infra::SessionRepository;
+use crate::shared::{types::*, AppResult};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Active session, as shown to its owner
+///
+/// Leaves out the CSRF token.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub id: SessionId,
+    pub ip_address: Option<String>,
+    /// Device, as reported by the browser
+    pub user_agent: Option<String>,
+    /// Label the client chose at login
+    pub device_name: Option<String>,
+    pub created_at: Timestamp,
+    pub last_seen_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+impl From<Session> for SessionSummary {
+    fn from(session: Session) -> Self {
+        Self {
+            id: session.id,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent,
+            device_name: session.device_name,
+            created_at: session.created_at,
+            last_seen_at: session.updated_at,
+            expires_at: session.expires_at,
+        }
+    }
+}
+
+/// List Sessions Use Case
+/// Lists the user's live sessions, most recently active first
+pub struct ListSessionsUseCase {
+    session_repo: Arc<dyn SessionRepository>,
+}
+
+impl ListSessionsUseCase {
+    pub fn new(session_repo: Arc<dyn SessionRepository>) -> Self {
+        Self { session_repo }
+    }
+
+    /// Execute the use case to list a user's sessions
+    pub async fn execute(&self, user_id: UserId) -> AppResult<Vec<SessionSummary>> {
+        let sessions = self.session_repo.find_all_by_user_id(user_id).await?;
+        Ok(sessions.into_iter().map(SessionSummary::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::infra::in_memory::InMemorySessionRepository;
+
+    fn session(user_id: UserId, user_agent: &str) -> Session {
+        Session::new(
+            user_id,
+            Some("10.0.0.1".to_string()),
+            Some(user_agent.to_string()),
+            3600,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_two_logins_leave_two_live_sessions() {
+        let repo = Arc::new(InMemorySessionRepository::default());
+        let user_id = UserId::new();
+        repo.save(&session(user_id, "Phone")).await.unwrap();
+        repo.save(&session(user_id, "Laptop")).await.unwrap();
+        repo.save(&session(UserId::new(), "Someone else")).await.unwrap();
+
+        let sessions = ListSessionsUseCase::new(repo).execute(user_id).await.unwrap();
+
+        let devices: Vec<_> = sessions.iter().filter_map(|s| s.user_agent.as_deref()).collect();
+        assert_eq!(devices.len(), 2);
+        assert!(devices.contains(&"Phone") && devices.contains(&"Laptop"));
+    }
+
+    #[tokio::test]
+    async fn test_oldest_session_is_evicted_beyond_limit() {
+        let repo = Arc::new(InMemorySessionRepository {
+            max_concurrent: Some(1),
+            ..Default::default()
+        });
+        let user_id = UserId::new();
+        let mut phone = session(user_id, "Phone");
+        phone.created_at -= chrono::Duration::seconds(60);
+        repo.save(&phone).await.unwrap();
+        repo.save(&session(user_id, "Laptop")).await.unwrap();
+
+        let sessions = ListSessionsUseCase::new(repo).execute(user_id).await.unwrap();
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].user_agent.as_deref(), Some("Laptop"));
+    }
+
+    #[tokio::test]
+    async fn test_device_name_is_listed() {
+        let repo = Arc::new(InMemorySessionRepository::default());
+        let user_id = UserId::new();
+        repo.save(&session(user_id, "Phone").with_device_name(Some("John's iPhone".to_string())))
+            .await
+            .unwrap();
+
+        let sessions = ListSessionsUseCase::new(repo).execute(user_id).await.unwrap();
+
+        assert_eq!(sessions[0].device_name.as_deref(), Some("John's iPhone"));
+    }
+
+    #[tokio::test]
+    async fn test_expired_sessions_are_not_listed() {
+        let repo = Arc::new(InMemorySessionRepository::default());
+        let user_id = UserId::new();
+        let mut expired = session(user_id, "Old");
+        expired.expires_at = now() - chrono::Duration::seconds(1);
+        repo.save(&expired).await.unwrap();
+
+        let sessions = ListSessionsUseCase::new(repo).execute(user_id).await.unwrap();
+
+        assert!(sessions.is_empty());
+    }
+}
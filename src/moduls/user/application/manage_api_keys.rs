@@ -0,0 +1,185 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::domain::ApiKey;
+use crate::moduls::auth::infra::ApiKeyRepository;
+use crate::shared::{types::*, AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Create API Key Command (DTO)
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateApiKeyCommand {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+
+    /// When the key stops working; keys without one never expire
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+}
+
+/// API key, as shown to its owner
+///
+/// Leaves out the hash; the plain key is only in `CreatedApiKey`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKeySummary {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub last_used_at: Option<Timestamp>,
+    pub expires_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
+
+impl From<ApiKey> for ApiKeySummary {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            last_used_at: key.last_used_at,
+            expires_at: key.expires_at,
+            created_at: key.created_at,
+        }
+    }
+}
+
+/// Newly minted API key, including the plain key (shown only once)
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub summary: ApiKeySummary,
+    pub key: String,
+}
+
+/// Manage API Keys Use Case
+/// Mints, lists and revokes the user's API keys
+pub struct ManageApiKeysUseCase {
+    api_key_repo: Arc<dyn ApiKeyRepository>,
+    audit_log: Arc<AuditLog>,
+}
+
+impl ManageApiKeysUseCase {
+    pub fn new(api_key_repo: Arc<dyn ApiKeyRepository>, audit_log: Arc<AuditLog>) -> Self {
+        Self {
+            api_key_repo,
+            audit_log,
+        }
+    }
+
+    /// Mint a key authenticating as `user_id` within `tenant_id`
+    ///
+    /// # Errors
+    /// - Validation error if the name is blank or `expires_at` has passed
+    /// - Database errors
+    pub async fn create(
+        &self,
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+        cmd: CreateApiKeyCommand,
+    ) -> AppResult<CreatedApiKey> {
+        let name = cmd.name.trim();
+        if name.is_empty() {
+            return Err(AppError::Validation("Name is required".into()));
+        }
+        if cmd.expires_at.is_some_and(|expires_at| expires_at <= now()) {
+            return Err(AppError::Validation("Expiry must be in the future".into()));
+        }
+
+        let (key, plain) = ApiKey::issue(user_id, tenant_id, name.to_string(), cmd.expires_at);
+        let key = self.api_key_repo.save(&key).await?;
+
+        tracing::info!("User {} created API key {}", user_id, key.id);
+        self.audit_log
+            .record(AuditEvent::new(AuditAction::ApiKeyCreated, Some(user_id)))
+            .await;
+
+        Ok(CreatedApiKey {
+            summary: key.into(),
+            key: plain,
+        })
+    }
+
+    /// List the user's unrevoked keys, newest first
+    pub async fn list(&self, user_id: UserId) -> AppResult<Vec<ApiKeySummary>> {
+        let keys = self.api_key_repo.find_by_user_id(user_id).await?;
+        Ok(keys.into_iter().map(ApiKeySummary::from).collect())
+    }
+
+    /// Revoke one of the user's keys
+    ///
+    /// # Errors
+    /// - NotFound if the key doesn't exist, is already revoked, or belongs
+    ///   to another user
+    pub async fn revoke(&self, user_id: UserId, key_id: uuid::Uuid) -> AppResult<()> {
+        if !self.api_key_repo.revoke(key_id, user_id).await? {
+            return Err(AppError::NotFound("API key not found".into()));
+        }
+
+        self.audit_log
+            .record(AuditEvent::new(AuditAction::ApiKeyRevoked, Some(user_id)))
+            .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::infra::in_memory::InMemoryApiKeyRepository;
+
+    fn use_case(repo: Arc<InMemoryApiKeyRepository>) -> ManageApiKeysUseCase {
+        ManageApiKeysUseCase::new(repo, Arc::new(AuditLog::for_tests()))
+    }
+
+    fn command(name: &str) -> CreateApiKeyCommand {
+        CreateApiKeyCommand {
+            name: name.to_string(),
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_created_key_is_stored_hashed_and_listed() {
+        let repo = Arc::new(InMemoryApiKeyRepository::default());
+        let use_case = use_case(repo.clone());
+        let user_id = UserId::new();
+
+        let created = use_case.create(user_id, None, command(" billing ")).await.unwrap();
+
+        assert!(created.key.starts_with(ApiKey::PREFIX));
+        let stored = repo.find_by_hash(&ApiKey::hash(&created.key)).await.unwrap().unwrap();
+        assert_eq!(stored.name, "billing");
+
+        let listed = use_case.list(user_id).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, created.summary.id);
+    }
+
+    #[tokio::test]
+    async fn test_past_expiry_is_rejected() {
+        let use_case = use_case(Arc::new(InMemoryApiKeyRepository::default()));
+        let cmd = CreateApiKeyCommand {
+            expires_at: Some(now() - chrono::Duration::seconds(1)),
+            ..command("ci")
+        };
+
+        let result = use_case.create(UserId::new(), None, cmd).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_other_users_key_is_not_found() {
+        let repo = Arc::new(InMemoryApiKeyRepository::default());
+        let use_case = use_case(repo.clone());
+        let owner = UserId::new();
+        let created = use_case.create(owner, None, command("ci")).await.unwrap();
+
+        let result = use_case.revoke(UserId::new(), created.summary.id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        use_case.revoke(owner, created.summary.id).await.unwrap();
+        assert!(use_case.list(owner).await.unwrap().is_empty());
+        let result = use_case.revoke(owner, created.summary.id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
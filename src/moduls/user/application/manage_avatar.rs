@@ -0,0 +1,194 @@
+use crate::moduls::user::domain::{Avatar, AvatarLimits, UserProfile};
+use crate::moduls::user::infra::{AvatarRepository, UserProfileRepository};
+use crate::shared::{types::UserId, AppError, AppResult};
+use std::sync::Arc;
+
+/// Manage Avatar Use Case
+/// Stores uploaded avatars and serves them back
+///
+/// Uploads are decoded, checked against `AvatarLimits` and normalized to
+/// PNG (see `Avatar::from_upload`); the profile's `avatar_url` then points
+/// at `GET /api/users/{id}/avatar`.
+pub struct ManageAvatarUseCase {
+    avatar_repo: Arc<dyn AvatarRepository>,
+    profile_repo: Arc<dyn UserProfileRepository>,
+    limits: AvatarLimits,
+    /// Public base URL the avatar URL is built on
+    app_url: String,
+}
+
+impl ManageAvatarUseCase {
+    pub fn new(
+        avatar_repo: Arc<dyn AvatarRepository>,
+        profile_repo: Arc<dyn UserProfileRepository>,
+        limits: AvatarLimits,
+        app_url: String,
+    ) -> Self {
+        Self {
+            avatar_repo,
+            profile_repo,
+            limits,
+            app_url,
+        }
+    }
+
+    /// Replace the avatar of `user_id` with the uploaded image
+    ///
+    /// # Errors
+    /// - Validation if the upload isn't a PNG, JPEG or WebP image, or is
+    ///   larger than the source dimension limit
+    /// - NotFound if the user doesn't exist
+    /// - Database errors
+    pub async fn upload(&self, user_id: UserId, bytes: &[u8]) -> AppResult<UserProfile> {
+        // 1. Load current profile
+        let mut profile = self
+            .profile_repo
+            .find_by_user_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Profile not found".into()))?;
+
+        // 2. Decode and normalize (business rules applied)
+        let avatar = Avatar::from_upload(user_id, bytes, self.limits)?;
+        self.avatar_repo.save(&avatar).await?;
+
+        // 3. Point the profile at it; the version busts client caches
+        profile.update_avatar(Some(format!(
+            "{}/api/users/{}/avatar?v={}",
+            self.app_url,
+            user_id,
+            avatar.updated_at.timestamp_millis()
+        )))?;
+        self.profile_repo.update(&profile).await
+    }
+
+    /// Uploaded avatar of `user_id`
+    ///
+    /// # Errors
+    /// - NotFound if the user has no uploaded avatar
+    /// - Database errors
+    pub async fn get(&self, user_id: UserId) -> AppResult<Avatar> {
+        self.avatar_repo
+            .find_by_user_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Avatar not found".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::user::domain::PublicUserDto;
+    use crate::moduls::user::infra::in_memory::InMemoryAvatarRepository;
+    use crate::shared::types::OrganizationId;
+    use async_trait::async_trait;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    struct MockUserProfileRepository {
+        profile: Mutex<UserProfile>,
+    }
+
+    #[async_trait]
+    impl UserProfileRepository for MockUserProfileRepository {
+        async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<UserProfile>> {
+            let profile = self.profile.lock().unwrap();
+            Ok((profile.user_id == user_id).then(|| profile.clone()))
+        }
+
+        async fn update(&self, profile: &UserProfile) -> AppResult<UserProfile> {
+            *self.profile.lock().unwrap() = profile.clone();
+            Ok(profile.clone())
+        }
+
+        async fn find_public(
+            &self,
+            _user_id: UserId,
+            _tenant_id: Option<OrganizationId>,
+        ) -> AppResult<Option<PublicUserDto>> {
+            Ok(None)
+        }
+    }
+
+    struct Fixture {
+        use_case: ManageAvatarUseCase,
+        avatar_repo: Arc<InMemoryAvatarRepository>,
+        user_id: UserId,
+    }
+
+    fn fixture() -> Fixture {
+        let user_id = UserId::new();
+        let profile_repo = Arc::new(MockUserProfileRepository {
+            profile: Mutex::new(UserProfile {
+                user_id,
+                name: "Test User".to_string(),
+                email: "test@example.com".to_string(),
+                bio: None,
+                avatar_url: None,
+                updated_at: chrono::Utc::now(),
+            }),
+        });
+        let avatar_repo = Arc::new(InMemoryAvatarRepository::default());
+
+        Fixture {
+            use_case: ManageAvatarUseCase::new(
+                avatar_repo.clone(),
+                profile_repo,
+                AvatarLimits {
+                    max_dimension: 512,
+                    max_source_dimension: 4096,
+                },
+                "https://app.example.com".to_string(),
+            ),
+            avatar_repo,
+            user_id,
+        }
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[tokio::test]
+    async fn test_upload_stores_avatar_and_links_profile() {
+        let f = fixture();
+
+        let profile = f.use_case.upload(f.user_id, &png(64, 64)).await.unwrap();
+
+        let url = profile.avatar_url.unwrap();
+        assert!(url.starts_with(&format!(
+            "https://app.example.com/api/users/{}/avatar?v=",
+            f.user_id
+        )));
+        let avatar = f.use_case.get(f.user_id).await.unwrap();
+        assert_eq!(image::guess_format(&avatar.data).unwrap(), ImageFormat::Png);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_upload_keeps_previous_avatar() {
+        let f = fixture();
+        f.use_case.upload(f.user_id, &png(64, 64)).await.unwrap();
+        let previous = f.use_case.get(f.user_id).await.unwrap().data;
+
+        let result = f.use_case.upload(f.user_id, &png(5000, 1)).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        assert_eq!(f.use_case.get(f.user_id).await.unwrap().data, previous);
+        assert_eq!(f.avatar_repo.avatars.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_or_missing_avatar_is_not_found() {
+        let f = fixture();
+
+        let result = f.use_case.upload(UserId::new(), &png(64, 64)).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        let result = f.use_case.get(f.user_id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
@@ -1,7 +1,33 @@
+pub mod change_email;
 pub mod change_password;
+pub mod delete_account;
 pub mod get_profile;
+pub mod get_public_profile;
+pub mod list_audit_events;
+pub mod list_sessions;
+pub mod manage_avatar;
+pub mod manage_api_keys;
+pub mod notification_preferences;
+pub mod revoke_session;
 pub mod update_profile;
+pub mod verify_password;
 
+pub use change_email::{
+    ChangeEmailUseCase, ConfirmEmailChangeCommand, RequestEmailChangeCommand,
+};
 pub use change_password::{ChangePasswordCommand, ChangePasswordUseCase};
+pub use delete_account::{DeleteAccountCommand, DeleteAccountUseCase};
 pub use get_profile::GetProfileUseCase;
+pub use get_public_profile::GetPublicProfileUseCase;
+pub use list_audit_events::{AuditEventSummary, ListAuditEventsUseCase};
+pub use list_sessions::{ListSessionsUseCase, SessionSummary};
+pub use manage_avatar::ManageAvatarUseCase;
+pub use manage_api_keys::{
+    ApiKeySummary, CreateApiKeyCommand, CreatedApiKey, ManageApiKeysUseCase,
+};
+pub use notification_preferences::{
+    NotificationPreferencesUseCase, UpdateNotificationPreferencesCommand,
+};
+pub use revoke_session::RevokeSessionUseCase;
 pub use update_profile::{UpdateProfileCommand, UpdateProfileUseCase};
+pub use verify_password::{VerifyPasswordCommand, VerifyPasswordLimits, VerifyPasswordUseCase};
@@ -0,0 +1,125 @@
+use crate::moduls::auth::domain::{NotificationCategory, NotificationPreferences};
+use crate::moduls::auth::infra::UserRepository;
+use crate::shared::{types::UserId, AppError, AppResult};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Update Notification Preferences Command (DTO)
+/// Categories to turn on or off; omitted ones are left unchanged
+#[derive(Debug, Clone, Default, serde::Deserialize, Validate)]
+pub struct UpdateNotificationPreferencesCommand {
+    pub new_device_login: Option<bool>,
+    pub password_changed: Option<bool>,
+    /// Accepted only as `true`: reset emails are mandatory
+    pub password_reset: Option<bool>,
+}
+
+/// Notification Preferences Use Case
+/// Reads and changes which security emails the user receives
+pub struct NotificationPreferencesUseCase {
+    user_repo: Arc<dyn UserRepository>,
+}
+
+impl NotificationPreferencesUseCase {
+    pub fn new(user_repo: Arc<dyn UserRepository>) -> Self {
+        Self { user_repo }
+    }
+
+    /// Current preferences of `user_id`
+    ///
+    /// # Errors
+    /// - NotFound if the user doesn't exist
+    /// - Database errors
+    pub async fn get(&self, user_id: UserId) -> AppResult<NotificationPreferences> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+        Ok(user.notification_preferences)
+    }
+
+    /// Apply `cmd` and return the resulting preferences
+    ///
+    /// # Errors
+    /// - Validation if the command disables a mandatory category (nothing
+    ///   is saved)
+    /// - NotFound if the user doesn't exist
+    /// - Database errors
+    pub async fn update(
+        &self,
+        user_id: UserId,
+        cmd: UpdateNotificationPreferencesCommand,
+    ) -> AppResult<NotificationPreferences> {
+        let mut user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+        let changes = [
+            (NotificationCategory::NewDeviceLogin, cmd.new_device_login),
+            (NotificationCategory::PasswordChanged, cmd.password_changed),
+            (NotificationCategory::PasswordReset, cmd.password_reset),
+        ];
+        for (category, enabled) in changes {
+            if let Some(enabled) = enabled {
+                user.notification_preferences.set(category, enabled)?;
+            }
+        }
+
+        let user = self.user_repo.update(&user).await?;
+        Ok(user.notification_preferences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{Email, User};
+    use crate::moduls::auth::infra::in_memory::InMemoryUserRepository;
+
+    fn fixture() -> (NotificationPreferencesUseCase, UserId) {
+        let user = User::new(
+            Email::new("prefs@example.com").unwrap(),
+            "password123",
+            "Prefs User".to_string(),
+        )
+        .unwrap();
+        let user_id = user.id;
+        let use_case =
+            NotificationPreferencesUseCase::new(Arc::new(InMemoryUserRepository::with_user(user)));
+        (use_case, user_id)
+    }
+
+    #[tokio::test]
+    async fn test_update_changes_only_given_categories() {
+        let (use_case, user_id) = fixture();
+
+        let cmd = UpdateNotificationPreferencesCommand {
+            new_device_login: Some(false),
+            ..Default::default()
+        };
+        let updated = use_case.update(user_id, cmd).await.unwrap();
+
+        assert!(!updated.new_device_login);
+        assert!(updated.password_changed);
+        assert_eq!(use_case.get(user_id).await.unwrap(), updated);
+    }
+
+    #[tokio::test]
+    async fn test_disabling_password_reset_is_rejected_without_saving() {
+        let (use_case, user_id) = fixture();
+
+        let cmd = UpdateNotificationPreferencesCommand {
+            new_device_login: Some(false),
+            password_reset: Some(false),
+            ..Default::default()
+        };
+        let result = use_case.update(user_id, cmd).await;
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        assert!(use_case.get(user_id).await.unwrap().new_device_login);
+    }
+}
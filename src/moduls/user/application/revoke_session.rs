@@ -0,0 +1,122 @@
+use crate::moduls::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::moduls::auth::infra::{SessionRepository, TokenRepository};
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+
+/// Revoke Session Use Case
+/// Signs one of the user's sessions out (e.g. a lost device), along with
+/// the API tokens minted under it
+pub struct RevokeSessionUseCase {
+    session_repo: Arc<dyn SessionRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    audit_log: Arc<AuditLog>,
+}
+
+impl RevokeSessionUseCase {
+    pub fn new(
+        session_repo: Arc<dyn SessionRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        audit_log: Arc<AuditLog>,
+    ) -> Self {
+        Self {
+            session_repo,
+            token_repo,
+            audit_log,
+        }
+    }
+
+    /// Execute the use case to revoke `session_id`
+    ///
+    /// # Errors
+    /// - NotFound if the session doesn't exist or belongs to another user
+    ///   (the two are indistinguishable, so session ids don't leak)
+    /// - Database errors
+    pub async fn execute(&self, user_id: UserId, session_id: SessionId) -> AppResult<()> {
+        self.session_repo
+            .find_by_id(session_id)
+            .await?
+            .filter(|s| s.user_id == user_id)
+            .ok_or_else(|| AppError::NotFound("Session not found".into()))?;
+
+        self.session_repo.delete(session_id).await?;
+        self.token_repo.revoke_session_tokens(session_id).await?;
+
+        self.audit_log
+            .record(AuditEvent::new(AuditAction::SessionRevoked, Some(user_id)))
+            .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{ClaimsFormat, JwtKeys, Session, TokenPair};
+    use crate::moduls::auth::infra::in_memory::{InMemorySessionRepository, InMemoryTokenRepository};
+
+    fn use_case(
+        session_repo: Arc<InMemorySessionRepository>,
+        token_repo: Arc<InMemoryTokenRepository>,
+    ) -> RevokeSessionUseCase {
+        RevokeSessionUseCase::new(session_repo, token_repo, Arc::new(AuditLog::for_tests()))
+    }
+
+    #[tokio::test]
+    async fn test_revoke_leaves_other_sessions() {
+        let repo = Arc::new(InMemorySessionRepository::default());
+        let user_id = UserId::new();
+        let phone = repo.save(&Session::new(user_id, None, None, 3600)).await.unwrap();
+        let laptop = repo.save(&Session::new(user_id, None, None, 3600)).await.unwrap();
+        let use_case = use_case(repo.clone(), Arc::new(InMemoryTokenRepository::default()));
+
+        use_case.execute(user_id, phone.id).await.unwrap();
+
+        assert!(repo.find_by_id(phone.id).await.unwrap().is_none());
+        assert!(repo.find_by_id(laptop.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_other_users_session_is_not_found() {
+        let repo = Arc::new(InMemorySessionRepository::default());
+        let other = repo.save(&Session::new(UserId::new(), None, None, 3600)).await.unwrap();
+        let use_case = use_case(repo.clone(), Arc::new(InMemoryTokenRepository::default()));
+
+        let result = use_case.execute(UserId::new(), other.id).await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        assert!(repo.find_by_id(other.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_cascades_to_session_tokens() {
+        let session_repo = Arc::new(InMemorySessionRepository::default());
+        let token_repo = Arc::new(InMemoryTokenRepository::default());
+        let user_id = UserId::new();
+        let session = session_repo.save(&Session::new(user_id, None, None, 3600)).await.unwrap();
+        let keys = JwtKeys::hmac("test_secret_key_for_jwt_signing_minimum_32_chars");
+        let (_, bridged, _) = TokenPair::generate_with_auth_time(
+            user_id,
+            None,
+            None,
+            None,
+            Some(session.id),
+            ClaimsFormat::Verbose,
+            &keys,
+            900,
+            3600,
+        )
+        .unwrap();
+        let (_, standalone, _) = TokenPair::generate(user_id, &keys, 900, 3600).unwrap();
+        token_repo.save(&bridged).await.unwrap();
+        token_repo.save(&standalone).await.unwrap();
+
+        use_case(session_repo, token_repo.clone())
+            .execute(user_id, session.id)
+            .await
+            .unwrap();
+
+        assert!(token_repo.find_by_jti(bridged.jti).await.unwrap().unwrap().is_revoked());
+        assert!(!token_repo.find_by_jti(standalone.jti).await.unwrap().unwrap().is_revoked());
+    }
+}
@@ -35,23 +35,19 @@ impl UpdateProfileUseCase {
         user_id: UserId,
         cmd: UpdateProfileCommand,
     ) -> AppResult<UserProfile> {
-        // 1. Validate input
-        cmd.validate()
-            .map_err(|e| AppError::Validation(e.to_string()))?;
-
-        // 2. Load current profile
+        // 1. Load current profile
         let mut profile = self
             .profile_repo
             .find_by_user_id(user_id)
             .await?
             .ok_or_else(|| AppError::NotFound("Profile not found".into()))?;
 
-        // 3. Update fields using domain methods (business rules applied)
+        // 2. Update fields using domain methods (business rules applied)
         profile.update_name(cmd.name)?;
         profile.update_bio(cmd.bio)?;
         profile.update_avatar(cmd.avatar_url)?;
 
-        // 4. Save and return updated profile
+        // 3. Save and return updated profile
         self.profile_repo.update(&profile).await
     }
 }
@@ -60,7 +56,6 @@ impl UpdateProfileUseCase {
 mod tests {
     use super::*;
     use crate::moduls::user::domain::UserProfile;
-    use crate::shared::types::new_id;
     use async_trait::async_trait;
 
     struct MockUserProfileRepository {
@@ -76,11 +71,19 @@ mod tests {
         async fn update(&self, profile: &UserProfile) -> AppResult<UserProfile> {
             Ok(profile.clone())
         }
+
+        async fn find_public(
+            &self,
+            _user_id: UserId,
+            _tenant_id: Option<crate::shared::types::OrganizationId>,
+        ) -> AppResult<Option<crate::moduls::user::domain::PublicUserDto>> {
+            Ok(None)
+        }
     }
 
     #[tokio::test]
     async fn test_update_profile_success() {
-        let user_id = new_id();
+        let user_id = UserId::new();
         let profile = UserProfile {
             user_id,
             name: "Old Name".to_string(),
@@ -110,7 +113,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_profile_empty_name_fails() {
-        let user_id = new_id();
+        let user_id = UserId::new();
         let profile = UserProfile {
             user_id,
             name: "Old Name".to_string(),
@@ -0,0 +1,237 @@
+use crate::moduls::auth::application::AccountLockout;
+use crate::moduls::auth::domain::PasswordHash;
+use crate::moduls::auth::infra::{LoginAttemptRepository, UserRepository};
+use crate::shared::{types::*, AppError, AppResult};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Verify Password Command (DTO)
+#[derive(Debug, Clone, serde::Deserialize, Validate)]
+pub struct VerifyPasswordCommand {
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+/// Limits on password verification attempts
+#[derive(Debug, Clone, Copy)]
+pub struct VerifyPasswordLimits {
+    /// Failed attempts allowed within `window_seconds` before verification is refused
+    pub max_failures: u32,
+    pub window_seconds: i64,
+}
+
+/// Verify Password Use Case
+/// Checks the current user's password without issuing tokens (step-up, sudo mode)
+///
+/// Business Logic:
+/// 1. Reject oversized passwords before verifying
+/// 2. Refuse while the user has too many recent failed attempts, so the
+///    endpoint can't be used as a password oracle
+/// 3. Load user and verify the password
+/// 4. Record failures with the login attempts, so they count toward the
+///    same limit and lockout as failed logins and show in the security
+///    summary
+pub struct VerifyPasswordUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    login_attempt_repo: Arc<dyn LoginAttemptRepository>,
+    max_password_length: usize,
+    limits: VerifyPasswordLimits,
+    lockout: Option<Arc<AccountLockout>>,
+}
+
+impl VerifyPasswordUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        login_attempt_repo: Arc<dyn LoginAttemptRepository>,
+        max_password_length: usize,
+        limits: VerifyPasswordLimits,
+    ) -> Self {
+        Self {
+            user_repo,
+            login_attempt_repo,
+            max_password_length,
+            limits,
+            lockout: None,
+        }
+    }
+
+    /// Lock accounts after repeated failures, like failed logins do
+    pub fn with_lockout(mut self, lockout: Option<Arc<AccountLockout>>) -> Self {
+        self.lockout = lockout;
+        self
+    }
+
+    /// Execute the use case
+    ///
+    /// # Errors
+    /// - Too many requests while the failure limit is reached
+    /// - Authentication error if the password does not match
+    /// - Not found if the user no longer exists
+    pub async fn execute(&self, user_id: UserId, cmd: VerifyPasswordCommand) -> AppResult<()> {
+        // 1. Reject oversized passwords
+        PasswordHash::ensure_max_length(&cmd.password, self.max_password_length)?;
+
+        // 2. Rate limit on recent failures
+        let since = now() - chrono::Duration::seconds(self.limits.window_seconds);
+        let summary = self.login_attempt_repo.security_summary(user_id, since).await?;
+        if summary.recent_failed_logins >= i64::from(self.limits.max_failures) {
+            tracing::warn!("Password verification refused for user {}: too many failures", user_id);
+            return Err(AppError::too_many_requests(
+                "Too many failed attempts, try again later",
+            ));
+        }
+
+        // 3. Load user and verify
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        if !user.verify_password(&cmd.password)? {
+            // 4. Count the failure
+            self.login_attempt_repo.record(user_id, false, None).await?;
+            if let Some(lockout) = &self.lockout {
+                lockout.after_failure(&user).await;
+            }
+            return Err(AppError::authentication("Invalid password"));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduls::auth::domain::{Email, User};
+    use crate::moduls::auth::infra::in_memory::*;
+
+    struct Fixture {
+        use_case: VerifyPasswordUseCase,
+        login_attempt_repo: Arc<InMemoryLoginAttemptRepository>,
+        user_id: UserId,
+    }
+
+    fn fixture(max_failures: u32) -> Fixture {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        let user_id = user.id;
+        let login_attempt_repo = Arc::new(InMemoryLoginAttemptRepository::default());
+
+        let use_case = VerifyPasswordUseCase::new(
+            Arc::new(InMemoryUserRepository::with_user(user)),
+            login_attempt_repo.clone(),
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            VerifyPasswordLimits {
+                max_failures,
+                window_seconds: 900,
+            },
+        );
+
+        Fixture {
+            use_case,
+            login_attempt_repo,
+            user_id,
+        }
+    }
+
+    fn command(password: &str) -> VerifyPasswordCommand {
+        VerifyPasswordCommand {
+            password: password.to_string(),
+        }
+    }
+
+    fn failures(f: &Fixture) -> usize {
+        f.login_attempt_repo
+            .attempts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| a.user_id == f.user_id && !a.succeeded)
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_correct_password_verifies() {
+        let f = fixture(5);
+
+        f.use_case.execute(f.user_id, command("password123")).await.unwrap();
+
+        assert_eq!(failures(&f), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_password_is_recorded_as_failure() {
+        let f = fixture(5);
+
+        let result = f.use_case.execute(f.user_id, command("wrongpassword")).await;
+
+        assert!(matches!(result, Err(AppError::Authentication(_))));
+        assert_eq!(failures(&f), 1);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_failures_lock_verification() {
+        let f = fixture(3);
+        for _ in 0..3 {
+            let _ = f.use_case.execute(f.user_id, command("wrongpassword")).await;
+        }
+
+        // Even the right password is refused until the window passes
+        let result = f.use_case.execute(f.user_id, command("password123")).await;
+
+        assert!(matches!(result, Err(AppError::TooManyRequests(_))));
+        assert_eq!(failures(&f), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_logins_count_toward_limit() {
+        let f = fixture(2);
+        f.login_attempt_repo.record(f.user_id, false, None).await.unwrap();
+        f.login_attempt_repo.record(f.user_id, false, None).await.unwrap();
+
+        let result = f.use_case.execute(f.user_id, command("password123")).await;
+
+        assert!(matches!(result, Err(AppError::TooManyRequests(_))));
+    }
+
+    #[tokio::test]
+    async fn test_failures_count_toward_account_lockout() {
+        use crate::moduls::audit::AuditLog;
+        use crate::moduls::auth::application::LockoutPolicy;
+        use crate::moduls::auth::domain::AccountStatus;
+
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, "password123", "Test User".to_string()).unwrap();
+        let user_id = user.id;
+        let user_repo = Arc::new(InMemoryUserRepository::with_user(user));
+        let login_attempt_repo = Arc::new(InMemoryLoginAttemptRepository::default());
+        let lockout = AccountLockout::new(
+            user_repo.clone(),
+            login_attempt_repo.clone(),
+            Arc::new(AuditLog::for_tests()),
+            LockoutPolicy {
+                threshold: 2,
+                duration_seconds: 900,
+            },
+        );
+        let use_case = VerifyPasswordUseCase::new(
+            user_repo.clone(),
+            login_attempt_repo,
+            PasswordHash::DEFAULT_MAX_LENGTH,
+            VerifyPasswordLimits {
+                max_failures: 5,
+                window_seconds: 900,
+            },
+        )
+        .with_lockout(Some(Arc::new(lockout)));
+
+        for _ in 0..2 {
+            let _ = use_case.execute(user_id, command("wrongpassword")).await;
+        }
+
+        let user = user_repo.find_by_id(user_id).await.unwrap().unwrap();
+        assert_eq!(user.status(), AccountStatus::Locked);
+    }
+}
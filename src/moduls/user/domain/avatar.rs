@@ -0,0 +1,183 @@
+use crate::shared::{
+    types::{Timestamp, UserId},
+    AppError, AppResult,
+};
+use image::{imageops::FilterType, DynamicImage, ImageFormat, ImageReader, Limits};
+use std::io::Cursor;
+
+/// Dimension limits of uploaded avatars
+#[derive(Debug, Clone, Copy)]
+pub struct AvatarLimits {
+    /// Largest stored width and height; bigger images are scaled down
+    pub max_dimension: u32,
+    /// Largest accepted width and height of the upload itself
+    pub max_source_dimension: u32,
+}
+
+/// Avatar domain entity
+/// An uploaded avatar, normalized to PNG
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Avatar {
+    pub user_id: UserId,
+    pub data: Vec<u8>,
+    pub updated_at: Timestamp,
+}
+
+impl Avatar {
+    /// Content type of the stored image
+    pub const CONTENT_TYPE: &'static str = "image/png";
+
+    /// Decode an uploaded PNG, JPEG or WebP image and normalize it
+    ///
+    /// Business Rules:
+    /// - The dimensions are read from the header before decoding, so an
+    ///   image that would inflate to gigabytes (a small file claiming huge
+    ///   dimensions) is rejected without allocating its pixels
+    /// - Images wider or taller than `max_dimension` are scaled down,
+    ///   keeping their aspect ratio
+    /// - Whatever the upload, the stored image is an RGBA PNG
+    pub fn from_upload(user_id: UserId, bytes: &[u8], limits: AvatarLimits) -> AppResult<Self> {
+        let reader = || {
+            ImageReader::new(Cursor::new(bytes))
+                .with_guessed_format()
+                .map_err(|_| AppError::Validation("Avatar is not a valid image".into()))
+        };
+
+        let format = reader()?.format();
+        if !matches!(format, Some(ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP)) {
+            return Err(AppError::Validation(
+                "Avatar must be a PNG, JPEG or WebP image".into(),
+            ));
+        }
+
+        let (width, height) = reader()?
+            .into_dimensions()
+            .map_err(|_| AppError::Validation("Avatar is not a valid image".into()))?;
+        if width == 0 || height == 0 {
+            return Err(AppError::Validation("Avatar is not a valid image".into()));
+        }
+        if width > limits.max_source_dimension || height > limits.max_source_dimension {
+            return Err(AppError::Validation(format!(
+                "Avatar cannot exceed {0}x{0} pixels",
+                limits.max_source_dimension
+            )));
+        }
+
+        // The decoder enforces the same bound in case the header lied
+        let mut decoder_limits = Limits::default();
+        decoder_limits.max_image_width = Some(limits.max_source_dimension);
+        decoder_limits.max_image_height = Some(limits.max_source_dimension);
+        let mut reader = reader()?;
+        reader.limits(decoder_limits);
+        let mut image = reader
+            .decode()
+            .map_err(|_| AppError::Validation("Avatar is not a valid image".into()))?;
+
+        if width > limits.max_dimension || height > limits.max_dimension {
+            image = image.resize(limits.max_dimension, limits.max_dimension, FilterType::Lanczos3);
+        }
+
+        let mut data = Vec::new();
+        DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .map_err(|e| AppError::internal(format!("Failed to encode avatar: {}", e)))?;
+
+        Ok(Self {
+            user_id,
+            data,
+            updated_at: chrono::Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, RgbImage};
+
+    const LIMITS: AvatarLimits = AvatarLimits {
+        max_dimension: 512,
+        max_source_dimension: 4096,
+    };
+
+    fn encode(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .unwrap();
+        bytes
+    }
+
+    fn dimensions(avatar: &Avatar) -> (u32, u32) {
+        image::load_from_memory_with_format(&avatar.data, ImageFormat::Png)
+            .unwrap()
+            .dimensions()
+    }
+
+    /// CRC-32 as used by PNG chunks
+    fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for byte in bytes {
+            crc ^= u32::from(*byte);
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            }
+        }
+        !crc
+    }
+
+    #[test]
+    fn test_normal_image_is_kept_as_is() {
+        let avatar = Avatar::from_upload(UserId::new(), &encode(200, 150, ImageFormat::Jpeg), LIMITS)
+            .unwrap();
+
+        assert_eq!(dimensions(&avatar), (200, 150));
+        assert_eq!(image::guess_format(&avatar.data).unwrap(), ImageFormat::Png);
+    }
+
+    #[test]
+    fn test_over_dimensioned_image_is_scaled_down() {
+        let avatar = Avatar::from_upload(UserId::new(), &encode(1024, 600, ImageFormat::Png), LIMITS)
+            .unwrap();
+
+        assert_eq!(dimensions(&avatar), (512, 300));
+    }
+
+    #[test]
+    fn test_image_beyond_source_limit_is_rejected() {
+        let result = Avatar::from_upload(UserId::new(), &encode(4097, 1, ImageFormat::Png), LIMITS);
+
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_decompression_bomb_is_rejected() {
+        // A tiny PNG whose header claims 60000x60000 pixels (~14 GB decoded)
+        let mut bytes = encode(1, 1, ImageFormat::Png);
+        bytes[16..20].copy_from_slice(&60_000u32.to_be_bytes());
+        bytes[20..24].copy_from_slice(&60_000u32.to_be_bytes());
+        let crc = crc32(&bytes[12..29]);
+        bytes[29..33].copy_from_slice(&crc.to_be_bytes());
+        assert!(bytes.len() < 100);
+
+        let result = Avatar::from_upload(UserId::new(), &bytes, LIMITS);
+
+        match result {
+            Err(AppError::Validation(message)) => assert!(message.contains("4096x4096")),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_or_garbage_input_is_rejected() {
+        let gif = b"GIF89a\x01\x00\x01\x00\x00\x00\x00;";
+        assert!(matches!(
+            Avatar::from_upload(UserId::new(), gif, LIMITS),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            Avatar::from_upload(UserId::new(), b"not an image", LIMITS),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::moduls::auth::domain::{one_time_token, Email};
+use crate::shared::types::*;
+
+/// Pending change of a user's login email
+///
+/// Like email verification tokens, only the hash of the confirmation
+/// token is stored and a request is single-use. The token is sent to
+/// `new_email`, so redeeming it proves the user owns that address.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct EmailChangeRequest {
+    pub id: uuid::Uuid,
+    pub user_id: UserId,
+    pub new_email: Email,
+    pub token_hash: String,
+    pub expires_at: Timestamp,
+    pub used_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
+
+impl EmailChangeRequest {
+    /// Issue a new request for a user
+    ///
+    /// Returns the entity together with the plain token to deliver.
+    pub fn issue(user_id: UserId, new_email: Email, ttl_seconds: i64) -> (Self, String) {
+        let plain = one_time_token::generate();
+        let now = now();
+
+        let request = Self {
+            id: new_id(),
+            user_id,
+            new_email,
+            token_hash: Self::hash(&plain),
+            expires_at: now + chrono::Duration::seconds(ttl_seconds),
+            used_at: None,
+            created_at: now,
+        };
+
+        (request, plain)
+    }
+
+    /// Hash a plain token for storage and lookup
+    pub fn hash(plain: &str) -> String {
+        one_time_token::hash(plain)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        now() > self.expires_at
+    }
+
+    /// Whether the request can still be confirmed
+    pub fn is_usable(&self) -> bool {
+        self.used_at.is_none() && !self.is_expired()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_stores_only_hash() {
+        let email = Email::new("new@example.com").unwrap();
+        let (request, plain) = EmailChangeRequest::issue(UserId::new(), email, 86400);
+
+        assert_eq!(request.token_hash, EmailChangeRequest::hash(&plain));
+        assert_ne!(request.token_hash, plain);
+        assert!(request.is_usable());
+    }
+
+    #[test]
+    fn test_expired_request_is_unusable() {
+        let email = Email::new("new@example.com").unwrap();
+        let (request, _) = EmailChangeRequest::issue(UserId::new(), email, -1);
+
+        assert!(!request.is_usable());
+    }
+}
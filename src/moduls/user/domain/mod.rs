@@ -1,3 +1,7 @@
+pub mod avatar;
+pub mod email_change;
 pub mod user_profile;
 
-pub use user_profile::UserProfile;
+pub use avatar::{Avatar, AvatarLimits};
+pub use email_change::EmailChangeRequest;
+pub use user_profile::{PublicUserDto, UserProfile};
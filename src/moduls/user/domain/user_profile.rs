@@ -88,14 +88,24 @@ impl UserProfile {
     }
 }
 
+/// Minimal view of another user (e.g. "who mentioned me")
+///
+/// Only what other users of the tenant may see: never the email, account
+/// status or activity.
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
+pub struct PublicUserDto {
+    pub id: UserId,
+    pub name: String,
+    pub avatar_url: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::shared::types::new_id;
 
     fn create_test_profile() -> UserProfile {
         UserProfile {
-            user_id: new_id(),
+            user_id: UserId::new(),
             name: "Test User".to_string(),
             email: "test@example.com".to_string(),
             bio: None,
@@ -147,4 +157,18 @@ mod tests {
             .update_avatar(Some("not-a-url".to_string()))
             .is_err());
     }
+
+    #[test]
+    fn test_public_user_serializes_only_public_fields() {
+        let public = PublicUserDto {
+            id: UserId::new(),
+            name: "Test User".to_string(),
+            avatar_url: None,
+        };
+
+        let json = serde_json::to_value(&public).unwrap();
+        let mut keys: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, ["avatar_url", "id", "name"]);
+    }
 }
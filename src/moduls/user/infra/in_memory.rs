@@ -0,0 +1,67 @@
+//! In-memory repository implementations for unit tests
+
+use super::{AvatarRepository, EmailChangeRepository};
+use crate::moduls::user::domain::{Avatar, EmailChangeRequest};
+use crate::shared::{types::*, AppResult};
+use async_trait::async_trait;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// In-memory EmailChangeRepository
+#[derive(Default)]
+pub struct InMemoryEmailChangeRepository {
+    pub requests: Mutex<Vec<EmailChangeRequest>>,
+}
+
+#[async_trait]
+impl EmailChangeRepository for InMemoryEmailChangeRepository {
+    async fn save(&self, request: &EmailChangeRequest) -> AppResult<EmailChangeRequest> {
+        self.requests.lock().unwrap().push(request.clone());
+        Ok(request.clone())
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<EmailChangeRequest>> {
+        let requests = self.requests.lock().unwrap();
+        Ok(requests.iter().find(|r| r.token_hash == token_hash).cloned())
+    }
+
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool> {
+        let mut requests = self.requests.lock().unwrap();
+        match requests.iter_mut().find(|r| r.id == id && r.used_at.is_none()) {
+            Some(request) => {
+                request.used_at = Some(now());
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn invalidate_user_requests(&self, user_id: UserId) -> AppResult<()> {
+        let mut requests = self.requests.lock().unwrap();
+        for request in requests.iter_mut().filter(|r| r.user_id == user_id && r.used_at.is_none()) {
+            request.used_at = Some(now());
+        }
+        Ok(())
+    }
+}
+
+/// In-memory AvatarRepository
+#[derive(Default)]
+pub struct InMemoryAvatarRepository {
+    pub avatars: Mutex<Vec<Avatar>>,
+}
+
+#[async_trait]
+impl AvatarRepository for InMemoryAvatarRepository {
+    async fn save(&self, avatar: &Avatar) -> AppResult<()> {
+        let mut avatars = self.avatars.lock().unwrap();
+        avatars.retain(|a| a.user_id != avatar.user_id);
+        avatars.push(avatar.clone());
+        Ok(())
+    }
+
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<Avatar>> {
+        let avatars = self.avatars.lock().unwrap();
+        Ok(avatars.iter().find(|a| a.user_id == user_id).cloned())
+    }
+}
@@ -1,5 +1,12 @@
+pub mod postgres_avatar_repository;
+pub mod postgres_email_change_repository;
 pub mod postgres_user_profile_repository;
 
+#[cfg(test)]
+pub mod in_memory;
+
+pub use postgres_avatar_repository::{AvatarRepository, PostgresAvatarRepository};
+pub use postgres_email_change_repository::{EmailChangeRepository, PostgresEmailChangeRepository};
 pub use postgres_user_profile_repository::{
     PostgresUserProfileRepository, UserProfileRepository,
 };
@@ -0,0 +1,60 @@
+use crate::moduls::user::domain::Avatar;
+use crate::shared::{db::DbPools, types::UserId, AppResult};
+use async_trait::async_trait;
+
+/// AvatarRepository trait defining avatar persistence
+///
+/// A user has at most one avatar; saving replaces it.
+#[async_trait]
+pub trait AvatarRepository: Send + Sync {
+    /// Save the avatar of `avatar.user_id`, replacing any previous one
+    async fn save(&self, avatar: &Avatar) -> AppResult<()>;
+
+    /// Find the avatar of a user
+    ///
+    /// Returns None if the user never uploaded one
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<Avatar>>;
+}
+
+/// PostgreSQL implementation of AvatarRepository
+pub struct PostgresAvatarRepository {
+    db: DbPools,
+}
+
+impl PostgresAvatarRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AvatarRepository for PostgresAvatarRepository {
+    async fn save(&self, avatar: &Avatar) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_avatars (user_id, data, updated_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE
+            SET data = EXCLUDED.data, updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(avatar.user_id)
+        .bind(&avatar.data)
+        .bind(avatar.updated_at)
+        .execute(self.db.writer())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<Avatar>> {
+        let avatar = sqlx::query_as::<_, Avatar>(
+            "SELECT user_id, data, updated_at FROM user_avatars WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(self.db.reader())
+        .await?;
+
+        Ok(avatar)
+    }
+}
@@ -0,0 +1,115 @@
+use crate::moduls::user::domain::EmailChangeRequest;
+use crate::shared::{db::DbPools, types::*, AppError, AppResult};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// EmailChangeRepository trait defining email change request persistence
+///
+/// Requests are looked up by token hash; confirming one is a conditional
+/// update so the same link cannot be used twice.
+#[async_trait]
+pub trait EmailChangeRepository: Send + Sync {
+    /// Save new email change request
+    async fn save(&self, request: &EmailChangeRequest) -> AppResult<EmailChangeRequest>;
+
+    /// Find request by the hash of its plain token
+    ///
+    /// Returns None if request not found
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<EmailChangeRequest>>;
+
+    /// Mark a request as used
+    ///
+    /// Returns false if it had already been used
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool>;
+
+    /// Mark every outstanding request of a user as used
+    async fn invalidate_user_requests(&self, user_id: UserId) -> AppResult<()>;
+}
+
+/// PostgreSQL implementation of EmailChangeRepository
+///
+/// Reads stay on the primary: a confirmed request must not look unused on a lagging replica.
+pub struct PostgresEmailChangeRepository {
+    db: DbPools,
+}
+
+impl PostgresEmailChangeRepository {
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl EmailChangeRepository for PostgresEmailChangeRepository {
+    async fn save(&self, request: &EmailChangeRequest) -> AppResult<EmailChangeRequest> {
+        let result = sqlx::query_as::<_, EmailChangeRequest>(
+            r#"
+            INSERT INTO email_change_requests (id, user_id, new_email, token_hash, expires_at, used_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, user_id, new_email, token_hash, expires_at, used_at, created_at
+            "#,
+        )
+        .bind(request.id)
+        .bind(request.user_id)
+        .bind(&request.new_email)
+        .bind(&request.token_hash)
+        .bind(request.expires_at)
+        .bind(request.used_at)
+        .bind(request.created_at)
+        .fetch_one(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to save email change request: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> AppResult<Option<EmailChangeRequest>> {
+        let result = sqlx::query_as::<_, EmailChangeRequest>(
+            r#"
+            SELECT id, user_id, new_email, token_hash, expires_at, used_at, created_at
+            FROM email_change_requests
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to find email change request: {}", e)))?;
+
+        Ok(result)
+    }
+
+    async fn mark_used(&self, id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE email_change_requests
+            SET used_at = $2
+            WHERE id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to redeem email change request: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn invalidate_user_requests(&self, user_id: UserId) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE email_change_requests
+            SET used_at = $2
+            WHERE user_id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(now())
+        .execute(self.db.writer())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to invalidate email change requests: {}", e)))?;
+
+        Ok(())
+    }
+}
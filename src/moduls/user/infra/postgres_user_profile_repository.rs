@@ -1,7 +1,10 @@
-use crate::moduls::user::domain::UserProfile;
-use crate::shared::{types::UserId, AppResult};
+use crate::moduls::user::domain::{PublicUserDto, UserProfile};
+use crate::shared::{
+    db::DbPools,
+    types::{OrganizationId, UserId},
+    AppResult,
+};
 use async_trait::async_trait;
-use sqlx::PgPool;
 
 /// User Profile Repository Trait
 /// Defines operations for persisting and retrieving user profiles
@@ -9,17 +12,28 @@ use sqlx::PgPool;
 pub trait UserProfileRepository: Send + Sync {
     async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<UserProfile>>;
     async fn update(&self, profile: &UserProfile) -> AppResult<UserProfile>;
+
+    /// Find another user's public profile as seen from `tenant_id`
+    ///
+    /// Users of a tenant (scoped to it or members) are visible within it;
+    /// without a tenant, only global users are.
+    async fn find_public(
+        &self,
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+    ) -> AppResult<Option<PublicUserDto>>;
 }
 
 /// PostgreSQL implementation of UserProfileRepository
-/// Note: Profile data is stored in the users table, not a separate table
+/// Note: Name and email live in the users table, bio and avatar in
+/// user_profiles; users without a profile row have an empty profile
 pub struct PostgresUserProfileRepository {
-    pool: PgPool,
+    db: DbPools,
 }
 
 impl PostgresUserProfileRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(db: DbPools) -> Self {
+        Self { db }
     }
 }
 
@@ -30,41 +44,54 @@ impl UserProfileRepository for PostgresUserProfileRepository {
         let profile = sqlx::query_as::<_, UserProfile>(
             r#"
             SELECT
-                id as user_id,
-                name,
-                email,
-                bio,
-                avatar_url,
-                updated_at
-            FROM users
-            WHERE id = $1
+                u.id as user_id,
+                u.name,
+                u.email,
+                p.bio,
+                p.avatar_url,
+                u.updated_at
+            FROM users u
+            LEFT JOIN user_profiles p ON p.user_id = u.id
+            WHERE u.id = $1
             "#,
         )
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.db.reader())
         .await?;
 
         Ok(profile)
     }
 
     /// Update user profile
+    /// Creates the profile row if the user has none yet
     async fn update(&self, profile: &UserProfile) -> AppResult<UserProfile> {
         let updated = sqlx::query_as::<_, UserProfile>(
             r#"
-            UPDATE users
-            SET
-                name = $1,
-                bio = $2,
-                avatar_url = $3,
-                updated_at = $4
-            WHERE id = $5
-            RETURNING
-                id as user_id,
-                name,
-                email,
-                bio,
-                avatar_url,
-                updated_at
+            WITH u AS (
+                UPDATE users
+                SET
+                    name = $1,
+                    updated_at = $4
+                WHERE id = $5
+                RETURNING id, name, email, updated_at
+            ), p AS (
+                INSERT INTO user_profiles (user_id, bio, avatar_url, updated_at)
+                SELECT id, $2, $3, $4 FROM u
+                ON CONFLICT (user_id) DO UPDATE
+                SET
+                    bio = EXCLUDED.bio,
+                    avatar_url = EXCLUDED.avatar_url,
+                    updated_at = EXCLUDED.updated_at
+                RETURNING bio, avatar_url
+            )
+            SELECT
+                u.id as user_id,
+                u.name,
+                u.email,
+                p.bio,
+                p.avatar_url,
+                u.updated_at
+            FROM u, p
             "#,
         )
         .bind(&profile.name)
@@ -72,11 +99,38 @@ impl UserProfileRepository for PostgresUserProfileRepository {
         .bind(&profile.avatar_url)
         .bind(profile.updated_at)
         .bind(profile.user_id)
-        .fetch_one(&self.pool)
+        .fetch_one(self.db.writer())
         .await?;
 
         Ok(updated)
     }
+
+    async fn find_public(
+        &self,
+        user_id: UserId,
+        tenant_id: Option<OrganizationId>,
+    ) -> AppResult<Option<PublicUserDto>> {
+        let profile = sqlx::query_as::<_, PublicUserDto>(
+            r#"
+            SELECT u.id, u.name, p.avatar_url
+            FROM users u
+            LEFT JOIN user_profiles p ON p.user_id = u.id
+            WHERE u.id = $1 AND CASE
+                WHEN $2::uuid IS NULL THEN u.tenant_id IS NULL
+                ELSE u.tenant_id = $2 OR EXISTS (
+                    SELECT 1 FROM tenant_memberships m
+                    WHERE m.organization_id = $2 AND m.user_id = u.id
+                )
+            END
+            "#,
+        )
+        .bind(user_id)
+        .bind(tenant_id)
+        .fetch_optional(self.db.reader())
+        .await?;
+
+        Ok(profile)
+    }
 }
 
 #[cfg(test)]
@@ -16,5 +16,5 @@ pub mod infra;
 pub mod web;
 
 // Re-export commonly used items
-pub use api::user_api_routes;
+pub use api::{user_api_routes, users_api_routes};
 pub use web::user_web_routes;
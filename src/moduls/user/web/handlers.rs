@@ -1,9 +1,14 @@
 use crate::bootstrap::AppState;
+use crate::moduls::auth::api::handlers::login_outcome_response;
+use crate::moduls::auth::domain::Session;
+use crate::moduls::auth::web::middleware::AuthenticatedSession;
+use crate::moduls::organization::api::TenantContext;
+use crate::moduls::user::application::{ChangePasswordCommand, UpdateProfileCommand};
 use crate::shared::AppError;
 use axum::{
     extract::State,
-    response::{IntoResponse, Redirect},
-    Form,
+    response::{IntoResponse, Redirect, Response},
+    Extension, Form,
 };
 use serde::Deserialize;
 
@@ -23,91 +28,119 @@ pub struct ChangePasswordForm {
     pub new_password_confirmation: String,
 }
 
+/// Form data for minting API tokens from the session
+#[derive(Debug, Default, Deserialize)]
+pub struct SessionTokenForm {
+    /// Tenant to mint for; required when the user belongs to several
+    #[serde(default)]
+    pub tenant_slug: Option<String>,
+    /// Current TOTP code; required when the user has 2FA enabled
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
 /// GET /web/user/profile
 /// Show user profile page (Inertia)
-pub async fn show_profile(State(_state): State<AppState>) -> Result<impl IntoResponse, AppError> {
-    // TODO: Extract user_id from authenticated session
-    // For now, return placeholder
-    // let use_case = GetProfileUseCase::new(state.profile_repo);
-    // let profile = use_case.execute(auth_session.user_id).await?;
+pub async fn show_profile(
+    State(_state): State<AppState>,
+    _auth_session: AuthenticatedSession,
+) -> Result<impl IntoResponse, AppError> {
+    // TODO: Render with Inertia
+    // let profile = state.get_profile_use_case.execute(auth_session.user_id).await?;
     // Inertia::render("User/Profile", ProfilePageProps { profile })
 
-    Ok("Profile page (Inertia not yet implemented - TODO: extract user from session)")
+    Ok("Profile page (Inertia not yet implemented)")
 }
 
 /// GET /web/user/profile/edit
 /// Show edit profile form (Inertia)
 pub async fn show_edit_profile(
     State(_state): State<AppState>,
+    _auth_session: AuthenticatedSession,
 ) -> Result<impl IntoResponse, AppError> {
-    // TODO: Extract user_id from authenticated session
-    // let use_case = GetProfileUseCase::new(state.profile_repo);
-    // let profile = use_case.execute(auth_session.user_id).await?;
+    // TODO: Render with Inertia
+    // let profile = state.get_profile_use_case.execute(auth_session.user_id).await?;
     // Inertia::render("User/EditProfile", EditProfilePageProps { profile, errors: None })
 
-    Ok("Edit profile page (Inertia not yet implemented - TODO: extract user from session)")
+    Ok("Edit profile page (Inertia not yet implemented)")
 }
 
 /// POST /web/user/profile/edit
 /// Handle profile update form submission
 pub async fn handle_update_profile(
-    State(_state): State<AppState>,
-    Form(_form): Form<UpdateProfileForm>,
+    State(state): State<AppState>,
+    auth_session: AuthenticatedSession,
+    Form(form): Form<UpdateProfileForm>,
 ) -> Result<Redirect, AppError> {
-    // TODO: Extract user_id from authenticated session
-    // For now, return error
-    /*
     let cmd = UpdateProfileCommand {
         name: form.name,
         bio: form.bio,
         avatar_url: form.avatar_url,
     };
 
-    let use_case = UpdateProfileUseCase::new(state.profile_repo);
-    use_case.execute(auth_session.user_id, cmd).await?;
+    state
+        .update_profile_use_case
+        .execute(auth_session.user_id, cmd)
+        .await?;
 
     Ok(Redirect::to("/web/user/profile"))
-    */
-
-    Err(AppError::Authentication(
-        "Session authentication not yet implemented".into(),
-    ))
 }
 
 /// GET /web/user/settings/password
 /// Show change password form (Inertia)
 pub async fn show_change_password(
     State(_state): State<AppState>,
+    _auth_session: AuthenticatedSession,
 ) -> Result<impl IntoResponse, AppError> {
-    // TODO: Extract user_id from authenticated session
+    // TODO: Render with Inertia
     // Inertia::render("User/ChangePassword", ChangePasswordPageProps { errors: None })
 
-    Ok("Change password page (Inertia not yet implemented - TODO: extract user from session)")
+    Ok("Change password page (Inertia not yet implemented)")
 }
 
 /// POST /web/user/settings/password
 /// Handle password change form submission
 pub async fn handle_change_password(
-    State(_state): State<AppState>,
-    Form(_form): Form<ChangePasswordForm>,
+    State(state): State<AppState>,
+    auth_session: AuthenticatedSession,
+    Form(form): Form<ChangePasswordForm>,
 ) -> Result<Redirect, AppError> {
-    // TODO: Extract user_id from authenticated session
-    // For now, return error
-    /*
     let cmd = ChangePasswordCommand {
         current_password: form.current_password,
         new_password: form.new_password,
-        new_password_confirmation: form.new_password_confirmation,
+        new_password_confirmation: Some(form.new_password_confirmation),
     };
 
-    let use_case = ChangePasswordUseCase::new(state.change_password_use_case.clone());
-    use_case.execute(auth_session.user_id, cmd).await?;
+    state
+        .change_password_use_case
+        .execute(auth_session.user_id, cmd)
+        .await?;
 
     // TODO: Show success message
     Ok(Redirect::to("/web/user/profile"))
-    */
+}
 
-    Err(AppError::Authentication(
-        "Session authentication not yet implemented".into(),
-    ))
+/// POST /web/user/api-token
+/// Mint API tokens for the signed-in session (session-to-JWT bridge)
+///
+/// The tokens carry the session as their `sid` claim: logging the session
+/// out or revoking it revokes them too. Users with 2FA enabled must send a
+/// current TOTP `code`.
+pub async fn handle_issue_api_token(
+    State(state): State<AppState>,
+    Extension(session): Extension<Session>,
+    tenant: Option<Extension<TenantContext>>,
+    Form(form): Form<SessionTokenForm>,
+) -> Result<Response, AppError> {
+    let outcome = state
+        .login_user_use_case
+        .exchange_session(
+            &session,
+            tenant.map(|Extension(t)| t.organization_id),
+            form.tenant_slug.as_deref(),
+            form.code.as_deref(),
+        )
+        .await?;
+
+    Ok(login_outcome_response(&state, outcome))
 }
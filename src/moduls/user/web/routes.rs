@@ -1,14 +1,19 @@
 use crate::bootstrap::AppState;
+use crate::moduls::auth::api::middleware::require_fresh_auth;
+use crate::moduls::auth::web::middleware::{csrf_middleware, session_auth_middleware};
 use axum::{
-    routing::get,
+    handler::Handler,
+    middleware,
+    routing::{get, post},
     Router,
 };
 
 use super::handlers;
 
 /// User web routes (Inertia.js / session-based authentication)
-/// All routes require authentication via session middleware
-pub fn user_web_routes() -> Router<AppState> {
+/// All routes require authentication via session middleware; form
+/// submissions additionally require the session's CSRF token
+pub fn user_web_routes(state: AppState) -> Router<AppState> {
     Router::new()
         // Profile viewing
         .route("/profile", get(handlers::show_profile))
@@ -17,11 +22,17 @@ pub fn user_web_routes() -> Router<AppState> {
             "/profile/edit",
             get(handlers::show_edit_profile).post(handlers::handle_update_profile),
         )
-        // Password change
+        // Password change (submitting requires a recent login)
         .route(
             "/settings/password",
-            get(handlers::show_change_password).post(handlers::handle_change_password),
+            get(handlers::show_change_password).post(handlers::handle_change_password.layer(
+                middleware::from_fn_with_state(state.clone(), require_fresh_auth),
+            )),
         )
-    // TODO: Add session authentication middleware when implemented
-    // .layer(middleware::session_layer())
+        // API tokens linked to the session
+        .route("/api-token", post(handlers::handle_issue_api_token))
+        // CSRF check runs after (inside) session authentication
+        .route_layer(middleware::from_fn_with_state(state.clone(), csrf_middleware))
+        // Add session authentication middleware to all routes
+        .route_layer(middleware::from_fn_with_state(state, session_auth_middleware))
 }
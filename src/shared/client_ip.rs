@@ -0,0 +1,187 @@
+//! Client address of a request
+//!
+//! The TCP peer, unless it is one of the `TRUSTED_PROXIES`: then the
+//! `X-Forwarded-For` hops are walked from the right, skipping trusted
+//! proxies, and the first other address is the client (`X-Real-IP` when
+//! the proxy doesn't send `X-Forwarded-For`). Without trusted proxies the
+//! headers are ignored, since any client could set them.
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, Extensions, HeaderMap},
+};
+use ipnet::IpNet;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+/// Extractor for the client address (`None` when unknown)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIp(pub Option<String>);
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(client_ip(&parts.headers, &parts.extensions)))
+    }
+}
+
+/// Reverse proxies whose forwarding headers are believed
+///
+/// Added to every request as an extension (see `build_app`); requests
+/// without it trust no proxy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedProxies(Vec<IpNet>);
+
+impl TrustedProxies {
+    /// Parse a comma separated list of addresses and CIDR ranges
+    /// (`10.0.0.0/8, ::1`)
+    pub fn parse(value: &str) -> Result<Self, String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                item.parse::<IpNet>()
+                    .or_else(|_| item.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| format!("TRUSTED_PROXIES: invalid address or range '{}'", item))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|net| net.contains(&ip))
+    }
+}
+
+/// Client address from request headers and extensions
+pub fn client_ip(headers: &HeaderMap, extensions: &Extensions) -> Option<String> {
+    let peer = extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())?;
+
+    let client = match extensions.get::<TrustedProxies>() {
+        Some(trusted) if trusted.contains(peer) => forwarded_ip(headers, trusted).unwrap_or(peer),
+        _ => peer,
+    };
+    Some(client.to_string())
+}
+
+/// Client address reported by a trusted proxy
+fn forwarded_ip(headers: &HeaderMap, trusted: &TrustedProxies) -> Option<IpAddr> {
+    let Some(forwarded_for) = headers.get("x-forwarded-for") else {
+        return headers
+            .get("x-real-ip")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok());
+    };
+
+    // Proxies append the address they received the request from, so only
+    // the hops added by trusted proxies are reliable
+    let mut client = None;
+    for hop in forwarded_for.to_str().ok()?.rsplit(',') {
+        let ip: IpAddr = hop.trim().parse().ok()?;
+        client = Some(ip);
+        if !trusted.contains(ip) {
+            break;
+        }
+    }
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn extensions(peer: [u8; 4], trusted: &str) -> Extensions {
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::from((peer, 4000))));
+        extensions.insert(TrustedProxies::parse(trusted).unwrap());
+        extensions
+    }
+
+    fn forwarded_for(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_forwarded_for_from_trusted_proxy() {
+        let mut headers = forwarded_for("203.0.113.7, 10.0.0.1");
+        headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.1"));
+
+        let client = client_ip(&headers, &extensions([10, 0, 0, 2], "10.0.0.0/8"));
+
+        assert_eq!(client.as_deref(), Some("203.0.113.7"));
+    }
+
+    #[test]
+    fn test_spoofed_hops_before_the_proxy_are_ignored() {
+        // The client sent "X-Forwarded-For: 1.2.3.4"; the proxy appended its peer
+        let headers = forwarded_for("1.2.3.4, 203.0.113.7");
+
+        let client = client_ip(&headers, &extensions([10, 0, 0, 2], "10.0.0.0/8"));
+
+        assert_eq!(client.as_deref(), Some("203.0.113.7"));
+    }
+
+    #[test]
+    fn test_headers_ignored_without_trusted_proxy() {
+        let mut headers = forwarded_for("203.0.113.7");
+        headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.1"));
+
+        // No proxy configured, or the peer isn't one
+        assert_eq!(
+            client_ip(&headers, &extensions([10, 0, 0, 2], "")).as_deref(),
+            Some("10.0.0.2")
+        );
+        assert_eq!(
+            client_ip(&headers, &extensions([192, 0, 2, 1], "10.0.0.0/8")).as_deref(),
+            Some("192.0.2.1")
+        );
+    }
+
+    #[test]
+    fn test_real_ip_and_malformed_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.1"));
+        let trusted = extensions([10, 0, 0, 2], "10.0.0.2");
+        assert_eq!(
+            client_ip(&headers, &trusted).as_deref(),
+            Some("198.51.100.1")
+        );
+
+        let headers = forwarded_for("not-an-ip");
+        assert_eq!(client_ip(&headers, &trusted).as_deref(), Some("10.0.0.2"));
+    }
+
+    #[test]
+    fn test_falls_back_to_peer_address() {
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))));
+
+        assert_eq!(
+            client_ip(&HeaderMap::new(), &extensions).as_deref(),
+            Some("127.0.0.1")
+        );
+        assert_eq!(client_ip(&HeaderMap::new(), &Extensions::new()), None);
+    }
+
+    #[test]
+    fn test_trusted_proxies_parse() {
+        let trusted = TrustedProxies::parse("10.0.0.0/8, ::1 ,192.0.2.1").unwrap();
+
+        assert!(trusted.contains("10.1.2.3".parse().unwrap()));
+        assert!(trusted.contains("::1".parse().unwrap()));
+        assert!(trusted.contains("192.0.2.1".parse().unwrap()));
+        assert!(!trusted.contains("192.0.2.2".parse().unwrap()));
+        assert_eq!(
+            TrustedProxies::parse("").unwrap(),
+            TrustedProxies::default()
+        );
+        assert!(TrustedProxies::parse("10.0.0.0/33").is_err());
+    }
+}
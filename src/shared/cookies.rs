@@ -0,0 +1,117 @@
+use crate::shared::types::SessionId;
+use axum::http::{header, HeaderMap};
+
+/// Cookie carrying the web session ID
+pub const SESSION_COOKIE_NAME: &str = "session_id";
+
+/// `SameSite` attribute of the session cookie (`SESSION_COOKIE_SAME_SITE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// Sent on top-level navigation from other sites (following a link)
+    Lax,
+    /// Only sent on same-site requests
+    Strict,
+}
+
+impl SameSite {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SameSite::Lax => "Lax",
+            SameSite::Strict => "Strict",
+        }
+    }
+}
+
+/// Read a cookie value from the request's `Cookie` headers
+///
+/// Returns `None` when the cookie is absent or empty.
+pub fn read_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, value)| *key == name && !value.is_empty())
+        .map(|(_, value)| value.to_string())
+}
+
+/// `Set-Cookie` value for the web session cookie
+///
+/// Always `HttpOnly` and scoped to `/`. With `max_age` (seconds) the cookie
+/// outlives the browser; without, it is dropped when the browser closes.
+pub fn build_session_cookie(
+    session_id: SessionId,
+    secure: bool,
+    same_site: SameSite,
+    max_age: Option<i64>,
+) -> String {
+    let mut cookie = format!(
+        "{}={}; Path=/; HttpOnly; SameSite={}",
+        SESSION_COOKIE_NAME,
+        session_id,
+        same_site.as_str()
+    );
+    if let Some(max_age) = max_age {
+        cookie.push_str(&format!("; Max-Age={}", max_age));
+    }
+    if secure {
+        cookie.push_str("; Secure");
+    }
+    cookie
+}
+
+/// `Set-Cookie` value clearing the web session cookie (at logout)
+pub fn clear_session_cookie(secure: bool, same_site: SameSite) -> String {
+    let mut cookie = format!(
+        "{}=; Path=/; HttpOnly; SameSite={}; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+        SESSION_COOKIE_NAME,
+        same_site.as_str()
+    );
+    if secure {
+        cookie.push_str("; Secure");
+    }
+    cookie
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_read_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.append(header::COOKIE, HeaderValue::from_static("theme=dark; session_id=abc"));
+        headers.append(header::COOKIE, HeaderValue::from_static("lang=en; empty="));
+
+        assert_eq!(read_cookie(&headers, "session_id").as_deref(), Some("abc"));
+        assert_eq!(read_cookie(&headers, "lang").as_deref(), Some("en"));
+        assert_eq!(read_cookie(&headers, "empty"), None);
+        assert_eq!(read_cookie(&headers, "missing"), None);
+    }
+
+    #[test]
+    fn test_build_session_cookie_flags() {
+        let session_id = SessionId::new();
+
+        let cookie = build_session_cookie(session_id, true, SameSite::Strict, Some(3600));
+        assert_eq!(
+            cookie,
+            format!("session_id={}; Path=/; HttpOnly; SameSite=Strict; Max-Age=3600; Secure", session_id)
+        );
+
+        let cookie = build_session_cookie(session_id, false, SameSite::Lax, None);
+        assert_eq!(cookie, format!("session_id={}; Path=/; HttpOnly; SameSite=Lax", session_id));
+    }
+
+    #[test]
+    fn test_clear_session_cookie_expires_it() {
+        let cookie = clear_session_cookie(true, SameSite::Lax);
+
+        assert!(cookie.starts_with("session_id=; Path=/; HttpOnly; SameSite=Lax"));
+        assert!(cookie.contains("; Max-Age=0"));
+        assert!(cookie.contains("; Expires=Thu, 01 Jan 1970 00:00:00 GMT"));
+        assert!(cookie.ends_with("; Secure"));
+    }
+}
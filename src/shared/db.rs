@@ -0,0 +1,166 @@
+//! Primary / read-replica pool routing
+//!
+//! Repositories write through `DbPools::writer` and read through
+//! `DbPools::reader`, which uses the replica when one is configured. Once a
+//! request has written, its later reads go to the primary too (see
+//! `read_your_writes`), so a request never misses its own writes because of
+//! replication lag. Reads that must never be stale (session and token
+//! revocation) use `DbPools::primary` directly.
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::cell::Cell;
+
+tokio::task_local! {
+    static WROTE: Cell<bool>;
+}
+
+/// Primary pool plus an optional read replica
+#[derive(Clone, Debug)]
+pub struct DbPools {
+    primary: PgPool,
+    replica: Option<PgPool>,
+}
+
+impl DbPools {
+    pub fn new(primary: PgPool, replica: Option<PgPool>) -> Self {
+        Self { primary, replica }
+    }
+
+    /// Primary pool, without marking the request as having written
+    pub fn primary(&self) -> &PgPool {
+        &self.primary
+    }
+
+    /// Pool for writes (always the primary)
+    ///
+    /// Marks the current request so its later reads stay on the primary.
+    pub fn writer(&self) -> &PgPool {
+        let _ = WROTE.try_with(|wrote| wrote.set(true));
+        &self.primary
+    }
+
+    /// Pool for reads: the replica, unless none is configured or the
+    /// current request already wrote
+    pub fn reader(&self) -> &PgPool {
+        let wrote = WROTE.try_with(Cell::get).unwrap_or(false);
+        match &self.replica {
+            Some(replica) if !wrote => replica,
+            _ => &self.primary,
+        }
+    }
+
+    pub fn has_replica(&self) -> bool {
+        self.replica.is_some()
+    }
+
+    /// Connection counts of the primary pool and the replica pool, if any
+    pub fn stats(&self) -> Vec<PoolStats> {
+        let mut stats = vec![PoolStats::of("primary", &self.primary)];
+        if let Some(replica) = &self.replica {
+            stats.push(PoolStats::of("replica", replica));
+        }
+        stats
+    }
+}
+
+/// Point-in-time connection counts of a pool
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStats {
+    pub pool: &'static str,
+    /// Open connections, idle or in use
+    pub size: u32,
+    pub idle: u32,
+    pub in_use: u32,
+    /// `DATABASE_MAX_CONNECTIONS`
+    pub max_size: u32,
+}
+
+impl PoolStats {
+    fn of(name: &'static str, pool: &PgPool) -> Self {
+        let size = pool.size();
+        let idle = pool.num_idle() as u32;
+        Self {
+            pool: name,
+            size,
+            idle,
+            in_use: size.saturating_sub(idle),
+            max_size: pool.options().get_max_connections(),
+        }
+    }
+}
+
+impl From<PgPool> for DbPools {
+    fn from(primary: PgPool) -> Self {
+        Self::new(primary, None)
+    }
+}
+
+/// Track writes per request so reads after a write use the primary
+pub async fn read_your_writes(request: Request, next: Next) -> Response {
+    WROTE.scope(Cell::new(false), next.run(request)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lazy_pool(database: &str) -> PgPool {
+        sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy(&format!("postgres://localhost/{}", database))
+            .unwrap()
+    }
+
+    fn database(pool: &PgPool) -> String {
+        pool.connect_options().get_database().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_reads_use_replica_when_configured() {
+        let pools = DbPools::new(lazy_pool("primary"), Some(lazy_pool("replica")));
+
+        assert_eq!(database(pools.reader()), "replica");
+        assert_eq!(database(pools.writer()), "primary");
+        assert_eq!(database(pools.primary()), "primary");
+    }
+
+    #[tokio::test]
+    async fn test_reads_fall_back_to_primary_without_replica() {
+        let pools = DbPools::from(lazy_pool("primary"));
+
+        assert!(!pools.has_replica());
+        assert_eq!(database(pools.reader()), "primary");
+    }
+
+    #[tokio::test]
+    async fn test_stats_cover_each_pool() {
+        let pools = DbPools::new(lazy_pool("primary"), Some(lazy_pool("replica")));
+
+        let stats = pools.stats();
+
+        assert_eq!(stats.iter().map(|s| s.pool).collect::<Vec<_>>(), ["primary", "replica"]);
+        // Lazy pools open no connections until used
+        assert_eq!((stats[0].size, stats[0].idle, stats[0].in_use), (0, 0, 0));
+        assert!(stats[0].max_size > 0);
+    }
+
+    #[tokio::test]
+    async fn test_reads_after_a_write_use_primary() {
+        let pools = DbPools::new(lazy_pool("primary"), Some(lazy_pool("replica")));
+
+        let (before, after) = WROTE
+            .scope(Cell::new(false), async {
+                let before = database(pools.reader());
+                pools.writer();
+                (before, database(pools.reader()))
+            })
+            .await;
+        assert_eq!(before, "replica");
+        assert_eq!(after, "primary");
+
+        // Each request starts on the replica again
+        let next = WROTE.scope(Cell::new(false), async { database(pools.reader()) }).await;
+        assert_eq!(next, "replica");
+    }
+}
@@ -4,6 +4,9 @@ use axum::{
     Json,
 };
 use serde::Serialize;
+use super::{i18n, request_id, AppResult};
+use crate::moduls::auth::domain::PasswordPolicyViolation;
+use std::collections::BTreeMap;
 use std::fmt;
 
 /// Application error types
@@ -15,18 +18,56 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// Per-field validation failures (from `ValidatedJson`)
+    #[error("Validation failed")]
+    FieldValidation(#[from] validator::ValidationErrors),
+
+    /// New password fails the password policy (every failed rule listed)
+    #[error("Password does not meet the password policy")]
+    PasswordPolicy(PasswordPolicyViolation),
+
+    /// Per-field validation failures together with the password policy
+    /// violation of the same request (see `check_fields`)
+    #[error("Validation failed")]
+    InvalidFields(validator::ValidationErrors, Box<PasswordPolicyViolation>),
+
+    /// Client-side password hash made with parameters other than the
+    /// account's; the client should negotiate them again
+    #[error("Client hash parameters do not match: {0}")]
+    ClientHashMismatch(String),
+
     #[error("Authentication error: {0}")]
     Authentication(String),
 
     #[error("Authorization error: {0}")]
     Authorization(String),
 
+    /// Credentials are valid but too old for a sensitive action
+    #[error("Re-authentication required: {0}")]
+    ReauthRequired(String),
+
+    #[error("Email not verified: {0}")]
+    EmailNotVerified(String),
+
+    /// The tenant's policy requires 2FA, which the user has not set up
+    #[error("Two-factor setup required: {0}")]
+    TwoFactorSetupRequired(String),
+
+    /// The password must be changed (reset) before logging in again
+    #[error("Password change required: {0}")]
+    PasswordChangeRequired(String),
+
     #[error("Not found: {0}")]
     NotFound(String),
 
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// Conflict naming the field that clashed, for trusted callers only;
+    /// public endpoints report a plain `Conflict` instead
+    #[error("Conflict: {message}")]
+    FieldConflict { field: String, message: String },
+
     #[error("Internal error: {0}")]
     Internal(String),
 
@@ -35,6 +76,16 @@ pub enum AppError {
 
     #[error("Bad request: {0}")]
     BadRequest(String),
+
+    #[error("Request timeout: {0}")]
+    RequestTimeout(String),
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    /// Rate limited (e.g. too many failed password checks)
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
 }
 
 /// Error response structure
@@ -48,7 +99,16 @@ struct ErrorDetail {
     message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     details: Option<String>,
+    /// Field name -> messages, for field validation errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<BTreeMap<String, Vec<String>>>,
+    /// Failed rules and the full policy, for password policy errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password_policy: Option<PasswordPolicyViolation>,
     code: String,
+    /// ID of the failed request, for support tickets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
 }
 
 impl AppError {
@@ -57,6 +117,25 @@ impl AppError {
         AppError::Validation(msg.into())
     }
 
+    /// Report a request's field errors together with the outcome of a
+    /// password policy check, so every invalid field is listed at once
+    ///
+    /// Other errors from `checked` are returned as they are.
+    pub fn check_fields(errors: validator::ValidationErrors, checked: AppResult<()>) -> AppResult<()> {
+        match (errors.is_empty(), checked) {
+            (false, Err(AppError::PasswordPolicy(violation))) => {
+                Err(AppError::InvalidFields(errors, Box::new(violation)))
+            }
+            (false, Ok(())) => Err(AppError::FieldValidation(errors)),
+            (_, checked) => checked,
+        }
+    }
+
+    /// Create a client hash mismatch error
+    pub fn client_hash_mismatch(msg: impl Into<String>) -> Self {
+        AppError::ClientHashMismatch(msg.into())
+    }
+
     /// Create an authentication error
     pub fn authentication(msg: impl Into<String>) -> Self {
         AppError::Authentication(msg.into())
@@ -67,6 +146,26 @@ impl AppError {
         AppError::Authorization(msg.into())
     }
 
+    /// Create a re-authentication required error
+    pub fn reauth_required(msg: impl Into<String>) -> Self {
+        AppError::ReauthRequired(msg.into())
+    }
+
+    /// Create a two-factor setup required error
+    pub fn two_factor_setup_required(msg: impl Into<String>) -> Self {
+        AppError::TwoFactorSetupRequired(msg.into())
+    }
+
+    /// Create a password change required error
+    pub fn password_change_required(msg: impl Into<String>) -> Self {
+        AppError::PasswordChangeRequired(msg.into())
+    }
+
+    /// Create an email not verified error
+    pub fn email_not_verified(msg: impl Into<String>) -> Self {
+        AppError::EmailNotVerified(msg.into())
+    }
+
     /// Create a not found error
     pub fn not_found(msg: impl Into<String>) -> Self {
         AppError::NotFound(msg.into())
@@ -77,6 +176,14 @@ impl AppError {
         AppError::Conflict(msg.into())
     }
 
+    /// Create a conflict error naming the conflicting field
+    pub fn field_conflict(field: impl Into<String>, msg: impl Into<String>) -> Self {
+        AppError::FieldConflict {
+            field: field.into(),
+            message: msg.into(),
+        }
+    }
+
     /// Create an internal error
     pub fn internal(msg: impl Into<String>) -> Self {
         AppError::Internal(msg.into())
@@ -87,14 +194,40 @@ impl AppError {
         AppError::BadRequest(msg.into())
     }
 
+    /// Create a request timeout error
+    pub fn request_timeout(msg: impl Into<String>) -> Self {
+        AppError::RequestTimeout(msg.into())
+    }
+
+    /// Create a payload too large error
+    pub fn payload_too_large(msg: impl Into<String>) -> Self {
+        AppError::PayloadTooLarge(msg.into())
+    }
+
+    /// Create a too many requests error
+    pub fn too_many_requests(msg: impl Into<String>) -> Self {
+        AppError::TooManyRequests(msg.into())
+    }
+
     /// Get HTTP status code for this error
     fn status_code(&self) -> StatusCode {
         match self {
-            AppError::Validation(_) | AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
-            AppError::Authentication(_) => StatusCode::UNAUTHORIZED,
-            AppError::Authorization(_) => StatusCode::FORBIDDEN,
+            AppError::Validation(_)
+            | AppError::FieldValidation(_)
+            | AppError::PasswordPolicy(_)
+            | AppError::InvalidFields(..)
+            | AppError::ClientHashMismatch(_)
+            | AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Authentication(_) | AppError::ReauthRequired(_) => StatusCode::UNAUTHORIZED,
+            AppError::Authorization(_)
+            | AppError::EmailNotVerified(_)
+            | AppError::TwoFactorSetupRequired(_)
+            | AppError::PasswordChangeRequired(_) => StatusCode::FORBIDDEN,
             AppError::NotFound(_) => StatusCode::NOT_FOUND,
-            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Conflict(_) | AppError::FieldConflict { .. } => StatusCode::CONFLICT,
+            AppError::RequestTimeout(_) => StatusCode::REQUEST_TIMEOUT,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
             AppError::Database(_) | AppError::Internal(_) | AppError::Config(_) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
@@ -105,14 +238,25 @@ impl AppError {
     fn error_code(&self) -> &'static str {
         match self {
             AppError::Database(_) => "DATABASE_ERROR",
-            AppError::Validation(_) => "VALIDATION_ERROR",
+            AppError::Validation(_)
+            | AppError::FieldValidation(_)
+            | AppError::PasswordPolicy(_)
+            | AppError::InvalidFields(..) => "VALIDATION_ERROR",
+            AppError::ClientHashMismatch(_) => "CLIENT_HASH_MISMATCH",
             AppError::Authentication(_) => "AUTHENTICATION_ERROR",
             AppError::Authorization(_) => "AUTHORIZATION_ERROR",
+            AppError::ReauthRequired(_) => "REAUTH_REQUIRED",
+            AppError::EmailNotVerified(_) => "EMAIL_NOT_VERIFIED",
+            AppError::TwoFactorSetupRequired(_) => "TWO_FACTOR_SETUP_REQUIRED",
+            AppError::PasswordChangeRequired(_) => "PASSWORD_CHANGE_REQUIRED",
             AppError::NotFound(_) => "NOT_FOUND",
-            AppError::Conflict(_) => "CONFLICT",
+            AppError::Conflict(_) | AppError::FieldConflict { .. } => "CONFLICT",
             AppError::Internal(_) => "INTERNAL_ERROR",
             AppError::Config(_) => "CONFIG_ERROR",
             AppError::BadRequest(_) => "BAD_REQUEST",
+            AppError::RequestTimeout(_) => "REQUEST_TIMEOUT",
+            AppError::PayloadTooLarge(_) => "PAYLOAD_TOO_LARGE",
+            AppError::TooManyRequests(_) => "TOO_MANY_REQUESTS",
         }
     }
 
@@ -128,6 +272,18 @@ impl AppError {
         }
     }
 
+    /// Get field-keyed messages for field validation errors and field
+    /// conflicts
+    fn fields(&self) -> Option<BTreeMap<String, Vec<String>>> {
+        match self {
+            AppError::FieldValidation(errors) => Some(field_messages(errors)),
+            AppError::FieldConflict { field, message } => {
+                Some(BTreeMap::from([(field.clone(), vec![message.clone()])]))
+            }
+            _ => None,
+        }
+    }
+
     /// Get error details (for debugging)
     fn details(&self) -> Option<String> {
         match self {
@@ -139,6 +295,24 @@ impl AppError {
     }
 }
 
+/// Messages of each invalid field
+///
+/// Uses the rule's custom message if set, otherwise its code
+/// (e.g. `email`, `length`).
+fn field_messages(errors: &validator::ValidationErrors) -> BTreeMap<String, Vec<String>> {
+    errors
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            let messages = errors
+                .iter()
+                .map(|e| e.message.as_ref().unwrap_or(&e.code).to_string())
+                .collect();
+            (field.to_string(), messages)
+        })
+        .collect()
+}
+
 /// Implement IntoResponse for Axum integration
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
@@ -158,15 +332,39 @@ impl IntoResponse for AppError {
             _ => {}
         }
 
+        let locale = i18n::current_locale();
+        let code = self.error_code();
+
         let error_response = ErrorResponse {
             error: ErrorDetail {
-                message: self.user_message(),
+                message: i18n::message(locale, code)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| self.user_message()),
                 details: if cfg!(debug_assertions) {
                     self.details()
                 } else {
                     None
                 },
-                code: self.error_code().to_string(),
+                fields: match &self {
+                    AppError::PasswordPolicy(violation) => {
+                        Some(i18n::localize_password_policy(locale, violation))
+                    }
+                    AppError::InvalidFields(errors, violation) => {
+                        let mut fields = i18n::localize_fields(locale, code, field_messages(errors));
+                        fields.extend(i18n::localize_password_policy(locale, violation));
+                        Some(fields)
+                    }
+                    _ => self
+                        .fields()
+                        .map(|fields| i18n::localize_fields(locale, code, fields)),
+                },
+                password_policy: match &self {
+                    AppError::PasswordPolicy(violation) => Some(violation.clone()),
+                    AppError::InvalidFields(_, violation) => Some(violation.as_ref().clone()),
+                    _ => None,
+                },
+                code: code.to_string(),
+                request_id: request_id::current().map(|id| id.to_string()),
             },
         };
 
@@ -215,6 +413,10 @@ mod tests {
             AppError::Validation("test".to_string()).status_code(),
             StatusCode::BAD_REQUEST
         );
+        assert_eq!(
+            AppError::ClientHashMismatch("test".to_string()).status_code(),
+            StatusCode::BAD_REQUEST
+        );
         assert_eq!(
             AppError::Authentication("test".to_string()).status_code(),
             StatusCode::UNAUTHORIZED
@@ -223,6 +425,22 @@ mod tests {
             AppError::Authorization("test".to_string()).status_code(),
             StatusCode::FORBIDDEN
         );
+        assert_eq!(
+            AppError::ReauthRequired("test".to_string()).status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            AppError::TwoFactorSetupRequired("test".to_string()).status_code(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            AppError::EmailNotVerified("test".to_string()).status_code(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            AppError::PasswordChangeRequired("test".to_string()).status_code(),
+            StatusCode::FORBIDDEN
+        );
         assert_eq!(
             AppError::NotFound("test".to_string()).status_code(),
             StatusCode::NOT_FOUND
@@ -231,6 +449,22 @@ mod tests {
             AppError::Conflict("test".to_string()).status_code(),
             StatusCode::CONFLICT
         );
+        assert_eq!(
+            AppError::field_conflict("email", "test").status_code(),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            AppError::RequestTimeout("test".to_string()).status_code(),
+            StatusCode::REQUEST_TIMEOUT
+        );
+        assert_eq!(
+            AppError::PayloadTooLarge("test".to_string()).status_code(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            AppError::TooManyRequests("test".to_string()).status_code(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
         assert_eq!(
             AppError::Internal("test".to_string()).status_code(),
             StatusCode::INTERNAL_SERVER_ERROR
@@ -247,5 +481,124 @@ mod tests {
             AppError::Authentication("test".to_string()).error_code(),
             "AUTHENTICATION_ERROR"
         );
+        assert_eq!(
+            AppError::EmailNotVerified("test".to_string()).error_code(),
+            "EMAIL_NOT_VERIFIED"
+        );
+        assert_eq!(
+            AppError::ReauthRequired("test".to_string()).error_code(),
+            "REAUTH_REQUIRED"
+        );
+        assert_eq!(
+            AppError::PasswordChangeRequired("test".to_string()).error_code(),
+            "PASSWORD_CHANGE_REQUIRED"
+        );
+        assert_eq!(
+            AppError::TwoFactorSetupRequired("test".to_string()).error_code(),
+            "TWO_FACTOR_SETUP_REQUIRED"
+        );
+        assert_eq!(
+            AppError::ClientHashMismatch("test".to_string()).error_code(),
+            "CLIENT_HASH_MISMATCH"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_password_policy_response_lists_every_failure() {
+        use crate::moduls::auth::domain::PasswordPolicy;
+
+        let policy = PasswordPolicy {
+            min_length: 12,
+            require_digit: true,
+            ..PasswordPolicy::default()
+        };
+        let error = policy.enforce("short", "password").unwrap_err();
+
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+        assert_eq!(
+            body["error"]["fields"]["password"],
+            serde_json::json!([
+                "Password must be at least 12 characters",
+                "Password must contain a digit"
+            ])
+        );
+        assert_eq!(
+            body["error"]["password_policy"],
+            serde_json::json!({
+                "failed": ["min_length", "digit"],
+                "policy": {
+                    "min_length": 12,
+                    "require_uppercase": false,
+                    "require_lowercase": false,
+                    "require_digit": true,
+                    "require_symbol": false
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_fields_response_lists_each_field() {
+        use crate::moduls::auth::domain::PasswordPolicy;
+
+        let mut errors = validator::ValidationErrors::new();
+        errors.add("email", validator::ValidationError::new("email"));
+        let policy = PasswordPolicy::default().enforce("short", "password");
+        let error = AppError::check_fields(errors, policy).unwrap_err();
+
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+        assert_eq!(
+            body["error"]["fields"],
+            serde_json::json!({
+                "email": ["email"],
+                "password": ["Password must be at least 8 characters"]
+            })
+        );
+        assert_eq!(body["error"]["password_policy"]["failed"], serde_json::json!(["min_length"]));
+    }
+
+    #[test]
+    fn test_check_fields_without_field_errors_keeps_the_outcome() {
+        let none = validator::ValidationErrors::new;
+
+        assert!(AppError::check_fields(none(), Ok(())).is_ok());
+        assert!(matches!(
+            AppError::check_fields(none(), Err(AppError::validation("test"))),
+            Err(AppError::Validation(_))
+        ));
+
+        let mut errors = none();
+        errors.add("name", validator::ValidationError::new("length"));
+        assert!(matches!(
+            AppError::check_fields(errors, Ok(())),
+            Err(AppError::FieldValidation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_field_conflict_response_names_the_field() {
+        let generic = AppError::conflict("Email already exists").into_response();
+        let body = axum::body::to_bytes(generic.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(body["error"].get("fields").is_none());
+
+        let response = AppError::field_conflict("email", "Email already exists").into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body["error"]["code"], "CONFLICT");
+        assert_eq!(body["error"]["message"], "Conflict: Email already exists");
+        assert_eq!(body["error"]["fields"], serde_json::json!({ "email": ["Email already exists"] }));
     }
 }
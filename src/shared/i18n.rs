@@ -0,0 +1,291 @@
+//! Localized error messages
+//!
+//! `localize` picks a `Locale` from `Accept-Language` for the duration of a
+//! request; `AppError`'s response then resolves its message (by error code)
+//! and field messages (by error code + field) from the bundled tables below.
+//! English is the default and keeps the original, more specific messages.
+//! The machine-readable `code` never changes with the language.
+
+use crate::bootstrap::AppState;
+use crate::moduls::auth::domain::{PasswordPolicy, PasswordPolicyViolation, PasswordRequirement};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Supported response languages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+}
+
+impl Locale {
+    /// Language tag, as used in `Accept-Language` / `Content-Language`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+        }
+    }
+
+    /// Best supported locale from an `Accept-Language` header value
+    ///
+    /// Honors `q` weights and matches on the primary subtag (`es-MX` → `es`).
+    /// Falls back to `default` when nothing acceptable is supported.
+    pub fn negotiate(accept_language: Option<&str>, default: Locale) -> Locale {
+        let Some(accept_language) = accept_language else {
+            return default;
+        };
+
+        let mut candidates: Vec<(f32, Locale)> = accept_language
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.trim().split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                let primary = tag.split('-').next()?;
+                let locale = primary.parse::<Locale>().ok()?;
+                (quality > 0.0).then_some((quality, locale))
+            })
+            .collect();
+
+        // Stable sort keeps header order for equal weights
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.first().map_or(default, |(_, locale)| *locale)
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "en" => Ok(Locale::En),
+            "es" => Ok(Locale::Es),
+            other => Err(format!("Unsupported locale: {}", other)),
+        }
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_LOCALE: Locale;
+}
+
+/// Locale of the request being handled (`Locale::En` outside `localize`)
+pub fn current_locale() -> Locale {
+    CURRENT_LOCALE.try_with(|locale| *locale).unwrap_or_default()
+}
+
+/// Negotiate the response language and resolve error messages in it
+///
+/// The chosen language is echoed in `Content-Language`.
+pub async fn localize(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let accept_language = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok());
+    let locale = Locale::negotiate(accept_language, state.config.server.default_locale);
+
+    let mut response = CURRENT_LOCALE.scope(locale, next.run(request)).await;
+    response
+        .headers_mut()
+        .insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.as_str()));
+    response
+}
+
+/// Localized top-level message for an error code, if translated
+pub fn message(locale: Locale, code: &str) -> Option<&'static str> {
+    match locale {
+        Locale::En => None,
+        Locale::Es => es_message(code),
+    }
+}
+
+/// Replace field messages with their translations where available
+pub fn localize_fields(
+    locale: Locale,
+    code: &str,
+    fields: BTreeMap<String, Vec<String>>,
+) -> BTreeMap<String, Vec<String>> {
+    let translate = match locale {
+        Locale::En => return fields,
+        Locale::Es => es_field_message,
+    };
+
+    fields
+        .into_iter()
+        .map(|(field, messages)| match translate(code, &field) {
+            Some(localized) => (field, vec![localized.to_string()]),
+            None => (field, messages),
+        })
+        .collect()
+}
+
+/// Messages for each failed password rule, keyed by the password field
+pub fn localize_password_policy(
+    locale: Locale,
+    violation: &PasswordPolicyViolation,
+) -> BTreeMap<String, Vec<String>> {
+    let messages = violation
+        .failed
+        .iter()
+        .map(|requirement| match locale {
+            Locale::En => requirement.message(&violation.policy),
+            Locale::Es => es_password_requirement(*requirement, &violation.policy),
+        })
+        .collect();
+
+    BTreeMap::from([(violation.field.to_string(), messages)])
+}
+
+fn es_password_requirement(requirement: PasswordRequirement, policy: &PasswordPolicy) -> String {
+    match requirement {
+        PasswordRequirement::MinLength => {
+            format!("La contraseña debe tener al menos {} caracteres", policy.min_length)
+        }
+        PasswordRequirement::MaxLength => format!(
+            "La contraseña debe tener como máximo {} caracteres",
+            policy.max_length.unwrap_or_default()
+        ),
+        PasswordRequirement::Uppercase => "La contraseña debe contener una letra mayúscula".to_string(),
+        PasswordRequirement::Lowercase => "La contraseña debe contener una letra minúscula".to_string(),
+        PasswordRequirement::Digit => "La contraseña debe contener un número".to_string(),
+        PasswordRequirement::Symbol => "La contraseña debe contener un símbolo".to_string(),
+        PasswordRequirement::NotCommon => "La contraseña es demasiado común".to_string(),
+        PasswordRequirement::NotReused => format!(
+            "La contraseña debe ser distinta de tus últimas {} contraseñas",
+            policy.history.unwrap_or_default()
+        ),
+    }
+}
+
+fn es_message(code: &str) -> Option<&'static str> {
+    let message = match code {
+        "VALIDATION_ERROR" => "Los datos enviados no son válidos",
+        "BAD_REQUEST" => "Solicitud incorrecta",
+        "CLIENT_HASH_MISMATCH" => "Los parámetros del hash de la contraseña no coinciden",
+        "AUTHENTICATION_ERROR" => "Autenticación fallida",
+        "AUTHORIZATION_ERROR" => "No tienes permiso para realizar esta acción",
+        "REAUTH_REQUIRED" => "Vuelve a iniciar sesión para continuar",
+        "EMAIL_NOT_VERIFIED" => "Debes verificar tu correo electrónico",
+        "TWO_FACTOR_SETUP_REQUIRED" => "Debes configurar la autenticación de dos factores",
+        "PASSWORD_CHANGE_REQUIRED" => "Debes cambiar tu contraseña para continuar",
+        "NOT_FOUND" => "Recurso no encontrado",
+        "CONFLICT" => "El recurso ya existe",
+        "REQUEST_TIMEOUT" => "La solicitud tardó demasiado",
+        "PAYLOAD_TOO_LARGE" => "La solicitud es demasiado grande",
+        "TOO_MANY_REQUESTS" => "Demasiados intentos, inténtalo más tarde",
+        "DATABASE_ERROR" => "Ocurrió un error de base de datos",
+        "INTERNAL_ERROR" => "Ocurrió un error interno",
+        "CONFIG_ERROR" => "Ocurrió un error de configuración",
+        _ => return None,
+    };
+    Some(message)
+}
+
+fn es_field_message(code: &str, field: &str) -> Option<&'static str> {
+    if code == "CONFLICT" {
+        return match field {
+            "email" => Some("Ya existe una cuenta con este correo electrónico"),
+            _ => None,
+        };
+    }
+    if code != "VALIDATION_ERROR" {
+        return None;
+    }
+
+    let message = match field {
+        "email" => "El correo electrónico no es válido",
+        "password" | "new_password" => "La contraseña debe tener al menos 8 caracteres",
+        "current_password" => "La contraseña actual es obligatoria",
+        "new_password_confirmation" => "Las contraseñas no coinciden",
+        "name" => "El nombre es obligatorio",
+        "bio" => "La biografía no puede superar los 500 caracteres",
+        "avatar_url" => "La URL del avatar no es válida",
+        _ => return None,
+    };
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_accept_language() {
+        assert_eq!(Locale::negotiate(None, Locale::En), Locale::En);
+        assert_eq!(Locale::negotiate(Some("es"), Locale::En), Locale::Es);
+        assert_eq!(Locale::negotiate(Some("es-MX,es;q=0.9"), Locale::En), Locale::Es);
+        assert_eq!(Locale::negotiate(Some("fr-FR, es;q=0.5, en;q=0.8"), Locale::En), Locale::En);
+        assert_eq!(Locale::negotiate(Some("en;q=0, es;q=0.1"), Locale::En), Locale::Es);
+        assert_eq!(Locale::negotiate(Some("de, fr"), Locale::Es), Locale::Es);
+        assert_eq!(Locale::negotiate(Some("*"), Locale::En), Locale::En);
+    }
+
+    #[test]
+    fn test_english_keeps_original_messages() {
+        let fields = BTreeMap::from([(
+            "email".to_string(),
+            vec!["email".to_string()],
+        )]);
+
+        assert_eq!(message(Locale::En, "VALIDATION_ERROR"), None);
+        assert_eq!(localize_fields(Locale::En, "VALIDATION_ERROR", fields.clone()), fields);
+    }
+
+    #[test]
+    fn test_spanish_field_messages() {
+        let fields = BTreeMap::from([
+            ("email".to_string(), vec!["email".to_string()]),
+            ("nickname".to_string(), vec!["length".to_string()]),
+        ]);
+
+        let localized = localize_fields(Locale::Es, "VALIDATION_ERROR", fields);
+
+        assert_eq!(localized["email"], vec!["El correo electrónico no es válido"]);
+        // Untranslated fields keep their original message
+        assert_eq!(localized["nickname"], vec!["length"]);
+
+        let conflict = BTreeMap::from([("email".to_string(), vec!["Email already exists".to_string()])]);
+        let localized = localize_fields(Locale::Es, "CONFLICT", conflict);
+        assert_eq!(localized["email"], vec!["Ya existe una cuenta con este correo electrónico"]);
+    }
+
+    #[test]
+    fn test_spanish_password_policy_messages() {
+        let violation = PasswordPolicyViolation {
+            field: "new_password",
+            failed: vec![PasswordRequirement::MinLength, PasswordRequirement::Digit],
+            policy: crate::moduls::auth::domain::PasswordPolicy {
+                min_length: 12,
+                require_digit: true,
+                ..Default::default()
+            },
+        };
+
+        let localized = localize_password_policy(Locale::Es, &violation);
+
+        assert_eq!(
+            localized["new_password"],
+            vec![
+                "La contraseña debe tener al menos 12 caracteres",
+                "La contraseña debe contener un número"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_current_locale_is_scoped() {
+        assert_eq!(current_locale(), Locale::En);
+        let inside = CURRENT_LOCALE.scope(Locale::Es, async { current_locale() }).await;
+        assert_eq!(inside, Locale::Es);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::config::{MailerConfig, SmtpTls};
+use crate::moduls::auth::domain::Email;
+use crate::shared::{AppError, AppResult};
+use async_trait::async_trait;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+/// Mailer trait: delivers plain-text emails
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, to: &Email, subject: &str, body: &str) -> AppResult<()>;
+
+    /// Check that mail can be delivered (startup dependency check)
+    async fn ping(&self) -> AppResult<()> {
+        Ok(())
+    }
+}
+
+/// Mailer that writes messages to the log, for local development
+///
+/// Bodies carry one-time tokens, so they are logged at debug level only and
+/// production logs (info) never contain them.
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, to: &Email, subject: &str, body: &str) -> AppResult<()> {
+        tracing::info!("Mail to {}: {}", to.as_str(), subject);
+        tracing::debug!("Mail body for {}:\n{}", to.as_str(), body);
+        Ok(())
+    }
+}
+
+/// Mailer delivering through an SMTP relay
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    /// Build the transport from config
+    ///
+    /// No connection is made until the first send.
+    ///
+    /// # Errors
+    /// - Internal if `SMTP_HOST` is missing, the TLS setup fails or
+    ///   `MAIL_FROM` is not a valid mailbox
+    pub fn new(config: &MailerConfig) -> AppResult<Self> {
+        let host = config
+            .smtp_host
+            .as_deref()
+            .ok_or_else(|| AppError::internal("SMTP_HOST is not set"))?;
+
+        let builder = match config.smtp_tls {
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                host,
+            )),
+        }
+        .map_err(|e| AppError::internal(format!("Invalid SMTP relay {}: {}", host, e)))?;
+
+        let mut builder = builder.port(config.smtp_port);
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        let from = config.from.parse::<Mailbox>().map_err(|e| {
+            AppError::internal(format!("Invalid MAIL_FROM '{}': {}", config.from, e))
+        })?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, to: &Email, subject: &str, body: &str) -> AppResult<()> {
+        let to = to
+            .as_str()
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::internal(format!("Invalid recipient: {}", e)))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body.to_string())
+            .map_err(|e| AppError::internal(format!("Failed to build email: {}", e)))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to send email: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Connect to the relay and issue `NOOP`
+    async fn ping(&self) -> AppResult<()> {
+        match self.transport.test_connection().await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(AppError::internal("SMTP relay did not answer NOOP")),
+            Err(e) => Err(AppError::internal(format!("Failed to connect to SMTP relay: {}", e))),
+        }
+    }
+}
+
+/// A message captured by `MockMailer`
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct SentMail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Mailer capturing messages instead of sending them
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockMailer {
+    pub sent: std::sync::Mutex<Vec<SentMail>>,
+}
+
+#[cfg(test)]
+impl MockMailer {
+    /// The most recently sent message
+    pub fn last(&self) -> Option<SentMail> {
+        self.sent.lock().unwrap().last().cloned()
+    }
+
+    pub fn count(&self) -> usize {
+        self.sent.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Mailer for MockMailer {
+    async fn send(&self, to: &Email, subject: &str, body: &str) -> AppResult<()> {
+        self.sent.lock().unwrap().push(SentMail {
+            to: to.as_str().to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_mailer_captures_last_message() {
+        let mailer = MockMailer::default();
+        let to = Email::new("user@example.com").unwrap();
+
+        mailer.send(&to, "First", "one").await.unwrap();
+        mailer.send(&to, "Second", "two").await.unwrap();
+
+        let last = mailer.last().unwrap();
+        assert_eq!(mailer.count(), 2);
+        assert_eq!(last.to, "user@example.com");
+        assert_eq!(last.subject, "Second");
+        assert_eq!(last.body, "two");
+    }
+
+    #[test]
+    fn test_smtp_mailer_requires_host() {
+        let config = MailerConfig::default();
+        assert!(SmtpMailer::new(&config).is_err());
+
+        let config = MailerConfig {
+            smtp_host: Some("localhost".to_string()),
+            smtp_tls: SmtpTls::None,
+            ..MailerConfig::default()
+        };
+        assert!(SmtpMailer::new(&config).is_ok());
+    }
+}
@@ -0,0 +1,112 @@
+//! Process-wide counters
+//!
+//! Each increment is also emitted as a structured `metrics` log event
+//! (`metric`, `value` fields) so log-based pipelines can chart it. With
+//! `POOL_STATS`, counters and database pool gauges are also served in the
+//! Prometheus text format on `/metrics` (see `render`).
+
+use crate::shared::db::PoolStats;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Monotonic counter
+pub struct Counter {
+    name: &'static str,
+    value: AtomicU64,
+}
+
+impl Counter {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            value: AtomicU64::new(0),
+        }
+    }
+
+    /// Increment the counter and return the new value
+    pub fn increment(&self) -> u64 {
+        let value = self.value.fetch_add(1, Ordering::Relaxed) + 1;
+        tracing::info!(target: "metrics", metric = self.name, value);
+        value
+    }
+
+    /// Current value
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// JWTs rejected because the signature did not match the current secret
+///
+/// A spike right after a deploy usually means `JWT_SECRET` changed.
+pub static JWT_SIGNATURE_FAILURES: Counter = Counter::new("auth_jwt_signature_failures_total");
+
+/// Counters served on `/metrics`
+static COUNTERS: [&Counter; 1] = [&JWT_SIGNATURE_FAILURES];
+
+/// Prometheus text exposition of the counters and of `pools`
+///
+/// Pools are reported as `db_pool_connections` gauges, labelled by pool
+/// and by state (`idle`, `in_use`), next to `db_pool_max_connections`.
+pub fn render(pools: &[PoolStats]) -> String {
+    let mut out = String::new();
+
+    for counter in COUNTERS {
+        let _ = writeln!(out, "# TYPE {} counter", counter.name());
+        let _ = writeln!(out, "{} {}", counter.name(), counter.get());
+    }
+
+    let _ = writeln!(out, "# TYPE db_pool_connections gauge");
+    for stats in pools {
+        for (state, value) in [("idle", stats.idle), ("in_use", stats.in_use)] {
+            let _ = writeln!(
+                out,
+                "db_pool_connections{{pool=\"{}\",state=\"{}\"}} {}",
+                stats.pool, state, value
+            );
+        }
+    }
+
+    let _ = writeln!(out, "# TYPE db_pool_max_connections gauge");
+    for stats in pools {
+        let _ = writeln!(out, "db_pool_max_connections{{pool=\"{}\"}} {}", stats.pool, stats.max_size);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_increment() {
+        let counter = Counter::new("test_total");
+
+        assert_eq!(counter.increment(), 1);
+        assert_eq!(counter.increment(), 2);
+        assert_eq!(counter.get(), 2);
+    }
+
+    #[test]
+    fn test_render_pool_gauges() {
+        let pools = [PoolStats {
+            pool: "primary",
+            size: 5,
+            idle: 3,
+            in_use: 2,
+            max_size: 10,
+        }];
+
+        let text = render(&pools);
+
+        assert!(text.contains("# TYPE auth_jwt_signature_failures_total counter\n"));
+        assert!(text.contains("db_pool_connections{pool=\"primary\",state=\"idle\"} 3\n"));
+        assert!(text.contains("db_pool_connections{pool=\"primary\",state=\"in_use\"} 2\n"));
+        assert!(text.contains("db_pool_max_connections{pool=\"primary\"} 10\n"));
+    }
+}
@@ -1,6 +1,17 @@
+pub mod client_ip;
+pub mod cookies;
+pub mod db;
 pub mod error;
+pub mod i18n;
+pub mod mailer;
+pub mod metrics;
+pub mod pagination;
+pub mod request_id;
 pub mod result;
 pub mod types;
+pub mod validated_json;
 
+pub use client_ip::ClientIp;
 pub use error::AppError;
 pub use result::AppResult;
+pub use validated_json::{UncheckedJson, ValidatedJson};
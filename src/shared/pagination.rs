@@ -0,0 +1,111 @@
+//! Offset pagination for list endpoints
+
+use serde::Serialize;
+
+/// Page requested by a client, 1-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl PageRequest {
+    /// Items per page when the client does not ask for a size
+    pub const DEFAULT_PER_PAGE: u32 = 20;
+
+    /// Build a request from optional `page`/`per_page` query parameters
+    ///
+    /// Missing values fall back to the first page and `DEFAULT_PER_PAGE`;
+    /// the page is at least 1 and the size is clamped to `1..=max_per_page`.
+    pub fn new(page: Option<u32>, per_page: Option<u32>, max_per_page: u32) -> Self {
+        let max_per_page = max_per_page.max(1);
+        Self {
+            page: page.unwrap_or(1).max(1),
+            per_page: per_page
+                .unwrap_or(Self::DEFAULT_PER_PAGE)
+                .clamp(1, max_per_page),
+        }
+    }
+
+    /// SQL `LIMIT`
+    pub fn limit(&self) -> i64 {
+        i64::from(self.per_page)
+    }
+
+    /// SQL `OFFSET`
+    pub fn offset(&self) -> i64 {
+        i64::from(self.page - 1) * i64::from(self.per_page)
+    }
+}
+
+/// One page of a listing, with the total across all pages
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u32,
+    pub per_page: u32,
+    pub total: u64,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, request: PageRequest, total: u64) -> Self {
+        Self {
+            items,
+            page: request.page,
+            per_page: request.per_page,
+            total,
+        }
+    }
+
+    /// Same page with every item converted
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            page: self.page,
+            per_page: self.per_page,
+            total: self.total,
+        }
+    }
+}
+
+/// `ILIKE` pattern matching `search` anywhere, with the wildcards in it
+/// matched literally
+pub fn contains_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_clamping() {
+        assert_eq!(
+            PageRequest::new(None, None, 100),
+            PageRequest { page: 1, per_page: PageRequest::DEFAULT_PER_PAGE }
+        );
+        assert_eq!(
+            PageRequest::new(Some(0), Some(0), 100),
+            PageRequest { page: 1, per_page: 1 }
+        );
+        assert_eq!(PageRequest::new(Some(3), Some(500), 100).per_page, 100);
+    }
+
+    #[test]
+    fn test_contains_pattern_escapes_wildcards() {
+        assert_eq!(contains_pattern("acme"), "%acme%");
+        assert_eq!(contains_pattern("50%_off\\"), "%50\\%\\_off\\\\%");
+    }
+
+    #[test]
+    fn test_offset() {
+        let request = PageRequest::new(Some(3), Some(25), 100);
+
+        assert_eq!(request.limit(), 25);
+        assert_eq!(request.offset(), 50);
+    }
+}
@@ -0,0 +1,142 @@
+//! Request correlation IDs
+//!
+//! `request_id` takes the ID from the client's `x-request-id` header or
+//! generates a UUIDv7, stores it in the request extensions, runs the request
+//! in a tracing span carrying it and echoes it in the response. Error
+//! responses include it (see `current`), so clients can quote it in support
+//! tickets.
+
+use axum::{
+    extract::Request,
+    http::{header::HeaderName, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+
+/// Header carrying the request ID (taken from the client or generated)
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied ID kept; longer ones are replaced
+const MAX_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
+/// ID of a request, in its extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// The client's `x-request-id`, or a new UUIDv7 when it is missing,
+    /// empty, too long or not visible ASCII
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let id = headers
+            .get(&REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty() && v.len() <= MAX_LENGTH)
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::now_v7().to_string());
+        Self(id)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// ID of the request being handled (`None` outside `request_id`)
+pub fn current() -> Option<RequestId> {
+    CURRENT_REQUEST_ID.try_with(RequestId::clone).ok()
+}
+
+/// Assign the request its ID and echo it in `x-request-id`
+///
+/// Everything logged while handling the request (panics included) carries
+/// the ID.
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = RequestId::from_headers(request.headers());
+    request.extensions_mut().insert(id.clone());
+
+    let span = crate::request_span!(id);
+    let mut response = CURRENT_REQUEST_ID
+        .scope(id.clone(), next.run(request))
+        .instrument(span)
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(id.as_str()) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::AppError;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route(
+                "/fail",
+                get(|| async { Err::<(), _>(AppError::not_found("Missing")) }),
+            )
+            .layer(axum::middleware::from_fn(request_id))
+    }
+
+    async fn send(uri: &str, request_id: Option<&str>) -> Response {
+        let mut request = Request::builder().uri(uri);
+        if let Some(id) = request_id {
+            request = request.header("x-request-id", id);
+        }
+        app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_supplied_id_is_echoed_unchanged() {
+        let response = send("/ok", Some("support-ticket-42")).await;
+
+        assert_eq!(response.headers()["x-request-id"], "support-ticket-42");
+    }
+
+    #[tokio::test]
+    async fn test_missing_id_is_generated() {
+        let response = send("/ok", None).await;
+
+        let id = response.headers()["x-request-id"].to_str().unwrap();
+        assert_eq!(uuid::Uuid::parse_str(id).unwrap().get_version_num(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_id_is_replaced() {
+        let response = send("/ok", Some(&"a".repeat(MAX_LENGTH + 1))).await;
+
+        let id = response.headers()["x-request-id"].to_str().unwrap();
+        assert!(uuid::Uuid::parse_str(id).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_error_body_includes_request_id() {
+        let response = send("/fail", Some("req-123")).await;
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["request_id"], "req-123");
+    }
+
+    #[test]
+    fn test_no_current_id_outside_a_request() {
+        assert_eq!(current(), None);
+    }
+}
@@ -1,14 +1,101 @@
 use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use uuid::Uuid;
 
-/// Type alias for User ID
-pub type UserId = Uuid;
+/// Define a UUID newtype for one kind of entity ID
+///
+/// Distinct types keep a session ID from being passed where a user ID is
+/// expected. They are stored as plain `UUID` columns (`sqlx(transparent)`)
+/// and serialized as plain UUID strings (`serde(transparent)`).
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord,
+            serde::Serialize, serde::Deserialize, sqlx::Type,
+        )]
+        #[serde(transparent)]
+        #[sqlx(transparent)]
+        pub struct $name(Uuid);
+
+        impl $name {
+            /// New time-ordered ID (UUID v7)
+            #[allow(clippy::new_without_default)]
+            pub fn new() -> Self {
+                Self(Uuid::now_v7())
+            }
+
+            pub const fn from_uuid(id: Uuid) -> Self {
+                Self(id)
+            }
+
+            pub const fn into_inner(self) -> Uuid {
+                self.0
+            }
+        }
 
-/// Type alias for Session ID
-pub type SessionId = Uuid;
+        impl From<$name> for Uuid {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = uuid::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Uuid::parse_str(s).map(Self)
+            }
+        }
+    };
+}
 
-/// Type alias for Token ID
-pub type TokenId = Uuid;
+id_type!(
+    /// User ID
+    ///
+    /// ```
+    /// use multitenant::moduls::auth::infra::UserRepository;
+    /// use multitenant::shared::types::UserId;
+    ///
+    /// async fn lookup(repo: &impl UserRepository) {
+    ///     let _ = repo.find_by_id(UserId::new()).await;
+    /// }
+    /// ```
+    ///
+    /// Other kinds of ID are rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// use multitenant::moduls::auth::infra::UserRepository;
+    /// use multitenant::shared::types::SessionId;
+    ///
+    /// async fn lookup(repo: &impl UserRepository) {
+    ///     let _ = repo.find_by_id(SessionId::new()).await;
+    /// }
+    /// ```
+    UserId
+);
+
+id_type!(
+    /// Web session ID
+    SessionId
+);
+
+id_type!(
+    /// JWT ID (`jti`)
+    TokenId
+);
+
+id_type!(
+    /// Organization (tenant) ID
+    OrganizationId
+);
 
 /// Type alias for timestamps
 pub type Timestamp = DateTime<Utc>;
@@ -16,7 +103,9 @@ pub type Timestamp = DateTime<Utc>;
 /// Generate a new UUID v7 (time-ordered)
 ///
 /// UUID v7 provides better database indexing performance than v4
-/// because it includes a timestamp component.
+/// because it includes a timestamp component. Typed IDs have their own
+/// constructors (`UserId::new()`, `UserId::from_uuid(..)`), so a plain
+/// UUID never turns into an ID by accident.
 pub fn new_id() -> Uuid {
     Uuid::now_v7()
 }
@@ -26,6 +115,95 @@ pub fn now() -> Timestamp {
     Utc::now()
 }
 
+/// Source of the current time
+///
+/// Domain constructors use `SystemClock` unless given another one, so
+/// time-dependent logic can be tested with a `FixedClock`.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Timestamp;
+}
+
+/// Source of new entity IDs (see `Clock`)
+pub trait IdGenerator: Send + Sync {
+    fn new_id(&self) -> Uuid;
+}
+
+/// The real time (`now()`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        now()
+    }
+}
+
+/// Random UUID v7 (`new_id()`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemIdGenerator;
+
+impl IdGenerator for SystemIdGenerator {
+    fn new_id(&self) -> Uuid {
+        new_id()
+    }
+}
+
+/// Clock standing still until set or advanced
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<Timestamp>,
+}
+
+impl FixedClock {
+    pub fn new(now: Timestamp) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    pub fn set(&self, now: Timestamp) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, duration: chrono::Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> Timestamp {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Predictable UUID v7: all share the timestamp of `start` and the
+/// remaining bits count up from 1, so they sort in generation order
+#[derive(Debug)]
+pub struct SequentialIdGenerator {
+    millis: u64,
+    counter: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    pub fn new(start: Timestamp) -> Self {
+        Self {
+            millis: start.timestamp_millis().max(0) as u64,
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// The `n`th ID this generator returns (starting at 1)
+    pub fn nth(&self, n: u64) -> Uuid {
+        let mut counter_bytes = [0u8; 10];
+        counter_bytes[2..].copy_from_slice(&n.to_be_bytes());
+        uuid::Builder::from_unix_timestamp_millis(self.millis, &counter_bytes).into_uuid()
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn new_id(&self) -> Uuid {
+        self.nth(self.counter.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,18 +212,61 @@ mod tests {
     fn test_new_id_generates_uuid_v7() {
         let id = new_id();
         assert_eq!(id.get_version_num(), 7);
+        assert_eq!(UserId::new().into_inner().get_version_num(), 7);
     }
 
     #[test]
     fn test_new_id_is_unique() {
-        let id1 = new_id();
-        let id2 = new_id();
+        let id1 = UserId::new();
+        let id2 = UserId::new();
         assert_ne!(id1, id2);
     }
 
+    #[test]
+    fn test_id_types_round_trip() {
+        let uuid = Uuid::now_v7();
+        let user_id = UserId::from_uuid(uuid);
+
+        assert_eq!(user_id.into_inner(), uuid);
+        assert_eq!(user_id.to_string(), uuid.to_string());
+        assert_eq!(uuid.to_string().parse::<UserId>().unwrap(), user_id);
+        assert!("not-a-uuid".parse::<SessionId>().is_err());
+        assert_eq!(serde_json::to_string(&user_id).unwrap(), format!("\"{}\"", uuid));
+    }
+
     #[test]
     fn test_now_returns_utc() {
         let timestamp = now();
         assert_eq!(timestamp.timezone(), Utc);
     }
+
+    #[test]
+    fn test_fixed_clock_only_moves_when_told() {
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let clock = FixedClock::new(start);
+
+        assert_eq!(clock.now(), start);
+        clock.advance(chrono::Duration::seconds(90));
+        assert_eq!(clock.now(), start + chrono::Duration::seconds(90));
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+
+    #[test]
+    fn test_sequential_ids_are_ordered_uuid_v7() {
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let ids = SequentialIdGenerator::new(start);
+
+        let generated: Vec<Uuid> = (0..3).map(|_| ids.new_id()).collect();
+
+        assert_eq!(generated, [ids.nth(1), ids.nth(2), ids.nth(3)]);
+        assert!(generated.windows(2).all(|pair| pair[0] < pair[1]));
+        for id in &generated {
+            assert_eq!(id.get_version_num(), 7);
+            let (secs, _) = id.get_timestamp().unwrap().to_unix();
+            assert_eq!(secs, 1_700_000_000);
+        }
+        // Same start, same IDs
+        assert_eq!(SequentialIdGenerator::new(start).new_id(), generated[0]);
+    }
 }
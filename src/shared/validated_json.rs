@@ -0,0 +1,125 @@
+use crate::shared::AppError;
+use axum::{
+    extract::{FromRequest, Request},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+/// JSON extractor that also runs `validator` rules
+///
+/// Rejects malformed JSON with 400 Bad Request and failed rules with a
+/// 400 `VALIDATION_ERROR` whose `fields` map each field to its messages,
+/// before the handler body runs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| AppError::bad_request(rejection.body_text()))?;
+
+        value.validate()?;
+
+        Ok(Self(value))
+    }
+}
+
+/// JSON extractor that leaves the `validator` rules to the use case
+///
+/// For requests whose field errors are reported together with checks only
+/// the use case can make (see `AppError::check_fields`). Malformed JSON is
+/// rejected like `ValidatedJson` does.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UncheckedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for UncheckedJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| AppError::bad_request(rejection.body_text()))?;
+
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, response::IntoResponse};
+
+    #[derive(Debug, serde::Deserialize, Validate)]
+    struct SignupInput {
+        #[validate(email)]
+        email: String,
+        #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+        password: String,
+    }
+
+    async fn extract(body: &str) -> Result<ValidatedJson<SignupInput>, AppError> {
+        let request = Request::builder()
+            .method("POST")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        ValidatedJson::<SignupInput>::from_request(request, &()).await
+    }
+
+    async fn error_body(error: AppError) -> (StatusCode, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_valid_input_is_extracted() {
+        let ValidatedJson(input) = extract(r#"{"email":"a@example.com","password":"longenough"}"#)
+            .await
+            .unwrap();
+
+        assert_eq!(input.email, "a@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_input_has_field_keyed_errors() {
+        let error = extract(r#"{"email":"not-an-email","password":"short"}"#)
+            .await
+            .unwrap_err();
+
+        let (status, body) = error_body(error).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+        assert_eq!(body["error"]["fields"]["email"][0], "email");
+        assert_eq!(
+            body["error"]["fields"]["password"][0],
+            "Password must be at least 8 characters"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_is_bad_request() {
+        let error = extract(r#"{"email":"#).await.unwrap_err();
+
+        let (status, body) = error_body(error).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "BAD_REQUEST");
+        assert!(body["error"].get("fields").is_none());
+    }
+}
@@ -1,19 +1,33 @@
-use crate::bootstrap::AppState;
-use crate::moduls::auth::{auth_api_routes, auth_web_routes};
-use crate::moduls::user::{user_api_routes, user_web_routes};
+use crate::bootstrap::{
+    access_log::access_log, body_timeout::body_read_timeout, catch_panic::catch_panic_layer, AppState,
+};
+use crate::config::CorsConfig;
+use crate::shared::db::{read_your_writes, PoolStats};
+use crate::shared::i18n::localize;
+use crate::shared::request_id::request_id;
+use crate::moduls::audit::audit_context;
+use crate::moduls::auth::api::handlers::jwks;
+use crate::moduls::auth::{admin_api_routes, auth_api_routes, auth_web_routes};
+use crate::moduls::oauth::{oauth_api_routes, oauth_link_api_routes};
+use crate::moduls::organization::api::tenant_middleware;
+use crate::moduls::organization::organization_api_routes;
+use crate::moduls::user::{user_api_routes, user_web_routes, users_api_routes};
 use axum::{
-    extract::State,
+    extract::{Request, State},
     http::StatusCode,
-    response::Json,
+    middleware::{self, Next},
+    response::{Json, Response},
     routing::get,
-    Router,
+    Extension, Router,
 };
 use serde::Serialize;
 use axum::http::{header, HeaderValue, Method};
+use std::time::Duration;
 use tower_http::{
     compression::CompressionLayer,
-    cors::CorsLayer,
+    cors::{Any, CorsLayer},
     set_header::SetResponseHeaderLayer,
+    timeout::TimeoutLayer,
     trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer},
     LatencyUnit,
 };
@@ -23,43 +37,96 @@ use tower_http::{
 struct HealthResponse {
     status: String,
     database: String,
+    /// Embedded migrations not applied to the database yet
+    pending_migrations: bool,
+    /// Primary pool connection counts
+    pool_connections: PoolConnections,
+    /// Crate version
+    version: &'static str,
+    /// Optional dependencies that failed their startup check
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    degraded: Vec<String>,
+    /// Database pool connection counts (with `POOL_STATS`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pools: Option<Vec<PoolStats>>,
     timestamp: String,
 }
 
+/// Idle and total (idle or in use) connections of a pool
+#[derive(Debug, Serialize)]
+struct PoolConnections {
+    idle: u32,
+    total: u32,
+}
+
+/// Liveness check response
+#[derive(Debug, Serialize)]
+struct LivenessResponse {
+    status: &'static str,
+    version: &'static str,
+}
+
 /// Build the Axum application with all routes and middleware
 pub async fn build_app(state: AppState) -> Router {
     tracing::info!("Building application router...");
 
-    // Configure CORS - restrict origins in production
-    let allowed_origins = std::env::var("ALLOWED_ORIGINS")
-        .unwrap_or_else(|_| "http://localhost:3000,http://localhost:5173".to_string());
-
-    let origins: Vec<HeaderValue> = allowed_origins
-        .split(',')
-        .filter_map(|origin| origin.trim().parse().ok())
-        .collect();
-
-    let cors = if origins.is_empty() {
-        CorsLayer::permissive()
-    } else {
-        CorsLayer::new()
-            .allow_origin(origins)
-            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
-            .allow_credentials(true)
-    };
-
     // Create the main router
     let app = Router::new()
-        // Health check endpoint
-        .route("/health", get(health_check))
+        // Health check endpoints (`/health` is an alias of readiness)
+        .route("/health", get(readiness_check))
+        .route("/health/ready", get(readiness_check))
+        .route("/health/live", get(liveness_check))
+        // Public keys for verifying RS256 access tokens
+        .route("/.well-known/jwks.json", get(jwks))
         // Mount authentication routes
         .nest("/web/auth", auth_web_routes())
-        .nest("/api/auth", auth_api_routes())
+        .nest("/api/auth", auth_api_routes(state.clone()))
+        .nest("/api/auth/oauth", oauth_api_routes())
         // Mount user module routes
-        .nest("/web/user", user_web_routes())
+        .nest("/web/user", user_web_routes(state.clone()))
         .nest("/api/user", user_api_routes(state.clone()))
+        .nest("/api/user/oauth", oauth_link_api_routes(state.clone()))
+        .nest("/api/users", users_api_routes(state.clone()))
+        // Mount organization (tenant) routes
+        .nest("/api/organizations", organization_api_routes(state.clone()))
+        // Mount admin routes
+        .nest("/api/admin", admin_api_routes(state.clone()));
+
+    // Prometheus metrics, opted into along with the pool stats
+    let app = if state.config.server.pool_stats {
+        app.route("/metrics", get(metrics))
+    } else {
+        app
+    };
+
+    // Development endpoints: compiled in with `dev-tools`, mounted with DEV_MODE
+    #[cfg(feature = "dev-tools")]
+    let app = if state.config.server.dev_mode {
+        tracing::warn!("DEV_MODE is on: /api/dev mints tokens without a password");
+        app.nest("/api/dev", crate::moduls::auth::dev_api_routes())
+    } else {
+        app
+    };
+
+    let app = app
         .with_state(state.clone())
+        // Reads after a write in the same request use the primary database
+        .layer(middleware::from_fn(read_your_writes))
+        // Tenant from X-Tenant-Slug or the Host subdomain (TenantContext)
+        .layer(middleware::from_fn_with_state(state.clone(), tenant_middleware))
+        // Panics answer 500 with the JSON error envelope (localized below)
+        .layer(catch_panic_layer())
+        // Error messages in the client's language (Accept-Language)
+        .layer(middleware::from_fn_with_state(state.clone(), localize))
+        // Client IP address and User-Agent for audit events
+        .layer(middleware::from_fn(audit_context))
+        // Handler timeout, started once the body has been read
+        .layer(TimeoutLayer::with_status_code(
+            StatusCode::SERVICE_UNAVAILABLE,
+            Duration::from_secs(state.config.server.request_timeout),
+        ))
+        // Abort slow request bodies with 408 (separate from the handler timeout)
+        .layer(middleware::from_fn_with_state(state.clone(), body_read_timeout))
         // Add security headers
         .layer(SetResponseHeaderLayer::overriding(
             header::X_CONTENT_TYPE_OPTIONS,
@@ -78,11 +145,17 @@ pub async fn build_app(state: AppState) -> Router {
             HeaderValue::from_static("max-age=31536000; includeSubDomains"),
         ))
         // Add CORS middleware
-        .layer(cors)
+        // Outside every route layer, so preflights never reach auth/tenant middleware
+        .layer(cors_layer(&state.config.cors))
+        .layer(middleware::from_fn(preflight_no_content))
         // Add compression middleware
-        .layer(CompressionLayer::new())
-        // Add tracing middleware
-        .layer(
+        .layer(CompressionLayer::new());
+
+    // Add request logging: JSON access log or tracing spans, never both
+    let app = if state.config.server.access_log {
+        app.layer(middleware::from_fn(access_log))
+    } else {
+        app.layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().include_headers(true))
                 .on_response(
@@ -90,51 +163,376 @@ pub async fn build_app(state: AppState) -> Router {
                         .include_headers(true)
                         .latency_unit(LatencyUnit::Micros),
                 ),
-        );
+        )
+    };
+
+    // Proxies allowed to report the client address, for everything reading it
+    let app = app.layer(Extension(state.config.server.trusted_proxies.clone()));
+
+    // Outermost, so the logs above and every error response carry the request ID
+    let app = app.layer(middleware::from_fn(request_id));
 
     tracing::info!("Application router built successfully");
     app
 }
 
-/// Health check handler
-async fn health_check(State(state): State<AppState>) -> Result<Json<HealthResponse>, StatusCode> {
+/// Configure CORS - restrict origins in production
+///
+/// Only an empty `ALLOWED_ORIGINS` allows any origin. Configured origins
+/// that are not valid are skipped with a warning; if none is left, every
+/// cross-origin request is refused rather than allowed.
+fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let layer = CorsLayer::new()
+        .allow_methods(config.allowed_methods.clone())
+        .allow_headers(config.allowed_headers.clone())
+        .expose_headers(config.expose_headers.clone())
+        .allow_credentials(config.allow_credentials)
+        .max_age(Duration::from_secs(config.max_age));
+
+    if config.allowed_origins.is_empty() {
+        tracing::warn!("ALLOWED_ORIGINS is empty: CORS allows any origin");
+        return layer.allow_origin(Any);
+    }
+
+    let origins: Vec<HeaderValue> = config
+        .allowed_origins
+        .iter()
+        .filter_map(|origin| {
+            let parsed = parse_origin(origin);
+            if parsed.is_none() {
+                tracing::warn!("Ignoring invalid CORS origin '{}'", origin);
+            }
+            parsed
+        })
+        .collect();
+
+    if origins.is_empty() {
+        tracing::warn!("No valid origin in ALLOWED_ORIGINS: all cross-origin requests are refused");
+    }
+
+    layer.allow_origin(origins)
+}
+
+/// Parse a configured origin (`scheme://host[:port]`) into the value
+/// browsers send in `Origin`, which has no trailing slash
+fn parse_origin(origin: &str) -> Option<HeaderValue> {
+    let origin = origin.trim_end_matches('/');
+    let (scheme, host) = origin.split_once("://")?;
+    if scheme.is_empty() || host.is_empty() || host.contains('/') {
+        return None;
+    }
+    origin.parse().ok()
+}
+
+/// Answer CORS preflights with 204 No Content
+///
+/// `CorsLayer` short-circuits preflights with an empty 200; browsers accept
+/// either, but 204 is what clients and proxies expect for an empty body.
+async fn preflight_no_content(request: Request, next: Next) -> Response {
+    let is_preflight = request.method() == Method::OPTIONS
+        && request
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+
+    let mut response = next.run(request).await;
+    if is_preflight && response.status() == StatusCode::OK {
+        *response.status_mut() = StatusCode::NO_CONTENT;
+    }
+
+    response
+}
+
+/// Liveness check handler
+///
+/// Answers as long as the process serves requests, without touching the
+/// database, so a database outage doesn't get the process restarted.
+async fn liveness_check() -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: "alive",
+        version: env!("CARGO_PKG_VERSION"),
+    })
+}
+
+/// Readiness check handler
+///
+/// Reports 503 until startup warmup has completed, and while the database
+/// is unreachable or has migrations pending.
+async fn readiness_check(State(state): State<AppState>) -> Result<Json<HealthResponse>, StatusCode> {
+    if !state.readiness.is_ready() {
+        tracing::debug!("Readiness check failed: warmup still in progress");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+
     // Check database connectivity
-    let db_status = match crate::bootstrap::database::health_check(&state.db).await {
-        Ok(_) => "connected",
+    if let Err(e) = crate::bootstrap::database::health_check(&state.db).await {
+        tracing::error!("Database health check failed: {:?}", e);
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    let pending_migrations = match crate::bootstrap::database::has_pending_migrations(&state.db).await {
+        Ok(pending) => pending,
         Err(e) => {
-            tracing::error!("Database health check failed: {:?}", e);
-            "disconnected"
+            tracing::error!("Migration status check failed: {:?}", e);
+            return Err(StatusCode::SERVICE_UNAVAILABLE);
         }
     };
+    if pending_migrations {
+        tracing::warn!("Readiness check failed: database migrations are pending");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
 
-    let response = HealthResponse {
+    Ok(Json(HealthResponse {
         status: "healthy".to_string(),
-        database: db_status.to_string(),
+        database: "connected".to_string(),
+        pending_migrations,
+        pool_connections: PoolConnections {
+            idle: state.db.num_idle() as u32,
+            total: state.db.size(),
+        },
+        version: env!("CARGO_PKG_VERSION"),
+        degraded: state.readiness.degraded(),
+        pools: state.config.server.pool_stats.then(|| state.pools.stats()),
         timestamp: chrono::Utc::now().to_rfc3339(),
-    };
-
-    // If database is down, return 503 Service Unavailable
-    if db_status == "disconnected" {
-        return Err(StatusCode::SERVICE_UNAVAILABLE);
-    }
+    }))
+}
 
-    Ok(Json(response))
+/// Prometheus metrics handler (`POOL_STATS`)
+async fn metrics(State(state): State<AppState>) -> ([(header::HeaderName, &'static str); 1], String) {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::shared::metrics::render(&state.pools.stats()),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    async fn preflight(path: &str) -> Response {
+        preflight_with(AppState::for_tests(), path).await
+    }
+
+    async fn preflight_with(state: AppState, path: &str) -> Response {
+        use tower::ServiceExt;
+
+        let app = build_app(state).await;
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri(path)
+            .header(header::ORIGIN, "http://localhost:3000")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        app.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_preflight_to_protected_route_skips_auth() {
+        for path in ["/api/auth/me", "/api/user/profile", "/api/user/oauth/google/link"] {
+            let response = preflight(path).await;
+
+            assert_eq!(response.status(), StatusCode::NO_CONTENT, "{}", path);
+            assert_eq!(
+                response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+                "http://localhost:3000"
+            );
+            assert!(response
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_METHODS));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_origins_do_not_allow_any_origin() {
+        let mut state = AppState::for_tests();
+        state.config.cors.allowed_origins = vec!["localhost:3000".to_string()];
+
+        let response = preflight_with(state, "/api/auth/me").await;
+
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn test_cors_credentials_and_max_age_are_configurable() {
+        let mut state = AppState::for_tests();
+        state.config.cors.allowed_origins = vec!["http://localhost:3000/".to_string()];
+        state.config.cors.allow_credentials = false;
+        state.config.cors.max_age = 60;
+
+        let response = preflight_with(state, "/api/auth/me").await;
+
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:3000"
+        );
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_MAX_AGE], "60");
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_CREDENTIALS));
+    }
+
+    #[tokio::test]
+    async fn test_preflight_allows_api_headers() {
+        use tower::ServiceExt;
+
+        let app = build_app(AppState::for_tests()).await;
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/auth/login")
+            .header(header::ORIGIN, "http://localhost:3000")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(
+                header::ACCESS_CONTROL_REQUEST_HEADERS,
+                "content-type,x-tenant-slug,idempotency-key",
+            )
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let allowed = response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS]
+            .to_str()
+            .unwrap()
+            .to_string();
+        for name in ["x-tenant-slug", "idempotency-key", "x-csrf-token", "x-api-key", "x-request-id"] {
+            assert!(allowed.contains(name), "{} not in {}", name, allowed);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_responses_expose_request_and_tenant_ids() {
+        use tower::ServiceExt;
+
+        let app = build_app(AppState::for_tests()).await;
+        let request = Request::builder()
+            .uri("/health")
+            .header(header::ORIGIN, "http://localhost:3000")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        let exposed = response.headers()[header::ACCESS_CONTROL_EXPOSE_HEADERS].to_str().unwrap();
+        assert!(exposed.contains("x-request-id"));
+        assert!(exposed.contains("x-tenant-id"));
+    }
+
+    #[test]
+    fn test_parse_origin() {
+        assert_eq!(parse_origin("https://example.com/").unwrap(), "https://example.com");
+        assert!(parse_origin("http://localhost:3000").is_some());
+        assert!(parse_origin("example.com").is_none());
+        assert!(parse_origin("https://example.com/app").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plain_options_is_not_rewritten() {
+        use tower::ServiceExt;
+
+        let app = build_app(AppState::for_tests()).await;
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/health")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_ne!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_with_and_without_access_log() {
+        use tower::ServiceExt;
+
+        for enabled in [true, false] {
+            let mut state = AppState::for_tests();
+            state.config.server.access_log = enabled;
+            let request = Request::builder()
+                .uri("/health/ready")
+                .body(axum::body::Body::empty())
+                .unwrap();
+
+            let response = build_app(state).await.oneshot(request).await.unwrap();
+
+            assert!(response.headers().contains_key("x-request-id"));
+        }
+    }
+
     #[test]
     fn test_health_response_serialization() {
         let response = HealthResponse {
             status: "healthy".to_string(),
             database: "connected".to_string(),
+            pending_migrations: false,
+            pool_connections: PoolConnections { idle: 3, total: 5 },
+            version: "0.1.0",
+            degraded: Vec::new(),
+            pools: None,
             timestamp: "2025-01-17T10:30:00Z".to_string(),
         };
 
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("healthy"));
         assert!(json.contains("connected"));
+        assert!(json.contains(r#""pending_migrations":false"#));
+        assert!(json.contains(r#""pool_connections":{"idle":3,"total":5}"#));
+        assert!(!json.contains("degraded"));
+        assert!(!json.contains("pools"));
+    }
+
+    #[tokio::test]
+    async fn test_liveness_independent_of_database() {
+        use tower::ServiceExt;
+
+        // Nothing listens on port 1
+        let mut state = AppState::for_tests();
+        state.db = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("postgres://postgres@127.0.0.1:1/unreachable")
+            .unwrap();
+        let app = build_app(state).await;
+
+        for (path, expected) in [
+            ("/health/live", StatusCode::OK),
+            ("/health/ready", StatusCode::SERVICE_UNAVAILABLE),
+            ("/health", StatusCode::SERVICE_UNAVAILABLE),
+        ] {
+            let request = Request::builder().uri(path).body(axum::body::Body::empty()).unwrap();
+
+            let response = app.clone().oneshot(request).await.unwrap();
+
+            assert_eq!(response.status(), expected, "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_mounted_with_pool_stats() {
+        use tower::ServiceExt;
+
+        for enabled in [true, false] {
+            let mut state = AppState::for_tests();
+            state.config.server.pool_stats = enabled;
+            let request = Request::builder()
+                .uri("/metrics")
+                .body(axum::body::Body::empty())
+                .unwrap();
+
+            let response = build_app(state).await.oneshot(request).await.unwrap();
+
+            if enabled {
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                let text = String::from_utf8(body.to_vec()).unwrap();
+                assert!(text.contains("db_pool_connections{pool=\"primary\",state=\"idle\"} 0"));
+            } else {
+                assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            }
+        }
     }
 }
@@ -1,11 +1,15 @@
 mod common;
 
-use common::TestApp;
+use common::{SeedUser, TestApp, TEST_PASSWORD};
+use multitenant::moduls::auth::api::middleware::OptionalAuthenticatedUser;
+use multitenant::moduls::auth::domain::{ClientHashParams, Email, User};
+use multitenant::moduls::auth::infra::UserRepository;
+use multitenant::shared::types::{SessionId, UserId};
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_health_check() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     let response = app.get("/health").await;
 
@@ -14,14 +18,70 @@ async fn test_health_check() {
     let body: serde_json::Value = response.json().await.expect("Failed to parse response");
     assert_eq!(body["status"], "healthy");
     assert_eq!(body["database"], "connected");
+    assert_eq!(body["pending_migrations"], false);
+    assert!(body["pool_connections"]["total"].as_u64().unwrap() >= 1);
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+
+    let response = app.get("/health/live").await;
+    assert_eq!(response.status(), 200);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_readiness_check() {
+    let app = TestApp::spawn_isolated().await;
+
+    let response = app.get("/health/ready").await;
+
+    assert_eq!(response.status(), 200);
+
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert_eq!(body["database"], "connected");
+    assert!(body.get("pools").is_none());
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_readiness_reports_pool_stats() {
+    let app = TestApp::spawn_isolated_with(|config| config.server.pool_stats = true).await;
+
+    let requests: Vec<_> = (0..10)
+        .map(|_| {
+            let request = app.client.get(format!("{}/health", app.address)).send();
+            tokio::spawn(async move { request.await.unwrap().status() })
+        })
+        .collect();
+    for request in requests {
+        assert_eq!(request.await.unwrap(), 200);
+    }
+
+    let response = app.get("/health/ready").await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let primary = &body["pools"][0];
+    assert_eq!(primary["pool"], "primary");
+    let size = primary["size"].as_u64().unwrap();
+    assert!(size >= 1);
+    assert_eq!(
+        primary["idle"].as_u64().unwrap() + primary["in_use"].as_u64().unwrap(),
+        size
+    );
+    assert!(primary["max_size"].as_u64().unwrap() >= size);
+
+    let metrics = app.get("/metrics").await.text().await.unwrap();
+    assert!(metrics.contains("db_pool_max_connections{pool=\"primary\"}"));
 
     app.cleanup().await;
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_register_success() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     let response = app
         .post_json(
@@ -44,9 +104,9 @@ async fn test_register_success() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_register_duplicate_email() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     // First registration - should succeed
     let response = app
@@ -75,14 +135,327 @@ async fn test_register_duplicate_email() {
         .await;
 
     assert_eq!(response.status(), 409, "Expected 409 Conflict");
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "CONFLICT");
+    assert!(body["error"].get("fields").is_none(), "Public conflicts stay generic");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_admin_create_user_reports_conflicting_field() {
+    let app = TestApp::spawn_isolated().await;
+    let member_token = app.register_and_token("member@example.com").await;
+    app.register_and_token("admin@example.com").await;
+    grant_role(&app, "admin@example.com", "admin").await;
+    let token = app.login_token("admin@example.com", TEST_PASSWORD).await;
+    let new_user = |email: &str| {
+        serde_json::json!({
+            "name": "Created User",
+            "email": email,
+            "password": "SecurePassword123!"
+        })
+    };
+
+    let response = app
+        .authed_post_json("/api/admin/users", &token, &new_user("created@example.com"))
+        .await;
+    assert_eq!(response.status(), 201);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["email"], "created@example.com");
+
+    let response = app
+        .authed_post_json("/api/admin/users", &token, &new_user("Member@Example.com"))
+        .await;
+    assert_eq!(response.status(), 409);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "CONFLICT");
+    assert_eq!(body["error"]["fields"]["email"], serde_json::json!(["Email already exists"]));
+
+    // Only admins get the detailed answer
+    let response = app
+        .authed_post_json("/api/admin/users", &member_token, &new_user("member@example.com"))
+        .await;
+    assert_eq!(response.status(), 403);
+
+    app.cleanup().await;
+}
+
+/// Admin token, after seeding `count` extra users named `user-NNN`
+async fn admin_with_users(app: &TestApp, count: usize) -> String {
+    use multitenant::moduls::auth::domain::PasswordHasher;
+
+    app.register_and_token("admin@example.com").await;
+    grant_role(app, "admin@example.com", "admin").await;
+
+    let hasher = PasswordHasher::Bcrypt { cost: 4 };
+    for i in 0..count {
+        let email = Email::new(&format!("user-{:03}@example.com", i)).unwrap();
+        let user = User::with_hasher(email, TEST_PASSWORD, format!("User {:03}", i), &hasher).unwrap();
+        app.state.user_repo.save(&user).await.unwrap();
+    }
+
+    app.login_token("admin@example.com", TEST_PASSWORD).await
+}
+
+fn emails(page: &serde_json::Value) -> Vec<&str> {
+    page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|u| u["email"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_admin_list_users_pagination_boundaries() {
+    let app = TestApp::spawn_isolated().await;
+    let token = admin_with_users(&app, 104).await;
+    let list = |query: &'static str| {
+        let app = &app;
+        let token = &token;
+        async move {
+            let response = app.authed_get(&format!("/api/admin/users{}", query), token).await;
+            assert_eq!(response.status(), 200);
+            response.json::<serde_json::Value>().await.unwrap()
+        }
+    };
+
+    // Defaults, oldest first
+    let page = list("").await;
+    assert_eq!((page["page"].as_u64(), page["per_page"].as_u64()), (Some(1), Some(20)));
+    assert_eq!(page["total"], 105);
+    assert_eq!(emails(&page)[..2], ["admin@example.com", "user-000@example.com"]);
+    assert!(page["items"][0].get("password_hash").is_none());
+
+    // Page size is capped at 100, the last page is partial
+    let page = list("?per_page=500").await;
+    assert_eq!(page["per_page"], 100);
+    assert_eq!(emails(&page).len(), 100);
+    let page = list("?page=2&per_page=100").await;
+    assert_eq!(emails(&page).len(), 5);
+    assert_eq!(emails(&page)[4], "user-103@example.com");
+
+    // Past the end and below the bounds
+    let page = list("?page=3&per_page=100").await;
+    assert!(emails(&page).is_empty());
+    assert_eq!(page["total"], 105);
+    let page = list("?page=0&per_page=0").await;
+    assert_eq!((page["page"].as_u64(), page["per_page"].as_u64()), (Some(1), Some(1)));
+    assert_eq!(emails(&page), ["admin@example.com"]);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_admin_list_users_search() {
+    let app = TestApp::spawn_isolated().await;
+    let member_token = app.register_and_token("member@acme.com").await;
+    let token = admin_with_users(&app, 12).await;
+    let search = |q: &'static str| {
+        let app = &app;
+        let token = &token;
+        async move {
+            let response = app.authed_get(&format!("/api/admin/users?q={}", q), token).await;
+            assert_eq!(response.status(), 200);
+            response.json::<serde_json::Value>().await.unwrap()
+        }
+    };
+
+    let page = search("ACME").await;
+    assert_eq!(emails(&page), ["member@acme.com"]);
+    assert_eq!(page["total"], 1);
+
+    // Names are searched too
+    let page = search("user%2001").await;
+    assert_eq!(emails(&page), ["user-010@example.com", "user-011@example.com"]);
+    assert_eq!(page["total"], 2);
+
+    // Wildcards are matched literally, blanks match everyone
+    assert_eq!(search("%25").await["total"], 0);
+    assert_eq!(search("user_0").await["total"], 0);
+    assert_eq!(search("%20%20").await["total"], 14);
+
+    // Only admins may browse users
+    let response = app.authed_get("/api/admin/users", &member_token).await;
+    assert_eq!(response.status(), 403);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_admin_deactivation_signs_user_out_until_reactivated() {
+    let app = TestApp::spawn_isolated().await;
+    let user_token = app.register_and_token("user@example.com").await;
+    let token = admin_with_users(&app, 0).await;
+    let user_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("user@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let path = format!("/api/admin/users/{}/status", user_id);
+    let set_active = |active: bool| serde_json::json!({ "active": active });
+    assert_eq!(app.authed_get("/api/auth/me", &user_token).await.status(), 200);
+
+    let response = app.authed_patch_json(&path, &token, &set_active(false)).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["is_active"], false);
+    assert_eq!(body["status"], "inactive");
+
+    // Signed out at once, and can't sign in again
+    assert_eq!(app.authed_get("/api/auth/me", &user_token).await.status(), 401);
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({ "email": "user@example.com", "password": TEST_PASSWORD }),
+        )
+        .await;
+    assert_eq!(response.status(), 401);
+
+    let response = app.authed_patch_json(&path, &token, &set_active(true)).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["is_active"], true);
+
+    // The old token stays revoked; a fresh login works
+    assert_eq!(app.authed_get("/api/auth/me", &user_token).await.status(), 401);
+    let user_token = app.login_token("user@example.com", TEST_PASSWORD).await;
+    assert_eq!(app.authed_get("/api/auth/me", &user_token).await.status(), 200);
+
+    // Unknown users, the admin themselves and plain users are refused
+    let unknown = format!("/api/admin/users/{}/status", uuid::Uuid::now_v7());
+    let response = app.authed_patch_json(&unknown, &token, &set_active(false)).await;
+    assert_eq!(response.status(), 404);
+    let admin_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("admin@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let own = format!("/api/admin/users/{}/status", admin_id);
+    let response = app.authed_patch_json(&own, &token, &set_active(false)).await;
+    assert_eq!(response.status(), 400);
+    let response = app.authed_patch_json(&path, &user_token, &set_active(false)).await;
+    assert_eq!(response.status(), 403);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_failed_logins_lock_account_until_admin_unlocks() {
+    let app = TestApp::spawn_isolated_with(|config| config.security.lockout_threshold = 2).await;
+    app.register_and_token("user@example.com").await;
+    let token = admin_with_users(&app, 0).await;
+    let user_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("user@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let app = &app;
+    let login = |password: &'static str| async move {
+        app.post_json(
+            "/api/auth/login",
+            &serde_json::json!({ "email": "user@example.com", "password": password }),
+        )
+        .await
+    };
+
+    assert_eq!(login("wrong-password").await.status(), 401);
+    assert_eq!(login("wrong-password").await.status(), 401);
+
+    // Locked: even the right password is refused
+    let response = login(TEST_PASSWORD).await;
+    assert_eq!(response.status(), 401);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["error"]["message"].as_str().unwrap().contains("locked"));
+
+    let path = format!("/api/admin/users/{}/lock", user_id);
+    let response = app.authed_delete(&path, &token).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_ne!(body["status"], "locked");
+    let user_token = app.login_token("user@example.com", TEST_PASSWORD).await;
+
+    // An expiry in the past refuses the user's tokens at once
+    let path = format!("/api/admin/users/{}/expiry", user_id);
+    let expired = serde_json::json!({ "expires_at": "2000-01-01T00:00:00Z" });
+    let response = app.authed_put_json(&path, &token, &expired).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(app.authed_get("/api/auth/me", &user_token).await.status(), 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_register_with_idempotency_key_replays_response() {
+    let app = TestApp::spawn_isolated().await;
+    let body = serde_json::json!({
+        "name": "Retry User",
+        "email": "retry@example.com",
+        "password": TEST_PASSWORD,
+    });
+    let register = |key: &'static str, body: &serde_json::Value| {
+        app.client
+            .post(format!("{}/api/auth/register", app.address))
+            .header("Idempotency-Key", key)
+            .json(body)
+            .send()
+    };
+
+    let first = register("retry-1", &body).await.unwrap();
+    assert_eq!(first.status(), 201);
+    assert!(first.headers().get("idempotent-replayed").is_none());
+    let first_body = first.text().await.unwrap();
+
+    let retry = register("retry-1", &body).await.unwrap();
+    assert_eq!(retry.status(), 201);
+    assert_eq!(retry.headers()["idempotent-replayed"], "true");
+    assert_eq!(retry.text().await.unwrap(), first_body);
+
+    // The tokens it carries aren't stored in the clear
+    let stored: Vec<u8> = sqlx::query_scalar("SELECT body FROM idempotency_keys")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let tokens: serde_json::Value = serde_json::from_str(&first_body).unwrap();
+    let access_token = tokens["access_token"].as_str().unwrap().as_bytes();
+    assert!(!stored.windows(access_token.len()).any(|w| w == access_token));
+
+    let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE email = $1")
+        .bind("retry@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(users, 1);
+    let keys: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM idempotency_keys")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(keys, 1);
+
+    // The same key for another request is refused; a new key runs it again
+    // (and finds the email taken)
+    let other = serde_json::json!({
+        "name": "Other User",
+        "email": "other@example.com",
+        "password": TEST_PASSWORD,
+    });
+    assert_eq!(register("retry-1", &other).await.unwrap().status(), 400);
+    assert_eq!(register("retry-2", &body).await.unwrap().status(), 409);
 
     app.cleanup().await;
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_register_invalid_email() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     let response = app
         .post_json(
@@ -97,13 +470,99 @@ async fn test_register_invalid_email() {
 
     assert_eq!(response.status(), 400, "Expected 400 Bad Request");
 
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+    assert!(
+        body["error"]["fields"]["email"].is_array(),
+        "Expected field-keyed email error, got {}",
+        body
+    );
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_validation_error_localized_to_spanish() {
+    let app = TestApp::spawn_isolated().await;
+
+    let response = app
+        .client
+        .post(format!("{}/api/auth/register", app.address))
+        .header("accept-language", "es-ES,es;q=0.9,en;q=0.5")
+        .json(&serde_json::json!({
+            "name": "Test User",
+            "email": "user@example.com",
+            "password": "short"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+    assert_eq!(response.headers()["content-language"], "es");
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR", "Code must not be translated");
+    assert_eq!(body["error"]["message"], "Los datos enviados no son válidos");
+    assert_eq!(
+        body["error"]["fields"]["password"][0],
+        "La contraseña debe tener al menos 8 caracteres"
+    );
+
+    // Without Accept-Language the English message is kept
+    let response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "user@example.com",
+                "password": "short"
+            }),
+        )
+        .await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["message"], "Password does not meet the password policy");
+    assert_eq!(body["error"]["password_policy"]["failed"], serde_json::json!(["min_length"]));
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_register_reports_every_invalid_field() {
+    let app = TestApp::spawn_isolated().await;
+
+    let response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "invalid-email",
+                "password": "short"
+            }),
+        )
+        .await;
+
+    assert_eq!(response.status(), 400);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+    assert_eq!(
+        body["error"]["fields"],
+        serde_json::json!({
+            "email": ["email"],
+            "password": ["Password must be at least 8 characters"]
+        })
+    );
+    assert_eq!(body["error"]["password_policy"]["failed"], serde_json::json!(["min_length"]));
+
     app.cleanup().await;
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_register_weak_password() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     let response = app
         .post_json(
@@ -122,9 +581,86 @@ async fn test_register_weak_password() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
+async fn test_register_rejects_denied_password() {
+    let denylist = std::env::temp_dir().join("multitenant-password-denylist.txt");
+    std::fs::write(&denylist, "# common passwords\ncorrecthorse\n").unwrap();
+    let app = TestApp::spawn_isolated_with(|config| {
+        config.security.password_denylist_path = Some(denylist.display().to_string());
+        config.security.password_max_length = Some(12);
+    })
+    .await;
+
+    let register = |password: &str| {
+        let body = serde_json::json!({
+            "name": "Test User",
+            "email": "common@example.com",
+            "password": password
+        });
+        let app = &app;
+        async move { app.post_json("/api/auth/register", &body).await }
+    };
+
+    let response = register("CorrectHorse").await;
+    assert_eq!(response.status(), 400);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert_eq!(body["error"]["password_policy"]["failed"], serde_json::json!(["not_common"]));
+    assert_eq!(body["error"]["password_policy"]["policy"]["reject_common"], true);
+    assert_eq!(body["error"]["fields"]["password"][0], "Password is too common");
+
+    let response = register("correct-horse-staple").await;
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert_eq!(body["error"]["password_policy"]["failed"], serde_json::json!(["max_length"]));
+
+    assert_eq!(register("CorrectHorse1").await.status(), 400);
+    assert_eq!(register("CorrectHors3").await.status(), 201);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_oversized_password_rejected() {
+    let app = TestApp::spawn_isolated().await;
+    let oversized = "a".repeat(100 * 1024);
+
+    let response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "test@example.com",
+                "password": oversized
+            }),
+        )
+        .await;
+
+    assert_eq!(response.status(), 400, "Expected 400 Bad Request");
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body["error"]["message"],
+        "Validation error: Password must be at most 256 characters"
+    );
+
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({
+                "email": "test@example.com",
+                "password": oversized
+            }),
+        )
+        .await;
+
+    assert_eq!(response.status(), 400, "Expected 400 Bad Request");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
 async fn test_login_success() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     // First, register a user
     let register_response = app
@@ -161,9 +697,74 @@ async fn test_login_success() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
+async fn test_login_returns_absolute_expiry() {
+    use multitenant::moduls::auth::domain::TokenPair;
+
+    let app = TestApp::spawn_with_seed(&[SeedUser::new("expiry@example.com")]).await;
+
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({ "email": "expiry@example.com", "password": TEST_PASSWORD }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+
+    let expires_at = |field: &str| {
+        chrono::DateTime::parse_from_rfc3339(body[field].as_str().expect("RFC 3339 timestamp"))
+            .expect("Invalid timestamp")
+            .timestamp()
+    };
+    let jwt = &app.state.config.jwt;
+    let access = TokenPair::decode(body["access_token"].as_str().unwrap(), &app.state.jwt_keys).unwrap();
+    let refresh = TokenPair::decode(body["refresh_token"].as_str().unwrap(), &app.state.jwt_keys).unwrap();
+    assert_eq!(expires_at("access_expires_at"), access.iat + jwt.access_expiry as i64);
+    assert_eq!(expires_at("refresh_expires_at"), refresh.iat + jwt.refresh_expiry as i64);
+    assert_eq!(body["expires_in"], jwt.access_expiry);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_login_normalizes_email_but_not_password() {
+    let app = TestApp::spawn_isolated().await;
+
+    let response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "padded@example.com",
+                "password": "  SecurePassword123!"
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), 201);
+
+    let login = |email: &str, password: &str| {
+        let body = serde_json::json!({ "email": email, "password": password });
+        let app = &app;
+        async move { app.post_json("/api/auth/login", &body).await.status() }
+    };
+
+    assert_eq!(login(" padded@example.com ", "  SecurePassword123!").await, 200);
+    assert_eq!(login("\u{200B}padded@example.com", "  SecurePassword123!").await, 200);
+    assert_eq!(
+        login("padded@example.com", "SecurePassword123!").await,
+        401,
+        "Leading spaces are part of the password"
+    );
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
 async fn test_login_invalid_email() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     let response = app
         .post_json(
@@ -181,9 +782,9 @@ async fn test_login_invalid_email() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_login_invalid_password() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     // Register a user
     let register_response = app
@@ -216,9 +817,9 @@ async fn test_login_invalid_password() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_refresh_token() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     // Register a user
     let register_response = app
@@ -267,9 +868,9 @@ async fn test_refresh_token() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_refresh_token_invalid() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     let response = app
         .post_json(
@@ -286,43 +887,1846 @@ async fn test_refresh_token_invalid() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
-async fn test_logout_success() {
-    let app = TestApp::spawn().await;
+#[ignore = "integration test requires database"]
+async fn test_refresh_token_replay_revokes_family() {
+    let app = TestApp::spawn_isolated().await;
 
-    // Register and login
-    let register_response = app
+    let response = app
         .post_json(
             "/api/auth/register",
             &serde_json::json!({
                 "name": "Test User",
-                "email": "logout@example.com",
+                "email": "replay@example.com",
                 "password": "SecurePassword123!"
             }),
         )
         .await;
+    assert_eq!(response.status(), 201);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let stolen = body["refresh_token"].as_str().unwrap().to_string();
+
+    let refresh = |token: String| {
+        let app = &app;
+        async move {
+            app.post_json("/api/auth/refresh", &serde_json::json!({ "refresh_token": token }))
+                .await
+        }
+    };
+
+    // The legitimate client rotates its token
+    let response = refresh(stolen.clone()).await;
+    assert_eq!(response.status(), 200);
+    let current: serde_json::Value = response.json().await.unwrap();
 
-    assert_eq!(register_response.status(), 201);
+    // The attacker replays the rotated one
+    assert_eq!(refresh(stolen).await.status(), 401);
 
-    let register_body: serde_json::Value = register_response
-        .json()
-        .await
-        .expect("Failed to parse response");
+    // The whole chain is gone: current refresh and access tokens included
+    let response = refresh(current["refresh_token"].as_str().unwrap().to_string()).await;
+    assert_eq!(response.status(), 401, "Current refresh token should be revoked");
+    let response = app
+        .authed_get("/api/auth/me", current["access_token"].as_str().unwrap())
+        .await;
+    assert_eq!(response.status(), 401, "Current access token should be revoked");
 
-    let access_token = register_body["access_token"]
-        .as_str()
-        .expect("access_token should be a string");
+    app.cleanup().await;
+}
 
-    // Logout
-    let logout_response = app
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_concurrent_refresh_revokes_family() {
+    let app = TestApp::spawn_isolated().await;
+
+    let response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "race@example.com",
+                "password": "SecurePassword123!"
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), 201);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let request = serde_json::json!({ "refresh_token": body["refresh_token"] });
+
+    let (first, second, third) = tokio::join!(
+        app.post_json("/api/auth/refresh", &request),
+        app.post_json("/api/auth/refresh", &request),
+        app.post_json("/api/auth/refresh", &request),
+    );
+
+    // At most one rotation wins; any other is a reuse, which kills the
+    // winner's tokens along with the rest of the family
+    let winners: Vec<_> = [first, second, third]
+        .into_iter()
+        .filter(|response| response.status() == 200)
+        .collect();
+    assert!(winners.len() <= 1, "{} concurrent refreshes succeeded", winners.len());
+    if let Some(winner) = winners.into_iter().next() {
+        let current: serde_json::Value = winner.json().await.unwrap();
+        let response = app
+            .post_json(
+                "/api/auth/refresh",
+                &serde_json::json!({ "refresh_token": current["refresh_token"] }),
+            )
+            .await;
+        assert_eq!(response.status(), 401);
+    }
+
+    app.cleanup().await;
+}
+
+/// Value of the `refresh_token` cookie set by a response
+fn refresh_cookie(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .find_map(|v| v.strip_prefix("refresh_token="))
+        .and_then(|v| v.split(';').next())
+        .map(str::to_string)
+}
+
+async fn spawn_with_refresh_cookie() -> TestApp {
+    TestApp::spawn_isolated_with(|config| config.jwt.refresh_cookie = true).await
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_refresh_via_cookie_rotates_cookie() {
+    let app = spawn_with_refresh_cookie().await;
+    app.register_and_token("cookie@example.com").await;
+
+    let login_response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({
+                "email": "cookie@example.com",
+                "password": TEST_PASSWORD
+            }),
+        )
+        .await;
+    assert_eq!(login_response.status(), 200);
+    let login_cookie = refresh_cookie(&login_response).expect("login should set refresh cookie");
+    let set_cookie = login_response.headers()["set-cookie"].to_str().unwrap().to_string();
+    assert!(set_cookie.contains("HttpOnly"), "{}", set_cookie);
+    assert!(set_cookie.contains("Path=/api/auth"), "{}", set_cookie);
+
+    // No body: the client's cookie jar supplies the token
+    let refresh_response = app
         .client
-        .post(&format!("{}/api/auth/logout", app.address))
-        .bearer_auth(access_token)
+        .post(format!("{}/api/auth/refresh", app.address))
         .send()
         .await
-        .expect("Failed to execute logout request");
+        .unwrap();
 
-    assert_eq!(logout_response.status(), 204, "Expected 204 No Content");
+    assert_eq!(refresh_response.status(), 200, "Expected 200 OK");
+    let rotated = refresh_cookie(&refresh_response).expect("refresh should rotate the cookie");
+    assert_ne!(rotated, login_cookie);
+    let body: serde_json::Value = refresh_response.json().await.unwrap();
+    assert_eq!(body["refresh_token"], rotated.as_str());
+
+    // The previous cookie value was revoked by rotation
+    let replay = reqwest::Client::new()
+        .post(format!("{}/api/auth/refresh", app.address))
+        .header("cookie", format!("refresh_token={}", login_cookie))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(replay.status(), 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_logout_clears_refresh_cookie() {
+    let app = spawn_with_refresh_cookie().await;
+    app.register_and_token("logout-cookie@example.com").await;
+
+    let login_response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({
+                "email": "logout-cookie@example.com",
+                "password": TEST_PASSWORD
+            }),
+        )
+        .await;
+    assert_eq!(login_response.status(), 200);
+    assert!(refresh_cookie(&login_response).is_some());
+    let login_body: serde_json::Value = login_response.json().await.unwrap();
+
+    // The jar's cookie is read back by the refresh endpoint
+    let refresh_response = app
+        .client
+        .post(format!("{}/api/auth/refresh", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(refresh_response.status(), 200);
+    let refreshed: serde_json::Value = refresh_response.json().await.unwrap();
+    assert_ne!(refreshed["access_token"], login_body["access_token"]);
+
+    let response = app
+        .authed_post_json(
+            "/api/auth/logout",
+            refreshed["access_token"].as_str().unwrap(),
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(response.status(), 204);
+    let set_cookie = response.headers()["set-cookie"].to_str().unwrap().to_string();
+    assert!(set_cookie.starts_with("refresh_token=;"), "{}", set_cookie);
+    assert!(set_cookie.contains("Max-Age=0"), "{}", set_cookie);
+    assert!(set_cookie.contains("Path=/api/auth"), "{}", set_cookie);
+
+    // The jar dropped the cookie, so there is nothing left to refresh with
+    let response = app
+        .client
+        .post(format!("{}/api/auth/refresh", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_refresh_via_body_with_cookies_enabled() {
+    let app = spawn_with_refresh_cookie().await;
+
+    let register_response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "body@example.com",
+                "password": TEST_PASSWORD
+            }),
+        )
+        .await;
+    assert_eq!(register_response.status(), 201);
+    let register_body: serde_json::Value = register_response.json().await.unwrap();
+
+    // A non-browser client without a cookie jar sends the token in the body
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/auth/refresh", app.address))
+        .json(&serde_json::json!({
+            "refresh_token": register_body["refresh_token"]
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200, "Expected 200 OK");
+    assert!(refresh_cookie(&response).is_some());
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_refresh_cookie_ignored_when_disabled() {
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("nocookie@example.com").await;
+
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({
+                "email": "nocookie@example.com",
+                "password": TEST_PASSWORD
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    assert!(refresh_cookie(&response).is_none());
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/auth/refresh", app.address))
+        .header("cookie", "refresh_token=anything")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_minimal_claims_tokens_authenticate() {
+    let app = TestApp::spawn_isolated_with(|config| config.jwt.minimal_claims = true).await;
+
+    let token = app.register_and_token("minimal@example.com").await;
+    let response = app.authed_get("/api/auth/me", &token).await;
+
+    assert_eq!(response.status(), 200, "Expected 200 OK");
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["user"]["email"], "minimal@example.com");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_logout_success() {
+    let app = TestApp::spawn_isolated().await;
+
+    // Register and login
+    let register_response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "logout@example.com",
+                "password": "SecurePassword123!"
+            }),
+        )
+        .await;
+
+    assert_eq!(register_response.status(), 201);
+
+    let register_body: serde_json::Value = register_response
+        .json()
+        .await
+        .expect("Failed to parse response");
+
+    let access_token = register_body["access_token"]
+        .as_str()
+        .expect("access_token should be a string");
+
+    // Logout
+    let logout_response = app
+        .client
+        .post(format!("{}/api/auth/logout", app.address))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .expect("Failed to execute logout request");
+
+    assert_eq!(logout_response.status(), 204, "Expected 204 No Content");
+
+    // The token was revoked by the logout
+    let response = app.authed_get("/api/auth/me", access_token).await;
+    assert_eq!(response.status(), 401, "Token should be rejected after logout");
+
+    // And so was the refresh token
+    let response = app
+        .post_json(
+            "/api/auth/refresh",
+            &serde_json::json!({ "refresh_token": register_body["refresh_token"] }),
+        )
+        .await;
+    assert_eq!(response.status(), 401, "Refresh token should be rejected after logout");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_logout_applies_at_once_with_revocation_cache() {
+    let app = TestApp::spawn_isolated_with(|config| config.jwt.revocation_cache = true).await;
+    let token = app.register_and_token("cached@example.com").await;
+
+    // Cached by the first request
+    for _ in 0..2 {
+        assert_eq!(app.authed_get("/api/auth/me", &token).await.status(), 200);
+    }
+
+    let response = app
+        .client
+        .post(format!("{}/api/auth/logout", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+
+    let response = app.authed_get("/api/auth/me", &token).await;
+    assert_eq!(response.status(), 401, "Logout must evict the cached token");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_logout_without_credential_strict_by_default() {
+    let app = TestApp::spawn_isolated().await;
+
+    let response = app
+        .client
+        .post(format!("{}/api/auth/logout", app.address))
+        .send()
+        .await
+        .expect("Failed to execute logout request");
+
+    assert_eq!(response.status(), 401, "Expected 401 Unauthorized");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_logout_without_credential_lenient() {
+    let app = TestApp::spawn_isolated_with(|config| config.security.lenient_logout = true).await;
+
+    let response = app
+        .client
+        .post(format!("{}/api/auth/logout", app.address))
+        .send()
+        .await
+        .expect("Failed to execute logout request");
+
+    assert_eq!(response.status(), 204, "Expected 204 No Content");
+
+    // A credential that is presented must still be valid
+    let response = app
+        .client
+        .post(format!("{}/api/auth/logout", app.address))
+        .bearer_auth("not-a-jwt")
+        .send()
+        .await
+        .expect("Failed to execute logout request");
+
+    assert_eq!(response.status(), 401, "Expected 401 Unauthorized");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_optional_authenticated_user_with_valid_token() {
+    use axum::extract::FromRequestParts;
+
+    let app = TestApp::spawn_isolated().await;
+
+    let register_response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "optional@example.com",
+                "password": "SecurePassword123!"
+            }),
+        )
+        .await;
+
+    assert_eq!(register_response.status(), 201);
+
+    let register_body: serde_json::Value = register_response
+        .json()
+        .await
+        .expect("Failed to parse response");
+
+    let access_token = register_body["access_token"]
+        .as_str()
+        .expect("access_token should be a string");
+
+    let (mut parts, _) = axum::http::Request::builder()
+        .uri("/")
+        .header("Authorization", format!("Bearer {}", access_token))
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    let OptionalAuthenticatedUser(user) =
+        OptionalAuthenticatedUser::from_request_parts(&mut parts, &app.state)
+            .await
+            .unwrap();
+
+    let user = user.expect("valid token should yield an authenticated user");
+    assert_eq!(user.user_id.to_string(), register_body["user"]["id"].as_str().unwrap());
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_me_reports_failed_login_activity() {
+    let app = TestApp::spawn_with_seed(&[SeedUser::new("activity@example.com")]).await;
+
+    // Two failed attempts
+    for _ in 0..2 {
+        let response = app
+            .post_json(
+                "/api/auth/login",
+                &serde_json::json!({
+                    "email": "activity@example.com",
+                    "password": "WrongPassword123!"
+                }),
+            )
+            .await;
+
+        assert_eq!(response.status(), 401);
+    }
+
+    // Successful login
+    let access_token = app.login_token("activity@example.com", TEST_PASSWORD).await;
+
+    let me_response = app.authed_get("/api/auth/me", &access_token).await;
+
+    assert_eq!(me_response.status(), 200, "Expected 200 OK");
+
+    let body: serde_json::Value = me_response.json().await.expect("Failed to parse response");
+    assert_eq!(body["user"]["email"], "activity@example.com");
+    assert_eq!(body["security"]["recent_failed_logins"], 2);
+    assert!(body["security"]["last_failed_at"].is_string());
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_me_returns_current_user() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("me@example.com").await;
+
+    let response = app.authed_get("/api/auth/me", &token).await;
+
+    assert_eq!(response.status(), 200, "Expected 200 OK");
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["user"]["email"], "me@example.com");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_me_after_user_deleted() {
+    use multitenant::moduls::auth::domain::TokenPair;
+    use multitenant::moduls::auth::infra::UserRepository;
+
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("gone@example.com").await;
+    let claims = TokenPair::decode(&token, &app.state.jwt_keys).unwrap();
+    let user_id = claims.sub.parse::<UserId>().unwrap();
+
+    app.state.user_repo.delete(user_id).await.unwrap();
+
+    // Deleting the user cascades to its tokens, so the token is rejected
+    // before `me` could report the user missing
+    let response = app.authed_get("/api/auth/me", &token).await;
+    assert_eq!(response.status(), 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_auth_events_are_audited() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("audit@example.com").await;
+
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({ "email": "audit@example.com", "password": "WrongPassword123!" }),
+        )
+        .await;
+    assert_eq!(response.status(), 401);
+    let response = app
+        .client
+        .post(format!("{}/api/auth/logout", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+
+    let actions: Vec<String> =
+        sqlx::query_scalar("SELECT action FROM audit_events ORDER BY occurred_at")
+            .fetch_all(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(actions, vec!["login_failed", "logout"]);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_me_requires_authentication() {
+    let app = TestApp::spawn_isolated().await;
+
+    let response = app.get("/api/auth/me").await;
+
+    assert_eq!(response.status(), 401, "Expected 401 Unauthorized");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_token_watermark_invalidates_older_tokens() {
+    let app = TestApp::spawn_isolated().await;
+    let old_token = app.register_and_token("watermark@example.com").await;
+
+    assert_eq!(app.authed_get("/api/auth/me", &old_token).await.status(), 200);
+
+    // Tokens carry a whole-second `iat`; make sure the watermark lands after it
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    app.state
+        .token_watermark
+        .invalidate_before(chrono::Utc::now())
+        .await
+        .expect("Failed to move watermark");
+
+    let response = app.authed_get("/api/auth/me", &old_token).await;
+    assert_eq!(response.status(), 401, "Token issued before watermark should be rejected");
+
+    let new_token = app.login_token("watermark@example.com", TEST_PASSWORD).await;
+    let response = app.authed_get("/api/auth/me", &new_token).await;
+    assert_eq!(response.status(), 200, "Token issued after watermark should work");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_user_token_watermark_only_invalidates_that_user() {
+    let app = TestApp::spawn_isolated().await;
+    let alice_token = app.register_and_token("alice@example.com").await;
+    let bob_token = app.register_and_token("bob@example.com").await;
+
+    let alice_body: serde_json::Value = app
+        .authed_get("/api/auth/me", &alice_token)
+        .await
+        .json()
+        .await
+        .expect("Failed to parse response");
+    let alice_id = alice_body["user"]["id"]
+        .as_str()
+        .and_then(|id| id.parse::<UserId>().ok())
+        .expect("user id should be a UUID");
+
+    // Tokens carry a whole-second `iat`; make sure the watermark lands after it
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    app.state
+        .token_watermark
+        .invalidate_user_tokens(alice_id)
+        .await
+        .expect("Failed to move user watermark");
+
+    assert_eq!(app.authed_get("/api/auth/me", &alice_token).await.status(), 401);
+    assert_eq!(app.authed_get("/api/auth/me", &bob_token).await.status(), 200);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_startup_detects_jwt_secret_change() {
+    use multitenant::bootstrap::jwt_secret::{check_jwt_secret, JwtSecretStatus};
+
+    let app = TestApp::spawn_isolated().await;
+    let secret = app.state.jwt_secret.clone();
+
+    let status = check_jwt_secret(&app.db, &secret).await.unwrap();
+    assert_eq!(status, JwtSecretStatus::Recorded);
+
+    let status = check_jwt_secret(&app.db, &secret).await.unwrap();
+    assert_eq!(status, JwtSecretStatus::Unchanged);
+
+    let status = check_jwt_secret(&app.db, "rotated_jwt_secret_key_minimum_32_characters")
+        .await
+        .unwrap();
+    assert_eq!(status, JwtSecretStatus::Changed);
+
+    // The new secret is now the baseline
+    let status = check_jwt_secret(&app.db, "rotated_jwt_secret_key_minimum_32_characters")
+        .await
+        .unwrap();
+    assert_eq!(status, JwtSecretStatus::Unchanged);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_rs256_tokens_and_key_rotation() {
+    use multitenant::moduls::auth::domain::{JwtKeys, TokenPair};
+
+    let fixture = |name: &str| format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let key_a = JwtKeys::rsa(
+        &std::fs::read(fixture("jwt_rsa_a.pem")).unwrap(),
+        &std::fs::read(fixture("jwt_rsa_a.pub.pem")).unwrap(),
+    )
+    .unwrap();
+
+    // Signing with key B while key A is retired but still accepted
+    let app = TestApp::spawn_isolated_with(|c| {
+        c.jwt.algorithm = jsonwebtoken::Algorithm::RS256;
+        c.jwt.private_key_path = Some(fixture("jwt_rsa_b.pem"));
+        c.jwt.public_key_paths = vec![fixture("jwt_rsa_b.pub.pem"), fixture("jwt_rsa_a.pub.pem")];
+    })
+    .await;
+
+    let token = app.register_and_token("rs256@example.com").await;
+    let header = jsonwebtoken::decode_header(&token).unwrap();
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::RS256);
+    assert_eq!(header.kid.as_deref(), app.state.jwt_keys.kid());
+    assert_eq!(app.authed_get("/api/auth/me", &token).await.status(), 200);
+
+    // A token issued with key A before the rotation still works
+    let claims = TokenPair::decode(&token, &app.state.jwt_keys).unwrap();
+    let user_id = claims.sub.parse::<UserId>().unwrap();
+    let (old_pair, old_access, _) = TokenPair::generate(user_id, &key_a, 900, 3600).unwrap();
+    app.state.token_repo.save(&old_access).await.unwrap();
+    assert_eq!(app.authed_get("/api/auth/me", &old_pair.access_token).await.status(), 200);
+
+    // HS256 tokens are not accepted once RS256 is configured
+    let hs256 = JwtKeys::hmac(app.state.jwt_secret.clone());
+    let (hs_pair, hs_access, _) = TokenPair::generate(user_id, &hs256, 900, 3600).unwrap();
+    app.state.token_repo.save(&hs_access).await.unwrap();
+    assert_eq!(app.authed_get("/api/auth/me", &hs_pair.access_token).await.status(), 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_jwks_publishes_active_rsa_keys() {
+    let fixture = |name: &str| format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let app = TestApp::spawn_isolated_with(|c| {
+        c.jwt.algorithm = jsonwebtoken::Algorithm::RS256;
+        c.jwt.private_key_path = Some(fixture("jwt_rsa_b.pem"));
+        c.jwt.public_key_paths = vec![fixture("jwt_rsa_b.pub.pem"), fixture("jwt_rsa_a.pub.pem")];
+    })
+    .await;
+
+    let response = app.get("/.well-known/jwks.json").await;
+    assert_eq!(response.status(), 200);
+    let jwks: jsonwebtoken::jwk::JwkSet = response.json().await.unwrap();
+
+    // Current and retired key, each usable to verify what it signed
+    assert_eq!(jwks.keys.len(), 2);
+    let token = app.register_and_token("jwks@example.com").await;
+    let kid = jsonwebtoken::decode_header(&token).unwrap().kid.unwrap();
+    let jwk = jwks.find(&kid).expect("signing key is published");
+    assert_eq!(jwk.common.public_key_use, Some(jsonwebtoken::jwk::PublicKeyUse::Signature));
+
+    let key = jsonwebtoken::DecodingKey::from_jwk(jwk).unwrap();
+    let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::RS256);
+    validation.validate_aud = false;
+    jsonwebtoken::decode::<serde_json::Value>(&token, &key, &validation).unwrap();
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_slow_request_body_is_aborted() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let app = TestApp::spawn_isolated_with(|config| config.server.body_read_timeout = 1).await;
+    let addr = app.address.trim_start_matches("http://");
+
+    // Announce 100 bytes but only send the first few, then stall
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            b"POST /api/auth/login HTTP/1.1\r\n\
+              Host: localhost\r\n\
+              Content-Type: application/json\r\n\
+              Content-Length: 100\r\n\r\n\
+              {\"email\":",
+        )
+        .await
+        .unwrap();
+
+    let mut response = vec![0u8; 1024];
+    let read = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        stream.read(&mut response),
+    )
+    .await
+    .expect("server should answer before the client gives up")
+    .unwrap();
+
+    let response = String::from_utf8_lossy(&response[..read]);
+    assert!(response.starts_with("HTTP/1.1 408"), "got: {}", response);
+    assert!(response.contains("REQUEST_TIMEOUT"));
+
+    app.cleanup().await;
+}
+
+/// Store a reset token for `email` directly (the plain token is never
+/// persisted, so it can't be read back from the forgot-password flow)
+async fn insert_reset_token(app: &TestApp, email: &str, ttl_seconds: i64) -> String {
+    use multitenant::moduls::auth::domain::PasswordResetToken;
+
+    let user_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let (token, plain) = PasswordResetToken::issue(user_id, ttl_seconds);
+    sqlx::query(
+        "INSERT INTO password_reset_tokens (id, user_id, token_hash, expires_at) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(token.id)
+    .bind(token.user_id)
+    .bind(&token.token_hash)
+    .bind(token.expires_at)
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    plain
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_forgot_password_does_not_reveal_accounts() {
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("forgot@example.com").await;
+
+    let known = app
+        .post_json("/api/auth/forgot-password", &serde_json::json!({ "email": "forgot@example.com" }))
+        .await;
+    let unknown = app
+        .post_json("/api/auth/forgot-password", &serde_json::json!({ "email": "nobody@example.com" }))
+        .await;
+
+    assert_eq!(known.status(), 200);
+    assert_eq!(unknown.status(), 200);
+    let known: serde_json::Value = known.json().await.unwrap();
+    let unknown: serde_json::Value = unknown.json().await.unwrap();
+    assert_eq!(known, unknown);
+
+    let stored: Vec<String> = sqlx::query_scalar("SELECT token_hash FROM password_reset_tokens")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 1, "Only the existing account gets a token");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_reset_password_flow() {
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("reset@example.com").await;
+    let token = insert_reset_token(&app, "reset@example.com", 1800).await;
+    let body = serde_json::json!({ "token": token, "new_password": "BrandNewPassword456!" });
+
+    let response = app.post_json("/api/auth/reset-password", &body).await;
+    assert_eq!(response.status(), 200);
+
+    // Existing tokens are revoked; only the new password works
+    let response = app.authed_get("/api/auth/me", &access_token).await;
+    assert_eq!(response.status(), 401);
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({ "email": "reset@example.com", "password": TEST_PASSWORD }),
+        )
+        .await;
+    assert_eq!(response.status(), 401);
+    app.login_token("reset@example.com", "BrandNewPassword456!").await;
+
+    // The token is single-use
+    let response = app.post_json("/api/auth/reset-password", &body).await;
+    assert_eq!(response.status(), 400);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_reset_password_rejects_recent_password() {
+    let app = TestApp::spawn_isolated_with(|config| config.security.password_history_size = 3).await;
+    app.register_and_token("history@example.com").await;
+    let reset = |token: String, password: &str| {
+        let body = serde_json::json!({ "token": token, "new_password": password });
+        let app = &app;
+        async move { app.post_json("/api/auth/reset-password", &body).await }
+    };
+
+    // First-ever change: only the current password is refused
+    let token = insert_reset_token(&app, "history@example.com", 1800).await;
+    let response = reset(token.clone(), TEST_PASSWORD).await;
+    assert_eq!(response.status(), 400);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert_eq!(body["error"]["password_policy"]["failed"], serde_json::json!(["not_reused"]));
+    assert_eq!(body["error"]["password_policy"]["policy"]["history"], 3);
+    assert_eq!(reset(token, "BrandNewPassword456!").await.status(), 200);
+
+    // The replaced password is remembered
+    let token = insert_reset_token(&app, "history@example.com", 1800).await;
+    assert_eq!(reset(token.clone(), TEST_PASSWORD).await.status(), 400);
+    assert_eq!(reset(token, "AnotherPassword789!").await.status(), 200);
+    app.login_token("history@example.com", "AnotherPassword789!").await;
+
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM password_history")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(stored, 2);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_reset_password_rejects_expired_token() {
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("expired-reset@example.com").await;
+    let token = insert_reset_token(&app, "expired-reset@example.com", -1).await;
+
+    let response = app
+        .post_json(
+            "/api/auth/reset-password",
+            &serde_json::json!({ "token": token, "new_password": "BrandNewPassword456!" }),
+        )
+        .await;
+
+    assert_eq!(response.status(), 400);
+    app.login_token("expired-reset@example.com", TEST_PASSWORD).await;
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_email_verification_flow() {
+    use multitenant::moduls::auth::domain::EmailVerificationToken;
+
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("verify@example.com").await;
+
+    let response = app
+        .client
+        .post(format!("{}/api/auth/send-verification", app.address))
+        .bearer_auth(&access_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_verification_tokens")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(stored, 1);
+
+    // The plain token is only sent to the user, so store a known one
+    let user_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("verify@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let (token, plain) = EmailVerificationToken::issue(user_id, 86400);
+    sqlx::query(
+        "INSERT INTO email_verification_tokens (id, user_id, token_hash, expires_at) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(token.id)
+    .bind(token.user_id)
+    .bind(&token.token_hash)
+    .bind(token.expires_at)
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let path = format!("/api/auth/verify-email?token={}", plain);
+    let response = app.get(&path).await;
+    assert_eq!(response.status(), 200);
+
+    let response = app.authed_get("/api/auth/me", &access_token).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["user"]["email_verified"], true);
+
+    // Tokens are single-use, and verified users can't request more
+    let response = app.get(&path).await;
+    assert_eq!(response.status(), 400);
+    let response = app
+        .client
+        .post(format!("{}/api/auth/send-verification", app.address))
+        .bearer_auth(&access_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 409);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_verified_recovery_email_receives_password_resets() {
+    use multitenant::moduls::auth::domain::{Email, EmailVerificationToken};
+
+    // Two reset requests for the same account
+    let app = TestApp::spawn_isolated_with(|config| config.security.account_email_limit_per_email = 2).await;
+    let access_token = app.register_and_token("recover@example.com").await;
+    let reset_tokens = || async {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM password_reset_tokens")
+            .fetch_one(&app.db)
+            .await
+            .unwrap()
+    };
+    let forgot_via_recovery = serde_json::json!({
+        "email": "recover@example.com",
+        "deliver_to": "recovery",
+    });
+
+    // The login email can't double as the recovery email
+    let response = app
+        .authed_put_json(
+            "/api/auth/recovery-email",
+            &access_token,
+            &serde_json::json!({ "email": "recover@example.com" }),
+        )
+        .await;
+    assert_eq!(response.status(), 400);
+
+    let response = app
+        .authed_put_json(
+            "/api/auth/recovery-email",
+            &access_token,
+            &serde_json::json!({ "email": "backup@example.com" }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["recovery_email"], "backup@example.com");
+    assert_eq!(body["recovery_email_verified"], false);
+
+    // Unverified, it gets no reset links
+    let response = app.post_json("/api/auth/forgot-password", &forgot_via_recovery).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(reset_tokens().await, 0);
+
+    // The plain token is only sent to the user, so store a known one
+    let user_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("recover@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let (token, plain) = EmailVerificationToken::issue_for_recovery(
+        user_id,
+        Email::new("backup@example.com").unwrap(),
+        86400,
+    );
+    sqlx::query(
+        "INSERT INTO email_verification_tokens (id, user_id, token_hash, expires_at, recovery_email) VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(token.id)
+    .bind(token.user_id)
+    .bind(&token.token_hash)
+    .bind(token.expires_at)
+    .bind(&token.recovery_email)
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let response = app.get(&format!("/api/auth/verify-email?token={}", plain)).await;
+    assert_eq!(response.status(), 200);
+
+    let response = app.authed_get("/api/auth/me", &access_token).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["user"]["recovery_email_verified"], true);
+    assert_eq!(body["user"]["email_verified"], false);
+
+    let response = app.post_json("/api/auth/forgot-password", &forgot_via_recovery).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(reset_tokens().await, 1);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_send_verification_requires_authentication() {
+    let app = TestApp::spawn_isolated().await;
+
+    let response = app
+        .post_json("/api/auth/send-verification", &serde_json::json!({}))
+        .await;
+
+    assert_eq!(response.status(), 401);
+
+    app.cleanup().await;
+}
+
+/// Seed a user and a web session for it
+async fn seed_session(
+    app: &TestApp,
+    repo: &multitenant::moduls::auth::infra::PostgresSessionRepository,
+    email: &str,
+) -> multitenant::moduls::auth::domain::Session {
+    use multitenant::moduls::auth::domain::Session;
+    use multitenant::moduls::auth::infra::SessionRepository;
+
+    app.register_and_token(email).await;
+    let user_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    repo.save(&Session::new(user_id, Some("10.0.0.1".to_string()), None, 3600))
+        .await
+        .unwrap()
+}
+
+async fn session_row_exists(app: &TestApp, id: SessionId) -> bool {
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM sessions WHERE id = $1)")
+        .bind(id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_soft_deleted_session_is_retained_until_retention() {
+    use multitenant::moduls::auth::infra::{PostgresSessionRepository, SessionRepository};
+
+    let app = TestApp::spawn_isolated().await;
+    let repo = PostgresSessionRepository::new(app.db.clone().into()).with_soft_delete(3600);
+    let session = seed_session(&app, &repo, "soft-session@example.com").await;
+
+    repo.delete(session.id).await.unwrap();
+
+    // Logged out: no longer active, but kept for investigation
+    assert!(repo.find_by_id(session.id).await.unwrap().is_none());
+    assert!(repo.find_by_user_id(session.user_id).await.unwrap().is_none());
+    assert_eq!(repo.delete_expired().await.unwrap(), 0);
+    assert!(session_row_exists(&app, session.id).await);
+
+    // Purged once the retention window has passed
+    sqlx::query("UPDATE sessions SET revoked_at = NOW() - INTERVAL '2 hours' WHERE id = $1")
+        .bind(session.id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(repo.delete_expired().await.unwrap(), 1);
+    assert!(!session_row_exists(&app, session.id).await);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_sessions_are_hard_deleted_by_default() {
+    use multitenant::moduls::auth::infra::{PostgresSessionRepository, SessionRepository};
+
+    let app = TestApp::spawn_isolated().await;
+    let repo = PostgresSessionRepository::new(app.db.clone().into());
+    let session = seed_session(&app, &repo, "hard-session@example.com").await;
+
+    repo.delete(session.id).await.unwrap();
+
+    assert!(!session_row_exists(&app, session.id).await);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_repeated_forgot_password_sends_one_email() {
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("throttled@example.com").await;
+
+    for _ in 0..3 {
+        let response = app
+            .post_json(
+                "/api/auth/forgot-password",
+                &serde_json::json!({ "email": "throttled@example.com" }),
+            )
+            .await;
+        assert_eq!(response.status(), 200);
+    }
+
+    // One token (and so one email) per address within the window
+    let issued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM password_reset_tokens")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(issued, 1);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_resend_verification_is_generic_and_throttled() {
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("resend@example.com").await;
+
+    let mut bodies = Vec::new();
+    for email in ["resend@example.com", "resend@example.com", "nobody@example.com"] {
+        let response = app
+            .post_json("/api/auth/resend-verification", &serde_json::json!({ "email": email }))
+            .await;
+        assert_eq!(response.status(), 200);
+        bodies.push(response.json::<serde_json::Value>().await.unwrap());
+    }
+    assert!(bodies.iter().all(|body| *body == bodies[0]));
+
+    let issued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_verification_tokens")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(issued, 1);
+
+    app.cleanup().await;
+}
+
+/// TOTP code for a base32 secret, `offset_steps` steps from now
+fn totp_code(secret: &str, offset_steps: i64) -> String {
+    let bytes = totp_rs::Secret::Encoded(secret.to_string()).to_bytes().unwrap();
+    let totp = totp_rs::TOTP::new(totp_rs::Algorithm::SHA1, 6, 1, 30, bytes, None, String::new())
+        .unwrap();
+    totp.generate((chrono::Utc::now().timestamp() + offset_steps * 30) as u64)
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_totp_login_flow() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("totp@example.com").await;
+
+    // Enroll
+    let response = app
+        .authed_post_json("/api/auth/mfa/totp/enable", &token, &serde_json::json!({}))
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let secret = body["secret"].as_str().unwrap().to_string();
+    assert!(body["otpauth_uri"].as_str().unwrap().starts_with("otpauth://totp/"));
+
+    // The previous step is within the tolerated drift
+    let response = app
+        .authed_post_json(
+            "/api/auth/mfa/totp/confirm",
+            &token,
+            &serde_json::json!({ "code": totp_code(&secret, -1) }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+
+    // Password alone now yields a challenge instead of tokens
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({ "email": "totp@example.com", "password": TEST_PASSWORD }),
+        )
+        .await;
+    assert_eq!(response.status(), 202);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["mfa_required"], true);
+    assert!(body.get("access_token").is_none());
+    let mfa_token = body["mfa_token"].as_str().unwrap().to_string();
+
+    let current = totp_code(&secret, 0);
+    let wrong = if current == "000000" { "111111" } else { "000000" };
+    let response = app
+        .post_json(
+            "/api/auth/mfa/verify",
+            &serde_json::json!({ "mfa_token": mfa_token, "code": wrong }),
+        )
+        .await;
+    assert_eq!(response.status(), 401);
+
+    let response = app
+        .post_json(
+            "/api/auth/mfa/verify",
+            &serde_json::json!({ "mfa_token": mfa_token, "code": current }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["access_token"].is_string());
+    assert_eq!(body["user"]["two_factor_enabled"], true);
+
+    // The challenge is single-use
+    let response = app
+        .post_json(
+            "/api/auth/mfa/verify",
+            &serde_json::json!({ "mfa_token": mfa_token, "code": current }),
+        )
+        .await;
+    assert_eq!(response.status(), 401);
+
+    app.cleanup().await;
+}
+
+/// Grant `role` to the registered user `email`, bypassing the admin API
+async fn grant_role(app: &TestApp, email: &str, role: &str) -> UserId {
+    use multitenant::moduls::auth::domain::Role;
+    use multitenant::moduls::auth::infra::RoleRepository;
+
+    let user_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.state
+        .role_repo
+        .grant(user_id, &Role::new(role).unwrap())
+        .await
+        .unwrap();
+    user_id
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_admin_route_rejects_normal_user() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("normal@example.com").await;
+    let user_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("normal@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    let response = app
+        .authed_put_json(
+            &format!("/api/admin/users/{}/roles/admin", user_id),
+            &token,
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(response.status(), 403);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "AUTHORIZATION_ERROR");
+
+    // Without a token the route is unauthenticated, not forbidden
+    let response = app.delete(&format!("/api/admin/users/{}/roles/admin", user_id)).await;
+    assert_eq!(response.status(), 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_admin_grants_role_via_token_claim() {
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("admin@example.com").await;
+    app.register_and_token("member@example.com").await;
+    grant_role(&app, "admin@example.com", "admin").await;
+    let member_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("member@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    // A fresh login embeds the role in the token
+    let token = app.login_token("admin@example.com", TEST_PASSWORD).await;
+    let response = app
+        .authed_put_json(
+            &format!("/api/admin/users/{}/roles/admin", member_id),
+            &token,
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+
+    let roles: Vec<String> = sqlx::query_scalar(
+        "SELECT r.name FROM user_roles ur JOIN roles r ON r.id = ur.role_id WHERE ur.user_id = $1",
+    )
+    .bind(member_id)
+    .fetch_all(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(roles, vec!["admin".to_string()]);
+
+    // Unknown roles are rejected
+    let response = app
+        .authed_put_json(
+            &format!("/api/admin/users/{}/roles/superuser", member_id),
+            &token,
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(response.status(), 404);
+
+    // Admins can't hand out super_admin, not even to themselves
+    let response = app
+        .authed_put_json(
+            &format!("/api/admin/users/{}/roles/super_admin", member_id),
+            &token,
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(response.status(), 403);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_admin_role_falls_back_to_database() {
+    let app = TestApp::spawn_isolated().await;
+    // Registration tokens carry no roles claim
+    let token = app.register_and_token("late-admin@example.com").await;
+    let user_id = grant_role(&app, "late-admin@example.com", "admin").await;
+
+    let response = app
+        .authed_delete(&format!("/api/admin/users/{}/roles/admin", user_id), &token)
+        .await;
+
+    // Found to be admin, but admins can't drop their own admin role
+    assert_eq!(response.status(), 400);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_admin_impersonation_is_time_boxed() {
+    let app = TestApp::spawn_isolated_with(|config| config.jwt.impersonation_ttl = 600).await;
+    app.register_and_token("support@example.com").await;
+    app.register_and_token("customer@example.com").await;
+    app.register_and_token("other@example.com").await;
+    grant_role(&app, "support@example.com", "admin").await;
+    let other_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("other@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let admin_token = app.login_token("support@example.com", TEST_PASSWORD).await;
+
+    // Checked against the database, as the impersonation token has no roles
+    let customer_id = grant_role(&app, "customer@example.com", "admin").await;
+
+    let response = app
+        .authed_post_json(
+            &format!("/api/admin/users/{}/impersonate", customer_id),
+            &admin_token,
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["expires_in"], 600);
+    assert!(body.get("refresh_token").is_none());
+    let token = body["access_token"].as_str().unwrap();
+
+    // Acts as the customer
+    let response = app.authed_get("/api/auth/me", token).await;
+    assert_eq!(response.status(), 200);
+    let me: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(me["user"]["email"], "customer@example.com");
+
+    // Can't be refreshed
+    let response = app
+        .post_json("/api/auth/refresh", &serde_json::json!({ "refresh_token": token }))
+        .await;
+    assert_eq!(response.status(), 401);
+
+    // Can't start another impersonation, even as a user who is an admin
+    let response = app
+        .authed_post_json(
+            &format!("/api/admin/users/{}/impersonate", other_id),
+            token,
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(response.status(), 403);
+
+    // Can't leave a way back into the account behind
+    let response = app
+        .authed_post_json("/api/user/api-keys", token, &serde_json::json!({ "name": "backdoor" }))
+        .await;
+    assert_eq!(response.status(), 403);
+
+    app.cleanup().await;
+}
+
+async fn spawn_with_client_hashing() -> TestApp {
+    TestApp::spawn_isolated_with(|config| {
+        config.security.client_password_hashing = true;
+        config.security.client_hash_iterations = 1000;
+    })
+    .await
+}
+
+/// Fetch the password parameters of `email`
+async fn password_params(app: &TestApp, email: &str) -> serde_json::Value {
+    let response = app
+        .post_json("/api/auth/password-params", &serde_json::json!({ "email": email }))
+        .await;
+    assert_eq!(response.status(), 200);
+    response.json().await.expect("Failed to parse response")
+}
+
+fn client_hash_params(body: &serde_json::Value) -> ClientHashParams {
+    assert_eq!(body["mode"], "client_hash");
+    serde_json::from_value(body.clone()).expect("Invalid client hash parameters")
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_client_hash_register_login_round_trip() {
+    let app = spawn_with_client_hashing().await;
+    let params = client_hash_params(&password_params(&app, "hashed@example.com").await);
+    assert_eq!(params.iterations, 1000);
+    let client_hash = params.derive(TEST_PASSWORD);
+
+    let response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "hashed@example.com",
+                "password": client_hash,
+                "client_hash": params
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), 201);
+
+    // Same parameters once the account exists
+    let stored = client_hash_params(&password_params(&app, "hashed@example.com").await);
+    assert_eq!(stored, params);
+
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({
+                "email": "hashed@example.com",
+                "password": client_hash,
+                "client_hash": params
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert!(body["access_token"].is_string());
+
+    // The server derives the hash for clients sending the password as typed
+    app.login_token("hashed@example.com", TEST_PASSWORD).await;
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_client_hash_with_mismatched_params_rejected() {
+    let app = spawn_with_client_hashing().await;
+    let params = client_hash_params(&password_params(&app, "stale@example.com").await);
+    let stale = ClientHashParams::new(params.salt.clone(), params.iterations * 2);
+
+    let response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "stale@example.com",
+                "password": stale.derive(TEST_PASSWORD),
+                "client_hash": stale
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), 400);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert_eq!(body["error"]["code"], "CLIENT_HASH_MISMATCH");
+
+    app.register_and_token("stale@example.com").await;
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({
+                "email": "stale@example.com",
+                "password": stale.derive(TEST_PASSWORD),
+                "client_hash": stale
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), 400);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert_eq!(body["error"]["code"], "CLIENT_HASH_MISMATCH");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_plaintext_account_migrates_to_client_hash_at_login() {
+    let app = spawn_with_client_hashing().await;
+    // Created before client-side hashing was enabled
+    let email = Email::new("legacy@example.com").unwrap();
+    let user = User::new(email, TEST_PASSWORD, "Legacy User".to_string()).unwrap();
+    app.state.user_repo.save(&user).await.unwrap();
+
+    let body = password_params(&app, "legacy@example.com").await;
+    assert_eq!(body["mode"], "plaintext");
+
+    app.login_token("legacy@example.com", TEST_PASSWORD).await;
+
+    let params = client_hash_params(&password_params(&app, "legacy@example.com").await);
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({
+                "email": "legacy@example.com",
+                "password": params.derive(TEST_PASSWORD),
+                "client_hash": params
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_login_upgrades_bcrypt_hash_to_argon2() {
+    use multitenant::config::PasswordHashAlgorithm;
+
+    let app = TestApp::spawn_isolated_with(|config| {
+        config.security.password_hasher = PasswordHashAlgorithm::Argon2;
+    })
+    .await;
+    // Hashed with bcrypt before the switch
+    let email = Email::new("upgrade@example.com").unwrap();
+    let user = User::new(email, TEST_PASSWORD, "Test User".to_string()).unwrap();
+    app.state.user_repo.save(&user).await.unwrap();
+
+    app.login_token("upgrade@example.com", TEST_PASSWORD).await;
+
+    let hash: String = sqlx::query_scalar("SELECT password_hash FROM users WHERE id = $1")
+        .bind(user.id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert!(hash.starts_with("$argon2id$"), "Expected an argon2id hash, got {}", &hash[..7]);
+    app.login_token("upgrade@example.com", TEST_PASSWORD).await;
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_hash_migration_flags_outdated_hashes_until_reset() {
+    use multitenant::moduls::auth::domain::{PasswordHasher, Role};
+    use multitenant::moduls::auth::infra::RoleRepository;
+
+    let app = TestApp::spawn_isolated().await;
+    // Hashed with a lower bcrypt cost than the configured one, never logged in since
+    let email = Email::new("dormant@example.com").unwrap();
+    let hasher = PasswordHasher::Bcrypt { cost: 4 };
+    let dormant = User::with_hasher(email, TEST_PASSWORD, "Dormant".to_string(), &hasher).unwrap();
+    app.state.user_repo.save(&dormant).await.unwrap();
+
+    app.register_and_token("root@example.com").await;
+    let root_id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("root@example.com")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.state
+        .role_repo
+        .grant(root_id, &Role::new(Role::SUPER_ADMIN).unwrap())
+        .await
+        .unwrap();
+    let root_token = app.login_token("root@example.com", TEST_PASSWORD).await;
+
+    let response = app
+        .authed_post_json("/api/admin/password-hashes/migrations", &root_token, &serde_json::json!({}))
+        .await;
+    assert_eq!(response.status(), 202);
+    let mut progress = serde_json::Value::Null;
+    for _ in 0..50 {
+        let response = app.authed_get("/api/admin/password-hashes/migrations", &root_token).await;
+        assert_eq!(response.status(), 200);
+        progress = response.json().await.unwrap();
+        if progress["run"]["status"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(progress["run"]["status"], "completed");
+    assert_eq!(progress["run"]["scanned_users"], 2);
+    assert_eq!(progress["run"]["flagged_users"], 1);
+    assert_eq!(progress["pending_users"], 1);
+
+    // The right password no longer logs in; a wrong one still reads as wrong
+    let login = |password: &'static str| {
+        let app = &app;
+        async move {
+            app.post_json(
+                "/api/auth/login",
+                &serde_json::json!({ "email": "dormant@example.com", "password": password }),
+            )
+            .await
+        }
+    };
+    let response = login(TEST_PASSWORD).await;
+    assert_eq!(response.status(), 403);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "PASSWORD_CHANGE_REQUIRED");
+    assert_eq!(login("WrongPassword123!").await.status(), 401);
+
+    // Resetting the password clears the flag
+    let token = insert_reset_token(&app, "dormant@example.com", 1800).await;
+    let response = app
+        .post_json(
+            "/api/auth/reset-password",
+            &serde_json::json!({ "token": token, "new_password": "BrandNewPassword456!" }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    app.login_token("dormant@example.com", "BrandNewPassword456!").await;
+
+    let response = app.authed_get("/api/admin/password-hashes/migrations", &root_token).await;
+    let progress: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(progress["pending_users"], 0);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_revoked_access_token_fails_auth() {
+    let app = TestApp::spawn_with_seed(&[SeedUser::new("revoke@example.com")]).await;
+    let token = app.login_token("revoke@example.com", TEST_PASSWORD).await;
+    let other = app.login_token("revoke@example.com", TEST_PASSWORD).await;
+
+    let response = app
+        .authed_post_json(
+            "/api/auth/revoke",
+            &other,
+            &serde_json::json!({ "token": token, "token_type_hint": "access_token" }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+
+    let response = app.authed_get("/api/auth/me", &token).await;
+    assert_eq!(response.status(), 401, "Revoked token should be rejected");
+    // Only that token: the one used to revoke it still works
+    let response = app.authed_get("/api/auth/me", &other).await;
+    assert_eq!(response.status(), 200);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_revoke_unknown_token_returns_ok() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("unknown-revoke@example.com").await;
+
+    for unknown in ["not-a-jwt", "eyJhbGciOiJIUzI1NiJ9.e30.c2lnbmF0dXJl"] {
+        let response = app
+            .authed_post_json("/api/auth/revoke", &token, &serde_json::json!({ "token": unknown }))
+            .await;
+        assert_eq!(response.status(), 200);
+    }
+
+    // Revoking twice is fine too
+    let body = serde_json::json!({ "token": token });
+    let other = app.login_token("unknown-revoke@example.com", TEST_PASSWORD).await;
+    assert_eq!(app.authed_post_json("/api/auth/revoke", &other, &body).await.status(), 200);
+    assert_eq!(app.authed_post_json("/api/auth/revoke", &other, &body).await.status(), 200);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_revoke_other_users_token_forbidden() {
+    let app = TestApp::spawn_isolated().await;
+    let victim = app.register_and_token("victim@example.com").await;
+    let attacker = app.register_and_token("attacker@example.com").await;
+
+    let response = app
+        .authed_post_json("/api/auth/revoke", &attacker, &serde_json::json!({ "token": victim }))
+        .await;
+    assert_eq!(response.status(), 403);
+    assert_eq!(app.authed_get("/api/auth/me", &victim).await.status(), 200);
+
+    // Revoking requires a credential
+    let response = app.post_json("/api/auth/revoke", &serde_json::json!({ "token": victim })).await;
+    assert_eq!(response.status(), 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_login_is_rate_limited_per_ip() {
+    let app = TestApp::spawn_isolated_with(|config| config.security.rate_limit_per_minute = 3).await;
+    let login = |ip: &'static str| {
+        let app = &app;
+        async move {
+            app.client
+                .post(format!("{}/api/auth/login", app.address))
+                .header("X-Forwarded-For", ip)
+                .json(&serde_json::json!({ "email": "nobody@example.com", "password": "password123" }))
+                .send()
+                .await
+                .expect("Failed to send request")
+        }
+    };
+
+    for _ in 0..3 {
+        assert_eq!(login("203.0.113.7").await.status(), 401);
+    }
+    let response = login("203.0.113.7").await;
+    assert_eq!(response.status(), 429);
+    assert_eq!(response.headers()["retry-after"], "20");
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert_eq!(body["error"]["code"], "TOO_MANY_REQUESTS");
+
+    // Other clients and unthrottled endpoints are unaffected
+    assert_eq!(login("198.51.100.1").await.status(), 401);
+    let response = app
+        .post_json("/api/auth/password-params", &serde_json::json!({ "email": "nobody@example.com" }))
+        .await;
+    assert_ne!(response.status(), 429);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_dev_token_endpoint_is_not_mounted_by_default() {
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("dev-off@example.com").await;
+
+    let response = app
+        .post_json("/api/dev/token", &serde_json::json!({ "email": "dev-off@example.com" }))
+        .await;
+
+    assert_eq!(response.status(), 404);
+
+    app.cleanup().await;
+}
+
+#[cfg(feature = "dev-tools")]
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_dev_token_mints_tokens_in_dev_mode() {
+    let app = TestApp::spawn_isolated_with(|config| config.server.dev_mode = true).await;
+    app.register_and_token("dev-on@example.com").await;
+
+    let response = app
+        .post_json("/api/dev/token", &serde_json::json!({ "email": "dev-on@example.com" }))
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let token = body["access_token"].as_str().unwrap();
+    let response = app.authed_get("/api/auth/me", token).await;
+    assert_eq!(response.status(), 200);
+    let me: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(me["user"]["email"], "dev-on@example.com");
+
+    let response = app
+        .post_json("/api/dev/token", &serde_json::json!({ "email": "nobody@example.com" }))
+        .await;
+    assert_eq!(response.status(), 404);
 
     app.cleanup().await;
 }
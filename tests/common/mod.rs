@@ -1,3 +1,5 @@
 pub mod test_app;
 
 pub use test_app::TestApp;
+#[allow(unused_imports)]
+pub use test_app::{SeedUser, TEST_PASSWORD};
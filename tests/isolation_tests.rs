@@ -0,0 +1,68 @@
+mod common;
+
+use common::TestApp;
+
+/// Both tests register the same email on purpose: with a shared database
+/// one of them would hit a 409 Conflict when run in parallel.
+async fn register_same_user_and_count() {
+    let app = TestApp::spawn_isolated().await;
+
+    let response = app
+        .post_json(
+            "/api/auth/register",
+            &serde_json::json!({
+                "name": "Test User",
+                "email": "isolated@example.com",
+                "password": "SecurePassword123!"
+            }),
+        )
+        .await;
+
+    assert_eq!(response.status(), 201, "Expected 201 Created");
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users")
+        .fetch_one(&app.db)
+        .await
+        .expect("Failed to count users");
+
+    assert_eq!(count, 1, "Isolated database should only contain this test's user");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_isolated_database_first() {
+    register_same_user_and_count().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_isolated_database_second() {
+    register_same_user_and_count().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_isolated_database_is_dropped_on_cleanup() {
+    let app = TestApp::spawn_isolated().await;
+
+    let (name,): (String,) = sqlx::query_as("SELECT current_database()")
+        .fetch_one(&app.db)
+        .await
+        .expect("Failed to read database name");
+
+    app.cleanup().await;
+
+    let other = TestApp::spawn_isolated().await;
+    let (exists,): (bool,) =
+        sqlx::query_as("SELECT EXISTS (SELECT 1 FROM pg_database WHERE datname = $1)")
+            .bind(&name)
+            .fetch_one(&other.db)
+            .await
+            .expect("Failed to query pg_database");
+
+    assert!(!exists, "Isolated database should be dropped on cleanup");
+
+    other.cleanup().await;
+}
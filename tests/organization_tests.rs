@@ -0,0 +1,607 @@
+mod common;
+
+use common::{TestApp, TEST_PASSWORD};
+use multitenant::moduls::organization::domain::{Organization, TenantMembership};
+use multitenant::moduls::organization::infra::{MembershipRepository, OrganizationRepository};
+use multitenant::shared::types::UserId;
+
+async fn create_org(app: &TestApp, slug: &str) -> Organization {
+    let org = Organization::new(slug.to_string(), slug).expect("Invalid organization");
+    app.state.org_repo.save(&org).await.expect("Failed to save organization")
+}
+
+async fn join(app: &TestApp, user_id: UserId, org: &Organization) {
+    app.state
+        .membership_repo
+        .add(&TenantMembership::new(org.id, user_id))
+        .await
+        .expect("Failed to add membership");
+}
+
+async fn register_user_id(app: &TestApp, email: &str) -> UserId {
+    let token = app.register_and_token(email).await;
+    user_id_of(app, &token).await
+}
+
+async fn user_id_of(app: &TestApp, token: &str) -> UserId {
+    let body: serde_json::Value = app
+        .authed_get("/api/auth/me", token)
+        .await
+        .json()
+        .await
+        .expect("Failed to parse response");
+
+    body["user"]["id"]
+        .as_str()
+        .and_then(|id| id.parse::<UserId>().ok())
+        .expect("user id should be a UUID")
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_membership_cap_is_enforced() {
+    let app = TestApp::spawn_isolated().await;
+    let owner_token = app.register_and_token("owner@example.com").await;
+    let owner_id = user_id_of(&app, &owner_token).await;
+    let user_id = register_user_id(&app, "member@example.com").await;
+    let max = app.state.config.tenancy.max_memberships_per_user;
+
+    // Concurrent joins beyond the cap: exactly `max` succeed
+    let mut joins = tokio::task::JoinSet::new();
+    for i in 0..max + 3 {
+        let slug = format!("org-{}", i);
+        let org = Organization::with_owner(slug.clone(), &slug, owner_id).unwrap();
+        app.state.org_repo.save(&org).await.unwrap();
+
+        let request = app
+            .client
+            .post(format!("{}/api/organizations/{}/members", app.address, slug))
+            .bearer_auth(&owner_token)
+            .json(&serde_json::json!({ "user_id": user_id }));
+        joins.spawn(async move { request.send().await.unwrap().status().as_u16() });
+    }
+    let statuses = joins.join_all().await;
+    assert_eq!(statuses.iter().filter(|status| **status == 201).count(), max as usize);
+    assert!(statuses.iter().all(|status| *status == 201 || *status == 409));
+
+    assert_eq!(
+        app.state.membership_repo.count_for_user(user_id).await.unwrap(),
+        i64::from(max)
+    );
+    let organizations = app
+        .state
+        .membership_repo
+        .list_organizations_for_user(user_id)
+        .await
+        .unwrap();
+    assert_eq!(organizations.len(), max as usize);
+
+    // Only the owner adds members, and only existing users
+    let other = create_org(&app, "not-owned").await;
+    let response = app
+        .authed_post_json(
+            &format!("/api/organizations/{}/members", other.slug),
+            &owner_token,
+            &serde_json::json!({ "user_id": owner_id }),
+        )
+        .await;
+    assert_eq!(response.status(), 403);
+    let response = app
+        .authed_post_json(
+            "/api/organizations/org-0/members",
+            &owner_token,
+            &serde_json::json!({ "user_id": UserId::new() }),
+        )
+        .await;
+    assert_eq!(response.status(), 404);
+
+    app.cleanup().await;
+}
+
+async fn login(app: &TestApp, email: &str, tenant_slug: Option<&str>) -> reqwest::Response {
+    app.post_json(
+        "/api/auth/login",
+        &serde_json::json!({
+            "email": email,
+            "password": TEST_PASSWORD,
+            "tenant_slug": tenant_slug
+        }),
+    )
+    .await
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_login_tenant_selection() {
+    let app = TestApp::spawn_isolated().await;
+    let user_id = register_user_id(&app, "multi@example.com").await;
+
+    // Single tenant: selected automatically
+    let acme = create_org(&app, "acme").await;
+    join(&app, user_id, &acme).await;
+
+    let response = login(&app, "multi@example.com", None).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["tenant"]["slug"], "acme");
+
+    // Several tenants: the client must choose
+    let globex = create_org(&app, "globex").await;
+    join(&app, user_id, &globex).await;
+
+    let response = login(&app, "multi@example.com", None).await;
+    assert_eq!(response.status(), 300, "Expected 300 Multiple Choices");
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body.get("access_token").is_none());
+    let slugs: Vec<_> = body["tenants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["slug"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(slugs, vec!["acme", "globex"]);
+
+    // Explicit valid choice
+    let response = login(&app, "multi@example.com", Some("globex")).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["tenant"]["id"], globex.id.to_string());
+
+    // The tenant survives a refresh
+    let refresh_token = body["refresh_token"].as_str().unwrap();
+    let response = app
+        .post_json("/api/auth/refresh", &serde_json::json!({ "refresh_token": refresh_token }))
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let claims = multitenant::moduls::auth::domain::TokenPair::decode(
+        body["access_token"].as_str().unwrap(),
+        &app.state.jwt_keys,
+    )
+    .unwrap();
+    assert_eq!(claims.tenant_id().unwrap(), Some(globex.id));
+
+    // Explicit choice of a tenant the user doesn't belong to
+    create_org(&app, "initech").await;
+    let response = login(&app, "multi@example.com", Some("initech")).await;
+    assert_eq!(response.status(), 403, "Expected 403 Forbidden");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_create_organization_via_api() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("owner@example.com").await;
+
+    let response = app
+        .authed_post_json(
+            "/api/organizations",
+            &token,
+            &serde_json::json!({ "name": "Acme Inc", "slug": "Acme" }),
+        )
+        .await;
+    assert_eq!(response.status(), 201, "Expected 201 Created");
+    let org: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(org["slug"], "acme");
+    assert!(org["owner_id"].is_string());
+
+    // The owner is a member and can see it
+    let response = app.authed_get("/api/organizations", &token).await;
+    assert_eq!(response.status(), 200);
+    let list: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(list.as_array().unwrap().len(), 1);
+    assert_eq!(list[0]["id"], org["id"]);
+
+    let response = app.authed_get("/api/organizations/acme", &token).await;
+    assert_eq!(response.status(), 200);
+
+    // Slugs are unique
+    let response = app
+        .authed_post_json(
+            "/api/organizations",
+            &token,
+            &serde_json::json!({ "name": "Acme Again", "slug": "acme" }),
+        )
+        .await;
+    assert_eq!(response.status(), 409, "Expected 409 Conflict");
+
+    // Other users can't see organizations they don't belong to
+    let other = app.register_and_token("other@example.com").await;
+    let response = app.authed_get("/api/organizations/acme", &other).await;
+    assert_eq!(response.status(), 404);
+
+    let response = app
+        .post_json("/api/organizations", &serde_json::json!({ "name": "X", "slug": "xyz" }))
+        .await;
+    assert_eq!(response.status(), 401, "Expected 401 without a token");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_tenant_two_factor_policy_enforced_at_login() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("owner@example.com").await;
+    let response = app
+        .authed_post_json(
+            "/api/organizations",
+            &token,
+            &serde_json::json!({ "name": "Acme Inc", "slug": "acme" }),
+        )
+        .await;
+    assert_eq!(response.status(), 201);
+
+    let set_policy = |policy: &'static str| {
+        app.client
+            .patch(format!("{}/api/organizations/acme", app.address))
+            .bearer_auth(&token)
+            .json(&serde_json::json!({ "two_factor_policy": policy }))
+            .send()
+    };
+
+    // Optional (default): normal login
+    let response = login(&app, "owner@example.com", None).await;
+    assert_eq!(response.status(), 200);
+
+    // Required: a user without 2FA must set it up first
+    let response = set_policy("required").await.unwrap();
+    assert_eq!(response.status(), 200);
+    let org: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(org["two_factor_policy"], "required");
+
+    let response = login(&app, "owner@example.com", None).await;
+    assert_eq!(response.status(), 403, "Expected 403 Forbidden");
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "TWO_FACTOR_SETUP_REQUIRED");
+
+    // Unknown policies are rejected
+    let response = set_policy("sometimes").await.unwrap();
+    assert_eq!(response.status(), 400);
+
+    // Back to optional
+    let response = set_policy("optional").await.unwrap();
+    assert_eq!(response.status(), 200);
+    let response = login(&app, "owner@example.com", None).await;
+    assert_eq!(response.status(), 200);
+
+    // Only the owner may change the policy
+    let other = app.register_and_token("other@example.com").await;
+    let response = app
+        .client
+        .patch(format!("{}/api/organizations/acme", app.address))
+        .bearer_auth(&other)
+        .json(&serde_json::json!({ "two_factor_policy": "optional" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    app.cleanup().await;
+}
+
+async fn post_in_tenant(
+    app: &TestApp,
+    tenant_slug: &str,
+    path: &str,
+    body: &serde_json::Value,
+) -> reqwest::Response {
+    app.client
+        .post(format!("{}{}", app.address, path))
+        .header("X-Tenant-Slug", tenant_slug)
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to execute request")
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_users_are_scoped_to_tenants() {
+    let app = TestApp::spawn_isolated().await;
+    create_org(&app, "acme").await;
+    create_org(&app, "globex").await;
+
+    // The same email registers once per tenant
+    let mut user_ids = Vec::new();
+    for (slug, password) in [("acme", "acme-password"), ("globex", "globex-password")] {
+        let response = post_in_tenant(
+            &app,
+            slug,
+            "/api/auth/register",
+            &serde_json::json!({
+                "email": "shared@example.com",
+                "password": password,
+                "name": "Shared User"
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), 201, "register in {}", slug);
+        let body: serde_json::Value = response.json().await.unwrap();
+        user_ids.push(body["user"]["id"].as_str().unwrap().to_string());
+    }
+    assert_ne!(user_ids[0], user_ids[1], "Each tenant gets its own user");
+
+    let login = |slug: &'static str, password: &'static str| {
+        let app = &app;
+        async move {
+            post_in_tenant(
+                app,
+                slug,
+                "/api/auth/login",
+                &serde_json::json!({ "email": "shared@example.com", "password": password }),
+            )
+            .await
+        }
+    };
+
+    // Globex's credentials do not authenticate through acme
+    assert_eq!(login("acme", "globex-password").await.status(), 401);
+
+    let response = login("acme", "acme-password").await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["user"]["id"], user_ids[0]);
+    assert_eq!(body["tenant"]["slug"], "acme");
+
+    // Without a tenant neither user exists
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({ "email": "shared@example.com", "password": "acme-password" }),
+        )
+        .await;
+    assert_eq!(response.status(), 401);
+
+    // Unknown tenants are rejected outright
+    assert_eq!(login("initech", "acme-password").await.status(), 404);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_revoke_all_credentials_of_a_tenant() {
+    use multitenant::moduls::auth::domain::Role;
+    use multitenant::moduls::auth::infra::RoleRepository;
+
+    // One row per statement, so revoking takes several batches
+    let app = TestApp::spawn_isolated_with(|config| config.tenancy.revoke_batch_size = 1).await;
+    let acme = create_org(&app, "acme").await;
+    create_org(&app, "globex").await;
+
+    let access_token = |response: reqwest::Response| async move {
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        body["access_token"].as_str().unwrap().to_string()
+    };
+    let mut tenant_tokens = Vec::new();
+    for slug in ["acme", "globex"] {
+        let credentials = serde_json::json!({
+            "email": "user@example.com",
+            "password": TEST_PASSWORD,
+            "name": "Tenant User"
+        });
+        post_in_tenant(&app, slug, "/api/auth/register", &credentials).await;
+        let response = post_in_tenant(&app, slug, "/api/auth/login", &credentials).await;
+        tenant_tokens.push(access_token(response).await);
+    }
+
+    // Members of the tenant are signed out as well
+    let member_id = register_user_id(&app, "member@example.com").await;
+    join(&app, member_id, &acme).await;
+    let member_token = access_token(login(&app, "member@example.com", Some("acme")).await).await;
+
+    let root_id = register_user_id(&app, "root@example.com").await;
+    app.state
+        .role_repo
+        .grant(root_id, &Role::new(Role::SUPER_ADMIN).unwrap())
+        .await
+        .unwrap();
+    let root_token = access_token(login(&app, "root@example.com", None).await).await;
+    let admin_id = register_user_id(&app, "admin@example.com").await;
+    app.state
+        .role_repo
+        .grant(admin_id, &Role::new(Role::ADMIN).unwrap())
+        .await
+        .unwrap();
+    let admin_token = access_token(login(&app, "admin@example.com", None).await).await;
+
+    let live_acme_tokens: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM jwt_tokens
+        WHERE NOT revoked AND user_id IN (
+            SELECT id FROM users WHERE tenant_id = $1
+            UNION SELECT user_id FROM tenant_memberships WHERE organization_id = $1
+        )
+        "#,
+    )
+    .bind(acme.id)
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    let path = format!("/api/admin/tenants/{}/revoke-all", acme.id);
+
+    // Plain admins may not revoke a tenant
+    let response = app.authed_post_json(&path, &admin_token, &serde_json::json!({})).await;
+    assert_eq!(response.status(), 403);
+    let response = app
+        .authed_post_json(
+            &format!("/api/admin/tenants/{}/revoke-all", uuid::Uuid::now_v7()),
+            &root_token,
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(response.status(), 404);
+
+    let response = app.authed_post_json(&path, &root_token, &serde_json::json!({})).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["tokens_revoked"], live_acme_tokens);
+    assert!(live_acme_tokens >= 4);
+
+    assert_eq!(app.authed_get("/api/auth/me", &tenant_tokens[0]).await.status(), 401);
+    assert_eq!(app.authed_get("/api/auth/me", &member_token).await.status(), 401);
+    assert_eq!(app.authed_get("/api/auth/me", &tenant_tokens[1]).await.status(), 200);
+    assert_eq!(app.authed_get("/api/auth/me", &root_token).await.status(), 200);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_tenant_response_headers() {
+    let app = TestApp::spawn_isolated_with(|config| config.tenancy.response_headers = true).await;
+    let acme = create_org(&app, "acme").await;
+
+    let response = app
+        .client
+        .get(format!("{}/health", app.address))
+        .header("X-Tenant-Slug", "ACME")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-tenant-id"], acme.id.to_string().as_str());
+    assert_eq!(response.headers()["x-tenant-slug"], "acme");
+
+    let response = app.get("/health").await;
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().get("x-tenant-id").is_none());
+    assert!(response.headers().get("x-tenant-slug").is_none());
+
+    let response = app
+        .client
+        .get(format!("{}/health", app.address))
+        .header("X-Tenant-Slug", "unknown")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    assert!(response.headers().get("x-tenant-id").is_none());
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_public_profile_is_tenant_scoped() {
+    let app = TestApp::spawn_isolated().await;
+    let acme = create_org(&app, "acme").await;
+    let globex = create_org(&app, "globex").await;
+    let mut ids = Vec::new();
+    for (email, org) in [
+        ("alice@example.com", &acme),
+        ("bob@example.com", &acme),
+        ("carol@example.com", &globex),
+    ] {
+        let user_id = register_user_id(&app, email).await;
+        join(&app, user_id, org).await;
+        ids.push(user_id);
+    }
+    let response = login(&app, "alice@example.com", None).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    let token = body["access_token"].as_str().unwrap();
+
+    let response = app.authed_get(&format!("/api/users/{}/public", ids[1]), token).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let mut fields: Vec<_> = body.as_object().unwrap().keys().cloned().collect();
+    fields.sort();
+    assert_eq!(fields, ["avatar_url", "id", "name"], "Only public fields may be exposed");
+    assert_eq!(body["id"], ids[1].to_string());
+
+    let response = app.authed_get(&format!("/api/users/{}/public", ids[2]), token).await;
+    assert_eq!(response.status(), 404, "Users of other tenants must not be visible");
+    let response = app
+        .authed_get(&format!("/api/users/{}/public", uuid::Uuid::now_v7()), token)
+        .await;
+    assert_eq!(response.status(), 404);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_admin_tenants_list() {
+    use multitenant::moduls::auth::domain::Role;
+    use multitenant::moduls::auth::infra::RoleRepository;
+
+    let app = TestApp::spawn_isolated().await;
+    let acme = create_org(&app, "acme").await;
+    let labs = create_org(&app, "acme-labs").await;
+    let mut globex = Organization::new("Globex Corporation".to_string(), "globex").unwrap();
+    globex.is_active = false;
+    app.state.org_repo.save(&globex).await.unwrap();
+
+    // Acme: two users of its own (members as well) and a global member
+    for email in ["alice@example.com", "bob@example.com"] {
+        let credentials = serde_json::json!({
+            "email": email,
+            "password": TEST_PASSWORD,
+            "name": "Tenant User"
+        });
+        let response = post_in_tenant(&app, "acme", "/api/auth/register", &credentials).await;
+        assert_eq!(response.status(), 201);
+    }
+    let member_id = register_user_id(&app, "member@example.com").await;
+    join(&app, member_id, &acme).await;
+    join(&app, member_id, &labs).await;
+
+    let root_id = register_user_id(&app, "root@example.com").await;
+    app.state
+        .role_repo
+        .grant(root_id, &Role::new(Role::SUPER_ADMIN).unwrap())
+        .await
+        .unwrap();
+    let response = login(&app, "root@example.com", None).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    let root_token = body["access_token"].as_str().unwrap().to_string();
+
+    let list = |query: &'static str| {
+        let app = &app;
+        let root_token = &root_token;
+        async move {
+            let response = app
+                .authed_get(&format!("/api/admin/tenants{}", query), root_token)
+                .await;
+            assert_eq!(response.status(), 200);
+            response.json::<serde_json::Value>().await.unwrap()
+        }
+    };
+    let slugs = |page: &serde_json::Value| -> Vec<String> {
+        page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["slug"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let page = list("").await;
+    assert_eq!(slugs(&page), ["acme", "acme-labs", "globex"]);
+    assert_eq!(page["total"], 3);
+    assert_eq!(page["items"][0]["user_count"], 3, "Users are counted once");
+    assert_eq!(page["items"][1]["user_count"], 1);
+    assert_eq!(page["items"][2]["user_count"], 0);
+
+    assert_eq!(slugs(&list("?q=ACME").await), ["acme", "acme-labs"]);
+    assert_eq!(slugs(&list("?q=corp").await), ["globex"], "Names are searched too");
+    assert_eq!(slugs(&list("?q=%25").await), Vec::<String>::new());
+    assert_eq!(slugs(&list("?is_active=false").await), ["globex"]);
+    assert_eq!(slugs(&list("?q=acme&is_active=false").await), Vec::<String>::new());
+
+    let page = list("?page=2&per_page=2").await;
+    assert_eq!(slugs(&page), ["globex"]);
+    assert_eq!(page["total"], 3);
+
+    // Plain users may not list tenants
+    let response = login(&app, "member@example.com", Some("acme")).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    let member_token = body["access_token"].as_str().unwrap();
+    assert_eq!(app.authed_get("/api/admin/tenants", member_token).await.status(), 403);
+
+    app.cleanup().await;
+}
@@ -1,43 +1,17 @@
 mod common;
 
-use common::TestApp;
-
-/// Helper function to register and get access token
-async fn register_and_login(app: &TestApp) -> String {
-    let response = app
-        .post_json(
-            "/api/auth/register",
-            &serde_json::json!({
-                "name": "Test User",
-                "email": "user@example.com",
-                "password": "SecurePassword123!"
-            }),
-        )
-        .await;
-
-    assert_eq!(response.status(), 201);
-
-    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
-    body["access_token"]
-        .as_str()
-        .expect("access_token should be a string")
-        .to_string()
-}
+use common::{SeedUser, TestApp, TEST_PASSWORD};
+use multitenant::moduls::auth::application::WebLoginOutcome;
+use multitenant::moduls::auth::domain::Session;
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_get_profile_success() {
-    let app = TestApp::spawn().await;
-    let access_token = register_and_login(&app).await;
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("user@example.com").await;
 
     // Get profile
-    let response = app
-        .client
-        .get(&format!("{}/api/user/profile", app.address))
-        .bearer_auth(&access_token)
-        .send()
-        .await
-        .expect("Failed to execute request");
+    let response = app.authed_get("/api/user/profile", &access_token).await;
 
     assert_eq!(response.status(), 200, "Expected 200 OK");
 
@@ -50,9 +24,9 @@ async fn test_get_profile_success() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_get_profile_unauthorized() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     // Try to get profile without authentication
     let response = app.get("/api/user/profile").await;
@@ -63,24 +37,23 @@ async fn test_get_profile_unauthorized() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_update_profile_success() {
-    let app = TestApp::spawn().await;
-    let access_token = register_and_login(&app).await;
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("user@example.com").await;
 
     // Update profile
     let response = app
-        .client
-        .put(&format!("{}/api/user/profile", app.address))
-        .bearer_auth(&access_token)
-        .json(&serde_json::json!({
-            "name": "Updated Name",
-            "bio": "This is my bio",
-            "avatar_url": "https://example.com/avatar.jpg"
-        }))
-        .send()
-        .await
-        .expect("Failed to execute request");
+        .authed_put_json(
+            "/api/user/profile",
+            &access_token,
+            &serde_json::json!({
+                "name": "Updated Name",
+                "bio": "This is my bio",
+                "avatar_url": "https://example.com/avatar.jpg"
+            }),
+        )
+        .await;
 
     assert_eq!(response.status(), 200, "Expected 200 OK");
 
@@ -92,24 +65,125 @@ async fn test_update_profile_success() {
     app.cleanup().await;
 }
 
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    image::DynamicImage::ImageRgb8(image::RgbImage::new(width, height))
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .unwrap();
+    bytes
+}
+
+async fn upload_avatar(app: &TestApp, access_token: &str, body: Vec<u8>) -> reqwest::Response {
+    app.client
+        .put(format!("{}/api/user/avatar", app.address))
+        .bearer_auth(access_token)
+        .header("Content-Type", "image/png")
+        .body(body)
+        .send()
+        .await
+        .expect("Failed to execute request")
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_upload_avatar_is_scaled_down_and_served() {
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("user@example.com").await;
+
+    let response = upload_avatar(&app, &access_token, png(1024, 768)).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let url = body["avatar_url"].as_str().expect("avatar_url should be set");
+    let path = &url[url.find("/api/").unwrap()..];
+
+    // Public, like any image
+    let response = app.get(path).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    let avatar = image::load_from_memory(&response.bytes().await.unwrap()).unwrap();
+    assert_eq!((avatar.width(), avatar.height()), (512, 384));
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_upload_avatar_rejects_bombs_and_oversized_bodies() {
+    let app = TestApp::spawn_isolated_with(|config| config.avatar.max_bytes = 64 * 1024).await;
+    let access_token = app.register_and_token("user@example.com").await;
+
+    // Small file, huge dimensions
+    let response = upload_avatar(&app, &access_token, png(5000, 1)).await;
+    assert_eq!(response.status(), 400);
+
+    let response = upload_avatar(&app, &access_token, vec![0; 65 * 1024]).await;
+    assert_eq!(response.status(), 413);
+
+    let body: serde_json::Value = app
+        .authed_get("/api/user/profile", &access_token)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert!(body["avatar_url"].is_null());
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_update_profile_creates_missing_profile_row() {
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("user@example.com").await;
+    let profiles = || {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM user_profiles").fetch_one(&app.db)
+    };
+    assert_eq!(profiles().await.unwrap(), 0, "Registration creates no profile row");
+
+    // Without a row the profile is empty
+    let response = app.authed_get("/api/user/profile", &access_token).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["bio"].is_null());
+
+    for bio in ["First bio", "Second bio"] {
+        let response = app
+            .authed_put_json(
+                "/api/user/profile",
+                &access_token,
+                &serde_json::json!({ "name": "Test User", "bio": bio }),
+            )
+            .await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["bio"], bio);
+        assert_eq!(profiles().await.unwrap(), 1, "Updates reuse the profile row");
+    }
+
+    let response = app.authed_get("/api/user/profile", &access_token).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["bio"], "Second bio");
+
+    app.cleanup().await;
+}
+
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_update_profile_invalid_name() {
-    let app = TestApp::spawn().await;
-    let access_token = register_and_login(&app).await;
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("user@example.com").await;
 
     // Try to update with empty name
     let response = app
-        .client
-        .put(&format!("{}/api/user/profile", app.address))
-        .bearer_auth(&access_token)
-        .json(&serde_json::json!({
-            "name": "",
-            "bio": "Bio"
-        }))
-        .send()
-        .await
-        .expect("Failed to execute request");
+        .authed_put_json(
+            "/api/user/profile",
+            &access_token,
+            &serde_json::json!({
+                "name": "",
+                "bio": "Bio"
+            }),
+        )
+        .await;
 
     assert_eq!(response.status(), 400, "Expected 400 Bad Request");
 
@@ -117,23 +191,22 @@ async fn test_update_profile_invalid_name() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_change_password_success() {
-    let app = TestApp::spawn().await;
-    let access_token = register_and_login(&app).await;
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("user@example.com").await;
 
     // Change password
     let response = app
-        .client
-        .put(&format!("{}/api/user/password", app.address))
-        .bearer_auth(&access_token)
-        .json(&serde_json::json!({
-            "current_password": "SecurePassword123!",
-            "new_password": "NewSecurePassword456!"
-        }))
-        .send()
-        .await
-        .expect("Failed to execute request");
+        .authed_put_json(
+            "/api/user/password",
+            &access_token,
+            &serde_json::json!({
+                "current_password": TEST_PASSWORD,
+                "new_password": "NewSecurePassword456!"
+            }),
+        )
+        .await;
 
     assert_eq!(response.status(), 200, "Expected 200 OK");
 
@@ -154,23 +227,22 @@ async fn test_change_password_success() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_change_password_wrong_current() {
-    let app = TestApp::spawn().await;
-    let access_token = register_and_login(&app).await;
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("user@example.com").await;
 
     // Try to change password with wrong current password
     let response = app
-        .client
-        .put(&format!("{}/api/user/password", app.address))
-        .bearer_auth(&access_token)
-        .json(&serde_json::json!({
-            "current_password": "WrongPassword123!",
-            "new_password": "NewSecurePassword456!"
-        }))
-        .send()
-        .await
-        .expect("Failed to execute request");
+        .authed_put_json(
+            "/api/user/password",
+            &access_token,
+            &serde_json::json!({
+                "current_password": "WrongPassword123!",
+                "new_password": "NewSecurePassword456!"
+            }),
+        )
+        .await;
 
     assert_eq!(response.status(), 401, "Expected 401 Unauthorized");
 
@@ -178,23 +250,22 @@ async fn test_change_password_wrong_current() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_change_password_weak_new_password() {
-    let app = TestApp::spawn().await;
-    let access_token = register_and_login(&app).await;
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("user@example.com").await;
 
     // Try to change to weak password
     let response = app
-        .client
-        .put(&format!("{}/api/user/password", app.address))
-        .bearer_auth(&access_token)
-        .json(&serde_json::json!({
-            "current_password": "SecurePassword123!",
-            "new_password": "weak"
-        }))
-        .send()
-        .await
-        .expect("Failed to execute request");
+        .authed_put_json(
+            "/api/user/password",
+            &access_token,
+            &serde_json::json!({
+                "current_password": TEST_PASSWORD,
+                "new_password": "weak"
+            }),
+        )
+        .await;
 
     assert_eq!(response.status(), 400, "Expected 400 Bad Request");
 
@@ -202,16 +273,16 @@ async fn test_change_password_weak_new_password() {
 }
 
 #[tokio::test]
-#[ignore = "integration test requires database and --test-threads=1"]
+#[ignore = "integration test requires database"]
 async fn test_change_password_unauthorized() {
-    let app = TestApp::spawn().await;
+    let app = TestApp::spawn_isolated().await;
 
     // Try to change password without authentication
     let response = app
         .put_json(
             "/api/user/password",
             &serde_json::json!({
-                "current_password": "SecurePassword123!",
+                "current_password": TEST_PASSWORD,
                 "new_password": "NewSecurePassword456!"
             }),
         )
@@ -221,3 +292,971 @@ async fn test_change_password_unauthorized() {
 
     app.cleanup().await;
 }
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_get_profile_seeded_user() {
+    let app = TestApp::spawn_with_seed(&[SeedUser::new("seeded@example.com").verified()]).await;
+    let access_token = app.login_token("seeded@example.com", TEST_PASSWORD).await;
+
+    let response = app.authed_get("/api/user/profile", &access_token).await;
+
+    assert_eq!(response.status(), 200, "Expected 200 OK");
+
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert_eq!(body["email"], "seeded@example.com");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_notification_preferences_round_trip() {
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("prefs@example.com").await;
+
+    let response = app
+        .authed_get("/api/user/notification-preferences", &access_token)
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({ "new_device_login": true, "password_changed": true })
+    );
+
+    let response = app
+        .authed_put_json(
+            "/api/user/notification-preferences",
+            &access_token,
+            &serde_json::json!({ "new_device_login": false }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+
+    let stored: serde_json::Value = sqlx::query_scalar(
+        "SELECT notification_preferences FROM users WHERE email = $1",
+    )
+    .bind("prefs@example.com")
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(stored["new_device_login"], false);
+    assert_eq!(stored["password_changed"], true);
+
+    // Password reset emails are mandatory
+    let response = app
+        .authed_put_json(
+            "/api/user/notification-preferences",
+            &access_token,
+            &serde_json::json!({ "password_reset": false }),
+        )
+        .await;
+    assert_eq!(response.status(), 400);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_delete_account() {
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("leaving@example.com").await;
+
+    let response = app
+        .authed_delete_json(
+            "/api/user/account",
+            &access_token,
+            &serde_json::json!({ "password": "WrongPassword123!" }),
+        )
+        .await;
+    assert_eq!(response.status(), 401);
+
+    let response = app
+        .authed_delete_json(
+            "/api/user/account",
+            &access_token,
+            &serde_json::json!({ "password": TEST_PASSWORD }),
+        )
+        .await;
+    assert_eq!(response.status(), 204);
+
+    // Logged out, and the address is free again
+    let response = app.authed_get("/api/user/profile", &access_token).await;
+    assert_eq!(response.status(), 401);
+    let (email, deleted): (String, bool) =
+        sqlx::query_as("SELECT email, deleted_at IS NOT NULL FROM users")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert!(email.starts_with("deleted+"));
+    assert!(deleted);
+    app.register_and_token("leaving@example.com").await;
+
+    app.cleanup().await;
+}
+
+/// Start changing the email of the token's user
+async fn request_email_change(app: &TestApp, access_token: &str, new_email: &str) -> reqwest::Response {
+    app.authed_post_json(
+        "/api/user/email",
+        access_token,
+        &serde_json::json!({ "new_email": new_email, "password": TEST_PASSWORD }),
+    )
+    .await
+}
+
+/// Confirmation token of the pending email change
+///
+/// The emailed token can't be read back, so the pending request is given
+/// a known one.
+async fn known_email_change_token(app: &TestApp) -> String {
+    use multitenant::moduls::user::domain::EmailChangeRequest;
+
+    let plain = "known-email-change-token";
+    sqlx::query("UPDATE email_change_requests SET token_hash = $1 WHERE used_at IS NULL")
+        .bind(EmailChangeRequest::hash(plain))
+        .execute(&app.db)
+        .await
+        .unwrap();
+    plain.to_string()
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_change_email_after_confirmation() {
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("old@example.com").await;
+
+    let response = request_email_change(&app, &access_token, "new@example.com").await;
+    assert_eq!(response.status(), 200);
+    let token = known_email_change_token(&app).await;
+
+    let response = app.get(&format!("/api/user/email/confirm?token={}", token)).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["email"], "new@example.com");
+    assert_eq!(body["email_verified"], false);
+
+    // Links work once
+    let response = app.get(&format!("/api/user/email/confirm?token={}", token)).await;
+    assert_eq!(response.status(), 400);
+
+    // Signing in uses the new email
+    app.login_token("new@example.com", TEST_PASSWORD).await;
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_change_email_to_existing_email_is_rejected() {
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("me@example.com").await;
+    app.register_and_token("taken@example.com").await;
+
+    let response = request_email_change(&app, &access_token, "taken@example.com").await;
+    assert_eq!(response.status(), 409);
+
+    // Taken between request and confirmation
+    let response = request_email_change(&app, &access_token, "late@example.com").await;
+    assert_eq!(response.status(), 200);
+    let token = known_email_change_token(&app).await;
+    app.register_and_token("late@example.com").await;
+
+    let response = app.get(&format!("/api/user/email/confirm?token={}", token)).await;
+    assert_eq!(response.status(), 409);
+    let response = app.authed_get("/api/user/profile", &access_token).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["email"], "me@example.com");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_change_password_invalidates_existing_tokens() {
+    let app = TestApp::spawn_isolated().await;
+    let access_token = app.register_and_token("user@example.com").await;
+
+    // Tokens carry a whole-second `iat`; make sure the change lands after it
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    let response = app
+        .authed_put_json(
+            "/api/user/password",
+            &access_token,
+            &serde_json::json!({
+                "current_password": TEST_PASSWORD,
+                "new_password": "NewSecurePassword456!"
+            }),
+        )
+        .await;
+
+    assert_eq!(response.status(), 200, "Expected 200 OK");
+
+    let response = app.authed_get("/api/user/profile", &access_token).await;
+    assert_eq!(response.status(), 401, "Token issued before the change should be rejected");
+
+    let new_token = app.login_token("user@example.com", "NewSecurePassword456!").await;
+    let response = app.authed_get("/api/user/profile", &new_token).await;
+    assert_eq!(response.status(), 200, "Fresh token should work");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_change_password_requires_fresh_token() {
+    let app = TestApp::spawn_isolated_with(|config| config.security.fresh_auth_window = 1).await;
+    let access_token = app.register_and_token("user@example.com").await;
+    let body = serde_json::json!({
+        "current_password": TEST_PASSWORD,
+        "new_password": "NewSecurePassword456!"
+    });
+
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+
+    let response = app.authed_put_json("/api/user/password", &access_token, &body).await;
+    assert_eq!(response.status(), 401, "Stale token should need re-authentication");
+    let error: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(error["error"]["code"], "REAUTH_REQUIRED");
+
+    // Reading the profile is not guarded
+    let response = app.authed_get("/api/user/profile", &access_token).await;
+    assert_eq!(response.status(), 200);
+
+    let fresh_token = app.login_token("user@example.com", TEST_PASSWORD).await;
+    let response = app.authed_put_json("/api/user/password", &fresh_token, &body).await;
+    assert_eq!(response.status(), 200, "Fresh token should pass the guard");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_refresh_does_not_renew_fresh_auth() {
+    use multitenant::moduls::auth::domain::TokenPair;
+
+    let app = TestApp::spawn_isolated_with(|config| config.security.fresh_auth_window = 1).await;
+    app.register_and_token("user@example.com").await;
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({ "email": "user@example.com", "password": TEST_PASSWORD }),
+        )
+        .await;
+    let login: serde_json::Value = response.json().await.unwrap();
+    let original = TokenPair::decode(login["access_token"].as_str().unwrap(), &app.state.jwt_keys).unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+
+    let response = app
+        .post_json(
+            "/api/auth/refresh",
+            &serde_json::json!({ "refresh_token": login["refresh_token"] }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    let refreshed: serde_json::Value = response.json().await.unwrap();
+    let access_token = refreshed["access_token"].as_str().unwrap();
+
+    let claims = TokenPair::decode(access_token, &app.state.jwt_keys).unwrap();
+    assert!(claims.iat > original.iat, "iat should move on refresh");
+    assert_eq!(claims.auth_time, original.auth_time, "auth_time should be carried forward");
+
+    let body = serde_json::json!({
+        "current_password": TEST_PASSWORD,
+        "new_password": "NewSecurePassword456!"
+    });
+    let response = app.authed_put_json("/api/user/password", access_token, &body).await;
+    assert_eq!(response.status(), 401, "Refreshing should not count as re-authentication");
+    let error: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(error["error"]["code"], "REAUTH_REQUIRED");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_verify_password() {
+    let app = TestApp::spawn_isolated_with(|config| config.security.password_verify_max_failures = 3).await;
+    let access_token = app.register_and_token("user@example.com").await;
+    let verify = |password: &str| {
+        let body = serde_json::json!({ "password": password });
+        let (app, access_token) = (&app, &access_token);
+        async move {
+            app.authed_post_json("/api/user/verify-password", access_token, &body)
+                .await
+        }
+    };
+
+    assert_eq!(verify(TEST_PASSWORD).await.status(), 204);
+    assert_eq!(verify("WrongPassword123!").await.status(), 401);
+
+    // Failures are counted with failed logins
+    let response = app.authed_get("/api/auth/me", &access_token).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["security"]["recent_failed_logins"], 1);
+
+    // A failed login plus another wrong password reach the limit
+    let response = app
+        .post_json(
+            "/api/auth/login",
+            &serde_json::json!({ "email": "user@example.com", "password": "WrongPassword123!" }),
+        )
+        .await;
+    assert_eq!(response.status(), 401);
+    assert_eq!(verify("WrongPassword123!").await.status(), 401);
+
+    let response = verify(TEST_PASSWORD).await;
+    assert_eq!(response.status(), 429, "Locked after repeated failures");
+    let error: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(error["error"]["code"], "TOO_MANY_REQUESTS");
+
+    app.cleanup().await;
+}
+
+/// Client that surfaces redirects instead of following them
+fn no_redirect_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap()
+}
+
+/// Register a user and open a web session for them
+///
+/// Returns the session ID and its CSRF token.
+async fn web_session(app: &TestApp, email: &str) -> (String, String) {
+    use multitenant::moduls::auth::application::LoginWebCommand;
+
+    app.register_and_token(email).await;
+    let session = app
+        .state
+        .login_user_use_case
+        .login_web(LoginWebCommand {
+            email: email.to_string(),
+            password: TEST_PASSWORD.to_string(),
+            ip_address: None,
+            user_agent: None,
+            device_name: None,
+            remember_me: false,
+            tenant_id: None,
+        })
+        .await
+        .map(logged_in_session)
+        .expect("web login failed");
+
+    (session.id.to_string(), session.csrf_token.into_inner())
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_web_profile_requires_session() {
+    let app = TestApp::spawn_isolated().await;
+    let client = no_redirect_client();
+
+    let response = client
+        .get(format!("{}/web/user/profile", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 302);
+    assert_eq!(response.headers()["location"], "/web/auth/login");
+
+    let (session_id, csrf_token) = web_session(&app, "web@example.com").await;
+    let response = client
+        .get(format!("{}/web/user/profile", app.address))
+        .header("cookie", format!("session_id={}", session_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200, "Expected 200 OK with a valid session");
+    assert_eq!(response.headers()["x-csrf-token"], csrf_token.as_str());
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_web_expired_session_redirects_to_login() {
+    use multitenant::moduls::auth::domain::Session;
+    use multitenant::moduls::auth::infra::SessionRepository;
+
+    let app = TestApp::spawn_isolated().await;
+    let (session_id, _) = web_session(&app, "expired@example.com").await;
+    let user_id = app
+        .state
+        .session_repo
+        .find_by_id(session_id.parse().unwrap())
+        .await
+        .unwrap()
+        .unwrap()
+        .user_id;
+
+    let expired = Session::new(user_id, None, None, -60);
+    app.state.session_repo.save(&expired).await.unwrap();
+
+    let response = no_redirect_client()
+        .get(format!("{}/web/user/profile", app.address))
+        .header("cookie", format!("session_id={}", expired.id))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 302);
+    assert_eq!(response.headers()["location"], "/web/auth/login");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_web_sliding_session_extended_on_activity() {
+    use multitenant::moduls::auth::domain::Session;
+    use multitenant::moduls::auth::infra::SessionRepository;
+
+    let app = TestApp::spawn_isolated_with(|config| config.session.sliding = true).await;
+    let (session_id, _) = web_session(&app, "sliding@example.com").await;
+    let user_id = app
+        .state
+        .session_repo
+        .find_by_id(session_id.parse().unwrap())
+        .await
+        .unwrap()
+        .unwrap()
+        .user_id;
+
+    // 40 minutes into a one hour session
+    let mut session = Session::new(user_id, None, None, 3600);
+    let forty_minutes = chrono::Duration::minutes(40);
+    session.created_at -= forty_minutes;
+    session.updated_at -= forty_minutes;
+    session.expires_at -= forty_minutes;
+    app.state.session_repo.save(&session).await.unwrap();
+
+    let response = no_redirect_client()
+        .get(format!("{}/web/user/profile", app.address))
+        .header("cookie", format!("session_id={}", session.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Extended to a full hour from now
+    let stored = app.state.session_repo.find_by_id(session.id).await.unwrap().unwrap();
+    assert!(stored.expires_at > chrono::Utc::now() + chrono::Duration::minutes(59));
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_web_update_profile_with_session() {
+    let app = TestApp::spawn_isolated().await;
+    let (session_id, csrf_token) = web_session(&app, "webedit@example.com").await;
+
+    let response = no_redirect_client()
+        .post(format!("{}/web/user/profile/edit", app.address))
+        .header("cookie", format!("session_id={}", session_id))
+        .form(&[
+            ("name", "Web Name"),
+            ("bio", "From the web form"),
+            ("_csrf", csrf_token.as_str()),
+        ])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 303, "Expected redirect after update");
+
+    let token = app.login_token("webedit@example.com", TEST_PASSWORD).await;
+    let body: serde_json::Value = app
+        .authed_get("/api/user/profile", &token)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["name"], "Web Name");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_web_form_rejects_bad_csrf_token() {
+    let app = TestApp::spawn_isolated().await;
+    let (session_id, csrf_token) = web_session(&app, "csrf@example.com").await;
+    let last = if csrf_token.ends_with('A') { "B" } else { "A" };
+    let tampered = format!("{}{}", &csrf_token[..csrf_token.len() - 1], last);
+
+    for token in [Some(tampered.as_str()), None] {
+        let mut request = no_redirect_client()
+            .post(format!("{}/web/user/profile/edit", app.address))
+            .header("cookie", format!("session_id={}", session_id))
+            .form(&[("name", "Hijacked")]);
+        if let Some(token) = token {
+            request = request.header("x-csrf-token", token);
+        }
+
+        let response = request.send().await.unwrap();
+        assert_eq!(response.status(), 403, "Expected 403 for token {:?}", token);
+    }
+
+    let token = app.login_token("csrf@example.com", TEST_PASSWORD).await;
+    let body: serde_json::Value = app
+        .authed_get("/api/user/profile", &token)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_ne!(body["name"], "Hijacked");
+
+    app.cleanup().await;
+}
+
+async fn web_login(app: &TestApp, email: &str, user_agent: &str) -> String {
+    use multitenant::moduls::auth::application::LoginWebCommand;
+
+    app.state
+        .login_user_use_case
+        .login_web(LoginWebCommand {
+            email: email.to_string(),
+            password: TEST_PASSWORD.to_string(),
+            ip_address: Some("10.0.0.1".to_string()),
+            user_agent: Some(user_agent.to_string()),
+            device_name: None,
+            remember_me: false,
+            tenant_id: None,
+        })
+        .await
+        .map(logged_in_session)
+        .expect("web login failed")
+        .id
+        .to_string()
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_concurrent_sessions_are_listed() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("devices@example.com").await;
+
+    let phone = web_login(&app, "devices@example.com", "Phone").await;
+    let laptop = web_login(&app, "devices@example.com", "Laptop").await;
+
+    let response = app.authed_get("/api/user/sessions", &token).await;
+    assert_eq!(response.status(), 200);
+    let sessions: Vec<serde_json::Value> = response.json().await.unwrap();
+
+    let ids: Vec<_> = sessions.iter().map(|s| s["id"].as_str().unwrap()).collect();
+    assert_eq!(sessions.len(), 2, "Both logins should stay live");
+    assert!(ids.contains(&phone.as_str()) && ids.contains(&laptop.as_str()));
+    assert_eq!(sessions[0]["ip_address"], "10.0.0.1");
+    assert!(sessions[0]["last_seen_at"].is_string());
+    assert!(sessions[0].get("csrf_token").is_none());
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_device_name_round_trips_to_session_list() {
+    use multitenant::moduls::auth::application::LoginWebCommand;
+
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("named@example.com").await;
+
+    let session = app
+        .state
+        .login_user_use_case
+        .login_web(LoginWebCommand {
+            email: "named@example.com".to_string(),
+            password: TEST_PASSWORD.to_string(),
+            ip_address: None,
+            user_agent: Some("Mozilla/5.0 (iPhone)".to_string()),
+            device_name: Some("John's iPhone".to_string()),
+            remember_me: false,
+            tenant_id: None,
+        })
+        .await
+        .map(logged_in_session)
+        .expect("web login failed");
+    assert_eq!(session.device_name.as_deref(), Some("John's iPhone"));
+    web_login(&app, "named@example.com", "Laptop").await;
+
+    let response = app.authed_get("/api/user/sessions", &token).await;
+    assert_eq!(response.status(), 200);
+    let sessions: Vec<serde_json::Value> = response.json().await.unwrap();
+
+    let named = sessions
+        .iter()
+        .find(|s| s["id"] == session.id.to_string())
+        .expect("named session should be listed");
+    assert_eq!(named["device_name"], "John's iPhone");
+    assert!(sessions.iter().any(|s| s["device_name"].is_null()));
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_session_limit_evicts_oldest() {
+    use multitenant::moduls::auth::domain::{Email, Session, TokenPair};
+    use multitenant::moduls::auth::infra::{
+        PostgresSessionRepository, SessionRepository, UserRepository,
+    };
+    use multitenant::shared::db::DbPools;
+
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("limit@example.com").await;
+    let user_id = app
+        .state
+        .user_repo
+        .find_by_email(&Email::new("limit@example.com").unwrap())
+        .await
+        .unwrap()
+        .unwrap()
+        .id;
+    let repo = PostgresSessionRepository::new(DbPools::from(app.state.db.clone()))
+        .with_max_concurrent(Some(2));
+
+    let mut saved = Vec::new();
+    let mut refresh_token = None;
+    for offset in [30, 20, 10] {
+        let mut session = Session::new(user_id, None, None, 3600);
+        session.created_at -= chrono::Duration::seconds(offset);
+        saved.push(repo.save(&session).await.unwrap().id);
+
+        // Tokens minted under the oldest session
+        if refresh_token.is_none() {
+            let (pair, access, refresh) = TokenPair::generate_with_auth_time(
+                user_id,
+                None,
+                None,
+                None,
+                Some(session.id),
+                Default::default(),
+                &app.state.jwt_keys,
+                900,
+                3600,
+            )
+            .unwrap();
+            app.state.token_repo.save(&access).await.unwrap();
+            app.state.token_repo.save(&refresh).await.unwrap();
+            refresh_token = Some(pair.refresh_token);
+        }
+    }
+
+    let live: Vec<_> = repo
+        .find_all_by_user_id(user_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    assert_eq!(live.len(), 2);
+    assert!(!live.contains(&saved[0]), "Oldest session should be evicted");
+
+    // Its tokens went with it
+    let response = app
+        .post_json(
+            "/api/auth/refresh",
+            &serde_json::json!({ "refresh_token": refresh_token.unwrap() }),
+        )
+        .await;
+    assert_eq!(response.status(), 401, "Evicted session's refresh token should be rejected");
+
+    app.cleanup().await;
+}
+
+async fn web_profile_status(app: &TestApp, session_id: &str) -> u16 {
+    no_redirect_client()
+        .get(format!("{}/web/user/profile", app.address))
+        .header("cookie", format!("session_id={}", session_id))
+        .send()
+        .await
+        .unwrap()
+        .status()
+        .as_u16()
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_revoking_one_session_leaves_others() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("lost@example.com").await;
+    let phone = web_login(&app, "lost@example.com", "Phone").await;
+    let laptop = web_login(&app, "lost@example.com", "Laptop").await;
+
+    let response = app
+        .authed_delete(&format!("/api/user/sessions/{}", phone), &token)
+        .await;
+    assert_eq!(response.status(), 200);
+
+    assert_eq!(web_profile_status(&app, &phone).await, 302, "Revoked session must not work");
+    assert_eq!(web_profile_status(&app, &laptop).await, 200);
+
+    let sessions: Vec<serde_json::Value> = app
+        .authed_get("/api/user/sessions", &token)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["id"], laptop.as_str());
+
+    // Already revoked
+    let response = app
+        .authed_delete(&format!("/api/user/sessions/{}", phone), &token)
+        .await;
+    assert_eq!(response.status(), 404);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_revoking_another_users_session_is_not_found() {
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("victim@example.com").await;
+    let victim_session = web_login(&app, "victim@example.com", "Phone").await;
+    let attacker_token = app.register_and_token("attacker@example.com").await;
+
+    let response = app
+        .authed_delete(&format!("/api/user/sessions/{}", victim_session), &attacker_token)
+        .await;
+
+    assert_eq!(response.status(), 404);
+    assert_eq!(web_profile_status(&app, &victim_session).await, 200);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_revoking_session_revokes_its_api_tokens() {
+    use multitenant::moduls::auth::domain::TokenPair;
+
+    let app = TestApp::spawn_isolated().await;
+    let (session_id, csrf_token) = web_session(&app, "bridge@example.com").await;
+    let standalone = app.login_token("bridge@example.com", TEST_PASSWORD).await;
+
+    let response = no_redirect_client()
+        .post(format!("{}/web/user/api-token", app.address))
+        .header("cookie", format!("session_id={}", session_id))
+        .form(&[("_csrf", csrf_token.as_str())])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let bridged = body["access_token"].as_str().unwrap().to_string();
+    let claims = TokenPair::decode(&bridged, &app.state.jwt_keys).unwrap();
+    assert_eq!(claims.sid.as_deref(), Some(session_id.as_str()));
+    assert_eq!(app.authed_get("/api/user/profile", &bridged).await.status(), 200);
+
+    let response = app
+        .authed_delete(&format!("/api/user/sessions/{}", session_id), &standalone)
+        .await;
+    assert_eq!(response.status(), 200);
+
+    assert_eq!(
+        app.authed_get("/api/user/profile", &bridged).await.status(),
+        401,
+        "Tokens minted under the session must be revoked with it"
+    );
+    assert_eq!(app.authed_get("/api/user/profile", &standalone).await.status(), 200);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_logout_everywhere() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("everywhere@example.com").await;
+    let phone = web_login(&app, "everywhere@example.com", "Phone").await;
+    let laptop = web_login(&app, "everywhere@example.com", "Laptop").await;
+
+    let response = app.authed_delete("/api/user/sessions", &token).await;
+    assert_eq!(response.status(), 200);
+
+    assert_eq!(web_profile_status(&app, &phone).await, 302);
+    assert_eq!(web_profile_status(&app, &laptop).await, 302);
+    let response = app.authed_get("/api/user/profile", &token).await;
+    assert_eq!(response.status(), 401, "JWTs must be revoked too");
+
+    app.cleanup().await;
+}
+
+/// Mint an API key via the API and return its id and plain key
+async fn create_api_key(app: &TestApp, token: &str, body: serde_json::Value) -> (String, String) {
+    let response = app.authed_post_json("/api/user/api-keys", token, &body).await;
+    assert_eq!(response.status(), 201);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    (
+        body["id"].as_str().unwrap().to_string(),
+        body["key"].as_str().unwrap().to_string(),
+    )
+}
+
+async fn profile_status_with_api_key(app: &TestApp, header: &str, value: &str) -> u16 {
+    app.client
+        .get(format!("{}/api/user/profile", app.address))
+        .header(header, value)
+        .send()
+        .await
+        .expect("Failed to execute request")
+        .status()
+        .as_u16()
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_valid_api_key_authenticates() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("service@example.com").await;
+
+    let (id, key) = create_api_key(&app, &token, serde_json::json!({ "name": "billing" })).await;
+    assert!(key.starts_with("mt_"));
+
+    assert_eq!(profile_status_with_api_key(&app, "X-API-Key", &key).await, 200);
+    assert_eq!(
+        profile_status_with_api_key(&app, "Authorization", &format!("ApiKey {}", key)).await,
+        200
+    );
+
+    // Listed without the key itself, with its last use recorded
+    let response = app.authed_get("/api/user/api-keys", &token).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["id"], id);
+    assert_eq!(body[0]["name"], "billing");
+    assert!(body[0].get("key").is_none());
+    assert!(body[0]["last_used_at"].is_string());
+
+    // Keys can't manage keys
+    let response = app
+        .client
+        .get(format!("{}/api/user/api-keys", app.address))
+        .header("X-API-Key", &key)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_expired_api_key_is_rejected() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("expiring@example.com").await;
+    let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+    let (id, key) = create_api_key(
+        &app,
+        &token,
+        serde_json::json!({ "name": "ci", "expires_at": expires_at }),
+    )
+    .await;
+    assert_eq!(profile_status_with_api_key(&app, "X-API-Key", &key).await, 200);
+
+    sqlx::query("UPDATE api_keys SET expires_at = NOW() - INTERVAL '1 second' WHERE id = $1")
+        .bind(uuid::Uuid::parse_str(&id).unwrap())
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    assert_eq!(profile_status_with_api_key(&app, "X-API-Key", &key).await, 401);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_revoked_api_key_is_rejected() {
+    let app = TestApp::spawn_isolated().await;
+    let token = app.register_and_token("revoking@example.com").await;
+    let (id, key) = create_api_key(&app, &token, serde_json::json!({ "name": "ci" })).await;
+
+    let response = app
+        .authed_delete(&format!("/api/user/api-keys/{}", id), &token)
+        .await;
+    assert_eq!(response.status(), 200);
+
+    assert_eq!(profile_status_with_api_key(&app, "X-API-Key", &key).await, 401);
+
+    let response = app.authed_get("/api/user/api-keys", &token).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body.as_array().unwrap().is_empty());
+
+    // Another user can't revoke it either way
+    let other = app.register_and_token("other@example.com").await;
+    let response = app
+        .authed_delete(&format!("/api/user/api-keys/{}", id), &other)
+        .await;
+    assert_eq!(response.status(), 404);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "integration test requires database"]
+async fn test_logins_are_listed_in_own_audit_trail() {
+    let app = TestApp::spawn_isolated().await;
+    app.register_and_token("audited@example.com").await;
+    let login = |password: &str| {
+        app.client
+            .post(format!("{}/api/auth/login", app.address))
+            .header("user-agent", "AuditTest/1.0")
+            .json(&serde_json::json!({ "email": "audited@example.com", "password": password }))
+            .send()
+    };
+
+    assert_eq!(login("WrongPassword123!").await.unwrap().status(), 401);
+    let response = login(TEST_PASSWORD).await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let token = body["access_token"].as_str().unwrap().to_string();
+
+    // One row per login, with its outcome
+    let rows: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT action, user_agent FROM audit_events ORDER BY occurred_at")
+            .fetch_all(&app.db)
+            .await
+            .unwrap();
+    let agent = Some("AuditTest/1.0".to_string());
+    assert_eq!(
+        rows,
+        vec![
+            ("login_failed".to_string(), agent.clone()),
+            ("login_succeeded".to_string(), agent)
+        ]
+    );
+
+    let response = app.authed_get("/api/user/audit", &token).await;
+    assert_eq!(response.status(), 200);
+    let events: Vec<serde_json::Value> = response.json().await.unwrap();
+    let actions: Vec<_> = events.iter().map(|e| e["action"].as_str().unwrap()).collect();
+    assert_eq!(actions, vec!["login_succeeded", "login_failed"]);
+    assert_eq!(events[0]["user_agent"], "AuditTest/1.0");
+
+    // Nobody else's events
+    let other = app.register_and_token("unaudited@example.com").await;
+    let response = app.authed_get("/api/user/audit", &other).await;
+    let events: Vec<serde_json::Value> = response.json().await.unwrap();
+    assert!(events.is_empty());
+
+    app.cleanup().await;
+}
+
+/// Session of a web login that needed no second factor
+fn logged_in_session(outcome: WebLoginOutcome) -> Session {
+    match outcome {
+        WebLoginOutcome::LoggedIn(result) => result.session,
+        WebLoginOutcome::MfaRequired { .. } => panic!("expected a session"),
+    }
+}